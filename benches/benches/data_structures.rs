@@ -0,0 +1,75 @@
+//! Criterion benchmark for the `data_structures` crate
+//!
+//! Bulk inserts into the cuckoo hash map (against std's `HashMap`), and the per-item
+//! update cost of the count-min sketch and HyperLogLog.
+//!
+//! Run with `cargo bench -p benches --bench data_structures`.
+
+use benches::{unique_keys, SEED, SIZES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use data_structures::cuckoo_hash::CuckooHashMap;
+use data_structures::sketches::{CountMinSketch, HyperLogLog};
+use std::collections::HashMap;
+
+fn bench_inserts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+
+    for size in SIZES {
+        let keys = unique_keys(size, SEED);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("cuckoo_hash", size), &keys, |b, keys| {
+            b.iter(|| {
+                let mut map = CuckooHashMap::new();
+                for &key in keys {
+                    map.insert(key, key);
+                }
+                map.len()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("std_hashmap", size), &keys, |b, keys| {
+            b.iter(|| {
+                let mut map = HashMap::new();
+                for &key in keys {
+                    map.insert(key, key);
+                }
+                map.len()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_sketches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sketch_add");
+
+    for size in SIZES {
+        let keys = unique_keys(size, SEED);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("count_min", size), &keys, |b, keys| {
+            b.iter(|| {
+                let mut sketch = CountMinSketch::with_error_bounds(0.001, 0.01);
+                for key in keys {
+                    sketch.add(key, 1);
+                }
+                sketch.total()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("hyperloglog", size), &keys, |b, keys| {
+            b.iter(|| {
+                let mut hll = HyperLogLog::new(12).unwrap();
+                for key in keys {
+                    hll.add(key);
+                }
+                hll.estimate()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_inserts, bench_sketches);
+criterion_main!(benches);
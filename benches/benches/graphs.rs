@@ -0,0 +1,50 @@
+//! Criterion benchmark for `algorithms::graph_traversal`
+//!
+//! Covers building the adjacency list from random connected graphs, reading back sorted
+//! neighbor lists (which every traversal step does) and the three traversals themselves,
+//! run silently from the first vertex.
+//!
+//! Run with `cargo bench -p benches --bench graphs`.
+
+use algorithms::graph_traversal::{Graph, Traversal};
+use benches::{connected_graph_edges, SEED, SIZES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn build(edges: &[(String, String)]) -> Graph {
+    let mut graph = Graph::new();
+    for (a, b) in edges {
+        graph.add_edge(a, b);
+    }
+    graph
+}
+
+fn bench_graphs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph");
+
+    for vertices in SIZES {
+        // Average degree of about six.
+        let edges = connected_graph_edges(vertices, vertices * 2, SEED);
+        let names: Vec<String> = (0..vertices).map(|i| format!("v{}", i)).collect();
+        group.throughput(Throughput::Elements(edges.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("build", vertices), &edges, |b, edges| {
+            b.iter(|| build(edges))
+        });
+
+        let graph = build(&edges);
+        group.bench_with_input(BenchmarkId::new("sorted_neighbors", vertices), &names, |b, names| {
+            b.iter(|| names.iter().map(|v| graph.get_sorted_neighbors(v).len()).sum::<usize>())
+        });
+
+        for traversal in Traversal::ALL {
+            group.bench_with_input(BenchmarkId::new(traversal.name(), vertices), &graph, |b, graph| {
+                b.iter(|| graph.traverse(traversal, "v0", |_| {}))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_graphs);
+criterion_main!(benches);
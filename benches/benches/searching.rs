@@ -0,0 +1,57 @@
+//! Criterion benchmark for searching a key set
+//!
+//! The same queries run against a linear scan, binary search over a vector sorted by
+//! `algorithms::sorting_algorithms::merge_sort`, the cuckoo hash map from
+//! `data_structures::cuckoo_hash`, and std's `HashMap` as a baseline, at an all-hit
+//! and a half-miss ratio.
+//!
+//! Run with `cargo bench -p benches --bench searching`.
+
+use algorithms::sorting_algorithms::merge_sort;
+use benches::{queries, unique_keys, SEED, SIZES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use data_structures::cuckoo_hash::CuckooHashMap;
+use std::collections::HashMap;
+
+const QUERIES: usize = 1_000;
+
+fn bench_lookups(c: &mut Criterion) {
+    for hit_percent in [100, 50] {
+        let mut group = c.benchmark_group(format!("search/{}%-hits", hit_percent));
+        group.throughput(Throughput::Elements(QUERIES as u64));
+
+        for size in SIZES {
+            let keys = unique_keys(size, SEED);
+            let queries = queries(&keys, QUERIES, hit_percent, SEED + 1);
+
+            // merge_sort works on i32, and every generated key fits.
+            let as_i32: Vec<i32> = keys.iter().map(|&k| k as i32).collect();
+            let sorted: Vec<u64> = merge_sort(&as_i32).into_iter().map(|k| k as u64).collect();
+
+            let mut cuckoo = CuckooHashMap::new();
+            let mut std_map = HashMap::new();
+            for &key in &keys {
+                cuckoo.insert(key, ());
+                std_map.insert(key, ());
+            }
+
+            group.bench_with_input(BenchmarkId::new("linear", size), &queries, |b, queries| {
+                b.iter(|| queries.iter().filter(|q| keys.contains(q)).count())
+            });
+            group.bench_with_input(BenchmarkId::new("binary", size), &queries, |b, queries| {
+                b.iter(|| queries.iter().filter(|q| sorted.binary_search(q).is_ok()).count())
+            });
+            group.bench_with_input(BenchmarkId::new("cuckoo_hash", size), &queries, |b, queries| {
+                b.iter(|| queries.iter().filter(|q| cuckoo.contains_key(q)).count())
+            });
+            group.bench_with_input(BenchmarkId::new("std_hashmap", size), &queries, |b, queries| {
+                b.iter(|| queries.iter().filter(|q| std_map.contains_key(*q)).count())
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_lookups);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! Criterion benchmark for `algorithms::sorting_algorithms`
+//!
+//! Every sort runs on every input distribution, so best/worst cases show up side by side:
+//! insertion sort on sorted data, quick sort (last-element pivot) on sorted data, and so on.
+//! The quadratic sorts only run on the smaller sizes.
+//!
+//! Run with `cargo bench -p benches --bench sorting`.
+
+use algorithms::sorting_algorithms::*;
+use benches::{int_vec, Distribution, QUADRATIC_SIZES, SEED, SIZES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+type SortFn = fn(&[i32]) -> Vec<i32>;
+
+fn bucket_sort_10(arr: &[i32]) -> Vec<i32> {
+    bucket_sort(arr, 10)
+}
+
+const QUADRATIC: [(&str, SortFn); 3] = [
+    ("bubble", bubble_sort),
+    ("selection", selection_sort),
+    ("insertion", insertion_sort),
+];
+
+const LINEARITHMIC: [(&str, SortFn); 7] = [
+    ("merge", merge_sort),
+    ("quick", quick_sort),
+    ("heap", heap_sort),
+    ("shell", shell_sort),
+    ("counting", counting_sort),
+    ("radix", radix_sort),
+    ("bucket", bucket_sort_10),
+];
+
+fn bench_group(c: &mut Criterion, group_name: &str, sorts: &[(&str, SortFn)], sizes: &[usize]) {
+    for distribution in Distribution::ALL {
+        let mut group = c.benchmark_group(format!("{}/{}", group_name, distribution.name()));
+
+        for &size in sizes {
+            let input = int_vec(distribution, size, SEED);
+            group.throughput(Throughput::Elements(size as u64));
+
+            for &(name, sort) in sorts {
+                group.bench_with_input(BenchmarkId::new(name, size), &input, |b, input| {
+                    b.iter(|| sort(input))
+                });
+            }
+            group.bench_with_input(BenchmarkId::new("std_sort_unstable", size), &input, |b, input| {
+                b.iter(|| {
+                    let mut v = input.clone();
+                    v.sort_unstable();
+                    v
+                })
+            });
+        }
+
+        group.finish();
+    }
+}
+
+fn bench_quadratic(c: &mut Criterion) {
+    bench_group(c, "sort_quadratic", &QUADRATIC, &QUADRATIC_SIZES);
+}
+
+fn bench_linearithmic(c: &mut Criterion) {
+    bench_group(c, "sort", &LINEARITHMIC, &SIZES);
+}
+
+criterion_group!(benches, bench_quadratic, bench_linearithmic);
+criterion_main!(benches);
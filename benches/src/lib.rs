@@ -0,0 +1,216 @@
+//! Seeded input generators shared by the criterion benches.
+//!
+//! Every generator takes an explicit seed, so two runs (or two machines) measure exactly
+//! the same data and differences in the numbers come from the code, not the input.
+//!
+//! Run a category with `cargo bench -p benches --bench <sorting|searching|graphs|data_structures>`.
+
+use algorithms::prng::{Pcg32, Rng as _};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Seed used by every bench unless it has a reason to pick its own.
+pub const SEED: u64 = 0x7EC4_0075;
+
+/// Input sizes for algorithms that are O(n log n) or better.
+pub const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// Smaller sizes for the O(n^2) algorithms, which would dominate a run otherwise.
+pub const QUADRATIC_SIZES: [usize; 2] = [100, 1_000];
+
+// ========== Integer Distributions ==========
+
+/// Shape of a generated integer sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /// Independent values drawn uniformly from `-len..len`
+    Uniform,
+    /// Ascending `0..len`
+    Sorted,
+    /// Descending `len..0`
+    Reversed,
+    /// Sorted, then about 5% of positions swapped at random
+    NearlySorted,
+    /// Values drawn from only 10 distinct keys
+    FewUnique,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 5] = [
+        Distribution::Uniform,
+        Distribution::Sorted,
+        Distribution::Reversed,
+        Distribution::NearlySorted,
+        Distribution::FewUnique,
+    ];
+
+    /// Short label for benchmark ids.
+    pub fn name(self) -> &'static str {
+        match self {
+            Distribution::Uniform => "uniform",
+            Distribution::Sorted => "sorted",
+            Distribution::Reversed => "reversed",
+            Distribution::NearlySorted => "nearly-sorted",
+            Distribution::FewUnique => "few-unique",
+        }
+    }
+}
+
+/// The seeded generator every helper below draws from.
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// `len` integers shaped by `distribution`.
+pub fn int_vec(distribution: Distribution, len: usize, seed: u64) -> Vec<i32> {
+    let mut rng = rng(seed);
+    let n = len as i32;
+
+    match distribution {
+        Distribution::Uniform => (0..len).map(|_| rng.random_range(-n..n.max(1))).collect(),
+        Distribution::Sorted => (0..n).collect(),
+        Distribution::Reversed => (0..n).rev().collect(),
+        Distribution::NearlySorted => {
+            let mut values: Vec<i32> = (0..n).collect();
+            if len > 1 {
+                for _ in 0..len.div_ceil(20) {
+                    let i = rng.random_range(0..len);
+                    let j = rng.random_range(0..len);
+                    values.swap(i, j);
+                }
+            }
+            values
+        }
+        Distribution::FewUnique => (0..len).map(|_| rng.random_range(0..10) * 100).collect(),
+    }
+}
+
+// ========== Lookup Workloads ==========
+
+/// `len` distinct keys in random order.
+pub fn unique_keys(len: usize, seed: u64) -> Vec<u64> {
+    let mut keys: Vec<u64> = (0..len as u64).map(|k| k * 2).collect();
+    keys.shuffle(&mut rng(seed));
+    keys
+}
+
+/// `count` queries against `keys`, of which roughly `hit_percent`% are present.
+///
+/// Misses are odd numbers, which `unique_keys` never produces.
+pub fn queries(keys: &[u64], count: usize, hit_percent: u32, seed: u64) -> Vec<u64> {
+    let mut rng = rng(seed);
+    (0..count)
+        .map(|_| {
+            if !keys.is_empty() && rng.random_range(0..100) < hit_percent {
+                keys[rng.random_range(0..keys.len())]
+            } else {
+                rng.random_range(0..u64::MAX / 2) * 2 + 1
+            }
+        })
+        .collect()
+}
+
+// ========== Graphs ==========
+
+/// Vertex names `v0..v{vertices}` joined by a random spanning tree plus `extra_edges`
+/// random edges, so the graph is always connected. No self-loops; duplicates are possible.
+///
+/// Draws from the snippets' own `Pcg32` rather than `rand`, so the graphs only change when
+/// that generator does.
+pub fn connected_graph_edges(vertices: usize, extra_edges: usize, seed: u64) -> Vec<(String, String)> {
+    let mut rng = Pcg32::new(seed, 0);
+    let mut below = |bound: usize| rng.below(bound as u64) as usize;
+    let name = |i: usize| format!("v{}", i);
+    let mut edges = Vec::with_capacity(vertices.saturating_sub(1) + extra_edges);
+
+    for i in 1..vertices {
+        edges.push((name(below(i)), name(i)));
+    }
+    if vertices > 1 {
+        for _ in 0..extra_edges {
+            let a = below(vertices);
+            let mut b = below(vertices - 1);
+            if b >= a {
+                b += 1;
+            }
+            edges.push((name(a), name(b)));
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_input() {
+        for distribution in Distribution::ALL {
+            assert_eq!(int_vec(distribution, 500, 7), int_vec(distribution, 500, 7));
+        }
+        assert_ne!(int_vec(Distribution::Uniform, 500, 7), int_vec(Distribution::Uniform, 500, 8));
+    }
+
+    #[test]
+    fn distributions_have_their_shape() {
+        let len = 1_000;
+        assert!(int_vec(Distribution::Sorted, len, SEED).is_sorted());
+        assert!(int_vec(Distribution::Reversed, len, SEED).windows(2).all(|w| w[0] > w[1]));
+
+        let nearly = int_vec(Distribution::NearlySorted, len, SEED);
+        let out_of_place = nearly.iter().enumerate().filter(|&(i, &v)| v != i as i32).count();
+        assert!(out_of_place > 0 && out_of_place <= len / 10, "{}", out_of_place);
+
+        let mut few = int_vec(Distribution::FewUnique, len, SEED);
+        few.sort();
+        few.dedup();
+        assert!(few.len() <= 10);
+
+        for distribution in Distribution::ALL {
+            assert_eq!(int_vec(distribution, len, SEED).len(), len);
+            assert!(int_vec(distribution, 0, SEED).is_empty());
+        }
+    }
+
+    #[test]
+    fn queries_respect_hit_rate() {
+        let keys = unique_keys(1_000, SEED);
+        let lookup: std::collections::HashSet<u64> = keys.iter().copied().collect();
+        assert_eq!(lookup.len(), keys.len());
+
+        let all_hits = queries(&keys, 500, 100, SEED);
+        assert!(all_hits.iter().all(|q| lookup.contains(q)));
+        let all_misses = queries(&keys, 500, 0, SEED);
+        assert!(all_misses.iter().all(|q| !lookup.contains(q)));
+
+        let hits = queries(&keys, 10_000, 50, SEED).iter().filter(|q| lookup.contains(q)).count();
+        assert!((4_500..5_500).contains(&hits), "{}", hits);
+    }
+
+    #[test]
+    fn generated_graph_is_connected_without_self_loops() {
+        let vertices = 200;
+        let edges = connected_graph_edges(vertices, 300, SEED);
+        assert_eq!(edges.len(), vertices - 1 + 300);
+        assert!(edges.iter().all(|(a, b)| a != b));
+        assert_eq!(edges, connected_graph_edges(vertices, 300, SEED));
+
+        // Union-find over the edge list must end with a single root.
+        let index = |name: &str| name[1..].parse::<usize>().unwrap();
+        let mut parent: Vec<usize> = (0..vertices).collect();
+        fn root(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                parent[x] = parent[parent[x]];
+                x = parent[x];
+            }
+            x
+        }
+        for (a, b) in &edges {
+            let (ra, rb) = (root(&mut parent, index(a)), root(&mut parent, index(b)));
+            parent[ra] = rb;
+        }
+        let r0 = root(&mut parent, 0);
+        assert!((0..vertices).all(|v| root(&mut parent, v) == r0));
+    }
+}
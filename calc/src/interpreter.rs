@@ -0,0 +1,742 @@
+//! The expression language behind `calc`.
+//!
+//! A line is lexed into tokens with byte spans, parsed by recursive descent into an
+//! `Expr` tree that keeps every node's span, and evaluated against an `Env` of variables
+//! and user-defined functions. Every error carries the span it is about, so
+//! `CalcError::render` can underline the offending part of the line.
+//!
+//! Grammar, lowest precedence first:
+//!
+//! ```text
+//! line   = expr "=" expr     assignment `x = ...` or definition `f(x, y) = ...`
+//!        | expr
+//! expr   = term (("+" | "-") term)*
+//! term   = unary (("*" | "/" | "%") unary)*
+//! unary  = "-" unary | power
+//! power  = atom ("^" unary)?          right-associative, and -2^2 is -(2^2)
+//! atom   = NUMBER | IDENT | IDENT "(" (expr ("," expr)*)? ")" | "(" expr ")"
+//! ```
+//!
+//! `#` starts a comment. `ans` holds the last value printed.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+// ========== Spans and Errors ==========
+
+/// Byte offsets into the line, end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    fn to(self, other: Span) -> Span {
+        Span { start: self.start, end: other.end }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    UnexpectedChar(char),
+    BadNumber,
+    /// What the parser wanted instead of the token at the span
+    Expected(&'static str),
+    BadAssignment,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArity { name: String, expected: usize, found: usize },
+    DivisionByZero,
+    NotFinite,
+    RedefinedBuiltin(String),
+    DuplicateParameter(String),
+    RecursionLimit(String),
+    NestingLimit,
+    /// An error inside a user function, reported at the call
+    InFunction { name: String, kind: Box<ErrorKind> },
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::BadNumber => write!(f, "malformed number"),
+            ErrorKind::Expected(what) => write!(f, "expected {}", what),
+            ErrorKind::BadAssignment => write!(f, "only `name = ...` or `f(x, ...) = ...` can be assigned"),
+            ErrorKind::UnknownVariable(name) => write!(f, "unknown variable `{}`", name),
+            ErrorKind::UnknownFunction(name) => write!(f, "unknown function `{}`", name),
+            ErrorKind::WrongArity { name, expected, found } => {
+                let plural = if *expected == 1 { "" } else { "s" };
+                write!(f, "`{}` takes {} argument{}, got {}", name, expected, plural, found)
+            }
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ErrorKind::NotFinite => write!(f, "result is not a finite number"),
+            ErrorKind::RedefinedBuiltin(name) => write!(f, "`{}` is a builtin and cannot be redefined", name),
+            ErrorKind::DuplicateParameter(name) => write!(f, "parameter `{}` appears twice", name),
+            ErrorKind::RecursionLimit(name) => write!(f, "`{}` recursed too deeply", name),
+            ErrorKind::NestingLimit => write!(f, "expression nested more than {} levels deep", MAX_NESTING),
+            ErrorKind::InFunction { name, kind } => write!(f, "in `{}`: {}", name, kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcError {
+    pub kind: ErrorKind,
+    pub span: Span,
+}
+
+impl CalcError {
+    fn new(kind: ErrorKind, span: Span) -> Self {
+        CalcError { kind, span }
+    }
+
+    /// The message, then `line` with the span underlined:
+    ///
+    /// ```text
+    /// error: unknown variable `y`
+    ///   | 2 * y + 1
+    ///   |     ^
+    /// ```
+    pub fn render(&self, line: &str) -> String {
+        // Columns count chars, not bytes, so the carets line up under non-ASCII input
+        let column = line[..self.span.start.min(line.len())].chars().count();
+        let width = line.get(self.span.start..self.span.end).map_or(0, |text| text.chars().count()).max(1);
+        format!("error: {}\n  | {}\n  | {}{}", self.kind, line, " ".repeat(column), "^".repeat(width))
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.kind, self.span.start + 1)
+    }
+}
+
+impl Error for CalcError {}
+
+// ========== Lexer ==========
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+    Equals,
+    End,
+}
+
+fn lex(line: &str) -> Result<Vec<(Token, Span)>, CalcError> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut stop = line.len();
+    while i < bytes.len() {
+        let start = i;
+        let c = line[i..].chars().next().unwrap_or_default();
+        let token = match c {
+            '#' => {
+                stop = i;
+                break;
+            }
+            c if c.is_whitespace() => {
+                i += c.len_utf8();
+                continue;
+            }
+            '0'..='9' | '.' => {
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                // An exponent only if digits follow, so `2e` is `2` then the variable `e`
+                let digits_at = |j: usize| bytes.get(j).is_some_and(u8::is_ascii_digit);
+                if matches!(bytes.get(i), Some(b'e' | b'E')) {
+                    let sign = usize::from(matches!(bytes.get(i + 1), Some(b'+' | b'-')));
+                    if digits_at(i + 1 + sign) {
+                        i += 1 + sign;
+                        while digits_at(i) {
+                            i += 1;
+                        }
+                    }
+                }
+                let span = Span { start, end: i };
+                let value = line[start..i].parse().map_err(|_| CalcError::new(ErrorKind::BadNumber, span))?;
+                tokens.push((Token::Number(value), span));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while let Some(c) = line[i..].chars().next().filter(|c| c.is_alphanumeric() || *c == '_') {
+                    i += c.len_utf8();
+                }
+                tokens.push((Token::Ident(line[start..i].to_string()), Span { start, end: i }));
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '=' => Token::Equals,
+            c => return Err(CalcError::new(ErrorKind::UnexpectedChar(c), Span { start, end: start + c.len_utf8() })),
+        };
+        i += c.len_utf8();
+        tokens.push((token, Span { start, end: i }));
+    }
+    // The end of the line is just after its last token, ahead of any comment
+    let end = line[..stop].trim_end().len();
+    tokens.push((Token::End, Span { start: end, end }));
+    Ok(tokens)
+}
+
+// ========== Parser ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64, Span),
+    Var(String, Span),
+    Neg(Box<Expr>, Span),
+    Binary { op: BinOp, lhs: Box<Expr>, rhs: Box<Expr>, span: Span },
+    Call { name: String, args: Vec<Expr>, span: Span },
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Number(_, span) | Expr::Var(_, span) | Expr::Neg(_, span) => *span,
+            Expr::Binary { span, .. } | Expr::Call { span, .. } => *span,
+        }
+    }
+}
+
+/// What one line asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Eval(Expr),
+    Assign { name: String, value: Expr },
+    Define { name: String, params: Vec<String>, body: Expr },
+}
+
+/// How deeply parentheses, calls, `-` and `^` may nest in one line. Each operator in a
+/// `+`/`*` chain counts as a level too, since the chain builds a left-leaning tree. Parsing
+/// and evaluation both recurse once per level, so without a limit a long enough line
+/// overflows the stack and takes the whole session down with it. Both limits are sized so
+/// that even a debug build stays well inside a spawned thread's default 2 MiB stack
+pub const MAX_NESTING: usize = 128;
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn span(&self) -> Span {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> (Token, Span) {
+        let token = self.tokens[self.pos].clone();
+        if token.0 != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, token: Token, what: &'static str) -> Result<Span, CalcError> {
+        if *self.peek() == token {
+            Ok(self.advance().1)
+        } else {
+            Err(CalcError::new(ErrorKind::Expected(what), self.span()))
+        }
+    }
+
+    /// Enters one level of nesting opened by the token at `span`; the caller leaves it with
+    /// `self.depth -= 1` once the nested part has parsed
+    fn nest(&mut self, span: Span) -> Result<(), CalcError> {
+        if self.depth == MAX_NESTING {
+            return Err(CalcError::new(ErrorKind::NestingLimit, span));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<Statement, CalcError> {
+        let target = self.expr()?;
+        let statement = if *self.peek() == Token::Equals {
+            self.advance();
+            let value = self.expr()?;
+            into_binding(target, value)?
+        } else {
+            Statement::Eval(target)
+        };
+        self.expect(Token::End, "an operator or the end of the line")?;
+        Ok(statement)
+    }
+
+    fn expr(&mut self) -> Result<Expr, CalcError> {
+        let mut lhs = self.term()?;
+        let mut links = 0;
+        loop {
+            let op = match self.peek() {
+                Token::Plus => BinOp::Add,
+                Token::Minus => BinOp::Sub,
+                _ => {
+                    self.depth -= links;
+                    return Ok(lhs);
+                }
+            };
+            let span = self.advance().1;
+            self.nest(span)?;
+            links += 1;
+            lhs = binary(op, lhs, self.term()?);
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, CalcError> {
+        let mut lhs = self.unary()?;
+        let mut links = 0;
+        loop {
+            let op = match self.peek() {
+                Token::Star => BinOp::Mul,
+                Token::Slash => BinOp::Div,
+                Token::Percent => BinOp::Rem,
+                _ => {
+                    self.depth -= links;
+                    return Ok(lhs);
+                }
+            };
+            let span = self.advance().1;
+            self.nest(span)?;
+            links += 1;
+            lhs = binary(op, lhs, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, CalcError> {
+        if *self.peek() == Token::Minus {
+            let minus = self.advance().1;
+            self.nest(minus)?;
+            let operand = self.unary()?;
+            self.depth -= 1;
+            let span = minus.to(operand.span());
+            return Ok(Expr::Neg(Box::new(operand), span));
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr, CalcError> {
+        let base = self.atom()?;
+        if *self.peek() == Token::Caret {
+            let caret = self.advance().1;
+            self.nest(caret)?;
+            let exponent = self.unary()?;
+            self.depth -= 1;
+            return Ok(binary(BinOp::Pow, base, exponent));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, CalcError> {
+        match self.advance() {
+            (Token::Number(value), span) => Ok(Expr::Number(value, span)),
+            (Token::Ident(name), span) if *self.peek() == Token::LParen => {
+                let open = self.advance().1;
+                self.nest(open)?;
+                let mut args = Vec::new();
+                if *self.peek() != Token::RParen {
+                    args.push(self.expr()?);
+                    while *self.peek() == Token::Comma {
+                        self.advance();
+                        args.push(self.expr()?);
+                    }
+                }
+                let close = self.expect(Token::RParen, "`,` or `)`")?;
+                self.depth -= 1;
+                Ok(Expr::Call { name, args, span: span.to(close) })
+            }
+            (Token::Ident(name), span) => Ok(Expr::Var(name, span)),
+            // The parentheses stay out of the span, so errors point at what is inside
+            (Token::LParen, open) => {
+                self.nest(open)?;
+                let inner = self.expr()?;
+                self.expect(Token::RParen, "`)`")?;
+                self.depth -= 1;
+                Ok(inner)
+            }
+            (_, span) => Err(CalcError::new(ErrorKind::Expected("a number, name or `(`"), span)),
+        }
+    }
+}
+
+fn binary(op: BinOp, lhs: Expr, rhs: Expr) -> Expr {
+    let span = lhs.span().to(rhs.span());
+    Expr::Binary { op, lhs: Box::new(lhs), rhs: Box::new(rhs), span }
+}
+
+/// `x = value` or `f(a, b) = body`; anything else on the left is an error
+fn into_binding(target: Expr, value: Expr) -> Result<Statement, CalcError> {
+    match target {
+        Expr::Var(name, _) => Ok(Statement::Assign { name, value }),
+        Expr::Call { name, args, span } => {
+            let mut params: Vec<String> = Vec::new();
+            for arg in args {
+                match arg {
+                    Expr::Var(param, span) if params.contains(&param) => {
+                        return Err(CalcError::new(ErrorKind::DuplicateParameter(param), span))
+                    }
+                    Expr::Var(param, _) => params.push(param),
+                    other => return Err(CalcError::new(ErrorKind::Expected("a parameter name"), other.span())),
+                }
+            }
+            if builtin(&name).is_some() {
+                return Err(CalcError::new(ErrorKind::RedefinedBuiltin(name), span));
+            }
+            Ok(Statement::Define { name, params, body: value })
+        }
+        other => Err(CalcError::new(ErrorKind::BadAssignment, other.span())),
+    }
+}
+
+pub fn parse(line: &str) -> Result<Statement, CalcError> {
+    Parser { tokens: lex(line)?, pos: 0, depth: 0 }.statement()
+}
+
+// ========== Evaluation ==========
+
+/// How deep user functions may call each other; with no conditionals in the language,
+/// any recursion is infinite, so this only has to catch it before the stack does
+pub const MAX_DEPTH: usize = 64;
+
+/// How many expression levels evaluation may recurse through, across all the calls in
+/// progress. A body can be up to `MAX_NESTING` levels deep, so a call is refused once less
+/// than that is left, however few calls deep it is
+pub const MAX_EVAL_LEVELS: usize = 768;
+
+type Builtin = (&'static str, usize, fn(&[f64]) -> f64);
+
+pub const BUILTINS: &[Builtin] = &[
+    ("abs", 1, |x| x[0].abs()),
+    ("ceil", 1, |x| x[0].ceil()),
+    ("cos", 1, |x| x[0].cos()),
+    ("exp", 1, |x| x[0].exp()),
+    ("floor", 1, |x| x[0].floor()),
+    ("ln", 1, |x| x[0].ln()),
+    ("log", 1, |x| x[0].log10()),
+    ("max", 2, |x| x[0].max(x[1])),
+    ("min", 2, |x| x[0].min(x[1])),
+    ("round", 1, |x| x[0].round()),
+    ("sin", 1, |x| x[0].sin()),
+    ("sqrt", 1, |x| x[0].sqrt()),
+    ("tan", 1, |x| x[0].tan()),
+];
+
+fn builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|(builtin, ..)| *builtin == name)
+}
+
+/// A user-defined function and the text of its body, for listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub params: Vec<String>,
+    pub body: Expr,
+    pub source: String,
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}) = {}", self.params.join(", "), self.source)
+    }
+}
+
+/// What a successful line did
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Value(f64),
+    Assigned(String, f64),
+    Defined(String),
+}
+
+/// Variables and functions that persist from one line to the next
+#[derive(Debug, Clone)]
+pub struct Env {
+    vars: BTreeMap<String, f64>,
+    functions: BTreeMap<String, Function>,
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        let vars = [("pi", std::f64::consts::PI), ("e", std::f64::consts::E)];
+        Env { vars: vars.map(|(name, value)| (name.to_string(), value)).into(), functions: BTreeMap::new() }
+    }
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses and runs one line; a plain expression also becomes `ans`
+    pub fn execute(&mut self, line: &str) -> Result<Outcome, CalcError> {
+        match parse(line)? {
+            Statement::Eval(expr) => {
+                let value = self.eval(&expr, &[], 0, 0)?;
+                self.vars.insert("ans".to_string(), value);
+                Ok(Outcome::Value(value))
+            }
+            Statement::Assign { name, value } => {
+                let value = self.eval(&value, &[], 0, 0)?;
+                self.vars.insert(name.clone(), value);
+                Ok(Outcome::Assigned(name, value))
+            }
+            Statement::Define { name, params, body } => {
+                let source = line[body.span().start..body.span().end].to_string();
+                self.functions.insert(name.clone(), Function { params, body, source });
+                Ok(Outcome::Defined(name))
+            }
+        }
+    }
+
+    pub fn vars(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.vars.iter().map(|(name, value)| (name.as_str(), *value))
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = (&str, &Function)> {
+        self.functions.iter().map(|(name, function)| (name.as_str(), function))
+    }
+
+    /// `locals` are the parameters of the user function being evaluated, if any; `depth`
+    /// counts the user calls in progress and `levels` the expression nodes being evaluated
+    fn eval(&self, expr: &Expr, locals: &[(&str, f64)], depth: usize, levels: usize) -> Result<f64, CalcError> {
+        let value = match expr {
+            Expr::Number(value, _) => *value,
+            Expr::Var(name, span) => match locals.iter().find(|(local, _)| local == name) {
+                Some((_, value)) => *value,
+                None => *self
+                    .vars
+                    .get(name)
+                    .ok_or_else(|| CalcError::new(ErrorKind::UnknownVariable(name.clone()), *span))?,
+            },
+            Expr::Neg(operand, _) => -self.eval(operand, locals, depth, levels + 1)?,
+            Expr::Binary { op, lhs, rhs, .. } => {
+                let (a, b) = (self.eval(lhs, locals, depth, levels + 1)?, self.eval(rhs, locals, depth, levels + 1)?);
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div | BinOp::Rem if b == 0.0 => {
+                        return Err(CalcError::new(ErrorKind::DivisionByZero, rhs.span()))
+                    }
+                    BinOp::Div => a / b,
+                    BinOp::Rem => a % b,
+                    BinOp::Pow => a.powf(b),
+                }
+            }
+            Expr::Call { name, args, span } => self.call(name, args, *span, locals, depth, levels + 1)?,
+        };
+        if !value.is_finite() {
+            return Err(CalcError::new(ErrorKind::NotFinite, expr.span()));
+        }
+        Ok(value)
+    }
+
+    fn call(&self, name: &str, args: &[Expr], span: Span, locals: &[(&str, f64)], depth: usize, levels: usize) -> Result<f64, CalcError> {
+        let arity = |expected: usize| {
+            if args.len() == expected {
+                Ok(())
+            } else {
+                let kind = ErrorKind::WrongArity { name: name.to_string(), expected, found: args.len() };
+                Err(CalcError::new(kind, span))
+            }
+        };
+        let values = args.iter().map(|arg| self.eval(arg, locals, depth, levels)).collect::<Result<Vec<f64>, _>>();
+
+        if let Some((_, expected, function)) = builtin(name) {
+            arity(*expected)?;
+            return Ok(function(&values?));
+        }
+        let function = self
+            .functions
+            .get(name)
+            .ok_or_else(|| CalcError::new(ErrorKind::UnknownFunction(name.to_string()), span))?;
+        arity(function.params.len())?;
+        if depth == MAX_DEPTH || levels + MAX_NESTING > MAX_EVAL_LEVELS {
+            return Err(CalcError::new(ErrorKind::RecursionLimit(name.to_string()), span));
+        }
+        let values = values?;
+        let scope: Vec<(&str, f64)> = function.params.iter().map(String::as_str).zip(values).collect();
+        // The body's spans point into the line that defined it, so report at this call
+        self.eval(&function.body, &scope, depth + 1, levels).map_err(|error| {
+            let kind = match error.kind {
+                ErrorKind::RecursionLimit(_) => error.kind,
+                kind => ErrorKind::InFunction { name: name.to_string(), kind: Box::new(kind) },
+            };
+            CalcError::new(kind, span)
+        })
+    }
+}
+
+/// Rounded to 12 decimal places like a desk calculator, so `0.1 + 0.2` shows `0.3`;
+/// very large and very small magnitudes switch to exponent form
+pub fn format_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-6..1e15).contains(&magnitude) {
+        return format!("{:e}", value);
+    }
+    let text = format!("{:.12}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(line: &str) -> f64 {
+        match Env::new().execute(line) {
+            Ok(Outcome::Value(value)) => value,
+            other => panic!("{:?} gave {:?}", line, other),
+        }
+    }
+
+    fn error(env: &mut Env, line: &str) -> CalcError {
+        env.execute(line).expect_err(line)
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        let cases = [
+            ("1 + 2 * 3", 7.0),
+            ("(1 + 2) * 3", 9.0),
+            ("10 - 4 - 3", 3.0),
+            ("2 ^ 3 ^ 2", 512.0),
+            ("-2 ^ 2", -4.0),
+            ("2 ^ -1", 0.5),
+            ("7 % 4 * 2", 6.0),
+            ("--3", 3.0),
+            ("1.5e3 + .5", 1500.5),
+            ("2e-1 * e", 0.2 * std::f64::consts::E),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(value(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn variables_functions_and_ans_persist() {
+        let mut env = Env::new();
+        assert_eq!(env.execute("r = 2").unwrap(), Outcome::Assigned("r".to_string(), 2.0));
+        assert_eq!(env.execute("area(r) = pi * r^2").unwrap(), Outcome::Defined("area".to_string()));
+        assert_eq!(env.execute("area(3) + r").unwrap(), Outcome::Value(9.0 * std::f64::consts::PI + 2.0));
+        assert_eq!(env.execute("hyp(a, b) = sqrt(a^2 + b^2)  # comment").unwrap(), Outcome::Defined("hyp".to_string()));
+        assert_eq!(env.execute("hyp(3, 4)").unwrap(), Outcome::Value(5.0));
+        assert_eq!(env.execute("ans * 2").unwrap(), Outcome::Value(10.0));
+        assert_eq!(env.functions().map(|(name, f)| format!("{}{}", name, f)).collect::<Vec<_>>(), [
+            "area(r) = pi * r^2",
+            "hyp(a, b) = sqrt(a^2 + b^2)",
+        ]);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_span() {
+        let mut env = Env::new();
+        let cases = [
+            ("1 + foo * 2", ErrorKind::UnknownVariable("foo".to_string()), (4, 7)),
+            ("1 / (2 - 2)", ErrorKind::DivisionByZero, (5, 10)),
+            ("1 +", ErrorKind::Expected("a number, name or `(`"), (3, 3)),
+            ("(1 + 2", ErrorKind::Expected("`)`"), (6, 6)),
+            ("1 2", ErrorKind::Expected("an operator or the end of the line"), (2, 3)),
+            ("3 $ 4", ErrorKind::UnexpectedChar('$'), (2, 3)),
+            ("1.2.3", ErrorKind::BadNumber, (0, 5)),
+            ("sqrt(1, 2)", ErrorKind::WrongArity { name: "sqrt".to_string(), expected: 1, found: 2 }, (0, 10)),
+            ("2 + 3 = 5", ErrorKind::BadAssignment, (0, 5)),
+            ("f(x, x) = x", ErrorKind::DuplicateParameter("x".to_string()), (5, 6)),
+            ("sqrt(x) = x", ErrorKind::RedefinedBuiltin("sqrt".to_string()), (0, 7)),
+            ("sqrt(-1)", ErrorKind::NotFinite, (0, 8)),
+        ];
+        for (line, kind, (start, end)) in cases {
+            assert_eq!(error(&mut env, line), CalcError { kind, span: Span { start, end } }, "{}", line);
+        }
+    }
+
+    #[test]
+    fn errors_inside_functions_are_reported_at_the_call() {
+        let mut env = Env::new();
+        env.execute("f(x) = x + y").unwrap();
+        env.execute("g(x) = 1 + f(x)").unwrap();
+        env.execute("loop(x) = loop(x)").unwrap();
+
+        let inner = ErrorKind::InFunction { name: "f".to_string(), kind: Box::new(ErrorKind::UnknownVariable("y".to_string())) };
+        let err = error(&mut env, "2 * g(1)");
+        assert_eq!(err.kind, ErrorKind::InFunction { name: "g".to_string(), kind: Box::new(inner) });
+        assert_eq!(err.span, Span { start: 4, end: 8 });
+        assert_eq!(error(&mut env, "loop(1)").kind, ErrorKind::RecursionLimit("loop".to_string()));
+    }
+
+    #[test]
+    fn nesting_is_limited_in_the_parser_and_across_calls() {
+        let mut env = Env::new();
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(value(&nested(MAX_NESTING)), 1.0);
+        let limit = MAX_NESTING;
+        let cases = [
+            (nested(MAX_NESTING + 1), (limit, limit + 1)),
+            (format!("{}1", "-".repeat(MAX_NESTING + 1)), (limit, limit + 1)),
+            (vec!["1"; MAX_NESTING + 2].join("+"), (2 * limit + 1, 2 * limit + 2)),
+            (format!("{}1", "2^".repeat(MAX_NESTING + 1)), (2 * limit + 1, 2 * limit + 2)),
+        ];
+        for (line, (start, end)) in cases {
+            assert_eq!(error(&mut env, &line), CalcError { kind: ErrorKind::NestingLimit, span: Span { start, end } });
+        }
+
+        // Each call is only a few levels deep, but together they would outgrow the stack
+        env.execute(&format!("deep(x) = {}deep(x)", "-".repeat(MAX_NESTING - 1))).unwrap();
+        assert_eq!(error(&mut env, "deep(1)").kind, ErrorKind::RecursionLimit("deep".to_string()));
+    }
+
+    #[test]
+    fn render_underlines_by_character_not_byte() {
+        let line = "größe + 1";
+        let err = Env::new().execute(line).unwrap_err();
+        assert_eq!(err.render(line), "error: unknown variable `größe`\n  | größe + 1\n  | ^^^^^");
+        let line = "1 +";
+        assert_eq!(Env::new().execute(line).unwrap_err().render(line).lines().last(), Some("  |    ^"));
+    }
+
+    #[test]
+    fn numbers_print_like_a_calculator() {
+        let cases = [(0.1 + 0.2, "0.3"), (42.0, "42"), (-0.0, "0"), (-2.5, "-2.5"), (1e20, "1e20"), (1.5e-9, "1.5e-9")];
+        for (value, expected) in cases {
+            assert_eq!(format_number(value), expected);
+        }
+    }
+}
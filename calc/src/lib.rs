@@ -0,0 +1,22 @@
+//! `calc`: a calculator REPL, and an example of the snippets composing into a small
+//! application.
+//!
+//! - `interpreter` lexes, parses and evaluates one line at a time, with every error
+//!   carrying the span of the input it is about
+//! - `repl` keeps the variables, functions and history between lines, and reports
+//!   failures from `:load` as a context chain built with `rust_lang::error_handling`
+//! - `main.rs` is the clap front end: a prompt on a terminal, plain line-by-line
+//!   output when stdin is a pipe, or `--eval` for one-off lines
+//!
+//! ```text
+//! $ cargo run -p calc
+//! > area(r) = pi * r^2
+//! defined area
+//! > area(2) / 0
+//! error: division by zero
+//!   | area(2) / 0
+//!   |           ^
+//! ```
+
+pub mod interpreter;
+pub mod repl;
@@ -0,0 +1,40 @@
+//! `calc`: an interactive calculator. See the library docs for the language.
+
+use clap::Parser;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "calc", version, about = "A calculator REPL with variables, functions and history")]
+struct Cli {
+    /// Run this line instead of reading stdin; repeat for several
+    #[arg(short, long = "eval", value_name = "LINE")]
+    eval: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let (mut out, mut err) = (io::stdout().lock(), io::stderr().lock());
+    let interactive = cli.eval.is_empty() && io::stdin().is_terminal();
+
+    let result = if cli.eval.is_empty() {
+        let prompt = interactive.then_some("> ");
+        calc::repl::run(&mut io::stdin().lock(), &mut out, &mut err, prompt)
+    } else {
+        let mut session = calc::repl::Session::new();
+        cli.eval
+            .iter()
+            .try_for_each(|line| session.handle(line, &mut out, &mut err).map(drop))
+            .map(|()| session)
+    };
+
+    match result {
+        // A script or `--eval` that hit an error fails, so shell pipelines notice
+        Ok(session) if session.errors() > 0 && !interactive => ExitCode::FAILURE,
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
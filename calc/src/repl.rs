@@ -0,0 +1,223 @@
+//! The read-eval-print loop around `interpreter::Env`.
+//!
+//! A `Session` takes one line at a time, so the binary, `:load` and the tests all drive
+//! it the same way. Results go to `out` and errors to `err`, rendered with the span
+//! underlined. Lines starting with `:` are commands, and `!!` or `!N` repeat an earlier
+//! line from the history, the way a shell does.
+
+use crate::interpreter::{format_number, Env, Outcome, BUILTINS};
+use rust_lang::error_handling::{report, BoxError, ResultExt};
+use std::io::{self, BufRead, Write};
+
+pub const HELP: &str = "\
+expressions   1 + 2 * 3, (1 + 2)^2, -x % 4, sqrt(2), max(a, b)
+variables     r = 2        (pi, e and ans are predefined; ans is the last result)
+functions     area(r) = pi * r^2
+history       !! repeats the last line, !N repeats line N of :history
+commands      :vars :funcs :history :load FILE :help :quit";
+
+/// Whether the loop should keep reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+#[derive(Debug, Default)]
+pub struct Session {
+    env: Env,
+    history: Vec<String>,
+    errors: usize,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lines that failed so far, including inside `:load`
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn handle(&mut self, line: &str, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<Control> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(Control::Continue);
+        }
+        let line = match self.expand(line) {
+            Ok(line) => line,
+            Err(message) => {
+                self.errors += 1;
+                writeln!(err, "error: {}", message)?;
+                return Ok(Control::Continue);
+            }
+        };
+        self.history.push(line.clone());
+        match line.strip_prefix(':') {
+            Some(command) => self.command(command, out, err),
+            None => {
+                self.evaluate(&line, out, err)?;
+                Ok(Control::Continue)
+            }
+        }
+    }
+
+    /// `!!` and `!N` become the line they name, which is what the history records
+    fn expand(&self, line: &str) -> Result<String, String> {
+        let Some(reference) = line.strip_prefix('!') else {
+            return Ok(line.to_string());
+        };
+        let index = match reference {
+            "!" => self.history.len().checked_sub(1),
+            n => n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)),
+        };
+        index
+            .and_then(|index| self.history.get(index))
+            .cloned()
+            .ok_or_else(|| format!("no history entry `{}`", line))
+    }
+
+    fn evaluate(&mut self, line: &str, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<()> {
+        match self.env.execute(line) {
+            Ok(outcome) => writeln!(out, "{}", describe(&outcome)),
+            Err(error) => {
+                self.errors += 1;
+                writeln!(err, "{}", error.render(line))
+            }
+        }
+    }
+
+    fn command(&mut self, command: &str, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<Control> {
+        let (name, argument) = command.split_once(' ').map_or((command, ""), |(name, rest)| (name, rest.trim()));
+        match name {
+            "q" | "quit" => return Ok(Control::Quit),
+            "h" | "help" => writeln!(out, "{}", HELP)?,
+            "vars" => {
+                for (name, value) in self.env.vars() {
+                    writeln!(out, "{} = {}", name, format_number(value))?;
+                }
+            }
+            "funcs" => {
+                for (name, function) in self.env.functions() {
+                    writeln!(out, "{}{}", name, function)?;
+                }
+                let builtins: Vec<&str> = BUILTINS.iter().map(|(name, ..)| *name).collect();
+                writeln!(out, "builtins: {}", builtins.join(" "))?;
+            }
+            "history" => {
+                for (number, line) in self.history.iter().enumerate() {
+                    writeln!(out, "{:>4}  {}", number + 1, line)?;
+                }
+            }
+            "load" if !argument.is_empty() => match self.load(argument, out) {
+                Ok(lines) => writeln!(out, "loaded {} lines from {}", lines, argument)?,
+                Err(error) => {
+                    self.errors += 1;
+                    writeln!(err, "{}", report(error.as_ref()))?;
+                }
+            },
+            "load" => {
+                self.errors += 1;
+                writeln!(err, "error: :load needs a file name")?;
+            }
+            _ => {
+                self.errors += 1;
+                writeln!(err, "error: unknown command `:{}`; :help lists them", command)?;
+            }
+        }
+        Ok(Control::Continue)
+    }
+
+    /// Runs every line of a file, stopping at the first that fails
+    pub fn load(&mut self, path: &str, out: &mut dyn Write) -> Result<usize, BoxError> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("could not read {}", path))?;
+        let mut count = 0;
+        for (number, line) in text.lines().enumerate() {
+            let code = line.split('#').next().unwrap_or_default().trim();
+            if code.is_empty() {
+                continue;
+            }
+            let outcome = self.env.execute(code).with_context(|| format!("{} line {}: {}", path, number + 1, code))?;
+            writeln!(out, "{}", describe(&outcome))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+fn describe(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Value(value) => format_number(*value),
+        Outcome::Assigned(name, value) => format!("{} = {}", name, format_number(*value)),
+        Outcome::Defined(name) => format!("defined {}", name),
+    }
+}
+
+/// Reads lines until EOF or `:quit`, printing `prompt` before each when it is given
+pub fn run(input: &mut dyn BufRead, out: &mut dyn Write, err: &mut dyn Write, prompt: Option<&str>) -> io::Result<Session> {
+    let mut session = Session::new();
+    let mut line = String::new();
+    loop {
+        if let Some(prompt) = prompt {
+            write!(out, "{}", prompt)?;
+            out.flush()?;
+        }
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            if prompt.is_some() {
+                writeln!(out)?;
+            }
+            return Ok(session);
+        }
+        if session.handle(&line, out, err)? == Control::Quit {
+            return Ok(session);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `script` to a fresh session; returns stdout, stderr and the error count
+    fn script(script: &str) -> (String, String, usize) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let session = run(&mut script.as_bytes(), &mut out, &mut err, None).unwrap();
+        (String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap(), session.errors())
+    }
+
+    #[test]
+    fn history_recall_reruns_and_records_the_expanded_line() {
+        let (out, err, errors) = script("x = 2\nx * 10\n!!\n!1\n!7\n:history\n");
+        assert_eq!(out, "x = 2\n20\n20\nx = 2\n   1  x = 2\n   2  x * 10\n   3  x * 10\n   4  x = 2\n   5  :history\n");
+        assert_eq!((err.as_str(), errors), ("error: no history entry `!7`\n", 1));
+    }
+
+    #[test]
+    fn quit_stops_reading_and_unknown_commands_are_errors() {
+        let (out, err, errors) = script("1 + 1\n:frobnicate\n:quit\n2 + 2\n");
+        assert_eq!(out, "2\n");
+        assert_eq!((err.as_str(), errors), ("error: unknown command `:frobnicate`; :help lists them\n", 1));
+    }
+
+    #[test]
+    fn load_reports_the_failing_line_through_the_context_chain() {
+        let path = std::env::temp_dir().join(format!("tech-notes-calc-{}.calc", std::process::id()));
+        std::fs::write(&path, "# setup\nrate = 0.5\n\ncost(n) = n * rate\ncost(4) + tax\n").unwrap();
+        let (out, err, errors) = script(&format!(":load {}\n:vars\n", path.display()));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(out.starts_with("rate = 0.5\ndefined cost\n"), "{}", out);
+        assert!(out.contains("\nrate = 0.5\n"), "rate should stay defined: {}", out);
+        let expected = format!("error: {} line 5: cost(4) + tax\n  caused by: unknown variable `tax` at column 11\n", path.display());
+        assert_eq!((err, errors), (expected, 1));
+
+        let (_, err, _) = script(":load /no/such/file.calc\n");
+        assert!(err.starts_with("error: could not read /no/such/file.calc\n  caused by: "), "{}", err);
+    }
+}
@@ -0,0 +1,86 @@
+//! Runs the `calc` binary on scripted stdin, the way a pipe or a CI job would.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn calc() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("calc"))
+}
+
+#[test]
+fn a_piped_session_prints_one_result_per_line_without_prompts() {
+    calc()
+        .write_stdin("1 + 2 * 3\nr = 2\narea(r) = pi * r^2\nround(area(r) * 100) / 100\nans + 0.1 + 0.2\n")
+        .assert()
+        .success()
+        .stdout("7\nr = 2\ndefined area\n12.57\n12.87\n")
+        .stderr("");
+}
+
+#[test]
+fn errors_go_to_stderr_with_the_span_underlined_and_the_session_continues() {
+    calc()
+        .write_stdin("x = 4\n2 * (x - y)\nx / (x - 4)\nsqrt(x)\n")
+        .assert()
+        .failure()
+        .stdout("x = 4\n2\n")
+        .stderr(concat!(
+            "error: unknown variable `y`\n",
+            "  | 2 * (x - y)\n",
+            "  |          ^\n",
+            "error: division by zero\n",
+            "  | x / (x - 4)\n",
+            "  |      ^^^^^\n",
+        ));
+}
+
+#[test]
+fn functions_history_and_listings_work_across_lines() {
+    calc()
+        .write_stdin("sq(n) = n * n\nsq(12)\n!!\nsq(ans)\n:funcs\n:history\n:quit\nsq(1)\n")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "defined sq\n144\n144\n20736\n",
+            "sq(n) = n * n\n",
+            "builtins: abs ceil cos exp floor ln log max min round sin sqrt tan\n",
+            "   1  sq(n) = n * n\n   2  sq(12)\n   3  sq(12)\n   4  sq(ans)\n   5  :funcs\n   6  :history\n",
+        ));
+}
+
+#[test]
+fn a_failing_load_explains_which_line_and_why() {
+    let path = std::env::temp_dir().join(format!("tech-notes-calc-cli-{}.calc", std::process::id()));
+    std::fs::write(&path, "a = 1\nb = a +\n").unwrap();
+    let assert = calc().write_stdin(format!(":load {}\na\n", path.display())).assert();
+    std::fs::remove_file(&path).unwrap();
+
+    assert
+        .failure()
+        .stdout("a = 1\n1\n")
+        .stderr(format!("error: {} line 2: b = a +\n  caused by: expected a number, name or `(` at column 8\n", path.display()));
+}
+
+#[test]
+fn eval_runs_its_lines_and_skips_stdin() {
+    calc()
+        .args(["-e", "x = 3", "--eval", "x ^ 2"])
+        .write_stdin("this is never read\n")
+        .assert()
+        .success()
+        .stdout("x = 3\n9\n");
+    calc().args(["-e", "1 +"]).assert().failure().stderr(predicate::str::starts_with("error: expected"));
+}
+
+#[test]
+fn a_line_nested_past_the_limit_is_an_error_not_a_stack_overflow() {
+    let depth = 10_000;
+    let script = format!("{}1{}\n{}1\n1 + 1\n", "(".repeat(depth), ")".repeat(depth), "-".repeat(depth));
+    calc()
+        .write_stdin(script)
+        .assert()
+        .failure()
+        .stdout("2\n")
+        .stderr(predicate::str::starts_with("error: expression nested more than 128 levels deep\n"))
+        .stderr(predicate::str::contains("overflow").not());
+}
@@ -0,0 +1,64 @@
+//! Registry of the runnable snippet demos.
+//!
+//! Every entry is addressed as `<category>/<name>`, where the category is the
+//! snippet directory and the name is the module inside that crate. Each snippet crate
+//! registers its own `Demo` implementations in a `DEMOS` slice; this module only merges
+//! them. Demos that need an optional dependency are only registered when the matching
+//! cargo feature is on.
+
+use demo::Demo;
+
+/// Every registered demo, sorted by id.
+pub fn all() -> Vec<&'static dyn Demo> {
+    demo::collect(&[
+        algorithms::DEMOS,
+        concurrency::DEMOS,
+        data_structures::DEMOS,
+        networking::DEMOS,
+        patterns::DEMOS,
+        rust_lang::DEMOS,
+    ])
+}
+
+/// Looks up a demo by its exact `<category>/<name>` id.
+pub fn find(id: &str) -> Option<&'static dyn Demo> {
+    all().into_iter().find(|demo| demo.id() == id)
+}
+
+/// All demos whose id contains `filter`, or every demo when there is none.
+pub fn matching(filter: Option<&str>) -> Vec<&'static dyn Demo> {
+    all()
+        .into_iter()
+        .filter(|demo| filter.is_none_or(|f| demo.matches(f)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn ids_are_unique() {
+        let demos = all();
+        let ids: HashSet<String> = demos.iter().map(|demo| demo.id()).collect();
+        assert_eq!(ids.len(), demos.len());
+    }
+
+    #[test]
+    fn every_snippet_crate_registers_demos() {
+        let categories: HashSet<&str> = all().iter().map(|demo| demo.category()).collect();
+        for category in ["algorithms", "concurrency", "data-structures", "design-patterns", "networking", "rust-lang"] {
+            assert!(categories.contains(category), "no demos registered for {}", category);
+        }
+    }
+
+    #[test]
+    fn demos_write_to_the_given_sink() {
+        for id in ["data-structures/cuckoo_hash", "design-patterns/observer_pattern"] {
+            let mut out = Vec::new();
+            find(id).unwrap().run(&mut out).unwrap();
+            assert!(!out.is_empty(), "{} wrote nothing", id);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! `tech-notes`: list and run the Rust snippet demos without compiling each file by hand.
+//!
+//! Usage:
+//!
+//! ```text
+//! tech-notes list [--filter <TEXT>]
+//! tech-notes run <category>/<name> [--json]
+//! tech-notes run --all [--filter <TEXT>] [--json]
+//! tech-notes visualize [sort|graph] [OPTIONS]   (with the `visualization` feature)
+//! ```
+//!
+//! With `--json`, `run` prints one JSON object per demo instead of the narration: its `id`,
+//! the text it would have printed as `output`, and, for demos that compute something worth
+//! inspecting (sort traces, traversal orders), a `results` value.
+
+mod demos;
+
+use clap::{Parser, Subcommand};
+use demo::Demo;
+use serde::Serialize;
+use std::io;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "tech-notes", version, about = "Lists and runs the Rust snippet demos")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the registered demos as `<category>/<name>`
+    List {
+        /// Only show demos whose id contains this text
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Run one demo, or every matching demo with `--all`
+    Run {
+        /// Demo to run, e.g. `algorithms/sorting_algorithms`
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        demo: Option<String>,
+        /// Run every registered demo
+        #[arg(long)]
+        all: bool,
+        /// With `--all`, only run demos whose id contains this text
+        #[arg(long, requires = "all")]
+        filter: Option<String>,
+        /// Print one JSON object per demo instead of its narration
+        #[arg(long)]
+        json: bool,
+    },
+    /// Animate sorting and graph traversal traces in the terminal
+    #[cfg(feature = "visualization")]
+    Visualize(tui_visualizer::Options),
+}
+
+fn list(filter: Option<&str>) -> ExitCode {
+    let matches = demos::matching(filter);
+    if matches.is_empty() {
+        eprintln!("no demos match {:?}", filter.unwrap_or_default());
+        return ExitCode::FAILURE;
+    }
+
+    let width = matches.iter().map(|demo| demo.id().len()).max().unwrap_or(0);
+    for demo in matches {
+        println!("{:<width$}  {}", demo.id(), demo.description());
+    }
+    ExitCode::SUCCESS
+}
+
+/// One line of `run --json` output.
+#[derive(Serialize)]
+struct Report {
+    id: String,
+    output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    results: Option<serde_json::Value>,
+}
+
+/// Runs `demo` against stdout, reporting a failed write instead of panicking.
+fn run_demo(demo: &dyn Demo, json: bool) -> bool {
+    let written = if json {
+        let mut output = Vec::new();
+        demo.run(&mut output).map(|()| {
+            let report = Report {
+                id: demo.id(),
+                output: String::from_utf8_lossy(&output).into_owned(),
+                results: demo.results(),
+            };
+            println!("{}", serde_json::to_string(&report).expect("reports always serialize"));
+        })
+    } else {
+        demo.run(&mut io::stdout())
+    };
+
+    match written {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("{}: {}", demo.id(), error);
+            false
+        }
+    }
+}
+
+fn run_one(id: &str, json: bool) -> ExitCode {
+    let Some(demo) = demos::find(id) else {
+        eprintln!("unknown demo '{}'; see `tech-notes list`", id);
+        return ExitCode::FAILURE;
+    };
+    if run_demo(demo, json) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn run_all(filter: Option<&str>, json: bool) -> ExitCode {
+    let matches = demos::matching(filter);
+    if matches.is_empty() {
+        eprintln!("no demos match {:?}", filter.unwrap_or_default());
+        return ExitCode::FAILURE;
+    }
+
+    for (i, demo) in matches.iter().enumerate() {
+        // JSON output is one object per line, with no banners between them
+        if !json {
+            if i > 0 {
+                println!();
+            }
+            println!("########## {} ##########", demo.id());
+        }
+        if !run_demo(*demo, json) {
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Command::List { filter } => list(filter.as_deref()),
+        Command::Run { demo: Some(id), json, .. } => run_one(&id, json),
+        Command::Run { filter, json, .. } => run_all(filter.as_deref(), json),
+        #[cfg(feature = "visualization")]
+        Command::Visualize(options) => tui_visualizer::run(options),
+    }
+}
@@ -0,0 +1,237 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn tech_notes() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("tech-notes"))
+}
+
+#[test]
+fn list_shows_every_category() {
+    tech_notes()
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("algorithms/sorting_algorithms"))
+        .stdout(predicate::str::contains("concurrency/thread_pool"))
+        .stdout(predicate::str::contains("data-structures/cuckoo_hash"))
+        .stdout(predicate::str::contains("design-patterns/observer_pattern"));
+}
+
+#[test]
+fn list_filter_narrows_the_output() {
+    let output = tech_notes()
+        .args(["list", "--filter", "PATTERN"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let ids: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            "design-patterns/adapter_pattern",
+            "design-patterns/bridge_pattern",
+            "design-patterns/builder_pattern",
+            "design-patterns/chain_of_responsibility_pattern",
+            "design-patterns/dependency_injection_pattern",
+            "design-patterns/event_bus",
+            "design-patterns/facade_pattern",
+            "design-patterns/factory_pattern",
+            "design-patterns/flyweight_pattern",
+            "design-patterns/interpreter_pattern",
+            "design-patterns/iterator_pattern",
+            "design-patterns/mediator_pattern",
+            "design-patterns/memento_pattern",
+            "design-patterns/object_pool_pattern",
+            "design-patterns/observer_pattern",
+            "design-patterns/prototype_pattern",
+            "design-patterns/proxy_pattern",
+            "design-patterns/repository_pattern",
+            "design-patterns/singleton_pattern",
+            "design-patterns/state_pattern",
+            "design-patterns/strategy_pattern",
+            "design-patterns/template_method_pattern",
+            "design-patterns/threaded_observer_pattern",
+            "design-patterns/visitor_pattern",
+            "rust-lang/testing_patterns",
+        ]
+    );
+}
+
+#[test]
+fn list_filter_without_matches_fails() {
+    tech_notes()
+        .args(["list", "--filter", "no-such-demo"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no demos match"));
+}
+
+#[test]
+fn run_executes_a_single_demo() {
+    tech_notes()
+        .args(["run", "algorithms/sorting_algorithms"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Quick Sort: [11, 12, 22, 25, 34, 64, 90]",
+        ));
+}
+
+#[test]
+fn run_rejects_an_unknown_demo() {
+    tech_notes()
+        .args(["run", "algorithms/bogo_sort"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown demo 'algorithms/bogo_sort'"));
+}
+
+#[test]
+fn run_requires_a_demo_or_all() {
+    tech_notes().arg("run").assert().failure().code(2);
+}
+
+#[test]
+fn run_filter_needs_all() {
+    tech_notes()
+        .args(["run", "--filter", "sorting"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn run_all_with_filter_runs_each_match_in_order() {
+    let output = tech_notes()
+        .args(["run", "--all", "--filter", "design-patterns/"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let headers: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("##########"))
+        .collect();
+    assert_eq!(
+        headers,
+        [
+            "########## design-patterns/adapter_pattern ##########",
+            "########## design-patterns/bridge_pattern ##########",
+            "########## design-patterns/builder_pattern ##########",
+            "########## design-patterns/chain_of_responsibility_pattern ##########",
+            "########## design-patterns/dependency_injection_pattern ##########",
+            "########## design-patterns/event_bus ##########",
+            "########## design-patterns/facade_pattern ##########",
+            "########## design-patterns/factory_pattern ##########",
+            "########## design-patterns/flyweight_pattern ##########",
+            "########## design-patterns/interpreter_pattern ##########",
+            "########## design-patterns/iterator_pattern ##########",
+            "########## design-patterns/mediator_pattern ##########",
+            "########## design-patterns/memento_pattern ##########",
+            "########## design-patterns/object_pool_pattern ##########",
+            "########## design-patterns/observer_pattern ##########",
+            "########## design-patterns/prototype_pattern ##########",
+            "########## design-patterns/proxy_pattern ##########",
+            "########## design-patterns/repository_pattern ##########",
+            "########## design-patterns/singleton_pattern ##########",
+            "########## design-patterns/state_pattern ##########",
+            "########## design-patterns/strategy_pattern ##########",
+            "########## design-patterns/template_method_pattern ##########",
+            "########## design-patterns/threaded_observer_pattern ##########",
+            "########## design-patterns/visitor_pattern ##########",
+        ]
+    );
+    assert!(stdout.contains("Heat index:"));
+}
+
+#[test]
+fn run_json_reports_output_and_results() {
+    let output = tech_notes()
+        .args(["run", "algorithms/sorting_algorithms", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["id"], "algorithms/sorting_algorithms");
+    assert!(report["output"].as_str().unwrap().contains("Quick Sort: [11, 12, 22, 25, 34, 64, 90]"));
+
+    let runs = report["results"].as_array().unwrap();
+    assert_eq!(runs.len(), 7);
+    assert_eq!(runs[4]["algorithm"], "quick");
+    assert_eq!(runs[4]["sorted"], serde_json::json!([11, 12, 22, 25, 34, 64, 90]));
+    assert!(!runs[4]["steps"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn run_all_json_prints_one_object_per_line() {
+    let output = tech_notes()
+        .args(["run", "--all", "--filter", "design-patterns/", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let reports: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let ids: Vec<&str> = reports.iter().map(|r| r["id"].as_str().unwrap()).collect();
+    assert_eq!(
+        ids,
+        [
+            "design-patterns/adapter_pattern",
+            "design-patterns/bridge_pattern",
+            "design-patterns/builder_pattern",
+            "design-patterns/chain_of_responsibility_pattern",
+            "design-patterns/dependency_injection_pattern",
+            "design-patterns/event_bus",
+            "design-patterns/facade_pattern",
+            "design-patterns/factory_pattern",
+            "design-patterns/flyweight_pattern",
+            "design-patterns/interpreter_pattern",
+            "design-patterns/iterator_pattern",
+            "design-patterns/mediator_pattern",
+            "design-patterns/memento_pattern",
+            "design-patterns/object_pool_pattern",
+            "design-patterns/observer_pattern",
+            "design-patterns/prototype_pattern",
+            "design-patterns/proxy_pattern",
+            "design-patterns/repository_pattern",
+            "design-patterns/singleton_pattern",
+            "design-patterns/state_pattern",
+            "design-patterns/strategy_pattern",
+            "design-patterns/template_method_pattern",
+            "design-patterns/threaded_observer_pattern",
+            "design-patterns/visitor_pattern",
+        ]
+    );
+    // These demos only narrate
+    assert!(reports.iter().all(|r| r.get("results").is_none()));
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn run_all_handles_async_demos() {
+    tech_notes()
+        .args(["run", "--all", "--filter", "async_basics"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Shutdown report"));
+}
+
+#[cfg(feature = "visualization")]
+#[test]
+fn visualize_validates_options_before_taking_the_terminal() {
+    tech_notes()
+        .args(["visualize", "graph", "--start", "Z"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("start vertex 'Z' is not in the graph"));
+}
@@ -0,0 +1,176 @@
+//! Snapshot tests of what every registered demo prints.
+//!
+//! Each demo runs against an in-memory sink and its output is compared with
+//! `tests/snapshots/`. Timestamps and timings are replaced with placeholders first, so
+//! only changes to what the demo actually teaches show up. After an intended change,
+//! review the new output with `cargo insta review` (or rerun with `INSTA_UPDATE=always`).
+
+use demo::Demo;
+
+fn registry() -> Vec<&'static dyn Demo> {
+    demo::collect(&[
+        algorithms::DEMOS,
+        concurrency::DEMOS,
+        data_structures::DEMOS,
+        networking::DEMOS,
+        patterns::DEMOS,
+        rust_lang::DEMOS,
+    ])
+}
+
+fn output_of(id: &str) -> String {
+    let demo = registry()
+        .into_iter()
+        .find(|demo| demo.id() == id)
+        .unwrap_or_else(|| panic!("no demo registered as {}", id));
+    let mut out = Vec::new();
+    demo.run(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// Just the `=====` section headings, for demos whose lines depend on thread scheduling.
+fn headings_of(id: &str) -> String {
+    let output = output_of(id);
+    let headings: Vec<&str> = output.lines().filter(|line| line.starts_with("=====")).collect();
+    assert!(!headings.is_empty(), "{} printed no section headings", id);
+    headings.join("\n")
+}
+
+/// Placeholders for everything that changes from run to run or machine to machine.
+fn redactions() -> Vec<(&'static str, &'static str)> {
+    vec![
+        (r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}", "[timestamp]"),
+        (r"SystemTime \{[^}]*\}", "[timestamp]"),
+        // Right-aligned timings pad to the width of the real value, so the padding goes too
+        (r" {2,}\d+(\.\d+)?(ns|µs|ms|s)\b", "  [duration]"),
+        (r"\d+(\.\d+)?(ns|µs|ms|s)\b", "[duration]"),
+        (r"using \d+ threads", "using [n] threads"),
+        // Object file sizes measured by rust-lang's build.rs depend on the toolchain
+        (r"(generic|dyn|enum) \d+ bytes", "$1 [n] bytes"),
+    ]
+}
+
+macro_rules! snapshot_tests {
+    ($ids:ident, $render:ident: $($(#[$attr:meta])* $test:ident => $id:literal,)*) => {
+        $(
+            $(#[$attr])*
+            #[test]
+            fn $test() {
+                insta::with_settings!({ filters => redactions(), prepend_module_to_snapshot => false }, {
+                    insta::assert_snapshot!(stringify!($test), $render($id));
+                });
+            }
+        )*
+
+        const $ids: &[&str] = &[$($(#[$attr])* $id,)*];
+    };
+}
+
+snapshot_tests! {
+    FULL_OUTPUT, output_of:
+    array_greedy => "algorithms/array_greedy",
+    bigint => "algorithms/bigint",
+    bit_tricks => "algorithms/bit_tricks",
+    bitset => "algorithms/bitset",
+    bwt => "algorithms/bwt",
+    checksums => "algorithms/checksums",
+    combinatorics => "algorithms/combinatorics",
+    convex_hull => "algorithms/convex_hull",
+    csp => "algorithms/csp",
+    encoding => "algorithms/encoding",
+    factorization => "algorithms/factorization",
+    fft => "algorithms/fft",
+    fractional_knapsack => "algorithms/fractional_knapsack",
+    gray_code => "algorithms/gray_code",
+    graph_traversal => "algorithms/graph_traversal",
+    huffman => "algorithms/huffman",
+    intervals => "algorithms/intervals",
+    job_scheduling => "algorithms/job_scheduling",
+    lz => "algorithms/lz",
+    matrix => "algorithms/matrix",
+    matrix_expo => "algorithms/matrix_expo",
+    miller_rabin => "algorithms/miller_rabin",
+    modular => "algorithms/modular",
+    n_queens => "algorithms/n_queens",
+    polygon => "algorithms/polygon",
+    primes => "algorithms/primes",
+    prng => "algorithms/prng",
+    quadtree => "algorithms/quadtree",
+    rle => "algorithms/rle",
+    rsa_demo => "algorithms/rsa_demo",
+    sorting_algorithms => "algorithms/sorting_algorithms",
+    statistics => "algorithms/statistics",
+    subsets => "algorithms/subsets",
+    sudoku => "algorithms/sudoku",
+    word_search => "algorithms/word_search",
+    actor_runtime => "concurrency/actor_runtime",
+    futures_from_scratch => "concurrency/futures_from_scratch",
+    #[cfg(feature = "rayon")]
+    rayon_parallel => "concurrency/rayon_parallel",
+    sync_primitives => "concurrency/sync_primitives",
+    cuckoo_hash => "data-structures/cuckoo_hash",
+    sketches => "data-structures/sketches",
+    adapter_pattern => "design-patterns/adapter_pattern",
+    bridge_pattern => "design-patterns/bridge_pattern",
+    builder_pattern => "design-patterns/builder_pattern",
+    chain_of_responsibility_pattern => "design-patterns/chain_of_responsibility_pattern",
+    dependency_injection_pattern => "design-patterns/dependency_injection_pattern",
+    event_bus => "design-patterns/event_bus",
+    facade_pattern => "design-patterns/facade_pattern",
+    factory_pattern => "design-patterns/factory_pattern",
+    flyweight_pattern => "design-patterns/flyweight_pattern",
+    interpreter_pattern => "design-patterns/interpreter_pattern",
+    iterator_pattern => "design-patterns/iterator_pattern",
+    mediator_pattern => "design-patterns/mediator_pattern",
+    memento_pattern => "design-patterns/memento_pattern",
+    object_pool_pattern => "design-patterns/object_pool_pattern",
+    observer_pattern => "design-patterns/observer_pattern",
+    prototype_pattern => "design-patterns/prototype_pattern",
+    proxy_pattern => "design-patterns/proxy_pattern",
+    repository_pattern => "design-patterns/repository_pattern",
+    singleton_pattern => "design-patterns/singleton_pattern",
+    state_pattern => "design-patterns/state_pattern",
+    strategy_pattern => "design-patterns/strategy_pattern",
+    template_method_pattern => "design-patterns/template_method_pattern",
+    threaded_observer_pattern => "design-patterns/threaded_observer_pattern",
+    visitor_pattern => "design-patterns/visitor_pattern",
+    http_server => "networking/http_server",
+    tcp_echo => "networking/tcp_echo",
+    udp_chat => "networking/udp_chat",
+    dispatch => "rust-lang/dispatch",
+    error_handling => "rust-lang/error_handling",
+    file_io => "rust-lang/file_io",
+    iterators => "rust-lang/iterators",
+    lifetimes => "rust-lang/lifetimes",
+    macros_declarative => "rust-lang/macros_declarative",
+    smart_pointers => "rust-lang/smart_pointers",
+    testing_patterns => "rust-lang/testing_patterns",
+}
+
+// Interleaving, per-worker counts and early-exit points vary between runs here
+snapshot_tests! {
+    HEADINGS_ONLY, headings_of:
+    #[cfg(feature = "tokio")]
+    async_basics => "concurrency/async_basics",
+    #[cfg(feature = "tokio")]
+    async_channels => "concurrency/async_channels",
+    coordination => "concurrency/coordination",
+    #[cfg(feature = "crossbeam")]
+    crossbeam_examples => "concurrency/crossbeam_examples",
+    lock_free => "concurrency/lock_free",
+    producer_consumer => "concurrency/producer_consumer",
+    rate_limiter => "concurrency/rate_limiter",
+    scoped_threads => "concurrency/scoped_threads",
+    thread_pool => "concurrency/thread_pool",
+    work_stealing => "concurrency/work_stealing",
+}
+
+#[test]
+fn every_demo_has_a_snapshot() {
+    let missing: Vec<String> = registry()
+        .iter()
+        .map(|demo| demo.id())
+        .filter(|id| !FULL_OUTPUT.contains(&id.as_str()) && !HEADINGS_ONLY.contains(&id.as_str()))
+        .collect();
+    assert!(missing.is_empty(), "no snapshot test for {:?}", missing);
+}
@@ -3,6 +3,18 @@ set -e
 
 FILE=$1
 FILENAME=$(basename "$FILE")
+NAME="${FILENAME%.rs}"
+
+# Snippets that live in a workspace crate run as that crate's example
+DIR=$(dirname "$FILE")
+while [ "$DIR" != "." ] && [ "$DIR" != "/" ]; do
+    if [ -f "$DIR/Cargo.toml" ] && [ -f "$DIR/examples/$NAME.rs" ]; then
+        echo "Running $FILENAME with cargo..."
+        cargo run --quiet --manifest-path "$DIR/Cargo.toml" --example "$NAME"
+        exit 0
+    fi
+    DIR=$(dirname "$DIR")
+done
 
 echo "Running $FILENAME with rustc..."
 rustc -o temp_executable "$FILE" && ./temp_executable
@@ -0,0 +1,27 @@
+//! The `calc` interpreter, one `Env` per input, fed arbitrary lines.
+//!
+//! A line may fail, but never panic or overflow the stack. Errors must point inside the
+//! line on character boundaries, so `render` can underline them, and every value a line
+//! produces is finite.
+
+#![no_main]
+
+use calc::interpreter::{Env, Outcome};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|script: &str| {
+    let mut env = Env::new();
+    for line in script.lines() {
+        match env.execute(line) {
+            Ok(Outcome::Value(value) | Outcome::Assigned(_, value)) => assert!(value.is_finite(), "{}", line),
+            Ok(Outcome::Defined(_)) => {}
+            Err(error) => {
+                let span = error.span;
+                assert!(span.start <= span.end && span.end <= line.len(), "{:?} in {:?}", span, line);
+                assert!(line.is_char_boundary(span.start) && line.is_char_boundary(span.end));
+                error.render(line);
+            }
+        }
+    }
+    assert!(env.vars().all(|(_, value)| value.is_finite()));
+});
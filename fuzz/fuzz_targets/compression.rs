@@ -0,0 +1,52 @@
+//! Huffman, RLE, LZ77, LZW and the BWT pipeline: round trips, and decompressors fed garbage.
+//!
+//! Every compressor must round-trip the input. Every decompressor must turn arbitrary
+//! bytes into either an error or some output, never a panic, an out-of-bounds slice or an
+//! allocation the input cannot justify.
+
+#![no_main]
+
+use algorithms::{bwt, huffman, lz, rle};
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    data: &'a [u8],
+    garbage: &'a [u8],
+    primary: u16,
+}
+
+/// Garbage whose bitmap header claims more than this is not decoded: a few bytes of gamma
+/// codes can legitimately describe a bitmap far bigger than libFuzzer's malloc limit. The
+/// rle unit tests cover headers that cannot be allocated at all
+const MAX_BITMAP_BYTES: u64 = 1 << 20;
+
+fuzz_target!(|input: Input| {
+    let data = input.data;
+    assert_eq!(huffman::decompress(&huffman::compress(data)).as_deref(), Ok(data));
+    assert_eq!(rle::decode_pairs(&rle::encode_pairs(data)).as_deref(), Ok(data));
+    assert_eq!(rle::decode(&rle::encode(data)).as_deref(), Ok(data));
+    assert_eq!(rle::decode_bitmap(&rle::encode_bitmap(data)).as_deref(), Ok(data));
+    assert_eq!(lz::lz77_decompress(&lz::Lz77::default().compress(data)).as_deref(), Ok(data));
+    assert_eq!(lz::lz77_decompress(&lz::Lz77::new(16, 5).compress(data)).as_deref(), Ok(data));
+    assert_eq!(lz::lzw_decompress(&lz::lzw_compress(data)).as_deref(), Ok(data));
+    assert_eq!(bwt::mtf_decode(&bwt::mtf_encode(data)), data);
+    let (last, primary) = bwt::bwt(data);
+    assert_eq!(bwt::inverse_bwt(&last, primary).as_deref(), Ok(data));
+    assert_eq!(bwt::decompress(&bwt::compress(data)).as_deref(), Ok(data));
+
+    let garbage = input.garbage;
+    let _ = huffman::decompress(garbage);
+    let _ = rle::decode_pairs(garbage);
+    let _ = rle::decode(garbage);
+    let claimed = garbage.first_chunk::<8>().map(|header| u64::from_le_bytes(*header));
+    if claimed.is_none_or(|bytes| bytes <= MAX_BITMAP_BYTES) {
+        let _ = rle::decode_bitmap(garbage);
+    }
+    let _ = lz::lz77_decompress(garbage);
+    let _ = lz::lzw_decompress(garbage);
+    let _ = bwt::mtf_decode(garbage);
+    let _ = bwt::inverse_bwt(garbage, usize::from(input.primary));
+    let _ = bwt::decompress(garbage);
+});
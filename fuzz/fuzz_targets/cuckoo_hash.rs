@@ -0,0 +1,64 @@
+//! `CuckooHashMap` against `std::collections::HashMap` as an oracle.
+//!
+//! Small tables, short displacement limits and tiny stashes push the fuzzer into the
+//! rehash, resize and stash paths that ordinary workloads rarely reach.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use data_structures::cuckoo_hash::{CuckooConfig, CuckooHashMap};
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashMap;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Insert(u8, u32),
+    TryInsert(u8, u32),
+    Remove(u8),
+    Get(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    capacity: u8,
+    max_displacements: u8,
+    stash_capacity: u8,
+    max_rehashes: u8,
+    seed: u64,
+    ops: Vec<Op>,
+}
+
+fuzz_target!(|input: Input| {
+    let config = CuckooConfig {
+        max_displacements: usize::from(input.max_displacements % 16),
+        stash_capacity: usize::from(input.stash_capacity % 4),
+        max_rehashes: usize::from(input.max_rehashes % 4) + 1,
+        seed: input.seed,
+    };
+    let mut map = CuckooHashMap::with_config(usize::from(input.capacity % 16) + 1, config);
+    let mut oracle = HashMap::new();
+
+    for op in input.ops {
+        match op {
+            Op::Insert(key, value) => assert_eq!(map.insert(key, value), oracle.insert(key, value)),
+            Op::TryInsert(key, value) => match map.try_insert(key, value) {
+                Ok(old) => assert_eq!(old, oracle.insert(key, value)),
+                Err((evicted, evicted_value)) => {
+                    // Some key, not necessarily this one, lost its slot
+                    oracle.insert(key, value);
+                    assert_eq!(oracle.remove(&evicted), Some(evicted_value));
+                }
+            },
+            Op::Remove(key) => assert_eq!(map.remove(&key), oracle.remove(&key)),
+            Op::Get(key) => assert_eq!(map.get(&key), oracle.get(&key)),
+        }
+
+        assert_eq!(map.len(), oracle.len());
+        assert!(map.stash_len() <= config.stash_capacity);
+        assert!(map.load_factor() <= 1.0);
+    }
+
+    for (key, value) in &oracle {
+        assert_eq!(map.get(key), Some(value));
+    }
+});
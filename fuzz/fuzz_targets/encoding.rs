@@ -0,0 +1,33 @@
+//! Base64, Base32 and hex: round trips, and decoders that only ever accept canonical text.
+//!
+//! Every encoding must decode its own output back to the input. Arbitrary text must either
+//! be rejected or decode to bytes that encode back to exactly that text (lower-cased, for
+//! hex), since each byte string has one accepted encoding.
+
+#![no_main]
+
+use algorithms::encoding::ALL;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    data: &'a [u8],
+    text: &'a str,
+}
+
+fuzz_target!(|input: Input| {
+    for encoding in ALL {
+        let encoded = encoding.encode(input.data);
+        assert_eq!(encoded.len(), encoding.encoded_len(input.data.len()), "{}", encoding.name());
+        assert_eq!(encoding.decode(&encoded).as_deref(), Ok(input.data), "{}", encoding.name());
+
+        if let Ok(decoded) = encoding.decode(input.text) {
+            let canonical = match encoding.name() {
+                "hex" => input.text.to_ascii_lowercase(),
+                _ => input.text.to_string(),
+            };
+            assert_eq!(encoding.encode(&decoded), canonical, "{}", encoding.name());
+        }
+    }
+});
@@ -0,0 +1,55 @@
+//! Edge-list parsing and the three traversals on whatever graph it yields.
+//!
+//! Every traversal must visit each vertex reachable from the start exactly once, start
+//! with the start vertex, and agree with the others on which vertices are reachable.
+
+#![no_main]
+
+use algorithms::graph_traversal::{parse_edges, Graph, Traversal};
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashSet;
+
+fuzz_target!(|spec: &str| {
+    let Ok(edges) = parse_edges(spec) else {
+        return;
+    };
+    for &(a, b) in &edges {
+        for endpoint in [a, b] {
+            assert!(!endpoint.is_empty());
+            assert!(!endpoint.contains(|c: char| c == ',' || c.is_whitespace()));
+        }
+    }
+
+    let graph = Graph::from_edges(edges.iter().copied());
+    let Some(start) = graph.vertices().into_iter().next() else {
+        assert!(edges.is_empty());
+        return;
+    };
+
+    let mut reachable: Option<HashSet<String>> = None;
+    for traversal in Traversal::ALL {
+        let order = graph.traverse(traversal, &start, |_| {});
+        assert_eq!(order.first(), Some(&start), "{:?}", traversal);
+
+        let visited: HashSet<String> = order.iter().cloned().collect();
+        assert_eq!(visited.len(), order.len(), "{:?} visited a vertex twice", traversal);
+        assert!(visited.iter().all(|vertex| graph.contains(vertex)));
+        match &reachable {
+            Some(expected) => assert_eq!(&visited, expected, "{:?}", traversal),
+            None => reachable = Some(visited),
+        }
+
+        // The trace reports the same visits the plain traversal returns
+        let frames = graph.trace(traversal, &start);
+        assert_eq!(frames.len(), order.len());
+        assert_eq!(frames.last().map(|frame| &frame.visited), Some(&order));
+    }
+
+    // The visited set is closed under taking neighbors, i.e. it is the whole component
+    let reachable = reachable.unwrap();
+    for vertex in &reachable {
+        for neighbor in graph.get_sorted_neighbors(vertex) {
+            assert!(reachable.contains(&neighbor), "{} -> {} not visited", vertex, neighbor);
+        }
+    }
+});
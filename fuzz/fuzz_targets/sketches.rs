@@ -0,0 +1,82 @@
+//! Count-min sketch and HyperLogLog invariants over arbitrary streams.
+//!
+//! A count-min estimate never undercounts, and merging the sketches of two halves of a
+//! stream must give exactly the sketch of the whole stream, for both structures.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use data_structures::sketches::{CountMinSketch, HyperLogLog};
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashMap;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    width: u8,
+    depth: u8,
+    precision: u8,
+    split: u16,
+    stream: Vec<(u16, u16)>,
+}
+
+fuzz_target!(|input: Input| {
+    let width = usize::from(input.width % 64) + 1;
+    let depth = usize::from(input.depth % 8) + 1;
+    let precision = input.precision % 13 + 4;
+    let split = usize::from(input.split).min(input.stream.len());
+    let (first, second) = input.stream.split_at(split);
+
+    let build_cms = |items: &[(u16, u16)]| {
+        let mut sketch = CountMinSketch::new(width, depth);
+        for &(item, count) in items {
+            sketch.add(&item, u64::from(count));
+        }
+        sketch
+    };
+    let build_hll = |items: &[(u16, u16)]| {
+        let mut sketch = HyperLogLog::new(precision).unwrap();
+        for (item, _) in items {
+            sketch.add(item);
+        }
+        sketch
+    };
+
+    // Count-min: estimates bracket the true counts
+    let whole = build_cms(&input.stream);
+    let mut truth: HashMap<u16, u64> = HashMap::new();
+    for &(item, count) in &input.stream {
+        *truth.entry(item).or_default() += u64::from(count);
+    }
+    assert_eq!(whole.total(), truth.values().sum::<u64>());
+    for (item, &count) in &truth {
+        let estimate = whole.estimate(item);
+        assert!(estimate >= count, "undercounted {}: {} < {}", item, estimate, count);
+        assert!(estimate <= whole.total());
+    }
+
+    let mut merged = build_cms(first);
+    merged.merge(&build_cms(second)).unwrap();
+    assert_eq!(merged.total(), whole.total());
+    for item in truth.keys() {
+        assert_eq!(merged.estimate(item), whole.estimate(item));
+    }
+    assert!(merged.merge(&CountMinSketch::new(width + 1, depth)).is_err());
+
+    // HyperLogLog: merging is exact, and re-adding a seen item changes nothing
+    let whole = build_hll(&input.stream);
+    let estimate = whole.estimate();
+    assert!(estimate.is_finite() && estimate >= 0.0);
+
+    let mut merged = build_hll(first);
+    merged.merge(&build_hll(second)).unwrap();
+    assert_eq!(merged.estimate().to_bits(), estimate.to_bits());
+
+    if let Some((item, _)) = input.stream.first() {
+        let mut again = whole.clone();
+        again.add(item);
+        assert_eq!(again.estimate().to_bits(), estimate.to_bits());
+    }
+    if precision < 16 {
+        assert!(merged.merge(&HyperLogLog::new(precision + 1).unwrap()).is_err());
+    }
+});
@@ -0,0 +1,221 @@
+// Browser front end for the `wasm` feature of the algorithms crate.
+// Build the bindings into ./pkg first; see README.md in this directory.
+import init, {
+  sortAlgorithms,
+  sortTrace,
+  traversals,
+  sampleGraphEdges,
+  graphTrace,
+} from "./pkg/algorithms.js";
+
+const $ = (id) => document.getElementById(id);
+
+// ========== Player ==========
+
+// Steps through `count` frames, calling `render(index)`; index -1 is the initial state.
+class Player {
+  constructor(render, speedInput, playButton) {
+    this.render = render;
+    this.speedInput = speedInput;
+    this.playButton = playButton;
+    this.count = 0;
+    this.index = -1;
+    this.timer = null;
+  }
+
+  load(count) {
+    this.pause();
+    this.count = count;
+    this.index = -1;
+    this.render(this.index);
+  }
+
+  step() {
+    if (this.index + 1 >= this.count) {
+      this.pause();
+      return false;
+    }
+    this.index += 1;
+    this.render(this.index);
+    return true;
+  }
+
+  play() {
+    if (this.index + 1 >= this.count) this.load(this.count);
+    this.playButton.textContent = "Pause";
+    const tick = () => {
+      if (this.step()) this.timer = setTimeout(tick, 1000 / Number(this.speedInput.value));
+    };
+    tick();
+  }
+
+  pause() {
+    clearTimeout(this.timer);
+    this.timer = null;
+    this.playButton.textContent = "Play";
+  }
+
+  toggle() {
+    this.timer === null ? this.play() : this.pause();
+  }
+}
+
+function showError(error) {
+  $("error").textContent = error ? String(error.message ?? error) : "";
+}
+
+// ========== Sorting ==========
+
+let sortData = null;
+let sortValues = [];
+
+function loadSort() {
+  const values = $("sort-input").value.split(/[\s,]+/).filter(Boolean).map(Number);
+  if (values.some((v) => !Number.isInteger(v))) {
+    showError("Sort input must be whole numbers separated by spaces or commas");
+    return;
+  }
+  try {
+    sortData = JSON.parse(sortTrace($("sort-algorithm").value, Int32Array.from(values)));
+    showError(null);
+  } catch (error) {
+    showError(error);
+    return;
+  }
+  sortPlayer.load(sortData.steps.length);
+}
+
+function renderSort(index) {
+  // Replay from the input; steps are cheap and this keeps Step/Reset trivial.
+  sortValues = sortData.input.slice();
+  for (let k = 0; k <= index; k++) applyStep(sortValues, sortData.steps[k]);
+  const step = index >= 0 ? sortData.steps[index] : null;
+
+  const canvas = $("sort-canvas");
+  const ctx = canvas.getContext("2d");
+  ctx.clearRect(0, 0, canvas.width, canvas.height);
+  const min = Math.min(0, ...sortValues);
+  const max = Math.max(1, ...sortValues);
+  const width = canvas.width / Math.max(1, sortValues.length);
+
+  sortValues.forEach((value, i) => {
+    const height = ((value - min) / (max - min)) * (canvas.height - 20) + 4;
+    ctx.fillStyle = colorFor(step, i);
+    ctx.fillRect(i * width + 1, canvas.height - height, Math.max(1, width - 2), height);
+  });
+
+  const counts = { compare: 0, swap: 0, set: 0 };
+  for (let k = 0; k <= index; k++) counts[sortData.steps[k].op] += 1;
+  $("sort-status").textContent =
+    `${sortData.algorithm}: step ${index + 1}/${sortData.steps.length}` +
+    `  compares=${counts.compare} swaps=${counts.swap} writes=${counts.set}\n` +
+    (step ? describeStep(step) : "initial input");
+}
+
+function applyStep(values, step) {
+  if (step.op === "swap") [values[step.i], values[step.j]] = [values[step.j], values[step.i]];
+  if (step.op === "set") values[step.i] = step.value;
+}
+
+function colorFor(step, i) {
+  if (!step) return "#8ab6d6";
+  const touched = step.op === "set" ? i === step.i : i === step.i || i === step.j;
+  if (!touched) return "#8ab6d6";
+  return { compare: "#f2a900", swap: "#d62828", set: "#2a9d8f" }[step.op];
+}
+
+function describeStep(step) {
+  if (step.op === "compare") return `compare [${step.i}] and [${step.j}]`;
+  if (step.op === "swap") return `swap [${step.i}] and [${step.j}]`;
+  return `write ${step.value} to [${step.i}]`;
+}
+
+const sortPlayer = new Player(renderSort, $("sort-speed"), $("sort-play"));
+
+// ========== Graph Traversal ==========
+
+let graphData = null;
+let layout = new Map();
+
+function loadGraph() {
+  try {
+    graphData = JSON.parse(graphTrace($("graph-traversal").value, $("graph-edges").value, $("graph-start").value.trim()));
+    showError(null);
+  } catch (error) {
+    showError(error);
+    return;
+  }
+  // Vertices on a circle, in sorted order.
+  layout = new Map();
+  const n = graphData.vertices.length;
+  graphData.vertices.forEach((vertex, i) => {
+    const angle = (2 * Math.PI * i) / n - Math.PI / 2;
+    layout.set(vertex, { x: 450 + 180 * Math.cos(angle), y: 210 + 180 * Math.sin(angle) });
+  });
+  graphPlayer.load(graphData.frames.length);
+}
+
+function renderGraph(index) {
+  const frame = index >= 0 ? graphData.frames[index] : { vertex: null, frontier: [], visited: [] };
+  const svg = $("graph-svg");
+  const parts = [];
+
+  for (const [a, b] of graphData.edges) {
+    const p = layout.get(a);
+    const q = layout.get(b);
+    parts.push(`<line x1="${p.x}" y1="${p.y}" x2="${q.x}" y2="${q.y}" stroke="#999" stroke-width="2"/>`);
+  }
+  for (const vertex of graphData.vertices) {
+    const { x, y } = layout.get(vertex);
+    let fill = "#e0e0e0";
+    if (frame.visited.includes(vertex)) fill = "#2a9d8f";
+    if (frame.frontier.includes(vertex)) fill = "#f2a900";
+    if (frame.vertex === vertex) fill = "#d62828";
+    parts.push(`<circle cx="${x}" cy="${y}" r="22" fill="${fill}" stroke="#333"/>`);
+    parts.push(`<text x="${x}" y="${y + 5}" text-anchor="middle" font-size="16">${escapeXml(vertex)}</text>`);
+  }
+  svg.innerHTML = parts.join("");
+
+  const frontierName = graphData.traversal === "bfs" ? "queue" : graphData.traversal === "dfs-iterative" ? "stack" : "path";
+  $("graph-status").textContent =
+    `${graphData.traversal} from ${graphData.start}: step ${index + 1}/${graphData.frames.length}\n` +
+    (frame.vertex
+      ? `visiting ${frame.vertex}  ${frontierName}=[${frame.frontier.join(", ")}]  visited=[${frame.visited.join(", ")}]`
+      : "not started");
+}
+
+function escapeXml(text) {
+  return text.replace(/[<>&"]/g, (c) => ({ "<": "&lt;", ">": "&gt;", "&": "&amp;", '"': "&quot;" })[c]);
+}
+
+const graphPlayer = new Player(renderGraph, $("graph-speed"), $("graph-play"));
+
+// ========== Wiring ==========
+
+function fillSelect(select, names) {
+  select.innerHTML = names.map((name) => `<option value="${name}">${name}</option>`).join("");
+}
+
+await init();
+
+fillSelect($("sort-algorithm"), sortAlgorithms());
+fillSelect($("graph-traversal"), traversals());
+$("graph-edges").value = sampleGraphEdges();
+
+$("sort-algorithm").addEventListener("change", loadSort);
+$("sort-input").addEventListener("change", loadSort);
+$("sort-random").addEventListener("click", () => {
+  $("sort-input").value = Array.from({ length: 24 }, () => Math.floor(Math.random() * 100)).join(" ");
+  loadSort();
+});
+$("sort-play").addEventListener("click", () => sortPlayer.toggle());
+$("sort-step").addEventListener("click", () => { sortPlayer.pause(); sortPlayer.step(); });
+$("sort-reset").addEventListener("click", () => sortPlayer.load(sortData.steps.length));
+
+for (const id of ["graph-traversal", "graph-edges", "graph-start"]) $(id).addEventListener("change", loadGraph);
+$("graph-play").addEventListener("click", () => graphPlayer.toggle());
+$("graph-step").addEventListener("click", () => { graphPlayer.pause(); graphPlayer.step(); });
+$("graph-reset").addEventListener("click", () => graphPlayer.load(graphData.frames.length));
+
+loadSort();
+loadGraph();
@@ -0,0 +1,78 @@
+//! Python bindings for the algorithm and data structure snippets.
+//!
+//! The pyo3 module lives in `python.rs` behind the `python` feature, and maturin builds it
+//! as the `tech_notes` extension module (see `README.md`). This file holds the plain-Rust
+//! glue the module calls: name lookups and the tuples Python gets back. It is kept apart
+//! so it builds and tests without a Python toolchain.
+//!
+//! ```text
+//! >>> import tech_notes
+//! >>> tech_notes.sort([3, 1, 2], "heap")
+//! [1, 2, 3]
+//! >>> tech_notes.Graph([("A", "B"), ("A", "C")]).traverse("A")
+//! ['A', 'B', 'C']
+//! ```
+
+#[cfg(feature = "python")]
+mod python;
+
+use algorithms::graph_traversal::{Traversal, TraversalFrame};
+use algorithms::sorting_algorithms::{SortAlgorithm, SortStep};
+
+/// A sort step as `(op, index, operand)`: the operand is the second index for `"compare"`
+/// and `"swap"`, and the written value for `"set"`.
+pub type StepTuple = (&'static str, usize, i64);
+
+/// A traversal frame as `(vertex, frontier, visited)`.
+pub type FrameTuple = (String, Vec<String>, Vec<String>);
+
+/// Looks up a sort by the name Python passes in.
+pub fn parse_sort_algorithm(name: &str) -> Result<SortAlgorithm, String> {
+    SortAlgorithm::from_name(name).ok_or_else(|| {
+        let known: Vec<&str> = SortAlgorithm::ALL.iter().map(|a| a.name()).collect();
+        format!("unknown sort algorithm '{}' (expected one of {})", name, known.join(", "))
+    })
+}
+
+/// Looks up a traversal by the name Python passes in.
+pub fn parse_traversal(name: &str) -> Result<Traversal, String> {
+    Traversal::from_name(name).ok_or_else(|| {
+        let known: Vec<&str> = Traversal::ALL.iter().map(|t| t.name()).collect();
+        format!("unknown traversal '{}' (expected one of {})", name, known.join(", "))
+    })
+}
+
+pub fn step_tuple(step: SortStep) -> StepTuple {
+    match step {
+        SortStep::Compare(i, j) => ("compare", i, j as i64),
+        SortStep::Swap(i, j) => ("swap", i, j as i64),
+        SortStep::Set(i, value) => ("set", i, value.into()),
+    }
+}
+
+pub fn frame_tuple(frame: TraversalFrame) -> FrameTuple {
+    (frame.vertex, frame.frontier, frame.visited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algorithms::sorting_algorithms::sort_steps;
+
+    #[test]
+    fn names_resolve_or_list_the_alternatives() {
+        assert_eq!(parse_sort_algorithm("heap"), Ok(SortAlgorithm::Heap));
+        let error = parse_sort_algorithm("bogo").unwrap_err();
+        assert!(error.starts_with("unknown sort algorithm 'bogo'"), "{}", error);
+        assert!(error.contains("bubble, selection"), "{}", error);
+
+        assert_eq!(parse_traversal("dfs-iterative"), Ok(Traversal::DfsIterative));
+        assert!(parse_traversal("walk").unwrap_err().contains("bfs, dfs-recursive, dfs-iterative"));
+    }
+
+    #[test]
+    fn steps_become_flat_tuples() {
+        let steps: Vec<StepTuple> = sort_steps(SortAlgorithm::Insertion, &[2, -1]).into_iter().map(step_tuple).collect();
+        assert_eq!(steps, [("compare", 0, 1), ("set", 1, 2), ("set", 0, -1)]);
+    }
+}
@@ -0,0 +1,277 @@
+//! The `tech_notes` extension module.
+//!
+//! Errors the snippets report as `Err(String)` (and the panics they use for invalid
+//! arguments) become `ValueError`; lookups of a missing vertex or key raise `KeyError`.
+
+use crate::{frame_tuple, parse_sort_algorithm, parse_traversal, step_tuple, FrameTuple, StepTuple};
+use algorithms::graph_traversal::{parse_edges, Graph};
+use algorithms::sorting_algorithms::{sort_steps as steps_of, sort_traced, SortAlgorithm};
+use data_structures::cuckoo_hash::CuckooHashMap;
+use data_structures::sketches::{CountMinSketch, HyperLogLog};
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+
+fn value_error(message: String) -> PyErr {
+    PyValueError::new_err(message)
+}
+
+// ========== Sorting ==========
+
+/// Names accepted by `sort` and `sort_steps`.
+#[pyfunction]
+fn sort_algorithms() -> Vec<&'static str> {
+    SortAlgorithm::ALL.iter().map(|a| a.name()).collect()
+}
+
+/// Returns a sorted copy of `values`.
+#[pyfunction]
+#[pyo3(signature = (values, algorithm = "merge"))]
+fn sort(values: Vec<i32>, algorithm: &str) -> PyResult<Vec<i32>> {
+    let algorithm = parse_sort_algorithm(algorithm).map_err(value_error)?;
+    Ok(sort_traced(algorithm, &values, |_| {}))
+}
+
+/// Every compare, swap and write the sort makes, as `(op, index, operand)` tuples.
+#[pyfunction]
+#[pyo3(signature = (values, algorithm = "merge"))]
+fn sort_steps(values: Vec<i32>, algorithm: &str) -> PyResult<Vec<StepTuple>> {
+    let algorithm = parse_sort_algorithm(algorithm).map_err(value_error)?;
+    Ok(steps_of(algorithm, &values).into_iter().map(step_tuple).collect())
+}
+
+// ========== Graph ==========
+
+/// An undirected graph with string vertices.
+#[pyclass(name = "Graph")]
+struct PyGraph(Graph);
+
+impl PyGraph {
+    fn require(&self, vertex: &str) -> PyResult<()> {
+        if self.0.contains(vertex) {
+            Ok(())
+        } else {
+            Err(PyKeyError::new_err(vertex.to_string()))
+        }
+    }
+}
+
+#[pymethods]
+impl PyGraph {
+    #[new]
+    #[pyo3(signature = (edges = Vec::new()))]
+    fn new(edges: Vec<(String, String)>) -> Self {
+        PyGraph(Graph::from_edges(edges.iter().map(|(a, b)| (a.as_str(), b.as_str()))))
+    }
+
+    /// Builds a graph from an edge list such as `"A-B A-C, B-D"`.
+    #[staticmethod]
+    fn parse(spec: &str) -> PyResult<Self> {
+        let edges = parse_edges(spec).map_err(value_error)?;
+        Ok(PyGraph(Graph::from_edges(edges)))
+    }
+
+    fn add_vertex(&mut self, vertex: &str) {
+        self.0.add_vertex(vertex);
+    }
+
+    fn add_edge(&mut self, a: &str, b: &str) {
+        self.0.add_edge(a, b);
+    }
+
+    /// All vertices in sorted order.
+    fn vertices(&self) -> Vec<String> {
+        self.0.vertices()
+    }
+
+    fn neighbors(&self, vertex: &str) -> PyResult<Vec<String>> {
+        self.require(vertex)?;
+        Ok(self.0.get_sorted_neighbors(vertex))
+    }
+
+    /// Visit order of `traversal` ("bfs", "dfs-recursive" or "dfs-iterative") from `start`.
+    #[pyo3(signature = (start, traversal = "bfs"))]
+    fn traverse(&self, start: &str, traversal: &str) -> PyResult<Vec<String>> {
+        let traversal = parse_traversal(traversal).map_err(value_error)?;
+        self.require(start)?;
+        Ok(self.0.traverse(traversal, start, |_| {}))
+    }
+
+    /// One `(vertex, frontier, visited)` tuple per visit.
+    #[pyo3(signature = (start, traversal = "bfs"))]
+    fn trace(&self, start: &str, traversal: &str) -> PyResult<Vec<FrameTuple>> {
+        let traversal = parse_traversal(traversal).map_err(value_error)?;
+        self.require(start)?;
+        Ok(self.0.trace(traversal, start).into_iter().map(frame_tuple).collect())
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.vertices().len()
+    }
+
+    fn __contains__(&self, vertex: &str) -> bool {
+        self.0.contains(vertex)
+    }
+}
+
+// ========== Cuckoo Hash Map ==========
+
+/// A cuckoo hash map from strings to any Python object.
+#[pyclass(name = "CuckooHashMap")]
+struct PyCuckooHashMap(CuckooHashMap<String, Py<PyAny>>);
+
+#[pymethods]
+impl PyCuckooHashMap {
+    #[new]
+    fn new() -> Self {
+        PyCuckooHashMap(CuckooHashMap::new())
+    }
+
+    #[pyo3(signature = (key, default = None))]
+    fn get(&self, py: Python<'_>, key: String, default: Option<Py<PyAny>>) -> Option<Py<PyAny>> {
+        self.0.get(&key).map(|value| value.clone_ref(py)).or(default)
+    }
+
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    fn load_factor(&self) -> f64 {
+        self.0.load_factor()
+    }
+
+    /// Entries that didn't fit in either table.
+    fn stash_len(&self) -> usize {
+        self.0.stash_len()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __contains__(&self, key: String) -> bool {
+        self.0.contains_key(&key)
+    }
+
+    fn __getitem__(&self, py: Python<'_>, key: String) -> PyResult<Py<PyAny>> {
+        match self.0.get(&key) {
+            Some(value) => Ok(value.clone_ref(py)),
+            None => Err(PyKeyError::new_err(key)),
+        }
+    }
+
+    fn __setitem__(&mut self, key: String, value: Py<PyAny>) {
+        self.0.insert(key, value);
+    }
+
+    fn __delitem__(&mut self, key: String) -> PyResult<()> {
+        match self.0.remove(&key) {
+            Some(_) => Ok(()),
+            None => Err(PyKeyError::new_err(key)),
+        }
+    }
+}
+
+// ========== Sketches ==========
+
+/// Count-min frequency sketch over strings.
+#[pyclass(name = "CountMinSketch")]
+struct PyCountMinSketch(CountMinSketch);
+
+#[pymethods]
+impl PyCountMinSketch {
+    #[new]
+    fn new(width: usize, depth: usize) -> PyResult<Self> {
+        if width == 0 || depth == 0 {
+            return Err(value_error("sketch dimensions must be positive".to_string()));
+        }
+        Ok(PyCountMinSketch(CountMinSketch::new(width, depth)))
+    }
+
+    /// Sized so estimates exceed the true count by at most `epsilon * total` with
+    /// probability at least `1 - delta`.
+    #[staticmethod]
+    fn with_error_bounds(epsilon: f64, delta: f64) -> PyResult<Self> {
+        if !(epsilon > 0.0 && delta > 0.0 && delta < 1.0) {
+            return Err(value_error("invalid error bounds".to_string()));
+        }
+        Ok(PyCountMinSketch(CountMinSketch::with_error_bounds(epsilon, delta)))
+    }
+
+    #[pyo3(signature = (item, count = 1))]
+    fn add(&mut self, item: &str, count: u64) {
+        self.0.add(item, count);
+    }
+
+    /// Never less than the true count.
+    fn estimate(&self, item: &str) -> u64 {
+        self.0.estimate(item)
+    }
+
+    fn merge(&mut self, other: PyRef<'_, Self>) -> PyResult<()> {
+        self.0.merge(&other.0).map_err(value_error)
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[getter]
+    fn depth(&self) -> usize {
+        self.0.depth()
+    }
+
+    #[getter]
+    fn total(&self) -> u64 {
+        self.0.total()
+    }
+}
+
+/// HyperLogLog distinct-count estimator over strings.
+#[pyclass(name = "HyperLogLog")]
+struct PyHyperLogLog(HyperLogLog);
+
+#[pymethods]
+impl PyHyperLogLog {
+    #[new]
+    #[pyo3(signature = (precision = 14))]
+    fn new(precision: u8) -> PyResult<Self> {
+        HyperLogLog::new(precision).map(PyHyperLogLog).map_err(value_error)
+    }
+
+    fn add(&mut self, item: &str) {
+        self.0.add(item);
+    }
+
+    fn estimate(&self) -> f64 {
+        self.0.estimate()
+    }
+
+    fn merge(&mut self, other: PyRef<'_, Self>) -> PyResult<()> {
+        self.0.merge(&other.0).map_err(value_error)
+    }
+
+    #[getter]
+    fn precision(&self) -> u8 {
+        self.0.precision()
+    }
+
+    #[getter]
+    fn standard_error(&self) -> f64 {
+        self.0.standard_error()
+    }
+}
+
+// ========== Module ==========
+
+#[pymodule]
+fn tech_notes(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(sort_algorithms, m)?)?;
+    m.add_function(wrap_pyfunction!(sort, m)?)?;
+    m.add_function(wrap_pyfunction!(sort_steps, m)?)?;
+    m.add_class::<PyGraph>()?;
+    m.add_class::<PyCuckooHashMap>()?;
+    m.add_class::<PyCountMinSketch>()?;
+    m.add_class::<PyHyperLogLog>()?;
+    Ok(())
+}
@@ -0,0 +1,172 @@
+"""Tests for the `tech_notes` extension module.
+
+Build it into the active virtualenv first, then run pytest from `python/`:
+
+    maturin develop
+    pytest
+"""
+
+import pytest
+
+import tech_notes
+
+# ========== Sorting ==========
+
+
+def test_every_algorithm_sorts():
+    values = [5, -2, 9, 5, 0, 13, -7, 2]
+    for algorithm in tech_notes.sort_algorithms():
+        assert tech_notes.sort(values, algorithm) == sorted(values), algorithm
+
+
+def test_sort_leaves_the_input_alone_and_defaults_to_merge():
+    values = [3, 1, 2]
+    assert tech_notes.sort(values) == [1, 2, 3]
+    assert values == [3, 1, 2]
+
+
+def test_sort_steps_replay_to_the_sorted_list():
+    values = [4, 2, 3, 1]
+    replay = list(values)
+    for op, i, operand in tech_notes.sort_steps(values, "heap"):
+        if op == "swap":
+            replay[i], replay[operand] = replay[operand], replay[i]
+        elif op == "set":
+            replay[i] = operand
+    assert replay == [1, 2, 3, 4]
+
+
+def test_sort_steps_of_bubble_sort():
+    assert tech_notes.sort_steps([2, 1], "bubble") == [("compare", 0, 1), ("swap", 0, 1)]
+
+
+def test_unknown_algorithm_is_a_value_error():
+    with pytest.raises(ValueError, match="unknown sort algorithm 'bogo'"):
+        tech_notes.sort([1], "bogo")
+
+
+def test_values_outside_i32_overflow():
+    with pytest.raises(OverflowError):
+        tech_notes.sort([2**40])
+
+
+# ========== Graph ==========
+
+
+def sample_graph():
+    return tech_notes.Graph.parse("A-B A-C B-D B-E C-F E-F")
+
+
+def test_traversal_orders():
+    graph = sample_graph()
+    assert graph.traverse("A") == ["A", "B", "C", "D", "E", "F"]
+    assert graph.traverse("A", "dfs-recursive") == ["A", "B", "D", "E", "F", "C"]
+    assert graph.traverse("A", traversal="dfs-iterative") == graph.traverse("A", "dfs-recursive")
+
+
+def test_trace_frames_show_the_queue():
+    vertex, frontier, visited = sample_graph().trace("A")[2]
+    assert vertex == "C"
+    assert frontier == ["D", "E"]
+    assert visited == ["A", "B", "C"]
+
+
+def test_graph_building_and_lookups():
+    graph = tech_notes.Graph([("b", "a")])
+    graph.add_edge("a", "c")
+    graph.add_vertex("z")
+    assert graph.vertices() == ["a", "b", "c", "z"]
+    assert graph.neighbors("a") == ["b", "c"]
+    assert len(graph) == 4
+    assert "z" in graph and "y" not in graph
+    assert graph.traverse("z") == ["z"]
+
+
+def test_graph_errors():
+    graph = sample_graph()
+    with pytest.raises(KeyError):
+        graph.traverse("Z")
+    with pytest.raises(KeyError):
+        graph.neighbors("Z")
+    with pytest.raises(ValueError, match="unknown traversal 'walk'"):
+        graph.traverse("A", "walk")
+    with pytest.raises(ValueError, match="should look like A-B"):
+        tech_notes.Graph.parse("A-B AC")
+
+
+# ========== Cuckoo Hash Map ==========
+
+
+def test_cuckoo_map_behaves_like_a_dict():
+    cuckoo, oracle = tech_notes.CuckooHashMap(), {}
+    for i in range(500):
+        key = f"key-{i % 300}"
+        cuckoo[key] = [i]
+        oracle[key] = [i]
+    for i in range(0, 300, 3):
+        del cuckoo[f"key-{i}"]
+        del oracle[f"key-{i}"]
+
+    assert len(cuckoo) == len(oracle)
+    for i in range(300):
+        key = f"key-{i}"
+        assert (key in cuckoo) == (key in oracle)
+        assert cuckoo.get(key) == oracle.get(key)
+    assert 0 < cuckoo.load_factor() <= 1
+
+
+def test_cuckoo_map_missing_keys():
+    cuckoo = tech_notes.CuckooHashMap()
+    assert cuckoo.get("missing", "fallback") == "fallback"
+    with pytest.raises(KeyError):
+        cuckoo["missing"]
+    with pytest.raises(KeyError):
+        del cuckoo["missing"]
+
+
+def test_cuckoo_map_returns_the_same_object():
+    cuckoo = tech_notes.CuckooHashMap()
+    value = {"nested": True}
+    cuckoo["k"] = value
+    assert cuckoo["k"] is value
+
+
+# ========== Sketches ==========
+
+
+def test_count_min_never_undercounts():
+    sketch = tech_notes.CountMinSketch.with_error_bounds(0.01, 0.01)
+    counts = {f"item-{i}": i % 7 + 1 for i in range(200)}
+    for item, count in counts.items():
+        sketch.add(item, count)
+    sketch.add("item-0")
+    counts["item-0"] += 1
+
+    assert sketch.total == sum(counts.values())
+    for item, count in counts.items():
+        assert sketch.estimate(item) >= count
+
+
+def test_count_min_merge_needs_matching_dimensions():
+    left, right = tech_notes.CountMinSketch(64, 4), tech_notes.CountMinSketch(64, 4)
+    left.add("a", 2)
+    right.add("a", 3)
+    left.merge(right)
+    assert left.estimate("a") >= 5
+    with pytest.raises(ValueError):
+        left.merge(tech_notes.CountMinSketch(32, 4))
+    with pytest.raises(ValueError):
+        tech_notes.CountMinSketch(0, 4)
+
+
+def test_hyperloglog_estimates_distinct_items():
+    hll = tech_notes.HyperLogLog()
+    for i in range(20_000):
+        hll.add(f"user-{i % 10_000}")
+    assert hll.precision == 14
+    assert abs(hll.estimate() - 10_000) < 10_000 * 4 * hll.standard_error
+
+
+def test_hyperloglog_rejects_bad_precision():
+    with pytest.raises(ValueError, match="precision must be in 4..=16"):
+        tech_notes.HyperLogLog(3)
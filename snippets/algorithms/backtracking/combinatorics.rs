@@ -0,0 +1,395 @@
+//! Permutations, Combinations and Products in Rust
+//!
+//! Each generator here is a lazy iterator that keeps only a few indices into the input
+//! and works out the next arrangement from the current one, so taking the first few
+//! permutations of 20 items costs nothing like the 2.4 × 10^18 there are:
+//! - `permutations(k)` and `combinations(k)` come out in lexicographic order of item
+//!   positions, like Python's `itertools`. The next combination bumps the rightmost
+//!   index that still has room and resets everything after it.
+//! - `combinations_with_replacement(k)` lets an item repeat: indices only need to be
+//!   non-decreasing instead of increasing.
+//! - Heap's algorithm reaches every permutation of all items with a single swap per step,
+//!   at the cost of a less obvious order.
+//! - `product` is an odometer over several pools: the last digit turns fastest.
+//!
+//! The methods come from the `Combinatorics` trait, implemented for slices.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Counting ==========
+
+/// n! / (n - k)!, or `None` on overflow
+pub fn count_permutations(n: u64, k: u64) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+    (n - k + 1..=n).try_fold(1u64, |acc, x| acc.checked_mul(x))
+}
+
+/// n choose k, or `None` on overflow
+pub fn count_combinations(n: u64, k: u64) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    // Each prefix product is itself a binomial coefficient, so every division is exact
+    (0..k).try_fold(1u64, |acc, i| Some(acc.checked_mul(n - i)? / (i + 1)))
+}
+
+/// (n + k - 1) choose k: multisets of size k from n kinds
+pub fn count_combinations_with_replacement(n: u64, k: u64) -> Option<u64> {
+    match (n, k) {
+        (_, 0) => Some(1),
+        (0, _) => Some(0),
+        _ => count_combinations(n + k - 1, k),
+    }
+}
+
+// ========== Iterators ==========
+
+fn pick<T: Clone>(items: &[T], indices: &[usize]) -> Vec<T> {
+    indices.iter().map(|&i| items[i].clone()).collect()
+}
+
+/// k-permutations in lexicographic order of positions
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    /// `None` once exhausted
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Permutations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let current = pick(self.items, indices);
+        let n = self.items.len();
+
+        // The rightmost position that can take a larger index not used to its left
+        let mut advanced = false;
+        for i in (0..indices.len()).rev() {
+            let used_left = &indices[..i];
+            let larger = (indices[i] + 1..n).find(|x| !used_left.contains(x));
+            if let Some(next) = larger {
+                indices[i] = next;
+                // Refill the rest with the smallest indices still free, in order
+                let refill: Vec<usize> = (0..n).filter(|x| !indices[..=i].contains(x)).take(indices.len() - i - 1).collect();
+                indices[i + 1..].copy_from_slice(&refill);
+                advanced = true;
+                break;
+            }
+        }
+        if !advanced {
+            self.indices = None;
+        }
+        Some(current)
+    }
+}
+
+/// k-combinations in lexicographic order of positions
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let current = pick(self.items, indices);
+        let (n, k) = (self.items.len(), indices.len());
+
+        // Position i can go no higher than n - k + i, leaving room for the ones after it
+        match (0..k).rev().find(|&i| indices[i] < n - k + i) {
+            Some(i) => {
+                indices[i] += 1;
+                for j in i + 1..k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            }
+            None => self.indices = None,
+        }
+        Some(current)
+    }
+}
+
+/// k-multisets in lexicographic order of positions
+pub struct CombinationsWithReplacement<'a, T> {
+    items: &'a [T],
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for CombinationsWithReplacement<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let current = pick(self.items, indices);
+        let n = self.items.len();
+
+        match indices.iter().rposition(|&x| x + 1 < n) {
+            Some(i) => {
+                let bumped = indices[i] + 1;
+                indices[i..].fill(bumped);
+            }
+            None => self.indices = None,
+        }
+        Some(current)
+    }
+}
+
+/// All permutations by Heap's algorithm, one swap apart
+pub struct HeapPermutations<T> {
+    current: Vec<T>,
+    /// Loop counters of the recursive version, one per level
+    counters: Vec<usize>,
+    /// Level of the next swap; `None` before the first item, and past the end when done
+    level: Option<usize>,
+}
+
+impl<T: Clone> Iterator for HeapPermutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let Some(mut level) = self.level else {
+            self.level = Some(1);
+            return Some(self.current.clone());
+        };
+        while level < self.current.len() {
+            if self.counters[level] < level {
+                let other = if level % 2 == 0 { 0 } else { self.counters[level] };
+                self.current.swap(other, level);
+                self.counters[level] += 1;
+                self.level = Some(1);
+                return Some(self.current.clone());
+            }
+            self.counters[level] = 0;
+            level += 1;
+        }
+        self.level = Some(level);
+        None
+    }
+}
+
+/// Cartesian product of several pools, last pool fastest
+pub struct Product<'a, T> {
+    pools: &'a [&'a [T]],
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Product<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let current: Vec<T> = self.pools.iter().zip(indices.iter()).map(|(pool, &i)| pool[i].clone()).collect();
+
+        match (0..indices.len()).rev().find(|&i| indices[i] + 1 < self.pools[i].len()) {
+            Some(i) => {
+                indices[i] += 1;
+                indices[i + 1..].fill(0);
+            }
+            None => self.indices = None,
+        }
+        Some(current)
+    }
+}
+
+pub fn product<'a, T: Clone>(pools: &'a [&'a [T]]) -> Product<'a, T> {
+    let indices = pools.iter().all(|pool| !pool.is_empty()).then(|| vec![0; pools.len()]);
+    Product { pools, indices }
+}
+
+// ========== Slice Extension ==========
+
+pub trait Combinatorics<T> {
+    fn permutations(&self, k: usize) -> Permutations<'_, T>;
+    fn combinations(&self, k: usize) -> Combinations<'_, T>;
+    fn combinations_with_replacement(&self, k: usize) -> CombinationsWithReplacement<'_, T>;
+    fn heap_permutations(&self) -> HeapPermutations<T>;
+}
+
+impl<T: Clone> Combinatorics<T> for [T] {
+    fn permutations(&self, k: usize) -> Permutations<'_, T> {
+        Permutations { items: self, indices: (k <= self.len()).then(|| (0..k).collect()) }
+    }
+
+    fn combinations(&self, k: usize) -> Combinations<'_, T> {
+        Combinations { items: self, indices: (k <= self.len()).then(|| (0..k).collect()) }
+    }
+
+    fn combinations_with_replacement(&self, k: usize) -> CombinationsWithReplacement<'_, T> {
+        let indices = (k == 0 || !self.is_empty()).then(|| vec![0; k]);
+        CombinationsWithReplacement { items: self, indices }
+    }
+
+    fn heap_permutations(&self) -> HeapPermutations<T> {
+        HeapPermutations { current: self.to_vec(), counters: vec![0; self.len()], level: None }
+    }
+}
+
+// ========== Demo Code ==========
+
+fn join(items: &[char]) -> String {
+    items.iter().collect()
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct CombinatoricsDemo;
+
+impl Demo for CombinatoricsDemo {
+    fn name(&self) -> &'static str {
+        "combinatorics"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lazy permutation, combination, multiset and Cartesian product iterators"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let abcd = ['a', 'b', 'c', 'd'];
+
+        writeln!(out, "===== Permutations =====")?;
+        let twos: Vec<String> = abcd.permutations(2).map(|p| join(&p)).collect();
+        writeln!(out, "abcd, k=2: {} ({})", twos.join(" "), twos.len())?;
+        let lexicographic: Vec<String> = abcd[..3].permutations(3).map(|p| join(&p)).collect();
+        let heap: Vec<String> = abcd[..3].heap_permutations().map(|p| join(&p)).collect();
+        writeln!(out, "abc, lexicographic: {}", lexicographic.join(" "))?;
+        writeln!(out, "abc, Heap's:        {}", heap.join(" "))?;
+
+        writeln!(out, "\n===== Combinations =====")?;
+        let twos: Vec<String> = abcd.combinations(2).map(|c| join(&c)).collect();
+        writeln!(out, "abcd, k=2: {} ({})", twos.join(" "), twos.len())?;
+        let multisets: Vec<String> = abcd[..3].combinations_with_replacement(2).map(|c| join(&c)).collect();
+        writeln!(out, "abc with replacement, k=2: {} ({})", multisets.join(" "), multisets.len())?;
+
+        writeln!(out, "\n===== Cartesian Product =====")?;
+        let (sizes, colors) = (['S', 'M', 'L'], ['r', 'g']);
+        let pools: [&[char]; 2] = [&sizes, &colors];
+        let variants: Vec<String> = product(&pools).map(|v| join(&v)).collect();
+        writeln!(out, "sizes x colors: {}", variants.join(" "))?;
+
+        writeln!(out, "\n===== Laziness =====")?;
+        let twenty: Vec<u32> = (1..=20).collect();
+        writeln!(out, "20 items have {} permutations; the first three:", count_permutations(20, 20).unwrap_or(0))?;
+        for p in twenty.permutations(20).take(3) {
+            writeln!(out, "  {:?}", p)?;
+        }
+        let deep = twenty.combinations(13).nth(50_000);
+        writeln!(out, "combination #50000 of 13 from 20 (of {}): {:?}", count_combinations(20, 13).unwrap_or(0), deep)?;
+
+        writeln!(out, "\n===== Counts =====")?;
+        writeln!(out, "{:>3} {:>3} {:>8} {:>8} {:>8}", "n", "k", "P(n,k)", "C(n,k)", "multiset")?;
+        for (n, k) in [(5, 2), (6, 3), (10, 4), (52, 5)] {
+            writeln!(out, "{:>3} {:>3} {:>8} {:>8} {:>8}", n, k,
+                          count_permutations(n, k).unwrap_or(0), count_combinations(n, k).unwrap_or(0),
+                          count_combinations_with_replacement(n, k).unwrap_or(0))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&CombinatoricsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_sorted<T: Ord>(items: &[T]) -> bool {
+        items.windows(2).all(|w| w[0] < w[1])
+    }
+
+    #[test]
+    fn counts_match_formulas() {
+        for n in 0..7usize {
+            let items: Vec<usize> = (0..n).collect();
+            for k in 0..=n + 1 {
+                let (n64, k64) = (n as u64, k as u64);
+                assert_eq!(items.permutations(k).count() as u64, count_permutations(n64, k64).unwrap(), "P({}, {})", n, k);
+                assert_eq!(items.combinations(k).count() as u64, count_combinations(n64, k64).unwrap(), "C({}, {})", n, k);
+                assert_eq!(items.combinations_with_replacement(k).count() as u64,
+                           count_combinations_with_replacement(n64, k64).unwrap(), "multiset({}, {})", n, k);
+            }
+            assert_eq!(items.heap_permutations().count() as u64, count_permutations(n as u64, n as u64).unwrap());
+        }
+        assert_eq!(count_combinations(60, 30), Some(118_264_581_564_861_424));
+        assert_eq!(count_permutations(21, 21), None);
+    }
+
+    #[test]
+    fn lexicographic_order_without_duplicates() {
+        let items: Vec<usize> = (0..6).collect();
+        for k in 0..=6 {
+            assert!(is_sorted(&items.permutations(k).collect::<Vec<_>>()), "k = {}", k);
+            assert!(is_sorted(&items.combinations(k).collect::<Vec<_>>()), "k = {}", k);
+            assert!(is_sorted(&items.combinations_with_replacement(k).collect::<Vec<_>>()), "k = {}", k);
+        }
+        assert!(items.combinations(3).all(|c| is_sorted(&c)));
+        assert!(items.combinations_with_replacement(3).all(|c| c.windows(2).all(|w| w[0] <= w[1])));
+    }
+
+    #[test]
+    fn small_cases() {
+        let abc = ['a', 'b', 'c'];
+        let p: Vec<String> = abc.permutations(2).map(|p| join(&p)).collect();
+        assert_eq!(p, ["ab", "ac", "ba", "bc", "ca", "cb"]);
+        let c: Vec<String> = abc.combinations(2).map(|c| join(&c)).collect();
+        assert_eq!(c, ["ab", "ac", "bc"]);
+        let m: Vec<String> = abc.combinations_with_replacement(2).map(|c| join(&c)).collect();
+        assert_eq!(m, ["aa", "ab", "ac", "bb", "bc", "cc"]);
+        assert_eq!(abc.permutations(0).collect::<Vec<_>>(), [Vec::<char>::new()]);
+        assert_eq!(abc.permutations(4).count(), 0);
+        assert_eq!(<[char]>::combinations_with_replacement(&[], 0).count(), 1);
+        assert_eq!(<[char]>::combinations_with_replacement(&[], 2).count(), 0);
+    }
+
+    #[test]
+    fn heap_steps_differ_by_one_swap() {
+        let items: Vec<u8> = (0..5).collect();
+        let all: Vec<Vec<u8>> = items.heap_permutations().collect();
+        for pair in all.windows(2) {
+            let differing = pair[0].iter().zip(&pair[1]).filter(|(a, b)| a != b).count();
+            assert_eq!(differing, 2, "{:?} -> {:?}", pair[0], pair[1]);
+        }
+        let mut sorted = all.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, items.permutations(5).collect::<Vec<_>>());
+        // Exhausted iterators stay exhausted
+        let mut heap = [1].heap_permutations();
+        assert_eq!((heap.next(), heap.next(), heap.next()), (Some(vec![1]), None, None));
+    }
+
+    #[test]
+    fn cartesian_product() {
+        let (a, b) = ([1, 2], [10, 20, 30]);
+        let pools: [&[i32]; 2] = [&a, &b];
+        let all: Vec<Vec<i32>> = product(&pools).collect();
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[0], [1, 10]);
+        assert_eq!(all[1], [1, 20]);
+        assert_eq!(all[5], [2, 30]);
+        assert!(is_sorted(&all));
+        let with_empty: [&[i32]; 2] = [&a, &[]];
+        assert_eq!(product(&with_empty).count(), 0);
+        assert_eq!(product::<i32>(&[]).collect::<Vec<_>>(), [Vec::<i32>::new()]);
+    }
+
+    #[test]
+    fn lazy_on_huge_inputs() {
+        let items: Vec<u32> = (0..30).collect();
+        let first: Vec<Vec<u32>> = items.permutations(30).take(2).collect();
+        assert_eq!(first[0], items);
+        assert_eq!(&first[1][28..], [29, 28]);
+        assert_eq!(items.combinations(15).nth(1), Some((0..14).chain([15]).collect()));
+    }
+}
@@ -0,0 +1,506 @@
+//! Constraint Satisfaction in Rust
+//!
+//! A constraint satisfaction problem has variables, a finite domain of values for each,
+//! and constraints that say which combinations of values may appear together. Map
+//! colouring, cryptarithmetic, Sudoku and N-Queens all fit the shape, so one solver
+//! covers them:
+//! - Backtracking assigns one variable at a time and undoes the assignment when a
+//!   constraint whose variables are now all assigned is broken.
+//! - MRV (minimum remaining values) picks the unassigned variable with the fewest values
+//!   left, so dead ends show up near the top of the tree instead of deep inside it.
+//! - Forward checking looks at each constraint the new assignment touches; when that
+//!   leaves a single unassigned variable in it, values of that variable that would break
+//!   the constraint are removed from its domain. An emptied domain means backing up now.
+//!
+//! A constraint is a list of variables (its scope) and a predicate over their values, in
+//! scope order. The predicate only ever sees complete values, so it can be written as a
+//! plain check without caring about partial assignments.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Problem ==========
+
+/// Handle for a variable, returned by `Csp::add_variable`
+pub type Var = usize;
+
+type Predicate<V> = Box<dyn Fn(&[V]) -> bool>;
+
+struct Constraint<V> {
+    scope: Vec<Var>,
+    predicate: Predicate<V>,
+}
+
+/// Variables, their domains and the constraints between them
+pub struct Csp<V> {
+    names: Vec<String>,
+    domains: Vec<Vec<V>>,
+    constraints: Vec<Constraint<V>>,
+    /// For each variable, the constraints it appears in
+    by_var: Vec<Vec<usize>>,
+}
+
+impl<V> Default for Csp<V> {
+    fn default() -> Self {
+        Csp { names: Vec::new(), domains: Vec::new(), constraints: Vec::new(), by_var: Vec::new() }
+    }
+}
+
+impl<V: Clone> Csp<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_variable(&mut self, name: &str, domain: Vec<V>) -> Var {
+        self.names.push(name.to_string());
+        self.domains.push(domain);
+        self.by_var.push(Vec::new());
+        self.names.len() - 1
+    }
+
+    /// Adds a constraint that holds when `predicate` accepts the values of `scope`
+    pub fn add_constraint(&mut self, scope: &[Var], predicate: impl Fn(&[V]) -> bool + 'static) {
+        let index = self.constraints.len();
+        for &var in scope {
+            assert!(var < self.names.len(), "constraint refers to unknown variable {}", var);
+            if !self.by_var[var].contains(&index) {
+                self.by_var[var].push(index);
+            }
+        }
+        self.constraints.push(Constraint { scope: scope.to_vec(), predicate: Box::new(predicate) });
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, var: Var) -> &str {
+        &self.names[var]
+    }
+
+    pub fn constraint_count(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Whether a complete assignment, one value per variable, meets every constraint
+    pub fn is_satisfied(&self, assignment: &[V]) -> bool {
+        assignment.len() == self.len()
+            && self.constraints.iter().all(|c| {
+                let values: Vec<V> = c.scope.iter().map(|&var| assignment[var].clone()).collect();
+                (c.predicate)(&values)
+            })
+    }
+
+    /// First solution found with MRV and forward checking
+    pub fn solve(&self) -> Option<Vec<V>> {
+        self.solve_with(Strategy::FULL).0
+    }
+
+    /// First solution found with the given heuristics, and how much searching it took
+    pub fn solve_with(&self, strategy: Strategy) -> (Option<Vec<V>>, Stats) {
+        let mut found = None;
+        let stats = self.search(strategy, &mut |solution| {
+            found = Some(solution);
+            true
+        });
+        (found, stats)
+    }
+
+    /// Up to `limit` solutions
+    pub fn solutions(&self, limit: usize) -> Vec<Vec<V>> {
+        let mut found = Vec::new();
+        if limit > 0 {
+            self.search(Strategy::FULL, &mut |solution| {
+                found.push(solution);
+                found.len() == limit
+            });
+        }
+        found
+    }
+
+    fn search(&self, strategy: Strategy, on_solution: &mut dyn FnMut(Vec<V>) -> bool) -> Stats {
+        let mut search = Search::new(self, strategy);
+        if search.node_consistent() {
+            search.extend(on_solution);
+        }
+        search.stats
+    }
+}
+
+impl<V: Clone + PartialEq + 'static> Csp<V> {
+    /// Adds a not-equal constraint between every pair of `vars`
+    pub fn all_different(&mut self, vars: &[Var]) {
+        for (i, &a) in vars.iter().enumerate() {
+            for &b in &vars[i + 1..] {
+                self.add_constraint(&[a, b], |v| v[0] != v[1]);
+            }
+        }
+    }
+}
+
+/// Which heuristics the search uses on top of plain backtracking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strategy {
+    pub mrv: bool,
+    pub forward_checking: bool,
+}
+
+impl Strategy {
+    pub const PLAIN: Strategy = Strategy { mrv: false, forward_checking: false };
+    pub const FULL: Strategy = Strategy { mrv: true, forward_checking: true };
+}
+
+/// Counters from one search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Values tried, over all variables
+    pub assignments: u64,
+    /// Domain values removed by forward checking
+    pub pruned: u64,
+}
+
+// ========== Search ==========
+
+struct Search<'a, V> {
+    csp: &'a Csp<V>,
+    strategy: Strategy,
+    /// `alive[var][i]` is false once `domains[var][i]` has been ruled out
+    alive: Vec<Vec<bool>>,
+    remaining: Vec<usize>,
+    /// Index into the domain of each assigned variable
+    assigned: Vec<Option<usize>>,
+    /// Removed `(var, index)` pairs, so removals can be undone in order
+    trail: Vec<(Var, usize)>,
+    stats: Stats,
+}
+
+impl<'a, V: Clone> Search<'a, V> {
+    fn new(csp: &'a Csp<V>, strategy: Strategy) -> Self {
+        Search {
+            csp,
+            strategy,
+            alive: csp.domains.iter().map(|d| vec![true; d.len()]).collect(),
+            remaining: csp.domains.iter().map(Vec::len).collect(),
+            assigned: vec![None; csp.len()],
+            trail: Vec::new(),
+            stats: Stats::default(),
+        }
+    }
+
+    /// Drops values that break a single-variable constraint; false if a domain empties
+    fn node_consistent(&mut self) -> bool {
+        for c in self.csp.constraints.iter().filter(|c| c.scope.len() == 1) {
+            let var = c.scope[0];
+            for (i, value) in self.csp.domains[var].iter().enumerate() {
+                if self.alive[var][i] && !(c.predicate)(std::slice::from_ref(value)) {
+                    self.alive[var][i] = false;
+                    self.remaining[var] -= 1;
+                }
+            }
+        }
+        self.remaining.iter().all(|&r| r > 0)
+    }
+
+    fn select(&self) -> Option<Var> {
+        let unassigned = (0..self.csp.len()).filter(|&var| self.assigned[var].is_none());
+        if self.strategy.mrv {
+            unassigned.min_by_key(|&var| self.remaining[var])
+        } else {
+            unassigned.min()
+        }
+    }
+
+    fn value(&self, var: Var) -> Option<&V> {
+        self.assigned[var].map(|i| &self.csp.domains[var][i])
+    }
+
+    /// The constraint's values if all of its scope is assigned
+    fn values(&self, constraint: usize) -> Option<Vec<V>> {
+        self.csp.constraints[constraint].scope.iter().map(|&var| self.value(var).cloned()).collect()
+    }
+
+    fn holds(&self, constraint: usize) -> bool {
+        self.values(constraint).is_none_or(|values| (self.csp.constraints[constraint].predicate)(&values))
+    }
+
+    fn consistent(&self, var: Var) -> bool {
+        self.csp.by_var[var].iter().all(|&c| self.holds(c))
+    }
+
+    /// Prunes the last open variable of each constraint on `var`; false on a wipe-out
+    fn forward_check(&mut self, var: Var) -> bool {
+        for &c in &self.csp.by_var[var] {
+            let mut open = self.csp.constraints[c].scope.iter().filter(|&&v| self.assigned[v].is_none());
+            let (Some(&other), None) = (open.next(), open.next()) else { continue };
+            for i in 0..self.alive[other].len() {
+                if !self.alive[other][i] {
+                    continue;
+                }
+                self.assigned[other] = Some(i);
+                let ok = self.holds(c);
+                self.assigned[other] = None;
+                if !ok {
+                    self.alive[other][i] = false;
+                    self.remaining[other] -= 1;
+                    self.trail.push((other, i));
+                    self.stats.pruned += 1;
+                }
+            }
+            if self.remaining[other] == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn undo_to(&mut self, mark: usize) {
+        for (var, i) in self.trail.drain(mark..) {
+            self.alive[var][i] = true;
+            self.remaining[var] += 1;
+        }
+    }
+
+    /// Returns true once `on_solution` asks to stop
+    fn extend(&mut self, on_solution: &mut dyn FnMut(Vec<V>) -> bool) -> bool {
+        let Some(var) = self.select() else {
+            let solution = (0..self.csp.len()).filter_map(|v| self.value(v).cloned()).collect();
+            return on_solution(solution);
+        };
+        for i in 0..self.alive[var].len() {
+            if !self.alive[var][i] {
+                continue;
+            }
+            self.stats.assignments += 1;
+            self.assigned[var] = Some(i);
+            let mark = self.trail.len();
+            let ok = self.consistent(var) && (!self.strategy.forward_checking || self.forward_check(var));
+            if ok && self.extend(on_solution) {
+                return true;
+            }
+            self.undo_to(mark);
+            self.assigned[var] = None;
+        }
+        false
+    }
+}
+
+// ========== Example Problems ==========
+
+/// The seven regions of Australia and the pairs that share a border
+pub const AUSTRALIA: [&str; 7] = ["WA", "NT", "SA", "Q", "NSW", "V", "T"];
+pub const AUSTRALIA_BORDERS: [(&str, &str); 9] = [
+    ("WA", "NT"),
+    ("WA", "SA"),
+    ("NT", "SA"),
+    ("NT", "Q"),
+    ("SA", "Q"),
+    ("SA", "NSW"),
+    ("SA", "V"),
+    ("Q", "NSW"),
+    ("NSW", "V"),
+];
+
+/// Neighbouring regions get different colours
+pub fn map_coloring(regions: &[&str], borders: &[(&str, &str)], colors: &[&'static str]) -> Csp<&'static str> {
+    let mut csp = Csp::new();
+    for region in regions {
+        csp.add_variable(region, colors.to_vec());
+    }
+    let index = |name: &str| regions.iter().position(|r| *r == name).expect("border names a known region");
+    for &(a, b) in borders {
+        csp.add_constraint(&[index(a), index(b)], |v| v[0] != v[1]);
+    }
+    csp
+}
+
+/// SEND + MORE = MONEY as digits per letter plus one carry per column; the solution lists
+/// the letters in `SEND_MORE_LETTERS` order, then the carries from the units column up
+pub const SEND_MORE_LETTERS: [char; 8] = ['S', 'E', 'N', 'D', 'M', 'O', 'R', 'Y'];
+
+pub fn send_more_money() -> Csp<u8> {
+    let mut csp = Csp::new();
+    let [s, e, n, d, m, o, r, y] = SEND_MORE_LETTERS.map(|letter| csp.add_variable(&letter.to_string(), (0..=9).collect()));
+    let [c1, c2, c3, c4] = [1, 2, 3, 4].map(|k| csp.add_variable(&format!("c{}", k), vec![0, 1]));
+    csp.all_different(&[s, e, n, d, m, o, r, y]);
+    csp.add_constraint(&[s], |v| v[0] != 0);
+    csp.add_constraint(&[m], |v| v[0] != 0);
+    // Each column: top + bottom + carry in = digit + 10 × carry out
+    csp.add_constraint(&[d, e, y, c1], |v| v[0] + v[1] == v[2] + 10 * v[3]);
+    csp.add_constraint(&[n, r, c1, e, c2], |v| v[0] + v[1] + v[2] == v[3] + 10 * v[4]);
+    csp.add_constraint(&[e, o, c2, n, c3], |v| v[0] + v[1] + v[2] == v[3] + 10 * v[4]);
+    csp.add_constraint(&[s, m, c3, o, c4], |v| v[0] + v[1] + v[2] == v[3] + 10 * v[4]);
+    csp.add_constraint(&[c4, m], |v| v[0] == v[1]);
+    csp
+}
+
+/// One variable per row holding the queen's column
+pub fn n_queens(n: usize) -> Csp<usize> {
+    let mut csp = Csp::new();
+    let rows: Vec<Var> = (0..n).map(|row| csp.add_variable(&format!("row{}", row), (0..n).collect())).collect();
+    for (i, &a) in rows.iter().enumerate() {
+        for (gap, &b) in rows[i + 1..].iter().enumerate() {
+            csp.add_constraint(&[a, b], move |v| v[0] != v[1] && v[0].abs_diff(v[1]) != gap + 1);
+        }
+    }
+    csp
+}
+
+fn word_value(word: &str, digits: &[u8]) -> u64 {
+    word.chars().fold(0, |acc, c| {
+        let letter = SEND_MORE_LETTERS.iter().position(|&l| l == c).expect("letter of SEND+MORE");
+        acc * 10 + u64::from(digits[letter])
+    })
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct CspDemo;
+
+impl Demo for CspDemo {
+    fn name(&self) -> &'static str {
+        "csp"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generic constraint solver with MRV and forward checking"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Map Colouring: Australia =====")?;
+        let australia = map_coloring(&AUSTRALIA, &AUSTRALIA_BORDERS, &["red", "green", "blue"]);
+        match australia.solve() {
+            Some(colors) => {
+                for (var, color) in colors.iter().enumerate() {
+                    writeln!(out, "{:<4} {}", australia.name(var), color)?;
+                }
+            }
+            None => writeln!(out, "no colouring")?,
+        }
+        writeln!(out, "colourings with 3 colours: {}", australia.solutions(usize::MAX).len())?;
+        let two = map_coloring(&AUSTRALIA, &AUSTRALIA_BORDERS, &["red", "green"]);
+        writeln!(out, "colourings with 2 colours: {}", two.solutions(usize::MAX).len())?;
+
+        writeln!(out, "\n===== SEND + MORE = MONEY =====")?;
+        let puzzle = send_more_money();
+        if let Some(digits) = puzzle.solve() {
+            let letters: Vec<String> = SEND_MORE_LETTERS.iter().zip(&digits).map(|(l, d)| format!("{}={}", l, d)).collect();
+            writeln!(out, "{}", letters.join(" "))?;
+            writeln!(out, "  {:>5}", word_value("SEND", &digits))?;
+            writeln!(out, "+ {:>5}", word_value("MORE", &digits))?;
+            writeln!(out, "= {:>5}", word_value("MONEY", &digits))?;
+        }
+
+        writeln!(out, "\n===== What the Heuristics Save =====")?;
+        let stats = puzzle.solve_with(Strategy::FULL).1;
+        writeln!(out, "SEND+MORE with both: {} assignments, {} values pruned", stats.assignments, stats.pruned)?;
+        writeln!(out, "assignments until the first N-Queens solution:")?;
+        writeln!(out, "{:>3} {:>8} {:>8} {:>8} {:>8}", "n", "plain", "mrv", "fc", "both")?;
+        let strategies = [
+            Strategy::PLAIN,
+            Strategy { mrv: true, forward_checking: false },
+            Strategy { mrv: false, forward_checking: true },
+            Strategy::FULL,
+        ];
+        for n in [8, 12, 16] {
+            let queens = n_queens(n);
+            let counts: Vec<String> =
+                strategies.iter().map(|&s| format!("{:>8}", queens.solve_with(s).1.assignments)).collect();
+            writeln!(out, "{:>3} {}", n, counts.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&CspDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn australia_colouring_is_valid() {
+        let csp = map_coloring(&AUSTRALIA, &AUSTRALIA_BORDERS, &["red", "green", "blue"]);
+        let colors = csp.solve().expect("three colours are enough");
+        assert!(csp.is_satisfied(&colors));
+        for (a, b) in AUSTRALIA_BORDERS {
+            let index = |name| AUSTRALIA.iter().position(|&r| r == name).unwrap();
+            assert_ne!(colors[index(a)], colors[index(b)], "{} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn australia_solution_counts() {
+        // SA takes one of 3 colours, the ring around it alternates the other two (2 ways),
+        // and Tasmania is free
+        let three = map_coloring(&AUSTRALIA, &AUSTRALIA_BORDERS, &["r", "g", "b"]);
+        let all = three.solutions(usize::MAX);
+        assert_eq!(all.len(), 18);
+        assert!(all.iter().all(|s| three.is_satisfied(s)));
+        assert_eq!(three.solutions(5).len(), 5);
+        assert!(three.solutions(0).is_empty());
+        let two = map_coloring(&AUSTRALIA, &AUSTRALIA_BORDERS, &["r", "g"]);
+        assert_eq!(two.solve(), None);
+    }
+
+    #[test]
+    fn send_more_money_has_its_unique_answer() {
+        let csp = send_more_money();
+        let solutions = csp.solutions(usize::MAX);
+        assert_eq!(solutions.len(), 1);
+        let digits = &solutions[0];
+        assert!(csp.is_satisfied(digits));
+        assert_eq!(&digits[..8], &[9, 5, 6, 7, 1, 0, 8, 2]);
+        assert_eq!(word_value("SEND", digits) + word_value("MORE", digits), word_value("MONEY", digits));
+    }
+
+    #[test]
+    fn every_strategy_finds_a_valid_solution() {
+        let csp = n_queens(8);
+        let mut counts = Vec::new();
+        for strategy in [
+            Strategy::PLAIN,
+            Strategy { mrv: true, forward_checking: false },
+            Strategy { mrv: false, forward_checking: true },
+            Strategy::FULL,
+        ] {
+            let (solution, stats) = csp.solve_with(strategy);
+            let columns = solution.expect("8 queens has solutions");
+            assert!(csp.is_satisfied(&columns));
+            assert!(crate::n_queens::is_valid(&columns));
+            counts.push(stats.assignments);
+        }
+        assert!(counts[3] < counts[0], "{:?}", counts);
+    }
+
+    #[test]
+    fn solution_counts_match_n_queens() {
+        for n in 1..=7 {
+            assert_eq!(n_queens(n).solutions(usize::MAX).len() as u64, crate::n_queens::count(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn unary_constraints_and_rejected_assignments() {
+        let mut csp = Csp::new();
+        let x = csp.add_variable("x", vec![1, 2, 3, 4]);
+        let y = csp.add_variable("y", vec![1, 2, 3, 4]);
+        csp.add_constraint(&[x], |v| v[0] % 2 == 0);
+        csp.add_constraint(&[x, y], |v| v[0] + v[1] == 7);
+        assert_eq!(csp.solutions(usize::MAX), [vec![4, 3]]);
+        assert!(!csp.is_satisfied(&[3, 4]));
+        assert!(!csp.is_satisfied(&[4]));
+        csp.add_constraint(&[y], |v| v[0] > 4);
+        assert_eq!(csp.solve(), None);
+    }
+}
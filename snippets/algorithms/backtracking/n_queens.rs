@@ -0,0 +1,227 @@
+//! N-Queens in Rust
+//!
+//! Place `n` queens on an `n`×`n` board so that no two share a row, column or diagonal.
+//! Every solution has exactly one queen per row, so a search places one row at a time
+//! and backs up as soon as a row has no safe square:
+//! - The plain version keeps one flag per column and per diagonal. Squares on the same
+//!   `/` diagonal share `row + col`, and squares on the same `\` diagonal share
+//!   `row - col`, so checking a square is three lookups.
+//! - The bitmask version keeps the same three sets as bits of one word each, shifted by
+//!   one as it moves down a row so the diagonals stay lined up. The safe squares of a
+//!   whole row are then a single expression, and the lowest set bit picks the next one.
+//!
+//! A solution is a list of the queen's column in each row.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+/// Largest board the bitmask version handles; far beyond what can be counted anyway
+pub const MAX_BITMASK_N: usize = 32;
+
+// ========== Plain Backtracking ==========
+
+struct Board {
+    n: usize,
+    columns: Vec<usize>,
+    column_used: Vec<bool>,
+    /// Indexed by `row + col`
+    rising_used: Vec<bool>,
+    /// Indexed by `row + n - 1 - col`
+    falling_used: Vec<bool>,
+}
+
+impl Board {
+    fn new(n: usize) -> Self {
+        let diagonals = (2 * n).saturating_sub(1);
+        Board {
+            n,
+            columns: Vec::with_capacity(n),
+            column_used: vec![false; n],
+            rising_used: vec![false; diagonals],
+            falling_used: vec![false; diagonals],
+        }
+    }
+
+    fn set(&mut self, row: usize, col: usize, used: bool) {
+        self.column_used[col] = used;
+        self.rising_used[row + col] = used;
+        self.falling_used[row + self.n - 1 - col] = used;
+    }
+
+    fn place_from(&mut self, row: usize, found: &mut Vec<Vec<usize>>) {
+        if row == self.n {
+            found.push(self.columns.clone());
+            return;
+        }
+        for col in 0..self.n {
+            if self.column_used[col] || self.rising_used[row + col] || self.falling_used[row + self.n - 1 - col] {
+                continue;
+            }
+            self.set(row, col, true);
+            self.columns.push(col);
+            self.place_from(row + 1, found);
+            self.columns.pop();
+            self.set(row, col, false);
+        }
+    }
+}
+
+/// Every solution, in lexicographic order of the column lists
+pub fn solutions(n: usize) -> Vec<Vec<usize>> {
+    let mut found = Vec::new();
+    Board::new(n).place_from(0, &mut found);
+    found
+}
+
+// ========== Bitmask Backtracking ==========
+
+/// Bit `c` set in `columns` means column `c` is taken; `rising` and `falling` hold the
+/// squares of the current row attacked along `/` and `\` diagonals
+fn bitmask_search(full: u64, columns: u64, rising: u64, falling: u64, placed: &mut Vec<usize>, on_solution: &mut dyn FnMut(&[usize])) {
+    if columns == full {
+        on_solution(placed);
+        return;
+    }
+    let mut free = full & !(columns | rising | falling);
+    while free != 0 {
+        let bit = free & free.wrapping_neg();
+        free ^= bit;
+        placed.push(bit.trailing_zeros() as usize);
+        bitmask_search(full, columns | bit, (rising | bit) >> 1, ((falling | bit) << 1) & full, placed, on_solution);
+        placed.pop();
+    }
+}
+
+fn full_mask(n: usize) -> u64 {
+    assert!(n <= MAX_BITMASK_N, "bitmask N-Queens supports boards up to {}", MAX_BITMASK_N);
+    (1u64 << n) - 1
+}
+
+/// Same solutions as `solutions`, in the same order, from the bitmask search
+pub fn solutions_bitmask(n: usize) -> Vec<Vec<usize>> {
+    let mut found = Vec::new();
+    bitmask_search(full_mask(n), 0, 0, 0, &mut Vec::with_capacity(n), &mut |placed| found.push(placed.to_vec()));
+    found
+}
+
+/// Number of solutions, without keeping any of them
+pub fn count(n: usize) -> u64 {
+    let mut total = 0;
+    bitmask_search(full_mask(n), 0, 0, 0, &mut Vec::with_capacity(n), &mut |_| total += 1);
+    total
+}
+
+// ========== Checking and Printing ==========
+
+/// Whether `columns` places one queen per row with no two attacking each other
+pub fn is_valid(columns: &[usize]) -> bool {
+    let n = columns.len();
+    columns.iter().enumerate().all(|(row, &col)| {
+        col < n
+            && columns[..row]
+                .iter()
+                .enumerate()
+                .all(|(r, &c)| c != col && row - r != col.abs_diff(c))
+    })
+}
+
+/// The board as text, `Q` for a queen and `.` for an empty square
+pub fn render(columns: &[usize]) -> String {
+    let n = columns.len();
+    columns
+        .iter()
+        .map(|&col| (0..n).map(|c| if c == col { "Q" } else { "." }).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct NQueensDemo;
+
+impl Demo for NQueensDemo {
+    fn name(&self) -> &'static str {
+        "n_queens"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "N-Queens by backtracking, with flag arrays and with bitmasks"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== First Solution for 8 Queens =====")?;
+        let eight = solutions(8);
+        writeln!(out, "{}", render(&eight[0]))?;
+        writeln!(out, "columns {:?}", eight[0])?;
+
+        writeln!(out, "\n===== The Two Solutions for 4 Queens =====")?;
+        let four = solutions(4);
+        let (left, right) = (render(&four[0]), render(&four[1]));
+        for (a, b) in left.lines().zip(right.lines()) {
+            writeln!(out, "{}    {}", a, b)?;
+        }
+
+        writeln!(out, "\n===== Solution Counts =====")?;
+        writeln!(out, "{:>3} {:>10} {:>10}", "n", "plain", "bitmask")?;
+        for n in 1..=12 {
+            writeln!(out, "{:>3} {:>10} {:>10}", n, solutions(n).len(), count(n))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&NQueensDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// OEIS A000170, from n = 0
+    const KNOWN_COUNTS: [u64; 13] = [1, 1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200];
+
+    #[test]
+    fn counts_match_known_values() {
+        for (n, &expected) in KNOWN_COUNTS.iter().enumerate() {
+            assert_eq!(count(n), expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn plain_and_bitmask_agree() {
+        for (n, &expected) in KNOWN_COUNTS.iter().enumerate() {
+            let plain = solutions(n);
+            assert_eq!(plain.len() as u64, expected, "n = {}", n);
+            assert_eq!(solutions_bitmask(n), plain, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn every_solution_is_valid_and_distinct() {
+        let all = solutions(8);
+        assert!(all.iter().all(|s| is_valid(s)));
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+        assert!(!is_valid(&[0, 1]));
+        assert!(!is_valid(&[0, 2, 1]));
+        assert!(!is_valid(&[1, 3, 0, 4]));
+        assert!(is_valid(&[1, 3, 0, 2]));
+    }
+
+    #[test]
+    fn four_queens_board() {
+        assert_eq!(solutions(4), [vec![1, 3, 0, 2], vec![2, 0, 3, 1]]);
+        assert_eq!(render(&[1, 3, 0, 2]), ". Q . .\n. . . Q\nQ . . .\n. . Q .");
+    }
+
+    #[test]
+    #[should_panic(expected = "boards up to 32")]
+    fn bitmask_rejects_oversized_boards() {
+        count(33);
+    }
+}
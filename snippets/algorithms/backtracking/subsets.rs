@@ -0,0 +1,328 @@
+//! Subsets, Subset Sum and Partitions in Rust
+//!
+//! Every question here is about choosing some of `n` items, so the search space is the
+//! `2^n` subsets, and the work is in not visiting most of them:
+//! - The power set itself can be counted out with an `n`-bit mask (bit `i` set means item
+//!   `i` is in), or built by backtracking, which extends the current subset with each
+//!   later item in turn and so lists subsets in lexicographic order.
+//! - Subset sum tries the largest values first and gives up on a branch as soon as the
+//!   target is out of reach: when a value overshoots what is left, or when everything
+//!   still unchosen adds up to less than what is left.
+//! - Partitioning into `k` groups of equal sum fills buckets with the largest values
+//!   first and never tries two buckets holding the same total for the same value, since
+//!   they would lead to the same outcome.
+//!
+//! Values are non-negative integers; results refer to items by their position.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Power Set ==========
+
+/// Subsets in mask order: subset `m` holds item `i` when bit `i` of `m` is set
+pub fn power_set_bitmask<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    assert!(items.len() < 64, "a bitmask power set needs fewer than 64 items");
+    (0..1u64 << items.len())
+        .map(|mask| items.iter().enumerate().filter(|&(i, _)| mask & (1 << i) != 0).map(|(_, x)| x.clone()).collect())
+        .collect()
+}
+
+/// Subsets in lexicographic order of positions, by backtracking
+pub fn power_set_recursive<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    fn extend<T: Clone>(items: &[T], start: usize, current: &mut Vec<T>, out: &mut Vec<Vec<T>>) {
+        out.push(current.clone());
+        for i in start..items.len() {
+            current.push(items[i].clone());
+            extend(items, i + 1, current, out);
+            current.pop();
+        }
+    }
+    let mut out = Vec::with_capacity(1 << items.len().min(20));
+    extend(items, 0, &mut Vec::new(), &mut out);
+    out
+}
+
+// ========== Subset Sum ==========
+
+struct SubsetSearch<'a> {
+    values: &'a [u64],
+    /// Positions sorted by value, largest first
+    order: Vec<usize>,
+    /// `suffix[j]` is the sum of the values at `order[j..]`
+    suffix: Vec<u64>,
+    chosen: Vec<usize>,
+    found: Vec<Vec<usize>>,
+    first_only: bool,
+}
+
+impl<'a> SubsetSearch<'a> {
+    fn new(values: &'a [u64], first_only: bool) -> Self {
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(values[i]));
+        let mut suffix = vec![0u64; values.len() + 1];
+        for j in (0..values.len()).rev() {
+            suffix[j] = suffix[j + 1].saturating_add(values[order[j]]);
+        }
+        SubsetSearch { values, order, suffix, chosen: Vec::new(), found: Vec::new(), first_only }
+    }
+
+    /// Returns true to stop the whole search
+    fn search(&mut self, j: usize, remaining: u64) -> bool {
+        if self.suffix[j] < remaining {
+            return false;
+        }
+        if j == self.order.len() {
+            // Only reachable with nothing remaining, given the check above
+            let mut subset = self.chosen.clone();
+            subset.sort_unstable();
+            self.found.push(subset);
+            return self.first_only;
+        }
+        let item = self.order[j];
+        if self.values[item] <= remaining {
+            self.chosen.push(item);
+            let stop = self.search(j + 1, remaining - self.values[item]);
+            self.chosen.pop();
+            if stop {
+                return true;
+            }
+        }
+        self.search(j + 1, remaining)
+    }
+}
+
+/// Positions of some subset of `values` adding up to `target`
+pub fn find_subset_sum(values: &[u64], target: u64) -> Option<Vec<usize>> {
+    let mut search = SubsetSearch::new(values, true);
+    search.search(0, target);
+    search.found.pop()
+}
+
+/// Positions of every subset adding up to `target`, sorted
+pub fn all_subset_sums(values: &[u64], target: u64) -> Vec<Vec<usize>> {
+    let mut search = SubsetSearch::new(values, false);
+    search.search(0, target);
+    search.found.sort();
+    search.found
+}
+
+// ========== Partition into k Equal Sums ==========
+
+/// `k` groups of positions whose values all add up to the same total, using every value
+/// exactly once
+pub fn partition_k_equal(values: &[u64], k: usize) -> Option<Vec<Vec<usize>>> {
+    if k == 0 {
+        return values.is_empty().then(Vec::new);
+    }
+    let total: u64 = values.iter().sum();
+    if total % k as u64 != 0 {
+        return None;
+    }
+    let target = total / k as u64;
+    if values.iter().any(|&v| v > target) {
+        return None;
+    }
+
+    fn fill(values: &[u64], order: &[usize], target: u64, sums: &mut [u64], groups: &mut [Vec<usize>]) -> bool {
+        let Some((&item, rest)) = order.split_first() else {
+            return true;
+        };
+        for b in 0..sums.len() {
+            // A bucket with the same total as an earlier one would repeat its search
+            if sums[b] + values[item] > target || sums[..b].contains(&sums[b]) {
+                continue;
+            }
+            sums[b] += values[item];
+            groups[b].push(item);
+            if fill(values, rest, target, sums, groups) {
+                return true;
+            }
+            sums[b] -= values[item];
+            groups[b].pop();
+        }
+        false
+    }
+
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(values[i]));
+    let mut sums = vec![0; k];
+    let mut groups = vec![Vec::new(); k];
+    fill(values, &order, target, &mut sums, &mut groups).then_some(groups)
+}
+
+// ========== Demo Code ==========
+
+fn values_at(values: &[u64], positions: &[usize]) -> Vec<u64> {
+    positions.iter().map(|&i| values[i]).collect()
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct SubsetsDemo;
+
+impl Demo for SubsetsDemo {
+    fn name(&self) -> &'static str {
+        "subsets"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Power sets, subset sum with pruning, and partitioning into equal sums"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Power Set of abc =====")?;
+        let abc = ['a', 'b', 'c'];
+        let show = |subsets: Vec<Vec<char>>| -> String {
+            subsets.iter().map(|s| format!("{{{}}}", s.iter().collect::<String>())).collect::<Vec<_>>().join(" ")
+        };
+        writeln!(out, "bitmask:   {}", show(power_set_bitmask(&abc)))?;
+        writeln!(out, "recursive: {}", show(power_set_recursive(&abc)))?;
+
+        writeln!(out, "\n===== Subset Sum =====")?;
+        let values = [3, 34, 4, 12, 5, 2];
+        writeln!(out, "values {:?}", values)?;
+        for target in [9, 30, 60] {
+            match find_subset_sum(&values, target) {
+                Some(subset) => writeln!(out, "  {:>2}: yes, {:?}", target, values_at(&values, &subset))?,
+                None => writeln!(out, "  {:>2}: no", target)?,
+            }
+        }
+        let every = all_subset_sums(&values, 9);
+        writeln!(out, "all subsets for 9: {:?}", every.iter().map(|s| values_at(&values, s)).collect::<Vec<_>>())?;
+        let ones_to_twenty: Vec<u64> = (1..=20).collect();
+        writeln!(out, "subsets of 1..=20 adding up to 50: {}", all_subset_sums(&ones_to_twenty, 50).len())?;
+
+        writeln!(out, "\n===== Partition into k Equal Sums =====")?;
+        let cases = [
+            (vec![4, 3, 2, 3, 5, 2, 1], 4),
+            (vec![1, 2, 3, 4], 3),
+            (vec![2, 2, 2, 2, 3, 4, 5], 4),
+            (vec![10, 10, 10, 7, 7, 7, 7, 7, 7, 6, 6, 6], 3),
+        ];
+        for (values, k) in cases {
+            match partition_k_equal(&values, k) {
+                Some(groups) => {
+                    let shown: Vec<Vec<u64>> = groups.iter().map(|g| values_at(&values, g)).collect();
+                    writeln!(out, "{:?} into {}: {:?}", values, k, shown)?;
+                }
+                None => writeln!(out, "{:?} into {}: impossible", values, k)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&SubsetsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    /// Number of subsets with each sum up to `target`, by the usual table
+    fn count_by_dp(values: &[u64], target: u64) -> u64 {
+        let mut ways = vec![0u64; target as usize + 1];
+        ways[0] = 1;
+        for &v in values {
+            for s in (v as usize..=target as usize).rev() {
+                ways[s] += ways[s - v as usize];
+            }
+        }
+        ways[target as usize]
+    }
+
+    fn random_values(rng: &mut Pcg32, max: u64) -> Vec<u64> {
+        (0..rng.below(14)).map(|_| rng.below(max + 1)).collect()
+    }
+
+    #[test]
+    fn power_sets_agree() {
+        let items: Vec<u8> = (0..6).collect();
+        let mut by_mask = power_set_bitmask(&items);
+        let recursive = power_set_recursive(&items);
+        assert_eq!(by_mask.len(), 64);
+        assert!(recursive.windows(2).all(|w| w[0] < w[1]));
+        by_mask.sort();
+        assert_eq!(by_mask, recursive);
+        assert_eq!(power_set_recursive::<u8>(&[]), [Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn subset_sum_matches_dp() {
+        let mut rng = Pcg32::new(975, 0);
+        for _ in 0..300 {
+            let values = random_values(&mut rng, 20);
+            let target = rng.below(60);
+            let expected = count_by_dp(&values, target);
+            let all = all_subset_sums(&values, target);
+            assert_eq!(all.len() as u64, expected, "{:?} -> {}", values, target);
+            assert_eq!(find_subset_sum(&values, target).is_some(), expected > 0);
+            for subset in &all {
+                assert_eq!(values_at(&values, subset).iter().sum::<u64>(), target);
+            }
+        }
+    }
+
+    #[test]
+    fn subset_sum_edge_cases() {
+        assert_eq!(find_subset_sum(&[], 0), Some(vec![]));
+        assert_eq!(find_subset_sum(&[], 1), None);
+        // Zeros may or may not be included, so each doubles the count
+        assert_eq!(all_subset_sums(&[0, 0, 5], 5).len(), 4);
+        assert_eq!(all_subset_sums(&[3, 3, 3], 6), [vec![0, 1], vec![0, 2], vec![1, 2]]);
+        assert_eq!(find_subset_sum(&[u64::MAX, 1], u64::MAX), Some(vec![0]));
+    }
+
+    /// Tries every assignment of values to groups
+    fn partition_by_brute_force(values: &[u64], k: usize) -> bool {
+        let total: u64 = values.iter().sum();
+        if total % k as u64 != 0 {
+            return false;
+        }
+        (0..k.pow(values.len() as u32)).any(|mut code| {
+            let mut sums = vec![0; k];
+            for &v in values {
+                sums[code % k] += v;
+                code /= k;
+            }
+            sums.iter().all(|&s| s == total / k as u64)
+        })
+    }
+
+    #[test]
+    fn partitions_match_brute_force() {
+        let mut rng = Pcg32::new(975, 1);
+        for _ in 0..300 {
+            let values: Vec<u64> = (0..rng.below(8)).map(|_| rng.below(9)).collect();
+            let k = 1 + rng.below(4) as usize;
+            let found = partition_k_equal(&values, k);
+            assert_eq!(found.is_some(), partition_by_brute_force(&values, k), "{:?} into {}", values, k);
+            if let Some(groups) = found {
+                let mut used: Vec<usize> = groups.concat();
+                used.sort_unstable();
+                assert_eq!(used, (0..values.len()).collect::<Vec<_>>());
+                let sums: Vec<u64> = groups.iter().map(|g| values_at(&values, g).iter().sum()).collect();
+                assert!(sums.windows(2).all(|w| w[0] == w[1]), "{:?}", sums);
+            }
+        }
+    }
+
+    #[test]
+    fn two_way_partition_matches_dp() {
+        let mut rng = Pcg32::new(975, 2);
+        for _ in 0..300 {
+            let values = random_values(&mut rng, 30);
+            let total: u64 = values.iter().sum();
+            let expected = total % 2 == 0 && count_by_dp(&values, total / 2) > 0;
+            assert_eq!(partition_k_equal(&values, 2).is_some(), expected, "{:?}", values);
+        }
+        assert_eq!(partition_k_equal(&[], 0), Some(vec![]));
+        assert_eq!(partition_k_equal(&[1], 0), None);
+        assert_eq!(partition_k_equal(&[5, 1], 2), None);
+    }
+}
@@ -0,0 +1,488 @@
+//! Sudoku in Rust
+//!
+//! A solver that thinks before it guesses, and a generator built on top of it:
+//! - Each empty cell keeps the set of digits still possible there, as bits of a `u16`.
+//!   Placing a digit removes it from the cell's 20 peers (its row, column and box).
+//! - Propagation repeats two rules until nothing changes: a cell with one candidate left
+//!   gets that digit (naked single), and a digit with only one possible cell left in a
+//!   row, column or box goes there (hidden single). Many puzzles need nothing else.
+//! - When propagation stalls, the search picks the cell with the fewest candidates and
+//!   tries each one on a copy of the state, backing up when a copy hits a contradiction.
+//! - The generator fills an empty grid with shuffled candidate order, then removes clues
+//!   in random order, putting back any whose removal allows a second solution or makes
+//!   the puzzle harder than asked for.
+//!
+//! Difficulty is measured by how many guesses the solver needed, which tracks how much
+//! a human would have to do beyond spotting singles.
+
+use crate::prng::{Pcg32, Rng};
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// ========== Grid ==========
+
+/// A 9×9 grid; 0 marks an empty cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    cells: [u8; 81],
+}
+
+impl Grid {
+    pub const fn empty() -> Self {
+        Grid { cells: [0; 81] }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        Some(self.cells[row * 9 + col]).filter(|&d| d != 0)
+    }
+
+    pub fn clues(&self) -> usize {
+        self.cells.iter().filter(|&&d| d != 0).count()
+    }
+
+    /// All 81 cells filled with no digit twice in any row, column or box
+    pub fn is_solved(&self) -> bool {
+        self.clues() == 81
+            && tables().units.iter().all(|unit| {
+                let seen = unit.iter().fold(0u16, |seen, &cell| seen | 1 << self.cells[cell]);
+                seen == ALL_DIGITS
+            })
+    }
+
+    /// Every clue of `self` appears, unchanged, in `other`
+    pub fn is_completed_by(&self, other: &Grid) -> bool {
+        self.cells.iter().zip(&other.cells).all(|(&a, &b)| a == 0 || a == b)
+    }
+
+    /// One line of 81 characters, `.` for empty cells
+    pub fn to_line(&self) -> String {
+        self.cells.iter().map(|&d| if d == 0 { '.' } else { (b'0' + d) as char }).collect()
+    }
+}
+
+impl FromStr for Grid {
+    type Err = String;
+
+    /// Digits 1-9 for clues and `.` or `0` for empty cells; whitespace is ignored, so
+    /// both one-line and nine-line layouts work
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cells = [0; 81];
+        let mut count = 0;
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            let digit = match c {
+                '.' | '0' => 0,
+                '1'..='9' => c as u8 - b'0',
+                _ => return Err(format!("unexpected character {:?} in grid", c)),
+            };
+            if count == 81 {
+                return Err("more than 81 cells".to_string());
+            }
+            cells[count] = digit;
+            count += 1;
+        }
+        if count < 81 {
+            return Err(format!("only {} cells, expected 81", count));
+        }
+        Ok(Grid { cells })
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const RULE: &str = "+-------+-------+-------+";
+        for row in 0..9 {
+            if row % 3 == 0 {
+                writeln!(f, "{}", RULE)?;
+            }
+            for col in 0..9 {
+                if col % 3 == 0 {
+                    write!(f, "| ")?;
+                }
+                match self.get(row, col) {
+                    Some(d) => write!(f, "{} ", d)?,
+                    None => write!(f, ". ")?,
+                }
+            }
+            writeln!(f, "|")?;
+        }
+        write!(f, "{}", RULE)
+    }
+}
+
+// ========== Units and Peers ==========
+
+/// Bits 1 to 9 set
+const ALL_DIGITS: u16 = 0b11_1111_1110;
+
+struct Tables {
+    /// The 9 rows, 9 columns and 9 boxes
+    units: [[usize; 9]; 27],
+    /// Indices into `units` of each cell's row, column and box
+    units_of: [[usize; 3]; 81],
+    /// The 20 other cells sharing a unit with each cell
+    peers: [[usize; 20]; 81],
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let units: [[usize; 9]; 27] = std::array::from_fn(|u| {
+            let i = u % 9;
+            std::array::from_fn(|j| match u / 9 {
+                0 => i * 9 + j,
+                1 => j * 9 + i,
+                _ => (i / 3 * 3 + j / 3) * 9 + i % 3 * 3 + j % 3,
+            })
+        });
+        let units_of: [[usize; 3]; 81] = std::array::from_fn(|cell| {
+            let (row, col) = (cell / 9, cell % 9);
+            [row, 9 + col, 18 + row / 3 * 3 + col / 3]
+        });
+        let peers = std::array::from_fn(|cell| {
+            let mut found: Vec<usize> = units_of[cell].iter().flat_map(|&u| units[u]).filter(|&c| c != cell).collect();
+            found.sort_unstable();
+            found.dedup();
+            let mut peers = [0; 20];
+            peers.copy_from_slice(&found);
+            peers
+        });
+        Tables { units, units_of, peers }
+    })
+}
+
+fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (1..=9).filter(move |d| mask & (1 << d) != 0)
+}
+
+// ========== Solver ==========
+
+/// Candidate digits of every cell, as bits 1 to 9
+#[derive(Clone, Copy)]
+struct Candidates([u16; 81]);
+
+impl Candidates {
+    /// Candidates after placing every clue, or `None` if the clues already contradict
+    fn from_grid(grid: &Grid) -> Option<Self> {
+        let mut candidates = Candidates([ALL_DIGITS; 81]);
+        for (cell, &digit) in grid.cells.iter().enumerate() {
+            if digit != 0 && !candidates.assign(cell, digit) {
+                return None;
+            }
+        }
+        Some(candidates)
+    }
+
+    fn assign(&mut self, cell: usize, digit: u8) -> bool {
+        digits(self.0[cell] & !(1 << digit)).all(|other| self.eliminate(cell, other))
+    }
+
+    /// Removes `digit` from `cell` and follows the consequences; false on a contradiction
+    fn eliminate(&mut self, cell: usize, digit: u8) -> bool {
+        let bit = 1 << digit;
+        if self.0[cell] & bit == 0 {
+            return true;
+        }
+        self.0[cell] &= !bit;
+        let tables = tables();
+
+        // Naked single: the cell's last candidate leaves all its peers
+        match self.0[cell].count_ones() {
+            0 => return false,
+            1 => {
+                let last = self.0[cell].trailing_zeros() as u8;
+                if !tables.peers[cell].iter().all(|&peer| self.eliminate(peer, last)) {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+        // Hidden single: a unit with one place left for `digit` puts it there
+        for &unit in &tables.units_of[cell] {
+            let mut places = tables.units[unit].iter().filter(|&&c| self.0[c] & bit != 0);
+            match (places.next(), places.next()) {
+                (None, _) => return false,
+                (Some(&only), None) if self.0[only] != bit && !self.assign(only, digit) => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+
+    fn to_grid(self) -> Grid {
+        let mut cells = [0; 81];
+        for (cell, &mask) in self.0.iter().enumerate() {
+            if mask.count_ones() == 1 {
+                cells[cell] = mask.trailing_zeros() as u8;
+            }
+        }
+        Grid { cells }
+    }
+}
+
+struct Search<'a> {
+    guesses: usize,
+    /// Candidate order for each branch; shuffled when generating
+    rng: Option<&'a mut Pcg32>,
+}
+
+impl Search<'_> {
+    /// Calls `on_solution` for each solution until it returns false; returns false then
+    fn run(&mut self, candidates: Candidates, on_solution: &mut dyn FnMut(Grid) -> bool) -> bool {
+        // Minimum remaining values: branch on the unsolved cell with the fewest candidates
+        let open = (0..81).filter(|&c| candidates.0[c].count_ones() > 1);
+        let Some(cell) = open.min_by_key(|&c| candidates.0[c].count_ones()) else {
+            return on_solution(candidates.to_grid());
+        };
+        let mut options: Vec<u8> = digits(candidates.0[cell]).collect();
+        if let Some(rng) = self.rng.as_deref_mut() {
+            rng.shuffle(&mut options);
+        }
+        for digit in options {
+            self.guesses += 1;
+            let mut trial = candidates;
+            if trial.assign(cell, digit) && !self.run(trial, on_solution) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The first solution found, and how many guesses it took
+fn solve_counting_guesses(puzzle: &Grid, rng: Option<&mut Pcg32>) -> Option<(Grid, usize)> {
+    let candidates = Candidates::from_grid(puzzle)?;
+    let mut search = Search { guesses: 0, rng };
+    let mut found = None;
+    search.run(candidates, &mut |grid| {
+        found = Some(grid);
+        false
+    });
+    found.map(|grid| (grid, search.guesses))
+}
+
+pub fn solve(puzzle: &Grid) -> Option<Grid> {
+    solve_counting_guesses(puzzle, None).map(|(grid, _)| grid)
+}
+
+/// Number of solutions, stopping once `limit` are found; a proper puzzle has exactly one
+pub fn count_solutions(puzzle: &Grid, limit: usize) -> usize {
+    let Some(candidates) = Candidates::from_grid(puzzle) else {
+        return 0;
+    };
+    let mut count = 0;
+    Search { guesses: 0, rng: None }.run(candidates, &mut |_| {
+        count += 1;
+        count < limit
+    });
+    count
+}
+
+// ========== Difficulty and Generation ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    /// Singles alone solve it
+    Easy,
+    /// A few guesses
+    Medium,
+    /// Many guesses
+    Hard,
+}
+
+impl Difficulty {
+    fn from_guesses(guesses: usize) -> Self {
+        match guesses {
+            0 => Difficulty::Easy,
+            1..=3 => Difficulty::Medium,
+            _ => Difficulty::Hard,
+        }
+    }
+
+    /// Easy puzzles stop removing clues here, so they are generous as well as simple
+    fn min_clues(self) -> usize {
+        match self {
+            Difficulty::Easy => 36,
+            Difficulty::Medium | Difficulty::Hard => 17,
+        }
+    }
+}
+
+/// `None` for puzzles without a solution
+pub fn rate(puzzle: &Grid) -> Option<Difficulty> {
+    solve_counting_guesses(puzzle, None).map(|(_, guesses)| Difficulty::from_guesses(guesses))
+}
+
+/// Attempts at a hard enough puzzle before settling for the hardest one seen
+const GENERATION_ATTEMPTS: usize = 20;
+
+/// A puzzle with exactly one solution, rated `difficulty` where the attempts allow, and
+/// that solution
+pub fn generate(difficulty: Difficulty, seed: u64) -> (Grid, Grid) {
+    let mut rng = Pcg32::new(seed, 0);
+    let mut best: Option<(Grid, Grid, Difficulty)> = None;
+    for _ in 0..GENERATION_ATTEMPTS {
+        let (solution, _) = solve_counting_guesses(&Grid::empty(), Some(&mut rng)).expect("the empty grid has solutions");
+        let mut puzzle = solution;
+        let mut order: Vec<usize> = (0..81).collect();
+        rng.shuffle(&mut order);
+        for cell in order {
+            if puzzle.clues() <= difficulty.min_clues() {
+                break;
+            }
+            let clue = puzzle.cells[cell];
+            puzzle.cells[cell] = 0;
+            if count_solutions(&puzzle, 2) != 1 || rate(&puzzle) > Some(difficulty) {
+                puzzle.cells[cell] = clue;
+            }
+        }
+        let rating = rate(&puzzle).unwrap_or(Difficulty::Easy);
+        if rating == difficulty {
+            return (puzzle, solution);
+        }
+        if best.as_ref().is_none_or(|&(_, _, r)| rating > r) {
+            best = Some((puzzle, solution, rating));
+        }
+    }
+    let (puzzle, solution, _) = best.expect("at least one attempt ran");
+    (puzzle, solution)
+}
+
+// ========== Demo Code ==========
+
+/// A newspaper puzzle: singles are enough
+pub const CLASSIC: &str = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+/// Arto Inkala's 2012 "world's hardest sudoku"
+pub const INKALA: &str = "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+/// One of Peter Norvig's hard examples
+pub const NORVIG_HARD: &str = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct SudokuDemo;
+
+impl Demo for SudokuDemo {
+    fn name(&self) -> &'static str {
+        "sudoku"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Sudoku by constraint propagation and backtracking, plus a puzzle generator"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Inkala's Puzzle =====")?;
+        let puzzle: Grid = INKALA.parse().expect("valid grid");
+        let solution = solve(&puzzle);
+        for (left, right) in puzzle.to_string().lines().zip(solution.unwrap_or(Grid::empty()).to_string().lines()) {
+            writeln!(out, "{}   {}", left, right)?;
+        }
+
+        writeln!(out, "\n===== Ratings =====")?;
+        for (label, text) in [("classic", CLASSIC), ("inkala", INKALA), ("norvig", NORVIG_HARD)] {
+            let puzzle: Grid = text.parse().expect("valid grid");
+            let (_, guesses) = solve_counting_guesses(&puzzle, None).unwrap_or((Grid::empty(), 0));
+            writeln!(out, "{:<8} {} clues, {:>3} guesses, {:?}, {} solution(s)",
+                          label, puzzle.clues(), guesses, Difficulty::from_guesses(guesses), count_solutions(&puzzle, 2))?;
+        }
+
+        writeln!(out, "\n===== Generated Puzzles =====")?;
+        for (difficulty, seed) in [(Difficulty::Easy, 1), (Difficulty::Medium, 2), (Difficulty::Hard, 3)] {
+            let (puzzle, solution) = generate(difficulty, seed);
+            let rating = rate(&puzzle).map_or("unsolvable".to_string(), |d| format!("{:?}", d));
+            writeln!(out, "{:?} (seed {}): {} clues, rated {}", difficulty, seed, puzzle.clues(), rating)?;
+            writeln!(out, "  {}", puzzle.to_line())?;
+            writeln!(out, "  {}", solution.to_line())?;
+        }
+
+        writeln!(out, "\n===== Broken Input =====")?;
+        writeln!(out, "{:?}", "12345".parse::<Grid>())?;
+        let mut twice = ".".repeat(81);
+        twice.replace_range(0..2, "55");
+        writeln!(out, "two 5s in row 1: {} solutions", count_solutions(&twice.parse().expect("valid grid"), 2))?;
+        writeln!(out, "empty grid: at least {} solutions", count_solutions(&Grid::empty(), 2))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&SudokuDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(puzzle: &str) -> Grid {
+        let puzzle: Grid = puzzle.parse().unwrap();
+        let solution = solve(&puzzle).unwrap();
+        assert!(solution.is_solved(), "{}", solution);
+        assert!(puzzle.is_completed_by(&solution));
+        assert_eq!(count_solutions(&puzzle, 2), 1);
+        solution
+    }
+
+    #[test]
+    fn solves_known_puzzles() {
+        let classic = check(CLASSIC);
+        assert_eq!(classic.to_line(), "534678912672195348198342567859761423426853791713924856961537284287419635345286179");
+        let inkala = check(INKALA);
+        assert_eq!(inkala.to_line(), "812753649943682175675491283154237896369845721287169534521974368438526917796318452");
+        check(NORVIG_HARD);
+    }
+
+    #[test]
+    fn ratings() {
+        assert_eq!(rate(&CLASSIC.parse().unwrap()), Some(Difficulty::Easy));
+        assert_eq!(rate(&INKALA.parse().unwrap()), Some(Difficulty::Hard));
+        assert_eq!(rate(&Grid::empty()), Some(Difficulty::Hard));
+    }
+
+    #[test]
+    fn parse_and_print() {
+        let grid: Grid = CLASSIC.parse().unwrap();
+        assert_eq!(grid.to_line(), CLASSIC);
+        assert_eq!(grid.to_string().parse::<Grid>().unwrap_err(), "unexpected character '+' in grid");
+        let rows: String = CLASSIC.as_bytes().chunks(9).map(|row| format!("{}\n", String::from_utf8_lossy(row))).collect();
+        assert_eq!(rows.parse::<Grid>(), Ok(grid));
+        assert_eq!(grid.get(0, 0), Some(5));
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.clues(), 30);
+        assert!("1".repeat(80).parse::<Grid>().is_err());
+        assert!("1".repeat(82).parse::<Grid>().is_err());
+        assert!("x".repeat(81).parse::<Grid>().is_err());
+    }
+
+    #[test]
+    fn contradictions_have_no_solution() {
+        let mut row = ".".repeat(81);
+        row.replace_range(0..2, "11");
+        assert_eq!(solve(&row.parse().unwrap()), None);
+        // The last cell of row 1 can only be 9, but its column already has one
+        let column = format!("12345678.{}........9{}", ".".repeat(9), ".".repeat(54));
+        assert_eq!(solve(&column.parse().unwrap()), None);
+        assert_eq!(count_solutions(&column.parse().unwrap(), 2), 0);
+        assert_eq!(count_solutions(&Grid::empty(), 3), 3);
+    }
+
+    #[test]
+    fn generated_puzzles_are_unique_and_rated() {
+        for (difficulty, seed) in [(Difficulty::Easy, 11), (Difficulty::Medium, 12), (Difficulty::Hard, 13)] {
+            let (puzzle, solution) = generate(difficulty, seed);
+            assert!(solution.is_solved());
+            assert!(puzzle.is_completed_by(&solution));
+            assert_eq!(count_solutions(&puzzle, 2), 1);
+            assert_eq!(solve(&puzzle), Some(solution));
+            assert_eq!(rate(&puzzle), Some(difficulty), "seed {}", seed);
+        }
+        assert!(generate(Difficulty::Easy, 11).0.clues() >= 36);
+        assert_eq!(generate(Difficulty::Hard, 13), generate(Difficulty::Hard, 13));
+    }
+}
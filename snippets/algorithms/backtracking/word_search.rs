@@ -0,0 +1,424 @@
+//! Word Search and Boggle in Rust
+//!
+//! Both puzzles trace words through a letter grid, moving to a neighbouring cell at each
+//! step and never using a cell twice in one word:
+//! - Word search looks for one word along up/down/left/right steps. A depth-first search
+//!   starts from every cell holding the first letter, and a bitmask of visited cells is
+//!   set on the way down and cleared on the way back up.
+//! - Boggle allows diagonal steps too and asks for every dictionary word in the grid.
+//!   Searching for each word separately repeats the same walks over and over; walking
+//!   the grid once alongside a trie of the dictionary does them all together, and a path
+//!   stops as soon as its letters are not the start of any word.
+//!
+//! The trie lives here too: one node per distinct prefix, stored in a `Vec` and linked by
+//! index.
+
+use demo::Demo;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Trie ==========
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    children: BTreeMap<char, usize>,
+    is_word: bool,
+}
+
+/// A set of words that can also answer "does any word start with this?"
+#[derive(Debug, Clone)]
+pub struct Trie {
+    /// Node 0 is the root, the empty prefix
+    nodes: Vec<TrieNode>,
+    words: usize,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie { nodes: vec![TrieNode::default()], words: 0 }
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns false if the word was already there
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut node = 0;
+        for c in word.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, next);
+                    next
+                }
+            };
+        }
+        let added = !self.nodes[node].is_word;
+        self.nodes[node].is_word = true;
+        self.words += usize::from(added);
+        added
+    }
+
+    fn walk(&self, prefix: &str) -> Option<usize> {
+        prefix.chars().try_fold(0, |node, c| self.child(node, c))
+    }
+
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        self.nodes[node].children.get(&c).copied()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.walk(word).is_some_and(|node| self.nodes[node].is_word)
+    }
+
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.walk(prefix).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+
+    /// Nodes including the root; fewer than the total letter count when words share prefixes
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(words: I) -> Self {
+        let mut trie = Trie::new();
+        for word in words {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+// ========== Grid ==========
+
+/// A rectangle of letters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterGrid {
+    rows: usize,
+    cols: usize,
+    cells: Vec<char>,
+}
+
+/// One bit per grid cell
+struct Visited(Vec<u64>);
+
+impl Visited {
+    fn new(cells: usize) -> Self {
+        Visited(vec![0; cells.div_ceil(64)])
+    }
+
+    fn contains(&self, cell: usize) -> bool {
+        self.0[cell / 64] & (1 << (cell % 64)) != 0
+    }
+
+    fn toggle(&mut self, cell: usize) {
+        self.0[cell / 64] ^= 1 << (cell % 64);
+    }
+}
+
+impl LetterGrid {
+    /// One string per row, all the same length
+    pub fn new(rows: &[&str]) -> Result<Self, String> {
+        let cols = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Vec::with_capacity(rows.len() * cols);
+        for (i, row) in rows.iter().enumerate() {
+            let before = cells.len();
+            cells.extend(row.chars());
+            if cells.len() - before != cols {
+                return Err(format!("row {} has {} letters, expected {}", i, cells.len() - before, cols));
+            }
+        }
+        Ok(LetterGrid { rows: rows.len(), cols, cells })
+    }
+
+    fn neighbours(&self, cell: usize, diagonal: bool) -> impl Iterator<Item = usize> + '_ {
+        let (row, col) = ((cell / self.cols) as isize, (cell % self.cols) as isize);
+        (-1..=1isize)
+            .flat_map(|dr| (-1..=1isize).map(move |dc| (dr, dc)))
+            .filter(move |&(dr, dc)| (dr, dc) != (0, 0) && (diagonal || dr == 0 || dc == 0))
+            .filter_map(move |(dr, dc)| {
+                let (r, c) = (row + dr, col + dc);
+                let inside = (0..self.rows as isize).contains(&r) && (0..self.cols as isize).contains(&c);
+                inside.then(|| r as usize * self.cols + c as usize)
+            })
+    }
+
+    fn position(&self, cell: usize) -> (usize, usize) {
+        (cell / self.cols, cell % self.cols)
+    }
+
+    /// The cells, as (row, column), of one path spelling `word` with horizontal and
+    /// vertical steps, if there is one
+    pub fn find_word(&self, word: &str) -> Option<Vec<(usize, usize)>> {
+        self.find_path(word, false)
+    }
+
+    fn find_path(&self, word: &str, diagonal: bool) -> Option<Vec<(usize, usize)>> {
+        let letters: Vec<char> = word.chars().collect();
+        let mut visited = Visited::new(self.cells.len());
+        if letters.is_empty() {
+            return Some(Vec::new());
+        }
+        let mut path = Vec::with_capacity(letters.len());
+        for start in 0..self.cells.len() {
+            if self.extend_path(start, &letters, diagonal, &mut visited, &mut path) {
+                return Some(path.into_iter().map(|cell| self.position(cell)).collect());
+            }
+        }
+        None
+    }
+
+    fn extend_path(&self, cell: usize, rest: &[char], diagonal: bool, visited: &mut Visited, path: &mut Vec<usize>) -> bool {
+        if self.cells[cell] != rest[0] || visited.contains(cell) {
+            return false;
+        }
+        path.push(cell);
+        if rest.len() == 1 {
+            return true;
+        }
+        visited.toggle(cell);
+        let found = self.neighbours(cell, diagonal).any(|next| self.extend_path(next, &rest[1..], diagonal, visited, path));
+        visited.toggle(cell);
+        if !found {
+            path.pop();
+        }
+        found
+    }
+}
+
+impl fmt::Display for LetterGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.cells.chunks(self.cols.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let letters: Vec<String> = row.iter().map(char::to_string).collect();
+            write!(f, "{}", letters.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+// ========== Boggle ==========
+
+/// Every dictionary word of at least `min_len` letters that can be traced through the
+/// grid with steps in all eight directions, sorted
+pub fn boggle(grid: &LetterGrid, dictionary: &Trie, min_len: usize) -> Vec<String> {
+    struct Walk<'a> {
+        grid: &'a LetterGrid,
+        dictionary: &'a Trie,
+        min_len: usize,
+        visited: Visited,
+        word: String,
+        found: Vec<String>,
+    }
+
+    impl Walk<'_> {
+        fn visit(&mut self, cell: usize, node: usize) {
+            // No dictionary word continues this way, so none of the longer paths can help
+            let Some(node) = self.dictionary.child(node, self.grid.cells[cell]) else {
+                return;
+            };
+            self.word.push(self.grid.cells[cell]);
+            if self.dictionary.nodes[node].is_word && self.word.chars().count() >= self.min_len {
+                self.found.push(self.word.clone());
+            }
+            self.visited.toggle(cell);
+            let grid = self.grid;
+            for next in grid.neighbours(cell, true) {
+                if !self.visited.contains(next) {
+                    self.visit(next, node);
+                }
+            }
+            self.visited.toggle(cell);
+            self.word.pop();
+        }
+    }
+
+    let mut walk = Walk {
+        grid,
+        dictionary,
+        min_len,
+        visited: Visited::new(grid.cells.len()),
+        word: String::new(),
+        found: Vec::new(),
+    };
+    for cell in 0..grid.cells.len() {
+        walk.visit(cell, 0);
+    }
+    walk.found.sort();
+    walk.found.dedup();
+    walk.found
+}
+
+/// Official Boggle points for a word of this length
+pub fn boggle_score(word: &str) -> u32 {
+    match word.chars().count() {
+        0..=2 => 0,
+        3 | 4 => 1,
+        5 => 2,
+        6 => 3,
+        7 => 5,
+        _ => 11,
+    }
+}
+
+// ========== Demo Code ==========
+
+const DICTIONARY: &[&str] = &[
+    "ace", "aces", "age", "ages", "ate", "car", "care", "cares", "cart", "case", "cast", "cat", "cats", "crate",
+    "crates", "east", "eat", "eats", "gas", "gate", "gates", "great", "race", "races", "rat", "rate", "rates",
+    "react", "reacts", "rest", "sat", "scar", "sea", "seat", "stage", "star", "stare", "tar", "tea", "tear",
+    "tears", "trace", "traces", "zebra",
+];
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct WordSearchDemo;
+
+impl Demo for WordSearchDemo {
+    fn name(&self) -> &'static str {
+        "word_search"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Word search by DFS with a visited mask, and a trie-pruned Boggle solver"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Word Search =====")?;
+        let grid = LetterGrid::new(&["abce", "sfcs", "adee"]).expect("rectangular grid");
+        writeln!(out, "{}", grid)?;
+        for word in ["abcced", "see", "abcb", "sfcsee"] {
+            match grid.find_word(word) {
+                Some(path) => writeln!(out, "{:<7} found at {:?}", word, path)?,
+                None => writeln!(out, "{:<7} not found", word)?,
+            }
+        }
+
+        writeln!(out, "\n===== Boggle =====")?;
+        let board = LetterGrid::new(&["cate", "rsag", "etcs", "arae"]).expect("rectangular grid");
+        writeln!(out, "{}", board)?;
+        let dictionary: Trie = DICTIONARY.iter().copied().collect();
+        let letters: usize = DICTIONARY.iter().map(|w| w.len()).sum();
+        writeln!(out, "dictionary: {} words, {} letters, {} trie nodes", dictionary.len(), letters, dictionary.node_count())?;
+        let words = boggle(&board, &dictionary, 3);
+        let score: u32 = words.iter().map(|w| boggle_score(w)).sum();
+        writeln!(out, "{} words, {} points:", words.len(), score)?;
+        for chunk in words.chunks(8) {
+            writeln!(out, "  {}", chunk.join(" "))?;
+        }
+        let missing: Vec<&str> = DICTIONARY.iter().copied().filter(|w| words.binary_search(&w.to_string()).is_err()).collect();
+        writeln!(out, "not on the board: {}", missing.join(" "))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&WordSearchDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> LetterGrid {
+        LetterGrid::new(&["abce", "sfcs", "adee"]).unwrap()
+    }
+
+    #[test]
+    fn trie_basics() {
+        let mut trie: Trie = ["car", "cart", "cat"].into_iter().collect();
+        assert_eq!(trie.len(), 3);
+        assert!(trie.contains("car") && trie.contains("cart"));
+        assert!(!trie.contains("ca"));
+        assert!(trie.starts_with("ca") && trie.starts_with(""));
+        assert!(!trie.starts_with("cb"));
+        assert!(!trie.insert("car"));
+        assert!(trie.insert("ca"));
+        // root, c, a, r, t, t
+        assert_eq!(trie.node_count(), 6);
+        assert!(Trie::new().is_empty());
+    }
+
+    #[test]
+    fn finds_words_along_paths() {
+        let grid = example();
+        assert_eq!(grid.find_word("abcced"), Some(vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1)]));
+        assert!(grid.find_word("see").is_some());
+        // Would need the b cell twice
+        assert_eq!(grid.find_word("abcb"), None);
+        // Diagonal steps are not allowed here
+        assert_eq!(grid.find_word("fe"), None);
+        assert_eq!(grid.find_word(""), Some(vec![]));
+        assert_eq!(grid.find_word("abcesfcsadee".repeat(2).as_str()), None);
+    }
+
+    #[test]
+    fn every_found_path_is_valid() {
+        let grid = example();
+        for word in ["abcced", "see", "sfcsee", "ascb", "adfbcesc"] {
+            if let Some(path) = grid.find_word(word) {
+                let spelled: String = path.iter().map(|&(r, c)| grid.cells[r * grid.cols + c]).collect();
+                assert_eq!(spelled, word);
+                assert!(path.windows(2).all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1));
+                let mut cells = path.clone();
+                cells.sort_unstable();
+                cells.dedup();
+                assert_eq!(cells.len(), path.len());
+            }
+        }
+    }
+
+    #[test]
+    fn boggle_matches_searching_each_word() {
+        let board = LetterGrid::new(&["cate", "rsag", "etcs", "arae"]).unwrap();
+        let dictionary: Trie = DICTIONARY.iter().copied().collect();
+        let found = boggle(&board, &dictionary, 3);
+        let one_by_one: Vec<String> = DICTIONARY
+            .iter()
+            .filter(|w| board.find_path(w, true).is_some())
+            .map(|w| w.to_string())
+            .collect();
+        assert_eq!(found, one_by_one);
+        assert!(found.contains(&"traces".to_string()));
+        assert!(!found.contains(&"zebra".to_string()));
+    }
+
+    #[test]
+    fn boggle_rules() {
+        let board = LetterGrid::new(&["ab", "cd"]).unwrap();
+        let dictionary: Trie = ["ad", "bad", "dab", "abba", "cab", "dcba"].into_iter().collect();
+        // "ad" is too short, "abba" reuses cells; everything else is reachable diagonally
+        assert_eq!(boggle(&board, &dictionary, 3), ["bad", "cab", "dab", "dcba"]);
+        assert_eq!(boggle(&board, &dictionary, 2), ["ad", "bad", "cab", "dab", "dcba"]);
+        assert_eq!(boggle_score("cab"), 1);
+        assert_eq!(boggle_score("crates"), 3);
+        assert_eq!(boggle_score("abcdefghij"), 11);
+    }
+
+    #[test]
+    fn ragged_grids_are_rejected() {
+        assert_eq!(LetterGrid::new(&["abc", "de"]), Err("row 1 has 2 letters, expected 3".to_string()));
+        assert!(LetterGrid::new(&[]).is_ok());
+    }
+}
@@ -0,0 +1,332 @@
+//! Bit Tricks in Rust
+//!
+//! Small identities on machine words that replace loops or branches:
+//! - Counting set bits three ways: clearing the lowest set bit until none are left
+//!   (Kernighan), adding neighbouring bit fields in parallel (SWAR), and the hardware
+//!   instruction behind `count_ones`.
+//! - `x & -x` keeps only the lowest set bit, and `x & (x - 1)` clears it. A power of two
+//!   is a non-zero word that the second one turns into zero.
+//! - Swapping through XOR, reversing bits by swapping ever larger halves, and Gosper's
+//!   hack for the next larger word with the same number of set bits.
+//! - XOR puzzles: since `a ^ a == 0`, XOR-ing a list cancels every pair, which finds the
+//!   one value without a partner, and with a split on one differing bit, two of them.
+//!
+//! Everything works on `u64`; the standard library's versions are the reference in the
+//! tests.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Counting Bits ==========
+
+/// Loops once per set bit, clearing the lowest each time
+pub fn popcount_kernighan(mut x: u64) -> u32 {
+    let mut count = 0;
+    while x != 0 {
+        x &= x - 1;
+        count += 1;
+    }
+    count
+}
+
+/// Sums bits in pairs, then nibbles, then bytes, all in parallel inside the word
+pub fn popcount_swar(x: u64) -> u32 {
+    let pairs = x - ((x >> 1) & 0x5555_5555_5555_5555);
+    let nibbles = (pairs & 0x3333_3333_3333_3333) + ((pairs >> 2) & 0x3333_3333_3333_3333);
+    let bytes = (nibbles + (nibbles >> 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+    // Multiplying by 0x0101... adds every byte into the top one
+    (bytes.wrapping_mul(0x0101_0101_0101_0101) >> 56) as u32
+}
+
+/// Looks the count up one byte at a time
+pub fn popcount_table(x: u64) -> u32 {
+    const TABLE: [u8; 256] = {
+        let mut table = [0u8; 256];
+        let mut i = 1;
+        while i < 256 {
+            table[i] = table[i / 2] + (i & 1) as u8;
+            i += 1;
+        }
+        table
+    };
+    x.to_le_bytes().iter().map(|&b| u32::from(TABLE[b as usize])).sum()
+}
+
+// ========== Lowest Set Bit ==========
+
+/// The lowest set bit alone, or 0 for 0
+pub fn lowest_set_bit(x: u64) -> u64 {
+    x & x.wrapping_neg()
+}
+
+/// `x` with its lowest set bit cleared
+pub fn clear_lowest_set_bit(x: u64) -> u64 {
+    x & x.wrapping_sub(1)
+}
+
+pub fn is_power_of_two(x: u64) -> bool {
+    x != 0 && clear_lowest_set_bit(x) == 0
+}
+
+/// Smallest power of two at or above `x`, or `None` past `2^63`; 1 for 0
+pub fn next_power_of_two(x: u64) -> Option<u64> {
+    if x <= 1 {
+        return Some(1);
+    }
+    // Smear the highest set bit of x - 1 into every bit below it, then add one
+    let mut v = x - 1;
+    for shift in [1, 2, 4, 8, 16, 32] {
+        v |= v >> shift;
+    }
+    v.checked_add(1)
+}
+
+// ========== Rearranging Bits ==========
+
+/// Swaps two values through XOR, without a temporary; `std::mem::swap` is what real code
+/// should use
+#[allow(clippy::manual_swap)]
+pub fn xor_swap(a: &mut u64, b: &mut u64) {
+    // Two `&mut` can never alias in Rust, which is the case that breaks this in C
+    *a ^= *b;
+    *b ^= *a;
+    *a ^= *b;
+}
+
+/// Bit `i` moves to bit `63 - i`, by swapping halves, then quarters, down to single bits
+pub fn reverse_bits(mut x: u64) -> u64 {
+    x = x.rotate_left(32);
+    x = ((x >> 16) & 0x0000_ffff_0000_ffff) | ((x & 0x0000_ffff_0000_ffff) << 16);
+    x = ((x >> 8) & 0x00ff_00ff_00ff_00ff) | ((x & 0x00ff_00ff_00ff_00ff) << 8);
+    x = ((x >> 4) & 0x0f0f_0f0f_0f0f_0f0f) | ((x & 0x0f0f_0f0f_0f0f_0f0f) << 4);
+    x = ((x >> 2) & 0x3333_3333_3333_3333) | ((x & 0x3333_3333_3333_3333) << 2);
+    ((x >> 1) & 0x5555_5555_5555_5555) | ((x & 0x5555_5555_5555_5555) << 1)
+}
+
+/// Next larger word with as many set bits (Gosper's hack), or `None` if there is none
+pub fn next_bit_permutation(x: u64) -> Option<u64> {
+    if x == 0 {
+        return None;
+    }
+    let low = lowest_set_bit(x);
+    // Adding the lowest bit carries through the lowest run of ones, moving it up by one
+    let ripple = x.checked_add(low)?;
+    // The ones the carry swallowed, less one, go back to the bottom
+    let ones = ((x ^ ripple) >> 2) / low;
+    Some(ripple | ones)
+}
+
+/// Every `width`-bit word with `k` set bits, in increasing order
+pub fn words_with_k_bits(width: u32, k: u32) -> Vec<u64> {
+    assert!(width <= 63, "width {} leaves no room for the carry", width);
+    if k > width {
+        return Vec::new();
+    }
+    let limit = 1u64 << width;
+    let first = (1u64 << k) - 1;
+    std::iter::successors(Some(first), |&x| next_bit_permutation(x).filter(|&next| next < limit)).collect()
+}
+
+// ========== XOR Puzzles ==========
+
+/// The one value that appears an odd number of times, when every other appears twice
+pub fn single_number(values: &[u64]) -> u64 {
+    values.iter().fold(0, |acc, &v| acc ^ v)
+}
+
+/// The two values without a partner, smaller first, when every other appears twice
+pub fn two_single_numbers(values: &[u64]) -> (u64, u64) {
+    let both = single_number(values);
+    // The two differ in this bit, so it splits them into different halves of the list
+    let split = lowest_set_bit(both);
+    let one = values.iter().filter(|&&v| v & split != 0).fold(0, |acc, &v| acc ^ v);
+    let other = both ^ one;
+    (one.min(other), one.max(other))
+}
+
+/// The value that appears once when every other appears three times, by counting each
+/// bit position modulo 3 in two words
+pub fn single_among_triples(values: &[u64]) -> u64 {
+    // A bit is in `ones` after 1 (mod 3) sightings and in `twos` after 2
+    let (mut ones, mut twos) = (0u64, 0u64);
+    for &v in values {
+        ones = (ones ^ v) & !twos;
+        twos = (twos ^ v) & !ones;
+    }
+    ones
+}
+
+/// The number missing from `0..=n`, given the other `n`
+pub fn missing_number(values: &[u64]) -> u64 {
+    let n = values.len() as u64;
+    (0..=n).fold(0, |acc, v| acc ^ v) ^ single_number(values)
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct BitTricksDemo;
+
+impl Demo for BitTricksDemo {
+    fn name(&self) -> &'static str {
+        "bit_tricks"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Popcount, lowest set bit, bit reversal, Gosper's hack and XOR puzzles"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Counting Set Bits =====")?;
+        writeln!(out, "{:>18} {:>9} {:>5} {:>6} {:>10}", "x", "kernighan", "swar", "table", "count_ones")?;
+        for x in [0u64, 1, 0b1011_0110, 0xdead_beef, u64::MAX, 0x8000_0000_0000_0001] {
+            writeln!(
+                out,
+                "{:>#18x} {:>9} {:>5} {:>6} {:>10}",
+                x,
+                popcount_kernighan(x),
+                popcount_swar(x),
+                popcount_table(x),
+                x.count_ones()
+            )?;
+        }
+
+        writeln!(out, "\n===== Lowest Set Bit and Powers of Two =====")?;
+        for x in [0u64, 1, 12, 40, 64, 96, 1000] {
+            let next = next_power_of_two(x).map_or("-".to_string(), |p| p.to_string());
+            writeln!(
+                out,
+                "{:>5} = {:>#12b}  lowest {:>3}  cleared {:>4}  power of two {:<5}  next {}",
+                x,
+                x,
+                lowest_set_bit(x),
+                clear_lowest_set_bit(x),
+                is_power_of_two(x),
+                next
+            )?;
+        }
+
+        writeln!(out, "\n===== Swapping and Reversing =====")?;
+        let (mut a, mut b) = (17u64, 42u64);
+        xor_swap(&mut a, &mut b);
+        writeln!(out, "xor_swap(17, 42) -> ({}, {})", a, b)?;
+        for x in [1u64, 0b1101, 0x0123_4567_89ab_cdef] {
+            writeln!(out, "reverse {:#018x} -> {:#018x}", x, reverse_bits(x))?;
+        }
+
+        writeln!(out, "\n===== Gosper's Hack =====")?;
+        let words: Vec<String> = words_with_k_bits(5, 3).iter().map(|w| format!("{:05b}", w)).collect();
+        writeln!(out, "5-bit words with 3 bits set ({}):", words.len())?;
+        writeln!(out, "{}", words.join(" "))?;
+
+        writeln!(out, "\n===== XOR Puzzles =====")?;
+        let pairs_and_one = [4, 1, 2, 1, 2, 9, 4];
+        writeln!(out, "{:?}: single {}", pairs_and_one, single_number(&pairs_and_one))?;
+        let pairs_and_two = [1, 2, 1, 3, 2, 5];
+        writeln!(out, "{:?}: singles {:?}", pairs_and_two, two_single_numbers(&pairs_and_two))?;
+        let triples = [0, 1, 0, 1, 0, 1, 99];
+        writeln!(out, "{:?}: single among triples {}", triples, single_among_triples(&triples))?;
+        let missing = [3, 0, 1, 5, 2];
+        writeln!(out, "{:?}: missing {}", missing, missing_number(&missing))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&BitTricksDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    fn samples() -> Vec<u64> {
+        let mut rng = Pcg32::new(982, 1);
+        let mut values = vec![0, 1, 2, 3, u64::MAX, u64::MAX - 1, 1 << 63, (1 << 63) + 1];
+        values.extend((0..500).map(|_| rng.next_u64() >> rng.below(64)));
+        values
+    }
+
+    #[test]
+    fn popcounts_agree_with_count_ones() {
+        for x in samples() {
+            assert_eq!(popcount_kernighan(x), x.count_ones(), "{:#x}", x);
+            assert_eq!(popcount_swar(x), x.count_ones(), "{:#x}", x);
+            assert_eq!(popcount_table(x), x.count_ones(), "{:#x}", x);
+        }
+    }
+
+    #[test]
+    fn lowest_bit_and_powers_of_two() {
+        for x in samples() {
+            let expected = if x == 0 { 0 } else { 1 << x.trailing_zeros() };
+            assert_eq!(lowest_set_bit(x), expected);
+            assert_eq!(clear_lowest_set_bit(x), x - expected);
+            assert_eq!(is_power_of_two(x), x.is_power_of_two());
+            assert_eq!(next_power_of_two(x), x.checked_next_power_of_two(), "{:#x}", x);
+        }
+    }
+
+    #[test]
+    fn swap_and_reverse() {
+        let (mut a, mut b) = (5, u64::MAX);
+        xor_swap(&mut a, &mut b);
+        assert_eq!((a, b), (u64::MAX, 5));
+        for x in samples() {
+            assert_eq!(reverse_bits(x), x.reverse_bits(), "{:#x}", x);
+            assert_eq!(reverse_bits(reverse_bits(x)), x);
+        }
+    }
+
+    #[test]
+    fn gosper_visits_every_word_with_k_bits() {
+        for width in 0..=10 {
+            for k in 0..=width + 1 {
+                let expected: Vec<u64> = (0..1u64 << width).filter(|w| w.count_ones() == k).collect();
+                let found = words_with_k_bits(width, k);
+                assert_eq!(found, expected, "width {} k {}", width, k);
+            }
+        }
+        assert_eq!(next_bit_permutation(0b0111), Some(0b1011));
+        assert_eq!(next_bit_permutation(0b1100), Some(0b1_0001));
+        assert_eq!(next_bit_permutation(u64::MAX << 1), None);
+        assert_eq!(next_bit_permutation(0), None);
+    }
+
+    #[test]
+    fn xor_puzzles() {
+        let mut rng = Pcg32::new(982, 2);
+        for _ in 0..50 {
+            let mut values: Vec<u64> = (0..20).map(|_| rng.below(1000)).collect();
+            values.sort_unstable();
+            values.dedup();
+            let (a, b, c) = (values[0], values[1], values[2]);
+            let paired: Vec<u64> = values[3..].iter().flat_map(|&v| [v, v]).collect();
+            let tripled: Vec<u64> = values[3..].iter().flat_map(|&v| [v, v, v]).collect();
+
+            let mut one = paired.clone();
+            one.push(a);
+            rng.shuffle(&mut one);
+            assert_eq!(single_number(&one), a);
+
+            let mut two = paired.clone();
+            two.extend([b, c]);
+            rng.shuffle(&mut two);
+            assert_eq!(two_single_numbers(&two), (b, c));
+
+            let mut three = tripled;
+            three.push(a);
+            rng.shuffle(&mut three);
+            assert_eq!(single_among_triples(&three), a);
+        }
+        let mut numbers: Vec<u64> = (0..=40).collect();
+        numbers.remove(17);
+        assert_eq!(missing_number(&numbers), 17);
+        assert_eq!(missing_number(&[]), 0);
+    }
+}
@@ -0,0 +1,430 @@
+//! Bit Sets in Rust
+//!
+//! A set of small non-negative integers stored as one bit each in a `Vec<u64>`: 64 times
+//! smaller than a `Vec<bool>`, and whole-set operations work a word at a time.
+//! - Setting a bit past the end grows the set; `shrink_to_fit` gives back the words
+//!   after the highest set bit.
+//! - Union, intersection, difference and symmetric difference are one word operation
+//!   per 64 members, in place or through the `|`, `&`, `-` and `^` operators.
+//! - Rank (how many members are below `i`) and select (where the k-th member is) use
+//!   `count_ones` per word, then finish inside one word.
+//! - Iteration jumps from member to member with `trailing_zeros`, so empty stretches
+//!   cost one step per word rather than one per bit.
+//!
+//! The prime sieve keeps its composite marks in one, and graph traversal keeps its
+//! visited vertices in one.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+const WORD_BITS: usize = 64;
+
+// ========== BitSet ==========
+
+/// A growable set of `usize` values, one bit per possible value
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+fn split(bit: usize) -> (usize, u64) {
+    (bit / WORD_BITS, 1 << (bit % WORD_BITS))
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty set with room for `0..bits` before it has to grow
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet { words: vec![0; bits.div_ceil(WORD_BITS)] }
+    }
+
+    /// Adds `bit`, growing if needed; returns false if it was already there
+    pub fn set(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        self.grow(bit + 1);
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    /// Removes `bit`; returns false if it was not there
+    pub fn clear(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        match self.words.get_mut(word) {
+            Some(w) if *w & mask != 0 => {
+                *w &= !mask;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Toggles `bit`, growing if needed; returns whether it is now set
+    pub fn flip(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        self.grow(bit + 1);
+        self.words[word] ^= mask;
+        self.words[word] & mask != 0
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        self.words.get(word).is_some_and(|w| w & mask != 0)
+    }
+
+    /// Removes every member, keeping the storage
+    pub fn reset(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Number of members
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Bits that fit without growing
+    pub fn capacity(&self) -> usize {
+        self.words.len() * WORD_BITS
+    }
+
+    /// Bytes used by the words
+    pub fn memory_bytes(&self) -> usize {
+        self.words.len() * 8
+    }
+
+    /// Makes room for `0..bits`
+    pub fn grow(&mut self, bits: usize) {
+        let words = bits.div_ceil(WORD_BITS);
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+    }
+
+    /// Drops the words after the highest member
+    pub fn shrink_to_fit(&mut self) {
+        let used = self.words.iter().rposition(|&w| w != 0).map_or(0, |last| last + 1);
+        self.words.truncate(used);
+        self.words.shrink_to_fit();
+    }
+
+    /// Members below `bit`
+    pub fn rank(&self, bit: usize) -> usize {
+        let (word, _) = split(bit);
+        let whole: usize = self.words.iter().take(word).map(|w| w.count_ones() as usize).sum();
+        let partial = self.words.get(word).map_or(0, |w| (w & ((1 << (bit % WORD_BITS)) - 1)).count_ones());
+        whole + partial as usize
+    }
+
+    /// The member with `k` members below it, if there are that many
+    pub fn select(&self, mut k: usize) -> Option<usize> {
+        for (index, &w) in self.words.iter().enumerate() {
+            let ones = w.count_ones() as usize;
+            if k < ones {
+                // Drop the k lowest members of this word, then take the next one
+                let mut rest = w;
+                for _ in 0..k {
+                    rest &= rest - 1;
+                }
+                return Some(index * WORD_BITS + rest.trailing_zeros() as usize);
+            }
+            k -= ones;
+        }
+        None
+    }
+
+    /// Smallest member, if any
+    pub fn min(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    /// Largest member, if any
+    pub fn max(&self) -> Option<usize> {
+        let index = self.words.iter().rposition(|&w| w != 0)?;
+        Some(index * WORD_BITS + (WORD_BITS - 1 - self.words[index].leading_zeros() as usize))
+    }
+
+    /// Members in increasing order
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { words: &self.words, index: 0, current: self.words.first().copied().unwrap_or(0) }
+    }
+
+    fn combine_with(&mut self, other: &BitSet, op: impl Fn(u64, u64) -> u64) {
+        self.grow(other.capacity());
+        for (i, w) in self.words.iter_mut().enumerate() {
+            *w = op(*w, other.words.get(i).copied().unwrap_or(0));
+        }
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a | b);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a & b);
+    }
+
+    pub fn difference_with(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a & !b);
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a ^ b);
+    }
+
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.words.iter().enumerate().all(|(i, &w)| w & !other.words.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+}
+
+/// Members of a `BitSet` in increasing order
+pub struct Iter<'a> {
+    words: &'a [u64],
+    index: usize,
+    /// The members of `words[index]` not yet returned
+    current: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.index * WORD_BITS + bit)
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for bit in iter {
+            self.set(bit);
+        }
+    }
+}
+
+/// Equal when the members are; spare capacity does not count
+impl PartialEq for BitSet {
+    fn eq(&self, other: &BitSet) -> bool {
+        let longest = self.words.len().max(other.words.len());
+        (0..longest).all(|i| self.words.get(i).copied().unwrap_or(0) == other.words.get(i).copied().unwrap_or(0))
+    }
+}
+
+impl Eq for BitSet {}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+macro_rules! set_operator {
+    ($trait:ident, $method:ident, $in_place:ident) => {
+        impl $trait for &BitSet {
+            type Output = BitSet;
+
+            fn $method(self, other: &BitSet) -> BitSet {
+                let mut result = self.clone();
+                result.$in_place(other);
+                result
+            }
+        }
+    };
+}
+
+set_operator!(BitOr, bitor, union_with);
+set_operator!(BitAnd, bitand, intersect_with);
+set_operator!(Sub, sub, difference_with);
+set_operator!(BitXor, bitxor, symmetric_difference_with);
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct BitSetDemo;
+
+impl Demo for BitSetDemo {
+    fn name(&self) -> &'static str {
+        "bitset"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Growable u64-backed bit set with rank, select and set operators"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Building a Set =====")?;
+        let mut set: BitSet = (0..20).step_by(2).collect();
+        writeln!(out, "evens below 20: {:?}", set)?;
+        writeln!(out, "set 100 -> {}, set 100 again -> {}", set.set(100), set.set(100))?;
+        writeln!(out, "capacity after setting 100: {} bits", set.capacity())?;
+        writeln!(out, "clear 100 -> {}, flip 3 -> {}", set.clear(100), set.flip(3))?;
+        set.shrink_to_fit();
+        writeln!(out, "after shrink_to_fit: {:?}, capacity {} bits", set, set.capacity())?;
+
+        writeln!(out, "\n===== Set Operators =====")?;
+        let multiples_of_3: BitSet = (0..20).step_by(3).collect();
+        let evens: BitSet = (0..20).step_by(2).collect();
+        writeln!(out, "a = {:?}", evens)?;
+        writeln!(out, "b = {:?}", multiples_of_3)?;
+        writeln!(out, "a | b = {:?}", &evens | &multiples_of_3)?;
+        writeln!(out, "a & b = {:?}", &evens & &multiples_of_3)?;
+        writeln!(out, "a - b = {:?}", &evens - &multiples_of_3)?;
+        writeln!(out, "a ^ b = {:?}", &evens ^ &multiples_of_3)?;
+
+        writeln!(out, "\n===== Rank and Select =====")?;
+        let primes: BitSet = crate::primes::primes_up_to(200).into_iter().map(|p| p as usize).collect();
+        let largest = primes.max().map_or("none".to_string(), |p| p.to_string());
+        writeln!(out, "{} primes up to 200, largest {}", primes.count(), largest)?;
+        for n in [10, 100, 200] {
+            writeln!(out, "rank({}) = {} primes below {}", n, primes.rank(n), n)?;
+        }
+        for k in [0, 9, 24, 46] {
+            let found = primes.select(k).map_or("none".to_string(), |p| p.to_string());
+            writeln!(out, "select({}) = {}", k, found)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&BitSetDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+    use std::collections::{BTreeSet, HashSet};
+
+    fn sorted(set: &HashSet<usize>) -> Vec<usize> {
+        set.iter().copied().collect::<BTreeSet<_>>().into_iter().collect()
+    }
+
+    #[test]
+    fn random_operations_match_hash_set() {
+        let mut rng = Pcg32::new(983, 1);
+        for _ in 0..20 {
+            let mut bits = BitSet::new();
+            let mut reference = HashSet::new();
+            for _ in 0..400 {
+                let bit = rng.below(300) as usize;
+                match rng.below(4) {
+                    0 | 1 => assert_eq!(bits.set(bit), reference.insert(bit)),
+                    2 => assert_eq!(bits.clear(bit), reference.remove(&bit)),
+                    _ => {
+                        let now_set = reference.insert(bit) || !reference.remove(&bit);
+                        assert_eq!(bits.flip(bit), now_set);
+                    }
+                }
+                let probe = rng.below(350) as usize;
+                assert_eq!(bits.contains(probe), reference.contains(&probe));
+            }
+            let members = sorted(&reference);
+            assert_eq!(bits.iter().collect::<Vec<_>>(), members);
+            assert_eq!(bits.count(), reference.len());
+            assert_eq!(bits.is_empty(), reference.is_empty());
+            assert_eq!(bits.min(), members.first().copied());
+            assert_eq!(bits.max(), members.last().copied());
+        }
+    }
+
+    #[test]
+    fn rank_and_select_invert_each_other() {
+        let mut rng = Pcg32::new(983, 2);
+        let set: BitSet = (0..300).map(|_| rng.below(1000) as usize).collect();
+        let members: Vec<usize> = set.iter().collect();
+        for (k, &member) in members.iter().enumerate() {
+            assert_eq!(set.select(k), Some(member));
+            assert_eq!(set.rank(member), k);
+        }
+        assert_eq!(set.select(members.len()), None);
+        for bit in 0..1100 {
+            assert_eq!(set.rank(bit), members.iter().filter(|&&m| m < bit).count(), "rank({})", bit);
+        }
+    }
+
+    #[test]
+    fn set_operations_match_hash_set() {
+        let mut rng = Pcg32::new(983, 3);
+        for _ in 0..50 {
+            let a: HashSet<usize> = (0..rng.below(60)).map(|_| rng.below(200) as usize).collect();
+            let b: HashSet<usize> = (0..rng.below(60)).map(|_| rng.below(130) as usize).collect();
+            let (x, y): (BitSet, BitSet) = (a.iter().copied().collect(), b.iter().copied().collect());
+            assert_eq!((&x | &y).iter().collect::<Vec<_>>(), sorted(&(&a | &b)));
+            assert_eq!((&x & &y).iter().collect::<Vec<_>>(), sorted(&(&a & &b)));
+            assert_eq!((&x - &y).iter().collect::<Vec<_>>(), sorted(&(&a - &b)));
+            assert_eq!((&x ^ &y).iter().collect::<Vec<_>>(), sorted(&(&a ^ &b)));
+            assert_eq!(x.is_subset(&y), a.is_subset(&b));
+            assert_eq!(x.is_disjoint(&y), a.is_disjoint(&b));
+        }
+    }
+
+    #[test]
+    fn growing_and_shrinking() {
+        let mut set = BitSet::with_capacity(10);
+        assert_eq!(set.capacity(), 64);
+        set.set(1000);
+        assert_eq!(set.capacity(), 1024);
+        set.set(3);
+        set.clear(1000);
+        set.shrink_to_fit();
+        assert_eq!(set.capacity(), 64);
+        assert_eq!(set, [3].into_iter().collect());
+        set.reset();
+        assert!(set.is_empty());
+        set.shrink_to_fit();
+        assert_eq!(set.capacity(), 0);
+        assert_eq!(set.iter().next(), None);
+        assert_eq!((set.min(), set.max(), set.rank(50), set.select(0)), (None, None, 0, None));
+        assert!(!set.clear(5));
+    }
+
+    #[test]
+    fn equality_ignores_capacity() {
+        let small: BitSet = [1, 2, 3].into_iter().collect();
+        let mut big = BitSet::with_capacity(5000);
+        big.extend([3, 2, 1]);
+        assert_eq!(small, big);
+        big.set(4000);
+        assert_ne!(small, big);
+        assert_eq!(format!("{:?}", small), "{1, 2, 3}");
+    }
+}
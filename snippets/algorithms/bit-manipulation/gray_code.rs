@@ -0,0 +1,238 @@
+//! Gray Codes and Submask Enumeration in Rust
+//!
+//! Two ways of walking through sets of bits:
+//! - The reflected binary Gray code lists all `n`-bit words so that neighbours differ in
+//!   exactly one bit, including the last and the first. The `i`-th word is `i ^ (i >> 1)`,
+//!   and going back is a prefix XOR of the bits from the top down. Step `i` flips bit
+//!   `trailing_zeros(i)`, which lets a loop over all subsets update a running total with
+//!   one add or remove per step instead of rebuilding it.
+//! - The submasks of a mask, largest first: `(s - 1) & mask` clears the lowest set bit of
+//!   `s` that is in the mask and sets every mask bit below it, which is exactly the next
+//!   smaller submask. Looping over the submasks of every mask costs 3^n steps in total,
+//!   the basis of subset-sum style dynamic programming.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+/// Widest Gray code `gray_code` will build; its length is `2^width`
+pub const MAX_WIDTH: u32 = 63;
+
+// ========== Gray Code ==========
+
+/// The Gray code word at position `i` of the sequence
+pub fn to_gray(i: u64) -> u64 {
+    i ^ (i >> 1)
+}
+
+/// The position of Gray code word `g`, undoing `to_gray`
+pub fn from_gray(g: u64) -> u64 {
+    // Each bit becomes the XOR of itself and every bit above it, spread in doubling steps
+    let mut i = g;
+    for shift in [1, 2, 4, 8, 16, 32] {
+        i ^= i >> shift;
+    }
+    i
+}
+
+/// All `width`-bit words in Gray code order, starting from 0
+pub fn gray_code(width: u32) -> Vec<u64> {
+    assert!(width <= MAX_WIDTH, "a {}-bit Gray code is too long to list", width);
+    (0..1u64 << width).map(to_gray).collect()
+}
+
+/// The same sequence built by reflection: the `width - 1` code, then the same code in
+/// reverse with the new top bit set
+pub fn gray_code_reflected(width: u32) -> Vec<u64> {
+    assert!(width <= MAX_WIDTH, "a {}-bit Gray code is too long to list", width);
+    let mut code = vec![0u64];
+    for bit in 0..width {
+        let top = 1u64 << bit;
+        let mirrored: Vec<u64> = code.iter().rev().map(|&w| w | top).collect();
+        code.extend(mirrored);
+    }
+    code
+}
+
+/// Which bit changes on the way from position `i - 1` to position `i`
+pub fn flipped_bit(i: u64) -> u32 {
+    assert!(i > 0, "position 0 has no step before it");
+    i.trailing_zeros()
+}
+
+/// Every subset sum of `values`, in Gray code order of the subsets: one addition or
+/// subtraction per subset
+pub fn subset_sums_gray(values: &[i64]) -> Vec<i64> {
+    let width = u32::try_from(values.len()).expect("value count fits in u32");
+    assert!(width <= MAX_WIDTH, "too many values to list every subset");
+    let mut sums = Vec::with_capacity(1 << width);
+    let (mut sum, mut current) = (0i64, 0u64);
+    sums.push(sum);
+    for i in 1..1u64 << width {
+        let bit = flipped_bit(i);
+        current ^= 1 << bit;
+        if current >> bit & 1 == 1 {
+            sum += values[bit as usize];
+        } else {
+            sum -= values[bit as usize];
+        }
+        sums.push(sum);
+    }
+    sums
+}
+
+// ========== Submasks ==========
+
+/// The submasks of a mask, from the mask itself down to 0
+#[derive(Debug, Clone)]
+pub struct Submasks {
+    mask: u64,
+    next: Option<u64>,
+}
+
+impl Iterator for Submasks {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        self.next = (current != 0).then(|| (current - 1) & self.mask);
+        Some(current)
+    }
+}
+
+/// Every submask of `mask`, in decreasing order, ending with 0
+pub fn submasks(mask: u64) -> Submasks {
+    Submasks { mask, next: Some(mask) }
+}
+
+/// For every mask of `width` bits, the sum of `values[s]` over its submasks `s`, by the
+/// direct 3^n loop over submasks
+pub fn sum_over_submasks(values: &[i64]) -> Vec<i64> {
+    assert!(values.len().is_power_of_two(), "one value per mask of some width");
+    (0..values.len() as u64).map(|mask| submasks(mask).map(|s| values[s as usize]).sum()).collect()
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct GrayCodeDemo;
+
+impl Demo for GrayCodeDemo {
+    fn name(&self) -> &'static str {
+        "gray_code"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Gray codes, binary/Gray conversion and submask enumeration"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== 4-bit Gray Code =====")?;
+        writeln!(out, " i binary gray  back flipped")?;
+        for (i, g) in gray_code(4).into_iter().enumerate() {
+            let i = i as u64;
+            let flipped = if i == 0 { "-".to_string() } else { format!("bit {}", flipped_bit(i)) };
+            writeln!(out, "{:>2}  {:04b}  {:04b} {:>4}  {}", i, i, g, from_gray(g), flipped)?;
+        }
+
+        writeln!(out, "\n===== Subset Sums One Step at a Time =====")?;
+        let values = [3, 5, 9];
+        let sums = subset_sums_gray(&values);
+        let subsets: Vec<String> = gray_code(3)
+            .iter()
+            .zip(&sums)
+            .map(|(&set, sum)| {
+                let members: Vec<String> = (0..3).filter(|b| set >> b & 1 == 1).map(|b| values[b].to_string()).collect();
+                format!("{{{}}}={}", members.join(","), sum)
+            })
+            .collect();
+        writeln!(out, "values {:?}: {}", values, subsets.join(" "))?;
+
+        writeln!(out, "\n===== Submasks of 0b1011 =====")?;
+        let all: Vec<String> = submasks(0b1011).map(|s| format!("{:04b}", s)).collect();
+        writeln!(out, "{}", all.join(" "))?;
+        for width in [4u32, 8, 12] {
+            let steps: u64 = (0..1u64 << width).map(|mask| submasks(mask).count() as u64).sum();
+            writeln!(out, "submasks of every {:>2}-bit mask: {:>6} = 3^{}", width, steps, width)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&GrayCodeDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbours_differ_in_one_bit_including_wraparound() {
+        for width in 1..=12 {
+            let code = gray_code(width);
+            for (i, pair) in code.windows(2).enumerate() {
+                assert_eq!((pair[0] ^ pair[1]).count_ones(), 1, "width {} step {}", width, i + 1);
+                assert_eq!(pair[0] ^ pair[1], 1 << flipped_bit(i as u64 + 1));
+            }
+            assert_eq!((code[0] ^ code[code.len() - 1]).count_ones(), 1, "width {} wraparound", width);
+        }
+    }
+
+    #[test]
+    fn every_word_appears_once() {
+        for width in 0..=12 {
+            let mut code = gray_code(width);
+            assert_eq!(code, gray_code_reflected(width));
+            code.sort_unstable();
+            assert!(code.iter().copied().eq(0..1u64 << width), "width {}", width);
+        }
+    }
+
+    #[test]
+    fn conversion_round_trips() {
+        for i in (0..5000).chain([u64::MAX, u64::MAX - 1, 1 << 63, 0x0123_4567_89ab_cdef]) {
+            assert_eq!(from_gray(to_gray(i)), i, "{:#x}", i);
+            assert_eq!(to_gray(from_gray(i)), i, "{:#x}", i);
+        }
+        assert_eq!(to_gray(0b1011), 0b1110);
+    }
+
+    #[test]
+    fn gray_subset_sums_match_direct_sums() {
+        let values = [4, -7, 10, 1, 25, -3];
+        let sums = subset_sums_gray(&values);
+        for (i, &sum) in sums.iter().enumerate() {
+            let set = to_gray(i as u64);
+            let direct: i64 = (0..values.len()).filter(|b| set >> b & 1 == 1).map(|b| values[b]).sum();
+            assert_eq!(sum, direct, "subset {:06b}", set);
+        }
+        assert_eq!(subset_sums_gray(&[]), [0]);
+    }
+
+    #[test]
+    fn submasks_are_complete_and_decreasing() {
+        for mask in (0..1u64 << 10).chain([u64::MAX >> 52, 1 << 63, (1 << 63) | 5]) {
+            let found: Vec<u64> = submasks(mask).collect();
+            assert!(found.windows(2).all(|w| w[0] > w[1]), "{:#b}", mask);
+            assert!(found.iter().all(|&s| s & !mask == 0));
+            assert_eq!(found.len(), 1 << mask.count_ones());
+            assert_eq!((found[0], *found.last().unwrap()), (mask, 0));
+        }
+        let brute: Vec<u64> = (0..=0b1011).rev().filter(|s| s & !0b1011 == 0).collect();
+        assert_eq!(submasks(0b1011).collect::<Vec<_>>(), brute);
+    }
+
+    #[test]
+    fn sum_over_submasks_matches_brute_force() {
+        let values: Vec<i64> = (0..64).map(|i| (i * 37 % 11) - 5).collect();
+        let sums = sum_over_submasks(&values);
+        for (mask, &sum) in sums.iter().enumerate() {
+            let brute: i64 = (0..64).filter(|s| s & !mask == 0).map(|s| values[s]).sum();
+            assert_eq!(sum, brute, "mask {:06b}", mask);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Regenerates `include/tech_notes.h` from `ffi.rs` when the `ffi` feature is on.
+//!
+//! The header is committed too, so C code can use it without building with cbindgen.
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        let config = cbindgen::Config::from_file("cbindgen.toml").expect("cbindgen.toml should parse");
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src("ffi.rs")
+            .generate()
+            .expect("ffi.rs should be parseable by cbindgen")
+            .write_to_file("include/tech_notes.h");
+    }
+}
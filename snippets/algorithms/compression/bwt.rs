@@ -0,0 +1,334 @@
+//! Burrows-Wheeler Transform in Rust
+//!
+//! The BWT compresses nothing by itself. It permutes the input so that bytes with
+//! similar following context end up next to each other, which turns "the" appearing
+//! all over a text into a run of `t`s before a run of `h`s; the stages after it then
+//! have an easy job. This is the pipeline bzip2 is built on:
+//! - BWT: sort all suffixes (a suffix array, built here by prefix doubling) and output
+//!   the byte before each one. With an end marker that sorts first, the last column is
+//!   enough to rebuild the input: each row's predecessor is found by counting, the
+//!   LF mapping.
+//! - Move-to-front: replace each byte with its position in a recently-used list, so
+//!   runs of one byte become runs of zeros and a few bytes in rotation become small
+//!   numbers.
+//! - RLE collapses the zero runs and Huffman coding gives the small numbers short codes,
+//!   using the `rle` and `huffman` modules as they are.
+//!
+//! The mini compressor here treats the whole input as one block, where bzip2 cuts it
+//! into blocks of up to 900 kB.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Suffix Array ==========
+
+/// Start positions of the suffixes of `data` in sorted order, where a suffix that is a
+/// prefix of another sorts first. Prefix doubling: sort by the first `k` bytes, then use
+/// those ranks in pairs to sort by the first `2k`, in O(n log² n)
+pub fn suffix_array(data: &[u8]) -> Vec<usize> {
+    let n = data.len();
+    let mut sa: Vec<usize> = (0..n).collect();
+    if n < 2 {
+        return sa;
+    }
+    let mut rank: Vec<usize> = data.iter().map(|&b| usize::from(b)).collect();
+    let mut next_rank = vec![0; n];
+
+    let mut k = 1;
+    while k < n {
+        // Rank 0 stands for "past the end", so shorter suffixes win ties
+        let key = |i: usize| (rank[i], if i + k < n { rank[i + k] + 1 } else { 0 });
+        sa.sort_unstable_by_key(|&i| key(i));
+        next_rank[sa[0]] = 0;
+        for w in 1..n {
+            next_rank[sa[w]] = next_rank[sa[w - 1]] + usize::from(key(sa[w - 1]) != key(sa[w]));
+        }
+        std::mem::swap(&mut rank, &mut next_rank);
+        if rank[sa[n - 1]] == n - 1 {
+            break;
+        }
+        k *= 2;
+    }
+    sa
+}
+
+// ========== BWT ==========
+
+/// The last column of the sorted rotations of `data` + end marker, with the marker left
+/// out, and the row it was in
+pub fn bwt(data: &[u8]) -> (Vec<u8>, usize) {
+    // The empty suffix, the marker itself, sorts before everything: that is row 0
+    let mut last = Vec::with_capacity(data.len());
+    let mut primary = 0;
+    if let Some(&end) = data.last() {
+        last.push(end);
+    }
+    for (row, &start) in suffix_array(data).iter().enumerate() {
+        match start {
+            0 => primary = row + 1,
+            _ => last.push(data[start - 1]),
+        }
+    }
+    (last, primary)
+}
+
+pub fn inverse_bwt(last: &[u8], primary: usize) -> Result<Vec<u8>, String> {
+    let n = last.len();
+    if primary > n || (primary == 0 && n > 0) {
+        return Err(format!("end marker row {} is not valid for {} bytes", primary, n));
+    }
+    // Symbols with the marker put back: 0 for the marker, byte + 1 otherwise
+    let column: Vec<usize> = (0..=n)
+        .map(|row| match row.cmp(&primary) {
+            std::cmp::Ordering::Less => usize::from(last[row]) + 1,
+            std::cmp::Ordering::Equal => 0,
+            std::cmp::Ordering::Greater => usize::from(last[row - 1]) + 1,
+        })
+        .collect();
+
+    // LF mapping: the k-th occurrence of a symbol in the last column is the k-th in the
+    // first column, which is sorted, so its row is (symbols smaller than it) + k
+    let mut first_row = [0; 257];
+    for &symbol in &column {
+        first_row[symbol] += 1;
+    }
+    let mut total = 0;
+    for slot in first_row.iter_mut() {
+        (*slot, total) = (total, total + *slot);
+    }
+    let lf: Vec<usize> = column
+        .iter()
+        .map(|&symbol| {
+            first_row[symbol] += 1;
+            first_row[symbol] - 1
+        })
+        .collect();
+
+    // Row 0 starts with the marker, so its last byte is the input's last byte
+    let mut out = vec![0; n];
+    let mut row = 0;
+    for slot in out.iter_mut().rev() {
+        if column[row] == 0 {
+            return Err("reached the end marker early; the block is corrupt".to_string());
+        }
+        *slot = (column[row] - 1) as u8;
+        row = lf[row];
+    }
+    Ok(out)
+}
+
+// ========== Move-to-Front ==========
+
+pub fn mtf_encode(data: &[u8]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+    data.iter()
+        .map(|&byte| {
+            let position = order.iter().position(|&b| b == byte).unwrap_or_default();
+            order.remove(position);
+            order.insert(0, byte);
+            position as u8
+        })
+        .collect()
+}
+
+pub fn mtf_decode(positions: &[u8]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+    positions
+        .iter()
+        .map(|&position| {
+            let byte = order.remove(usize::from(position));
+            order.insert(0, byte);
+            byte
+        })
+        .collect()
+}
+
+// ========== Mini Compressor ==========
+
+/// Sizes after each stage, for seeing where the savings come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stages {
+    pub input: usize,
+    pub after_rle: usize,
+    pub output: usize,
+}
+
+/// BWT, then move-to-front, then escaped RLE, then Huffman; the marker row leads as a u64
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    compress_with_stages(data).0
+}
+
+pub fn compress_with_stages(data: &[u8]) -> (Vec<u8>, Stages) {
+    let (last, primary) = bwt(data);
+    let runs = crate::rle::encode(&mtf_encode(&last));
+    let mut out = (primary as u64).to_le_bytes().to_vec();
+    out.extend(crate::huffman::compress(&runs));
+    let stages = Stages { input: data.len(), after_rle: runs.len(), output: out.len() };
+    (out, stages)
+}
+
+pub fn decompress(compressed: &[u8]) -> Result<Vec<u8>, String> {
+    let (header, body) = compressed.split_first_chunk::<8>().ok_or("missing block header")?;
+    let primary = usize::try_from(u64::from_le_bytes(*header)).map_err(|e| e.to_string())?;
+    let runs = crate::huffman::decompress(body)?;
+    let last = mtf_decode(&crate::rle::decode(&runs)?);
+    inverse_bwt(&last, primary)
+}
+
+// ========== Demo Code ==========
+
+const TEXT: &str = "\
+It is a truth universally acknowledged, that a single man in possession of a good \
+fortune, must be in want of a wife. However little known the feelings or views of such \
+a man may be on his first entering a neighbourhood, this truth is so well fixed in the \
+minds of the surrounding families, that he is considered the rightful property of some \
+one or other of their daughters.";
+
+fn printable(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| if b == b' ' { '_' } else { b as char }).collect()
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct BwtDemo;
+
+impl Demo for BwtDemo {
+    fn name(&self) -> &'static str {
+        "bwt"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Burrows-Wheeler transform, move-to-front and a bzip2-style compressor"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Sorted Rotations of \"banana$\" =====")?;
+        let word = b"banana";
+        let mut rotations: Vec<usize> = vec![word.len()];
+        rotations.extend(suffix_array(word));
+        for start in rotations {
+            let (head, tail) = (&word[start..], &word[..start]);
+            writeln!(out, "  {}${}", String::from_utf8_lossy(head), String::from_utf8_lossy(tail))?;
+        }
+        let (last, primary) = bwt(word);
+        writeln!(out, "last column {:?}, '$' in row {}", printable(&last), primary)?;
+        writeln!(out, "inverse     {:?}", inverse_bwt(&last, primary).map(|d| printable(&d)))?;
+
+        writeln!(out, "\n===== Grouping and Move-to-Front =====")?;
+        let sentence = &TEXT.as_bytes()[..120];
+        let (last, _) = bwt(sentence);
+        writeln!(out, "input {}", printable(sentence))?;
+        writeln!(out, "bwt   {}", printable(&last))?;
+        let positions = mtf_encode(&last);
+        let zeros = positions.iter().filter(|&&p| p == 0).count();
+        let small = positions.iter().filter(|&&p| p < 4).count();
+        writeln!(out, "mtf   {} of {} positions are 0, {} are below 4", zeros, positions.len(), small)?;
+        let direct = mtf_encode(sentence);
+        writeln!(out, "      (without the BWT first: {} are 0, {} are below 4)",
+                      direct.iter().filter(|&&p| p == 0).count(), direct.iter().filter(|&&p| p < 4).count())?;
+
+        writeln!(out, "\n===== Pipeline =====")?;
+        let text = TEXT.repeat(3).into_bytes();
+        let logs: Vec<u8> = (0..200)
+            .flat_map(|i| format!("GET /api/items/{} 200 {}b\n", i % 17, 512 + (i % 5) * 128).into_bytes())
+            .collect();
+        writeln!(out, "{:<6} {:>6} {:>10} {:>8} {:>10} {:>8}", "input", "bytes", "after rle", "bwt", "huffman", "lz77")?;
+        for (label, data) in [("text", &text), ("logs", &logs)] {
+            let (_, stages) = compress_with_stages(data);
+            let huffman_only = crate::huffman::compress(data).len();
+            let lz77 = crate::lz::Lz77::default().compress(data).len();
+            writeln!(out, "{:<6} {:>6} {:>10} {:>8} {:>10} {:>8}", label, stages.input, stages.after_rle, stages.output, huffman_only, lz77)?;
+        }
+        writeln!(out, "(bwt = BWT + MTF + RLE + Huffman; the last two columns are each used alone)")?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&BwtDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    fn naive_suffix_array(data: &[u8]) -> Vec<usize> {
+        let mut sa: Vec<usize> = (0..data.len()).collect();
+        sa.sort_by_key(|&i| &data[i..]);
+        sa
+    }
+
+    fn corpora() -> Vec<Vec<u8>> {
+        let mut rng = Pcg32::new(971, 0);
+        vec![
+            Vec::new(),
+            b"a".to_vec(),
+            b"banana".to_vec(),
+            b"mississippi".to_vec(),
+            vec![b'z'; 1_000],
+            TEXT.repeat(5).into_bytes(),
+            include_str!("huffman.rs").as_bytes().to_vec(),
+            include_str!("rle.rs").as_bytes().to_vec(),
+            (0..3_000).map(|_| rng.below(256) as u8).collect(),
+            (0..3_000).map(|_| b"ACGT"[rng.below(4) as usize]).collect(),
+        ]
+    }
+
+    #[test]
+    fn suffix_array_matches_naive_sort() {
+        let mut rng = Pcg32::new(971, 1);
+        for _ in 0..200 {
+            let data: Vec<u8> = (0..rng.below(60)).map(|_| b"ab"[rng.below(2) as usize]).collect();
+            assert_eq!(suffix_array(&data), naive_suffix_array(&data), "{:?}", String::from_utf8_lossy(&data));
+        }
+        assert_eq!(suffix_array(b"banana"), [5, 3, 1, 0, 4, 2]);
+    }
+
+    #[test]
+    fn known_transforms() {
+        assert_eq!(bwt(b"banana"), (b"annbaa".to_vec(), 4));
+        assert_eq!(bwt(b""), (Vec::new(), 0));
+        assert_eq!(bwt(b"a"), (b"a".to_vec(), 1));
+        assert_eq!(bwt(b"mississippi"), (b"ipssmpissii".to_vec(), 5));
+    }
+
+    #[test]
+    fn bwt_round_trips() {
+        for data in corpora() {
+            let (last, primary) = bwt(&data);
+            assert_eq!(inverse_bwt(&last, primary), Ok(data.clone()), "{} bytes", data.len());
+        }
+        assert!(inverse_bwt(b"abc", 0).is_err());
+        assert!(inverse_bwt(b"abc", 4).is_err());
+        // The marker sits in the row the walk reaches after one step instead of three
+        assert!(inverse_bwt(b"abc", 1).is_err());
+    }
+
+    #[test]
+    fn mtf_round_trips_and_zeroes_runs() {
+        assert_eq!(mtf_encode(b"aaabbb"), [97, 0, 0, 98, 0, 0]);
+        assert_eq!(mtf_encode(b"abab"), [97, 98, 1, 1]);
+        for data in corpora() {
+            assert_eq!(mtf_decode(&mtf_encode(&data)), data);
+        }
+    }
+
+    #[test]
+    fn compressor_round_trips() {
+        for data in corpora() {
+            assert_eq!(decompress(&compress(&data)), Ok(data.clone()), "{} bytes", data.len());
+        }
+        assert!(decompress(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn compressor_beats_huffman_alone_on_text() {
+        for data in [TEXT.repeat(5).into_bytes(), include_str!("huffman.rs").as_bytes().to_vec()] {
+            assert!(compress(&data).len() < crate::huffman::compress(&data).len());
+        }
+    }
+}
@@ -0,0 +1,445 @@
+//! Huffman Coding in Rust
+//!
+//! Huffman coding gives frequent bytes short codes and rare bytes long ones, with no
+//! code a prefix of another so the bitstream needs no separators:
+//! - Build the tree bottom-up: a min-heap of weighted nodes, repeatedly merging the two
+//!   lightest. A byte's depth in the finished tree is its code length, and the average
+//!   length is within one bit of the entropy of the byte distribution.
+//! - Only the lengths matter. Canonical codes are handed out in order of (length, byte),
+//!   each one the previous plus one, shifted left whenever the length grows; the decoder
+//!   rebuilds exactly the same codes from the lengths, so the header stores just those.
+//! - The codes are packed most significant bit first into bytes, and the header records
+//!   the input length so the padding bits at the end aren't decoded.
+//!
+//! Compressed layout: input length (u64, little-endian), and for non-empty input the
+//! number of distinct bytes minus one, then a (byte, code length) pair for each, then
+//! the packed bits.
+
+use demo::Demo;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{self, Write};
+
+// ========== Bit Streams ==========
+
+/// Packs bits most significant first
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    bit_len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_bit(&mut self, bit: bool) {
+        if self.bit_len % 8 == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().expect("pushed above") |= 0x80 >> (self.bit_len % 8);
+        }
+        self.bit_len += 1;
+    }
+
+    /// The low `len` bits of `bits`, highest first
+    pub fn push_bits(&mut self, bits: u64, len: u32) {
+        for i in (0..len).rev() {
+            self.push_bit((bits >> i) & 1 == 1);
+        }
+    }
+
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// The packed bytes, with the last one padded with zeros
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads bits back in the order `BitWriter` wrote them
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    /// The next bit, or `None` past the end
+    pub fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Some(bit)
+    }
+
+    /// The next `len` bits as a number, highest first
+    pub fn read_bits(&mut self, len: u32) -> Option<u64> {
+        (0..len).try_fold(0, |acc, _| Some((acc << 1) | u64::from(self.read_bit()?)))
+    }
+}
+
+// ========== Code Construction ==========
+
+/// How often each byte value occurs
+pub fn frequencies(data: &[u8]) -> [u64; 256] {
+    let mut counts = [0; 256];
+    for &byte in data {
+        counts[byte as usize] += 1;
+    }
+    counts
+}
+
+/// Code length of every byte value, 0 for bytes that never occur. A lone distinct byte
+/// still gets a 1-bit code, so every occurrence costs something.
+pub fn code_lengths(counts: &[u64; 256]) -> [u8; 256] {
+    let mut lengths = [0; 256];
+    // Each node remembers its children; leaves are the byte values themselves
+    let mut children: Vec<Option<(usize, usize)>> = vec![None; 256];
+    // Ties are broken by node index, so the tree is the same on every run
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> =
+        (0..256).filter(|&b| counts[b] > 0).map(|b| Reverse((counts[b], b))).collect();
+
+    match heap.len() {
+        0 => return lengths,
+        1 => {
+            let Reverse((_, byte)) = heap.pop().expect("one node");
+            lengths[byte] = 1;
+            return lengths;
+        }
+        _ => {}
+    }
+    while heap.len() > 1 {
+        let Reverse((weight_a, a)) = heap.pop().expect("at least two nodes");
+        let Reverse((weight_b, b)) = heap.pop().expect("at least two nodes");
+        children.push(Some((a, b)));
+        heap.push(Reverse((weight_a + weight_b, children.len() - 1)));
+    }
+
+    let Reverse((_, root)) = heap.pop().expect("the root");
+    let mut stack = vec![(root, 0u8)];
+    while let Some((node, depth)) = stack.pop() {
+        match children[node] {
+            Some((left, right)) => {
+                stack.push((left, depth + 1));
+                stack.push((right, depth + 1));
+            }
+            None => lengths[node] = depth,
+        }
+    }
+    lengths
+}
+
+/// A canonical code: `(code, length)` per byte value, `None` for unused bytes
+pub fn canonical_codes(lengths: &[u8; 256]) -> [Option<(u64, u8)>; 256] {
+    let mut order: Vec<usize> = (0..256).filter(|&b| lengths[b] > 0).collect();
+    order.sort_by_key(|&b| (lengths[b], b));
+
+    let mut codes = [None; 256];
+    let (mut code, mut previous_length) = (0u64, 0u8);
+    for (i, &byte) in order.iter().enumerate() {
+        let length = lengths[byte];
+        if i > 0 {
+            code += 1;
+        }
+        code <<= length - previous_length;
+        codes[byte] = Some((code, length));
+        previous_length = length;
+    }
+    codes
+}
+
+// ========== Encoding ==========
+
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut out = (data.len() as u64).to_le_bytes().to_vec();
+    if data.is_empty() {
+        return out;
+    }
+    let lengths = code_lengths(&frequencies(data));
+    let used: Vec<usize> = (0..256).filter(|&b| lengths[b] > 0).collect();
+    out.push((used.len() - 1) as u8);
+    for &byte in &used {
+        out.extend([byte as u8, lengths[byte]]);
+    }
+
+    let codes = canonical_codes(&lengths);
+    let mut bits = BitWriter::new();
+    for &byte in data {
+        let (code, length) = codes[byte as usize].expect("every input byte has a code");
+        bits.push_bits(code, u32::from(length));
+    }
+    out.extend(bits.into_bytes());
+    out
+}
+
+// ========== Decoding ==========
+
+/// Canonical decoding: within one length the codes are consecutive, so a code of that
+/// length is valid when it falls in `first..first + count`
+struct Decoder {
+    /// Per length: first code, number of codes, index of the first symbol in `symbols`
+    levels: Vec<(u64, u64, usize)>,
+    symbols: Vec<u8>,
+}
+
+impl Decoder {
+    fn new(lengths: &[u8; 256]) -> Self {
+        let max_length = *lengths.iter().max().expect("256 entries") as usize;
+        let mut symbols: Vec<u8> = (0..=255).filter(|&b| lengths[b as usize] > 0).collect();
+        symbols.sort_by_key(|&b| (lengths[b as usize], b));
+
+        let mut levels = Vec::with_capacity(max_length + 1);
+        let (mut code, mut index) = (0u64, 0usize);
+        for length in 0..=max_length {
+            let count = lengths.iter().filter(|&&l| l as usize == length && length > 0).count();
+            levels.push((code, count as u64, index));
+            code = (code + count as u64) << 1;
+            index += count;
+        }
+        Decoder { levels, symbols }
+    }
+
+    fn decode_one(&self, bits: &mut BitReader) -> Result<u8, String> {
+        let mut code = 0u64;
+        for &(first, count, index) in &self.levels[1..] {
+            let bit = bits.read_bit().ok_or("compressed data ends in the middle of a code")?;
+            code = (code << 1) | u64::from(bit);
+            if code >= first && code - first < count {
+                return Ok(self.symbols[index + (code - first) as usize]);
+            }
+        }
+        Err("bits that are not a code".to_string())
+    }
+}
+
+pub fn decompress(compressed: &[u8]) -> Result<Vec<u8>, String> {
+    let header: [u8; 8] = compressed.get(..8).and_then(|h| h.try_into().ok()).ok_or("missing length header")?;
+    let len = usize::try_from(u64::from_le_bytes(header)).map_err(|_| "length does not fit in memory")?;
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let distinct = usize::from(*compressed.get(8).ok_or("missing code table")?) + 1;
+    let table = compressed.get(9..9 + 2 * distinct).ok_or("truncated code table")?;
+    let mut lengths = [0u8; 256];
+    for pair in table.chunks_exact(2) {
+        if pair[1] == 0 || pair[1] > 64 || lengths[pair[0] as usize] != 0 {
+            return Err(format!("invalid code table entry for byte {}", pair[0]));
+        }
+        lengths[pair[0] as usize] = pair[1];
+    }
+    let kraft: f64 = lengths.iter().filter(|&&l| l > 0).map(|&l| 0.5f64.powi(i32::from(l))).sum();
+    if kraft > 1.0 {
+        return Err("code lengths do not form a prefix code".to_string());
+    }
+
+    let decoder = Decoder::new(&lengths);
+    let mut bits = BitReader::new(&compressed[9 + 2 * distinct..]);
+    (0..len).map(|_| decoder.decode_one(&mut bits)).collect()
+}
+
+/// Shannon entropy of the byte distribution, in bits per byte: the lower bound on the
+/// average code length of any prefix code
+pub fn entropy(data: &[u8]) -> f64 {
+    let total = data.len() as f64;
+    frequencies(data)
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// ========== Demo Code ==========
+
+const SAMPLE: &str = "\
+It was the best of times, it was the worst of times, it was the age of wisdom, \
+it was the age of foolishness, it was the epoch of belief, it was the epoch of \
+incredulity, it was the season of Light, it was the season of Darkness, it was the \
+spring of hope, it was the winter of despair.";
+
+fn printable(byte: u8) -> String {
+    match byte {
+        b' ' => "' '".to_string(),
+        b'!'..=b'~' => (byte as char).to_string(),
+        _ => format!("0x{:02x}", byte),
+    }
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct HuffmanDemo;
+
+impl Demo for HuffmanDemo {
+    fn name(&self) -> &'static str {
+        "huffman"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Huffman coding with canonical codes and a packed bitstream"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let data = SAMPLE.as_bytes();
+        let counts = frequencies(data);
+        let lengths = code_lengths(&counts);
+        let codes = canonical_codes(&lengths);
+
+        writeln!(out, "===== Canonical Codes =====")?;
+        let mut used: Vec<usize> = (0..256).filter(|&b| counts[b] > 0).collect();
+        used.sort_by_key(|&b| (lengths[b], b));
+        let code_line = |byte: usize| {
+            let (code, length) = codes[byte].expect("used byte");
+            format!("{:>4}  count {:>3}  code {:0width$b}", printable(byte as u8), counts[byte], code, width = length as usize)
+        };
+        for &byte in &used[..8] {
+            writeln!(out, "{}", code_line(byte))?;
+        }
+        writeln!(out, "  ... {} more ...", used.len() - 11)?;
+        for &byte in &used[used.len() - 3..] {
+            writeln!(out, "{}", code_line(byte))?;
+        }
+
+        writeln!(out, "\n===== Compression =====")?;
+        let compressed = compress(data);
+        let payload_bits: u64 = (0..256).map(|b| counts[b] * u64::from(lengths[b])).sum();
+        writeln!(out, "input:      {} bytes, {} distinct", data.len(), used.len())?;
+        writeln!(out, "entropy:    {:.3} bits/byte", entropy(data))?;
+        writeln!(out, "huffman:    {:.3} bits/byte", payload_bits as f64 / data.len() as f64)?;
+        writeln!(out, "compressed: {} bytes ({} header + {} payload), ratio {:.1}%",
+                      compressed.len(), 9 + 2 * used.len(), payload_bits.div_ceil(8),
+                      100.0 * compressed.len() as f64 / data.len() as f64)?;
+        let restored = decompress(&compressed).expect("own output decodes");
+        writeln!(out, "round trip: {}", if restored == data { "identical" } else { "MISMATCH" })?;
+
+        writeln!(out, "\n===== Skewed vs Uniform Input =====")?;
+        let skewed: Vec<u8> = (0..4096u32).map(|i| if i % 16 == 0 { b'b' } else { b'a' }).collect();
+        let uniform: Vec<u8> = (0..4096u32).map(|i| (i % 256) as u8).collect();
+        for (label, input) in [("15:1 two-letter", &skewed), ("all 256 bytes", &uniform)] {
+            writeln!(out, "{:<16} {} -> {} bytes (entropy {:.3} bits/byte)",
+                          label, input.len(), compress(input).len(), entropy(input))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&HuffmanDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    fn round_trip(data: &[u8]) {
+        let compressed = compress(data);
+        assert_eq!(decompress(&compressed).as_deref(), Ok(data), "{} bytes", data.len());
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(b"");
+        round_trip(b"a");
+        round_trip(&[7; 1000]);
+        round_trip(b"abracadabra");
+        round_trip(SAMPLE.as_bytes());
+        round_trip(&(0..=255).collect::<Vec<u8>>());
+
+        let mut rng = Pcg32::new(966, 0);
+        for len in [1, 2, 10, 100, 5_000] {
+            // Narrow alphabets and the full byte range
+            for alphabet in [2, 5, 256] {
+                let data: Vec<u8> = (0..len).map(|_| rng.below(alphabet) as u8).collect();
+                round_trip(&data);
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_codes_form_a_complete_prefix_code() {
+        let lengths = code_lengths(&frequencies(SAMPLE.as_bytes()));
+        let codes: Vec<(u64, u8)> = canonical_codes(&lengths).iter().flatten().copied().collect();
+        let kraft: f64 = codes.iter().map(|&(_, l)| 0.5f64.powi(i32::from(l))).sum();
+        assert!((kraft - 1.0).abs() < 1e-12, "{}", kraft);
+        for (i, &(a, la)) in codes.iter().enumerate() {
+            for &(b, lb) in &codes[i + 1..] {
+                let (short, long, shift) = if la <= lb { (a, b, lb - la) } else { (b, a, la - lb) };
+                assert_ne!(long >> shift, short, "one code is a prefix of another");
+            }
+        }
+    }
+
+    #[test]
+    fn known_code_lengths() {
+        // Weights 1, 1, 2, 4: the classic skewed tree
+        let mut counts = [0; 256];
+        counts[b'a' as usize] = 4;
+        counts[b'b' as usize] = 2;
+        counts[b'c' as usize] = 1;
+        counts[b'd' as usize] = 1;
+        let lengths = code_lengths(&counts);
+        assert_eq!([lengths[b'a' as usize], lengths[b'b' as usize], lengths[b'c' as usize], lengths[b'd' as usize]], [1, 2, 3, 3]);
+        let codes = canonical_codes(&lengths);
+        assert_eq!(codes[b'a' as usize], Some((0b0, 1)));
+        assert_eq!(codes[b'b' as usize], Some((0b10, 2)));
+        assert_eq!(codes[b'c' as usize], Some((0b110, 3)));
+        assert_eq!(codes[b'd' as usize], Some((0b111, 3)));
+    }
+
+    #[test]
+    fn average_length_is_within_a_bit_of_entropy() {
+        let mut rng = Pcg32::new(7, 7);
+        for alphabet in [2, 3, 17, 256] {
+            let data: Vec<u8> = (0..10_000).map(|_| (rng.below(alphabet) * rng.below(alphabet) % alphabet) as u8).collect();
+            let counts = frequencies(&data);
+            let lengths = code_lengths(&counts);
+            let bits: u64 = (0..256).map(|b| counts[b] * u64::from(lengths[b])).sum();
+            let average = bits as f64 / data.len() as f64;
+            let h = entropy(&data);
+            assert!(h <= average + 1e-9 && average < h + 1.0, "alphabet {}: {} vs {}", alphabet, average, h);
+        }
+    }
+
+    #[test]
+    fn corrupt_input_is_an_error() {
+        assert!(decompress(&[1, 2, 3]).is_err());
+        let compressed = compress(SAMPLE.as_bytes());
+        assert!(decompress(&compressed[..compressed.len() - 5]).is_err(), "truncated payload");
+        assert!(decompress(&compressed[..12]).is_err(), "truncated table");
+
+        // Two one-bit codes and a third: more codes than a prefix code allows
+        let mut bogus = 5u64.to_le_bytes().to_vec();
+        bogus.extend([2, b'a', 1, b'b', 1, b'c', 1, 0]);
+        assert!(decompress(&bogus).is_err());
+    }
+
+    #[test]
+    fn bit_streams_round_trip() {
+        let mut writer = BitWriter::new();
+        writer.push_bits(0b101, 3);
+        writer.push_bits(0xABCD, 16);
+        writer.push_bit(true);
+        assert_eq!(writer.bit_len(), 20);
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes, [0b1011_0101, 0b0111_1001, 0b1011_0000]);
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!((reader.read_bits(3), reader.read_bits(16), reader.read_bit()), (Some(0b101), Some(0xABCD), Some(true)));
+        assert_eq!(reader.read_bits(4), Some(0));
+        assert_eq!(reader.read_bit(), None);
+    }
+}
@@ -0,0 +1,419 @@
+//! LZ77 and LZW in Rust
+//!
+//! Both replace repeated substrings with references to an earlier copy, and differ in
+//! where that copy lives:
+//! - LZ77 keeps a sliding window of the last few kilobytes of output. Each step emits
+//!   either a literal byte or a `(distance, length)` pair meaning "copy `length` bytes
+//!   starting `distance` back"; the copy may overlap what it produces, so `"abababab"`
+//!   is two literals and one match. A bigger window finds more matches but costs more
+//!   bits per distance, and the lookahead caps the length of one match.
+//! - LZW builds a dictionary instead: it starts with all 256 single bytes, emits the
+//!   code of the longest string already in the dictionary, and adds that string plus
+//!   the next byte as a new entry. The decoder rebuilds the same dictionary one step
+//!   behind, so no dictionary is ever stored.
+//!
+//! LZ77 output here is LZSS-style: a flag byte says which of the next eight tokens are
+//! matches, literals take one byte and matches three (16-bit distance, 8-bit length).
+//! LZW codes start at 9 bits and widen up to 12 as the dictionary grows to 4096 entries,
+//! after which it stays fixed.
+
+use crate::huffman::{BitReader, BitWriter};
+use demo::Demo;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Shorter matches take more space than the literals they replace
+pub const MIN_MATCH: usize = 3;
+
+/// Candidate positions checked per match search; bounds the time on repetitive input
+const MAX_CHAIN: usize = 256;
+
+// ========== LZ77 ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Literal(u8),
+    Match { distance: usize, length: usize },
+}
+
+/// LZ77 settings: how far back a match may start and how long it may be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lz77 {
+    window: usize,
+    lookahead: usize,
+}
+
+impl Default for Lz77 {
+    /// The classic LZSS sizes: a 4 KiB window and 18-byte matches
+    fn default() -> Self {
+        Lz77::new(4096, 18)
+    }
+}
+
+impl Lz77 {
+    /// `window` up to 65535 and `lookahead` up to 258, so both fit the token format
+    pub fn new(window: usize, lookahead: usize) -> Self {
+        assert!((1..=u16::MAX as usize).contains(&window), "window must be 1..=65535");
+        assert!((MIN_MATCH..=MIN_MATCH + 255).contains(&lookahead), "lookahead must be 3..=258");
+        Lz77 { window, lookahead }
+    }
+
+    /// Greedy parse: at each position take the longest match in the window, found
+    /// through a hash chain of earlier positions with the same next three bytes
+    pub fn tokenize(&self, data: &[u8]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut chains: HashMap<[u8; 3], Vec<usize>> = HashMap::new();
+        let key = |i: usize| -> Option<[u8; 3]> { data.get(i..i + 3).map(|k| [k[0], k[1], k[2]]) };
+
+        let mut i = 0;
+        while i < data.len() {
+            let (mut best_distance, mut best_length) = (0, 0);
+            if let Some(candidates) = key(i).and_then(|k| chains.get(&k)) {
+                let max_length = self.lookahead.min(data.len() - i);
+                for &start in candidates.iter().rev().take(MAX_CHAIN) {
+                    if i - start > self.window {
+                        break;
+                    }
+                    let length = (0..max_length).take_while(|&n| data[start + n] == data[i + n]).count();
+                    if length > best_length {
+                        (best_distance, best_length) = (i - start, length);
+                        if length == max_length {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let step = if best_length >= MIN_MATCH {
+                tokens.push(Token::Match { distance: best_distance, length: best_length });
+                best_length
+            } else {
+                tokens.push(Token::Literal(data[i]));
+                1
+            };
+            for j in i..i + step {
+                if let Some(k) = key(j) {
+                    chains.entry(k).or_default().push(j);
+                }
+            }
+            i += step;
+        }
+        tokens
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for group in self.tokenize(data).chunks(8) {
+            let flag_at = out.len();
+            out.push(0);
+            for (bit, token) in group.iter().enumerate() {
+                match *token {
+                    Token::Literal(byte) => out.push(byte),
+                    Token::Match { distance, length } => {
+                        out[flag_at] |= 1 << bit;
+                        out.extend((distance as u16).to_le_bytes());
+                        out.push((length - MIN_MATCH) as u8);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Replays tokens; matches copy byte by byte, so they may overlap their own output
+pub fn expand(tokens: &[Token]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for token in tokens {
+        match *token {
+            Token::Literal(byte) => out.push(byte),
+            Token::Match { distance, length } => {
+                if distance == 0 || distance > out.len() {
+                    return Err(format!("match reaches {} bytes back into {} bytes of output", distance, out.len()));
+                }
+                let start = out.len() - distance;
+                for n in 0..length {
+                    out.push(out[start + n]);
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+pub fn lz77_decompress(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < encoded.len() {
+        let flags = encoded[i];
+        i += 1;
+        for bit in 0..8 {
+            if i == encoded.len() {
+                break;
+            }
+            if flags & (1 << bit) == 0 {
+                tokens.push(Token::Literal(encoded[i]));
+                i += 1;
+            } else {
+                let bytes = encoded.get(i..i + 3).ok_or("match token is cut off")?;
+                let distance = usize::from(u16::from_le_bytes([bytes[0], bytes[1]]));
+                tokens.push(Token::Match { distance, length: usize::from(bytes[2]) + MIN_MATCH });
+                i += 3;
+            }
+        }
+    }
+    expand(&tokens)
+}
+
+// ========== LZW ==========
+
+/// Dictionary size at which LZW stops adding entries
+pub const LZW_MAX_CODES: usize = 4096;
+
+/// Bits per code while the largest code that can come next is `max_code`
+fn code_width(max_code: usize) -> u32 {
+    (usize::BITS - max_code.leading_zeros()).clamp(9, 12)
+}
+
+/// The LZW codes for `data`, before packing
+pub fn lzw_codes(data: &[u8]) -> Vec<u16> {
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = 256;
+    let mut codes = Vec::new();
+    let Some((&first, rest)) = data.split_first() else {
+        return codes;
+    };
+
+    let mut current = u16::from(first);
+    for &byte in rest {
+        match dictionary.get(&(current, byte)) {
+            Some(&code) => current = code,
+            None => {
+                codes.push(current);
+                if next < LZW_MAX_CODES {
+                    dictionary.insert((current, byte), next as u16);
+                    next += 1;
+                }
+                current = u16::from(byte);
+            }
+        }
+    }
+    codes.push(current);
+    codes
+}
+
+pub fn lzw_compress(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::new();
+    for (k, &code) in lzw_codes(data).iter().enumerate() {
+        // Before code k the encoder has added k entries, so codes up to 255 + k can occur
+        let width = code_width((255 + k).min(LZW_MAX_CODES - 1));
+        bits.push_bits(u64::from(code), width);
+    }
+    bits.into_bytes()
+}
+
+pub fn lzw_decompress(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    let mut dictionary: Vec<Vec<u8>> = (0..=255).map(|b| vec![b]).collect();
+    let mut bits = BitReader::new(encoded);
+    let mut out = Vec::new();
+    let mut previous: Option<Vec<u8>> = None;
+
+    for k in 0.. {
+        let width = code_width((255 + k).min(LZW_MAX_CODES - 1));
+        // Fewer bits than a code left means only the final byte's padding remains
+        let Some(code) = bits.read_bits(width) else { break };
+        let code = code as usize;
+
+        let entry = match (&previous, dictionary.get(code)) {
+            (_, Some(entry)) => entry.clone(),
+            // The code the encoder added on its last step: the previous string plus
+            // its own first byte
+            (Some(prev), None) if code == dictionary.len() => {
+                let mut entry = prev.clone();
+                entry.push(prev[0]);
+                entry
+            }
+            _ => return Err(format!("code {} is not in the dictionary yet", code)),
+        };
+        out.extend_from_slice(&entry);
+        if let Some(mut prev) = previous.take() {
+            if dictionary.len() < LZW_MAX_CODES {
+                prev.push(entry[0]);
+                dictionary.push(prev);
+            }
+        }
+        previous = Some(entry);
+    }
+    Ok(out)
+}
+
+// ========== Demo Code ==========
+
+const TEXT: &str = "\
+It was the best of times, it was the worst of times, it was the age of wisdom, it was \
+the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, it \
+was the season of Light, it was the season of Darkness, it was the spring of hope, it \
+was the winter of despair, we had everything before us, we had nothing before us, we \
+were all going direct to Heaven, we were all going direct the other way -- in short, \
+the period was so far like the present period, that some of its noisiest authorities \
+insisted on its being received, for good or for evil, in the superlative degree of \
+comparison only.";
+
+fn describe(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match *token {
+            Token::Literal(byte) => (byte as char).to_string(),
+            Token::Match { distance, length } => format!("<{},{}>", distance, length),
+        })
+        .collect()
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct LzDemo;
+
+impl Demo for LzDemo {
+    fn name(&self) -> &'static str {
+        "lz"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "LZ77 with a sliding window and LZW with a growing dictionary"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== LZ77 Tokens =====")?;
+        for input in ["abababababab", "to be or not to be, that is the question", "aaaaaaaaaaaaaaaaaaaaaaab"] {
+            writeln!(out, "{:?}", input)?;
+            writeln!(out, "  -> {}", describe(&Lz77::default().tokenize(input.as_bytes())))?;
+        }
+
+        writeln!(out, "\n===== LZW Codes =====")?;
+        let input = "TOBEORNOTTOBEORTOBEORNOT";
+        let codes = lzw_codes(input.as_bytes());
+        let shown: Vec<String> = codes
+            .iter()
+            .map(|&c| if c < 256 { (c as u8 as char).to_string() } else { c.to_string() })
+            .collect();
+        writeln!(out, "{:?} -> {} codes: {}", input, codes.len(), shown.join(" "))?;
+
+        writeln!(out, "\n===== Ratios (compressed / original) =====")?;
+        let text = TEXT.repeat(4).into_bytes();
+        let logs: Vec<u8> = (0..200)
+            .flat_map(|i| format!("2024-01-01 12:{:02}:{:02} INFO request {} served in {}ms\n", i / 60, i % 60, 1000 + i * 7, i % 13).into_bytes())
+            .collect();
+        let mut rng = crate::prng::Pcg32::new(968, 0);
+        let random: Vec<u8> = (0..4096).map(|_| crate::prng::Rng::next_u32(&mut rng) as u8).collect();
+        // `None` stands for LZW
+        let codecs = [
+            ("LZ77 window 256", Some(Lz77::new(256, 18))),
+            ("LZ77 window 4096", Some(Lz77::default())),
+            ("LZ77 window 32768", Some(Lz77::new(32_768, 258))),
+            ("LZW", None),
+        ];
+        writeln!(out, "{:<18} {:>14} {:>14} {:>14}", "codec",
+                      format!("text ({})", text.len()), format!("logs ({})", logs.len()), format!("random ({})", random.len()))?;
+        for (label, codec) in &codecs {
+            let ratio = |data: &[u8]| {
+                let compressed = codec.map_or_else(|| lzw_compress(data), |lz77| lz77.compress(data));
+                format!("{:.1}%", 100.0 * compressed.len() as f64 / data.len() as f64)
+            };
+            writeln!(out, "{:<18} {:>14} {:>14} {:>14}", label, ratio(&text), ratio(&logs), ratio(&random))?;
+        }
+        writeln!(out, "Random bytes have no repeats to find, so both schemes only add overhead.")?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&LzDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    fn corpus() -> Vec<Vec<u8>> {
+        let mut rng = Pcg32::new(968, 1);
+        vec![
+            Vec::new(),
+            b"a".to_vec(),
+            b"abababababababab".to_vec(),
+            vec![0; 10_000],
+            TEXT.repeat(3).into_bytes(),
+            (0..5_000).map(|_| rng.below(256) as u8).collect(),
+            (0..5_000).map(|_| b"ab "[rng.below(3) as usize]).collect(),
+            (0..=255).cycle().take(3_000).collect(),
+        ]
+    }
+
+    #[test]
+    fn lz77_round_trips_for_every_configuration() {
+        for data in corpus() {
+            for codec in [Lz77::new(1, 3), Lz77::new(16, 4), Lz77::default(), Lz77::new(65_535, 258)] {
+                let compressed = codec.compress(&data);
+                assert_eq!(lz77_decompress(&compressed), Ok(data.clone()), "{:?} on {} bytes", codec, data.len());
+            }
+        }
+    }
+
+    #[test]
+    fn lz77_respects_window_and_lookahead() {
+        let data = TEXT.repeat(2).into_bytes();
+        for codec in [Lz77::new(40, 5), Lz77::new(300, 10)] {
+            for token in codec.tokenize(&data) {
+                if let Token::Match { distance, length } = token {
+                    assert!(distance <= codec.window && (MIN_MATCH..=codec.lookahead).contains(&length), "{:?}", token);
+                }
+            }
+        }
+        // The second copy is out of reach of a small window, but not a large one
+        assert!(Lz77::new(64, 18).compress(&data).len() > Lz77::new(1024, 18).compress(&data).len());
+    }
+
+    #[test]
+    fn overlapping_matches() {
+        let tokens = Lz77::default().tokenize(b"abababab");
+        assert_eq!(tokens, [Token::Literal(b'a'), Token::Literal(b'b'), Token::Match { distance: 2, length: 6 }]);
+        assert_eq!(expand(&tokens), Ok(b"abababab".to_vec()));
+        assert!(expand(&[Token::Literal(b'x'), Token::Match { distance: 2, length: 3 }]).is_err());
+    }
+
+    #[test]
+    fn lzw_round_trips() {
+        for data in corpus() {
+            assert_eq!(lzw_decompress(&lzw_compress(&data)), Ok(data.clone()), "{} bytes", data.len());
+        }
+        // Long enough to fill the dictionary and keep going with 12-bit codes
+        let mut rng = Pcg32::new(968, 2);
+        let data: Vec<u8> = (0..60_000).map(|_| b"abcdefgh"[rng.below(8) as usize]).collect();
+        assert_eq!(lzw_decompress(&lzw_compress(&data)), Ok(data));
+    }
+
+    #[test]
+    fn lzw_known_codes() {
+        // The textbook example, including the code-before-it-exists case (BEOR -> 263)
+        let codes = lzw_codes(b"TOBEORNOTTOBEORTOBEORNOT");
+        let expected: Vec<u16> = vec![84, 79, 66, 69, 79, 82, 78, 79, 84, 256, 258, 260, 265, 259, 261, 263];
+        assert_eq!(codes, expected);
+        assert_eq!(lzw_codes(b"aaaaaaa"), [97, 256, 257, 97]);
+        assert_eq!(lzw_decompress(&lzw_compress(b"aaaaaaa")), Ok(b"aaaaaaa".to_vec()));
+    }
+
+    #[test]
+    fn corrupt_input_is_an_error() {
+        // A match flag with only one byte after it
+        assert!(lz77_decompress(&[0b1, 5]).is_err());
+        // A match before any output
+        assert!(lz77_decompress(&[0b1, 1, 0, 0]).is_err());
+        // Code 300 as the very first code
+        let mut bits = BitWriter::new();
+        bits.push_bits(300, 9);
+        assert!(lzw_decompress(&bits.into_bytes()).is_err());
+    }
+}
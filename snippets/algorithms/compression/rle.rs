@@ -0,0 +1,367 @@
+//! Run-Length Encoding in Rust
+//!
+//! RLE replaces a run of identical symbols with one copy and a count. It only helps
+//! when the input really has runs, and the framing decides what happens when it doesn't:
+//! - Naive `(count, byte)` pairs are binary-safe but double the size of data without
+//!   runs.
+//! - Escaped RLE copies bytes through unchanged and marks runs with an escape byte:
+//!   `ESCAPE, length, byte`. An escape byte in the input becomes `ESCAPE, 0`, so any
+//!   binary data round-trips, and only escape bytes and runs cost extra.
+//! - Bit-level RLE suits sparse bitmaps: the bits alternate between runs of zeros and
+//!   runs of ones, so only the run lengths are stored, each as an Elias gamma code
+//!   (`n` in binary, preceded by one zero per bit after the first). Short runs, the
+//!   common case, take only a few bits.
+
+use crate::huffman::{BitReader, BitWriter};
+use demo::Demo;
+use std::io::{self, Write};
+
+/// Marks a run in escaped RLE; `ESCAPE, 0` stands for the byte itself
+pub const ESCAPE: u8 = 0x90;
+
+/// Runs shorter than this are cheaper as plain bytes than as a three-byte run
+pub const MIN_RUN: usize = 4;
+
+/// Lengths of the runs of identical bytes in `data`, each capped at `max`
+fn runs(data: &[u8], max: usize) -> impl Iterator<Item = (u8, usize)> + '_ {
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        let &byte = data.get(i)?;
+        let len = data[i..].iter().take(max).take_while(|&&b| b == byte).count();
+        i += len;
+        Some((byte, len))
+    })
+}
+
+// ========== Naive Pairs ==========
+
+/// Every run as `(count, byte)`, runs capped at 255
+pub fn encode_pairs(data: &[u8]) -> Vec<u8> {
+    runs(data, 255).flat_map(|(byte, len)| [len as u8, byte]).collect()
+}
+
+pub fn decode_pairs(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    if encoded.len() % 2 != 0 {
+        return Err("pair-encoded data has odd length".to_string());
+    }
+    let mut out = Vec::new();
+    for pair in encoded.chunks_exact(2) {
+        if pair[0] == 0 {
+            return Err("zero-length run".to_string());
+        }
+        out.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+    }
+    Ok(out)
+}
+
+// ========== Escaped RLE ==========
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for (byte, len) in runs(data, 255) {
+        if len >= MIN_RUN || (byte == ESCAPE && len > 1) {
+            out.extend([ESCAPE, len as u8, byte]);
+        } else if byte == ESCAPE {
+            out.extend([ESCAPE, 0]);
+        } else {
+            out.extend(std::iter::repeat_n(byte, len));
+        }
+    }
+    out
+}
+
+pub fn decode(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        if encoded[i] != ESCAPE {
+            out.push(encoded[i]);
+            i += 1;
+            continue;
+        }
+        match encoded.get(i + 1..) {
+            Some([0, ..]) => {
+                out.push(ESCAPE);
+                i += 2;
+            }
+            Some([len, byte, ..]) => {
+                out.extend(std::iter::repeat_n(*byte, *len as usize));
+                i += 3;
+            }
+            _ => return Err(format!("escape at offset {} is cut off", i)),
+        }
+    }
+    Ok(out)
+}
+
+// ========== Bit-Level RLE ==========
+
+/// Elias gamma code for `n >= 1`
+fn write_gamma(bits: &mut BitWriter, n: u64) {
+    let width = 64 - n.leading_zeros();
+    bits.push_bits(0, width - 1);
+    bits.push_bits(n, width);
+}
+
+fn read_gamma(bits: &mut BitReader) -> Option<u64> {
+    let mut zeros = 0;
+    while !bits.read_bit()? {
+        zeros += 1;
+        if zeros > 63 {
+            return None;
+        }
+    }
+    Some((1 << zeros) | bits.read_bits(zeros)?)
+}
+
+/// `bitmap` (most significant bit first) as alternating zero and one runs, starting
+/// with zeros. Layout: bitmap length in bytes (u64, little-endian), then each run
+/// length plus one in gamma code, since the first run of zeros may be empty.
+pub fn encode_bitmap(bitmap: &[u8]) -> Vec<u8> {
+    let mut out = (bitmap.len() as u64).to_le_bytes().to_vec();
+    let mut bits = BitWriter::new();
+    let mut reader = BitReader::new(bitmap);
+    let (mut current, mut run) = (false, 0u64);
+    while let Some(bit) = reader.read_bit() {
+        if bit != current {
+            write_gamma(&mut bits, run + 1);
+            (current, run) = (bit, 0);
+        }
+        run += 1;
+    }
+    if !bitmap.is_empty() {
+        write_gamma(&mut bits, run + 1);
+    }
+    out.extend(bits.into_bytes());
+    out
+}
+
+/// Decodes in two passes: the run lengths are read and checked against the header before
+/// anything is allocated, so a corrupt length or run cannot make the decoder reserve more
+/// memory than the input justifies
+pub fn decode_bitmap(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    let header: [u8; 8] = encoded.get(..8).and_then(|h| h.try_into().ok()).ok_or("missing length header")?;
+    let total_bits = u64::from_le_bytes(header).checked_mul(8).ok_or("bitmap length does not fit in memory")?;
+
+    let mut reader = BitReader::new(&encoded[8..]);
+    let mut runs = Vec::new();
+    let mut covered = 0u64;
+    while covered < total_bits {
+        let run = read_gamma(&mut reader).ok_or("run lengths end before the bitmap does")? - 1;
+        covered = covered.checked_add(run).filter(|&sum| sum <= total_bits).ok_or("runs add up to more than the bitmap length")?;
+        runs.push(run);
+    }
+    // Only the zero padding of the last byte may follow the final run
+    let mut padding = 0;
+    while let Some(bit) = reader.read_bit() {
+        padding += 1;
+        if bit || padding == 8 {
+            return Err("data follows the last run".to_string());
+        }
+    }
+
+    let len = usize::try_from(total_bits / 8).map_err(|_| "bitmap length does not fit in memory")?;
+    let mut bitmap = Vec::new();
+    bitmap.try_reserve_exact(len).map_err(|_| "bitmap length does not fit in memory")?;
+    bitmap.resize(len, 0);
+    // Runs alternate starting with zeros, so only the odd ones need writing
+    let mut position = 0;
+    for (i, &run) in runs.iter().enumerate() {
+        let run = run as usize;
+        if i % 2 == 1 {
+            for bit in position..position + run {
+                bitmap[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+        position += run;
+    }
+    Ok(bitmap)
+}
+
+// ========== Demo Code ==========
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct RleDemo;
+
+impl Demo for RleDemo {
+    fn name(&self) -> &'static str {
+        "rle"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Binary-safe escaped RLE and gamma-coded bit runs for sparse bitmaps"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Escaped Runs =====")?;
+        let sample = [b'A', b'A', b'A', b'A', b'A', b'A', b'B', b'C', b'C', ESCAPE, b'D', ESCAPE, ESCAPE, ESCAPE];
+        writeln!(out, "input:   {}", hex(&sample))?;
+        writeln!(out, "pairs:   {}", hex(&encode_pairs(&sample)))?;
+        writeln!(out, "escaped: {}", hex(&encode(&sample)))?;
+
+        writeln!(out, "\n===== Sizes =====")?;
+        let text = b"Run-length encoding works on runs, and ordinary text has almost none.".to_vec();
+        let mut scanlines = Vec::new();
+        for row in 0..32 {
+            scanlines.extend(std::iter::repeat_n(0u8, 20 + row));
+            scanlines.extend(std::iter::repeat_n(0xFFu8, 40));
+            scanlines.extend(std::iter::repeat_n(0u8, 12 - row / 3));
+        }
+        let ramp: Vec<u8> = (0..=255).collect();
+        let escapes = [ESCAPE, b'x'].repeat(64);
+        writeln!(out, "{:<20} {:>6} {:>6} {:>8}", "input", "bytes", "pairs", "escaped")?;
+        for (label, data) in [("text", &text), ("image scanlines", &scanlines), ("byte ramp 0..=255", &ramp), ("escape bytes", &escapes)] {
+            let encoded = encode(data);
+            writeln!(out, "{:<20} {:>6} {:>6} {:>8}", label, data.len(), encode_pairs(data).len(), encoded.len())?;
+        }
+
+        writeln!(out, "\n===== Sparse Bitmap =====")?;
+        // A 64x64 one-bit image: a circle outline
+        let mut bitmap = vec![0u8; 64 * 64 / 8];
+        for y in 0..64i32 {
+            for x in 0..64i32 {
+                let d = (x - 32) * (x - 32) + (y - 32) * (y - 32);
+                if (26 * 26..28 * 28).contains(&d) {
+                    let i = (y * 64 + x) as usize;
+                    bitmap[i / 8] |= 0x80 >> (i % 8);
+                }
+            }
+        }
+        let set_bits: u32 = bitmap.iter().map(|b| b.count_ones()).sum();
+        let encoded = encode_bitmap(&bitmap);
+        writeln!(out, "64x64 circle outline, {} of 4096 bits set", set_bits)?;
+        writeln!(out, "raw {} bytes, byte RLE {} bytes, bit runs {} bytes",
+                      bitmap.len(), encode(&bitmap).len(), encoded.len())?;
+        writeln!(out, "round trip: {}", decode_bitmap(&encoded).as_ref() == Ok(&bitmap))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&RleDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    /// Random bytes with random run lengths, drawn from a small alphabet that always
+    /// includes the escape byte
+    fn runny_data(rng: &mut Pcg32, len: usize) -> Vec<u8> {
+        let alphabet = [0, 1, 0xFF, ESCAPE, b'a'];
+        let mut data = Vec::with_capacity(len);
+        while data.len() < len {
+            let byte = alphabet[rng.below(alphabet.len() as u64) as usize];
+            let run = [1, 2, 3, 4, 5, 255, 256, 600][rng.below(8) as usize];
+            data.extend(std::iter::repeat_n(byte, run.min(len - data.len())));
+        }
+        data
+    }
+
+    #[test]
+    fn escaped_rle_round_trips_arbitrary_bytes() {
+        let mut rng = Pcg32::new(967, 0);
+        for round in 0..500 {
+            let len = rng.below(2_000) as usize;
+            let data = if round % 2 == 0 {
+                runny_data(&mut rng, len)
+            } else {
+                (0..len).map(|_| rng.below(256) as u8).collect()
+            };
+            assert_eq!(decode(&encode(&data)), Ok(data.clone()), "round {}", round);
+            assert_eq!(decode_pairs(&encode_pairs(&data)), Ok(data));
+        }
+    }
+
+    #[test]
+    fn escaped_format() {
+        assert_eq!(encode(b""), b"");
+        assert_eq!(encode(b"abc"), b"abc");
+        assert_eq!(encode(b"aaab"), b"aaab");
+        assert_eq!(encode(b"aaaab"), [ESCAPE, 4, b'a', b'b']);
+        assert_eq!(encode(&[ESCAPE]), [ESCAPE, 0]);
+        assert_eq!(encode(&[ESCAPE, ESCAPE]), [ESCAPE, 2, ESCAPE]);
+        // Runs longer than 255 split
+        assert_eq!(encode(&[7; 300]), [ESCAPE, 255, 7, ESCAPE, 45, 7]);
+        // Input without runs or escapes never grows
+        let plain: Vec<u8> = (0..=255).filter(|&b| b != ESCAPE).collect();
+        assert_eq!(encode(&plain), plain);
+    }
+
+    #[test]
+    fn truncated_escapes_are_errors() {
+        assert!(decode(&[b'a', ESCAPE]).is_err());
+        assert!(decode(&[ESCAPE, 5]).is_err());
+        assert_eq!(decode(&[ESCAPE, 0, ESCAPE, 3, b'z']), Ok(vec![ESCAPE, b'z', b'z', b'z']));
+        assert!(decode_pairs(&[3]).is_err());
+        assert!(decode_pairs(&[0, 1]).is_err());
+    }
+
+    #[test]
+    fn bitmaps_round_trip_at_every_density() {
+        let mut rng = Pcg32::new(967, 1);
+        for density in [0, 1, 5, 50, 95, 100] {
+            for len in [0, 1, 2, 17, 512] {
+                let bitmap: Vec<u8> = (0..len)
+                    .map(|_| (0..8).fold(0u8, |byte, _| (byte << 1) | u8::from(rng.below(100) < density)))
+                    .collect();
+                assert_eq!(decode_bitmap(&encode_bitmap(&bitmap)), Ok(bitmap), "density {}% len {}", density, len);
+            }
+        }
+    }
+
+    #[test]
+    fn sparse_bitmaps_shrink() {
+        let mut bitmap = vec![0u8; 1_000];
+        bitmap[10] = 0b0001_0000;
+        bitmap[500] = 0xFF;
+        let encoded = encode_bitmap(&bitmap);
+        assert!(encoded.len() < 20, "{} bytes", encoded.len());
+        assert_eq!(decode_bitmap(&encoded), Ok(bitmap));
+    }
+
+    #[test]
+    fn corrupt_bitmaps_are_errors() {
+        assert!(decode_bitmap(&[1, 0, 0]).is_err());
+        let encoded = encode_bitmap(&[0b1010_1010; 4]);
+        assert!(decode_bitmap(&encoded[..encoded.len() - 1]).is_err());
+        // Claims 1 byte, but the first run is 9 bits of zeros
+        let mut bits = BitWriter::new();
+        write_gamma(&mut bits, 10);
+        let mut bogus = 1u64.to_le_bytes().to_vec();
+        bogus.extend(bits.into_bytes());
+        assert!(decode_bitmap(&bogus).is_err());
+        // Trailing bits after the last run, or a whole extra byte of padding
+        let mut padded = encoded.clone();
+        padded.push(0);
+        assert!(decode_bitmap(&padded).is_err());
+    }
+
+    #[test]
+    fn huge_claimed_lengths_are_errors_not_aborts() {
+        // Used to push a 2^34-bit run bit by bit before noticing the input had run out
+        let mut bits = BitWriter::new();
+        write_gamma(&mut bits, 1);
+        write_gamma(&mut bits, 1 << 34);
+        let mut bomb = (1u64 << 60).to_le_bytes().to_vec();
+        bomb.extend(bits.into_bytes());
+        assert!(bomb.len() < 40);
+        assert_eq!(decode_bitmap(&bomb), Err("run lengths end before the bitmap does".to_string()));
+
+        // Runs that really do cover an impossible length
+        let mut bits = BitWriter::new();
+        write_gamma(&mut bits, (1 << 62) + 1);
+        let mut exact = (1u64 << 59).to_le_bytes().to_vec();
+        exact.extend(bits.into_bytes());
+        assert_eq!(decode_bitmap(&exact), Err("bitmap length does not fit in memory".to_string()));
+    }
+}
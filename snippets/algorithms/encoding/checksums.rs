@@ -0,0 +1,445 @@
+//! Checksums and Hashes in Rust
+//!
+//! Four ways to boil a byte stream down to a short fingerprint, from cheapest to
+//! strongest:
+//! - Adler-32 keeps two running sums mod 65521: the bytes, and the sums so far. It is
+//!   fast but weak on short inputs, where the sums barely leave the low bits.
+//! - CRC-32 is the remainder of dividing the input, as a polynomial over GF(2), by a
+//!   fixed degree-32 polynomial. It catches every burst error up to 32 bits; a 256-entry
+//!   table handles a whole byte per step instead of one bit.
+//! - FNV-1a XORs in each byte and multiplies by a prime. It spreads bits well, which is
+//!   what hash tables want, but guarantees nothing about errors.
+//! - SHA-256 is a cryptographic hash: 64 rounds of mixing per 64-byte block, designed so
+//!   that nobody can find two inputs with the same digest.
+//!
+//! All of them implement `Digest`, so input can arrive in pieces of any size. The
+//! SHA-256 here is for learning how it works: it is not constant-time or tuned, so use a
+//! vetted crate for anything real. The other three are not security measures at all.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Digest Trait ==========
+
+/// A hash fed incrementally; `finish` can be called at any point without ending the stream
+pub trait Digest: Default {
+    type Output;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finish(&self) -> Self::Output;
+
+    /// Hash of one complete input
+    fn digest(data: &[u8]) -> Self::Output {
+        let mut hasher = Self::default();
+        hasher.update(data);
+        hasher.finish()
+    }
+}
+
+// ========== CRC-32 ==========
+
+/// The IEEE polynomial 0x04C11DB7 bit-reversed, since this CRC processes the low bit first
+const CRC32_POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Remainder of each possible byte, worked out once at compile time
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ CRC32_POLYNOMIAL } else { crc >> 1 };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+};
+
+/// CRC-32 as used by zip, gzip, PNG and Ethernet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32 {
+    /// Kept inverted, which is what makes leading zero bytes change the result
+    state: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32 { state: !0 }
+    }
+}
+
+impl Digest for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state = (self.state >> 8) ^ CRC32_TABLE[((self.state ^ u32::from(byte)) & 0xff) as usize];
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+// ========== Adler-32 ==========
+
+/// Largest prime below 2^16
+const ADLER_MODULUS: u32 = 65_521;
+
+/// Bytes that can be summed before `b` could overflow a u32, so the modulo runs once per chunk
+const ADLER_CHUNK: usize = 5_552;
+
+/// Adler-32 as used by zlib
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Adler32 { a: 1, b: 0 }
+    }
+}
+
+impl Digest for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(ADLER_CHUNK) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= ADLER_MODULUS;
+            self.b %= ADLER_MODULUS;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+// ========== FNV-1a ==========
+
+/// 32-bit FNV-1a
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1a32(u32);
+
+impl Default for Fnv1a32 {
+    fn default() -> Self {
+        Fnv1a32(0x811c_9dc5)
+    }
+}
+
+impl Digest for Fnv1a32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 = (self.0 ^ u32::from(byte)).wrapping_mul(0x0100_0193);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        self.0
+    }
+}
+
+/// 64-bit FNV-1a
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1a64(u64);
+
+impl Default for Fnv1a64 {
+    fn default() -> Self {
+        Fnv1a64(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Digest for Fnv1a64 {
+    type Output = u64;
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// ========== SHA-256 ==========
+
+/// First 32 bits of the fractional parts of the square roots of the first 8 primes
+const SHA256_INITIAL: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// First 32 bits of the fractional parts of the cube roots of the first 64 primes
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 (FIPS 180-4), written for reading rather than speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sha256 {
+    state: [u32; 8],
+    /// Bytes of the current block received so far
+    block: [u8; 64],
+    block_len: usize,
+    /// Total input length in bytes, which goes into the padding
+    length: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Sha256 { state: SHA256_INITIAL, block: [0; 64], block_len: 0, length: 0 }
+    }
+}
+
+impl Sha256 {
+    fn compress(state: &mut [u32; 8], block: &[u8; 64]) {
+        // Message schedule: the 16 input words stretched to one word per round
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (&k, &w) in SHA256_ROUND_CONSTANTS.iter().zip(&w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choose = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(choose).wrapping_add(k).wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (word, add) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(add);
+        }
+    }
+}
+
+impl Digest for Sha256 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+        while !data.is_empty() {
+            let take = (64 - self.block_len).min(data.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+            if self.block_len == 64 {
+                Sha256::compress(&mut self.state, &self.block);
+                self.block_len = 0;
+            }
+        }
+    }
+
+    /// Pads a copy: a 1 bit, zeros up to 8 bytes short of a block, then the bit length
+    fn finish(&self) -> [u8; 32] {
+        let mut state = self.state;
+        let mut block = [0u8; 64];
+        block[..self.block_len].copy_from_slice(&self.block[..self.block_len]);
+        block[self.block_len] = 0x80;
+        if self.block_len >= 56 {
+            Sha256::compress(&mut state, &block);
+            block = [0; 64];
+        }
+        block[56..].copy_from_slice(&(self.length * 8).to_be_bytes());
+        Sha256::compress(&mut state, &block);
+
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+// ========== Demo Code ==========
+
+fn hex(bytes: &[u8]) -> String {
+    crate::encoding::HEX.encode(bytes)
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ChecksumsDemo;
+
+impl Demo for ChecksumsDemo {
+    fn name(&self) -> &'static str {
+        "checksums"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "CRC-32, Adler-32, FNV-1a and SHA-256 behind one streaming Digest trait"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Digests =====")?;
+        for input in ["", "a", "123456789", "Wikipedia"] {
+            writeln!(out, "{:?}", input)?;
+            let data = input.as_bytes();
+            writeln!(out, "  crc32    {:08x}", Crc32::digest(data))?;
+            writeln!(out, "  adler32  {:08x}", Adler32::digest(data))?;
+            writeln!(out, "  fnv1a32  {:08x}", Fnv1a32::digest(data))?;
+            writeln!(out, "  fnv1a64  {:016x}", Fnv1a64::digest(data))?;
+            writeln!(out, "  sha256   {}", hex(&Sha256::digest(data)))?;
+        }
+
+        writeln!(out, "\n===== Streaming =====")?;
+        let message = b"The quick brown fox jumps over the lazy dog";
+        let mut sha = Sha256::default();
+        for (i, word) in message.split_inclusive(|&b| b == b' ').enumerate() {
+            sha.update(word);
+            if i % 3 == 2 {
+                writeln!(out, "after {:>2} bytes  {}", sha.length, hex(&sha.finish()))?;
+            }
+        }
+        writeln!(out, "all {} bytes     {}", message.len(), hex(&sha.finish()))?;
+        writeln!(out, "one-shot         {}", hex(&Sha256::digest(message)))?;
+
+        writeln!(out, "\n===== One Flipped Bit =====")?;
+        let mut flipped = *message;
+        flipped[10] ^= 0x01;
+        writeln!(out, "{:?}", String::from_utf8_lossy(&flipped))?;
+        writeln!(out, "  crc32    {:08x} -> {:08x}", Crc32::digest(message), Crc32::digest(&flipped))?;
+        writeln!(out, "  adler32  {:08x} -> {:08x}", Adler32::digest(message), Adler32::digest(&flipped))?;
+        writeln!(out, "  fnv1a32  {:08x} -> {:08x}", Fnv1a32::digest(message), Fnv1a32::digest(&flipped))?;
+        let (before, after) = (Sha256::digest(message), Sha256::digest(&flipped));
+        let changed: u32 = before.iter().zip(&after).map(|(x, y)| (x ^ y).count_ones()).sum();
+        writeln!(out, "  sha256   {} of 256 digest bits changed", changed)?;
+
+        writeln!(out, "\n===== Adler-32 on Short Input =====")?;
+        // Sums of a few bytes never reach the modulus, so the high bits stay zero
+        for input in ["ab", "ba", "abc", "acb"] {
+            writeln!(out, "{:<6} adler32 {:08x}  crc32 {:08x}", format!("{:?}", input), Adler32::digest(input.as_bytes()), Crc32::digest(input.as_bytes()))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ChecksumsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    #[test]
+    fn crc32_vectors() {
+        assert_eq!(Crc32::digest(b""), 0);
+        assert_eq!(Crc32::digest(b"123456789"), 0xcbf4_3926);
+        assert_eq!(Crc32::digest(b"The quick brown fox jumps over the lazy dog"), 0x414f_a339);
+    }
+
+    #[test]
+    fn adler32_vectors() {
+        assert_eq!(Adler32::digest(b""), 1);
+        assert_eq!(Adler32::digest(b"Wikipedia"), 0x11e6_0398);
+        // Long enough to need the modulo many times over; the value zlib gives
+        assert_eq!(Adler32::digest(&[0xff; 100_000]), 0x149a_302c);
+    }
+
+    #[test]
+    fn fnv1a_vectors() {
+        assert_eq!(Fnv1a32::digest(b""), 0x811c_9dc5);
+        assert_eq!(Fnv1a32::digest(b"a"), 0xe40c_292c);
+        assert_eq!(Fnv1a32::digest(b"foobar"), 0xbf9c_f968);
+        assert_eq!(Fnv1a64::digest(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(Fnv1a64::digest(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(Fnv1a64::digest(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn sha256_fips_vectors() {
+        let cases: [(&[u8], &str); 3] = [
+            (b"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            (b"abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(hex(&Sha256::digest(input)), expected, "{:?}", String::from_utf8_lossy(input));
+        }
+        let mut sha = Sha256::default();
+        for _ in 0..1000 {
+            sha.update(&[b'a'; 1000]);
+        }
+        assert_eq!(hex(&sha.finish()), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+
+    #[test]
+    fn sha256_padding_boundaries() {
+        // 55 bytes is the longest input whose padding fits in one block
+        assert_eq!(hex(&Sha256::digest(&[b'a'; 55])), "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318");
+        assert_eq!(hex(&Sha256::digest(&[b'a'; 56])), "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a");
+        assert_eq!(hex(&Sha256::digest(&[b'a'; 64])), "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb");
+    }
+
+    fn streams_like_one_shot<D: Digest>(data: &[u8], rng: &mut Pcg32)
+    where
+        D::Output: PartialEq + std::fmt::Debug,
+    {
+        let mut hasher = D::default();
+        let mut rest = data;
+        while !rest.is_empty() {
+            let (piece, tail) = rest.split_at((rng.below(150) as usize).min(rest.len()));
+            hasher.update(piece);
+            rest = tail;
+        }
+        assert_eq!(hasher.finish(), D::digest(data), "{} bytes", data.len());
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let mut rng = Pcg32::new(970, 0);
+        for len in [0, 1, 55, 56, 63, 64, 65, 1000, 20_000] {
+            let data: Vec<u8> = (0..len).map(|_| rng.below(256) as u8).collect();
+            streams_like_one_shot::<Crc32>(&data, &mut rng);
+            streams_like_one_shot::<Adler32>(&data, &mut rng);
+            streams_like_one_shot::<Fnv1a32>(&data, &mut rng);
+            streams_like_one_shot::<Fnv1a64>(&data, &mut rng);
+            streams_like_one_shot::<Sha256>(&data, &mut rng);
+        }
+    }
+
+    #[test]
+    fn finish_does_not_end_the_stream() {
+        let mut sha = Sha256::default();
+        sha.update(b"ab");
+        assert_eq!(sha.finish(), Sha256::digest(b"ab"));
+        sha.update(b"c");
+        assert_eq!(sha.finish(), Sha256::digest(b"abc"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Base64, Base32 and Hex in Rust
+//!
+//! The RFC 4648 encodings all do the same thing with a different group size: read the
+//! input as one long bit string, cut it into `k`-bit pieces and print each piece as one
+//! of `2^k` symbols.
+//! - Hex (Base16) uses 4 bits per character, so every byte is exactly two characters.
+//! - Base32 uses 5 bits; 5 bytes make 8 characters, and a shorter final group is padded
+//!   with `=` up to 8. Only upper-case letters and `2-7`, so it survives case folding.
+//! - Base64 uses 6 bits; 3 bytes make 4 characters, padded up to 4. The URL-safe
+//!   alphabet swaps `+/` for `-_`, which need no escaping in URLs and file names.
+//!
+//! Decoding is strict: every character must be in the alphabet, padding must be present
+//! exactly when the encoding uses it and of the right length, and the unused low bits
+//! of the last character must be zero (RFC 4648 §3.5), so each byte string has exactly
+//! one accepted encoding. Errors say what was wrong and where.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Errors ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A character outside the alphabet, or `=` anywhere but the end
+    InvalidCharacter { index: usize, character: char },
+    /// No number of whole bytes encodes to this many characters
+    InvalidLength { length: usize },
+    /// Padding was missing or of the wrong length
+    InvalidPadding { expected: usize, found: usize },
+    /// The last character carries bits past the end of the data that are not zero
+    TrailingBits { index: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidCharacter { index, character } => {
+                write!(f, "invalid character {:?} at index {}", character, index)
+            }
+            DecodeError::InvalidLength { length } => write!(f, "{} characters cannot encode whole bytes", length),
+            DecodeError::InvalidPadding { expected, found } => {
+                write!(f, "expected {} padding characters, found {}", expected, found)
+            }
+            DecodeError::TrailingBits { index } => {
+                write!(f, "character at index {} has non-zero bits past the end of the data", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// ========== Encodings ==========
+
+/// One alphabet and padding rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    name: &'static str,
+    symbols: &'static [u8],
+    /// Padded encodings fill the last group up to this many characters
+    group: Option<usize>,
+    /// Accept lower- and upper-case letters alike when decoding
+    ignore_case: bool,
+}
+
+const BASE64_SYMBOLS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_SYMBOLS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const BASE32_SYMBOLS: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const HEX_SYMBOLS: &[u8; 16] = b"0123456789abcdef";
+
+const PAD: u8 = b'=';
+
+pub const BASE64: Encoding = Encoding { name: "base64", symbols: BASE64_SYMBOLS, group: Some(4), ignore_case: false };
+pub const BASE64_NO_PAD: Encoding = BASE64.without_padding("base64 (no padding)");
+pub const BASE64_URL: Encoding = Encoding { name: "base64url", symbols: BASE64_URL_SYMBOLS, group: Some(4), ignore_case: false };
+pub const BASE64_URL_NO_PAD: Encoding = BASE64_URL.without_padding("base64url (no padding)");
+pub const BASE32: Encoding = Encoding { name: "base32", symbols: BASE32_SYMBOLS, group: Some(8), ignore_case: false };
+pub const BASE32_NO_PAD: Encoding = BASE32.without_padding("base32 (no padding)");
+/// Lower case out, either case in
+pub const HEX: Encoding = Encoding { name: "hex", symbols: HEX_SYMBOLS, group: None, ignore_case: true };
+
+pub const ALL: [Encoding; 7] = [BASE64, BASE64_NO_PAD, BASE64_URL, BASE64_URL_NO_PAD, BASE32, BASE32_NO_PAD, HEX];
+
+impl Encoding {
+    const fn without_padding(self, name: &'static str) -> Encoding {
+        Encoding { name, group: None, ..self }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Bits per character: 4, 5 or 6
+    fn bits(&self) -> usize {
+        self.symbols.len().trailing_zeros() as usize
+    }
+
+    /// Characters needed for `n` bytes, padding included
+    pub fn encoded_len(&self, n: usize) -> usize {
+        let chars = (n * 8).div_ceil(self.bits());
+        match self.group {
+            Some(group) => chars.next_multiple_of(group),
+            None => chars,
+        }
+    }
+
+    pub fn encode(&self, data: &[u8]) -> String {
+        let bits = self.bits();
+        let mask = (1 << bits) - 1;
+        let mut out = String::with_capacity(self.encoded_len(data.len()));
+        let (mut buffer, mut buffered) = (0u32, 0);
+
+        for &byte in data {
+            buffer = (buffer << 8) | u32::from(byte);
+            buffered += 8;
+            while buffered >= bits {
+                buffered -= bits;
+                out.push(self.symbols[(buffer >> buffered) as usize & mask] as char);
+            }
+        }
+        if buffered > 0 {
+            // Fill the last character out with zero bits
+            out.push(self.symbols[(buffer << (bits - buffered)) as usize & mask] as char);
+        }
+        if let Some(group) = self.group {
+            while out.len() % group != 0 {
+                out.push(PAD as char);
+            }
+        }
+        out
+    }
+
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, DecodeError> {
+        let input = text.as_bytes();
+        let data = match self.group {
+            Some(group) => {
+                let data = input.strip_suffix(&[PAD]).map_or(input, |rest| {
+                    let kept = rest.iter().rposition(|&c| c != PAD).map_or(0, |i| i + 1);
+                    &rest[..kept]
+                });
+                let expected = (group - data.len() % group) % group;
+                let found = input.len() - data.len();
+                if found != expected && self.is_valid_length(data.len()) {
+                    return Err(DecodeError::InvalidPadding { expected, found });
+                }
+                data
+            }
+            None => input,
+        };
+        if !self.is_valid_length(data.len()) {
+            return Err(DecodeError::InvalidLength { length: data.len() });
+        }
+
+        let table = self.decode_table();
+        let bits = self.bits();
+        let mut out = Vec::with_capacity(data.len() * bits / 8);
+        let (mut buffer, mut buffered) = (0u32, 0);
+        for (index, &c) in data.iter().enumerate() {
+            let value = table[c as usize];
+            if value == INVALID {
+                let character = text[index..].chars().next().unwrap_or(c as char);
+                return Err(DecodeError::InvalidCharacter { index, character });
+            }
+            buffer = (buffer << bits) | u32::from(value);
+            buffered += bits;
+            if buffered >= 8 {
+                buffered -= 8;
+                out.push((buffer >> buffered) as u8);
+            }
+        }
+        if buffer & ((1 << buffered) - 1) != 0 {
+            return Err(DecodeError::TrailingBits { index: data.len() - 1 });
+        }
+        Ok(out)
+    }
+
+    /// Whether `n` characters (padding excluded) are exactly some number of whole bytes:
+    /// the bits left over after the last byte must not fill a whole character
+    fn is_valid_length(&self, n: usize) -> bool {
+        n * self.bits() % 8 < self.bits()
+    }
+
+    fn decode_table(&self) -> [u8; 256] {
+        let mut table = [INVALID; 256];
+        for (value, &symbol) in self.symbols.iter().enumerate() {
+            table[symbol as usize] = value as u8;
+            if self.ignore_case {
+                table[symbol.to_ascii_uppercase() as usize] = value as u8;
+            }
+        }
+        table
+    }
+}
+
+/// Decode-table entry for bytes outside the alphabet
+const INVALID: u8 = u8::MAX;
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct EncodingDemo;
+
+impl Demo for EncodingDemo {
+    fn name(&self) -> &'static str {
+        "encoding"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "RFC 4648 Base64, Base32 and hex, with strict decoding"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== RFC 4648 Test Vectors =====")?;
+        writeln!(out, "{:<8} {:<10} {:<18} hex", "input", "base64", "base32")?;
+        for input in ["", "f", "fo", "foo", "foob", "fooba", "foobar"] {
+            let data = input.as_bytes();
+            let row = format!("{:<8} {:<10} {:<18} {}", format!("{:?}", input), BASE64.encode(data), BASE32.encode(data), HEX.encode(data));
+            writeln!(out, "{}", row.trim_end())?;
+        }
+
+        writeln!(out, "\n===== Every Variant =====")?;
+        let data = [0xfb, 0xff, 0xbf, 0x00];
+        writeln!(out, "bytes {:02x?}", data)?;
+        for encoding in ALL {
+            writeln!(out, "  {:<24} {}", encoding.name(), encoding.encode(&data))?;
+        }
+
+        writeln!(out, "\n===== Size Overhead =====")?;
+        for (encoding, per) in [(HEX, "1 byte -> 2 chars"), (BASE32, "5 bytes -> 8 chars"), (BASE64, "3 bytes -> 4 chars")] {
+            writeln!(out, "{:<8} {:<20} 1000 bytes -> {} chars", encoding.name(), per, encoding.encoded_len(1000))?;
+        }
+
+        writeln!(out, "\n===== Rejected Input =====")?;
+        let cases = [
+            (BASE64, "Zm9v!mFy"),
+            (BASE64, "Zm9vY"),
+            (BASE64, "Zm9vYg="),
+            (BASE64, "Zm9vYg"),
+            (BASE64_NO_PAD, "Zm9vYg=="),
+            (BASE64, "Zm9vYh=="),
+            (BASE64_URL, "+/8="),
+            (BASE32, "MZXW6YQ"),
+            (BASE32, "mzxw6yq="),
+            (HEX, "abc"),
+            (HEX, "zz"),
+        ];
+        for (encoding, text) in cases {
+            match encoding.decode(text) {
+                Ok(data) => writeln!(out, "{:<24} {:<11} ok {:?}", encoding.name(), format!("{:?}", text), data)?,
+                Err(e) => writeln!(out, "{:<24} {:<11} {}", encoding.name(), format!("{:?}", text), e)?,
+            }
+        }
+        writeln!(out, "Hex decoding ignores case: {:?}", HEX.decode("C0FFEE").map(|d| HEX.encode(&d)))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&EncodingDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    const RFC_INPUTS: [&str; 7] = ["", "f", "fo", "foo", "foob", "fooba", "foobar"];
+
+    fn check_vectors(encoding: Encoding, expected: [&str; 7]) {
+        for (input, encoded) in RFC_INPUTS.iter().zip(expected) {
+            assert_eq!(encoding.encode(input.as_bytes()), encoded, "{} of {:?}", encoding.name(), input);
+            assert_eq!(encoding.decode(encoded), Ok(input.as_bytes().to_vec()), "{} of {:?}", encoding.name(), encoded);
+        }
+    }
+
+    #[test]
+    fn rfc_4648_vectors() {
+        check_vectors(BASE64, ["", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=", "Zm9vYmFy"]);
+        check_vectors(BASE64_NO_PAD, ["", "Zg", "Zm8", "Zm9v", "Zm9vYg", "Zm9vYmE", "Zm9vYmFy"]);
+        check_vectors(BASE32, ["", "MY======", "MZXQ====", "MZXW6===", "MZXW6YQ=", "MZXW6YTB", "MZXW6YTBOI======"]);
+        check_vectors(BASE32_NO_PAD, ["", "MY", "MZXQ", "MZXW6", "MZXW6YQ", "MZXW6YTB", "MZXW6YTBOI"]);
+        check_vectors(HEX, ["", "66", "666f", "666f6f", "666f6f62", "666f6f6261", "666f6f626172"]);
+        assert_eq!(HEX.decode("666F6F626172"), Ok(b"foobar".to_vec()));
+    }
+
+    #[test]
+    fn url_safe_alphabet() {
+        let data = [0xfb, 0xff];
+        assert_eq!(BASE64.encode(&data), "+/8=");
+        assert_eq!(BASE64_URL.encode(&data), "-_8=");
+        assert_eq!(BASE64_URL_NO_PAD.encode(&data), "-_8");
+        assert_eq!(BASE64_URL.decode("+/8="), Err(DecodeError::InvalidCharacter { index: 0, character: '+' }));
+        assert_eq!(BASE64.decode("-_8="), Err(DecodeError::InvalidCharacter { index: 0, character: '-' }));
+    }
+
+    #[test]
+    fn detailed_errors() {
+        assert_eq!(BASE64.decode("Zm9vY"), Err(DecodeError::InvalidLength { length: 5 }));
+        assert_eq!(BASE64_NO_PAD.decode("Zm9vY"), Err(DecodeError::InvalidLength { length: 5 }));
+        assert_eq!(BASE64.decode("Zm9vYg"), Err(DecodeError::InvalidPadding { expected: 2, found: 0 }));
+        assert_eq!(BASE64.decode("Zm9vYg="), Err(DecodeError::InvalidPadding { expected: 2, found: 1 }));
+        assert_eq!(BASE64.decode("Zm8=Zm8="), Err(DecodeError::InvalidCharacter { index: 3, character: '=' }));
+        assert_eq!(BASE64.decode("Zm9v===="), Err(DecodeError::InvalidPadding { expected: 0, found: 4 }));
+        assert_eq!(BASE64.decode("Zg=A"), Err(DecodeError::InvalidCharacter { index: 2, character: '=' }));
+        assert_eq!(BASE64_NO_PAD.decode("Zg=="), Err(DecodeError::InvalidCharacter { index: 2, character: '=' }));
+        assert_eq!(BASE64.decode("Zh=="), Err(DecodeError::TrailingBits { index: 1 }));
+        assert_eq!(BASE32.decode("MZXW6Y=="), Err(DecodeError::InvalidLength { length: 6 }));
+        assert_eq!(BASE32.decode("MZXQ==="), Err(DecodeError::InvalidPadding { expected: 4, found: 3 }));
+        assert_eq!(BASE32.decode("MZX====="), Err(DecodeError::InvalidLength { length: 3 }));
+        assert_eq!(BASE32.decode("mzxq===="), Err(DecodeError::InvalidCharacter { index: 0, character: 'm' }));
+        assert_eq!(HEX.decode("6g"), Err(DecodeError::InvalidCharacter { index: 1, character: 'g' }));
+        assert_eq!(HEX.decode("0é0"), Err(DecodeError::InvalidCharacter { index: 1, character: 'é' }));
+        assert_eq!(DecodeError::TrailingBits { index: 3 }.to_string(), "character at index 3 has non-zero bits past the end of the data");
+    }
+
+    #[test]
+    fn padding_must_match_the_data() {
+        assert_eq!(BASE64.decode("Zg=="), Ok(b"f".to_vec()));
+        assert_eq!(BASE32.decode("MY======"), Ok(b"f".to_vec()));
+        assert_eq!(BASE32.decode("MZXW6Y==").unwrap_err(), DecodeError::InvalidLength { length: 6 });
+        assert_eq!(BASE32.decode("MZXW6YQ="), Ok(b"foob".to_vec()));
+        assert_eq!(BASE32.decode("MZXQ====MY======").unwrap_err(), DecodeError::InvalidCharacter { index: 4, character: '=' });
+    }
+
+    #[test]
+    fn encoded_len_matches_encode() {
+        for encoding in ALL {
+            for n in 0..40 {
+                assert_eq!(encoding.encode(&vec![0xa5; n]).len(), encoding.encoded_len(n), "{} of {} bytes", encoding.name(), n);
+            }
+        }
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = Pcg32::new(969, 0);
+        for _ in 0..500 {
+            let data: Vec<u8> = (0..rng.below(64)).map(|_| rng.below(256) as u8).collect();
+            for encoding in ALL {
+                let encoded = encoding.encode(&data);
+                assert_eq!(encoding.decode(&encoded), Ok(data.clone()), "{} of {:?}", encoding.name(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn random_text_decodes_canonically_or_fails() {
+        // Whatever is accepted must be the one encoding of what it decodes to
+        let mut rng = Pcg32::new(969, 1);
+        for encoding in ALL {
+            let mut accepted = 0;
+            for _ in 0..2_000 {
+                let text: String = (0..rng.below(12))
+                    .map(|_| match rng.below(8) {
+                        0 => '=',
+                        _ => encoding.symbols[rng.below(encoding.symbols.len() as u64) as usize] as char,
+                    })
+                    .collect();
+                if let Ok(data) = encoding.decode(&text) {
+                    assert_eq!(encoding.encode(&data), text, "{}", encoding.name());
+                    accepted += 1;
+                }
+            }
+            assert!(accepted > 0, "{} accepted nothing", encoding.name());
+        }
+    }
+}
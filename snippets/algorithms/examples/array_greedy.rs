@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::array_greedy::run_demo();
+}
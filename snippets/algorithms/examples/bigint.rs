@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::bigint::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::bit_tricks::run_demo();
+}
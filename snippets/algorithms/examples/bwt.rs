@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::bwt::run_demo();
+}
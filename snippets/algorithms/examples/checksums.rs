@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::checksums::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::combinatorics::run_demo();
+}
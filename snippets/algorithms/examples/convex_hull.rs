@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::convex_hull::run_demo();
+}
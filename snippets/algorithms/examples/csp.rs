@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::csp::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::encoding::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::factorization::run_demo();
+}
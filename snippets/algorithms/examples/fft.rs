@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::fft::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::fractional_knapsack::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::graph_traversal::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::gray_code::run_demo();
+}
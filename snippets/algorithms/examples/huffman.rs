@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::huffman::run_demo();
+}
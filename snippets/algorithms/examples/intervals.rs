@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::intervals::run_demo();
+}
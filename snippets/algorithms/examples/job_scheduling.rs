@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::job_scheduling::run_demo();
+}
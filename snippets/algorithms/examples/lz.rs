@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::lz::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::matrix::run_demo();
+}
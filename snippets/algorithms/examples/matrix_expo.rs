@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::matrix_expo::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::miller_rabin::run_demo();
+}
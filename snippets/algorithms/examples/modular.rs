@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::modular::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::n_queens::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::polygon::run_demo();
+}
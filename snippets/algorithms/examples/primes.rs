@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::primes::run_demo();
+}
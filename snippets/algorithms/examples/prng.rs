@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::prng::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::quadtree::run_demo();
+}
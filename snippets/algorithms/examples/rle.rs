@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::rle::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::rsa_demo::run_demo();
+}
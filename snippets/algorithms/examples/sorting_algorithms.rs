@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::sorting_algorithms::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::statistics::run_demo();
+}
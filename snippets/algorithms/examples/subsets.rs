@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::subsets::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::sudoku::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    algorithms::word_search::run_demo();
+}
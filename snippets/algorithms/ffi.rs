@@ -0,0 +1,271 @@
+//! C bindings for the sorts and graph traversals
+//!
+//! Built with the crate's `ffi` feature, which also regenerates `include/tech_notes.h`
+//! with cbindgen. The functions follow the usual rules for a C API over Rust code:
+//!
+//! - Every function returns a `TnStatus` instead of panicking or aborting, and a panic
+//!   is caught before it can unwind into C.
+//! - Pointers are checked for null before they are dereferenced, and strings are
+//!   borrowed as `CStr` and must be UTF-8.
+//! - A `TnGraph` is an opaque handle: C only ever sees the pointer `tn_graph_new`
+//!   returns and gives it back to `tn_graph_free`.
+//! - Results that have no fixed size are written into a caller-provided buffer, with
+//!   the required size reported when it is too small.
+//!
+//! See `tests/c/ffi_test.c` for a C program using all of it.
+
+use crate::graph_traversal::{Graph, Traversal};
+use crate::sorting_algorithms::{sort_traced, SortAlgorithm};
+use std::ffi::{c_char, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+// ========== Status Codes ==========
+
+/// Result of every `tn_` function.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnStatus {
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// An algorithm or traversal name that doesn't exist
+    UnknownName = 3,
+    /// The start vertex is not in the graph
+    UnknownVertex = 4,
+    /// The output buffer can't hold the result; see the `needed` out-parameter
+    BufferTooSmall = 5,
+    /// The Rust code panicked; the call had no effect
+    Panicked = 6,
+}
+
+/// A static, NUL-terminated description of `status`.
+#[no_mangle]
+pub extern "C" fn tn_status_message(status: TnStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        TnStatus::Ok => c"ok",
+        TnStatus::NullPointer => c"null pointer argument",
+        TnStatus::InvalidUtf8 => c"string argument is not valid UTF-8",
+        TnStatus::UnknownName => c"unknown algorithm or traversal name",
+        TnStatus::UnknownVertex => c"start vertex is not in the graph",
+        TnStatus::BufferTooSmall => c"output buffer is too small",
+        TnStatus::Panicked => c"internal error",
+    };
+    message.as_ptr()
+}
+
+/// Runs `f`, turning a panic into `Panicked` so it never unwinds into C.
+fn guard(f: impl FnOnce() -> Result<(), TnStatus>) -> TnStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => TnStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => TnStatus::Panicked,
+    }
+}
+
+/// Borrows a C string as `&str`.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string that outlives `'a`.
+unsafe fn borrow_str<'a>(s: *const c_char) -> Result<&'a str, TnStatus> {
+    if s.is_null() {
+        return Err(TnStatus::NullPointer);
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| TnStatus::InvalidUtf8)
+}
+
+// ========== Sorting ==========
+
+/// Sorts `len` values at `values` in place with merge sort.
+///
+/// # Safety
+///
+/// `values` must point to `len` initialized `int32_t`s, or may be null when `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn tn_sort_i32(values: *mut i32, len: usize) -> TnStatus {
+    tn_sort_i32_with(c"merge".as_ptr(), values, len)
+}
+
+/// Sorts `len` values at `values` in place with the named algorithm (`"quick"`,
+/// `"heap"`, ...; see `SortAlgorithm::name`).
+///
+/// # Safety
+///
+/// `algorithm` must be a NUL-terminated string, and `values` must point to `len`
+/// initialized `int32_t`s, or may be null when `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn tn_sort_i32_with(algorithm: *const c_char, values: *mut i32, len: usize) -> TnStatus {
+    guard(|| {
+        let name = borrow_str(algorithm)?;
+        let algorithm = SortAlgorithm::from_name(name).ok_or(TnStatus::UnknownName)?;
+        if len == 0 {
+            return Ok(());
+        }
+        if values.is_null() {
+            return Err(TnStatus::NullPointer);
+        }
+
+        let values = slice::from_raw_parts_mut(values, len);
+        let sorted = sort_traced(algorithm, values, |_| {});
+        values.copy_from_slice(&sorted);
+        Ok(())
+    })
+}
+
+// ========== Graph ==========
+
+/// An undirected graph owned by Rust; C holds it only through a pointer.
+pub struct TnGraph {
+    graph: Graph,
+}
+
+/// A new, empty graph. Release it with `tn_graph_free`.
+#[no_mangle]
+pub extern "C" fn tn_graph_new() -> *mut TnGraph {
+    Box::into_raw(Box::new(TnGraph { graph: Graph::new() }))
+}
+
+/// Frees a graph from `tn_graph_new`. Null is ignored.
+///
+/// # Safety
+///
+/// `graph` must be null or a pointer from `tn_graph_new` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tn_graph_free(graph: *mut TnGraph) {
+    if !graph.is_null() {
+        drop(Box::from_raw(graph));
+    }
+}
+
+/// Adds the undirected edge `a`-`b`, creating either vertex if needed.
+///
+/// # Safety
+///
+/// `graph` must be a live pointer from `tn_graph_new`, and `a` and `b` NUL-terminated
+/// strings.
+#[no_mangle]
+pub unsafe extern "C" fn tn_graph_add_edge(graph: *mut TnGraph, a: *const c_char, b: *const c_char) -> TnStatus {
+    guard(|| {
+        let graph = graph.as_mut().ok_or(TnStatus::NullPointer)?;
+        let (a, b) = (borrow_str(a)?, borrow_str(b)?);
+        graph.graph.add_edge(a, b);
+        Ok(())
+    })
+}
+
+/// Runs the named traversal (`"bfs"`, `"dfs-recursive"` or `"dfs-iterative"`) from
+/// `start` and writes the visit order into `out` as a NUL-terminated, space-separated
+/// list, e.g. `"A B C"`.
+///
+/// `*needed` is always set to the buffer size the result takes, including the NUL, so a
+/// caller can pass a null `out` with `out_len` 0 to ask for the size first. When
+/// `out_len` is too small nothing is written and `BufferTooSmall` is returned.
+///
+/// # Safety
+///
+/// `graph` must be a live pointer from `tn_graph_new`, `traversal` and `start`
+/// NUL-terminated strings, `out` valid for `out_len` bytes (or null when `out_len` is
+/// 0) and `needed` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn tn_graph_traverse(
+    graph: *const TnGraph,
+    traversal: *const c_char,
+    start: *const c_char,
+    out: *mut c_char,
+    out_len: usize,
+    needed: *mut usize,
+) -> TnStatus {
+    guard(|| {
+        let graph = graph.as_ref().ok_or(TnStatus::NullPointer)?;
+        let needed = needed.as_mut().ok_or(TnStatus::NullPointer)?;
+        let traversal = Traversal::from_name(borrow_str(traversal)?).ok_or(TnStatus::UnknownName)?;
+        let start = borrow_str(start)?;
+        if !graph.graph.contains(start) {
+            return Err(TnStatus::UnknownVertex);
+        }
+
+        let order = graph.graph.traverse(traversal, start, |_| {}).join(" ");
+        *needed = order.len() + 1;
+        if out_len < *needed {
+            return Err(TnStatus::BufferTooSmall);
+        }
+        if out.is_null() {
+            return Err(TnStatus::NullPointer);
+        }
+
+        let out = slice::from_raw_parts_mut(out.cast::<u8>(), *needed);
+        out[..order.len()].copy_from_slice(order.as_bytes());
+        out[order.len()] = 0;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn sorts_in_place() {
+        let mut values = [5, -1, 3, 3, 0];
+        assert_eq!(unsafe { tn_sort_i32(values.as_mut_ptr(), values.len()) }, TnStatus::Ok);
+        assert_eq!(values, [-1, 0, 3, 3, 5]);
+
+        let mut values = [2, 1];
+        let status = unsafe { tn_sort_i32_with(c"heap".as_ptr(), values.as_mut_ptr(), 2) };
+        assert_eq!(status, TnStatus::Ok);
+        assert_eq!(values, [1, 2]);
+    }
+
+    #[test]
+    fn sort_rejects_bad_arguments() {
+        let mut values = [2, 1];
+        assert_eq!(unsafe { tn_sort_i32(ptr::null_mut(), 2) }, TnStatus::NullPointer);
+        assert_eq!(unsafe { tn_sort_i32(ptr::null_mut(), 0) }, TnStatus::Ok);
+        let status = unsafe { tn_sort_i32_with(c"bogo".as_ptr(), values.as_mut_ptr(), 2) };
+        assert_eq!(status, TnStatus::UnknownName);
+        assert_eq!(values, [2, 1]);
+    }
+
+    #[test]
+    fn traverse_reports_the_size_it_needs() {
+        let graph = tn_graph_new();
+        unsafe {
+            assert_eq!(tn_graph_add_edge(graph, c"A".as_ptr(), c"B".as_ptr()), TnStatus::Ok);
+            assert_eq!(tn_graph_add_edge(graph, c"A".as_ptr(), c"C".as_ptr()), TnStatus::Ok);
+
+            let mut needed = 0;
+            let status = tn_graph_traverse(graph, c"bfs".as_ptr(), c"A".as_ptr(), ptr::null_mut(), 0, &mut needed);
+            assert_eq!(status, TnStatus::BufferTooSmall);
+            assert_eq!(needed, "A B C".len() + 1);
+
+            let mut buffer = vec![0 as c_char; needed];
+            let status =
+                tn_graph_traverse(graph, c"bfs".as_ptr(), c"A".as_ptr(), buffer.as_mut_ptr(), needed, &mut needed);
+            assert_eq!(status, TnStatus::Ok);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()), c"A B C");
+
+            let status = tn_graph_traverse(graph, c"bfs".as_ptr(), c"Z".as_ptr(), ptr::null_mut(), 0, &mut needed);
+            assert_eq!(status, TnStatus::UnknownVertex);
+            tn_graph_free(graph);
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_is_reported() {
+        let graph = tn_graph_new();
+        let invalid = b"\xff\0";
+        let status = unsafe { tn_graph_add_edge(graph, invalid.as_ptr().cast(), c"B".as_ptr()) };
+        assert_eq!(status, TnStatus::InvalidUtf8);
+        unsafe { tn_graph_free(graph) };
+    }
+
+    #[test]
+    fn every_status_has_a_message() {
+        let message = unsafe { CStr::from_ptr(tn_status_message(TnStatus::BufferTooSmall)) };
+        assert_eq!(message, c"output buffer is too small");
+    }
+}
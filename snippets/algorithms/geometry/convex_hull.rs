@@ -0,0 +1,330 @@
+//! Convex Hull in Rust
+//!
+//! The convex hull is the smallest convex polygon containing every point: a rubber band
+//! snapped around them. Both algorithms here are O(n log n), dominated by a sort, and
+//! both rest on one predicate, the orientation of three points:
+//! - `cross(o, a, b)` is twice the signed area of triangle `oab`: positive for a
+//!   counter-clockwise (left) turn, negative for clockwise, zero when collinear.
+//!   Floating-point coordinates can get the sign of a near-zero area wrong, and a hull
+//!   built on a wrong turn can come out non-convex, so points here have integer
+//!   coordinates and the cross product is computed exactly in `i128`, which has room
+//!   for coordinates up to `MAX_COORDINATE` in magnitude.
+//! - Graham scan sorts the points by angle around the lowest one, then walks them with
+//!   a stack, popping the top while it would make a right turn.
+//! - Andrew's monotone chain sorts by x and builds the lower and upper hulls with the
+//!   same stack walk; sorting by coordinates avoids angle ties entirely.
+//!
+//! Duplicates are removed and points on an edge of the hull (collinear with two hull
+//! vertices) are left out, so both return only the corners, counter-clockwise.
+
+use crate::prng::{Pcg32, Rng};
+use demo::Demo;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Points and Orientation ==========
+
+/// Largest coordinate magnitude `cross` handles exactly: differences stay below 2^63,
+/// so each product stays below 2^126 and their difference fits in `i128`
+pub const MAX_COORDINATE: i64 = 1 << 62;
+
+/// A point with integer coordinates; ordered by x, then y
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn distance_squared(self, other: Point) -> i128 {
+        let (dx, dy) = (i128::from(other.x) - i128::from(self.x), i128::from(other.y) - i128::from(self.y));
+        dx * dx + dy * dy
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// `(a - o) x (b - o)`, exact for coordinates within `±MAX_COORDINATE`
+pub fn cross(o: Point, a: Point, b: Point) -> i128 {
+    let (ax, ay) = (i128::from(a.x) - i128::from(o.x), i128::from(a.y) - i128::from(o.y));
+    let (bx, by) = (i128::from(b.x) - i128::from(o.x), i128::from(b.y) - i128::from(o.y));
+    ax * by - ay * bx
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    Collinear,
+}
+
+/// Which way the path `a -> b -> c` turns at `b`
+pub fn orientation(a: Point, b: Point, c: Point) -> Orientation {
+    match cross(a, b, c).cmp(&0) {
+        Ordering::Greater => Orientation::CounterClockwise,
+        Ordering::Less => Orientation::Clockwise,
+        Ordering::Equal => Orientation::Collinear,
+    }
+}
+
+// ========== Graham Scan ==========
+
+/// Hull corners counter-clockwise, starting from the lowest (then leftmost) point
+pub fn graham_scan(points: &[Point]) -> Vec<Point> {
+    let mut points = points.to_vec();
+    points.sort_unstable();
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let pivot = *points.iter().min_by_key(|p| (p.y, p.x)).expect("not empty");
+    // By angle around the pivot; equal angles nearest first, so the stack pops them
+    points.sort_by(|&a, &b| {
+        if a == pivot || b == pivot {
+            return (b == pivot).cmp(&(a == pivot));
+        }
+        match cross(pivot, a, b) {
+            0 => pivot.distance_squared(a).cmp(&pivot.distance_squared(b)),
+            area if area > 0 => Ordering::Less,
+            _ => Ordering::Greater,
+        }
+    });
+
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len());
+    for p in points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull
+}
+
+// ========== Andrew's Monotone Chain ==========
+
+/// Hull corners counter-clockwise, starting from the leftmost (then lowest) point
+pub fn monotone_chain(points: &[Point]) -> Vec<Point> {
+    let mut points = points.to_vec();
+    points.sort_unstable();
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len() + 1);
+    // Lower hull left to right
+    for &p in &points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // Upper hull right to left, never popping into the lower one
+    let lower_len = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // The walk ends back at the first point
+    hull.pop();
+    hull
+}
+
+// ========== Demo Code ==========
+
+/// Points on a small grid: `#` for hull corners, `o` for the other points
+fn plot(points: &[Point], hull: &[Point], size: i64) -> String {
+    let mut rows = Vec::new();
+    for y in (0..size).rev() {
+        let row: Vec<&str> = (0..size)
+            .map(|x| {
+                let p = Point::new(x, y);
+                if hull.contains(&p) {
+                    "#"
+                } else if points.contains(&p) {
+                    "o"
+                } else {
+                    "."
+                }
+            })
+            .collect();
+        rows.push(row.join(" "));
+    }
+    rows.join("\n")
+}
+
+fn format_points(points: &[Point]) -> String {
+    points.iter().map(Point::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ConvexHullDemo;
+
+impl Demo for ConvexHullDemo {
+    fn name(&self) -> &'static str {
+        "convex_hull"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Graham scan and Andrew's monotone chain with an exact orientation test"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Orientation =====")?;
+        let (a, b) = (Point::new(0, 0), Point::new(4, 2));
+        for c in [Point::new(1, 3), Point::new(5, 0), Point::new(8, 4)] {
+            writeln!(out, "{} -> {} -> {}: {:?} (cross = {})", a, b, c, orientation(a, b, c), cross(a, b, c))?;
+        }
+        // Each product is about 2^62, where f64 can only count in steps of 1024
+        let (b, c) = (Point::new((1 << 31) + 1, 1 << 31), Point::new((1 << 31) + 2, (1 << 31) + 1));
+        let in_f64 = b.x as f64 * c.y as f64 - b.y as f64 * c.x as f64;
+        writeln!(out, "{} -> {} -> {}: {:?} (cross = {}), but f64 says cross = {}",
+                      a, b, c, orientation(a, b, c), cross(a, b, c), in_f64)?;
+
+        writeln!(out, "\n===== Random Points =====")?;
+        let mut rng = Pcg32::new(11, 0);
+        let mut points: Vec<Point> =
+            (0..24).map(|_| Point::new(rng.below(12) as i64, rng.below(12) as i64)).collect();
+        // A duplicate and a point in the middle of a hull edge, which are both dropped
+        points.extend([points[0], Point::new(0, 0), Point::new(0, 6), Point::new(0, 11)]);
+        let graham = graham_scan(&points);
+        let andrew = monotone_chain(&points);
+        writeln!(out, "{}", plot(&points, &graham, 12))?;
+        writeln!(out, "Graham scan:    {}", format_points(&graham))?;
+        writeln!(out, "monotone chain: {}", format_points(&andrew))?;
+
+        writeln!(out, "\n===== Degenerate Inputs =====")?;
+        let cases: [(&str, Vec<Point>); 4] = [
+            ("empty", vec![]),
+            ("one point, three times", vec![Point::new(2, 2); 3]),
+            ("collinear", (0..6).map(|i| Point::new(i, 2 * i)).collect()),
+            ("square with its centre", vec![
+                Point::new(0, 0), Point::new(2, 0), Point::new(2, 2), Point::new(0, 2), Point::new(1, 1),
+            ]),
+        ];
+        for (label, points) in &cases {
+            writeln!(out, "{:<24} -> [{}]", label, format_points(&monotone_chain(points)))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ConvexHullDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Corners of the hull by definition: endpoints of every segment `ab` with all
+    /// points on its left or on the segment itself
+    fn brute_force_corners(points: &[Point]) -> BTreeSet<Point> {
+        let unique: BTreeSet<Point> = points.iter().copied().collect();
+        if unique.len() < 2 {
+            return unique;
+        }
+        let mut corners = BTreeSet::new();
+        for &a in &unique {
+            for &b in &unique {
+                let is_edge = a != b
+                    && unique.iter().all(|&p| match cross(a, b, p) {
+                        0 => a.distance_squared(p) <= a.distance_squared(b) && b.distance_squared(p) <= a.distance_squared(b),
+                        area => area > 0,
+                    });
+                if is_edge {
+                    corners.extend([a, b]);
+                }
+            }
+        }
+        corners
+    }
+
+    fn assert_strictly_convex_ccw(hull: &[Point]) {
+        if hull.len() < 3 {
+            return;
+        }
+        for i in 0..hull.len() {
+            let (a, b, c) = (hull[i], hull[(i + 1) % hull.len()], hull[(i + 2) % hull.len()]);
+            assert_eq!(orientation(a, b, c), Orientation::CounterClockwise, "{:?}", hull);
+        }
+    }
+
+    fn random_points(rng: &mut Pcg32, count: usize, span: u64) -> Vec<Point> {
+        (0..count).map(|_| Point::new(rng.below(span) as i64 - 5, rng.below(span) as i64 - 5)).collect()
+    }
+
+    #[test]
+    fn orientation_signs() {
+        let (a, b) = (Point::new(0, 0), Point::new(2, 0));
+        assert_eq!(orientation(a, b, Point::new(1, 1)), Orientation::CounterClockwise);
+        assert_eq!(orientation(a, b, Point::new(1, -1)), Orientation::Clockwise);
+        assert_eq!(orientation(a, b, Point::new(7, 0)), Orientation::Collinear);
+        // At the coordinate limit the products need almost all of i128
+        let (low, high) = (Point::new(-MAX_COORDINATE, -MAX_COORDINATE), Point::new(MAX_COORDINATE, MAX_COORDINATE));
+        assert_eq!(orientation(low, Point::new(0, 0), high), Orientation::Collinear);
+        assert_eq!(orientation(low, Point::new(0, 1), high), Orientation::Clockwise);
+        assert_eq!(orientation(low, Point::new(MAX_COORDINATE, -MAX_COORDINATE), high), Orientation::CounterClockwise);
+        // A one-unit turn that f64 rounds away
+        let (b, c) = (Point::new((1 << 31) + 1, 1 << 31), Point::new((1 << 31) + 2, (1 << 31) + 1));
+        assert_eq!(cross(Point::new(0, 0), b, c), 1);
+        assert_eq!(b.x as f64 * c.y as f64 - b.y as f64 * c.x as f64, 0.0);
+    }
+
+    #[test]
+    fn both_hulls_match_brute_force_on_random_points() {
+        let mut rng = Pcg32::new(960, 0);
+        for round in 0..300 {
+            // Small spans force plenty of duplicates and collinear triples
+            let span = [4, 8, 30, 1_000][round % 4];
+            let points = random_points(&mut rng, 1 + round % 40, span);
+            let expected = brute_force_corners(&points);
+            for hull in [graham_scan(&points), monotone_chain(&points)] {
+                assert_eq!(hull.iter().copied().collect::<BTreeSet<_>>(), expected, "{:?}", points);
+                assert_eq!(hull.len(), expected.len(), "repeated corner in {:?}", hull);
+                assert_strictly_convex_ccw(&hull);
+            }
+        }
+    }
+
+    #[test]
+    fn hulls_start_where_documented() {
+        let points = [Point::new(3, 0), Point::new(0, 1), Point::new(4, 4), Point::new(0, 3), Point::new(2, 2)];
+        assert_eq!(graham_scan(&points), [Point::new(3, 0), Point::new(4, 4), Point::new(0, 3), Point::new(0, 1)]);
+        assert_eq!(monotone_chain(&points), [Point::new(0, 1), Point::new(3, 0), Point::new(4, 4), Point::new(0, 3)]);
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        for hull in [graham_scan, monotone_chain] {
+            assert!(hull(&[]).is_empty());
+            assert_eq!(hull(&[Point::new(1, 1); 4]), [Point::new(1, 1)]);
+            assert_eq!(hull(&[Point::new(5, 5), Point::new(1, 1), Point::new(5, 5)]), [Point::new(1, 1), Point::new(5, 5)]);
+
+            let line: Vec<Point> = (0..10).rev().map(|i| Point::new(i, 3 * i)).collect();
+            assert_eq!(hull(&line).len(), 2);
+            assert!(hull(&line).contains(&Point::new(0, 0)) && hull(&line).contains(&Point::new(9, 27)));
+
+            // Points along every edge of a square, plus the inside
+            let grid: Vec<Point> = (0..5).flat_map(|x| (0..5).map(move |y| Point::new(x, y))).collect();
+            assert_eq!(hull(&grid).len(), 4);
+        }
+    }
+}
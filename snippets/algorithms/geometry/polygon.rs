@@ -0,0 +1,374 @@
+//! Polygons in Rust
+//!
+//! A polygon is its vertices in order, with an edge from each one to the next and from
+//! the last back to the first. On integer points (`convex_hull::Point`) everything
+//! below is built from the exact cross product:
+//! - Shoelace formula: the cross products of consecutive vertices sum to twice the
+//!   signed area, positive when the vertices run counter-clockwise. The centroid
+//!   weights each of those triangle slices by its area.
+//! - Ray casting: a ray from the point crosses the boundary an odd number of times
+//!   exactly when the point is inside (the even-odd rule).
+//! - Winding number: how many times the boundary goes around the point; non-zero means
+//!   inside. The two rules agree on simple polygons but not on self-intersecting ones,
+//!   where the middle of a pentagram is wound around twice.
+//! - Convexity: every turn goes the same way, and the boundary goes around only once.
+//!
+//! Points exactly on an edge are reported as `OnBoundary` by both tests, rather than
+//! whatever the crossing count happens to say.
+
+use crate::convex_hull::{cross, Point};
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Polygon ==========
+
+/// Where a point is relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    OnBoundary,
+    Outside,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        assert!(vertices.len() >= 3, "a polygon needs at least three vertices");
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Every edge `(start, end)`, including the closing one
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    // ========== Area and Centroid ==========
+
+    /// Twice the signed area, exactly; positive for counter-clockwise vertices
+    pub fn doubled_signed_area(&self) -> i128 {
+        self.edges().map(|(a, b)| cross(Point::new(0, 0), a, b)).sum()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.doubled_signed_area().abs() as f64 / 2.0
+    }
+
+    pub fn is_counter_clockwise(&self) -> bool {
+        self.doubled_signed_area() > 0
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges().map(|(a, b)| (a.distance_squared(b) as f64).sqrt()).sum()
+    }
+
+    /// The centre of mass of the enclosed region; `None` when the area is zero
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let doubled_area = self.doubled_signed_area();
+        if doubled_area == 0 {
+            return None;
+        }
+        let (mut cx, mut cy) = (0i128, 0i128);
+        for (a, b) in self.edges() {
+            let slice = cross(Point::new(0, 0), a, b);
+            cx += (i128::from(a.x) + i128::from(b.x)) * slice;
+            cy += (i128::from(a.y) + i128::from(b.y)) * slice;
+        }
+        let scale = 3.0 * doubled_area as f64;
+        Some((cx as f64 / scale, cy as f64 / scale))
+    }
+
+    // ========== Convexity ==========
+
+    /// Whether the polygon is convex. Straight-through vertices (collinear with both
+    /// neighbours) are allowed; a polygon with no turns at all is not convex.
+    pub fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        let mut turn_sign = 0;
+        for i in 0..n {
+            let turn = cross(self.vertices[i], self.vertices[(i + 1) % n], self.vertices[(i + 2) % n]).signum();
+            if turn != 0 {
+                if turn_sign != 0 && turn != turn_sign {
+                    return false;
+                }
+                turn_sign = turn;
+            }
+        }
+        // Same-way turns can still wind around twice, like a pentagram; a convex polygon
+        // changes horizontal direction exactly twice on the way round
+        let directions: Vec<i64> =
+            self.edges().map(|(a, b)| (b.x - a.x).signum()).filter(|&dx| dx != 0).collect();
+        let flips = (0..directions.len())
+            .filter(|&i| directions[i] != directions[(i + 1) % directions.len()])
+            .count();
+        turn_sign != 0 && flips == 2
+    }
+
+    // ========== Point in Polygon ==========
+
+    /// Whether `p` lies on some edge
+    pub fn on_boundary(&self, p: Point) -> bool {
+        self.edges().any(|(a, b)| {
+            cross(a, b, p) == 0 && a.x.min(b.x) <= p.x && p.x <= a.x.max(b.x) && a.y.min(b.y) <= p.y && p.y <= a.y.max(b.y)
+        })
+    }
+
+    /// Even-odd rule: counts edges crossing the ray from `p` towards +x
+    pub fn locate_by_ray_casting(&self, p: Point) -> Location {
+        if self.on_boundary(p) {
+            return Location::OnBoundary;
+        }
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            // Half-open in y, so a ray through a vertex counts it for only one of its edges
+            if (a.y > p.y) != (b.y > p.y) {
+                // The crossing is to the right of p when p is on the upward edge's left
+                let p_left_of_edge = cross(a, b, p) > 0;
+                if p_left_of_edge == (b.y > a.y) {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Net number of counter-clockwise turns the boundary makes around `p`
+    pub fn winding_number(&self, p: Point) -> i32 {
+        let mut winding = 0;
+        for (a, b) in self.edges() {
+            if a.y <= p.y {
+                if b.y > p.y && cross(a, b, p) > 0 {
+                    winding += 1;
+                }
+            } else if b.y <= p.y && cross(a, b, p) < 0 {
+                winding -= 1;
+            }
+        }
+        winding
+    }
+
+    /// Non-zero rule: inside when the winding number is not zero
+    pub fn locate_by_winding_number(&self, p: Point) -> Location {
+        if self.on_boundary(p) {
+            Location::OnBoundary
+        } else if self.winding_number(p) != 0 {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+fn polygon(points: &[(i64, i64)]) -> Polygon {
+    Polygon::new(points.iter().map(|&(x, y)| Point::new(x, y)).collect())
+}
+
+/// A grid of locations: `#` inside, `+` on the boundary, `.` outside
+fn plot(width: i64, height: i64, locate: impl Fn(Point) -> Location) -> String {
+    let rows: Vec<String> = (0..=height)
+        .rev()
+        .map(|y| {
+            let cells: Vec<&str> = (0..=width)
+                .map(|x| match locate(Point::new(x, y)) {
+                    Location::Inside => "#",
+                    Location::OnBoundary => "+",
+                    Location::Outside => ".",
+                })
+                .collect();
+            cells.join(" ")
+        })
+        .collect();
+    rows.join("\n")
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct PolygonDemo;
+
+impl Demo for PolygonDemo {
+    fn name(&self) -> &'static str {
+        "polygon"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Shoelace area, centroid, convexity and point-in-polygon tests"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Area, Centroid and Convexity =====")?;
+        let shapes = [
+            ("square 4x4", polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)])),
+            ("L shape", polygon(&[(0, 0), (6, 0), (6, 2), (2, 2), (2, 6), (0, 6)])),
+            ("triangle, clockwise", polygon(&[(0, 0), (0, 3), (6, 0)])),
+            ("pentagram*", polygon(&[(5, 0), (8, 9), (0, 3), (10, 3), (2, 9)])),
+        ];
+        for (label, shape) in &shapes {
+            let centroid = match shape.centroid() {
+                Some((x, y)) => format!("({:.3}, {:.3})", x, y),
+                None => "none".to_string(),
+            };
+            writeln!(out, "{:<20} area = {:>5.1}, ccw = {:<5}, convex = {:<5}, centroid = {}",
+                          label, shape.area(), shape.is_counter_clockwise(), shape.is_convex(), centroid)?;
+        }
+        writeln!(out, "* self-intersecting: the shoelace sum counts the centre pentagon twice")?;
+
+        writeln!(out, "\n===== Point in a Concave Polygon =====")?;
+        let arrow = polygon(&[(0, 2), (6, 2), (6, 0), (10, 4), (6, 8), (6, 6), (0, 6), (3, 4)]);
+        writeln!(out, "{}", plot(10, 8, |p| arrow.locate_by_ray_casting(p)))?;
+        let probes = [Point::new(3, 4), Point::new(2, 4), Point::new(8, 4), Point::new(6, 7), Point::new(1, 4)];
+        for p in probes {
+            writeln!(out, "{:<8} ray casting: {:<10} winding number: {}",
+                          p.to_string(), format!("{:?}", arrow.locate_by_ray_casting(p)), arrow.winding_number(p))?;
+        }
+
+        writeln!(out, "\n===== Even-Odd vs Non-Zero on a Pentagram =====")?;
+        let star = &shapes[3].1;
+        writeln!(out, "ray casting:")?;
+        writeln!(out, "{}", plot(10, 9, |p| star.locate_by_ray_casting(p)))?;
+        writeln!(out, "winding number:")?;
+        writeln!(out, "{}", plot(10, 9, |p| star.locate_by_winding_number(p)))?;
+        writeln!(out, "winding number at the centre (5, 5): {}", star.winding_number(Point::new(5, 5)))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&PolygonDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convex_hull::monotone_chain;
+    use crate::prng::{Pcg32, Rng};
+
+    fn l_shape() -> Polygon {
+        polygon(&[(0, 0), (6, 0), (6, 2), (2, 2), (2, 6), (0, 6)])
+    }
+
+    #[test]
+    fn shoelace_area_and_orientation() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!((square.doubled_signed_area(), square.area()), (32, 16.0));
+        assert!(square.is_counter_clockwise());
+        assert_eq!(square.perimeter(), 16.0);
+
+        let mut reversed = square.vertices().to_vec();
+        reversed.reverse();
+        let reversed = Polygon::new(reversed);
+        assert_eq!((reversed.doubled_signed_area(), reversed.area()), (-32, 16.0));
+
+        assert_eq!(l_shape().area(), 20.0);
+        assert_eq!(polygon(&[(0, 0), (5, 0), (0, 3)]).area(), 7.5);
+        assert_eq!(polygon(&[(0, 0), (1, 1), (2, 2)]).area(), 0.0);
+    }
+
+    #[test]
+    fn centroids() {
+        assert_eq!(polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]).centroid(), Some((2.0, 2.0)));
+        assert_eq!(polygon(&[(0, 0), (6, 0), (0, 3)]).centroid(), Some((2.0, 1.0)));
+        // L shape: a 6x2 bar at (3, 1) and a 2x4 bar at (1, 4), weighted 12 to 8
+        let (x, y) = l_shape().centroid().unwrap();
+        assert!((x - 2.2).abs() < 1e-12 && (y - 2.2).abs() < 1e-12, "({}, {})", x, y);
+        assert_eq!(polygon(&[(0, 0), (1, 1), (2, 2)]).centroid(), None);
+    }
+
+    #[test]
+    fn convexity() {
+        assert!(polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]).is_convex());
+        assert!(polygon(&[(0, 0), (0, 4), (4, 4), (4, 0)]).is_convex());
+        assert!(polygon(&[(0, 0), (2, 0), (4, 0), (4, 4), (0, 4)]).is_convex(), "straight-through vertex");
+        assert!(!l_shape().is_convex());
+        assert!(!polygon(&[(5, 0), (8, 9), (0, 3), (10, 3), (2, 9)]).is_convex(), "pentagram");
+        assert!(!polygon(&[(0, 0), (1, 1), (2, 2)]).is_convex(), "no area");
+    }
+
+    #[test]
+    fn concave_polygon_membership() {
+        let l = l_shape();
+        for (p, expected) in [
+            ((1, 1), Location::Inside),
+            ((1, 5), Location::Inside),
+            ((5, 1), Location::Inside),
+            ((4, 4), Location::Outside),
+            ((3, 3), Location::Outside),
+            ((7, 1), Location::Outside),
+            ((-1, 0), Location::Outside),
+            // Rays from these pass straight through vertices
+            ((1, 2), Location::Inside),
+            ((-1, 2), Location::Outside),
+            ((-1, 6), Location::Outside),
+        ] {
+            let p = Point::new(p.0, p.1);
+            assert_eq!(l.locate_by_ray_casting(p), expected, "{}", p);
+            assert_eq!(l.locate_by_winding_number(p), expected, "{}", p);
+        }
+    }
+
+    #[test]
+    fn points_on_edges_and_vertices_are_on_the_boundary() {
+        let l = l_shape();
+        for (x, y) in [(0, 0), (3, 0), (6, 1), (4, 2), (2, 2), (2, 5), (1, 6), (0, 3), (6, 2)] {
+            let p = Point::new(x, y);
+            assert_eq!(l.locate_by_ray_casting(p), Location::OnBoundary, "{}", p);
+            assert_eq!(l.locate_by_winding_number(p), Location::OnBoundary, "{}", p);
+        }
+        // On the line through an edge but beyond its end
+        assert_eq!(l.locate_by_ray_casting(Point::new(8, 2)), Location::Outside);
+    }
+
+    #[test]
+    fn self_intersecting_polygons_split_the_rules() {
+        let star = polygon(&[(5, 0), (8, 9), (0, 3), (10, 3), (2, 9)]);
+        let centre = Point::new(5, 5);
+        assert_eq!(star.winding_number(centre).abs(), 2);
+        assert_eq!(star.locate_by_winding_number(centre), Location::Inside);
+        assert_eq!(star.locate_by_ray_casting(centre), Location::Outside);
+    }
+
+    #[test]
+    fn both_rules_agree_with_edge_sides_on_random_convex_polygons() {
+        let mut rng = Pcg32::new(963, 0);
+        for _ in 0..100 {
+            let points: Vec<Point> = (0..12).map(|_| Point::new(rng.below(20) as i64, rng.below(20) as i64)).collect();
+            let hull = monotone_chain(&points);
+            if hull.len() < 3 {
+                continue;
+            }
+            let convex = Polygon::new(hull);
+            assert!(convex.is_convex() && convex.is_counter_clockwise());
+            for x in -1..=21 {
+                for y in -1..=21 {
+                    let p = Point::new(x, y);
+                    let sides: Vec<i128> = convex.edges().map(|(a, b)| cross(a, b, p)).collect();
+                    let expected = if sides.iter().any(|&s| s < 0) {
+                        Location::Outside
+                    } else if sides.contains(&0) {
+                        Location::OnBoundary
+                    } else {
+                        Location::Inside
+                    };
+                    assert_eq!(convex.locate_by_ray_casting(p), expected, "{} in {:?}", p, convex);
+                    assert_eq!(convex.locate_by_winding_number(p), expected, "{} in {:?}", p, convex);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Quadtree in Rust
+//!
+//! A point quadtree splits a square-ish region into four quadrants whenever one region
+//! holds more points than its bucket size, and keeps splitting as long as points crowd
+//! together. Dense areas end up with small cells and empty areas stay as one big cell,
+//! so a range query only descends into cells that overlap the query rectangle:
+//! - Insert walks down to the leaf containing the point and splits it if it overflows.
+//! - Remove deletes one copy of a point and merges four children back into a leaf once
+//!   they fit in a single bucket again.
+//! - A rectangle query skips every subtree whose bounds miss the rectangle, and takes
+//!   whole subtrees without checking points once the rectangle covers them.
+//!
+//! Coordinates are integers (`convex_hull::Point`), and a region one unit wide can't be
+//! split any further, so a leaf there simply holds more than a bucket's worth.
+
+use crate::convex_hull::Point;
+use crate::prng::{Pcg32, Rng};
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Rectangles ==========
+
+/// An axis-aligned rectangle, inclusive on all sides. `x_min > x_max` is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x_min: i64,
+    pub y_min: i64,
+    pub x_max: i64,
+    pub y_max: i64,
+}
+
+impl Rect {
+    pub const fn new(x_min: i64, y_min: i64, x_max: i64, y_max: i64) -> Self {
+        Rect { x_min, y_min, x_max, y_max }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x_min > self.x_max || self.y_min > self.y_max
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.x_min..=self.x_max).contains(&p.x) && (self.y_min..=self.y_max).contains(&p.y)
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.x_min <= other.x_max
+            && other.x_min <= self.x_max
+            && self.y_min <= other.y_max
+            && other.y_min <= self.y_max
+    }
+
+    /// Whether all of `other` lies inside `self`
+    pub fn covers(&self, other: &Rect) -> bool {
+        other.is_empty()
+            || (self.x_min <= other.x_min
+                && other.x_max <= self.x_max
+                && self.y_min <= other.y_min
+                && other.y_max <= self.y_max)
+    }
+
+    /// Only a single cell can't be divided
+    fn can_split(&self) -> bool {
+        self.x_min < self.x_max || self.y_min < self.y_max
+    }
+
+    /// South-west, south-east, north-west and north-east, split through the middle; a
+    /// side one unit wide leaves two of them empty
+    fn quadrants(&self) -> [Rect; 4] {
+        let x_mid = self.x_min + (self.x_max - self.x_min) / 2;
+        let y_mid = self.y_min + (self.y_max - self.y_min) / 2;
+        [
+            Rect::new(self.x_min, self.y_min, x_mid, y_mid),
+            Rect::new(x_mid + 1, self.y_min, self.x_max, y_mid),
+            Rect::new(self.x_min, y_mid + 1, x_mid, self.y_max),
+            Rect::new(x_mid + 1, y_mid + 1, self.x_max, self.y_max),
+        ]
+    }
+}
+
+// ========== Tree Nodes ==========
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Rect,
+    /// Points of a leaf; always empty once the node has children
+    points: Vec<Point>,
+    children: Option<Box<[Node; 4]>>,
+    /// Points in this whole subtree
+    len: usize,
+}
+
+impl Node {
+    fn leaf(bounds: Rect) -> Self {
+        Node { bounds, points: Vec::new(), children: None, len: 0 }
+    }
+
+    fn insert(&mut self, p: Point, bucket_size: usize) {
+        self.len += 1;
+        match &mut self.children {
+            Some(children) => {
+                let child = children.iter_mut().find(|child| child.bounds.contains(p)).expect("quadrants tile the node");
+                child.insert(p, bucket_size);
+            }
+            None => {
+                self.points.push(p);
+                if self.points.len() > bucket_size && self.bounds.can_split() {
+                    self.split(bucket_size);
+                }
+            }
+        }
+    }
+
+    fn split(&mut self, bucket_size: usize) {
+        let mut children = Box::new(self.bounds.quadrants().map(Node::leaf));
+        for p in self.points.drain(..) {
+            let child = children.iter_mut().find(|child| child.bounds.contains(p)).expect("quadrants tile the node");
+            child.insert(p, bucket_size);
+        }
+        self.children = Some(children);
+    }
+
+    fn remove(&mut self, p: Point, bucket_size: usize) -> bool {
+        let removed = match &mut self.children {
+            Some(children) => children
+                .iter_mut()
+                .find(|child| child.bounds.contains(p))
+                .is_some_and(|child| child.remove(p, bucket_size)),
+            None => match self.points.iter().position(|&q| q == p) {
+                Some(i) => {
+                    self.points.swap_remove(i);
+                    true
+                }
+                None => false,
+            },
+        };
+        if removed {
+            self.len -= 1;
+            if self.children.is_some() && self.len <= bucket_size {
+                self.merge();
+            }
+        }
+        removed
+    }
+
+    /// Pulls every point of the subtree back into this node as a leaf
+    fn merge(&mut self) {
+        let mut points = Vec::with_capacity(self.len);
+        self.collect(&mut points);
+        self.points = points;
+        self.children = None;
+    }
+
+    fn collect(&self, out: &mut Vec<Point>) {
+        out.extend_from_slice(&self.points);
+        if let Some(children) = &self.children {
+            children.iter().for_each(|child| child.collect(out));
+        }
+    }
+
+    fn query(&self, area: &Rect, out: &mut Vec<Point>) {
+        if self.len == 0 || !area.intersects(&self.bounds) {
+            return;
+        }
+        if area.covers(&self.bounds) {
+            self.collect(out);
+            return;
+        }
+        out.extend(self.points.iter().filter(|&&p| area.contains(p)));
+        if let Some(children) = &self.children {
+            children.iter().for_each(|child| child.query(area, out));
+        }
+    }
+
+    fn for_each_leaf(&self, visit: &mut impl FnMut(&Node, usize), depth: usize) {
+        match &self.children {
+            Some(children) => children.iter().for_each(|child| child.for_each_leaf(visit, depth + 1)),
+            None => visit(self, depth),
+        }
+    }
+}
+
+// ========== Quadtree ==========
+
+#[derive(Debug, Clone)]
+pub struct QuadTree {
+    root: Node,
+    bucket_size: usize,
+}
+
+impl QuadTree {
+    /// An empty tree over `bounds` whose leaves split beyond `bucket_size` points
+    pub fn new(bounds: Rect, bucket_size: usize) -> Self {
+        assert!(!bounds.is_empty(), "quadtree bounds must not be empty");
+        assert!(bucket_size > 0, "bucket size must be at least 1");
+        QuadTree { root: Node::leaf(bounds), bucket_size }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.root.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.root.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.len == 0
+    }
+
+    /// Adds `p`, duplicates included; `false` if it lies outside the tree's bounds
+    pub fn insert(&mut self, p: Point) -> bool {
+        if !self.root.bounds.contains(p) {
+            return false;
+        }
+        self.root.insert(p, self.bucket_size);
+        true
+    }
+
+    /// Removes one copy of `p`; `false` if there was none
+    pub fn remove(&mut self, p: Point) -> bool {
+        self.root.remove(p, self.bucket_size)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        !self.query(&Rect::new(p.x, p.y, p.x, p.y)).is_empty()
+    }
+
+    /// Every point inside `area`, in no particular order
+    pub fn query(&self, area: &Rect) -> Vec<Point> {
+        let mut found = Vec::new();
+        self.root.query(area, &mut found);
+        found
+    }
+
+    /// Number of leaves, and the depth of the deepest one (the root alone is depth 0)
+    pub fn shape(&self) -> (usize, usize) {
+        let (mut leaves, mut deepest) = (0, 0);
+        self.root.for_each_leaf(
+            &mut |_, depth| {
+                leaves += 1;
+                deepest = deepest.max(depth);
+            },
+            0,
+        );
+        (leaves, deepest)
+    }
+
+    /// ASCII picture of the subdivision with y pointing up: every leaf's border drawn
+    /// between the cells, `*` for a cell holding points. Meant for small bounds.
+    pub fn render(&self) -> String {
+        let b = self.root.bounds;
+        let (width, height) = ((b.x_max - b.x_min + 1) as usize, (b.y_max - b.y_min + 1) as usize);
+        // Cell (x, y) is at (2x + 1, 2y + 1); borders run along the even rows and columns
+        let (cols, rows) = (2 * width + 1, 2 * height + 1);
+        let mut horizontal = vec![vec![false; cols]; rows];
+        let mut vertical = vec![vec![false; cols]; rows];
+        let mut grid = vec![vec![' '; cols]; rows];
+        self.root.for_each_leaf(
+            &mut |leaf, _| {
+                if leaf.bounds.is_empty() {
+                    return;
+                }
+                let left = 2 * (leaf.bounds.x_min - b.x_min) as usize;
+                let right = 2 * (leaf.bounds.x_max - b.x_min + 1) as usize;
+                let bottom = 2 * (leaf.bounds.y_min - b.y_min) as usize;
+                let top = 2 * (leaf.bounds.y_max - b.y_min + 1) as usize;
+                for row in [bottom, top] {
+                    horizontal[row][left..=right].fill(true);
+                }
+                for row in &mut vertical[bottom..=top] {
+                    row[left] = true;
+                    row[right] = true;
+                }
+                for p in &leaf.points {
+                    let (col, row) = (2 * (p.x - b.x_min) as usize + 1, 2 * (p.y - b.y_min) as usize + 1);
+                    grid[row][col] = '*';
+                }
+            },
+            0,
+        );
+        for row in 0..rows {
+            for col in 0..cols {
+                if row % 2 == 0 || col % 2 == 0 {
+                    grid[row][col] = match (horizontal[row][col], vertical[row][col]) {
+                        (true, true) if row % 2 == 0 && col % 2 == 0 => '+',
+                        (true, _) if row % 2 == 0 => '-',
+                        (_, true) if col % 2 == 0 => '|',
+                        _ => ' ',
+                    };
+                }
+            }
+        }
+        let lines: Vec<String> = grid.iter().rev().map(|row| row.iter().collect::<String>().trim_end().to_string()).collect();
+        lines.join("\n")
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct QuadTreeDemo;
+
+impl Demo for QuadTreeDemo {
+    fn name(&self) -> &'static str {
+        "quadtree"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Point quadtree with bucketed leaves, removal and rectangle queries"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Subdivision (bucket size 2) =====")?;
+        let mut tree = QuadTree::new(Rect::new(0, 0, 15, 15), 2);
+        let mut rng = Pcg32::new(965, 0);
+        // A loose scatter plus a tight cluster in the north-east
+        let mut points: Vec<Point> = (0..10).map(|_| Point::new(rng.below(16) as i64, rng.below(16) as i64)).collect();
+        points.extend((0..8).map(|_| Point::new(11 + rng.below(3) as i64, 11 + rng.below(3) as i64)));
+        for &p in &points {
+            tree.insert(p);
+        }
+        let (leaves, depth) = tree.shape();
+        writeln!(out, "{} points, {} leaves, depth {}", tree.len(), leaves, depth)?;
+        writeln!(out, "{}", tree.render())?;
+
+        writeln!(out, "\n===== Range Query =====")?;
+        let area = Rect::new(8, 8, 13, 15);
+        let mut found = tree.query(&area);
+        found.sort();
+        let brute_force = points.iter().filter(|&&p| area.contains(p)).count();
+        writeln!(out, "points in x 8..=13, y 8..=15: {} (brute force: {})", found.len(), brute_force)?;
+        writeln!(out, "{}", found.iter().map(Point::to_string).collect::<Vec<_>>().join(" "))?;
+
+        writeln!(out, "\n===== Removal Merges Cells =====")?;
+        for &p in &points[10..] {
+            tree.remove(p);
+        }
+        let (leaves, depth) = tree.shape();
+        writeln!(out, "cluster removed: {} points, {} leaves, depth {}", tree.len(), leaves, depth)?;
+        writeln!(out, "{}", tree.render())?;
+        writeln!(out, "insert outside the bounds: {}", tree.insert(Point::new(16, 3)))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&QuadTreeDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut points: Vec<Point>) -> Vec<Point> {
+        points.sort();
+        points
+    }
+
+    fn brute_force(points: &[Point], area: &Rect) -> Vec<Point> {
+        sorted(points.iter().copied().filter(|&p| area.contains(p)).collect())
+    }
+
+    fn random_rect(rng: &mut Pcg32, span: u64) -> Rect {
+        let (x1, x2) = (rng.below(span) as i64 - 10, rng.below(span) as i64 - 10);
+        let (y1, y2) = (rng.below(span) as i64 - 10, rng.below(span) as i64 - 10);
+        Rect::new(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2))
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut rng = Pcg32::new(965, 1);
+        for bucket_size in [1, 3, 8, 64] {
+            let mut tree = QuadTree::new(Rect::new(-10, -10, 89, 89), bucket_size);
+            let points: Vec<Point> =
+                (0..500).map(|_| Point::new(rng.below(100) as i64 - 10, rng.below(100) as i64 - 10)).collect();
+            for &p in &points {
+                assert!(tree.insert(p));
+            }
+            assert_eq!(tree.len(), points.len());
+            for _ in 0..200 {
+                let area = random_rect(&mut rng, 120);
+                assert_eq!(sorted(tree.query(&area)), brute_force(&points, &area), "{:?}", area);
+            }
+            assert_eq!(sorted(tree.query(&tree.bounds())), sorted(points.clone()));
+        }
+    }
+
+    #[test]
+    fn removal_keeps_queries_correct_and_merges_leaves() {
+        let mut rng = Pcg32::new(965, 2);
+        let mut tree = QuadTree::new(Rect::new(0, 0, 63, 63), 4);
+        let mut points: Vec<Point> = (0..300).map(|_| Point::new(rng.below(64) as i64, rng.below(64) as i64)).collect();
+        points.iter().for_each(|&p| assert!(tree.insert(p)));
+        assert!(tree.shape().0 > 1);
+
+        rng.shuffle(&mut points);
+        while let Some(p) = points.pop() {
+            assert!(tree.remove(p));
+            if points.len() % 25 == 0 {
+                let area = random_rect(&mut rng, 80);
+                assert_eq!(sorted(tree.query(&area)), brute_force(&points, &area));
+                assert_eq!(tree.len(), points.len());
+            }
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.shape(), (1, 0), "everything merged back into the root");
+        assert!(!tree.remove(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn duplicates_and_unsplittable_cells() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 7, 7), 2);
+        for _ in 0..10 {
+            assert!(tree.insert(Point::new(3, 5)));
+        }
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree.query(&Rect::new(3, 5, 3, 5)).len(), 10);
+        // 8x8 halves three times down to a single cell
+        assert_eq!(tree.shape().1, 3);
+
+        assert!(tree.remove(Point::new(3, 5)));
+        assert_eq!(tree.query(&tree.bounds()).len(), 9);
+        assert!(tree.contains(Point::new(3, 5)));
+        assert!(!tree.contains(Point::new(5, 3)));
+    }
+
+    #[test]
+    fn points_outside_the_bounds_are_refused() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 9, 4), 1);
+        assert!(!tree.insert(Point::new(10, 0)));
+        assert!(!tree.insert(Point::new(0, -1)));
+        assert!(tree.insert(Point::new(9, 4)));
+        assert!(tree.insert(Point::new(0, 0)));
+        assert_eq!(tree.len(), 2);
+        assert!(tree.query(&Rect::new(3, 3, 1, 1)).is_empty(), "empty query rectangle");
+    }
+
+    #[test]
+    fn render_draws_leaf_borders() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 3, 3), 1);
+        tree.insert(Point::new(0, 0));
+        tree.insert(Point::new(3, 3));
+        // Two points in opposite corners: one split into four 2x2 leaves
+        assert_eq!(tree.shape(), (4, 1));
+        let expected = [
+            "+---+---+",
+            "|   |  *|",
+            "|   |   |",
+            "|   |   |",
+            "+---+---+",
+            "|   |   |",
+            "|   |   |",
+            "|*  |   |",
+            "+---+---+",
+        ];
+        assert_eq!(tree.render(), expected.join("\n"));
+    }
+}
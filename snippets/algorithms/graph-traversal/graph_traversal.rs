@@ -1,35 +1,123 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+//! Graph Traversal Algorithms in Rust
+//!
+//! This program demonstrates BFS and DFS traversal algorithms on a graph.
+//!
+//! Each traversal is written once in `Graph::traverse`, which reports every visit to a
+//! trace hook as a `TraversalStep`. The `bfs` / `dfs_*` methods just return the visit
+//! order; `Graph::execute` takes an `ExecutionMode` to also trace the steps or animate
+//! them on a writer, and visualizers and tests collect the steps with `Graph::trace`.
+//!
+//! Run: cargo run -p algorithms --example graph_traversal
+
+use crate::bitset::BitSet;
+use demo::{Demo, ExecutionMode};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
 use std::thread;
 use std::time::Duration;
 
-/// Graph Traversal Algorithms in Rust
-///
-/// This program demonstrates BFS and DFS traversal algorithms on a graph.
-///
-/// Compile: rustc graph_traversal.rs
-/// Run: ./graph_traversal
+// ========== Traversal Tracing ==========
+
+/// Which traversal to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum Traversal {
+    Bfs,
+    DfsRecursive,
+    DfsIterative,
+}
+
+impl Traversal {
+    pub const ALL: [Traversal; 3] = [Traversal::Bfs, Traversal::DfsRecursive, Traversal::DfsIterative];
+
+    /// Short name, e.g. `"dfs-iterative"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Traversal::Bfs => "bfs",
+            Traversal::DfsRecursive => "dfs-recursive",
+            Traversal::DfsIterative => "dfs-iterative",
+        }
+    }
+
+    /// Inverse of `name`.
+    pub fn from_name(name: &str) -> Option<Traversal> {
+        Traversal::ALL.into_iter().find(|traversal| traversal.name() == name)
+    }
+}
+
+/// What a traversal looks like at the moment it visits a vertex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraversalStep<'a> {
+    /// The vertex just visited
+    pub vertex: &'a str,
+    /// The BFS queue (front first) or iterative DFS stack (bottom first) after `vertex`
+    /// was taken off it; for recursive DFS, the recursion path leading to `vertex`
+    pub frontier: &'a [String],
+    /// Visit order so far, ending with `vertex`
+    pub visited: &'a [String],
+}
+
+/// Owned copy of a `TraversalStep`, as collected by `Graph::trace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraversalFrame {
+    pub vertex: String,
+    pub frontier: Vec<String>,
+    pub visited: Vec<String>,
+}
+
+impl From<&TraversalStep<'_>> for TraversalFrame {
+    fn from(step: &TraversalStep<'_>) -> Self {
+        TraversalFrame {
+            vertex: step.vertex.to_string(),
+            frontier: step.frontier.to_vec(),
+            visited: step.visited.to_vec(),
+        }
+    }
+}
+
+/// One traversal from start to finish: the visit order and every step that led to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraversalRun {
+    pub traversal: Traversal,
+    pub start: String,
+    pub order: Vec<String>,
+    pub frames: Vec<TraversalFrame>,
+}
+
+// ========== Graph ==========
 
 /// A graph using adjacency list representation
-struct Graph {
+pub struct Graph {
     // Adjacency list representation
     adjacency_list: HashMap<String, Vec<String>>,
 }
 
 impl Graph {
     /// Creates a new empty graph
-    fn new() -> Self {
+    pub fn new() -> Self {
         Graph {
             adjacency_list: HashMap::new(),
         }
     }
 
+    /// Builds a graph from a list of undirected edges
+    pub fn from_edges<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut g = Graph::new();
+        for (v1, v2) in edges {
+            g.add_edge(v1, v2);
+        }
+        g
+    }
+
     /// Adds a vertex to the graph
-    fn add_vertex(&mut self, vertex: &str) {
-        self.adjacency_list.entry(vertex.to_string()).or_insert(Vec::new());
+    pub fn add_vertex(&mut self, vertex: &str) {
+        self.adjacency_list.entry(vertex.to_string()).or_default();
     }
 
     /// Adds an edge between two vertices
-    fn add_edge(&mut self, v1: &str, v2: &str) {
+    pub fn add_edge(&mut self, v1: &str, v2: &str) {
         // Ensure both vertices exist
         self.add_vertex(v1);
         self.add_vertex(v2);
@@ -39,49 +127,79 @@ impl Graph {
         self.adjacency_list.get_mut(v2).unwrap().push(v1.to_string());
     }
 
+    /// All vertices in sorted order
+    pub fn vertices(&self) -> Vec<String> {
+        let mut vertices: Vec<String> = self.adjacency_list.keys().cloned().collect();
+        vertices.sort();
+        vertices
+    }
+
+    /// Whether the graph contains this vertex
+    pub fn contains(&self, vertex: &str) -> bool {
+        self.adjacency_list.contains_key(vertex)
+    }
+
     /// Helper method to get sorted neighbors for consistent output
-    fn get_sorted_neighbors(&self, vertex: &str) -> Vec<String> {
+    pub fn get_sorted_neighbors(&self, vertex: &str) -> Vec<String> {
         let mut neighbors = self.adjacency_list[vertex].clone();
         neighbors.sort();
         neighbors
     }
 
-    /// Performs a breadth-first search traversal starting from the given vertex
-    fn bfs(&self, start: &str) -> Vec<String> {
-        if !self.adjacency_list.contains_key(start) {
+    /// Runs `traversal` from `start`, calling `on_step` at every visit, and returns the
+    /// visit order. Neighbors are explored in sorted order so the result is deterministic.
+    pub fn traverse<F: FnMut(&TraversalStep)>(&self, traversal: Traversal, start: &str, mut on_step: F) -> Vec<String> {
+        if !self.contains(start) {
             return Vec::new();
         }
-        
-        let mut visited = HashSet::new();
+
+        match traversal {
+            Traversal::Bfs => self.bfs_core(start, &mut on_step),
+            Traversal::DfsRecursive => {
+                let mut visited = VisitedSet::new(self);
+                let mut result = Vec::new();
+                let mut path = Vec::new();
+                self.dfs_helper(start, &mut visited, &mut result, &mut path, &mut on_step);
+                result
+            }
+            Traversal::DfsIterative => self.dfs_iterative_core(start, &mut on_step),
+        }
+    }
+
+    /// Every step of `traversal` from `start`, in order.
+    pub fn trace(&self, traversal: Traversal, start: &str) -> Vec<TraversalFrame> {
+        let mut frames = Vec::new();
+        self.traverse(traversal, start, |step| frames.push(TraversalFrame::from(step)));
+        frames
+    }
+
+    /// Runs `traversal` from `start`, keeping the visit order and every step.
+    pub fn record(&self, traversal: Traversal, start: &str) -> TraversalRun {
+        let mut frames = Vec::new();
+        let order = self.traverse(traversal, start, |step| frames.push(TraversalFrame::from(step)));
+        TraversalRun { traversal, start: start.to_string(), order, frames }
+    }
+
+    fn bfs_core<F: FnMut(&TraversalStep)>(&self, start: &str, on_step: &mut F) -> Vec<String> {
+        let mut visited = VisitedSet::new(self);
         let mut queue = VecDeque::new();
         let mut result = Vec::new();
         
         // Initialize with starting vertex
-        visited.insert(start.to_string());
+        visited.insert(start);
         queue.push_back(start.to_string());
         
-        println!("Starting BFS traversal from vertex {}", start);
-        
-        while !queue.is_empty() {
-            // Dequeue the first vertex
-            let vertex = queue.pop_front().unwrap();
+        // Dequeue the first vertex
+        while let Some(vertex) = queue.pop_front() {
             result.push(vertex.clone());
-            
-            println!("Visiting: {}", vertex);
-            println!("Queue: {:?}", queue);
-            println!("Visited so far: {:?}", result);
-            println!("------------------------------");
-            
-            // Pause for demonstration
-            thread::sleep(Duration::from_millis(500));
+            on_step(&TraversalStep { vertex: &vertex, frontier: queue.make_contiguous(), visited: &result });
             
             // Get sorted neighbors for consistent order
             let neighbors = self.get_sorted_neighbors(&vertex);
             
             // Enqueue all unvisited neighbors
             for neighbor in neighbors {
-                if !visited.contains(&neighbor) {
-                    visited.insert(neighbor.clone());
+                if visited.insert(&neighbor) {
                     queue.push_back(neighbor);
                 }
             }
@@ -90,77 +208,47 @@ impl Graph {
         result
     }
 
-    /// Performs a recursive depth-first search traversal starting from the given vertex
-    fn dfs_recursive(&self, start: &str) -> Vec<String> {
-        if !self.adjacency_list.contains_key(start) {
-            return Vec::new();
-        }
-        
-        let mut visited = HashSet::new();
-        let mut result = Vec::new();
-        
-        println!("Starting recursive DFS traversal from vertex {}", start);
-        
-        self.dfs_helper(start, &mut visited, &mut result);
-        
-        result
-    }
-
-    /// Helper method for recursive DFS
-    fn dfs_helper(&self, vertex: &str, visited: &mut HashSet<String>, result: &mut Vec<String>) {
+    /// Helper method for recursive DFS; `path` holds the vertices on the recursion stack
+    fn dfs_helper<F: FnMut(&TraversalStep)>(
+        &self,
+        vertex: &str,
+        visited: &mut VisitedSet<'_>,
+        result: &mut Vec<String>,
+        path: &mut Vec<String>,
+        on_step: &mut F,
+    ) {
         // Mark as visited and add to result
-        visited.insert(vertex.to_string());
+        visited.insert(vertex);
         result.push(vertex.to_string());
-        
-        println!("Visiting: {}", vertex);
-        println!("Visited so far: {:?}", result);
-        println!("------------------------------");
-        
-        // Pause for demonstration
-        thread::sleep(Duration::from_millis(500));
+        on_step(&TraversalStep { vertex, frontier: path, visited: result });
         
         // Get sorted neighbors for consistent order
         let neighbors = self.get_sorted_neighbors(vertex);
         
         // Recursively visit all unvisited neighbors
+        path.push(vertex.to_string());
         for neighbor in neighbors {
             if !visited.contains(&neighbor) {
-                self.dfs_helper(&neighbor, visited, result);
+                self.dfs_helper(&neighbor, visited, result, path, on_step);
             }
         }
+        path.pop();
     }
 
-    /// Performs an iterative depth-first search traversal starting from the given vertex
-    fn dfs_iterative(&self, start: &str) -> Vec<String> {
-        if !self.adjacency_list.contains_key(start) {
-            return Vec::new();
-        }
-        
-        let mut visited = HashSet::new();
+    fn dfs_iterative_core<F: FnMut(&TraversalStep)>(&self, start: &str, on_step: &mut F) -> Vec<String> {
+        let mut visited = VisitedSet::new(self);
         let mut stack = Vec::new();
         let mut result = Vec::new();
         
         // Initialize with starting vertex
         stack.push(start.to_string());
         
-        println!("Starting iterative DFS traversal from vertex {}", start);
-        
-        while !stack.is_empty() {
-            // Pop the top vertex
-            let vertex = stack.pop().unwrap();
-            
+        // Pop the top vertex
+        while let Some(vertex) = stack.pop() {
             // If not visited, process it
-            if !visited.contains(&vertex) {
-                visited.insert(vertex.clone());
+            if visited.insert(&vertex) {
                 result.push(vertex.clone());
-                
-                println!("Visiting: {}", vertex);
-                println!("Stack: {:?}", stack);
-                println!("Visited so far: {:?}", result);
-                println!("------------------------------");
-                
-                // Pause for demonstration
-                thread::sleep(Duration::from_millis(500));
+                on_step(&TraversalStep { vertex: &vertex, frontier: &stack, visited: &result });
                 
                 // Get sorted neighbors in reverse order for stack
                 let mut neighbors = self.get_sorted_neighbors(&vertex);
@@ -178,26 +266,132 @@ impl Graph {
         result
     }
 
-    /// Prints a visualization of the graph structure
-    fn visualize_graph(&self) {
-        println!("\nGraph Structure:");
-        println!("------------------------------");
+    /// Performs a breadth-first search traversal starting from the given vertex
+    pub fn bfs(&self, start: &str) -> Vec<String> {
+        self.traverse(Traversal::Bfs, start, |_| {})
+    }
+
+    /// Performs a recursive depth-first search traversal starting from the given vertex
+    pub fn dfs_recursive(&self, start: &str) -> Vec<String> {
+        self.traverse(Traversal::DfsRecursive, start, |_| {})
+    }
+
+    /// Performs an iterative depth-first search traversal starting from the given vertex
+    pub fn dfs_iterative(&self, start: &str) -> Vec<String> {
+        self.traverse(Traversal::DfsIterative, start, |_| {})
+    }
+
+    /// Runs `traversal` from `start` in the given mode and returns the visit order.
+    /// Only `Animated` writes anything, so only it can fail.
+    pub fn execute(
+        &self,
+        traversal: Traversal,
+        start: &str,
+        mode: ExecutionMode<'_, TraversalFrame>,
+    ) -> io::Result<Vec<String>> {
+        match mode {
+            ExecutionMode::Silent => Ok(self.traverse(traversal, start, |_| {})),
+            ExecutionMode::Traced(callback) => {
+                Ok(self.traverse(traversal, start, |step| callback(&TraversalFrame::from(step))))
+            }
+            ExecutionMode::Animated(out, delay) => self.narrate(traversal, start, out, delay),
+        }
+    }
+
+    /// Writes every step of the traversal to `out`, pausing for `delay` between them
+    fn narrate(&self, traversal: Traversal, start: &str, out: &mut dyn Write, delay: Duration) -> io::Result<Vec<String>> {
+        if !self.contains(start) {
+            return Ok(Vec::new());
+        }
+
+        let (title, frontier_label) = match traversal {
+            Traversal::Bfs => ("BFS traversal", Some("Queue")),
+            Traversal::DfsRecursive => ("recursive DFS traversal", None),
+            Traversal::DfsIterative => ("iterative DFS traversal", Some("Stack")),
+        };
+        writeln!(out, "Starting {} from vertex {}", title, start)?;
+
+        // The hook can't return an error, so remember the first one and stop writing.
+        let mut written = Ok(());
+        let order = self.traverse(traversal, start, |step| {
+            if written.is_ok() {
+                written = write_step(out, step, frontier_label);
+            }
+
+            // Pause for demonstration
+            thread::sleep(delay);
+        });
+        written.map(|()| order)
+    }
+
+    /// Writes the adjacency list, one vertex per line
+    pub fn visualize_graph(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "\nGraph Structure:")?;
+        writeln!(out, "------------------------------")?;
         
         // Sort vertices for consistent output
-        let mut vertices: Vec<String> = self.adjacency_list.keys().cloned().collect();
-        vertices.sort();
-        
-        for vertex in vertices {
+        for vertex in self.vertices() {
             let neighbors = self.get_sorted_neighbors(&vertex);
-            println!("{} -> {:?}", vertex, neighbors);
+            writeln!(out, "{} -> {:?}", vertex, neighbors)?;
         }
         
-        println!("------------------------------");
+        writeln!(out, "------------------------------")
+    }
+}
+
+fn write_step(out: &mut dyn Write, step: &TraversalStep, frontier_label: Option<&str>) -> io::Result<()> {
+    writeln!(out, "Visiting: {}", step.vertex)?;
+    if let Some(label) = frontier_label {
+        writeln!(out, "{}: {:?}", label, step.frontier)?;
+    }
+    writeln!(out, "Visited so far: {:?}", step.visited)?;
+    writeln!(out, "------------------------------")
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Visited vertices as one bit each, numbering the vertices once per traversal
+struct VisitedSet<'g> {
+    index: HashMap<&'g str, usize>,
+    bits: BitSet,
+}
+
+impl<'g> VisitedSet<'g> {
+    fn new(graph: &'g Graph) -> Self {
+        let index: HashMap<&str, usize> =
+            graph.adjacency_list.keys().enumerate().map(|(i, vertex)| (vertex.as_str(), i)).collect();
+        let bits = BitSet::with_capacity(index.len());
+        VisitedSet { index, bits }
+    }
+
+    /// Marks `vertex` visited; returns false if it already was
+    fn insert(&mut self, vertex: &str) -> bool {
+        self.bits.set(self.index[vertex])
+    }
+
+    fn contains(&self, vertex: &str) -> bool {
+        self.bits.contains(self.index[vertex])
     }
 }
 
+/// Parses an edge list such as `"A-B A-C, B-D"` (edges separated by spaces or commas)
+/// into pairs for `Graph::from_edges`.
+pub fn parse_edges(spec: &str) -> Result<Vec<(&str, &str)>, String> {
+    spec.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.split_once('-') {
+            Some((a, b)) if !a.is_empty() && !b.is_empty() => Ok((a, b)),
+            _ => Err(format!("edge '{}' should look like A-B", token)),
+        })
+        .collect()
+}
+
 /// Creates a sample graph for demonstration
-fn create_sample_graph() -> Graph {
+pub fn create_sample_graph() -> Graph {
     let mut g = Graph::new();
     
     // Add edges to build this graph:
@@ -220,23 +414,216 @@ fn create_sample_graph() -> Graph {
     g
 }
 
-fn main() {
-    // Create a sample graph
-    let g = create_sample_graph();
-    g.visualize_graph();
-    
-    // Demonstrate BFS
-    println!("\n=== BFS Traversal ===");
-    let bfs_result = g.bfs("A");
-    println!("BFS Result: {:?}", bfs_result);
-    
-    // Demonstrate recursive DFS
-    println!("\n=== DFS Traversal (Recursive) ===");
-    let dfs_rec_result = g.dfs_recursive("A");
-    println!("DFS Recursive Result: {:?}", dfs_rec_result);
-    
-    // Demonstrate iterative DFS
-    println!("\n=== DFS Traversal (Iterative) ===");
-    let dfs_iter_result = g.dfs_iterative("A");
-    println!("DFS Iterative Result: {:?}", dfs_iter_result);
-} 
\ No newline at end of file
+/// Pause between animated steps in the demo
+const STEP_DELAY: Duration = Duration::from_millis(500);
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct GraphTraversalDemo;
+
+impl Demo for GraphTraversalDemo {
+    fn name(&self) -> &'static str {
+        "graph_traversal"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "BFS and DFS over an adjacency list"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        // Create a sample graph
+        let g = create_sample_graph();
+        g.visualize_graph(out)?;
+
+        // Demonstrate BFS
+        writeln!(out, "\n=== BFS Traversal ===")?;
+        let bfs_result = g.execute(Traversal::Bfs, "A", ExecutionMode::Animated(out, STEP_DELAY))?;
+        writeln!(out, "BFS Result: {:?}", bfs_result)?;
+
+        // Demonstrate recursive DFS
+        writeln!(out, "\n=== DFS Traversal (Recursive) ===")?;
+        let dfs_rec_result = g.execute(Traversal::DfsRecursive, "A", ExecutionMode::Animated(out, STEP_DELAY))?;
+        writeln!(out, "DFS Recursive Result: {:?}", dfs_rec_result)?;
+
+        // Demonstrate iterative DFS
+        writeln!(out, "\n=== DFS Traversal (Iterative) ===")?;
+        let dfs_iter_result = g.execute(Traversal::DfsIterative, "A", ExecutionMode::Animated(out, STEP_DELAY))?;
+        writeln!(out, "DFS Iterative Result: {:?}", dfs_iter_result)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    fn results(&self) -> Option<serde_json::Value> {
+        let g = create_sample_graph();
+        let runs = Traversal::ALL.map(|traversal| g.record(traversal, "A"));
+        serde_json::to_value(runs).ok()
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&GraphTraversalDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(result: &[String]) -> Vec<&str> {
+        result.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn edges_are_undirected_and_neighbors_sorted() {
+        let g = create_sample_graph();
+        assert_eq!(g.get_sorted_neighbors("B"), ["A", "D", "E"]);
+        assert_eq!(g.get_sorted_neighbors("F"), ["C", "E"]);
+        assert_eq!(g.get_sorted_neighbors("D"), ["B"]);
+    }
+
+    #[test]
+    fn bfs_visits_level_by_level() {
+        let g = create_sample_graph();
+        assert_eq!(names(&g.bfs("A")), ["A", "B", "C", "D", "E", "F"]);
+    }
+
+    #[test]
+    fn dfs_recursive_goes_deep_first() {
+        let g = create_sample_graph();
+        assert_eq!(names(&g.dfs_recursive("A")), ["A", "B", "D", "E", "F", "C"]);
+    }
+
+    #[test]
+    fn dfs_iterative_matches_recursive_order() {
+        let g = create_sample_graph();
+        assert_eq!(g.dfs_iterative("A"), g.dfs_recursive("A"));
+    }
+
+    #[test]
+    fn traversals_from_an_unknown_vertex_are_empty() {
+        let g = create_sample_graph();
+        assert!(g.bfs("Z").is_empty());
+        assert!(g.dfs_recursive("Z").is_empty());
+        assert!(g.dfs_iterative("Z").is_empty());
+    }
+
+    #[test]
+    fn traversals_stay_in_the_start_component() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_vertex("X");
+
+        assert_eq!(names(&g.bfs("A")), ["A", "B"]);
+        assert_eq!(names(&g.dfs_iterative("X")), ["X"]);
+    }
+
+    #[test]
+    fn bfs_trace_shows_the_queue_after_each_dequeue() {
+        let g = create_sample_graph();
+        let frames = g.trace(Traversal::Bfs, "A");
+        let frontiers: Vec<(&str, Vec<&str>)> =
+            frames.iter().map(|f| (f.vertex.as_str(), names(&f.frontier))).collect();
+        assert_eq!(
+            frontiers,
+            [
+                ("A", vec![]),
+                ("B", vec!["C"]),
+                ("C", vec!["D", "E"]),
+                ("D", vec!["E", "F"]),
+                ("E", vec!["F"]),
+                ("F", vec![]),
+            ]
+        );
+        assert_eq!(names(&frames[2].visited), ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn recursive_dfs_frontier_is_the_recursion_path() {
+        let g = create_sample_graph();
+        let frames = g.trace(Traversal::DfsRecursive, "A");
+        let d = frames.iter().find(|f| f.vertex == "D").unwrap();
+        assert_eq!(names(&d.frontier), ["A", "B"]);
+        let c = frames.iter().find(|f| f.vertex == "C").unwrap();
+        assert_eq!(names(&c.frontier), ["A", "B", "E", "F"]);
+    }
+
+    #[test]
+    fn iterative_dfs_frontier_is_the_stack() {
+        let g = create_sample_graph();
+        let frames = g.trace(Traversal::DfsIterative, "A");
+        assert_eq!(names(&frames[1].frontier), ["C"]);
+        assert_eq!(names(&frames[2].frontier), ["C", "E"]);
+    }
+
+    #[test]
+    fn every_trace_ends_with_the_full_visit_order() {
+        let g = create_sample_graph();
+        for traversal in Traversal::ALL {
+            let order = g.traverse(traversal, "A", |_| {});
+            let frames = g.trace(traversal, "A");
+            assert_eq!(frames.len(), order.len());
+            assert_eq!(frames.last().unwrap().visited, order);
+            assert_eq!(Traversal::from_name(traversal.name()), Some(traversal));
+        }
+    }
+
+    #[test]
+    fn execution_modes_agree_on_the_result() {
+        let g = create_sample_graph();
+        let silent = g.execute(Traversal::Bfs, "A", ExecutionMode::Silent).unwrap();
+
+        let mut frames = Vec::new();
+        let mut record = |frame: &TraversalFrame| frames.push(frame.clone());
+        let traced = g.execute(Traversal::Bfs, "A", ExecutionMode::Traced(&mut record)).unwrap();
+
+        let mut out = Vec::new();
+        let animated = g.execute(Traversal::Bfs, "A", ExecutionMode::Animated(&mut out, Duration::ZERO)).unwrap();
+
+        assert_eq!(silent, g.bfs("A"));
+        assert_eq!(traced, silent);
+        assert_eq!(animated, silent);
+        assert_eq!(frames, g.trace(Traversal::Bfs, "A"));
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("Starting BFS traversal from vertex A\nVisiting: A\n"), "{}", text);
+        assert_eq!(text.matches("Visiting: ").count(), silent.len());
+    }
+
+    #[test]
+    fn from_edges_lists_sorted_vertices() {
+        let g = Graph::from_edges([("c", "a"), ("b", "a")]);
+        assert_eq!(g.vertices(), ["a", "b", "c"]);
+        assert!(g.contains("b"));
+        assert!(!g.contains("z"));
+        assert!(g.trace(Traversal::Bfs, "z").is_empty());
+    }
+
+    #[test]
+    fn parse_edges_accepts_spaces_and_commas() {
+        assert_eq!(parse_edges("A-B, A-C  B-D").unwrap(), [("A", "B"), ("A", "C"), ("B", "D")]);
+        assert!(parse_edges("").unwrap().is_empty());
+        assert_eq!(parse_edges("A-B AC").unwrap_err(), "edge 'AC' should look like A-B");
+        assert!(parse_edges("A-").is_err());
+    }
+
+    #[test]
+    fn record_keeps_the_order_and_every_frame() {
+        let g = create_sample_graph();
+        let run = g.record(Traversal::DfsRecursive, "A");
+        assert_eq!(run.order, g.dfs_recursive("A"));
+        assert_eq!(run.frames, g.trace(Traversal::DfsRecursive, "A"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn traces_round_trip_through_json() {
+        let frames = create_sample_graph().trace(Traversal::DfsIterative, "A");
+        let json = serde_json::to_string(&frames).unwrap();
+        assert!(json.starts_with(r#"[{"vertex":"A","frontier":[],"visited":["A"]}"#), "{}", json);
+        assert_eq!(serde_json::from_str::<Vec<TraversalFrame>>(&json).unwrap(), frames);
+        for traversal in Traversal::ALL {
+            assert_eq!(serde_json::to_string(&traversal).unwrap(), format!("\"{}\"", traversal.name()));
+        }
+    }
+}
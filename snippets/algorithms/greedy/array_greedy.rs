@@ -0,0 +1,251 @@
+//! Greedy Array Problems in Rust
+//!
+//! Three puzzles over an array where one left-to-right pass (or two) replaces a search:
+//! - Jump game: from index `i` you may jump up to `jumps[i]` places forward. Tracking the
+//!   farthest index reachable so far answers "can the end be reached?", and counting how
+//!   often that frontier has to be pushed gives the fewest jumps, like a breadth-first
+//!   search whose levels are ranges of indices.
+//! - Gas station: stations on a loop give `gas[i]` and cost `cost[i]` to reach the next.
+//!   If the total gas covers the total cost there is a start that works, and it is the
+//!   station after the last point where the running tank went negative.
+//! - Candy: children in a row each get at least one candy, and a child rated higher than
+//!   a neighbour gets more than that neighbour. One pass fixes the left neighbours, a
+//!   second fixes the right ones, and each child keeps the larger of its two demands.
+//!
+//! All three are O(n) time; the tests check them against slower searches.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Jump Game ==========
+
+/// Whether the last index can be reached from the first; false for an empty array
+pub fn can_reach_end(jumps: &[usize]) -> bool {
+    if jumps.is_empty() {
+        return false;
+    }
+    let mut farthest = 0;
+    for (i, &jump) in jumps.iter().enumerate() {
+        if i > farthest {
+            return false;
+        }
+        farthest = farthest.max(i.saturating_add(jump));
+    }
+    true
+}
+
+/// Fewest jumps from the first index to the last, or `None` if it cannot be reached
+pub fn min_jumps(jumps: &[usize]) -> Option<usize> {
+    let last = jumps.len().checked_sub(1)?;
+    // Indices up to `level_end` are reachable with `count` jumps
+    let (mut count, mut level_end, mut farthest) = (0, 0, 0);
+    for (i, &jump) in jumps.iter().enumerate().take(last) {
+        farthest = farthest.max(i.saturating_add(jump));
+        if i == level_end {
+            if farthest <= i {
+                return None;
+            }
+            count += 1;
+            level_end = farthest;
+            if level_end >= last {
+                break;
+            }
+        }
+    }
+    Some(count)
+}
+
+// ========== Gas Station ==========
+
+/// The station to start from to drive the whole loop, or `None` if none works; with
+/// several answers, the first
+pub fn gas_station_start(gas: &[i64], cost: &[i64]) -> Option<usize> {
+    assert_eq!(gas.len(), cost.len(), "one cost per station");
+    let (mut total, mut tank, mut start) = (0, 0, 0);
+    for (i, (g, c)) in gas.iter().zip(cost).enumerate() {
+        total += g - c;
+        tank += g - c;
+        // Nothing from `start` to `i` can be the start: each would get past `i` with no
+        // more fuel than the run from `start` had, and that one ran dry
+        if tank < 0 {
+            start = i + 1;
+            tank = 0;
+        }
+    }
+    (total >= 0 && !gas.is_empty()).then_some(start)
+}
+
+// ========== Candy ==========
+
+/// Fewest candies per child so each gets one or more and beats lower-rated neighbours
+pub fn candies(ratings: &[i32]) -> Vec<u64> {
+    let mut given = vec![1u64; ratings.len()];
+    for i in 1..ratings.len() {
+        if ratings[i] > ratings[i - 1] {
+            given[i] = given[i - 1] + 1;
+        }
+    }
+    for i in (0..ratings.len().saturating_sub(1)).rev() {
+        if ratings[i] > ratings[i + 1] {
+            given[i] = given[i].max(given[i + 1] + 1);
+        }
+    }
+    given
+}
+
+pub fn min_candies(ratings: &[i32]) -> u64 {
+    candies(ratings).iter().sum()
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ArrayGreedyDemo;
+
+impl Demo for ArrayGreedyDemo {
+    fn name(&self) -> &'static str {
+        "array_greedy"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Jump game, gas station and candy distribution in linear time"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Jump Game =====")?;
+        for jumps in [&[2, 3, 1, 1, 4][..], &[3, 2, 1, 0, 4], &[1, 1, 1, 1], &[5, 0, 0, 0, 0, 0, 1], &[0]] {
+            let fewest = min_jumps(jumps).map_or("-".to_string(), |n| n.to_string());
+            writeln!(out, "{:<22} reachable {:<5} fewest jumps {}", format!("{:?}", jumps), can_reach_end(jumps), fewest)?;
+        }
+
+        writeln!(out, "\n===== Gas Station =====")?;
+        let cases: [(&[i64], &[i64]); 3] =
+            [(&[1, 2, 3, 4, 5], &[3, 4, 5, 1, 2]), (&[2, 3, 4], &[3, 4, 3]), (&[5, 1, 2, 3, 4], &[4, 4, 1, 5, 1])];
+        for (gas, cost) in cases {
+            let start = gas_station_start(gas, cost).map_or("nowhere".to_string(), |s| format!("at station {}", s));
+            writeln!(out, "gas {:?} cost {:?}: start {}", gas, cost, start)?;
+        }
+
+        writeln!(out, "\n===== Candy =====")?;
+        for ratings in [&[1, 0, 2][..], &[1, 2, 2], &[1, 3, 4, 5, 2], &[5, 4, 3, 2, 1, 2, 3]] {
+            writeln!(out, "ratings {:?} -> candies {:?}, total {}", ratings, candies(ratings), min_candies(ratings))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ArrayGreedyDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    /// Fewest jumps to each index by dynamic programming, O(n²)
+    fn min_jumps_dp(jumps: &[usize]) -> Option<usize> {
+        let mut best: Vec<Option<usize>> = vec![None; jumps.len()];
+        if let Some(first) = best.first_mut() {
+            *first = Some(0);
+        }
+        for i in 0..jumps.len() {
+            let Some(here) = best[i] else { continue };
+            for target in best.iter_mut().skip(i + 1).take(jumps[i]) {
+                *target = Some(target.map_or(here + 1, |b| b.min(here + 1)));
+            }
+        }
+        best.last().copied().flatten()
+    }
+
+    /// Tries every start and drives the loop
+    fn gas_station_brute_force(gas: &[i64], cost: &[i64]) -> Option<usize> {
+        let n = gas.len();
+        (0..n).find(|&start| {
+            let mut tank = 0;
+            (0..n).all(|k| {
+                let i = (start + k) % n;
+                tank += gas[i] - cost[i];
+                tank >= 0
+            })
+        })
+    }
+
+    /// Raises candies until every rule holds; the fixed point is the least solution
+    fn candies_by_relaxation(ratings: &[i32]) -> Vec<u64> {
+        let mut given = vec![1u64; ratings.len()];
+        loop {
+            let mut changed = false;
+            for i in 0..ratings.len() {
+                for j in [i.wrapping_sub(1), i + 1] {
+                    if j < ratings.len() && ratings[i] > ratings[j] && given[i] <= given[j] {
+                        given[i] = given[j] + 1;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return given;
+            }
+        }
+    }
+
+    #[test]
+    fn jump_game_matches_dp() {
+        let mut rng = Pcg32::new(981, 1);
+        for _ in 0..300 {
+            let len = rng.below(15) as usize;
+            let jumps: Vec<usize> = (0..len).map(|_| rng.below(4) as usize).collect();
+            let expected = min_jumps_dp(&jumps);
+            assert_eq!(min_jumps(&jumps), expected, "{:?}", jumps);
+            assert_eq!(can_reach_end(&jumps), expected.is_some(), "{:?}", jumps);
+        }
+    }
+
+    #[test]
+    fn jump_game_examples() {
+        assert_eq!(min_jumps(&[2, 3, 1, 1, 4]), Some(2));
+        assert_eq!(min_jumps(&[3, 2, 1, 0, 4]), None);
+        assert!(!can_reach_end(&[3, 2, 1, 0, 4]));
+        assert_eq!(min_jumps(&[0]), Some(0));
+        assert_eq!(min_jumps(&[usize::MAX, 0, 0]), Some(1));
+        assert_eq!(min_jumps(&[]), None);
+        assert!(!can_reach_end(&[]));
+    }
+
+    #[test]
+    fn gas_station_matches_brute_force() {
+        let mut rng = Pcg32::new(981, 2);
+        for _ in 0..300 {
+            let n = 1 + rng.below(8) as usize;
+            let gas: Vec<i64> = (0..n).map(|_| rng.below(6) as i64).collect();
+            let cost: Vec<i64> = (0..n).map(|_| rng.below(6) as i64).collect();
+            assert_eq!(gas_station_start(&gas, &cost), gas_station_brute_force(&gas, &cost), "{:?} {:?}", gas, cost);
+        }
+        assert_eq!(gas_station_start(&[1, 2, 3, 4, 5], &[3, 4, 5, 1, 2]), Some(3));
+        assert_eq!(gas_station_start(&[2, 3, 4], &[3, 4, 3]), None);
+        assert_eq!(gas_station_start(&[], &[]), None);
+    }
+
+    #[test]
+    fn candies_match_relaxation() {
+        let mut rng = Pcg32::new(981, 3);
+        for _ in 0..300 {
+            let len = rng.below(12) as usize;
+            let ratings: Vec<i32> = (0..len).map(|_| rng.below(5) as i32).collect();
+            assert_eq!(candies(&ratings), candies_by_relaxation(&ratings), "{:?}", ratings);
+        }
+    }
+
+    #[test]
+    fn candy_examples() {
+        assert_eq!(candies(&[1, 0, 2]), [2, 1, 2]);
+        assert_eq!(min_candies(&[1, 2, 2]), 4);
+        assert_eq!(min_candies(&[]), 0);
+        assert_eq!(candies(&[5, 4, 3, 2, 1, 2, 3]), [5, 4, 3, 2, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,292 @@
+//! Fractional Knapsack in Rust
+//!
+//! Fill a bag of limited capacity with the most valuable load of items:
+//! - When items can be cut, greed is optimal: take items by value per unit of weight,
+//!   best first, and cut the first one that does not fit. Any load that leaves room
+//!   for a better ratio can swap weight over and do no worse.
+//! - When items must go whole (0/1 knapsack) the same greedy can fail badly, because the
+//!   item it skips may leave a gap nothing else fills. Dynamic programming over the
+//!   capacity finds the true optimum in O(items × capacity).
+//!
+//! The fractional answer is an upper bound on the 0/1 answer, and the greedy 0/1 load is
+//! a lower bound. When the fractional greedy cuts nothing, the two bounds meet and the
+//! greedy is optimal for the whole-item problem too; the demo measures how often that
+//! and the other lucky cases come up.
+
+use crate::prng::{Pcg32, Rng};
+use demo::Demo;
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+// ========== Items ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Item {
+    pub weight: u64,
+    pub value: u64,
+}
+
+/// Items from `(weight, value)` pairs
+pub fn items(pairs: &[(u64, u64)]) -> Vec<Item> {
+    pairs.iter().map(|&(weight, value)| Item { weight, value }).collect()
+}
+
+/// Best value per unit of weight first, compared without rounding; weightless items
+/// lead, and ties keep their input order
+fn by_ratio(items: &[Item]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (items[a], items[b]);
+        match (a.weight, b.weight) {
+            (0, 0) => Ordering::Equal,
+            (0, _) => Ordering::Less,
+            (_, 0) => Ordering::Greater,
+            _ => (u128::from(b.value) * u128::from(a.weight)).cmp(&(u128::from(a.value) * u128::from(b.weight))),
+        }
+    });
+    order
+}
+
+// ========== Fractional Knapsack ==========
+
+/// The greedy fractional load: whole items, plus at most one item cut to fit
+#[derive(Debug, Clone, PartialEq)]
+pub struct FractionalLoad {
+    pub whole: Vec<usize>,
+    /// The cut item and how much of its weight went in
+    pub partial: Option<(usize, u64)>,
+    pub value: f64,
+}
+
+pub fn fractional_knapsack(items: &[Item], capacity: u64) -> FractionalLoad {
+    let mut room = capacity;
+    let mut whole = Vec::new();
+    let mut partial = None;
+    let mut value = 0.0;
+    for i in by_ratio(items) {
+        let item = items[i];
+        if item.weight <= room {
+            room -= item.weight;
+            whole.push(i);
+            value += item.value as f64;
+        } else {
+            if room > 0 {
+                partial = Some((i, room));
+                value += item.value as f64 * room as f64 / item.weight as f64;
+            }
+            break;
+        }
+    }
+    FractionalLoad { whole, partial, value }
+}
+
+// ========== 0/1 Knapsack ==========
+
+/// Items taken whole, and their total value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Load {
+    pub items: Vec<usize>,
+    pub value: u64,
+}
+
+/// The fractional greedy with the cut item left out: a fast 0/1 answer, not always best
+pub fn greedy_01(items: &[Item], capacity: u64) -> Load {
+    let load = fractional_knapsack(items, capacity);
+    let mut taken = load.whole;
+    taken.sort_unstable();
+    let value = taken.iter().map(|&i| items[i].value).sum();
+    Load { items: taken, value }
+}
+
+/// The best 0/1 load, by dynamic programming over the capacity
+pub fn knapsack_01(items: &[Item], capacity: u64) -> Load {
+    let capacity = usize::try_from(capacity).expect("capacity fits in memory");
+    // best[i][c]: the most value from the first i items within capacity c
+    let mut best = vec![vec![0u64; capacity + 1]; items.len() + 1];
+    for (i, item) in items.iter().enumerate() {
+        let weight = usize::try_from(item.weight).unwrap_or(usize::MAX);
+        for c in 0..=capacity {
+            best[i + 1][c] = best[i][c];
+            if weight <= c {
+                best[i + 1][c] = best[i + 1][c].max(best[i][c - weight] + item.value);
+            }
+        }
+    }
+    // Walk back: item i was taken wherever it changed the best value
+    let mut taken = Vec::new();
+    let mut c = capacity;
+    for i in (0..items.len()).rev() {
+        if best[i + 1][c] != best[i][c] {
+            taken.push(i);
+            c -= items[i].weight as usize;
+        }
+    }
+    taken.reverse();
+    Load { items: taken, value: best[items.len()][capacity] }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FractionalKnapsackDemo;
+
+impl Demo for FractionalKnapsackDemo {
+    fn name(&self) -> &'static str {
+        "fractional_knapsack"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fractional knapsack greedily, against 0/1 knapsack by dynamic programming"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Fractional Knapsack =====")?;
+        let set = items(&[(10, 60), (20, 100), (30, 120)]);
+        writeln!(out, "items (weight, value): (10, 60) (20, 100) (30, 120), capacity 50")?;
+        let load = fractional_knapsack(&set, 50);
+        writeln!(out, "whole items: {:?}", load.whole)?;
+        if let Some((i, weight)) = load.partial {
+            writeln!(out, "cut item {}: {} of {}", i, weight, set[i].weight)?;
+        }
+        writeln!(out, "value: {:.1}", load.value)?;
+
+        writeln!(out, "\n===== The Same Items Whole =====")?;
+        let greedy = greedy_01(&set, 50);
+        let best = knapsack_01(&set, 50);
+        writeln!(out, "greedy by ratio: {:?} worth {}", greedy.items, greedy.value)?;
+        writeln!(out, "dynamic programming: {:?} worth {}", best.items, best.value)?;
+
+        writeln!(out, "\n===== How Often Greedy Is Optimal for 0/1 =====")?;
+        writeln!(out, "1000 random instances of 8 items and capacity 40; weights 1-20, except")?;
+        writeln!(out, "every tenth instance, where all items weigh 7:")?;
+        let mut rng = Pcg32::new(980, 0);
+        let (mut optimal, mut uncut, mut equal_weights_optimal) = (0, 0, 0);
+        let mut worst = 1.0f64;
+        let mut total_ratio = 0.0;
+        for round in 0..1000 {
+            let equal = round % 10 == 0;
+            let set: Vec<Item> = (0..8)
+                .map(|_| Item { weight: if equal { 7 } else { 1 + rng.below(20) }, value: 1 + rng.below(50) })
+                .collect();
+            let fractional = fractional_knapsack(&set, 40);
+            let greedy = greedy_01(&set, 40).value;
+            let best = knapsack_01(&set, 40).value;
+            let ratio = greedy as f64 / best as f64;
+            total_ratio += ratio;
+            worst = worst.min(ratio);
+            optimal += usize::from(greedy == best);
+            if fractional.partial.is_none() {
+                uncut += 1;
+            }
+            if equal && greedy == best {
+                equal_weights_optimal += 1;
+            }
+        }
+        writeln!(out, "greedy matched the optimum: {} of 1000", optimal)?;
+        writeln!(out, "nothing cut, so optimal by the bound: {} of 1000", uncut)?;
+        writeln!(out, "equal weights (100 instances), optimal: {} of 100", equal_weights_optimal)?;
+        writeln!(out, "greedy / optimum: mean {:.3}, worst {:.3}", total_ratio / 1000.0, worst)?;
+
+        writeln!(out, "\n===== A Bad Case for Greedy =====")?;
+        let trap = items(&[(1, 2), (100, 100)]);
+        writeln!(out, "items (1, 2) and (100, 100), capacity 100")?;
+        writeln!(out, "greedy takes value {}, the optimum is {}", greedy_01(&trap, 100).value, knapsack_01(&trap, 100).value)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FractionalKnapsackDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_items(rng: &mut Pcg32, count: usize) -> Vec<Item> {
+        (0..count).map(|_| Item { weight: rng.below(15), value: rng.below(40) }).collect()
+    }
+
+    fn best_by_brute_force(items: &[Item], capacity: u64) -> u64 {
+        (0u32..1 << items.len())
+            .map(|mask| (0..items.len()).filter(move |i| mask >> i & 1 == 1).map(|i| items[i]))
+            .filter_map(|chosen| {
+                let (weight, value) = chosen.fold((0, 0), |(w, v), item| (w + item.weight, v + item.value));
+                (weight <= capacity).then_some(value)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn textbook_fractional_example() {
+        let set = items(&[(10, 60), (20, 100), (30, 120)]);
+        let load = fractional_knapsack(&set, 50);
+        assert_eq!(load.whole, [0, 1]);
+        assert_eq!(load.partial, Some((2, 20)));
+        assert!((load.value - 240.0).abs() < 1e-9);
+        assert_eq!(greedy_01(&set, 50), Load { items: vec![0, 1], value: 160 });
+        assert_eq!(knapsack_01(&set, 50), Load { items: vec![1, 2], value: 220 });
+    }
+
+    #[test]
+    fn dp_matches_brute_force() {
+        let mut rng = Pcg32::new(980, 1);
+        for _ in 0..100 {
+            let count = rng.below(10) as usize;
+            let set = random_items(&mut rng, count);
+            let capacity = rng.below(40);
+            let best = knapsack_01(&set, capacity);
+            assert_eq!(best.value, best_by_brute_force(&set, capacity));
+            assert!(best.items.iter().map(|&i| set[i].weight).sum::<u64>() <= capacity);
+            assert_eq!(best.items.iter().map(|&i| set[i].value).sum::<u64>(), best.value);
+        }
+    }
+
+    #[test]
+    fn bounds_sandwich_the_optimum() {
+        let mut rng = Pcg32::new(980, 2);
+        for _ in 0..200 {
+            let count = rng.below(12) as usize;
+            let set = random_items(&mut rng, count);
+            let capacity = rng.below(50);
+            let fractional = fractional_knapsack(&set, capacity);
+            let greedy = greedy_01(&set, capacity).value;
+            let best = knapsack_01(&set, capacity).value;
+            assert!(greedy <= best);
+            assert!(best as f64 <= fractional.value + 1e-9);
+            // Nothing cut: the fractional bound is reached by whole items
+            if fractional.partial.is_none() {
+                assert_eq!(greedy, best);
+            }
+        }
+    }
+
+    #[test]
+    fn equal_weights_make_greedy_optimal() {
+        let mut rng = Pcg32::new(980, 3);
+        for _ in 0..100 {
+            let set: Vec<Item> = (0..8).map(|_| Item { weight: 5, value: rng.below(30) }).collect();
+            let capacity = rng.below(45);
+            assert_eq!(greedy_01(&set, capacity).value, knapsack_01(&set, capacity).value);
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(fractional_knapsack(&[], 10).value, 0.0);
+        assert_eq!(knapsack_01(&[], 10).value, 0);
+        let set = items(&[(0, 5), (3, 9), (4, 4)]);
+        let none = fractional_knapsack(&set, 0);
+        assert_eq!((none.whole, none.partial), (vec![0], None));
+        assert_eq!(knapsack_01(&set, 0).value, 5);
+        // Greedy can be arbitrarily far off for whole items
+        let trap = items(&[(1, 2), (100, 100)]);
+        assert_eq!(greedy_01(&trap, 100).value, 2);
+        assert_eq!(knapsack_01(&trap, 100).value, 100);
+    }
+}
@@ -0,0 +1,332 @@
+//! Interval Scheduling in Rust
+//!
+//! Three greedy algorithms over intervals on a line, each a sort followed by one pass:
+//! - Activity selection keeps as many pairwise disjoint intervals as possible by always
+//!   taking the one that ends first among those still compatible.
+//! - Meeting rooms gives every meeting a room, reusing the room that frees up earliest
+//!   (a min-heap of end times) and opening a new one only when none is free yet.
+//! - Merging sorts by start and folds each interval into the previous one when they meet.
+//!
+//! Intervals are half-open, `[start, end)`, so one meeting may start the moment another
+//! ends. The greedy choices are justified by exchange arguments, written next to each
+//! function and checked with `debug_assert!` as the loop runs.
+
+use demo::Demo;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Interval ==========
+
+/// The half-open range `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        assert!(start <= end, "interval ends before it starts: [{}, {})", start, end);
+        Interval { start, end }
+    }
+
+    /// Whether the two share a point; touching ends do not count
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn len(&self) -> i64 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// Builds intervals from `(start, end)` pairs
+pub fn intervals(pairs: &[(i64, i64)]) -> Vec<Interval> {
+    pairs.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+}
+
+// ========== Activity Selection ==========
+
+/// Indices of a largest set of pairwise non-overlapping intervals, in order of end time
+///
+/// Exchange argument: let `g1` be the interval that ends first and `O` any optimal
+/// selection, its first interval `o1`. Since `g1.end <= o1.end`, swapping `o1` for `g1`
+/// leaves `O` disjoint and the same size, so some optimum starts with `g1`. Removing
+/// everything that overlaps `g1` leaves the same problem on the rest, and induction
+/// finishes it. Equivalently, the greedy "stays ahead": its k-th interval never ends
+/// later than the k-th interval of any disjoint selection. The loop asserts the two
+/// local facts the argument rests on: each kept interval starts after the last kept one
+/// ends, and each skipped interval clashes with the last kept one.
+pub fn activity_selection(intervals: &[Interval]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&i| (intervals[i].end, intervals[i].start));
+    let mut chosen: Vec<usize> = Vec::new();
+    for i in order {
+        let candidate = intervals[i];
+        match chosen.last().map(|&last| intervals[last]) {
+            Some(last) if candidate.start < last.end => {
+                debug_assert!(candidate.end >= last.end && candidate.overlaps(&last));
+            }
+            last => {
+                debug_assert!(last.is_none_or(|l| l.end <= candidate.start));
+                chosen.push(i);
+            }
+        }
+    }
+    chosen
+}
+
+// ========== Meeting Rooms ==========
+
+/// A room number for each meeting, using as few rooms as possible; rooms count from 0
+///
+/// Meetings are handled by start time. When a meeting opens a new room, every room in
+/// use holds a meeting that started no later and has not ended yet, so all of them
+/// overlap at this start time and no schedule can do with fewer rooms. The heap holds
+/// one entry per room, so its size is the room count, asserted against the number of
+/// rooms handed out.
+pub fn assign_rooms(meetings: &[Interval]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..meetings.len()).collect();
+    order.sort_by_key(|&i| (meetings[i].start, meetings[i].end));
+    let mut rooms = vec![0; meetings.len()];
+    // (end time, room) for the meeting currently in each room, earliest end on top
+    let mut busy_until: BinaryHeap<Reverse<(i64, usize)>> = BinaryHeap::new();
+    let mut opened = 0;
+    for i in order {
+        let meeting = meetings[i];
+        let room = match busy_until.peek() {
+            Some(&Reverse((end, room))) if end <= meeting.start => {
+                busy_until.pop();
+                room
+            }
+            _ => {
+                debug_assert!(busy_until.iter().all(|&Reverse((end, _))| end > meeting.start));
+                opened += 1;
+                opened - 1
+            }
+        };
+        rooms[i] = room;
+        busy_until.push(Reverse((meeting.end, room)));
+        debug_assert_eq!(busy_until.len(), opened);
+    }
+    rooms
+}
+
+/// Fewest rooms that hold all meetings without two overlapping in one room
+pub fn min_meeting_rooms(meetings: &[Interval]) -> usize {
+    assign_rooms(meetings).into_iter().max().map_or(0, |room| room + 1)
+}
+
+/// Largest number of non-empty intervals sharing a point, found by sweeping the ends
+pub fn max_overlap(intervals: &[Interval]) -> usize {
+    // An end sorts before a start at the same position, since the ranges are half-open
+    let mut events: Vec<(i64, i32)> =
+        intervals.iter().filter(|i| !i.is_empty()).flat_map(|i| [(i.start, 1), (i.end, -1)]).collect();
+    events.sort_unstable();
+    let (mut depth, mut deepest) = (0i32, 0i32);
+    for (_, delta) in events {
+        depth += delta;
+        deepest = deepest.max(depth);
+    }
+    deepest as usize
+}
+
+// ========== Merging ==========
+
+/// The union of the intervals as sorted, disjoint intervals; touching ones are joined
+///
+/// After sorting by start, an interval that reaches the last merged one can only extend
+/// it, because everything merged so far starts no later. One that does not reach it
+/// starts past every point covered so far, so no later interval can bridge the gap
+/// either. The loop asserts that each new output interval starts strictly after the
+/// previous one ends.
+pub fn merge_intervals(intervals: &[Interval]) -> Vec<Interval> {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_unstable();
+    let mut merged: Vec<Interval> = Vec::new();
+    for interval in sorted {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+            _ => {
+                debug_assert!(merged.last().is_none_or(|last| last.end < interval.start));
+                merged.push(interval);
+            }
+        }
+    }
+    merged
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct IntervalsDemo;
+
+impl Demo for IntervalsDemo {
+    fn name(&self) -> &'static str {
+        "intervals"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Greedy interval scheduling: activity selection, meeting rooms and merging"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Activity Selection =====")?;
+        let activities = intervals(&[(1, 4), (3, 5), (0, 6), (5, 7), (3, 9), (5, 9), (6, 10), (8, 11), (8, 12), (2, 14), (12, 16)]);
+        let all: Vec<String> = activities.iter().map(Interval::to_string).collect();
+        writeln!(out, "activities: {}", all.join(" "))?;
+        let chosen = activity_selection(&activities);
+        let kept: Vec<String> = chosen.iter().map(|&i| activities[i].to_string()).collect();
+        writeln!(out, "kept {} of {}: {}", chosen.len(), activities.len(), kept.join(" "))?;
+
+        writeln!(out, "\n===== Meeting Rooms =====")?;
+        let meetings = intervals(&[(9, 10), (9, 12), (10, 11), (11, 13), (12, 13), (10, 14), (13, 15)]);
+        let rooms = assign_rooms(&meetings);
+        let mut order: Vec<usize> = (0..meetings.len()).collect();
+        order.sort_by_key(|&i| meetings[i]);
+        for i in order {
+            writeln!(out, "{:<9} room {}", meetings[i].to_string(), rooms[i])?;
+        }
+        writeln!(
+            out,
+            "rooms needed: {}, most meetings at one time: {}",
+            min_meeting_rooms(&meetings),
+            max_overlap(&meetings)
+        )?;
+
+        writeln!(out, "\n===== Merging =====")?;
+        for pairs in [&[(1, 3), (2, 6), (8, 10), (15, 18)][..], &[(1, 4), (4, 5)], &[(5, 7), (1, 10), (12, 13), (11, 12)]] {
+            let input: Vec<String> = intervals(pairs).iter().map(Interval::to_string).collect();
+            let output: Vec<String> = merge_intervals(&intervals(pairs)).iter().map(Interval::to_string).collect();
+            writeln!(out, "{} -> {}", input.join(" "), output.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&IntervalsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    fn random_intervals(rng: &mut Pcg32, count: usize, span: u64) -> Vec<Interval> {
+        (0..count)
+            .map(|_| {
+                let start = rng.below(span) as i64;
+                Interval::new(start, start + 1 + rng.below(span / 3) as i64)
+            })
+            .collect()
+    }
+
+    fn disjoint(intervals: &[Interval], chosen: &[usize]) -> bool {
+        chosen.iter().enumerate().all(|(k, &a)| chosen[..k].iter().all(|&b| !intervals[a].overlaps(&intervals[b])))
+    }
+
+    /// Every disjoint subset, each listed in order of end time
+    fn disjoint_subsets(intervals: &[Interval]) -> Vec<Vec<Interval>> {
+        (0u32..1 << intervals.len())
+            .map(|mask| (0..intervals.len()).filter(|i| mask >> i & 1 == 1).collect::<Vec<_>>())
+            .filter(|set| disjoint(intervals, set))
+            .map(|set| {
+                let mut chosen: Vec<Interval> = set.iter().map(|&i| intervals[i]).collect();
+                chosen.sort_by_key(|i| i.end);
+                chosen
+            })
+            .collect()
+    }
+
+    #[test]
+    fn activity_selection_matches_brute_force_and_stays_ahead() {
+        let mut rng = Pcg32::new(978, 1);
+        for _ in 0..60 {
+            let count = 1 + rng.below(10) as usize;
+            let set = random_intervals(&mut rng, count, 20);
+            let chosen = activity_selection(&set);
+            assert!(disjoint(&set, &chosen));
+            let greedy: Vec<Interval> = chosen.iter().map(|&i| set[i]).collect();
+            let others = disjoint_subsets(&set);
+            assert_eq!(chosen.len(), others.iter().map(Vec::len).max().unwrap());
+            // The k-th greedy interval ends no later than the k-th of any other selection
+            for other in &others {
+                assert!(greedy.iter().zip(other).all(|(g, o)| g.end <= o.end), "{:?} vs {:?}", greedy, other);
+            }
+        }
+    }
+
+    #[test]
+    fn classic_activity_example() {
+        let set = intervals(&[(1, 4), (3, 5), (0, 6), (5, 7), (3, 9), (5, 9), (6, 10), (8, 11), (8, 12), (2, 14), (12, 16)]);
+        assert_eq!(activity_selection(&set), [0, 3, 7, 10]);
+        assert!(activity_selection(&[]).is_empty());
+        // Half-open: back-to-back intervals are compatible
+        assert_eq!(activity_selection(&intervals(&[(0, 1), (1, 2), (2, 3)])).len(), 3);
+    }
+
+    #[test]
+    fn rooms_equal_the_deepest_overlap() {
+        let mut rng = Pcg32::new(978, 2);
+        for _ in 0..100 {
+            let count = rng.below(30) as usize;
+            let meetings = random_intervals(&mut rng, count, 40);
+            let rooms = assign_rooms(&meetings);
+            // No room is double-booked
+            for (a, b) in (0..count).flat_map(|a| (a + 1..count).map(move |b| (a, b))) {
+                assert!(rooms[a] != rooms[b] || !meetings[a].overlaps(&meetings[b]));
+            }
+            // Overlap depth is a lower bound, and the greedy meets it
+            assert_eq!(min_meeting_rooms(&meetings), max_overlap(&meetings));
+        }
+    }
+
+    #[test]
+    fn meeting_room_edge_cases() {
+        assert_eq!(min_meeting_rooms(&[]), 0);
+        assert_eq!(min_meeting_rooms(&intervals(&[(0, 30), (5, 10), (15, 20)])), 2);
+        assert_eq!(min_meeting_rooms(&intervals(&[(7, 10), (2, 4)])), 1);
+        assert_eq!(min_meeting_rooms(&intervals(&[(1, 5), (1, 5), (1, 5)])), 3);
+        assert_eq!(assign_rooms(&intervals(&[(0, 2), (2, 4), (1, 3)])), [0, 0, 1]);
+    }
+
+    #[test]
+    fn merged_intervals_cover_the_same_points() {
+        let mut rng = Pcg32::new(978, 3);
+        for _ in 0..100 {
+            let count = rng.below(12) as usize;
+            let set = random_intervals(&mut rng, count, 50);
+            let merged = merge_intervals(&set);
+            assert!(merged.windows(2).all(|w| w[0].end < w[1].start));
+            for point in -1..70 {
+                let inside = |list: &[Interval]| list.iter().any(|i| i.start <= point && point < i.end);
+                assert_eq!(inside(&set), inside(&merged), "point {}", point);
+            }
+        }
+        assert_eq!(merge_intervals(&intervals(&[(1, 3), (2, 6), (8, 10), (15, 18)])), intervals(&[(1, 6), (8, 10), (15, 18)]));
+        assert_eq!(merge_intervals(&intervals(&[(1, 4), (4, 5)])), intervals(&[(1, 5)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ends before it starts")]
+    fn rejects_backwards_interval() {
+        Interval::new(5, 2);
+    }
+}
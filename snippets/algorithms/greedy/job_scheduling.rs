@@ -0,0 +1,276 @@
+//! Job Sequencing and Rope Merging in Rust
+//!
+//! Two greedy problems where the order of decisions is the whole algorithm:
+//! - Job sequencing: each job takes one unit of time, earns its profit if it finishes by
+//!   its deadline, and only one job runs at a time. Taking jobs from most to least
+//!   profitable and putting each in the latest free slot before its deadline is optimal;
+//!   a late slot leaves the early ones open for jobs with tighter deadlines.
+//! - Rope merging: joining two ropes costs their combined length, and all ropes must end
+//!   up as one. Always joining the two shortest is optimal, for the same reason Huffman
+//!   coding is: the total cost is each rope's length times the number of joins it goes
+//!   through, so the short ones should go through the most.
+//!
+//! Finding the latest free slot is the interesting part of job sequencing. A scan back
+//! from the deadline is O(deadline) per job; a union-find where each filled slot points
+//! at the slot before it answers in near-constant time.
+
+use demo::Demo;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{self, Write};
+
+// ========== Job Sequencing ==========
+
+/// A unit-time job that pays `profit` if it runs in one of the slots `1..=deadline`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Job {
+    pub deadline: usize,
+    pub profit: u64,
+}
+
+/// Jobs from `(deadline, profit)` pairs
+pub fn jobs(pairs: &[(usize, u64)]) -> Vec<Job> {
+    pairs.iter().map(|&(deadline, profit)| Job { deadline, profit }).collect()
+}
+
+/// Which job runs in each time slot, and what the schedule earns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// `slots[t]` is the index of the job in slot `t + 1`, if any
+    pub slots: Vec<Option<usize>>,
+    pub profit: u64,
+}
+
+impl Schedule {
+    /// Indices of the scheduled jobs, in the order they run
+    pub fn order(&self) -> Vec<usize> {
+        self.slots.iter().flatten().copied().collect()
+    }
+}
+
+/// Latest free slot at or before a time; slot 0 stands for "none left"
+struct FreeSlots {
+    parent: Vec<usize>,
+}
+
+impl FreeSlots {
+    fn new(last: usize) -> Self {
+        FreeSlots { parent: (0..=last).collect() }
+    }
+
+    fn find(&mut self, slot: usize) -> usize {
+        let mut root = slot;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Path compression: point everything on the way straight at the answer
+        let mut node = slot;
+        while self.parent[node] != root {
+            let next = self.parent[node];
+            self.parent[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    /// Marks `slot` used, so lookups fall through to the one before it
+    fn fill(&mut self, slot: usize) {
+        self.parent[slot] = slot - 1;
+    }
+}
+
+/// A most profitable schedule; ties go to the job listed first
+pub fn sequence_jobs(jobs: &[Job]) -> Schedule {
+    // No job can use a slot past the last deadline, nor past the number of jobs
+    let last = jobs.iter().map(|j| j.deadline).max().unwrap_or(0).min(jobs.len());
+    let mut order: Vec<usize> = (0..jobs.len()).collect();
+    order.sort_by_key(|&i| Reverse(jobs[i].profit));
+    let mut free = FreeSlots::new(last);
+    let mut slots = vec![None; last];
+    let mut profit = 0;
+    for i in order {
+        let slot = free.find(jobs[i].deadline.min(last));
+        if slot > 0 {
+            free.fill(slot);
+            slots[slot - 1] = Some(i);
+            profit += jobs[i].profit;
+        }
+    }
+    Schedule { slots, profit }
+}
+
+/// Whether the jobs can all meet their deadlines, one per slot
+pub fn is_feasible(jobs: &[Job], chosen: &[usize]) -> bool {
+    let mut deadlines: Vec<usize> = chosen.iter().map(|&i| jobs[i].deadline).collect();
+    deadlines.sort_unstable();
+    deadlines.iter().enumerate().all(|(k, &d)| d > k)
+}
+
+// ========== Rope Merging ==========
+
+/// The cheapest way to join all ropes: each join as `(a, b)`, the lengths joined
+pub fn merge_ropes(lengths: &[u64]) -> Vec<(u64, u64)> {
+    let mut heap: BinaryHeap<Reverse<u64>> = lengths.iter().map(|&l| Reverse(l)).collect();
+    let mut joins = Vec::with_capacity(lengths.len().saturating_sub(1));
+    while heap.len() > 1 {
+        let Reverse(a) = heap.pop().expect("at least two ropes");
+        let Reverse(b) = heap.pop().expect("at least two ropes");
+        joins.push((a, b));
+        heap.push(Reverse(a + b));
+    }
+    joins
+}
+
+/// Total cost of the cheapest way to join all ropes
+pub fn min_merge_cost(lengths: &[u64]) -> u64 {
+    merge_ropes(lengths).iter().map(|&(a, b)| a + b).sum()
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct JobSchedulingDemo;
+
+impl Demo for JobSchedulingDemo {
+    fn name(&self) -> &'static str {
+        "job_scheduling"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Job sequencing with deadlines and greedy rope merging"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Job Sequencing =====")?;
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        let set = jobs(&[(2, 100), (1, 19), (2, 27), (1, 25), (3, 15), (4, 30), (4, 20)]);
+        writeln!(out, "job deadline profit")?;
+        for (name, job) in names.iter().zip(&set) {
+            writeln!(out, "{:>3} {:>8} {:>6}", name, job.deadline, job.profit)?;
+        }
+        let schedule = sequence_jobs(&set);
+        for (t, slot) in schedule.slots.iter().enumerate() {
+            let job = slot.map_or("-", |i| names[i]);
+            writeln!(out, "slot {}: {}", t + 1, job)?;
+        }
+        writeln!(out, "total profit: {}", schedule.profit)?;
+
+        writeln!(out, "\n===== Rope Merging =====")?;
+        let ropes = [4, 3, 2, 6, 8];
+        writeln!(out, "ropes: {:?}", ropes)?;
+        let mut total = 0;
+        for (a, b) in merge_ropes(&ropes) {
+            total += a + b;
+            writeln!(out, "join {:>2} + {:>2} = {:>2}, running cost {:>2}", a, b, a + b, total)?;
+        }
+        // Left to right, the first rope goes through every join
+        let (mut length, mut in_order) = (ropes[0], 0);
+        for rope in &ropes[1..] {
+            length += rope;
+            in_order += length;
+        }
+        writeln!(out, "greedy cost {}, joining left to right costs {}", min_merge_cost(&ropes), in_order)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&JobSchedulingDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Pcg32, Rng};
+
+    fn best_by_brute_force(jobs: &[Job]) -> u64 {
+        (0u32..1 << jobs.len())
+            .map(|mask| (0..jobs.len()).filter(|i| mask >> i & 1 == 1).collect::<Vec<_>>())
+            .filter(|chosen| is_feasible(jobs, chosen))
+            .map(|chosen| chosen.iter().map(|&i| jobs[i].profit).sum())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Tries every pair to join first, then recurses
+    fn cheapest_by_brute_force(lengths: &[u64]) -> u64 {
+        if lengths.len() < 2 {
+            return 0;
+        }
+        let mut best = u64::MAX;
+        for i in 0..lengths.len() {
+            for j in i + 1..lengths.len() {
+                let joined = lengths[i] + lengths[j];
+                let mut rest: Vec<u64> =
+                    lengths.iter().enumerate().filter(|&(k, _)| k != i && k != j).map(|(_, &l)| l).collect();
+                rest.push(joined);
+                best = best.min(joined + cheapest_by_brute_force(&rest));
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn classic_job_example() {
+        let set = jobs(&[(2, 100), (1, 19), (2, 27), (1, 25), (3, 15)]);
+        let schedule = sequence_jobs(&set);
+        assert_eq!(schedule.profit, 142);
+        assert_eq!(schedule.slots, [Some(2), Some(0), Some(4)]);
+        assert_eq!(schedule.order(), [2, 0, 4]);
+    }
+
+    #[test]
+    fn job_sequencing_matches_brute_force() {
+        let mut rng = Pcg32::new(979, 1);
+        for _ in 0..150 {
+            let count = rng.below(11) as usize;
+            let set: Vec<Job> =
+                (0..count).map(|_| Job { deadline: rng.below(7) as usize, profit: 1 + rng.below(50) }).collect();
+            let schedule = sequence_jobs(&set);
+            let chosen = schedule.order();
+            assert!(is_feasible(&set, &chosen));
+            for (t, slot) in schedule.slots.iter().enumerate() {
+                assert!(slot.is_none_or(|i| set[i].deadline > t));
+            }
+            assert_eq!(schedule.profit, chosen.iter().map(|&i| set[i].profit).sum::<u64>());
+            assert_eq!(schedule.profit, best_by_brute_force(&set), "{:?}", set);
+        }
+    }
+
+    #[test]
+    fn huge_deadlines_and_zero_deadlines() {
+        let set = jobs(&[(usize::MAX, 5), (0, 100), (usize::MAX, 7)]);
+        let schedule = sequence_jobs(&set);
+        assert_eq!(schedule.profit, 12);
+        assert_eq!(schedule.slots.len(), 3);
+        assert_eq!(sequence_jobs(&[]), Schedule { slots: vec![], profit: 0 });
+    }
+
+    #[test]
+    fn rope_merging_matches_brute_force() {
+        let mut rng = Pcg32::new(979, 2);
+        for _ in 0..60 {
+            let count = rng.below(7) as usize;
+            let ropes: Vec<u64> = (0..count).map(|_| 1 + rng.below(20)).collect();
+            let joins = merge_ropes(&ropes);
+            assert_eq!(joins.len(), count.saturating_sub(1));
+            assert_eq!(min_merge_cost(&ropes), cheapest_by_brute_force(&ropes), "{:?}", ropes);
+        }
+        assert_eq!(min_merge_cost(&[4, 3, 2, 6]), 29);
+        assert_eq!(min_merge_cost(&[7]), 0);
+    }
+
+    #[test]
+    fn rope_cost_is_huffman_weighted_length() {
+        let data = b"this is an example of a huffman tree";
+        let counts = crate::huffman::frequencies(data);
+        let lengths = crate::huffman::code_lengths(&counts);
+        let weighted: u64 = (0..256).map(|b| counts[b] * u64::from(lengths[b])).sum();
+        let ropes: Vec<u64> = counts.iter().copied().filter(|&c| c > 0).collect();
+        assert_eq!(min_merge_cost(&ropes), weighted);
+    }
+}
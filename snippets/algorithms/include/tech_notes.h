@@ -0,0 +1,97 @@
+/* Generated by cbindgen from snippets/algorithms/ffi.rs; do not edit by hand. */
+
+#ifndef TECH_NOTES_H
+#define TECH_NOTES_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every `tn_` function.
+typedef enum TnStatus {
+  TN_STATUS_OK = 0,
+  // A required pointer argument was null
+  TN_STATUS_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8
+  TN_STATUS_INVALID_UTF8 = 2,
+  // An algorithm or traversal name that doesn't exist
+  TN_STATUS_UNKNOWN_NAME = 3,
+  // The start vertex is not in the graph
+  TN_STATUS_UNKNOWN_VERTEX = 4,
+  // The output buffer can't hold the result; see the `needed` out-parameter
+  TN_STATUS_BUFFER_TOO_SMALL = 5,
+  // The Rust code panicked; the call had no effect
+  TN_STATUS_PANICKED = 6,
+} TnStatus;
+
+// An undirected graph owned by Rust; C holds it only through a pointer.
+typedef struct TnGraph TnGraph;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// A static, NUL-terminated description of `status`.
+const char *tn_status_message(enum TnStatus status);
+
+// Sorts `len` values at `values` in place with merge sort.
+//
+// # Safety
+//
+// `values` must point to `len` initialized `int32_t`s, or may be null when `len` is 0.
+enum TnStatus tn_sort_i32(int32_t *values, size_t len);
+
+// Sorts `len` values at `values` in place with the named algorithm (`"quick"`,
+// `"heap"`, ...; see `SortAlgorithm::name`).
+//
+// # Safety
+//
+// `algorithm` must be a NUL-terminated string, and `values` must point to `len`
+// initialized `int32_t`s, or may be null when `len` is 0.
+enum TnStatus tn_sort_i32_with(const char *algorithm, int32_t *values, size_t len);
+
+// A new, empty graph. Release it with `tn_graph_free`.
+struct TnGraph *tn_graph_new(void);
+
+// Frees a graph from `tn_graph_new`. Null is ignored.
+//
+// # Safety
+//
+// `graph` must be null or a pointer from `tn_graph_new` that hasn't been freed yet.
+void tn_graph_free(struct TnGraph *graph);
+
+// Adds the undirected edge `a`-`b`, creating either vertex if needed.
+//
+// # Safety
+//
+// `graph` must be a live pointer from `tn_graph_new`, and `a` and `b` NUL-terminated
+// strings.
+enum TnStatus tn_graph_add_edge(struct TnGraph *graph, const char *a, const char *b);
+
+// Runs the named traversal (`"bfs"`, `"dfs-recursive"` or `"dfs-iterative"`) from
+// `start` and writes the visit order into `out` as a NUL-terminated, space-separated
+// list, e.g. `"A B C"`.
+//
+// `*needed` is always set to the buffer size the result takes, including the NUL, so a
+// caller can pass a null `out` with `out_len` 0 to ask for the size first. When
+// `out_len` is too small nothing is written and `BufferTooSmall` is returned.
+//
+// # Safety
+//
+// `graph` must be a live pointer from `tn_graph_new`, `traversal` and `start`
+// NUL-terminated strings, `out` valid for `out_len` bytes (or null when `out_len` is
+// 0) and `needed` a valid pointer.
+enum TnStatus tn_graph_traverse(const struct TnGraph *graph,
+                                const char *traversal,
+                                const char *start,
+                                char *out,
+                                size_t out_len,
+                                size_t *needed);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TECH_NOTES_H */
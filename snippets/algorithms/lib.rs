@@ -0,0 +1,123 @@
+//! Algorithm snippets from the tech notes.
+//!
+//! Each module mirrors one topic directory and exposes its functions plus a `Demo`
+//! implementation, registered in `DEMOS`, whose `run_demo` wrapper the matching example
+//! binary calls.
+
+#[path = "greedy/array_greedy.rs"]
+pub mod array_greedy;
+#[path = "number-theory/bigint.rs"]
+pub mod bigint;
+#[path = "bit-manipulation/bit_tricks.rs"]
+pub mod bit_tricks;
+#[path = "bit-manipulation/bitset.rs"]
+pub mod bitset;
+#[path = "compression/bwt.rs"]
+pub mod bwt;
+#[path = "encoding/checksums.rs"]
+pub mod checksums;
+#[path = "backtracking/combinatorics.rs"]
+pub mod combinatorics;
+#[path = "geometry/convex_hull.rs"]
+pub mod convex_hull;
+#[path = "backtracking/csp.rs"]
+pub mod csp;
+#[path = "encoding/encoding.rs"]
+pub mod encoding;
+#[path = "number-theory/factorization.rs"]
+pub mod factorization;
+#[path = "number-theory/fft.rs"]
+pub mod fft;
+#[path = "greedy/fractional_knapsack.rs"]
+pub mod fractional_knapsack;
+#[path = "bit-manipulation/gray_code.rs"]
+pub mod gray_code;
+#[path = "graph-traversal/graph_traversal.rs"]
+pub mod graph_traversal;
+#[path = "compression/huffman.rs"]
+pub mod huffman;
+#[path = "greedy/intervals.rs"]
+pub mod intervals;
+#[path = "greedy/job_scheduling.rs"]
+pub mod job_scheduling;
+#[path = "compression/lz.rs"]
+pub mod lz;
+#[path = "number-theory/matrix.rs"]
+pub mod matrix;
+#[path = "number-theory/matrix_expo.rs"]
+pub mod matrix_expo;
+#[path = "number-theory/miller_rabin.rs"]
+pub mod miller_rabin;
+#[path = "number-theory/modular.rs"]
+pub mod modular;
+#[path = "backtracking/n_queens.rs"]
+pub mod n_queens;
+#[path = "geometry/polygon.rs"]
+pub mod polygon;
+#[path = "number-theory/primes.rs"]
+pub mod primes;
+#[path = "probability/prng.rs"]
+pub mod prng;
+#[path = "geometry/quadtree.rs"]
+pub mod quadtree;
+#[path = "compression/rle.rs"]
+pub mod rle;
+#[path = "number-theory/rsa_demo.rs"]
+pub mod rsa_demo;
+#[path = "probability/statistics.rs"]
+pub mod statistics;
+#[path = "backtracking/subsets.rs"]
+pub mod subsets;
+#[path = "backtracking/sudoku.rs"]
+pub mod sudoku;
+#[path = "backtracking/word_search.rs"]
+pub mod word_search;
+
+/// The sorts live in their own crate so they can build without std; see its docs.
+pub use sorting_algorithms;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use demo::Demo;
+
+/// Every demo in this crate, for `tech-notes` and the other front ends.
+pub static DEMOS: &[&dyn Demo] = &[
+    &array_greedy::ArrayGreedyDemo,
+    &bigint::BigIntDemo,
+    &bit_tricks::BitTricksDemo,
+    &bitset::BitSetDemo,
+    &bwt::BwtDemo,
+    &checksums::ChecksumsDemo,
+    &combinatorics::CombinatoricsDemo,
+    &convex_hull::ConvexHullDemo,
+    &csp::CspDemo,
+    &encoding::EncodingDemo,
+    &factorization::FactorizationDemo,
+    &fft::FftDemo,
+    &fractional_knapsack::FractionalKnapsackDemo,
+    &gray_code::GrayCodeDemo,
+    &graph_traversal::GraphTraversalDemo,
+    &huffman::HuffmanDemo,
+    &intervals::IntervalsDemo,
+    &job_scheduling::JobSchedulingDemo,
+    &lz::LzDemo,
+    &matrix::MatrixDemo,
+    &matrix_expo::MatrixExpoDemo,
+    &miller_rabin::MillerRabinDemo,
+    &modular::ModularDemo,
+    &n_queens::NQueensDemo,
+    &polygon::PolygonDemo,
+    &primes::PrimesDemo,
+    &prng::PrngDemo,
+    &quadtree::QuadTreeDemo,
+    &rle::RleDemo,
+    &rsa_demo::RsaDemo,
+    &sorting_algorithms::SortingAlgorithmsDemo,
+    &statistics::StatisticsDemo,
+    &subsets::SubsetsDemo,
+    &sudoku::SudokuDemo,
+    &word_search::WordSearchDemo,
+];
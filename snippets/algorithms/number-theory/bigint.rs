@@ -0,0 +1,522 @@
+//! Arbitrary-Precision Integers in Rust
+//!
+//! `BigUint` stores a non-negative integer as little-endian "limbs" in base 10^9, so
+//! each `u32` holds nine decimal digits and printing never needs a base conversion.
+//! Products of two limbs fit in a `u64`, which is all the arithmetic below relies on.
+//!
+//! - Addition and subtraction: the pencil-and-paper algorithms with a carry or borrow.
+//! - Multiplication: schoolbook O(n*m) for small operands, Karatsuba above
+//!   `KARATSUBA_THRESHOLD` limbs. Karatsuba splits each number in halves and gets the
+//!   product from three half-size products instead of four, for O(n^1.585).
+//! - Division: long division, guessing each quotient limb from the leading limbs and
+//!   correcting the guess, which is never off by more than a couple.
+
+use demo::Demo;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+/// The limb base: nine decimal digits
+const BASE: u64 = 1_000_000_000;
+
+/// Operands with fewer limbs than this use schoolbook multiplication
+const KARATSUBA_THRESHOLD: usize = 32;
+
+// ========== BigUint ==========
+
+/// A non-negative integer of any size
+///
+/// Invariant: no trailing (most significant) zero limbs, so zero has no limbs at all.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint::from(1u64)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    /// Number of decimal digits; 1 for zero
+    pub fn digit_count(&self) -> usize {
+        match self.limbs.last() {
+            None => 1,
+            Some(top) => (self.limbs.len() - 1) * 9 + top.to_string().len(),
+        }
+    }
+
+    /// `self - other`, or `None` if that would be negative
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        (*self >= *other).then(|| BigUint::from_limbs(sub_limbs(&self.limbs, &other.limbs)))
+    }
+
+    /// `(self / divisor, self % divisor)`; panics on division by zero
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "division by zero");
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = self.div_rem_small(divisor.limbs[0]);
+            return (quotient, BigUint::from(u64::from(remainder)));
+        }
+        self.div_rem_long(divisor)
+    }
+
+    /// Division by a single limb, one pass from the top
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = remainder * BASE + u64::from(limb);
+            quotient[i] = (current / u64::from(divisor)) as u32;
+            remainder = current % u64::from(divisor);
+        }
+        (BigUint::from_limbs(quotient), remainder as u32)
+    }
+
+    /// Long division: bring down one limb at a time and find the quotient limb that fits
+    fn div_rem_long(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        let n = divisor.limbs.len();
+        let divisor_top = u128::from(divisor.limbs[n - 1]) * u128::from(BASE) + u128::from(divisor.limbs[n - 2]);
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            // remainder = remainder * BASE + limb
+            remainder.limbs.insert(0, limb);
+            remainder = BigUint::from_limbs(remainder.limbs);
+            if remainder < *divisor {
+                continue;
+            }
+
+            // Estimate from the top three limbs of the remainder against the top two of
+            // the divisor, then correct
+            let limb_at = |k: usize| u128::from(remainder.limbs.get(k).copied().unwrap_or(0));
+            let remainder_top = (limb_at(n) * u128::from(BASE) + limb_at(n - 1)) * u128::from(BASE) + limb_at(n - 2);
+            let mut guess = (remainder_top / divisor_top).min(u128::from(BASE - 1)) as u64;
+            let mut product = divisor * &BigUint::from(guess);
+            while product > remainder {
+                guess -= 1;
+                product = &product - divisor;
+            }
+            let mut rest = &remainder - &product;
+            while rest >= *divisor {
+                guess += 1;
+                rest = &rest - divisor;
+            }
+            quotient[i] = guess as u32;
+            remainder = rest;
+        }
+        (BigUint::from_limbs(quotient), remainder)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigUint {
+        let mut result = BigUint::one();
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// `n!`
+    pub fn factorial(n: u32) -> BigUint {
+        (2..=u64::from(n)).fold(BigUint::one(), |acc, k| &acc * &BigUint::from(k))
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut value: u64) -> Self {
+        let mut limbs = Vec::new();
+        while value > 0 {
+            limbs.push((value % BASE) as u32);
+            value /= BASE;
+        }
+        BigUint { limbs }
+    }
+}
+
+// ========== Parsing and Printing ==========
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    /// Parses decimal digits, allowing `_` separators
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: Vec<u8> = s.bytes().filter(|&b| b != b'_').collect();
+        if digits.is_empty() {
+            return Err("empty number".to_string());
+        }
+        if let Some(bad) = digits.iter().find(|b| !b.is_ascii_digit()) {
+            return Err(format!("invalid digit '{}' in \"{}\"", *bad as char, s));
+        }
+        // Nine digits per limb, starting from the least significant end
+        let limbs = digits
+            .rchunks(9)
+            .map(|chunk| chunk.iter().fold(0u32, |acc, &d| acc * 10 + u32::from(d - b'0')))
+            .collect();
+        Ok(BigUint::from_limbs(limbs))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return f.pad("0");
+        };
+        // Every limb below the top one is zero-padded to nine digits
+        let mut digits = top.to_string();
+        for limb in rest.iter().rev() {
+            digits.push_str(&format!("{:09}", limb));
+        }
+        f.pad(&digits)
+    }
+}
+
+// ========== Comparison ==========
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// ========== Limb Arithmetic ==========
+
+fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &limb) in long.iter().enumerate() {
+        let total = u64::from(limb) + u64::from(short.get(i).copied().unwrap_or(0)) + carry;
+        sum.push((total % BASE) as u32);
+        carry = total / BASE;
+    }
+    if carry > 0 {
+        sum.push(carry as u32);
+    }
+    sum
+}
+
+/// `a - b` for `a >= b`
+fn sub_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut total = i64::from(limb) - i64::from(b.get(i).copied().unwrap_or(0)) - borrow;
+        borrow = i64::from(total < 0);
+        if total < 0 {
+            total += BASE as i64;
+        }
+        difference.push(total as u32);
+    }
+    debug_assert_eq!(borrow, 0, "subtraction underflow");
+    difference
+}
+
+fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            // At most (BASE-1)^2 + 2(BASE-1), well inside a u64
+            let total = product[i + j] + u64::from(x) * u64::from(y) + carry;
+            product[i + j] = total % BASE;
+            carry = total / BASE;
+        }
+        product[i + b.len()] += carry;
+    }
+    product.into_iter().map(|limb| limb as u32).collect()
+}
+
+/// `value * BASE^shift`
+fn shifted(value: &BigUint, shift: usize) -> Vec<u32> {
+    if value.is_zero() {
+        return Vec::new();
+    }
+    let mut limbs = vec![0; shift];
+    limbs.extend_from_slice(&value.limbs);
+    limbs
+}
+
+fn mul_karatsuba(a: &[u32], b: &[u32]) -> BigUint {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return BigUint::from_limbs(mul_schoolbook(a, b));
+    }
+
+    // a = a1 * BASE^m + a0, b = b1 * BASE^m + b0
+    let m = a.len().max(b.len()) / 2;
+    let split = |x: &[u32]| {
+        let (low, high) = x.split_at(m.min(x.len()));
+        (BigUint::from_limbs(low.to_vec()), BigUint::from_limbs(high.to_vec()))
+    };
+    let (a0, a1) = split(a);
+    let (b0, b1) = split(b);
+
+    let z0 = mul_karatsuba(&a0.limbs, &b0.limbs);
+    let z2 = mul_karatsuba(&a1.limbs, &b1.limbs);
+    // (a0 + a1)(b0 + b1) - z0 - z2 = a0*b1 + a1*b0, the cross terms from one product
+    let z1 = &(&mul_karatsuba(&add_limbs(&a0.limbs, &a1.limbs), &add_limbs(&b0.limbs, &b1.limbs)) - &z0) - &z2;
+
+    let high = BigUint::from_limbs(shifted(&z2, 2 * m));
+    let middle = BigUint::from_limbs(shifted(&z1, m));
+    &(&high + &middle) + &z0
+}
+
+// ========== Operators ==========
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(add_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if `rhs > self`; see `checked_sub`
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("BigUint subtraction underflow")
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        mul_karatsuba(&self.limbs, &rhs.limbs)
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+macro_rules! owned_ops {
+    ($($trait:ident $method:ident),*) => {
+        $(
+            impl $trait for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: BigUint) -> BigUint {
+                    (&self).$method(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+owned_ops!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct BigIntDemo;
+
+impl Demo for BigIntDemo {
+    fn name(&self) -> &'static str {
+        "bigint"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Base-10^9 BigUint with Karatsuba multiplication and long division"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Factorials =====")?;
+        for n in [20u32, 50, 100] {
+            writeln!(out, "{}! = {}", n, BigUint::factorial(n))?;
+        }
+        let big = BigUint::factorial(1_000);
+        writeln!(out, "1000! has {} digits ({} limbs)", big.digit_count(), big.limbs.len())?;
+
+        writeln!(out, "\n===== Arithmetic =====")?;
+        let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+        let b: BigUint = "987654321098765432109876543210".parse().unwrap();
+        writeln!(out, "a     = {}", a)?;
+        writeln!(out, "b     = {}", b)?;
+        writeln!(out, "a + b = {}", &a + &b)?;
+        writeln!(out, "b - a = {}", &b - &a)?;
+        writeln!(out, "a * b = {}", &a * &b)?;
+        let (quotient, remainder) = b.div_rem(&a);
+        writeln!(out, "b / a = {} remainder {}", quotient, remainder)?;
+        writeln!(out, "2^256 = {}", BigUint::from(2).pow(256))?;
+
+        writeln!(out, "\n===== Karatsuba =====")?;
+        let x = BigUint::factorial(2_000);
+        let y = BigUint::factorial(1_500);
+        let schoolbook = BigUint::from_limbs(mul_schoolbook(&x.limbs, &y.limbs));
+        writeln!(out, "2000! * 1500!: {} x {} limbs, Karatsuba matches schoolbook: {}",
+                      x.limbs.len(), y.limbs.len(), &x * &y == schoolbook)?;
+        writeln!(out, "(2000! * 1500!) / 1500! == 2000!: {}", &(&x * &y) / &y == x)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&BigIntDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint as Oracle;
+
+    /// Deterministic decimal strings of `1..=max_digits` digits
+    fn random_numbers(count: usize, max_digits: usize, mut seed: u64) -> Vec<String> {
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        (0..count)
+            .map(|_| {
+                let len = 1 + next() as usize % max_digits;
+                let digits: String = (0..len).map(|_| char::from(b'0' + (next() % 10) as u8)).collect();
+                digits.trim_start_matches('0').to_string()
+            })
+            .map(|s| if s.is_empty() { "0".to_string() } else { s })
+            .collect()
+    }
+
+    fn both(s: &str) -> (BigUint, Oracle) {
+        (s.parse().unwrap(), s.parse().unwrap())
+    }
+
+    #[test]
+    fn arithmetic_matches_num_bigint() {
+        let numbers = random_numbers(40, 120, 0x1234_5678);
+        for x in &numbers {
+            for y in &numbers {
+                let ((a, oa), (b, ob)) = (both(x), both(y));
+                assert_eq!((&a + &b).to_string(), (&oa + &ob).to_string(), "{} + {}", x, y);
+                assert_eq!((&a * &b).to_string(), (&oa * &ob).to_string(), "{} * {}", x, y);
+                assert_eq!(a.cmp(&b), oa.cmp(&ob), "{} <=> {}", x, y);
+                match a.checked_sub(&b) {
+                    Some(d) => assert_eq!(d.to_string(), (&oa - &ob).to_string()),
+                    None => assert!(oa < ob),
+                }
+                if !b.is_zero() {
+                    let (q, r) = a.div_rem(&b);
+                    assert_eq!(q.to_string(), (&oa / &ob).to_string(), "{} / {}", x, y);
+                    assert_eq!(r.to_string(), (&oa % &ob).to_string(), "{} % {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn karatsuba_sized_products_match_num_bigint() {
+        let numbers = random_numbers(8, 2_000, 99);
+        for pair in numbers.windows(2) {
+            let ((a, oa), (b, ob)) = (both(&pair[0]), both(&pair[1]));
+            assert_eq!((&a * &b).to_string(), (&oa * &ob).to_string());
+            assert_eq!(mul_karatsuba(&a.limbs, &b.limbs), BigUint::from_limbs(mul_schoolbook(&a.limbs, &b.limbs)));
+            if !b.is_zero() {
+                assert_eq!((&a % &b).to_string(), (&oa % &ob).to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn division_edge_cases() {
+        let ten_pow = |k: u32| BigUint::from(10).pow(k);
+        // Quotient limbs of BASE - 1 and remainders of divisor - 1
+        let n = &ten_pow(45) - &BigUint::one();
+        let d = &ten_pow(18) - &BigUint::one();
+        let (q, r) = n.div_rem(&d);
+        assert_eq!(&(&q * &d) + &r, n);
+        assert!(r < d);
+        assert_eq!(ten_pow(40).div_rem(&ten_pow(40)), (BigUint::one(), BigUint::zero()));
+        assert_eq!(BigUint::from(7).div_rem(&ten_pow(30)), (BigUint::zero(), BigUint::from(7)));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn dividing_by_zero_panics() {
+        let _ = BigUint::one() / BigUint::zero();
+    }
+
+    #[test]
+    fn parsing_and_printing() {
+        assert_eq!(BigUint::from_str("000").unwrap(), BigUint::zero());
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from_str("1_000_000_000").unwrap(), BigUint::from(1_000_000_000));
+        assert_eq!(BigUint::from(1_000_000_000_000_000_007).to_string(), "1000000000000000007");
+        assert_eq!(format!("{:>5}", BigUint::from(42)), "   42");
+        assert!(BigUint::from_str("").is_err());
+        assert_eq!(BigUint::from_str("12a4").unwrap_err(), "invalid digit 'a' in \"12a4\"");
+        assert_eq!(BigUint::from(999_999_999).digit_count(), 9);
+        assert_eq!(BigUint::from(1_000_000_000).digit_count(), 10);
+    }
+
+    #[test]
+    fn factorials_match_num_bigint() {
+        for n in [0u32, 1, 25, 100, 500] {
+            let oracle = (1..=n).fold(Oracle::from(1u32), |acc, k| acc * k);
+            assert_eq!(BigUint::factorial(n).to_string(), oracle.to_string(), "{}!", n);
+        }
+        assert_eq!(BigUint::factorial(1_000).digit_count(), 2_568);
+    }
+
+    #[test]
+    #[should_panic(expected = "underflow")]
+    fn subtracting_a_larger_number_panics() {
+        let _ = BigUint::from(1) - BigUint::from(2);
+    }
+}
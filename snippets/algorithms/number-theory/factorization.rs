@@ -0,0 +1,261 @@
+//! Integer Factorization in Rust
+//!
+//! Full prime factorization of any `u64`, in three layers:
+//! - Trial division strips the small primes, which most numbers are full of.
+//! - Miller-Rabin (`miller_rabin::is_prime`) recognizes when what's left is prime.
+//! - Pollard's rho splits the rest. Iterating `x -> x^2 + c (mod n)` is a pseudorandom
+//!   walk that cycles modulo every prime factor `p` after about `sqrt(p)` steps, and
+//!   `gcd(|x - y|, n)` notices the cycle mod `p` before the one mod `n`. So a 64-bit
+//!   semiprime takes about 2^16 steps instead of the 2^32 trial division would need.
+//!
+//! Brent's improvement replaces Floyd's tortoise and hare with a teleporting tortoise
+//! (fewer function evaluations) and multiplies many `|x - y|` together before taking one
+//! gcd, since the gcd is the expensive part.
+
+use crate::miller_rabin::is_prime;
+use crate::modular::{gcd, mul_mod};
+use demo::Demo;
+use std::io::{self, Write};
+
+/// Trial division covers every prime below this
+const TRIAL_LIMIT: u64 = 1_000;
+
+/// How many `|x - y|` Brent's loop multiplies together per gcd
+const BATCH: u64 = 128;
+
+// ========== Trial Division ==========
+
+/// Divides out every prime below `TRIAL_LIMIT`, pushing them onto `factors`, and
+/// returns the cofactor
+fn strip_small_factors(mut n: u64, factors: &mut Vec<u64>) -> u64 {
+    for d in std::iter::once(2).chain((3..TRIAL_LIMIT).step_by(2)) {
+        if d * d > n {
+            break;
+        }
+        while n % d == 0 {
+            factors.push(d);
+            n /= d;
+        }
+    }
+    if n > 1 && n < TRIAL_LIMIT * TRIAL_LIMIT {
+        // No factor below sqrt(n) was found, so n is prime
+        factors.push(n);
+        n = 1;
+    }
+    n
+}
+
+// ========== Pollard's Rho ==========
+
+/// A nontrivial factor of the odd composite `n` using the polynomial `x^2 + c`
+///
+/// Returns `None` when this `c` led to a cycle mod `n` itself; another `c` will work.
+pub fn pollard_brent(n: u64, c: u64) -> Option<u64> {
+    let f = |x: u64| ((u128::from(mul_mod(x, x, n)) + u128::from(c)) % u128::from(n)) as u64;
+    let (mut y, mut r, mut q, mut g) = (2u64, 1u64, 1u64, 1u64);
+    let (mut x, mut ys) = (y, y);
+
+    while g == 1 {
+        // The tortoise teleports to where the hare is, then the hare runs r steps
+        x = y;
+        for _ in 0..r {
+            y = f(y);
+        }
+        let mut k = 0;
+        while k < r && g == 1 {
+            ys = y;
+            for _ in 0..BATCH.min(r - k) {
+                y = f(y);
+                q = mul_mod(q, x.abs_diff(y), n);
+            }
+            g = gcd(q, n);
+            k += BATCH;
+        }
+        r *= 2;
+    }
+
+    if g == n {
+        // The batch overshot; replay it one step at a time
+        loop {
+            ys = f(ys);
+            g = gcd(x.abs_diff(ys), n);
+            if g > 1 {
+                break;
+            }
+        }
+    }
+    (g != n).then_some(g)
+}
+
+/// Splits `n` into primes, pushing each onto `factors`
+fn split(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let divisor = (1..).find_map(|c| pollard_brent(n, c)).expect("some c always splits a composite");
+    split(divisor, factors);
+    split(n / divisor, factors);
+}
+
+// ========== Public API ==========
+
+/// The prime factors of `n` with multiplicity, smallest first; empty for 0 and 1
+pub fn prime_factors(n: u64) -> Vec<u64> {
+    if n < 2 {
+        return Vec::new();
+    }
+    let mut factors = Vec::new();
+    let rest = strip_small_factors(n, &mut factors);
+    split(rest, &mut factors);
+    factors.sort_unstable();
+    factors
+}
+
+/// `(prime, exponent)` pairs whose product is `n`, smallest prime first
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut powers: Vec<(u64, u32)> = Vec::new();
+    for p in prime_factors(n) {
+        match powers.last_mut() {
+            Some((last, exponent)) if *last == p => *exponent += 1,
+            _ => powers.push((p, 1)),
+        }
+    }
+    powers
+}
+
+/// Writes a factorization as `2^3 * 3 * 5^2`
+pub fn format_factorization(powers: &[(u64, u32)]) -> String {
+    let terms: Vec<String> = powers
+        .iter()
+        .map(|&(p, e)| if e == 1 { p.to_string() } else { format!("{}^{}", p, e) })
+        .collect();
+    terms.join(" * ")
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FactorizationDemo;
+
+impl Demo for FactorizationDemo {
+    fn name(&self) -> &'static str {
+        "factorization"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Trial division, Pollard's rho (Brent) and Miller-Rabin factorization"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Factorizations =====")?;
+        let numbers = [
+            360u64,
+            600_851_475_143,
+            1u64 << 63,
+            4_294_967_297,
+            1_000_000_016_000_000_063,
+            18_446_744_073_709_551_615,
+            10_000_000_000_000_000_000,
+        ];
+        for n in numbers {
+            writeln!(out, "{:>20} = {}", n, format_factorization(&factorize(n)))?;
+        }
+
+        writeln!(out, "\n===== Pollard's Rho on a Semiprime =====")?;
+        let (p, q) = (4_294_967_291u64, 4_294_967_279u64);
+        let n = p * q;
+        let c = (1..).find(|&c| pollard_brent(n, c).is_some()).unwrap();
+        let factor = pollard_brent(n, c).unwrap();
+        writeln!(out, "{} = {} * {} (found with c = {})", n, factor.min(n / factor), factor.max(n / factor), c)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FactorizationDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(factors: &[u64]) -> u64 {
+        factors.iter().product()
+    }
+
+    #[test]
+    fn small_numbers_match_trial_division() {
+        for n in 2..20_000u64 {
+            let mut expected = Vec::new();
+            let mut rest = n;
+            let mut d = 2;
+            while rest > 1 {
+                while rest % d == 0 {
+                    expected.push(d);
+                    rest /= d;
+                }
+                d += 1;
+            }
+            assert_eq!(prime_factors(n), expected, "{}", n);
+        }
+        assert!(prime_factors(0).is_empty());
+        assert!(prime_factors(1).is_empty());
+    }
+
+    #[test]
+    fn semiprimes_split_into_their_primes() {
+        let primes = [1_000_003u64, 999_999_937, 4_294_967_291, 4_294_967_279, 2_147_483_647];
+        for (i, &p) in primes.iter().enumerate() {
+            for &q in &primes[i..] {
+                let Some(n) = p.checked_mul(q) else { continue };
+                let mut expected = vec![p, q];
+                expected.sort_unstable();
+                assert_eq!(prime_factors(n), expected, "{} * {}", p, q);
+            }
+        }
+    }
+
+    #[test]
+    fn perfect_powers() {
+        assert_eq!(factorize(1 << 63), [(2, 63)]);
+        assert_eq!(factorize(3u64.pow(40)), [(3, 40)]);
+        assert_eq!(factorize(1_000_003u64.pow(3)), [(1_000_003, 3)]);
+        assert_eq!(factorize(4_294_967_291u64.pow(2)), [(4_294_967_291, 2)]);
+        assert_eq!(factorize(10u64.pow(19)), [(2, 19), (5, 19)]);
+    }
+
+    #[test]
+    fn large_primes_are_their_own_factorization() {
+        for p in [18_446_744_073_709_551_557u64, 2_305_843_009_213_693_951] {
+            assert_eq!(prime_factors(p), [p]);
+        }
+    }
+
+    #[test]
+    fn products_round_trip() {
+        // A spread of values across the whole u64 range
+        let mut n = 0x9E37_79B9_7F4A_7C15u64;
+        for _ in 0..200 {
+            n ^= n << 13;
+            n ^= n >> 7;
+            n ^= n << 17;
+            let factors = prime_factors(n);
+            assert_eq!(product(&factors), n);
+            assert!(factors.iter().all(|&p| is_prime(p)), "{} -> {:?}", n, factors);
+        }
+    }
+
+    #[test]
+    fn factorization_formats_like_math() {
+        assert_eq!(format_factorization(&factorize(360)), "2^3 * 3^2 * 5");
+        assert_eq!(format_factorization(&factorize(4_294_967_297)), "641 * 6700417");
+    }
+}
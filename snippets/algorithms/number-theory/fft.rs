@@ -0,0 +1,391 @@
+//! FFT and NTT Polynomial Multiplication in Rust
+//!
+//! Multiplying two polynomials of degree `n` coefficient by coefficient takes O(n^2).
+//! The fast Fourier transform gets there in O(n log n) by changing representation:
+//! evaluate both polynomials at the `2n` complex roots of unity, multiply the values
+//! pointwise, and interpolate back with the inverse transform. Because the roots of
+//! unity square onto each other, each evaluation splits into two half-size ones
+//! (Cooley-Tukey), done here iteratively after a bit-reversal permutation.
+//!
+//! The number-theoretic transform is the same algorithm in arithmetic mod a prime
+//! `p = c * 2^k + 1`, which has roots of unity of every power-of-two order up to `2^k`.
+//! It's exact, so there is no rounding to worry about, but results come out mod `p`.
+//!
+//! Multiplying big numbers is multiplying polynomials whose coefficients are digits and
+//! then propagating carries, which is what `multiply_decimal` does.
+
+use crate::modular::{mul_mod, pow_mod};
+use demo::Demo;
+use std::f64::consts::PI;
+use std::io::{self, Write};
+use std::ops::{Add, Mul, Sub};
+
+/// `119 * 2^23 + 1`, the usual NTT prime
+pub const NTT_MODULUS: u64 = 998_244_353;
+
+/// A generator of the multiplicative group mod `NTT_MODULUS`
+const NTT_ROOT: u64 = 3;
+
+// ========== Complex Numbers ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    /// `e^(i * angle)`
+    pub fn from_angle(angle: f64) -> Self {
+        Complex::new(angle.cos(), angle.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Complex) -> Complex {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Complex) -> Complex {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: Complex) -> Complex {
+        Complex::new(self.re * rhs.re - self.im * rhs.im, self.re * rhs.im + self.im * rhs.re)
+    }
+}
+
+// ========== Transforms ==========
+
+/// Reorders `values` so index `i` holds what was at the bit-reversal of `i`
+fn bit_reverse_permute<T>(values: &mut [T]) {
+    let n = values.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+/// In-place FFT of `values`, whose length must be a power of two; `invert` computes the
+/// inverse transform, scaled so a round trip returns the input
+pub fn fft(values: &mut [Complex], invert: bool) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "FFT length must be a power of two");
+    bit_reverse_permute(values);
+
+    let mut len = 2;
+    while len <= n {
+        let angle = 2.0 * PI / len as f64 * if invert { -1.0 } else { 1.0 };
+        let step = Complex::from_angle(angle);
+        for chunk in values.chunks_mut(len) {
+            let mut w = Complex::new(1.0, 0.0);
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+                // Butterfly: combine the even and odd halves' transforms
+                let t = *v * w;
+                (*u, *v) = (*u + t, *u - t);
+                w = w * step;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        for value in values.iter_mut() {
+            *value = Complex::new(value.re / n as f64, value.im / n as f64);
+        }
+    }
+}
+
+/// In-place NTT of `values` mod `NTT_MODULUS`; same shape as `fft`
+pub fn ntt(values: &mut [u64], invert: bool) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "NTT length must be a power of two");
+    assert!((NTT_MODULUS - 1) % n as u64 == 0, "NTT length exceeds 2^23");
+    bit_reverse_permute(values);
+
+    let mut len = 2;
+    while len <= n {
+        // A primitive len-th root of unity, or its inverse
+        let mut step = pow_mod(NTT_ROOT, (NTT_MODULUS - 1) / len as u64, NTT_MODULUS);
+        if invert {
+            step = pow_mod(step, NTT_MODULUS - 2, NTT_MODULUS);
+        }
+        for chunk in values.chunks_mut(len) {
+            let mut w = 1;
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+                let t = mul_mod(*v, w, NTT_MODULUS);
+                (*u, *v) = ((*u + t) % NTT_MODULUS, (*u + NTT_MODULUS - t) % NTT_MODULUS);
+                w = mul_mod(w, step, NTT_MODULUS);
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let n_inverse = pow_mod(n as u64, NTT_MODULUS - 2, NTT_MODULUS);
+        for value in values.iter_mut() {
+            *value = mul_mod(*value, n_inverse, NTT_MODULUS);
+        }
+    }
+}
+
+// ========== Polynomial Multiplication ==========
+
+/// Transform size that holds a product with `result_len` coefficients
+fn transform_len(result_len: usize) -> usize {
+    result_len.next_power_of_two()
+}
+
+/// Coefficients of `a * b` via the complex FFT, rounded to the nearest integer
+///
+/// Exact as long as every product coefficient stays well below 2^52 or so.
+pub fn multiply_polynomials(a: &[i64], b: &[i64]) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let result_len = a.len() + b.len() - 1;
+    let n = transform_len(result_len);
+    let mut fa: Vec<Complex> = a.iter().map(|&x| Complex::new(x as f64, 0.0)).collect();
+    let mut fb: Vec<Complex> = b.iter().map(|&x| Complex::new(x as f64, 0.0)).collect();
+    fa.resize(n, Complex::default());
+    fb.resize(n, Complex::default());
+
+    fft(&mut fa, false);
+    fft(&mut fb, false);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = *x * *y;
+    }
+    fft(&mut fa, true);
+    fa[..result_len].iter().map(|c| c.re.round() as i64).collect()
+}
+
+/// Coefficients of `a * b` mod `NTT_MODULUS`, exactly, via the NTT
+pub fn multiply_polynomials_ntt(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let result_len = a.len() + b.len() - 1;
+    let n = transform_len(result_len);
+    let mut fa: Vec<u64> = a.iter().map(|x| x % NTT_MODULUS).collect();
+    let mut fb: Vec<u64> = b.iter().map(|x| x % NTT_MODULUS).collect();
+    fa.resize(n, 0);
+    fb.resize(n, 0);
+
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = mul_mod(*x, *y, NTT_MODULUS);
+    }
+    ntt(&mut fa, true);
+    fa.truncate(result_len);
+    fa
+}
+
+/// The O(n*m) convolution the transforms replace
+pub fn multiply_naive(a: &[i64], b: &[i64]) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+// ========== Big Number Multiplication ==========
+
+/// Multiplies two non-negative decimal numbers: digits become coefficients (least
+/// significant first), the FFT multiplies them, and carries turn the result back into
+/// digits
+///
+/// Fails unless both operands are one or more ASCII digits.
+pub fn multiply_decimal(a: &str, b: &str) -> Result<String, String> {
+    let digits = |s: &str| -> Result<Vec<i64>, String> {
+        if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(format!("not a decimal number: {:?}", s));
+        }
+        Ok(s.bytes().rev().map(|c| i64::from(c - b'0')).collect())
+    };
+    let mut coefficients = multiply_polynomials(&digits(a)?, &digits(b)?);
+
+    let mut carry = 0;
+    for coefficient in coefficients.iter_mut() {
+        let total = *coefficient + carry;
+        *coefficient = total % 10;
+        carry = total / 10;
+    }
+    while carry > 0 {
+        coefficients.push(carry % 10);
+        carry /= 10;
+    }
+    while coefficients.len() > 1 && coefficients.last() == Some(&0) {
+        coefficients.pop();
+    }
+    Ok(coefficients.iter().rev().map(|d| char::from(b'0' + *d as u8)).collect())
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FftDemo;
+
+impl Demo for FftDemo {
+    fn name(&self) -> &'static str {
+        "fft"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "FFT and NTT polynomial and big-number multiplication"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Polynomial Multiplication =====")?;
+        let (a, b) = ([1i64, 2, 3], [4i64, 5]);
+        writeln!(out, "(1 + 2x + 3x^2)(4 + 5x)")?;
+        writeln!(out, "  naive: {:?}", multiply_naive(&a, &b))?;
+        writeln!(out, "  FFT:   {:?}", multiply_polynomials(&a, &b))?;
+        writeln!(out, "  NTT:   {:?}", multiply_polynomials_ntt(&[1, 2, 3], &[4, 5]))?;
+
+        writeln!(out, "\n===== Transform Round Trip =====")?;
+        let mut values: Vec<Complex> = [1.0, 2.0, 3.0, 4.0].iter().map(|&x| Complex::new(x, 0.0)).collect();
+        fft(&mut values, false);
+        // Rounded first (and -0.0 folded into 0.0) so libm differences don't show
+        let tidy = |x: f64| (x * 10.0).round() / 10.0 + 0.0;
+        let spectrum: Vec<String> = values.iter().map(|c| format!("{:.1}{:+.1}i", tidy(c.re), tidy(c.im))).collect();
+        writeln!(out, "FFT of [1, 2, 3, 4] = [{}]", spectrum.join(", "))?;
+        fft(&mut values, true);
+        let back: Vec<String> = values.iter().map(|c| format!("{:.1}", tidy(c.re))).collect();
+        writeln!(out, "inverse             = [{}]", back.join(", "))?;
+
+        writeln!(out, "\n===== Big Number Multiplication =====")?;
+        let x = "3141592653589793238462643383279502884197169399375105820974944592";
+        let y = "2718281828459045235360287471352662497757247093699959574966967627";
+        writeln!(out, "pi digits * e digits =")?;
+        writeln!(out, "{}", multiply_decimal(x, y).expect("both are digit strings"))?;
+        let digits = "9".repeat(1_000);
+        let squared = multiply_decimal(&digits, &digits).expect("both are digit strings");
+        writeln!(out, "(10^1000 - 1)^2 has {} digits: 9...98 0...01 = {}",
+                      squared.len(),
+                      squared == format!("{}8{}1", "9".repeat(999), "0".repeat(999)))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FftDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigUint;
+
+    fn random_polynomial(len: usize, max: i64, seed: &mut u64) -> Vec<i64> {
+        (0..len)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                (*seed % (2 * max as u64 + 1)) as i64 - max
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_matches_naive_convolution() {
+        let mut seed = 7;
+        for (len_a, len_b) in [(1, 1), (1, 9), (3, 5), (16, 16), (17, 100), (300, 257)] {
+            let a = random_polynomial(len_a, 1_000, &mut seed);
+            let b = random_polynomial(len_b, 1_000, &mut seed);
+            assert_eq!(multiply_polynomials(&a, &b), multiply_naive(&a, &b), "{}x{}", len_a, len_b);
+        }
+        assert!(multiply_polynomials(&[], &[1, 2]).is_empty());
+    }
+
+    #[test]
+    fn ntt_matches_naive_convolution_mod_p() {
+        let mut seed = 11;
+        for (len_a, len_b) in [(1, 1), (2, 7), (64, 64), (129, 300)] {
+            let a: Vec<u64> = random_polynomial(len_a, 1_000_000, &mut seed).iter().map(|x| x.unsigned_abs()).collect();
+            let b: Vec<u64> = random_polynomial(len_b, 1_000_000, &mut seed).iter().map(|x| x.unsigned_abs()).collect();
+            let signed = |v: &[u64]| v.iter().map(|&x| x as i64).collect::<Vec<_>>();
+            let expected: Vec<u64> = multiply_naive(&signed(&a), &signed(&b))
+                .into_iter()
+                .map(|x| x as u64 % NTT_MODULUS)
+                .collect();
+            assert_eq!(multiply_polynomials_ntt(&a, &b), expected, "{}x{}", len_a, len_b);
+        }
+    }
+
+    #[test]
+    fn transforms_round_trip() {
+        let original: Vec<u64> = (0..64).map(|i| i * i % 97).collect();
+        let mut values = original.clone();
+        ntt(&mut values, false);
+        assert_ne!(values, original);
+        ntt(&mut values, true);
+        assert_eq!(values, original);
+
+        let mut complex: Vec<Complex> = original.iter().map(|&x| Complex::new(x as f64, 0.0)).collect();
+        fft(&mut complex, false);
+        fft(&mut complex, true);
+        for (c, &x) in complex.iter().zip(&original) {
+            assert!((c.re - x as f64).abs() < 1e-9 && c.im.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn decimal_products_match_biguint() {
+        let cases = [("0", "12345"), ("1", "1"), ("99", "99"), ("123456789", "987654321")];
+        for (a, b) in cases {
+            let expected = &a.parse::<BigUint>().unwrap() * &b.parse::<BigUint>().unwrap();
+            assert_eq!(multiply_decimal(a, b), Ok(expected.to_string()), "{} * {}", a, b);
+        }
+
+        let a = BigUint::factorial(300).to_string();
+        let b = BigUint::factorial(250).to_string();
+        let expected = &BigUint::factorial(300) * &BigUint::factorial(250);
+        assert_eq!(multiply_decimal(&a, &b), Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn decimal_operands_must_be_nonempty_digit_strings() {
+        assert_eq!(multiply_decimal("", "12"), Err("not a decimal number: \"\"".to_string()));
+        assert_eq!(multiply_decimal("12", ""), Err("not a decimal number: \"\"".to_string()));
+        assert_eq!(multiply_decimal("12", "-3"), Err("not a decimal number: \"-3\"".to_string()));
+        assert_eq!(multiply_decimal("1e3", "2"), Err("not a decimal number: \"1e3\"".to_string()));
+        assert_eq!(multiply_decimal("٣", "2"), Err("not a decimal number: \"٣\"".to_string()));
+        assert_eq!(multiply_decimal("000", "7"), Ok("0".to_string()));
+    }
+}
@@ -0,0 +1,546 @@
+//! Matrix Operations in Rust
+//!
+//! A dense, row-major `Matrix<T>` with the operations linear algebra courses start with:
+//! - Multiplication, both the O(n^3) triple loop and Strassen's algorithm, which
+//!   multiplies 2x2 blocks with 7 block products instead of 8 for O(n^2.807).
+//! - Transpose.
+//! - Gaussian elimination with partial pivoting, for the determinant and the inverse.
+//!   Pivoting swaps the largest remaining entry into place before dividing by it, which
+//!   keeps rounding errors from blowing up.
+//! - LU decomposition `PA = LU`, which does the elimination once so that every later
+//!   `Ax = b` costs only two triangular solves.
+//!
+//! Multiplication works for any numeric `T`; the elimination-based operations need
+//! division and are implemented for `f64`.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Pivots smaller than this count as zero, i.e. the matrix is singular
+pub const SINGULAR_TOLERANCE: f64 = 1e-12;
+
+/// Below this size Strassen falls back to the triple loop, which is faster for small blocks
+const STRASSEN_CUTOFF: usize = 16;
+
+// ========== Numeric Types ==========
+
+/// What matrix multiplication needs from an entry type
+pub trait Numeric: Copy + PartialEq + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+}
+
+macro_rules! numeric {
+    ($($t:ty: $zero:literal, $one:literal),*) => {
+        $(
+            impl Numeric for $t {
+                fn zero() -> Self {
+                    $zero
+                }
+
+                fn one() -> Self {
+                    $one
+                }
+            }
+        )*
+    };
+}
+
+numeric!(i32: 0, 1, i64: 0, 1, f64: 0.0, 1.0);
+
+// ========== Matrix ==========
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Numeric> Matrix<T> {
+    /// A `rows x cols` matrix of zeros
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix { rows, cols, data: vec![T::zero(); rows * cols] }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut matrix = Matrix::zeros(n, n);
+        for i in 0..n {
+            matrix[(i, i)] = T::one();
+        }
+        matrix
+    }
+
+    /// Builds a matrix from equally long rows
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == cols), "rows have different lengths");
+        Matrix { rows: rows.len(), cols, data: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut transposed = Matrix::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                transposed[(j, i)] = self[(i, j)];
+            }
+        }
+        transposed
+    }
+
+    /// The triple loop, in i-k-j order so the inner loop walks both matrices row-wise
+    pub fn multiply(&self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, other.rows, "can't multiply {}x{} by {}x{}", self.rows, self.cols, other.rows, other.cols);
+        let mut product = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..other.cols {
+                    product[(i, j)] = product[(i, j)] + a * other[(k, j)];
+                }
+            }
+        }
+        product
+    }
+
+    /// Strassen multiplication of two `n x n` matrices, `n` a power of two
+    pub fn strassen(&self, other: &Matrix<T>) -> Matrix<T> {
+        let n = self.rows;
+        assert!(
+            self.is_square() && other.is_square() && other.rows == n && n.is_power_of_two(),
+            "Strassen needs two n x n matrices with n a power of two"
+        );
+        if n <= STRASSEN_CUTOFF {
+            return self.multiply(other);
+        }
+
+        let half = n / 2;
+        let [a11, a12, a21, a22] = self.quadrants();
+        let [b11, b12, b21, b22] = other.quadrants();
+
+        let m1 = (&a11 + &a22).strassen(&(&b11 + &b22));
+        let m2 = (&a21 + &a22).strassen(&b11);
+        let m3 = a11.strassen(&(&b12 - &b22));
+        let m4 = a22.strassen(&(&b21 - &b11));
+        let m5 = (&a11 + &a12).strassen(&b22);
+        let m6 = (&a21 - &a11).strassen(&(&b11 + &b12));
+        let m7 = (&a12 - &a22).strassen(&(&b21 + &b22));
+
+        let c11 = &(&(&m1 + &m4) - &m5) + &m7;
+        let c12 = &m3 + &m5;
+        let c21 = &m2 + &m4;
+        let c22 = &(&(&m1 - &m2) + &m3) + &m6;
+
+        let mut product = Matrix::zeros(n, n);
+        for (block, (row, col)) in [(c11, (0, 0)), (c12, (0, half)), (c21, (half, 0)), (c22, (half, half))] {
+            for i in 0..half {
+                for j in 0..half {
+                    product[(row + i, col + j)] = block[(i, j)];
+                }
+            }
+        }
+        product
+    }
+
+    /// The four `n/2 x n/2` blocks: top-left, top-right, bottom-left, bottom-right
+    fn quadrants(&self) -> [Matrix<T>; 4] {
+        let half = self.rows / 2;
+        let block = |row: usize, col: usize| {
+            let mut block = Matrix::zeros(half, half);
+            for i in 0..half {
+                for j in 0..half {
+                    block[(i, j)] = self[(row + i, col + j)];
+                }
+            }
+            block
+        };
+        [block(0, 0), block(0, half), block(half, 0), block(half, half)]
+    }
+
+    fn zip_with(&self, other: &Matrix<T>, f: impl Fn(T, T) -> T) -> Matrix<T> {
+        assert_eq!((self.rows, self.cols), (other.rows, other.cols), "matrix dimensions differ");
+        let data = self.data.iter().zip(&other.data).map(|(&a, &b)| f(a, b)).collect();
+        Matrix { rows: self.rows, cols: self.cols, data }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is outside the matrix", row, col);
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is outside the matrix", row, col);
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl<T: Numeric> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<T: Numeric> Sub for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<T: Numeric> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.multiply(rhs)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.data.chunks(self.cols.max(1)) {
+            let cells: Vec<String> = row.iter().map(|x| format!("{:>8.3}", x)).collect();
+            writeln!(f, "[{}]", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+// ========== Gaussian Elimination (f64) ==========
+
+/// `PA = LU`: `L` unit lower triangular, `U` upper triangular, `P` a row permutation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lu {
+    pub l: Matrix<f64>,
+    pub u: Matrix<f64>,
+    /// Row `i` of `PA` is row `permutation[i]` of `A`
+    pub permutation: Vec<usize>,
+    /// Number of row swaps, whose parity is the sign of `det(P)`
+    pub swaps: usize,
+}
+
+impl Lu {
+    pub fn permutation_matrix(&self) -> Matrix<f64> {
+        let n = self.permutation.len();
+        let mut p = Matrix::zeros(n, n);
+        for (i, &row) in self.permutation.iter().enumerate() {
+            p[(i, row)] = 1.0;
+        }
+        p
+    }
+
+    /// Solves `Ax = b` by forward substitution with `L`, then back substitution with `U`
+    pub fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.permutation.len();
+        assert_eq!(b.len(), n, "right-hand side has the wrong length");
+        let mut y: Vec<f64> = self.permutation.iter().map(|&row| b[row]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                y[i] -= self.l[(i, j)] * y[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                y[i] -= self.u[(i, j)] * y[j];
+            }
+            y[i] /= self.u[(i, i)];
+        }
+        y
+    }
+}
+
+impl Matrix<f64> {
+    /// Row index of the largest `|entry|` in `col` at or below `from`
+    fn pivot_row(&self, col: usize, from: usize) -> usize {
+        (from..self.rows).max_by(|&a, &b| self[(a, col)].abs().total_cmp(&self[(b, col)].abs())).unwrap()
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.data.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+
+    /// LU decomposition with partial pivoting, or `None` for a singular matrix
+    pub fn lu(&self) -> Option<Lu> {
+        assert!(self.is_square(), "LU decomposition needs a square matrix");
+        let n = self.rows;
+        let mut u = self.clone();
+        let mut l = Matrix::identity(n);
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+
+        for col in 0..n {
+            let pivot = u.pivot_row(col, col);
+            if u[(pivot, col)].abs() < SINGULAR_TOLERANCE {
+                return None;
+            }
+            if pivot != col {
+                u.swap_rows(pivot, col);
+                permutation.swap(pivot, col);
+                // The multipliers already found move with their rows
+                for j in 0..col {
+                    let (a, b) = (l[(pivot, j)], l[(col, j)]);
+                    l[(pivot, j)] = b;
+                    l[(col, j)] = a;
+                }
+                swaps += 1;
+            }
+            for row in col + 1..n {
+                let factor = u[(row, col)] / u[(col, col)];
+                l[(row, col)] = factor;
+                for j in col..n {
+                    u[(row, j)] -= factor * u[(col, j)];
+                }
+            }
+        }
+        Some(Lu { l, u, permutation, swaps })
+    }
+
+    /// The determinant: the product of `U`'s diagonal, negated for each row swap
+    pub fn determinant(&self) -> f64 {
+        let Some(lu) = self.lu() else {
+            return 0.0;
+        };
+        let diagonal: f64 = (0..self.rows).map(|i| lu.u[(i, i)]).product();
+        if lu.swaps % 2 == 0 {
+            diagonal
+        } else {
+            -diagonal
+        }
+    }
+
+    /// The inverse by Gauss-Jordan elimination on `[A | I]`, or `None` if singular
+    pub fn inverse(&self) -> Option<Matrix<f64>> {
+        assert!(self.is_square(), "only square matrices have inverses");
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut inverse = Matrix::identity(n);
+
+        for col in 0..n {
+            let pivot = a.pivot_row(col, col);
+            if a[(pivot, col)].abs() < SINGULAR_TOLERANCE {
+                return None;
+            }
+            a.swap_rows(pivot, col);
+            inverse.swap_rows(pivot, col);
+
+            let scale = a[(col, col)];
+            for j in 0..n {
+                a[(col, j)] /= scale;
+                inverse[(col, j)] /= scale;
+            }
+            for row in (0..n).filter(|&row| row != col) {
+                let factor = a[(row, col)];
+                for j in 0..n {
+                    a[(row, j)] -= factor * a[(col, j)];
+                    inverse[(row, j)] -= factor * inverse[(col, j)];
+                }
+            }
+        }
+        Some(inverse)
+    }
+
+    /// Whether every entry is within `tolerance` of `other`'s
+    pub fn approx_eq(&self, other: &Matrix<f64>, tolerance: f64) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols)
+            && self.data.iter().zip(&other.data).all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct MatrixDemo;
+
+impl Demo for MatrixDemo {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Strassen multiplication, determinant, inverse and LU decomposition"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let a = Matrix::from_rows(vec![vec![2.0, 1.0, 1.0], vec![4.0, -6.0, 0.0], vec![-2.0, 7.0, 2.0]]);
+        writeln!(out, "===== A =====")?;
+        write!(out, "{}", a)?;
+        writeln!(out, "det(A) = {:.3}", a.determinant())?;
+
+        writeln!(out, "\n===== LU Decomposition (PA = LU) =====")?;
+        let lu = a.lu().expect("A is invertible");
+        writeln!(out, "row order of PA: {:?}", lu.permutation)?;
+        writeln!(out, "L =")?;
+        write!(out, "{}", lu.l)?;
+        writeln!(out, "U =")?;
+        write!(out, "{}", lu.u)?;
+        let x = lu.solve(&[5.0, -2.0, 9.0]);
+        writeln!(out, "solve Ax = [5, -2, 9]: x = [{:.3}, {:.3}, {:.3}]", x[0], x[1], x[2])?;
+
+        writeln!(out, "\n===== Inverse =====")?;
+        let inverse = a.inverse().expect("A is invertible");
+        write!(out, "{}", inverse)?;
+        writeln!(out, "A * A^-1 == I (within 1e-9): {}", (&a * &inverse).approx_eq(&Matrix::identity(3), 1e-9))?;
+        let singular = Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]);
+        writeln!(out, "[[1, 2], [2, 4]] has an inverse: {}", singular.inverse().is_some())?;
+
+        writeln!(out, "\n===== Strassen =====")?;
+        let n = 64;
+        let m = Matrix::from_rows((0..n).map(|i| (0..n).map(|j| ((i * 7 + j * 3) % 11) as i64 - 5).collect()).collect());
+        let product = m.strassen(&m.transpose());
+        writeln!(out, "{0}x{0} integer matrices: Strassen equals the triple loop: {1}", n, product == &m * &m.transpose())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&MatrixDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f64 = 1e-9;
+
+    /// A deterministic, well-conditioned-enough matrix with entries in -5..=5
+    fn sample(n: usize, seed: u64) -> Matrix<f64> {
+        let mut state = seed;
+        let rows = (0..n)
+            .map(|_| {
+                (0..n)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        (state % 11) as f64 - 5.0
+                    })
+                    .collect()
+            })
+            .collect();
+        Matrix::from_rows(rows)
+    }
+
+    #[test]
+    fn strassen_matches_the_triple_loop() {
+        for n in [1usize, 2, 16, 32, 64] {
+            let a = Matrix::from_rows((0..n).map(|i| (0..n).map(|j| (i * 31 + j * 17) as i64 % 13 - 6).collect()).collect());
+            let b = a.transpose();
+            assert_eq!(a.strassen(&b), a.multiply(&b), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn multiplication_of_rectangular_matrices() {
+        let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::from_rows(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+        assert_eq!(&a * &b, Matrix::from_rows(vec![vec![58, 64], vec![139, 154]]));
+        assert_eq!(a.transpose().transpose(), a);
+        assert_eq!((&a * &b).transpose(), &b.transpose() * &a.transpose());
+        assert_eq!(&a * &Matrix::identity(3), a);
+    }
+
+    #[test]
+    fn known_determinants() {
+        let a = Matrix::from_rows(vec![vec![2.0, 1.0, 1.0], vec![4.0, -6.0, 0.0], vec![-2.0, 7.0, 2.0]]);
+        assert!((a.determinant() - -16.0).abs() < TOLERANCE);
+        assert!((Matrix::<f64>::identity(5).determinant() - 1.0).abs() < TOLERANCE);
+        // A single swap flips the sign
+        let swapped = Matrix::from_rows(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        assert!((swapped.determinant() + 1.0).abs() < TOLERANCE);
+        assert_eq!(Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]).determinant(), 0.0);
+    }
+
+    #[test]
+    fn determinant_is_multiplicative() {
+        for seed in 1..20 {
+            let (a, b) = (sample(5, seed), sample(5, seed + 100));
+            let (det_a, det_b) = (a.determinant(), b.determinant());
+            let det_ab = (&a * &b).determinant();
+            assert!((det_ab - det_a * det_b).abs() <= 1e-6 * det_ab.abs().max(1.0), "seed {}", seed);
+            assert!((a.transpose().determinant() - det_a).abs() <= 1e-6 * det_a.abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn inverse_times_matrix_is_identity() {
+        for seed in 1..20 {
+            let a = sample(6, seed);
+            let Some(inverse) = a.inverse() else {
+                assert!(a.determinant().abs() < 1e-6, "seed {} is invertible", seed);
+                continue;
+            };
+            assert!((&a * &inverse).approx_eq(&Matrix::identity(6), TOLERANCE), "seed {}", seed);
+            assert!((&inverse * &a).approx_eq(&Matrix::identity(6), TOLERANCE), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn pivoting_handles_a_zero_on_the_diagonal() {
+        let a = Matrix::from_rows(vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 0.0], vec![3.0, 0.0, 1.0]]);
+        let inverse = a.inverse().unwrap();
+        assert!((&a * &inverse).approx_eq(&Matrix::identity(3), TOLERANCE));
+        assert!(Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]).inverse().is_none());
+    }
+
+    #[test]
+    fn lu_reconstructs_the_permuted_matrix() {
+        for seed in 1..20 {
+            let a = sample(7, seed);
+            let Some(lu) = a.lu() else { continue };
+            assert!((&lu.permutation_matrix() * &a).approx_eq(&(&lu.l * &lu.u), TOLERANCE), "seed {}", seed);
+            for i in 0..7 {
+                assert_eq!(lu.l[(i, i)], 1.0);
+                for j in i + 1..7 {
+                    assert_eq!(lu.l[(i, j)], 0.0);
+                    assert!(lu.u[(j, i)].abs() < TOLERANCE);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lu_solve_recovers_the_solution() {
+        let a = sample(6, 42);
+        let expected = [1.0, -2.0, 3.5, 0.0, 4.0, -1.25];
+        let b: Vec<f64> = (0..6).map(|i| (0..6).map(|j| a[(i, j)] * expected[j]).sum()).collect();
+        let x = a.lu().unwrap().solve(&b);
+        for (got, want) in x.iter().zip(expected) {
+            assert!((got - want).abs() < TOLERANCE, "{:?}", x);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "can't multiply 2x3 by 2x3")]
+    fn mismatched_dimensions_panic() {
+        let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let _ = &a * &a;
+    }
+}
@@ -0,0 +1,271 @@
+//! Matrix Exponentiation in Rust
+//!
+//! A linear recurrence `a(n) = c1*a(n-1) + c2*a(n-2) + ... + ck*a(n-k)` moves its
+//! window of the last `k` terms forward with one fixed `k x k` matrix, the companion
+//! matrix. For Fibonacci:
+//!
+//! ```text
+//! | F(n+1) |   | 1 1 |   | F(n)   |            | F(n+1) F(n)   |   | 1 1 |^n
+//! | F(n)   | = | 1 0 | * | F(n-1) |    and so  | F(n)   F(n-1) | = | 1 0 |
+//! ```
+//!
+//! Raising the matrix to the `n`th power by repeated squaring takes O(k^3 log n)
+//! operations instead of the O(k n) of stepping through every term, so `F(10^18) mod m`
+//! is instant. Everything is reduced mod `m`, because the terms themselves grow
+//! exponentially.
+
+use crate::modular::mul_mod;
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Square Matrices mod m ==========
+
+/// `a + b mod m` for `a, b < m`, safe up to `m = u64::MAX`
+fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((u128::from(a) + u128::from(b)) % u128::from(m)) as u64
+}
+
+/// A `k x k` matrix with entries reduced mod `modulus`, stored row-major
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModMatrix {
+    size: usize,
+    modulus: u64,
+    entries: Vec<u64>,
+}
+
+impl ModMatrix {
+    pub fn identity(size: usize, modulus: u64) -> Self {
+        let mut matrix = ModMatrix { size, modulus, entries: vec![0; size * size] };
+        for i in 0..size {
+            matrix.entries[i * size + i] = 1 % modulus;
+        }
+        matrix
+    }
+
+    /// Builds a matrix from its rows; every row must have `rows.len()` entries
+    pub fn from_rows(rows: &[Vec<u64>], modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        let size = rows.len();
+        assert!(rows.iter().all(|row| row.len() == size), "matrix must be square");
+        let entries = rows.iter().flatten().map(|&x| x % modulus).collect();
+        ModMatrix { size, modulus, entries }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u64 {
+        self.entries[row * self.size + col]
+    }
+
+    pub fn multiply(&self, other: &ModMatrix) -> ModMatrix {
+        assert_eq!((self.size, self.modulus), (other.size, other.modulus), "mismatched matrices");
+        let (n, m) = (self.size, self.modulus);
+        let mut entries = vec![0; n * n];
+        for i in 0..n {
+            for k in 0..n {
+                let a = self.get(i, k);
+                if a == 0 {
+                    continue;
+                }
+                for j in 0..n {
+                    entries[i * n + j] = add_mod(entries[i * n + j], mul_mod(a, other.get(k, j), m), m);
+                }
+            }
+        }
+        ModMatrix { size: n, modulus: m, entries }
+    }
+
+    /// `self^exp` by repeated squaring
+    pub fn pow(&self, mut exp: u64) -> ModMatrix {
+        let mut result = ModMatrix::identity(self.size, self.modulus);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.multiply(&base);
+            }
+            base = base.multiply(&base);
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+// ========== Fibonacci ==========
+
+/// `F(n) mod m` in O(log n), with `F(0) = 0` and `F(1) = 1`
+pub fn fibonacci_mod(n: u64, m: u64) -> u64 {
+    let q = ModMatrix::from_rows(&[vec![1, 1], vec![1, 0]], m);
+    // Q^n = [[F(n+1), F(n)], [F(n), F(n-1)]]
+    q.pow(n).get(0, 1)
+}
+
+// ========== Linear Recurrences ==========
+
+/// `a(n) = coefficients[0]*a(n-1) + ... + coefficients[k-1]*a(n-k)`, starting from
+/// `initial = [a(0), ..., a(k-1)]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    coefficients: Vec<u64>,
+    initial: Vec<u64>,
+}
+
+impl LinearRecurrence {
+    pub fn new(coefficients: Vec<u64>, initial: Vec<u64>) -> Self {
+        assert!(!coefficients.is_empty(), "a recurrence needs at least one term");
+        assert_eq!(coefficients.len(), initial.len(), "need one initial value per coefficient");
+        LinearRecurrence { coefficients, initial }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// The companion matrix: the coefficients on top, a shifted identity below
+    pub fn companion_matrix(&self, m: u64) -> ModMatrix {
+        let k = self.order();
+        let mut rows = vec![vec![0; k]; k];
+        rows[0].clone_from(&self.coefficients);
+        for i in 1..k {
+            rows[i][i - 1] = 1;
+        }
+        ModMatrix::from_rows(&rows, m)
+    }
+
+    /// `a(n) mod m` in O(k^3 log n)
+    pub fn nth(&self, n: u64, m: u64) -> u64 {
+        let k = self.order();
+        if n < k as u64 {
+            return self.initial[n as usize] % m;
+        }
+        // The state [a(i+k-1), ..., a(i)] advances one step per multiplication
+        let power = self.companion_matrix(m).pow(n - (k as u64 - 1));
+        (0..k).fold(0, |acc, j| add_mod(acc, mul_mod(power.get(0, j), self.initial[k - 1 - j] % m, m), m))
+    }
+
+    /// `a(n) mod m` by stepping through every term, in O(k n)
+    pub fn nth_iterative(&self, n: u64, m: u64) -> u64 {
+        let k = self.order();
+        let mut window: Vec<u64> = self.initial.iter().map(|x| x % m).collect();
+        for _ in k as u64..=n {
+            let next = self
+                .coefficients
+                .iter()
+                .zip(window.iter().rev())
+                .fold(0, |acc, (&c, &a)| add_mod(acc, mul_mod(c, a, m), m));
+            window.remove(0);
+            window.push(next);
+        }
+        if n < k as u64 {
+            window[n as usize]
+        } else {
+            window[k - 1]
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct MatrixExpoDemo;
+
+impl Demo for MatrixExpoDemo {
+    fn name(&self) -> &'static str {
+        "matrix_expo"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fibonacci and linear recurrences in O(log n) via matrix powers"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        const M: u64 = 1_000_000_007;
+
+        writeln!(out, "===== Fibonacci =====")?;
+        let q = ModMatrix::from_rows(&[vec![1, 1], vec![1, 0]], u64::MAX);
+        let q10 = q.pow(10);
+        writeln!(out, "[[1, 1], [1, 0]]^10 = [[{}, {}], [{}, {}]]",
+                      q10.get(0, 0), q10.get(0, 1), q10.get(1, 0), q10.get(1, 1))?;
+        for n in [10u64, 90, 1_000, 1_000_000_000_000_000_000] {
+            writeln!(out, "F({}) mod (10^9 + 7) = {}", n, fibonacci_mod(n, M))?;
+        }
+
+        writeln!(out, "\n===== Linear Recurrences =====")?;
+        let recurrences = [
+            ("Tribonacci  a(n) = a(n-1) + a(n-2) + a(n-3)", LinearRecurrence::new(vec![1, 1, 1], vec![0, 0, 1])),
+            ("Pell        a(n) = 2a(n-1) + a(n-2)", LinearRecurrence::new(vec![2, 1], vec![0, 1])),
+            ("Padovan     a(n) = a(n-2) + a(n-3)", LinearRecurrence::new(vec![0, 1, 1], vec![1, 1, 1])),
+        ];
+        for (label, recurrence) in &recurrences {
+            let first: Vec<u64> = (0..10).map(|n| recurrence.nth(n, M)).collect();
+            writeln!(out, "{}", label)?;
+            writeln!(out, "  first terms: {:?}", first)?;
+            writeln!(out, "  a(10^15) mod (10^9 + 7) = {}", recurrence.nth(1_000_000_000_000_000, M))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&MatrixExpoDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M: u64 = 1_000_000_007;
+
+    #[test]
+    fn fibonacci_matches_iteration() {
+        let (mut a, mut b) = (0u64, 1u64);
+        for n in 0..2_000 {
+            assert_eq!(fibonacci_mod(n, M), a, "F({})", n);
+            (a, b) = (b, (a + b) % M);
+        }
+    }
+
+    #[test]
+    fn fibonacci_without_reduction_and_with_tiny_moduli() {
+        assert_eq!(fibonacci_mod(93, u64::MAX), 12_200_160_415_121_876_738);
+        assert_eq!(fibonacci_mod(5, 1), 0);
+        // The Pisano period mod 10 is 60
+        for n in 0..100 {
+            assert_eq!(fibonacci_mod(n, 10), fibonacci_mod(n + 60, 10));
+        }
+    }
+
+    #[test]
+    fn recurrences_match_iteration() {
+        let recurrences = [
+            LinearRecurrence::new(vec![1, 1], vec![0, 1]),
+            LinearRecurrence::new(vec![1, 1, 1], vec![0, 0, 1]),
+            LinearRecurrence::new(vec![3, 0, 2, 7], vec![5, 1, 4, 1]),
+            LinearRecurrence::new(vec![2], vec![1]),
+        ];
+        for recurrence in &recurrences {
+            for n in 0..300 {
+                for m in [M, 97, u64::MAX] {
+                    assert_eq!(recurrence.nth(n, m), recurrence.nth_iterative(n, m), "{:?} n={} m={}", recurrence, n, m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn powers_of_two_as_a_recurrence() {
+        let doubling = LinearRecurrence::new(vec![2], vec![1]);
+        assert_eq!(doubling.nth(63, u64::MAX), 1 << 63);
+        assert_eq!(doubling.nth(1_000_000, M), crate::modular::pow_mod(2, 1_000_000, M));
+    }
+
+    #[test]
+    fn matrix_power_basics() {
+        let a = ModMatrix::from_rows(&[vec![2, 3], vec![5, 7]], M);
+        assert_eq!(a.pow(0), ModMatrix::identity(2, M));
+        assert_eq!(a.pow(1), a);
+        assert_eq!(a.pow(5), a.multiply(&a).multiply(&a).multiply(&a).multiply(&a));
+        assert_eq!(a.pow(7).multiply(&a.pow(9)), a.pow(16));
+    }
+}
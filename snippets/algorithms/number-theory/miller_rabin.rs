@@ -0,0 +1,271 @@
+//! Miller-Rabin Primality Testing in Rust
+//!
+//! Write `n - 1 = d * 2^s` with `d` odd. For a prime `n`, every base `a` gives either
+//! `a^d ≡ 1` or `a^(d * 2^r) ≡ -1 (mod n)` for some `r < s`. A composite `n` passes that
+//! check for at most a quarter of the bases; a base it passes for anyway is a "strong
+//! liar", and `n` is a strong pseudoprime to that base.
+//!
+//! Two ways to use it:
+//! - Deterministic for `u64`: the smallest composite that fools every base in a fixed set
+//!   is known, so checking the first 12 primes as bases proves primality below 2^64.
+//!   Smaller `n` need fewer bases (see `WITNESS_SETS`).
+//! - Probabilistic for `u128`: `k` random bases leave at most a `4^-k` chance of calling a
+//!   composite prime.
+
+use crate::modular::{mul_mod, pow_mod};
+use demo::Demo;
+use std::io::{self, Write};
+
+/// `(bound, bases)`: every odd composite below `bound` fails the test for one of `bases`
+///
+/// Each bound is the smallest strong pseudoprime to all of its bases, so it's where the
+/// next row takes over.
+pub const WITNESS_SETS: &[(u64, &[u64])] = &[
+    (2_047, &[2]),
+    (1_373_653, &[2, 3]),
+    (25_326_001, &[2, 3, 5]),
+    (3_215_031_751, &[2, 3, 5, 7]),
+    (2_152_302_898_747, &[2, 3, 5, 7, 11]),
+    (3_474_749_660_383, &[2, 3, 5, 7, 11, 13]),
+    (341_550_071_728_321, &[2, 3, 5, 7, 11, 13, 17]),
+    (3_825_123_056_546_413_051, &[2, 3, 5, 7, 11, 13, 17, 19, 23]),
+    (u64::MAX, &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]),
+];
+
+/// Primes tried as divisors before running Miller-Rabin at all
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// ========== Deterministic u64 Test ==========
+
+/// Whether odd `n > 2` passes the strong probable-prime test to base `a`
+pub fn strong_probable_prime(n: u64, a: u64) -> bool {
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut x = pow_mod(a, d, n);
+    if x == 1 || x == n - 1 || a % n == 0 {
+        return true;
+    }
+    for _ in 1..s {
+        x = mul_mod(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+    }
+    false
+}
+
+/// The smallest set of bases that settles `n`
+pub fn witnesses_for(n: u64) -> &'static [u64] {
+    let (_, bases) = WITNESS_SETS.iter().find(|(bound, _)| n < *bound).unwrap_or(&WITNESS_SETS[WITNESS_SETS.len() - 1]);
+    bases
+}
+
+/// Whether `n` is prime; exact for every `u64`
+pub fn is_prime(n: u64) -> bool {
+    for p in SMALL_PRIMES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+    if n < 41 * 41 {
+        return n > 1;
+    }
+    witnesses_for(n).iter().all(|&a| strong_probable_prime(n, a))
+}
+
+// ========== Probabilistic u128 Test ==========
+
+/// `a + b mod m` for `a, b < m`, without overflowing `u128`
+fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// `a * b mod m` by doubling and adding, since there's no wider type to multiply in
+fn mul_mod_u128(mut a: u128, mut b: u128, m: u128) -> u128 {
+    let mut result = 0;
+    a %= m;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, m);
+        }
+        a = add_mod_u128(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn pow_mod_u128(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u128(result, base, m);
+        }
+        base = mul_mod_u128(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// SplitMix64, enough randomness for picking bases (and toy RSA primes)
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Whether `n` is probably prime after `rounds` random bases drawn from `seed`
+///
+/// A `false` is always right; a `true` is wrong with probability at most `4^-rounds`.
+/// Inputs that fit in a `u64` go to the exact `is_prime` instead.
+pub fn is_probable_prime(n: u128, rounds: u32, seed: u64) -> bool {
+    if let Ok(small) = u64::try_from(n) {
+        return is_prime(small);
+    }
+    if n % 2 == 0 {
+        return false;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut rng = SplitMix64(seed);
+    'bases: for _ in 0..rounds {
+        let a = 2 + (u128::from(rng.next()) << 64 | u128::from(rng.next())) % (n - 3);
+        let mut x = pow_mod_u128(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod_u128(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct MillerRabinDemo;
+
+impl Demo for MillerRabinDemo {
+    fn name(&self) -> &'static str {
+        "miller_rabin"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Miller-Rabin: deterministic for u64, probabilistic for u128"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Strong Liars =====")?;
+        let liars = [(2_047u64, "2"), (3_215_031_751, "2, 3, 5, 7"), (3_825_123_056_546_413_051, "2 to 31")];
+        for (n, bases) in liars {
+            let fooled = SMALL_PRIMES.iter().take_while(|&&a| strong_probable_prime(n, a)).count();
+            writeln!(out, "{:>19} passes bases {:<10} first witness: {:>2}  is_prime: {}",
+                          n, bases, SMALL_PRIMES[fooled], is_prime(n))?;
+        }
+
+        writeln!(out, "\n===== Deterministic u64 =====")?;
+        for n in [97u64, 561, 1_000_000_007, 4_294_967_297, 18_446_744_073_709_551_557] {
+            writeln!(out, "{:>20}: {:<5} bases {:?}", n, is_prime(n), witnesses_for(n))?;
+        }
+
+        writeln!(out, "\n===== Probabilistic u128 =====")?;
+        let mersenne_127 = (1u128 << 127) - 1;
+        let semiprime = 18_446_744_073_709_551_557u128 * 18_446_744_073_709_551_533;
+        for (label, n) in [("2^127 - 1", mersenne_127), ("(2^64 - 59)(2^64 - 83)", semiprime)] {
+            writeln!(out, "{:<22} {:<5} after 20 rounds", label, is_probable_prime(n, 20, 42))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&MillerRabinDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes::sieve;
+
+    #[test]
+    fn agrees_with_the_sieve_below_a_million() {
+        let is_prime_table = sieve(1_000_000);
+        for (n, &expected) in is_prime_table.iter().enumerate() {
+            assert_eq!(is_prime(n as u64), expected, "{}", n);
+        }
+    }
+
+    #[test]
+    fn strong_pseudoprimes_fool_only_their_bases() {
+        assert!(strong_probable_prime(2_047, 2));
+        assert!(!strong_probable_prime(2_047, 3));
+        assert!([2, 3, 5, 7].iter().all(|&a| strong_probable_prime(3_215_031_751, a)));
+        assert!(!strong_probable_prime(3_215_031_751, 11));
+
+        let n = 3_825_123_056_546_413_051;
+        assert!(SMALL_PRIMES[..11].iter().all(|&a| strong_probable_prime(n, a)));
+        assert!(!strong_probable_prime(n, 37));
+
+        for (bound, _) in &WITNESS_SETS[..WITNESS_SETS.len() - 1] {
+            assert!(!is_prime(*bound), "{}", bound);
+        }
+    }
+
+    #[test]
+    fn carmichael_numbers_are_composite() {
+        for n in [561u64, 1_105, 1_729, 2_465, 2_821, 6_601, 8_911, 41_041, 825_265, 321_197_185] {
+            assert!(!is_prime(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn large_u64_primes_and_composites() {
+        for p in [1_000_000_007u64, 998_244_353, 2_305_843_009_213_693_951, 18_446_744_073_709_551_557] {
+            assert!(is_prime(p), "{}", p);
+        }
+        // 2^32 + 1 (Fermat's wrong guess), a square of a prime, and u64::MAX
+        for n in [4_294_967_297u64, 1_000_000_007 * 1_000_000_007, u64::MAX] {
+            assert!(!is_prime(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn probabilistic_mode_on_u128() {
+        assert!(is_probable_prime((1 << 127) - 1, 16, 1));
+        assert!(is_probable_prime((1 << 89) - 1, 16, 2));
+        assert!(!is_probable_prime(18_446_744_073_709_551_557u128 * 18_446_744_073_709_551_533, 16, 3));
+        assert!(!is_probable_prime(1 << 100, 16, 4));
+        // A strong pseudoprime to every base up to 37
+        assert!(!is_probable_prime(318_665_857_834_031_151_167_461, 16, 5));
+        // Falls back to the exact test
+        assert!(is_probable_prime(1_000_000_007, 0, 6));
+    }
+
+    #[test]
+    fn u128_helpers_handle_values_near_the_top() {
+        let m = u128::MAX - 158; // any large odd modulus
+        assert_eq!(add_mod_u128(m - 1, m - 1, m), m - 2);
+        assert_eq!(mul_mod_u128(m - 1, m - 1, m), 1);
+        assert_eq!(pow_mod_u128(3, 4, 1_000), 81);
+    }
+}
@@ -0,0 +1,408 @@
+//! Modular Arithmetic in Rust
+//!
+//! Working modulo `m` keeps numbers small no matter how many operations are chained,
+//! which is what makes hashing, cryptography and "answer mod 10^9 + 7" problems work.
+//!
+//! This file covers:
+//! - Fast exponentiation by repeated squaring: O(log e) multiplications.
+//! - Modular inverses, both through Fermat's little theorem (`a^(p-2)` for a prime `p`)
+//!   and through the extended Euclidean algorithm (any `m` coprime with `a`).
+//! - The Chinese Remainder Theorem, which merges `x ≡ r_i (mod m_i)` into one congruence.
+//! - `ModInt<M>`, a number that wraps every operation mod `M` so formulas read as usual.
+//!
+//! Products go through `u128`, so every modulus up to `u64::MAX` is safe.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// ========== Basic Operations ==========
+
+/// `a * b mod m` without overflow
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+/// `base^exp mod m` by repeated squaring
+pub fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus must be positive");
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Greatest common divisor
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a*x + b*y = g`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+// ========== Modular Inverse ==========
+
+/// The `x` with `a*x ≡ 1 (mod m)`, if `a` and `m` are coprime
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd(i128::from(a % m), i128::from(m));
+    (g == 1).then(|| x.rem_euclid(i128::from(m)) as u64)
+}
+
+/// The inverse of `a` modulo the prime `p`, as `a^(p-2)`
+///
+/// Only correct when `p` is prime; `mod_inverse` works for any modulus.
+pub fn mod_inverse_fermat(a: u64, p: u64) -> Option<u64> {
+    (a % p != 0).then(|| pow_mod(a, p - 2, p))
+}
+
+// ========== Chinese Remainder Theorem ==========
+
+/// Solves `x ≡ r (mod m)` for every `(r, m)` pair, returning `(x, lcm of the moduli)`
+///
+/// The moduli don't have to be coprime; `None` means the congruences contradict each
+/// other. Panics if the combined modulus overflows `u64`.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut modulus) = (0i128, 1i128);
+    for &(r, m) in congruences {
+        assert!(m > 0, "modulus must be positive");
+        let (r, m) = (i128::from(r % m), i128::from(m));
+        // x + modulus*k ≡ r (mod m)  =>  modulus*k ≡ r - x (mod m)
+        let (g, inverse, _) = extended_gcd(modulus, m);
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let combined = modulus.checked_mul(step).filter(|&combined| combined <= i128::from(u64::MAX));
+        let combined = combined.expect("combined modulus overflows u64");
+        // Both factors are reduced below `step`, so `mul_mod` keeps the product in range
+        let (diff, inverse) = (((r - x) / g).rem_euclid(step) as u64, inverse.rem_euclid(step) as u64);
+        let k = mul_mod(diff, inverse, step as u64);
+        // x < modulus and k < step, so the sum stays below the combined modulus
+        x += modulus * i128::from(k);
+        modulus = combined;
+    }
+    Some((x as u64, modulus as u64))
+}
+
+// ========== ModInt ==========
+
+/// An integer modulo `M`; every operation reduces its result
+///
+/// Division multiplies by the inverse and panics when there is none, so it needs `M`
+/// prime (or at least a divisor coprime with `M`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64>(u64);
+
+/// The modulus most competitive-programming answers are asked in
+pub type Mod1e9_7 = ModInt<1_000_000_007>;
+
+impl<const M: u64> ModInt<M> {
+    pub const fn new(value: u64) -> Self {
+        ModInt(value % M)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        ModInt(pow_mod(self.0, exp, M))
+    }
+
+    /// The multiplicative inverse, if `value` and `M` are coprime
+    pub fn inv(self) -> Option<Self> {
+        mod_inverse(self.0, M).map(ModInt)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        ModInt::new(value)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> Self {
+        ModInt(i128::from(value).rem_euclid(i128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        ModInt(((u128::from(self.0) + u128::from(rhs.0)) % u128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        ModInt((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        ModInt(mul_mod(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs.inv().unwrap_or_else(|| panic!("{} has no inverse mod {}", rhs.0, M));
+        self * inverse
+    }
+}
+
+macro_rules! assign_ops {
+    ($($trait:ident $method:ident => $op:tt),*) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+assign_ops!(AddAssign add_assign => +, SubAssign sub_assign => -, MulAssign mul_assign => *, DivAssign div_assign => /);
+
+/// `n choose k` mod a prime `M`, from factorials and their inverses
+pub fn binomial<const M: u64>(n: u64, k: u64) -> ModInt<M> {
+    if k > n {
+        return ModInt(0);
+    }
+    let factorial = |to: u64| (1..=to).fold(ModInt::<M>(1 % M), |acc, i| acc * ModInt::new(i));
+    factorial(n) / (factorial(k) * factorial(n - k))
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ModularDemo;
+
+impl Demo for ModularDemo {
+    fn name(&self) -> &'static str {
+        "modular"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Modular exponentiation, inverses, CRT and ModInt"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Fast Exponentiation =====")?;
+        writeln!(out, "3^200 mod 1000 = {}", pow_mod(3, 200, 1_000))?;
+        writeln!(out, "2^(10^18) mod (10^9 + 7) = {}", pow_mod(2, 1_000_000_000_000_000_000, 1_000_000_007))?;
+
+        writeln!(out, "\n===== Modular Inverse =====")?;
+        for (a, m) in [(3u64, 11u64), (10, 17), (6, 9)] {
+            match mod_inverse(a, m) {
+                Some(x) => writeln!(out, "{}^-1 mod {} = {} (check: {} * {} mod {} = {})",
+                                         a, m, x, a, x, m, mul_mod(a, x, m))?,
+                None => writeln!(out, "{}^-1 mod {} does not exist (gcd = {})", a, m, gcd(a, m))?,
+            }
+        }
+        writeln!(out, "Fermat: 10^-1 mod 17 = 10^15 mod 17 = {:?}", mod_inverse_fermat(10, 17))?;
+
+        writeln!(out, "\n===== Chinese Remainder Theorem =====")?;
+        let system = [(2, 3), (3, 5), (2, 7)];
+        writeln!(out, "x ≡ 2 (mod 3), x ≡ 3 (mod 5), x ≡ 2 (mod 7)  =>  {:?}", crt(&system))?;
+        writeln!(out, "x ≡ 1 (mod 4), x ≡ 2 (mod 6)  =>  {:?}", crt(&[(1, 4), (2, 6)]))?;
+
+        writeln!(out, "\n===== ModInt =====")?;
+        let a = Mod1e9_7::new(123_456_789);
+        let b = Mod1e9_7::new(987_654_321);
+        writeln!(out, "a = {}, b = {}", a, b)?;
+        writeln!(out, "a + b = {}, a - b = {}, a * b = {}, a / b = {}", a + b, a - b, a * b, a / b)?;
+        writeln!(out, "(a / b) * b == a: {}", a / b * b == a)?;
+        writeln!(out, "C(100, 50) mod (10^9 + 7) = {}", binomial::<1_000_000_007>(100, 50))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ModularDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = 1_000_000_007;
+
+    #[test]
+    fn pow_mod_matches_repeated_multiplication() {
+        for base in 0..20u64 {
+            for exp in 0..20u64 {
+                let expected = (0..exp).fold(1u64, |acc, _| acc * base % 97);
+                assert_eq!(pow_mod(base, exp, 97), expected, "{}^{}", base, exp);
+            }
+        }
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        assert_eq!(pow_mod(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn fermats_little_theorem() {
+        for p in [2u64, 3, 5, 7, 13, 101, P, 18_446_744_073_709_551_557] {
+            for a in [1u64, 2, 3, 12_345, p - 1].into_iter().filter(|a| a % p != 0) {
+                assert_eq!(pow_mod(a, p - 1, p), 1, "{}^({}-1)", a, p);
+            }
+        }
+    }
+
+    #[test]
+    fn wilsons_theorem() {
+        // (p-1)! ≡ -1 (mod p) exactly when p is prime
+        for n in 2..200u64 {
+            let factorial = (1..n).fold(1u64, |acc, i| mul_mod(acc, i, n));
+            let is_prime = (2..n).all(|d| n % d != 0);
+            assert_eq!(factorial == n - 1, is_prime, "{}", n);
+        }
+    }
+
+    #[test]
+    fn both_inverses_agree() {
+        for a in 1..500u64 {
+            let inverse = mod_inverse(a, P).unwrap();
+            assert_eq!(mul_mod(a, inverse, P), 1);
+            assert_eq!(mod_inverse_fermat(a, P), Some(inverse));
+        }
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(7, 30), Some(13));
+        assert_eq!(mod_inverse_fermat(0, 7), None);
+    }
+
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients() {
+        for (a, b) in [(240i128, 46i128), (17, 5), (0, 9), (1_000_000_007, 998_244_353)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, i128::from(gcd(a as u64, b as u64)));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn crt_solves_coprime_and_shared_moduli() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        let moduli = [999_999_937u64, 999_999_929];
+        let x = 123_456_789_012_345_678u64;
+        let congruences: Vec<(u64, u64)> = moduli.iter().map(|&m| (x % m, m)).collect();
+        assert_eq!(crt(&congruences), Some((x, moduli[0] * moduli[1])));
+    }
+
+    #[test]
+    fn crt_handles_moduli_whose_product_just_fits() {
+        let moduli = [4_294_967_291u64, 4_294_967_279];
+        let x = moduli[0] * moduli[1] - 12_345;
+        let congruences: Vec<(u64, u64)> = moduli.iter().map(|&m| (x % m, m)).collect();
+        assert_eq!(crt(&congruences), Some((x, moduli[0] * moduli[1])));
+    }
+
+    #[test]
+    #[should_panic(expected = "combined modulus overflows u64")]
+    fn crt_panics_when_moduli_near_two_to_the_63_overflow() {
+        let _ = crt(&[(1, (1 << 63) - 25), (2, (1 << 63) + 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "combined modulus overflows u64")]
+    fn crt_panics_instead_of_wrapping_for_moduli_near_u64_max() {
+        let _ = crt(&[(u64::MAX - 1, u64::MAX), (3, u64::MAX - 1)]);
+    }
+
+    #[test]
+    fn modint_arithmetic_identities() {
+        type F = ModInt<P>;
+        let a = F::new(123_456_789);
+        let b = F::from(-5i64);
+        assert_eq!(b.value(), P - 5);
+        assert_eq!(a - a, F::new(0));
+        assert_eq!(a + (-a), F::default());
+        assert_eq!(a / b * b, a);
+        assert_eq!(a * a.inv().unwrap(), F::new(1));
+        assert_eq!(a.pow(P - 1), F::new(1));
+
+        let mut c = a;
+        c += b;
+        c *= b;
+        c -= a * b;
+        c /= b;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn freshmans_dream_holds_mod_p() {
+        type F = ModInt<13>;
+        for a in 0..13u64 {
+            for b in 0..13u64 {
+                let (a, b) = (F::new(a), F::new(b));
+                assert_eq!((a + b).pow(13), a.pow(13) + b.pow(13));
+            }
+        }
+    }
+
+    #[test]
+    fn binomial_rows_sum_to_powers_of_two() {
+        for n in 0..40u64 {
+            let sum = (0..=n).fold(ModInt::<P>::new(0), |acc, k| acc + binomial::<P>(n, k));
+            assert_eq!(sum, ModInt::new(2).pow(n));
+        }
+        assert_eq!(binomial::<P>(10, 3).value(), 120);
+        assert_eq!(binomial::<P>(3, 10).value(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "has no inverse")]
+    fn dividing_by_zero_panics() {
+        let _ = ModInt::<7>::new(3) / ModInt::new(14);
+    }
+}
@@ -0,0 +1,306 @@
+//! Prime Sieves in Rust
+//!
+//! The sieve of Eratosthenes finds every prime up to `n` by crossing out the multiples of
+//! each prime it meets, in O(n log log n) time. This file shows three versions of it:
+//! - The classic sieve: one `bool` per number, simplest to read.
+//! - A bit-packed sieve: odd numbers only, one bit each, 16 times smaller.
+//! - A segmented sieve: sieves `[low, high)` one cache-sized block at a time, using only
+//!   the primes up to `sqrt(high)`, so it reaches ranges far beyond what fits in memory.
+//!
+//! `primes_up_to` and `nth_prime` are the convenience APIs built on top.
+
+use crate::bitset::BitSet;
+use demo::Demo;
+use std::io::{self, Write};
+
+/// Numbers sieved per block by `segmented_sieve`; 32 KiB fits in L1 on most CPUs
+const SEGMENT_SIZE: u64 = 32 * 1024;
+
+/// Integer square root, rounded down
+fn isqrt(n: u64) -> u64 {
+    let mut root = (n as f64).sqrt() as u64;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
+}
+
+// ========== Classic Sieve ==========
+
+/// `is_prime[i]` for every `i` in `0..=limit`
+pub fn sieve(limit: usize) -> Vec<bool> {
+    let mut is_prime = vec![true; limit + 1];
+    is_prime[0] = false;
+    if limit >= 1 {
+        is_prime[1] = false;
+    }
+
+    let mut p = 2;
+    while p * p <= limit {
+        if is_prime[p] {
+            // Smaller multiples were already crossed out by smaller primes
+            for multiple in (p * p..=limit).step_by(p) {
+                is_prime[multiple] = false;
+            }
+        }
+        p += 1;
+    }
+    is_prime
+}
+
+// ========== Bit-Packed Sieve ==========
+
+/// Sieve over the odd numbers up to `limit`, one bit per number
+///
+/// Bit `i` stands for `2i + 1` and is set when that number is composite.
+#[derive(Debug, Clone)]
+pub struct BitSieve {
+    limit: u64,
+    composite: BitSet,
+}
+
+impl BitSieve {
+    pub fn new(limit: u64) -> Self {
+        let odd_count = limit.div_ceil(2);
+        let mut sieve = BitSieve { limit, composite: BitSet::with_capacity(odd_count as usize) };
+        sieve.mark(0); // 1 is not prime
+
+        let mut p = 3;
+        while p * p <= limit {
+            if !sieve.is_marked(p / 2) {
+                // Step 2p so only odd multiples are visited
+                for multiple in (p * p..=limit).step_by(2 * p as usize) {
+                    sieve.mark(multiple / 2);
+                }
+            }
+            p += 2;
+        }
+        sieve
+    }
+
+    fn mark(&mut self, bit: u64) {
+        self.composite.set(bit as usize);
+    }
+
+    fn is_marked(&self, bit: u64) -> bool {
+        self.composite.contains(bit as usize)
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Whether `n` is prime; `n` must not exceed the limit
+    pub fn is_prime(&self, n: u64) -> bool {
+        assert!(n <= self.limit, "{} is beyond the sieve limit {}", n, self.limit);
+        match n {
+            2 => true,
+            _ if n < 2 || n % 2 == 0 => false,
+            _ => !self.is_marked(n / 2),
+        }
+    }
+
+    /// The primes up to the limit, in order
+    pub fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        let two = (self.limit >= 2).then_some(2);
+        two.into_iter()
+            .chain((3..=self.limit).step_by(2).filter(|&n| !self.is_marked(n / 2)))
+    }
+
+    /// How many primes there are up to the limit
+    pub fn count(&self) -> usize {
+        if self.limit < 2 {
+            return 0;
+        }
+        let odd_count = self.limit.div_ceil(2);
+        // Bits past the last odd number are never marked, so counting set bits is enough
+        odd_count as usize - self.composite.count() + 1
+    }
+
+    /// Bytes used by the bit array
+    pub fn memory_bytes(&self) -> usize {
+        self.composite.memory_bytes()
+    }
+}
+
+// ========== Segmented Sieve ==========
+
+/// The primes in `[low, high)`, sieved one `SEGMENT_SIZE` block at a time
+pub fn segmented_sieve(low: u64, high: u64) -> Vec<u64> {
+    let low = low.max(2);
+    if low >= high {
+        return Vec::new();
+    }
+
+    let root = isqrt(high - 1) as usize;
+    let base_primes: Vec<u64> = sieve(root)
+        .iter()
+        .enumerate()
+        .filter(|(_, &prime)| prime)
+        .map(|(p, _)| p as u64)
+        .collect();
+
+    let mut primes = Vec::new();
+    let mut block = vec![true; SEGMENT_SIZE as usize];
+    let mut start = low;
+    while start < high {
+        let end = (start + SEGMENT_SIZE).min(high);
+        let block = &mut block[..(end - start) as usize];
+        block.fill(true);
+
+        for &p in &base_primes {
+            // First multiple of p in the block, but never p itself
+            let first = (p * p).max(start.div_ceil(p) * p);
+            for multiple in (first..end).step_by(p as usize) {
+                block[(multiple - start) as usize] = false;
+            }
+        }
+
+        primes.extend(
+            block.iter().enumerate().filter(|(_, &prime)| prime).map(|(offset, _)| start + offset as u64),
+        );
+        start = end;
+    }
+    primes
+}
+
+// ========== Convenience APIs ==========
+
+/// Every prime `<= limit`, in order
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    segmented_sieve(2, limit.saturating_add(1))
+}
+
+/// The `n`th prime, counting from `nth_prime(1) == 2`
+pub fn nth_prime(n: usize) -> u64 {
+    assert!(n > 0, "primes are counted from 1");
+    // Rosser's bound: p_n < n (ln n + ln ln n) for n >= 6
+    let bound = if n < 6 {
+        13
+    } else {
+        let n = n as f64;
+        (n * (n.ln() + n.ln().ln())).ceil() as u64
+    };
+    primes_up_to(bound)[n - 1]
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct PrimesDemo;
+
+impl Demo for PrimesDemo {
+    fn name(&self) -> &'static str {
+        "primes"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Sieve of Eratosthenes: classic, bit-packed and segmented"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Classic Sieve =====")?;
+        let is_prime = sieve(50);
+        let small: Vec<usize> = (0..=50).filter(|&n| is_prime[n]).collect();
+        writeln!(out, "Primes up to 50: {:?}", small)?;
+
+        writeln!(out, "\n===== Bit-Packed Sieve =====")?;
+        for exponent in [3u32, 5, 7] {
+            let limit = 10u64.pow(exponent);
+            let packed = BitSieve::new(limit);
+            writeln!(out, "pi(10^{}) = {:>6}  ({:>7} bytes packed vs {:>8} as bool)",
+                          exponent, packed.count(), packed.memory_bytes(), limit + 1)?;
+        }
+
+        writeln!(out, "\n===== Segmented Sieve =====")?;
+        let low = 1_000_000_000_000u64;
+        let found = segmented_sieve(low, low + 100);
+        writeln!(out, "Primes in [10^12, 10^12 + 100): {:?}", found)?;
+        writeln!(out, "(sieved with the {} primes below 10^6)", primes_up_to(1_000_000).len())?;
+
+        writeln!(out, "\n===== nth_prime =====")?;
+        for n in [1usize, 10, 100, 1_000, 10_000] {
+            writeln!(out, "prime #{:<6} = {}", n, nth_prime(n))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&PrimesDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// pi(10^k) for k = 1..=6
+    const PRIME_COUNTS: [(u64, usize); 6] =
+        [(10, 4), (100, 25), (1_000, 168), (10_000, 1_229), (100_000, 9_592), (1_000_000, 78_498)];
+
+    #[test]
+    fn every_sieve_matches_known_prime_counts() {
+        for (limit, count) in PRIME_COUNTS {
+            assert_eq!(sieve(limit as usize).iter().filter(|&&p| p).count(), count, "classic, {}", limit);
+            assert_eq!(BitSieve::new(limit).count(), count, "bit-packed, {}", limit);
+            assert_eq!(BitSieve::new(limit).primes().count(), count, "bit-packed iter, {}", limit);
+            assert_eq!(primes_up_to(limit).len(), count, "segmented, {}", limit);
+        }
+    }
+
+    #[test]
+    fn sieves_agree_on_every_number() {
+        let limit = 100_003;
+        let classic = sieve(limit as usize);
+        let packed = BitSieve::new(limit);
+        for n in 0..=limit {
+            assert_eq!(packed.is_prime(n), classic[n as usize], "{}", n);
+        }
+        let primes: Vec<u64> = packed.primes().collect();
+        assert_eq!(primes, primes_up_to(limit));
+    }
+
+    #[test]
+    fn tiny_limits() {
+        assert_eq!(sieve(0), [false]);
+        assert_eq!(sieve(2), [false, false, true]);
+        assert_eq!(BitSieve::new(0).count(), 0);
+        assert_eq!(BitSieve::new(1).count(), 0);
+        assert_eq!(BitSieve::new(2).primes().collect::<Vec<_>>(), [2]);
+        assert_eq!(BitSieve::new(3).count(), 2);
+        assert!(primes_up_to(1).is_empty());
+        assert_eq!(primes_up_to(2), [2]);
+    }
+
+    #[test]
+    fn segmented_ranges_cross_block_boundaries() {
+        let all = primes_up_to(200_000);
+        let (low, high) = (SEGMENT_SIZE - 50, 3 * SEGMENT_SIZE + 50);
+        let expected: Vec<u64> = all.iter().copied().filter(|&p| p >= low && p < high).collect();
+        assert_eq!(segmented_sieve(low, high), expected);
+        assert!(segmented_sieve(20, 20).is_empty());
+        assert_eq!(segmented_sieve(0, 12), [2, 3, 5, 7, 11]);
+    }
+
+    #[test]
+    fn segmented_sieve_far_from_zero() {
+        // The primes just above 10^12 and 10^9
+        assert_eq!(segmented_sieve(1_000_000_000_000, 1_000_000_000_040), [1_000_000_000_039]);
+        assert_eq!(segmented_sieve(1_000_000_000, 1_000_000_010), [1_000_000_007, 1_000_000_009]);
+    }
+
+    #[test]
+    fn nth_prime_known_values() {
+        let known = [(1, 2), (2, 3), (5, 11), (6, 13), (100, 541), (1_000, 7_919), (10_000, 104_729)];
+        for (n, prime) in known {
+            assert_eq!(nth_prime(n), prime, "prime #{}", n);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Toy RSA in Rust
+//!
+//! RSA is the number-theory modules put together:
+//! - Key generation picks two random primes `p` and `q` (checked with Miller-Rabin),
+//!   publishes `n = p*q` and an exponent `e`, and keeps `d = e^-1 mod φ(n)` secret,
+//!   where `φ(n) = (p-1)(q-1)`. Finding `d` without `p` and `q` means factoring `n`.
+//! - Encryption is `c = m^e mod n` and decryption `m = c^d mod n`; Euler's theorem
+//!   makes `m^(e*d) ≡ m (mod n)`.
+//! - Signing runs decryption on a hash of the message, and anyone with the public key
+//!   can check it by running encryption.
+//!
+//! EDUCATIONAL ONLY. Keys here are at most 64 bits, which `factorization` breaks in
+//! milliseconds, and this is textbook RSA with no padding, so equal messages encrypt to
+//! equal ciphertexts and ciphertexts can be multiplied together. Asking for a bigger key
+//! is an error rather than a false sense of security.
+
+use crate::miller_rabin::{is_prime, SplitMix64};
+use crate::modular::{gcd, mod_inverse, mul_mod, pow_mod};
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Largest modulus this module will generate, in bits
+pub const MAX_KEY_BITS: u32 = 64;
+
+/// Smallest modulus; below it there are hardly any primes of the right size to pick from
+pub const MIN_KEY_BITS: u32 = 16;
+
+/// The usual public exponent, 2^16 + 1
+pub const DEFAULT_EXPONENT: u64 = 65_537;
+
+// ========== Errors ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RsaError {
+    /// Real key sizes are refused on purpose
+    KeyTooLarge { bits: u32 },
+    /// Too small, or an odd number of bits that can't split evenly between `p` and `q`
+    UnsupportedKeySize { bits: u32 },
+    /// Messages must be numbers below the modulus
+    MessageTooLarge { message: u64, modulus: u64 },
+}
+
+impl fmt::Display for RsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsaError::KeyTooLarge { bits } => write!(
+                f,
+                "{}-bit keys refused: this RSA is for education only and stops at {} bits; use a vetted library for real keys",
+                bits, MAX_KEY_BITS
+            ),
+            RsaError::UnsupportedKeySize { bits } => {
+                write!(f, "{}-bit keys unsupported: use an even size of at least {} bits", bits, MIN_KEY_BITS)
+            }
+            RsaError::MessageTooLarge { message, modulus } => {
+                write!(f, "message {} is not below the modulus {}", message, modulus)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RsaError {}
+
+// ========== Keys ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey {
+    pub n: u64,
+    pub e: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub n: u64,
+    pub d: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPair {
+    pub public: PublicKey,
+    pub private: PrivateKey,
+    /// The secret primes; anyone who learns them can recompute `d`
+    pub p: u64,
+    pub q: u64,
+}
+
+/// A random prime of exactly `bits` bits with its top two bits set, so that the product
+/// of two of them has exactly twice as many bits
+fn random_prime(bits: u32, rng: &mut SplitMix64) -> u64 {
+    let top_two = 0b11 << (bits - 2);
+    loop {
+        let candidate = (rng.next() >> (64 - bits)) | top_two | 1;
+        if is_prime(candidate) {
+            return candidate;
+        }
+    }
+}
+
+impl KeyPair {
+    /// Generates a key pair with a `bits`-bit modulus from a deterministic `seed`
+    pub fn generate(bits: u32, seed: u64) -> Result<KeyPair, RsaError> {
+        if bits > MAX_KEY_BITS {
+            return Err(RsaError::KeyTooLarge { bits });
+        }
+        if bits < MIN_KEY_BITS || bits % 2 != 0 {
+            return Err(RsaError::UnsupportedKeySize { bits });
+        }
+
+        let mut rng = SplitMix64(seed);
+        loop {
+            let p = random_prime(bits / 2, &mut rng);
+            let q = random_prime(bits / 2, &mut rng);
+            if p == q {
+                continue;
+            }
+            let phi = u128::from(p - 1) * u128::from(q - 1);
+            let phi = u64::try_from(phi).expect("phi < n < 2^64");
+            // 65537 unless it shares a factor with phi; small keys can make that happen
+            let Some(e) = (DEFAULT_EXPONENT..).step_by(2).find(|&e| gcd(e, phi) == 1) else {
+                continue;
+            };
+            let d = mod_inverse(e, phi).expect("e is coprime with phi");
+            let n = p * q;
+            return Ok(KeyPair { public: PublicKey { n, e }, private: PrivateKey { n, d }, p, q });
+        }
+    }
+}
+
+/// FNV-1a, reduced below the modulus; a stand-in for a real cryptographic hash
+pub fn toy_hash(message: &[u8], modulus: u64) -> u64 {
+    let hash = message
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3));
+    hash % modulus
+}
+
+impl PublicKey {
+    /// `message^e mod n`
+    pub fn encrypt(&self, message: u64) -> Result<u64, RsaError> {
+        if message >= self.n {
+            return Err(RsaError::MessageTooLarge { message, modulus: self.n });
+        }
+        Ok(pow_mod(message, self.e, self.n))
+    }
+
+    /// Whether `signature^e mod n` is the message's hash
+    pub fn verify(&self, message: &[u8], signature: u64) -> bool {
+        signature < self.n && pow_mod(signature, self.e, self.n) == toy_hash(message, self.n)
+    }
+}
+
+impl PrivateKey {
+    /// `ciphertext^d mod n`
+    pub fn decrypt(&self, ciphertext: u64) -> Result<u64, RsaError> {
+        if ciphertext >= self.n {
+            return Err(RsaError::MessageTooLarge { message: ciphertext, modulus: self.n });
+        }
+        Ok(pow_mod(ciphertext, self.d, self.n))
+    }
+
+    /// `hash(message)^d mod n`
+    pub fn sign(&self, message: &[u8]) -> u64 {
+        pow_mod(toy_hash(message, self.n), self.d, self.n)
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct RsaDemo;
+
+impl Demo for RsaDemo {
+    fn name(&self) -> &'static str {
+        "rsa_demo"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Toy RSA key generation, encryption and signatures (educational only)"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Key Generation =====")?;
+        let keys = KeyPair::generate(48, 2024).expect("48 bits is a toy size");
+        writeln!(out, "p = {}, q = {}", keys.p, keys.q)?;
+        writeln!(out, "public  (n, e) = ({}, {})", keys.public.n, keys.public.e)?;
+        writeln!(out, "private (n, d) = ({}, {})", keys.private.n, keys.private.d)?;
+        let phi = (keys.p - 1) * (keys.q - 1);
+        writeln!(out, "e * d mod phi(n) = {}", mul_mod(keys.public.e, keys.private.d, phi))?;
+
+        writeln!(out, "\n===== Encrypt and Decrypt =====")?;
+        for message in [42u64, 123_456_789, 100_000_000_000_000] {
+            let ciphertext = keys.public.encrypt(message).unwrap();
+            let decrypted = keys.private.decrypt(ciphertext).unwrap();
+            writeln!(out, "m = {:>15} -> c = {:>15} -> m = {:>15}", message, ciphertext, decrypted)?;
+        }
+        let (a, b) = (6u64, 7u64);
+        let product = mul_mod(keys.public.encrypt(a).unwrap(), keys.public.encrypt(b).unwrap(), keys.public.n);
+        writeln!(out, "No padding: E(6) * E(7) decrypts to {}", keys.private.decrypt(product).unwrap())?;
+
+        writeln!(out, "\n===== Sign and Verify =====")?;
+        let message = b"transfer 10 coins to alice";
+        let signature = keys.private.sign(message);
+        writeln!(out, "signature of {:?}: {}", String::from_utf8_lossy(message), signature)?;
+        writeln!(out, "verify original: {}", keys.public.verify(message, signature))?;
+        writeln!(out, "verify tampered: {}", keys.public.verify(b"transfer 99 coins to alice", signature))?;
+
+        writeln!(out, "\n===== Refusing Real Key Sizes =====")?;
+        writeln!(out, "{}", KeyPair::generate(2048, 1).unwrap_err())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&RsaDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factorization::prime_factors;
+
+    #[test]
+    fn encryption_round_trips_for_every_key_size() {
+        for bits in (MIN_KEY_BITS..=MAX_KEY_BITS).step_by(8) {
+            let keys = KeyPair::generate(bits, u64::from(bits)).unwrap();
+            assert_eq!(64 - keys.public.n.leading_zeros(), bits, "modulus size");
+            for message in [0, 1, 2, keys.public.n / 3, keys.public.n / 2, keys.public.n - 1] {
+                let ciphertext = keys.public.encrypt(message).unwrap();
+                assert_eq!(keys.private.decrypt(ciphertext), Ok(message), "{} bits, m = {}", bits, message);
+            }
+        }
+    }
+
+    #[test]
+    fn key_parts_fit_together() {
+        for seed in 0..20 {
+            let keys = KeyPair::generate(40, seed).unwrap();
+            assert!(is_prime(keys.p) && is_prime(keys.q) && keys.p != keys.q);
+            assert_eq!(keys.public.n, keys.p * keys.q);
+            let phi = (keys.p - 1) * (keys.q - 1);
+            assert_eq!(mul_mod(keys.public.e, keys.private.d, phi), 1);
+        }
+    }
+
+    #[test]
+    fn signatures_verify_only_their_message() {
+        let keys = KeyPair::generate(64, 7).unwrap();
+        let signature = keys.private.sign(b"hello");
+        assert!(keys.public.verify(b"hello", signature));
+        assert!(!keys.public.verify(b"hellO", signature));
+        assert!(!keys.public.verify(b"hello", signature ^ 1));
+
+        let other = KeyPair::generate(64, 8).unwrap();
+        assert!(!other.public.verify(b"hello", signature));
+    }
+
+    #[test]
+    fn toy_keys_are_broken_by_factoring() {
+        let keys = KeyPair::generate(64, 99).unwrap();
+        let factors = prime_factors(keys.public.n);
+        let phi = (factors[0] - 1) * (factors[1] - 1);
+        assert_eq!(mod_inverse(keys.public.e, phi), Some(keys.private.d));
+    }
+
+    #[test]
+    fn real_key_sizes_are_refused() {
+        let error = KeyPair::generate(2048, 1).unwrap_err();
+        assert_eq!(error, RsaError::KeyTooLarge { bits: 2048 });
+        assert!(error.to_string().contains("education only"), "{}", error);
+        assert_eq!(KeyPair::generate(65, 1), Err(RsaError::KeyTooLarge { bits: 65 }));
+        assert_eq!(KeyPair::generate(8, 1), Err(RsaError::UnsupportedKeySize { bits: 8 }));
+        assert_eq!(KeyPair::generate(33, 1), Err(RsaError::UnsupportedKeySize { bits: 33 }));
+    }
+
+    #[test]
+    fn messages_must_be_below_the_modulus() {
+        let keys = KeyPair::generate(32, 3).unwrap();
+        let n = keys.public.n;
+        assert_eq!(keys.public.encrypt(n), Err(RsaError::MessageTooLarge { message: n, modulus: n }));
+        assert!(keys.private.decrypt(u64::MAX).is_err());
+    }
+}
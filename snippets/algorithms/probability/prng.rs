@@ -0,0 +1,364 @@
+//! Pseudorandom Number Generators in Rust
+//!
+//! A PRNG is a small state machine: a transition function moves the state along and an
+//! output function turns each state into a number. Same seed, same sequence, which is
+//! what makes benches and tests reproducible. None of these are fit for cryptography.
+//! - LCG: `state = a*state + c mod 2^64`. One multiply and one add, but the low bits are
+//!   weak: bit `k` of the state repeats with period `2^(k+1)`, so bit 0 just alternates.
+//! - xorshift64*: three shift-xors scramble the state, and a final multiply hides the
+//!   linear structure the shifts leave behind.
+//! - PCG32: an LCG underneath, with a permuted output that keeps only the good high
+//!   bits and rotates them by an amount taken from the state itself.
+//! - Box-Muller turns two uniform numbers into two independent standard normals; the
+//!   adapter works on top of any of the generators.
+
+use demo::Demo;
+use std::f64::consts::TAU;
+use std::io::{self, Write};
+
+/// Multiplier shared by the LCG and PCG32, from Knuth's MMIX
+const LCG_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// Increment for the plain LCG; any odd number gives the full period
+const LCG_INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+// ========== The Rng Trait ==========
+
+/// A source of uniformly distributed 64-bit words, plus the conversions built on them
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    /// The high half of `next_u64`, which is the better half for LCG-style generators
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Uniform in `[0, 1)`, using the top 53 bits so every value is exactly representable
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[0, bound)`. Plain `% bound` favours small values whenever `bound`
+    /// doesn't divide 2^64, so draws from the short final stretch are rejected.
+    fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let x = self.next_u64();
+            if x >= threshold {
+                return x % bound;
+            }
+        }
+    }
+
+    /// Fisher-Yates: every permutation equally likely
+    fn shuffle<T>(&mut self, items: &mut [T])
+    where
+        Self: Sized,
+    {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+// ========== Linear Congruential Generator ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+}
+
+impl Rng for Lcg {
+    /// The raw state, low bits and all, so their weakness stays visible
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(LCG_MULTIPLIER).wrapping_add(LCG_INCREMENT);
+        self.state
+    }
+}
+
+// ========== xorshift64* ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorShift64Star {
+    state: u64,
+}
+
+impl XorShift64Star {
+    /// An all-zero state would stay zero forever, so seed 0 is swapped for a fixed constant
+    pub fn new(seed: u64) -> Self {
+        XorShift64Star { state: if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed } }
+    }
+}
+
+impl Rng for XorShift64Star {
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+// ========== PCG32 ==========
+
+/// PCG-XSH-RR: 64 bits of LCG state, 32 bits of output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Generators with different `stream`s produce unrelated sequences from the same seed
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Pcg32 { state: 0, increment: (stream << 1) | 1 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(LCG_MULTIPLIER).wrapping_add(self.increment);
+    }
+}
+
+impl Rng for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+}
+
+// ========== Normal Distribution ==========
+
+/// Normally distributed samples from any uniform generator, by the Box-Muller transform
+#[derive(Debug, Clone)]
+pub struct Normal<R: Rng> {
+    rng: R,
+    mean: f64,
+    std_dev: f64,
+    /// Box-Muller makes samples in pairs; the second waits here for the next call
+    spare: Option<f64>,
+}
+
+impl<R: Rng> Normal<R> {
+    pub fn new(rng: R, mean: f64, std_dev: f64) -> Self {
+        assert!(std_dev >= 0.0, "standard deviation must not be negative");
+        Normal { rng, mean, std_dev, spare: None }
+    }
+
+    /// The standard normal: mean 0, standard deviation 1
+    pub fn standard(rng: R) -> Self {
+        Normal::new(rng, 0.0, 1.0)
+    }
+
+    pub fn sample(&mut self) -> f64 {
+        let z = match self.spare.take() {
+            Some(z) => z,
+            None => {
+                // 1 - u lies in (0, 1], keeping ln away from zero
+                let radius = (-2.0 * (1.0 - self.rng.next_f64()).ln()).sqrt();
+                let angle = TAU * self.rng.next_f64();
+                self.spare = Some(radius * angle.sin());
+                radius * angle.cos()
+            }
+        };
+        self.mean + self.std_dev * z
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct PrngDemo;
+
+impl Demo for PrngDemo {
+    fn name(&self) -> &'static str {
+        "prng"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "LCG, xorshift64* and PCG32 generators with a Box-Muller normal adapter"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== First Outputs (seed 42) =====")?;
+        let mut lcg = Lcg::new(42);
+        let mut xorshift = XorShift64Star::new(42);
+        let mut pcg = Pcg32::new(42, 54);
+        writeln!(out, "{:<14} {:<20} {:<20} #3", "generator", "#1", "#2")?;
+        let lcg_row: Vec<u64> = (0..3).map(|_| lcg.next_u64()).collect();
+        let xorshift_row: Vec<u64> = (0..3).map(|_| xorshift.next_u64()).collect();
+        let pcg_row: Vec<u64> = (0..3).map(|_| u64::from(pcg.next_u32())).collect();
+        for (name, row) in [("LCG", lcg_row), ("xorshift64*", xorshift_row), ("PCG32", pcg_row)] {
+            writeln!(out, "{:<14} {:<20} {:<20} {}", name, row[0], row[1], row[2])?;
+        }
+
+        writeln!(out, "\n===== Low Bits of a Raw LCG =====")?;
+        let mut lcg = Lcg::new(7);
+        let bits: String = (0..32).map(|_| if lcg.next_u64() & 1 == 1 { '1' } else { '0' }).collect();
+        writeln!(out, "LCG bit 0:   {}", bits)?;
+        let mut pcg = Pcg32::new(7, 0);
+        let bits: String = (0..32).map(|_| if pcg.next_u32() & 1 == 1 { '1' } else { '0' }).collect();
+        writeln!(out, "PCG32 bit 0: {}", bits)?;
+
+        writeln!(out, "\n===== Dice (100000 rolls each) =====")?;
+        writeln!(out, "{:<14} {:>6} {:>6} {:>6} {:>6} {:>6} {:>6} {:>8}", "generator", 1, 2, 3, 4, 5, 6, "chi^2")?;
+        let generators: [(&str, Box<dyn Rng>); 3] = [
+            ("LCG", Box::new(Lcg::new(1))),
+            ("xorshift64*", Box::new(XorShift64Star::new(1))),
+            ("PCG32", Box::new(Pcg32::new(1, 0))),
+        ];
+        for (name, mut rng) in generators {
+            let mut counts = [0u64; 6];
+            for _ in 0..100_000 {
+                counts[rng.below(6) as usize] += 1;
+            }
+            let expected = 100_000.0 / 6.0;
+            let chi_square: f64 = counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum();
+            writeln!(out, "{:<14} {:>6} {:>6} {:>6} {:>6} {:>6} {:>6} {:>8.2}",
+                          name, counts[0], counts[1], counts[2], counts[3], counts[4], counts[5], chi_square)?;
+        }
+        writeln!(out, "(5 degrees of freedom: anything under 15.09 passes at the 1% level)")?;
+
+        writeln!(out, "\n===== Box-Muller Normal (mean 100, sd 15) =====")?;
+        let mut normal = Normal::new(Pcg32::new(3, 0), 100.0, 15.0);
+        let samples: Vec<f64> = (0..50_000).map(|_| normal.sample()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        writeln!(out, "sample mean = {:.2}, sample sd = {:.2}", mean, variance.sqrt())?;
+        for lo in (55..145).step_by(15) {
+            let count = samples.iter().filter(|&&x| x >= lo as f64 && x < (lo + 15) as f64).count();
+            writeln!(out, "[{:>3}, {:>3}) {}", lo, lo + 15, "#".repeat(count / 500))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&PrngDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 100_000;
+
+    fn generators() -> Vec<(&'static str, Box<dyn Rng>)> {
+        vec![
+            ("lcg", Box::new(Lcg::new(12_345))),
+            ("xorshift64*", Box::new(XorShift64Star::new(12_345))),
+            ("pcg32", Box::new(Pcg32::new(12_345, 1))),
+        ]
+    }
+
+    fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        (mean, variance)
+    }
+
+    #[test]
+    fn pcg32_matches_the_reference_implementation() {
+        // pcg32-demo from pcg-random.org, seeded with (42, 54)
+        let mut rng = Pcg32::new(42, 54);
+        let expected = [0xa15c_02b7, 0x7b47_f409, 0xba1d_3330, 0x83d2_f293, 0xbfa4_784b, 0xcbed_606e];
+        for want in expected {
+            assert_eq!(rng.next_u32(), want);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let draw = |rng: &mut dyn Rng| (0..100).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        assert_eq!(draw(&mut Lcg::new(5)), draw(&mut Lcg::new(5)));
+        assert_eq!(draw(&mut XorShift64Star::new(5)), draw(&mut XorShift64Star::new(5)));
+        assert_eq!(draw(&mut Pcg32::new(5, 0)), draw(&mut Pcg32::new(5, 0)));
+        assert_ne!(draw(&mut Pcg32::new(5, 0)), draw(&mut Pcg32::new(5, 1)));
+        assert_ne!(draw(&mut XorShift64Star::new(0)), vec![0; 100]);
+    }
+
+    #[test]
+    fn uniform_floats_have_the_right_mean_and_variance() {
+        for (name, mut rng) in generators() {
+            let samples: Vec<f64> = (0..SAMPLES).map(|_| rng.next_f64()).collect();
+            assert!(samples.iter().all(|x| (0.0..1.0).contains(x)), "{}", name);
+            let (mean, variance) = mean_and_variance(&samples);
+            assert!((mean - 0.5).abs() < 0.01, "{}: mean {}", name, mean);
+            assert!((variance - 1.0 / 12.0).abs() < 0.005, "{}: variance {}", name, variance);
+        }
+    }
+
+    #[test]
+    fn buckets_pass_a_chi_square_test() {
+        // 16 buckets: 15 degrees of freedom, critical value 30.58 at the 1% level
+        const BUCKETS: usize = 16;
+        for (name, mut rng) in generators() {
+            let mut counts = [0u64; BUCKETS];
+            for _ in 0..SAMPLES {
+                counts[rng.below(BUCKETS as u64) as usize] += 1;
+            }
+            let expected = SAMPLES as f64 / BUCKETS as f64;
+            let chi_square: f64 = counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum();
+            assert!(chi_square < 30.58, "{}: chi^2 = {}", name, chi_square);
+        }
+    }
+
+    #[test]
+    fn raw_lcg_low_bit_alternates() {
+        let mut rng = Lcg::new(99);
+        let bits: Vec<u64> = (0..64).map(|_| rng.next_u64() & 1).collect();
+        assert!(bits.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn below_stays_in_range_and_shuffle_permutes() {
+        let mut rng = XorShift64Star::new(8);
+        for bound in [1, 2, 3, 7, 1_000, u64::MAX] {
+            assert!((0..1_000).all(|_| rng.below(bound) < bound));
+        }
+        let mut items: Vec<u32> = (0..100).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..100).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn box_muller_matches_the_requested_distribution() {
+        let mut normal = Normal::new(XorShift64Star::new(77), 10.0, 2.0);
+        let samples: Vec<f64> = (0..SAMPLES).map(|_| normal.sample()).collect();
+        let (mean, variance) = mean_and_variance(&samples);
+        assert!((mean - 10.0).abs() < 0.05, "mean {}", mean);
+        assert!((variance - 4.0).abs() < 0.1, "variance {}", variance);
+        // About 68.27% within one standard deviation
+        let within = samples.iter().filter(|x| (*x - 10.0).abs() < 2.0).count() as f64 / SAMPLES as f64;
+        assert!((within - 0.6827).abs() < 0.01, "within 1 sd: {}", within);
+
+        let mut standard = Normal::standard(Pcg32::new(1, 1));
+        let (mean, variance) = mean_and_variance(&(0..SAMPLES).map(|_| standard.sample()).collect::<Vec<_>>());
+        assert!(mean.abs() < 0.02 && (variance - 1.0).abs() < 0.03, "{} {}", mean, variance);
+    }
+}
@@ -0,0 +1,598 @@
+//! Streaming Statistics in Rust
+//!
+//! Summaries that look at each value once and keep O(1) state (or a fixed-size sample),
+//! so they work on streams too long to store, such as every timing from a benchmark run:
+//! - Welford's algorithm updates the mean and the sum of squared deviations together.
+//!   The textbook `E[x^2] - E[x]^2` subtracts two huge, nearly equal numbers and loses
+//!   every digit when the spread is small next to the values themselves.
+//! - Min/max is the simplest stream summary, but it has to start out empty rather than
+//!   at some sentinel that might be a real value.
+//! - P² (Jain and Chlamtac) estimates a percentile with five markers whose heights are
+//!   nudged along a parabola as values arrive, instead of sorting everything.
+//! - Reservoir sampling (algorithm R) keeps a uniform random sample of `k` items from a
+//!   stream of unknown length: item `n` replaces a random slot with probability `k/n`.
+//! - A histogram counts values into fixed-width bins, plus the ones that fall outside.
+
+use crate::prng::{Normal, Pcg32, Rng};
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Mean and Variance ==========
+
+/// Count, mean and variance by Welford's online algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    /// Sum of squared deviations from the current mean
+    m2: f64,
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        // The second factor uses the updated mean; the product is the exact increase in m2
+        self.m2 += delta * (x - self.mean);
+    }
+
+    /// Combines two summaries as if one had seen both streams (Chan et al.), so each
+    /// thread can keep its own and they are merged at the end
+    pub fn merge(&self, other: &RunningStats) -> RunningStats {
+        if self.count == 0 {
+            return *other;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+        RunningStats {
+            count,
+            mean: self.mean + delta * weight,
+            m2: self.m2 + other.m2 + delta * delta * self.count as f64 * weight,
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// 0 for an empty stream
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance, dividing by `n`
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+
+    /// Sample variance, dividing by `n - 1`
+    pub fn sample_variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+
+    /// Sample standard deviation
+    pub fn std_dev(&self) -> f64 {
+        self.sample_variance().sqrt()
+    }
+}
+
+impl Extend<f64> for RunningStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for x in values {
+            self.push(x);
+        }
+    }
+}
+
+// ========== Min and Max ==========
+
+/// The smallest and largest values seen so far; `None` until the first value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinMax<T> {
+    bounds: Option<(T, T)>,
+}
+
+impl<T: PartialOrd + Copy> MinMax<T> {
+    pub fn new() -> Self {
+        MinMax { bounds: None }
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.bounds = match self.bounds {
+            None => Some((x, x)),
+            Some((min, max)) => Some((if x < min { x } else { min }, if x > max { x } else { max })),
+        };
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.bounds.map(|(min, _)| min)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.bounds.map(|(_, max)| max)
+    }
+
+    /// `max - min`, in whatever `T` subtracts to
+    pub fn range(&self) -> Option<T::Output>
+    where
+        T: std::ops::Sub,
+    {
+        self.bounds.map(|(min, max)| max - min)
+    }
+}
+
+impl<T: PartialOrd + Copy> Default for MinMax<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== P² Percentile Estimation ==========
+
+/// Streaming estimate of the `p`-quantile in O(1) memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct P2Quantile {
+    p: f64,
+    count: usize,
+    /// Marker heights: min, p/2, p, (1+p)/2 and max quantile estimates
+    heights: [f64; 5],
+    /// Actual marker positions, 1-based ranks
+    positions: [f64; 5],
+    /// Where each marker should be, and how far that moves per value
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// `p` in `(0, 1)`, e.g. 0.99 for the 99th percentile
+    pub fn new(p: f64) -> Self {
+        assert!(p > 0.0 && p < 1.0, "quantile must be strictly between 0 and 1");
+        P2Quantile {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn push(&mut self, x: f64) {
+        // The first five values become the markers directly
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Cell k holds x; stretch the end markers if it falls outside them
+        let h = &mut self.heights;
+        let k = if x < h[0] {
+            h[0] = x;
+            0
+        } else if x >= h[4] {
+            h[4] = x;
+            3
+        } else {
+            (1..5).find(|&i| x < h[i]).expect("x < h[4]") - 1
+        };
+        for position in &mut self.positions[k + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        // Move each middle marker at most one rank towards where it should be
+        for i in 1..4 {
+            let drift = self.desired[i] - self.positions[i];
+            let room_right = self.positions[i + 1] - self.positions[i] > 1.0;
+            let room_left = self.positions[i - 1] - self.positions[i] < -1.0;
+            if (drift >= 1.0 && room_right) || (drift <= -1.0 && room_left) {
+                let step = drift.signum();
+                let candidate = self.parabolic(i, step);
+                self.heights[i] = if self.heights[i - 1] < candidate && candidate < self.heights[i + 1] {
+                    candidate
+                } else {
+                    self.linear(i, step)
+                };
+                self.positions[i] += step;
+            }
+        }
+    }
+
+    /// Piecewise-parabolic prediction of marker `i`'s height after moving by `step`
+    fn parabolic(&self, i: usize, step: f64) -> f64 {
+        let (h, n) = (&self.heights, &self.positions);
+        h[i] + step / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + step) * (h[i + 1] - h[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - step) * (h[i] - h[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    /// Fallback when the parabola would overshoot a neighbour
+    fn linear(&self, i: usize, step: f64) -> f64 {
+        let j = if step > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i] + step * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// The current estimate; exact while there are five values or fewer
+    pub fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            1..5 => Some(exact_percentile(&self.heights[..self.count], self.p)),
+            _ => Some(self.heights[2]),
+        }
+    }
+}
+
+/// The batch answer: sorts a copy and interpolates between the two nearest ranks
+pub fn exact_percentile(values: &[f64], p: f64) -> f64 {
+    assert!(!values.is_empty(), "percentile of no values");
+    assert!((0.0..=1.0).contains(&p), "quantile must be between 0 and 1");
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = p * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+// ========== Reservoir Sampling ==========
+
+/// A uniform random sample of at most `capacity` items from everything pushed
+#[derive(Debug, Clone)]
+pub struct Reservoir<T> {
+    capacity: usize,
+    seen: u64,
+    items: Vec<T>,
+    rng: Pcg32,
+}
+
+impl<T> Reservoir<T> {
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        assert!(capacity > 0, "reservoir needs room for at least one item");
+        Reservoir { capacity, seen: 0, items: Vec::with_capacity(capacity), rng: Pcg32::new(seed, 0) }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+        } else {
+            let slot = self.rng.below(self.seen) as usize;
+            if slot < self.capacity {
+                self.items[slot] = item;
+            }
+        }
+    }
+
+    /// How many items have gone past, kept or not
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    pub fn sample(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_sample(self) -> Vec<T> {
+        self.items
+    }
+}
+
+// ========== Histogram ==========
+
+/// Counts of values in `bins` equal-width bins covering `[low, high)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    low: f64,
+    high: f64,
+    counts: Vec<u64>,
+    underflow: u64,
+    overflow: u64,
+}
+
+impl Histogram {
+    pub fn new(low: f64, high: f64, bins: usize) -> Self {
+        assert!(low < high, "histogram range must not be empty");
+        assert!(bins > 0, "histogram needs at least one bin");
+        Histogram { low, high, counts: vec![0; bins], underflow: 0, overflow: 0 }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        if x < self.low {
+            self.underflow += 1;
+        } else if x >= self.high {
+            self.overflow += 1;
+        } else {
+            let bin = ((x - self.low) / self.bin_width()) as usize;
+            // Rounding can put a value just below `high` one past the last bin
+            let bin = bin.min(self.counts.len() - 1);
+            self.counts[bin] += 1;
+        }
+    }
+
+    pub fn bin_width(&self) -> f64 {
+        (self.high - self.low) / self.counts.len() as f64
+    }
+
+    /// `[start, end)` of bin `i`
+    pub fn bin_range(&self, i: usize) -> (f64, f64) {
+        let width = self.bin_width();
+        (self.low + width * i as f64, self.low + width * (i + 1) as f64)
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Values below `low`
+    pub fn underflow(&self) -> u64 {
+        self.underflow
+    }
+
+    /// Values at or above `high`
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Everything pushed, inside the range or not
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.underflow + self.overflow
+    }
+}
+
+/// One bar per bin, scaled so the fullest bin is 40 characters wide
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tallest = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        for (i, &count) in self.counts.iter().enumerate() {
+            let (start, end) = self.bin_range(i);
+            let bar = "#".repeat((count * 40 / tallest) as usize);
+            writeln!(f, "[{:>7.2}, {:>7.2}) {:>6} {}", start, end, count, bar)?;
+        }
+        write!(f, "below {}: {}, from {} up: {}", self.low, self.underflow, self.high, self.overflow)
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct StatisticsDemo;
+
+impl Demo for StatisticsDemo {
+    fn name(&self) -> &'static str {
+        "statistics"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Welford mean/variance, P² percentiles, reservoir sampling and histograms"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Welford vs the Textbook Formula =====")?;
+        let values = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0];
+        let n = values.len() as f64;
+        let sum: f64 = values.iter().sum();
+        let sum_of_squares: f64 = values.iter().map(|x| x * x).sum();
+        let naive = (sum_of_squares - sum * sum / n) / (n - 1.0);
+        let mut welford = RunningStats::new();
+        welford.extend(values);
+        writeln!(out, "values: 1e9 + [4, 7, 13, 16], true sample variance 30")?;
+        writeln!(out, "sum of squares: {}", naive)?;
+        writeln!(out, "Welford:        {}", welford.sample_variance())?;
+
+        // A log-normal stream looks like request or benchmark timings: a hump and a long tail
+        let mut normal = Normal::new(Pcg32::new(2024, 0), 0.0, 0.5);
+        let timings: Vec<f64> = (0..100_000).map(|_| 10.0 * normal.sample().exp()).collect();
+
+        writeln!(out, "\n===== 100000 Simulated Timings (ms) =====")?;
+        let mut stats = RunningStats::new();
+        let mut bounds = MinMax::new();
+        for &t in &timings {
+            stats.push(t);
+            bounds.push(t);
+        }
+        writeln!(out, "count = {}, mean = {:.3}, sd = {:.3}", stats.count(), stats.mean(), stats.std_dev())?;
+        writeln!(out, "min = {:.3}, max = {:.3}", bounds.min().unwrap(), bounds.max().unwrap())?;
+        let (left, right) = timings.split_at(timings.len() / 3);
+        let (mut a, mut b) = (RunningStats::new(), RunningStats::new());
+        a.extend(left.iter().copied());
+        b.extend(right.iter().copied());
+        let merged = a.merge(&b);
+        writeln!(out, "merged from a 1/3 + 2/3 split: mean = {:.3}, sd = {:.3}", merged.mean(), merged.std_dev())?;
+
+        writeln!(out, "\n===== P² Percentiles =====")?;
+        writeln!(out, "{:<6} {:>10} {:>10}", "p", "P²", "exact")?;
+        for p in [0.5, 0.9, 0.99, 0.999] {
+            let mut estimator = P2Quantile::new(p);
+            timings.iter().for_each(|&t| estimator.push(t));
+            writeln!(out, "{:<6} {:>10.3} {:>10.3}", p, estimator.estimate().unwrap(), exact_percentile(&timings, p))?;
+        }
+
+        writeln!(out, "\n===== Reservoir Sample (k = 8) =====")?;
+        let mut reservoir = Reservoir::new(8, 7);
+        timings.iter().for_each(|&t| reservoir.push(t));
+        let sample: Vec<String> = reservoir.sample().iter().map(|t| format!("{:.2}", t)).collect();
+        writeln!(out, "{} of {} seen: {}", sample.len(), reservoir.seen(), sample.join(", "))?;
+
+        writeln!(out, "\n===== Histogram =====")?;
+        let mut histogram = Histogram::new(0.0, 30.0, 10);
+        timings.iter().for_each(|&t| histogram.push(t));
+        writeln!(out, "{}", histogram)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&StatisticsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(len: usize, seed: u64) -> Vec<f64> {
+        let mut normal = Normal::new(Pcg32::new(seed, 0), 0.0, 1.0);
+        (0..len).map(|_| 50.0 * normal.sample().exp()).collect()
+    }
+
+    fn batch_mean_and_variance(values: &[f64]) -> (f64, f64) {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+        (mean, variance)
+    }
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() <= tolerance * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn welford_matches_batch_mean_and_variance() {
+        for len in [2, 3, 10, 1_000, 50_000] {
+            let values = stream(len, len as u64);
+            let mut stats = RunningStats::new();
+            stats.extend(values.iter().copied());
+            let (mean, variance) = batch_mean_and_variance(&values);
+            assert_eq!(stats.count(), len as u64);
+            assert!(close(stats.mean(), mean, 1e-12), "{} vs {}", stats.mean(), mean);
+            assert!(close(stats.sample_variance(), variance, 1e-9), "{} vs {}", stats.sample_variance(), variance);
+            assert!(close(stats.variance(), variance * (len - 1) as f64 / len as f64, 1e-9));
+        }
+
+        let empty = RunningStats::new();
+        assert_eq!((empty.mean(), empty.variance(), empty.sample_variance()), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn welford_survives_a_large_offset() {
+        let mut stats = RunningStats::new();
+        stats.extend([1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0]);
+        assert!(close(stats.sample_variance(), 30.0, 1e-9), "{}", stats.sample_variance());
+    }
+
+    #[test]
+    fn merged_stats_match_one_pass_over_everything() {
+        let values = stream(10_000, 3);
+        let mut whole = RunningStats::new();
+        whole.extend(values.iter().copied());
+        for split in [0, 1, 137, 5_000, 10_000] {
+            let (mut a, mut b) = (RunningStats::new(), RunningStats::new());
+            a.extend(values[..split].iter().copied());
+            b.extend(values[split..].iter().copied());
+            let merged = a.merge(&b);
+            assert_eq!(merged.count(), whole.count());
+            assert!(close(merged.mean(), whole.mean(), 1e-12), "split {}", split);
+            assert!(close(merged.sample_variance(), whole.sample_variance(), 1e-9), "split {}", split);
+        }
+    }
+
+    #[test]
+    fn min_max_tracks_the_extremes() {
+        let mut bounds = MinMax::new();
+        assert_eq!((bounds.min(), bounds.max(), bounds.range()), (None, None, None));
+        for x in [3, -7, 12, 0, 12, -2] {
+            bounds.push(x);
+        }
+        assert_eq!((bounds.min(), bounds.max(), bounds.range()), (Some(-7), Some(12), Some(19)));
+
+        let values = stream(1_000, 9);
+        let mut bounds = MinMax::default();
+        values.iter().for_each(|&x| bounds.push(x));
+        assert_eq!(bounds.min(), values.iter().copied().reduce(f64::min));
+        assert_eq!(bounds.max(), values.iter().copied().reduce(f64::max));
+    }
+
+    #[test]
+    fn p2_estimates_are_close_to_exact_percentiles() {
+        let values = stream(100_000, 11);
+        for p in [0.1, 0.5, 0.9, 0.99] {
+            let mut estimator = P2Quantile::new(p);
+            values.iter().for_each(|&x| estimator.push(x));
+            let exact = exact_percentile(&values, p);
+            let estimate = estimator.estimate().unwrap();
+            assert!(close(estimate, exact, 0.02), "p = {}: {} vs {}", p, estimate, exact);
+        }
+
+        // Sorted input is the worst case for the markers
+        let mut estimator = P2Quantile::new(0.5);
+        (0..10_001).for_each(|x| estimator.push(x as f64));
+        assert!(close(estimator.estimate().unwrap(), 5_000.0, 0.01));
+    }
+
+    #[test]
+    fn p2_is_exact_for_tiny_streams() {
+        let mut estimator = P2Quantile::new(0.5);
+        assert_eq!(estimator.estimate(), None);
+        for (i, x) in [9.0, 1.0, 5.0, 3.0, 7.0].into_iter().enumerate() {
+            estimator.push(x);
+            let seen = [9.0, 1.0, 5.0, 3.0, 7.0];
+            assert_eq!(estimator.estimate(), Some(exact_percentile(&seen[..=i], 0.5)));
+        }
+        assert_eq!(exact_percentile(&[1.0, 2.0, 3.0, 4.0], 0.5), 2.5);
+    }
+
+    #[test]
+    fn reservoir_sampling_is_uniform() {
+        // Each of 20 items should land in a sample of 5 about a quarter of the time
+        let mut hits = [0u32; 20];
+        for seed in 0..4_000 {
+            let mut reservoir = Reservoir::new(5, seed);
+            (0..20).for_each(|i| reservoir.push(i));
+            assert_eq!(reservoir.seen(), 20);
+            for &i in reservoir.sample() {
+                hits[i] += 1;
+            }
+        }
+        assert!(hits.iter().all(|&h| (850..1_150).contains(&h)), "{:?}", hits);
+
+        let mut short = Reservoir::new(10, 1);
+        (0..3).for_each(|i| short.push(i));
+        assert_eq!(short.into_sample(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn histogram_counts_match_a_batch_count() {
+        let values = stream(10_000, 5);
+        let mut histogram = Histogram::new(0.0, 200.0, 8);
+        values.iter().for_each(|&x| histogram.push(x));
+        for (i, &count) in histogram.counts().iter().enumerate() {
+            let (start, end) = histogram.bin_range(i);
+            let expected = values.iter().filter(|&&x| x >= start && x < end).count() as u64;
+            assert_eq!(count, expected, "bin {}", i);
+        }
+        assert_eq!(histogram.overflow(), values.iter().filter(|&&x| x >= 200.0).count() as u64);
+        assert_eq!(histogram.underflow(), 0);
+        assert_eq!(histogram.total(), 10_000);
+
+        let mut edges = Histogram::new(-1.0, 1.0, 2);
+        [-1.5, -1.0, 0.0, 0.999, 1.0].into_iter().for_each(|x| edges.push(x));
+        assert_eq!((edges.underflow(), edges.counts(), edges.overflow()), (1, &[1, 2][..], 1));
+    }
+}
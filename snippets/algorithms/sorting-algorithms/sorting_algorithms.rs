@@ -1,15 +1,203 @@
+//! Sorting Algorithms in Rust
+//!
+//! Comparison sorts (bubble, selection, insertion, merge, quick, heap, shell) and
+//! non-comparison sorts (counting, radix, bucket). Every function takes a slice and
+//! returns a new sorted `Vec`, leaving the input untouched.
+//!
+//! The comparison sorts are written once against a trace hook: `sort_traced` reports every
+//! compare, swap and write as a `SortStep`, and the plain functions pass a hook that does
+//! nothing. Visualizers replay the steps instead of the sorts sleeping or printing, and
+//! `sort_with` takes an `ExecutionMode` for callers that want the steps traced or animated.
+//!
+//! The sorts themselves need only `alloc`: with the default `std` feature turned off the
+//! crate is `#![no_std]`, and what's left out is `sort_with` and the demo, which write to
+//! an `io::Write` and sleep.
+//!
+//! Run: cargo run -p algorithms --example sorting_algorithms
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use demo::{Demo, ExecutionMode};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::thread;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+// ========== Step Tracing ==========
+
+/// One primitive operation performed by a traced sort.
+///
+/// Replaying the steps with `apply` on a copy of the input reproduces every
+/// intermediate state of the array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum SortStep {
+    /// The values at these two indices were compared. For insertion and shell sort the
+    /// second index is the hole the held value will drop into.
+    Compare(usize, usize),
+    /// The values at these two indices were swapped
+    Swap(usize, usize),
+    /// This index was overwritten with a value
+    Set(usize, i32),
+}
+
+impl SortStep {
+    /// Applies the step to `values`; comparisons leave it unchanged.
+    pub fn apply(self, values: &mut [i32]) {
+        match self {
+            SortStep::Compare(..) => {}
+            SortStep::Swap(i, j) => values.swap(i, j),
+            SortStep::Set(i, value) => values[i] = value,
+        }
+    }
+}
+
+/// The sorts that can report their steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum SortAlgorithm {
+    Bubble,
+    Selection,
+    Insertion,
+    Merge,
+    Quick,
+    Heap,
+    Shell,
+}
+
+impl SortAlgorithm {
+    pub const ALL: [SortAlgorithm; 7] = [
+        SortAlgorithm::Bubble,
+        SortAlgorithm::Selection,
+        SortAlgorithm::Insertion,
+        SortAlgorithm::Merge,
+        SortAlgorithm::Quick,
+        SortAlgorithm::Heap,
+        SortAlgorithm::Shell,
+    ];
+
+    /// Lowercase name, e.g. `"quick"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            SortAlgorithm::Bubble => "bubble",
+            SortAlgorithm::Selection => "selection",
+            SortAlgorithm::Insertion => "insertion",
+            SortAlgorithm::Merge => "merge",
+            SortAlgorithm::Quick => "quick",
+            SortAlgorithm::Heap => "heap",
+            SortAlgorithm::Shell => "shell",
+        }
+    }
+
+    /// Inverse of `name`.
+    pub fn from_name(name: &str) -> Option<SortAlgorithm> {
+        SortAlgorithm::ALL.into_iter().find(|algorithm| algorithm.name() == name)
+    }
+}
+
+/// Sorts a copy of `arr` with `algorithm`, calling `trace` for every step it takes.
+pub fn sort_traced<F: FnMut(SortStep)>(algorithm: SortAlgorithm, arr: &[i32], mut trace: F) -> Vec<i32> {
+    let mut result = arr.to_vec();
+    let trace = &mut trace;
+
+    match algorithm {
+        SortAlgorithm::Bubble => bubble_sort_in_place(&mut result, trace),
+        SortAlgorithm::Selection => selection_sort_in_place(&mut result, trace),
+        SortAlgorithm::Insertion => insertion_sort_in_place(&mut result, trace),
+        SortAlgorithm::Merge => merge_sort_in_place(&mut result, 0, trace),
+        SortAlgorithm::Quick => {
+            if result.len() > 1 {
+                let high = (result.len() - 1) as i32;
+                quick_sort_helper(&mut result, 0, high, trace);
+            }
+        }
+        SortAlgorithm::Heap => heap_sort_in_place(&mut result, trace),
+        SortAlgorithm::Shell => shell_sort_in_place(&mut result, trace),
+    }
+
+    result
+}
+
+/// Every step `algorithm` takes to sort `arr`, in order.
+pub fn sort_steps(algorithm: SortAlgorithm, arr: &[i32]) -> Vec<SortStep> {
+    let mut steps = Vec::new();
+    sort_traced(algorithm, arr, |step| steps.push(step));
+    steps
+}
+
+/// One traced sort: the input, the result and every step in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SortRun {
+    pub algorithm: SortAlgorithm,
+    pub input: Vec<i32>,
+    pub sorted: Vec<i32>,
+    pub steps: Vec<SortStep>,
+}
+
+impl SortRun {
+    /// Sorts a copy of `input` with `algorithm`, keeping every step.
+    pub fn new(algorithm: SortAlgorithm, input: &[i32]) -> Self {
+        let mut steps = Vec::new();
+        let sorted = sort_traced(algorithm, input, |step| steps.push(step));
+        SortRun { algorithm, input: input.to_vec(), sorted, steps }
+    }
+}
+
+/// Sorts a copy of `arr` with `algorithm` in the given mode. `Animated` writes each
+/// step followed by the array it leaves behind.
+#[cfg(feature = "std")]
+pub fn sort_with(algorithm: SortAlgorithm, arr: &[i32], mode: ExecutionMode<'_, SortStep>) -> io::Result<Vec<i32>> {
+    match mode {
+        ExecutionMode::Silent => Ok(sort_traced(algorithm, arr, |_| {})),
+        ExecutionMode::Traced(callback) => Ok(sort_traced(algorithm, arr, |step| callback(&step))),
+        ExecutionMode::Animated(out, delay) => animate(algorithm, arr, out, delay),
+    }
+}
+
+#[cfg(feature = "std")]
+fn animate(algorithm: SortAlgorithm, arr: &[i32], out: &mut dyn Write, delay: Duration) -> io::Result<Vec<i32>> {
+    writeln!(out, "{} sort of {:?}", algorithm.name(), arr)?;
+
+    // The hook can't return an error, so remember the first one and stop writing.
+    let mut written = Ok(());
+    let mut state = arr.to_vec();
+    let sorted = sort_traced(algorithm, arr, |step| {
+        step.apply(&mut state);
+        if written.is_ok() {
+            written = writeln!(out, "  {:<14} {:?}", format!("{:?}", step), state);
+        }
+        thread::sleep(delay);
+    });
+    written.map(|()| sorted)
+}
+
+// ========== Comparison Sorts ==========
+
 /// Bubble Sort
 /// Time complexity: O(n^2)
-fn bubble_sort(arr: &[i32]) -> Vec<i32> {
-    let mut result = arr.to_vec();
+pub fn bubble_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Bubble, arr, |_| {})
+}
+
+fn bubble_sort_in_place<F: FnMut(SortStep)>(result: &mut [i32], trace: &mut F) {
     let n = result.len();
 
     for i in 0..n {
         let mut swapped = false;
 
         for j in 0..(n - i - 1) {
+            trace(SortStep::Compare(j, j + 1));
             if result[j] > result[j + 1] {
                 result.swap(j, j + 1);
+                trace(SortStep::Swap(j, j + 1));
                 swapped = true;
             }
         }
@@ -19,36 +207,42 @@ fn bubble_sort(arr: &[i32]) -> Vec<i32> {
             break;
         }
     }
-
-    result
 }
 
 /// Selection Sort
 /// Time complexity: O(n^2)
-fn selection_sort(arr: &[i32]) -> Vec<i32> {
-    let mut result = arr.to_vec();
+pub fn selection_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Selection, arr, |_| {})
+}
+
+fn selection_sort_in_place<F: FnMut(SortStep)>(result: &mut [i32], trace: &mut F) {
     let n = result.len();
 
     for i in 0..n {
         let mut min_idx = i;
 
         for j in (i + 1)..n {
+            trace(SortStep::Compare(j, min_idx));
             if result[j] < result[min_idx] {
                 min_idx = j;
             }
         }
 
         // Swap the found minimum element with the first element of the unsorted part
-        result.swap(i, min_idx);
+        if min_idx != i {
+            result.swap(i, min_idx);
+            trace(SortStep::Swap(i, min_idx));
+        }
     }
-
-    result
 }
 
 /// Insertion Sort
 /// Time complexity: O(n^2)
-fn insertion_sort(arr: &[i32]) -> Vec<i32> {
-    let mut result = arr.to_vec();
+pub fn insertion_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Insertion, arr, |_| {})
+}
+
+fn insertion_sort_in_place<F: FnMut(SortStep)>(result: &mut [i32], trace: &mut F) {
     let n = result.len();
 
     for i in 1..n {
@@ -56,35 +250,49 @@ fn insertion_sort(arr: &[i32]) -> Vec<i32> {
         let mut j = i as i32 - 1;
 
         // Move elements greater than key one position ahead
-        while j >= 0 && result[j as usize] > key {
+        while j >= 0 {
+            trace(SortStep::Compare(j as usize, (j + 1) as usize));
+            if result[j as usize] <= key {
+                break;
+            }
             result[(j + 1) as usize] = result[j as usize];
+            trace(SortStep::Set((j + 1) as usize, result[j as usize]));
             j -= 1;
         }
         result[(j + 1) as usize] = key;
+        trace(SortStep::Set((j + 1) as usize, key));
     }
-
-    result
 }
 
 /// Merge Sort
 /// Time complexity: O(n log n)
-fn merge_sort(arr: &[i32]) -> Vec<i32> {
+pub fn merge_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Merge, arr, |_| {})
+}
+
+/// Sorts `arr`, which starts at index `offset` of the whole array being traced.
+fn merge_sort_in_place<F: FnMut(SortStep)>(arr: &mut [i32], offset: usize, trace: &mut F) {
     if arr.len() <= 1 {
-        return arr.to_vec();
+        return;
     }
 
     let mid = arr.len() / 2;
-    let left = merge_sort(&arr[0..mid]);
-    let right = merge_sort(&arr[mid..]);
+    merge_sort_in_place(&mut arr[0..mid], offset, trace);
+    merge_sort_in_place(&mut arr[mid..], offset + mid, trace);
 
-    merge(&left, &right)
+    let merged = merge(&arr[0..mid], &arr[mid..], offset, trace);
+    for (k, value) in merged.into_iter().enumerate() {
+        arr[k] = value;
+        trace(SortStep::Set(offset + k, value));
+    }
 }
 
-fn merge(left: &[i32], right: &[i32]) -> Vec<i32> {
+fn merge<F: FnMut(SortStep)>(left: &[i32], right: &[i32], offset: usize, trace: &mut F) -> Vec<i32> {
     let mut result = Vec::with_capacity(left.len() + right.len());
     let (mut i, mut j) = (0, 0);
 
     while i < left.len() && j < right.len() {
+        trace(SortStep::Compare(offset + i, offset + left.len() + j));
         if left[i] <= right[j] {
             result.push(left[i]);
             i += 1;
@@ -103,91 +311,139 @@ fn merge(left: &[i32], right: &[i32]) -> Vec<i32> {
 
 /// Quick Sort
 /// Time complexity: O(n log n) average, O(n^2) worst case
-fn quick_sort(arr: &[i32]) -> Vec<i32> {
-    if arr.len() <= 1 {
-        return arr.to_vec();
-    }
-
-    let mut result = arr.to_vec();
-    quick_sort_helper(&mut result, 0, (result.len() - 1) as i32);
-    result
+pub fn quick_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Quick, arr, |_| {})
 }
 
-fn quick_sort_helper(arr: &mut [i32], low: i32, high: i32) {
+fn quick_sort_helper<F: FnMut(SortStep)>(arr: &mut [i32], low: i32, high: i32, trace: &mut F) {
     if low < high {
-        let pi = partition(arr, low, high);
+        let pi = partition(arr, low, high, trace);
 
         // Recursively sort elements before and after partition
-        quick_sort_helper(arr, low, pi - 1);
-        quick_sort_helper(arr, pi + 1, high);
+        quick_sort_helper(arr, low, pi - 1, trace);
+        quick_sort_helper(arr, pi + 1, high, trace);
     }
 }
 
-fn partition(arr: &mut [i32], low: i32, high: i32) -> i32 {
+fn partition<F: FnMut(SortStep)>(arr: &mut [i32], low: i32, high: i32, trace: &mut F) -> i32 {
     let pivot = arr[high as usize];
     let mut i = low - 1;
 
     for j in low..high {
+        trace(SortStep::Compare(j as usize, high as usize));
         if arr[j as usize] <= pivot {
             i += 1;
             arr.swap(i as usize, j as usize);
+            trace(SortStep::Swap(i as usize, j as usize));
         }
     }
 
     arr.swap((i + 1) as usize, high as usize);
+    trace(SortStep::Swap((i + 1) as usize, high as usize));
     i + 1
 }
 
 /// Heap Sort
 /// Time complexity: O(n log n)
-fn heap_sort(arr: &[i32]) -> Vec<i32> {
-    let mut result = arr.to_vec();
+pub fn heap_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Heap, arr, |_| {})
+}
+
+fn heap_sort_in_place<F: FnMut(SortStep)>(result: &mut [i32], trace: &mut F) {
     let n = result.len();
 
     // Build max heap
     for i in (0..(n / 2)).rev() {
-        heapify(&mut result, n, i);
+        heapify(result, n, i, trace);
     }
 
     // Extract elements from heap one by one
     for i in (1..n).rev() {
         // Move current root to end
         result.swap(0, i);
+        trace(SortStep::Swap(0, i));
 
         // Call heapify on the reduced heap
-        heapify(&mut result, i, 0);
+        heapify(result, i, 0, trace);
     }
-
-    result
 }
 
-fn heapify(arr: &mut [i32], n: usize, i: usize) {
+fn heapify<F: FnMut(SortStep)>(arr: &mut [i32], n: usize, i: usize, trace: &mut F) {
     let mut largest = i;      // Initialize largest as root
     let left = 2 * i + 1;     // left = 2*i + 1
     let right = 2 * i + 2;    // right = 2*i + 2
 
     // If left child is larger than root
-    if left < n && arr[left] > arr[largest] {
-        largest = left;
+    if left < n {
+        trace(SortStep::Compare(left, largest));
+        if arr[left] > arr[largest] {
+            largest = left;
+        }
     }
 
     // If right child is larger than largest so far
-    if right < n && arr[right] > arr[largest] {
-        largest = right;
+    if right < n {
+        trace(SortStep::Compare(right, largest));
+        if arr[right] > arr[largest] {
+            largest = right;
+        }
     }
 
     // If largest is not root
     if largest != i {
         arr.swap(i, largest);
+        trace(SortStep::Swap(i, largest));
 
         // Recursively heapify the affected sub-tree
-        heapify(arr, n, largest);
+        heapify(arr, n, largest, trace);
     }
 }
 
+/// Shell Sort
+/// Time complexity: depends on the gap sequence, usually O(n log^2 n)
+pub fn shell_sort(arr: &[i32]) -> Vec<i32> {
+    sort_traced(SortAlgorithm::Shell, arr, |_| {})
+}
+
+fn shell_sort_in_place<F: FnMut(SortStep)>(result: &mut [i32], trace: &mut F) {
+    let n = result.len();
+
+    // Start with a big gap, then reduce the gap
+    let mut gap = n / 2;
+
+    while gap > 0 {
+        for i in gap..n {
+            // Save result[i] in temp and make a hole at position i
+            let temp = result[i];
+
+            // Shift earlier gap-sorted elements up until the correct
+            // location for result[i] is found
+            let mut j = i;
+            while j >= gap {
+                trace(SortStep::Compare(j - gap, j));
+                if result[j - gap] <= temp {
+                    break;
+                }
+                result[j] = result[j - gap];
+                trace(SortStep::Set(j, result[j]));
+                j -= gap;
+            }
+
+            // Put temp (the original result[i]) in its correct location
+            result[j] = temp;
+            trace(SortStep::Set(j, temp));
+        }
+
+        // Reduce the gap
+        gap /= 2;
+    }
+}
+
+// ========== Non-Comparison Sorts ==========
+
 /// Counting Sort
 /// Time complexity: O(n + k) where k is the range of input elements
-fn counting_sort(arr: &[i32]) -> Vec<i32> {
+pub fn counting_sort(arr: &[i32]) -> Vec<i32> {
     if arr.is_empty() {
         return Vec::new();
     }
@@ -224,7 +480,7 @@ fn counting_sort(arr: &[i32]) -> Vec<i32> {
 
 /// Radix Sort
 /// Time complexity: O(d * (n + b)) with d being the number of digits and b being the base
-fn radix_sort(arr: &[i32]) -> Vec<i32> {
+pub fn radix_sort(arr: &[i32]) -> Vec<i32> {
     if arr.is_empty() {
         return Vec::new();
     }
@@ -279,7 +535,7 @@ fn radix_sort(arr: &[i32]) -> Vec<i32> {
 fn counting_sort_by_digit(arr: &mut [i32], exp: i32) {
     let n = arr.len();
     let mut output = vec![0; n];
-    let mut count = vec![0; 10];
+    let mut count = [0; 10];
 
     // Store count of occurrences in count[]
     for &val in arr.iter() {
@@ -301,14 +557,12 @@ fn counting_sort_by_digit(arr: &mut [i32], exp: i32) {
     }
 
     // Copy the output array to arr[]
-    for i in 0..n {
-        arr[i] = output[i];
-    }
+    arr.copy_from_slice(&output);
 }
 
 /// Bucket Sort
 /// Time complexity: O(n + k) where k is the number of buckets
-fn bucket_sort(arr: &[i32], num_buckets: usize) -> Vec<i32> {
+pub fn bucket_sort(arr: &[i32], num_buckets: usize) -> Vec<i32> {
     if arr.is_empty() {
         return Vec::new();
     }
@@ -342,52 +596,254 @@ fn bucket_sort(arr: &[i32], num_buckets: usize) -> Vec<i32> {
     result
 }
 
-/// Shell Sort
-/// Time complexity: depends on the gap sequence, usually O(n log^2 n)
-fn shell_sort(arr: &[i32]) -> Vec<i32> {
-    let mut result = arr.to_vec();
-    let n = result.len();
+// ========== Demo ==========
 
-    // Start with a big gap, then reduce the gap
-    let mut gap = n / 2;
+#[cfg(feature = "std")]
+const DEMO_ARRAY: [i32; 7] = [64, 34, 25, 12, 22, 11, 90];
 
-    while gap > 0 {
-        for i in gap..n {
-            // Save result[i] in temp and make a hole at position i
-            let temp = result[i];
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+#[cfg(feature = "std")]
+pub struct SortingAlgorithmsDemo;
 
-            // Shift earlier gap-sorted elements up until the correct
-            // location for result[i] is found
-            let mut j = i;
-            while j >= gap && result[j - gap] > temp {
-                result[j] = result[j - gap];
-                j -= gap;
+#[cfg(feature = "std")]
+impl Demo for SortingAlgorithmsDemo {
+    fn name(&self) -> &'static str {
+        "sorting_algorithms"
+    }
+
+    fn category(&self) -> &'static str {
+        "algorithms"
+    }
+
+    fn description(&self) -> &'static str {
+        "Comparison and non-comparison sorts"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let test_array = DEMO_ARRAY;
+
+        writeln!(out, "Original array: {:?}", test_array)?;
+        writeln!(out, "Bubble Sort: {:?}", bubble_sort(&test_array))?;
+        writeln!(out, "Selection Sort: {:?}", selection_sort(&test_array))?;
+        writeln!(out, "Insertion Sort: {:?}", insertion_sort(&test_array))?;
+        writeln!(out, "Merge Sort: {:?}", merge_sort(&test_array))?;
+        writeln!(out, "Quick Sort: {:?}", quick_sort(&test_array))?;
+        writeln!(out, "Heap Sort: {:?}", heap_sort(&test_array))?;
+        writeln!(out, "Counting Sort: {:?}", counting_sort(&test_array))?;
+        writeln!(out, "Radix Sort: {:?}", radix_sort(&test_array))?;
+        writeln!(out, "Bucket Sort: {:?}", bucket_sort(&test_array, 5))?; // Using 5 buckets
+        writeln!(out, "Shell Sort: {:?}", shell_sort(&test_array))?;
+
+        writeln!(out, "\n===== Step by step =====")?;
+        sort_with(SortAlgorithm::Insertion, &[4, 2, 3, 1], ExecutionMode::Animated(out, Duration::from_millis(100)))?;
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    fn results(&self) -> Option<serde_json::Value> {
+        let runs = SortAlgorithm::ALL.map(|algorithm| SortRun::new(algorithm, &DEMO_ARRAY));
+        serde_json::to_value(runs).ok()
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn run_demo() {
+    demo::run_to_stdout(&SortingAlgorithmsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type SortFn = fn(&[i32]) -> Vec<i32>;
+
+    fn bucket_sort_5(arr: &[i32]) -> Vec<i32> {
+        bucket_sort(arr, 5)
+    }
+
+    const SORTS: [(&str, SortFn); 10] = [
+        ("bubble", bubble_sort),
+        ("selection", selection_sort),
+        ("insertion", insertion_sort),
+        ("merge", merge_sort),
+        ("quick", quick_sort),
+        ("heap", heap_sort),
+        ("counting", counting_sort),
+        ("radix", radix_sort),
+        ("bucket", bucket_sort_5),
+        ("shell", shell_sort),
+    ];
+
+    fn assert_all_sort(input: &[i32]) {
+        let mut expected = input.to_vec();
+        expected.sort();
+        for (name, sort) in SORTS {
+            assert_eq!(sort(input), expected, "{} sort on {:?}", name, input);
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_all_sort(&[]);
+    }
+
+    #[test]
+    fn single_element() {
+        assert_all_sort(&[42]);
+    }
+
+    #[test]
+    fn two_elements_either_order() {
+        assert_all_sort(&[1, 2]);
+        assert_all_sort(&[2, 1]);
+    }
+
+    #[test]
+    fn duplicates() {
+        assert_all_sort(&[5, 3, 5, 1, 3, 5, 1]);
+        assert_all_sort(&[7, 7, 7, 7]);
+    }
+
+    #[test]
+    fn already_sorted_and_reversed() {
+        let sorted: Vec<i32> = (0..50).collect();
+        let reversed: Vec<i32> = (0..50).rev().collect();
+        assert_all_sort(&sorted);
+        assert_all_sort(&reversed);
+    }
+
+    #[test]
+    fn negatives_and_mixed_magnitudes() {
+        assert_all_sort(&[-3, 10, 0, -100, 7, 1000, -1, 42]);
+        assert_all_sort(&[-5, -1, -20]);
+    }
+
+    #[test]
+    fn demo_array() {
+        assert_all_sort(&[64, 34, 25, 12, 22, 11, 90]);
+    }
+
+    #[test]
+    fn pseudo_random_inputs() {
+        let mut state = 0x2545_f491_u32;
+        for len in [3, 17, 64, 129] {
+            let input: Vec<i32> = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    (state % 2001) as i32 - 1000
+                })
+                .collect();
+            assert_all_sort(&input);
+        }
+    }
+
+    #[test]
+    fn input_is_left_untouched() {
+        let input = [3, 1, 2];
+        for (_, sort) in SORTS {
+            sort(&input);
+        }
+        assert_eq!(input, [3, 1, 2]);
+    }
+
+    #[test]
+    fn replaying_steps_reproduces_the_sorted_output() {
+        let input = [5, -2, 9, 5, 0, 13, -7, 2, 2, 8];
+        for algorithm in SortAlgorithm::ALL {
+            let sorted = sort_traced(algorithm, &input, |_| {});
+            let mut replay = input.to_vec();
+            for step in sort_steps(algorithm, &input) {
+                step.apply(&mut replay);
+            }
+            assert_eq!(replay, sorted, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn trace_indices_stay_in_bounds() {
+        let input = [3, 1, 4, 1, 5, 9, 2, 6];
+        for algorithm in SortAlgorithm::ALL {
+            for step in sort_steps(algorithm, &input) {
+                let (SortStep::Compare(i, j) | SortStep::Swap(i, j)) = step else {
+                    continue;
+                };
+                assert!(i < input.len() && j < input.len(), "{:?}", step);
             }
+        }
+    }
 
-            // Put temp (the original result[i]) in its correct location
-            result[j] = temp;
+    #[test]
+    fn bubble_sort_on_sorted_input_only_compares() {
+        let steps = sort_steps(SortAlgorithm::Bubble, &[1, 2, 3, 4]);
+        assert_eq!(
+            steps,
+            [SortStep::Compare(0, 1), SortStep::Compare(1, 2), SortStep::Compare(2, 3)]
+        );
+    }
+
+    #[test]
+    fn trivial_inputs_produce_no_steps() {
+        for algorithm in SortAlgorithm::ALL {
+            assert!(sort_steps(algorithm, &[]).is_empty());
+            assert!(sort_steps(algorithm, &[1]).is_empty());
         }
+    }
 
-        // Reduce the gap
-        gap /= 2;
+    #[cfg(feature = "std")]
+    #[test]
+    fn animated_sort_writes_every_step_and_the_array_after_it() {
+        let mut out = Vec::new();
+        let sorted = sort_with(SortAlgorithm::Bubble, &[2, 1], ExecutionMode::Animated(&mut out, Duration::ZERO)).unwrap();
+        assert_eq!(sorted, [1, 2]);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "bubble sort of [2, 1]\n  Compare(0, 1)  [2, 1]\n  Swap(0, 1)     [1, 2]\n"
+        );
+
+        let mut steps = Vec::new();
+        let mut record = |step: &SortStep| steps.push(*step);
+        sort_with(SortAlgorithm::Heap, &[3, 1, 2], ExecutionMode::Traced(&mut record)).unwrap();
+        assert_eq!(steps, sort_steps(SortAlgorithm::Heap, &[3, 1, 2]));
+        assert_eq!(sort_with(SortAlgorithm::Heap, &[3, 1, 2], ExecutionMode::Silent).unwrap(), [1, 2, 3]);
     }
 
-    result
-}
+    #[test]
+    fn algorithm_names_round_trip() {
+        for algorithm in SortAlgorithm::ALL {
+            assert_eq!(SortAlgorithm::from_name(algorithm.name()), Some(algorithm));
+        }
+        assert_eq!(SortAlgorithm::from_name("bogo"), None);
+    }
 
-fn main() {
-    // Test array
-    let test_array = vec![64, 34, 25, 12, 22, 11, 90];
-
-    println!("Original array: {:?}", test_array);
-    println!("Bubble Sort: {:?}", bubble_sort(&test_array));
-    println!("Selection Sort: {:?}", selection_sort(&test_array));
-    println!("Insertion Sort: {:?}", insertion_sort(&test_array));
-    println!("Merge Sort: {:?}", merge_sort(&test_array));
-    println!("Quick Sort: {:?}", quick_sort(&test_array));
-    println!("Heap Sort: {:?}", heap_sort(&test_array));
-    println!("Counting Sort: {:?}", counting_sort(&test_array));
-    println!("Radix Sort: {:?}", radix_sort(&test_array));
-    println!("Bucket Sort: {:?}", bucket_sort(&test_array, 5)); // Using 5 buckets
-    println!("Shell Sort: {:?}", shell_sort(&test_array));
+    #[test]
+    fn bucket_sort_with_more_buckets_than_items() {
+        assert_eq!(bucket_sort(&[9, 2, 5], 10), vec![2, 5, 9]);
+        assert_eq!(bucket_sort(&[4, 4], 1), vec![4, 4]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn steps_serialize_with_the_names_used_elsewhere() {
+        let json = serde_json::to_string(&sort_steps(SortAlgorithm::Insertion, &[2, 1])).unwrap();
+        assert_eq!(json, r#"[{"compare":[0,1]},{"set":[1,2]},{"set":[0,1]}]"#);
+        for algorithm in SortAlgorithm::ALL {
+            let json = serde_json::to_string(&algorithm).unwrap();
+            assert_eq!(json, format!("\"{}\"", algorithm.name()));
+            assert_eq!(serde_json::from_str::<SortAlgorithm>(&json).unwrap(), algorithm);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn sort_runs_serialize_with_their_steps() {
+        let run = SortRun::new(SortAlgorithm::Bubble, &[2, 1]);
+        let json = serde_json::to_string(&run).unwrap();
+        assert_eq!(
+            json,
+            r#"{"algorithm":"bubble","input":[2,1],"sorted":[1,2],"steps":[{"compare":[0,1]},{"swap":[0,1]}]}"#
+        );
+        assert_eq!(serde_json::from_str::<SortRun>(&json).unwrap(), run);
+    }
 }
@@ -0,0 +1,76 @@
+/*
+ * Exercises the C API in include/tech_notes.h the way a C caller would.
+ * tests/ffi.rs compiles this against the crate's cdylib and runs it; it prints
+ * what it checked and exits non-zero on the first failure.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "tech_notes.h"
+
+#define CHECK(cond)                                                         \
+    do {                                                                    \
+        if (!(cond)) {                                                      \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            exit(1);                                                        \
+        }                                                                   \
+    } while (0)
+
+static void sort_in_place(void) {
+    int32_t values[] = {5, -1, 3, 3, 0};
+    const int32_t expected[] = {-1, 0, 3, 3, 5};
+    size_t len = sizeof values / sizeof values[0];
+
+    CHECK(tn_sort_i32(values, len) == TN_STATUS_OK);
+    CHECK(memcmp(values, expected, sizeof values) == 0);
+
+    int32_t again[] = {9, 8, 7};
+    CHECK(tn_sort_i32_with("quick", again, 3) == TN_STATUS_OK);
+    CHECK(again[0] == 7 && again[2] == 9);
+    printf("sorted in place\n");
+}
+
+static void report_errors(void) {
+    int32_t values[] = {2, 1};
+    TnStatus status = tn_sort_i32_with("bogo", values, 2);
+    CHECK(status == TN_STATUS_UNKNOWN_NAME);
+    CHECK(values[0] == 2);
+    CHECK(tn_sort_i32(NULL, 2) == TN_STATUS_NULL_POINTER);
+    printf("bogo sort: %s\n", tn_status_message(status));
+}
+
+static void traverse_graph(void) {
+    const char *edges[][2] = {{"A", "B"}, {"A", "C"}, {"B", "D"}, {"B", "E"}, {"C", "F"}, {"E", "F"}};
+    TnGraph *graph = tn_graph_new();
+    CHECK(graph != NULL);
+    for (size_t i = 0; i < sizeof edges / sizeof edges[0]; i++) {
+        CHECK(tn_graph_add_edge(graph, edges[i][0], edges[i][1]) == TN_STATUS_OK);
+    }
+
+    /* Ask for the size first, then allocate exactly that much */
+    size_t needed = 0;
+    CHECK(tn_graph_traverse(graph, "bfs", "A", NULL, 0, &needed) == TN_STATUS_BUFFER_TOO_SMALL);
+    char *order = malloc(needed);
+    CHECK(order != NULL);
+    CHECK(tn_graph_traverse(graph, "bfs", "A", order, needed, &needed) == TN_STATUS_OK);
+    CHECK(strcmp(order, "A B C D E F") == 0);
+    printf("bfs from A: %s\n", order);
+
+    CHECK(tn_graph_traverse(graph, "dfs-recursive", "A", order, needed, &needed) == TN_STATUS_OK);
+    CHECK(strcmp(order, "A B D E F C") == 0);
+    printf("dfs from A: %s\n", order);
+    free(order);
+
+    CHECK(tn_graph_traverse(graph, "bfs", "Z", NULL, 0, &needed) == TN_STATUS_UNKNOWN_VERTEX);
+    tn_graph_free(graph);
+    tn_graph_free(NULL);
+}
+
+int main(void) {
+    sort_in_place();
+    report_errors();
+    traverse_graph();
+    return 0;
+}
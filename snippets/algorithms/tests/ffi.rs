@@ -0,0 +1,61 @@
+//! Builds `tests/c/ffi_test.c` against the crate's cdylib and runs it, so the C API is
+//! tested from C as well as from the unit tests in `ffi.rs`.
+//!
+//! Run with `cargo test -p algorithms --features ffi --test ffi`; needs a C compiler as
+//! `cc` (or `$CC`).
+
+#![cfg(all(feature = "ffi", unix))]
+
+use std::env;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where cargo put the cdylib: next to this test binary, in `target/<profile>/deps`.
+fn library_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    let dir = exe.parent().unwrap().to_path_buf();
+    let library = format!("{}algorithms{}", env::consts::DLL_PREFIX, env::consts::DLL_SUFFIX);
+    assert!(dir.join(&library).exists(), "{} not found in {}", library, dir.display());
+    dir
+}
+
+#[test]
+fn c_program_uses_the_header_and_library() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let libs = library_dir();
+    let program = libs.join("tech_notes_ffi_test");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let compiled = Command::new(&compiler)
+        .args(["-std=c99", "-Wall", "-Werror"])
+        .arg(crate_dir.join("tests/c/ffi_test.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(&libs)
+        .arg(format!("-Wl,-rpath,{}", libs.display()))
+        .arg("-lalgorithms")
+        .arg("-o")
+        .arg(&program)
+        .output();
+    let compiled = match compiled {
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            eprintln!("skipping: no C compiler '{}'", compiler);
+            return;
+        }
+        result => result.unwrap(),
+    };
+    assert!(compiled.status.success(), "{}", String::from_utf8_lossy(&compiled.stderr));
+
+    let run = Command::new(&program).output().unwrap();
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    assert!(run.status.success(), "{}{}", stdout, String::from_utf8_lossy(&run.stderr));
+    assert_eq!(
+        stdout,
+        "sorted in place\n\
+         bogo sort: unknown algorithm or traversal name\n\
+         bfs from A: A B C D E F\n\
+         dfs from A: A B D E F C\n"
+    );
+}
@@ -0,0 +1,211 @@
+//! WebAssembly bindings for the browser playground
+//!
+//! Built with the crate's `wasm` feature. Every export returns a JSON string so the page
+//! can `JSON.parse` it without any generated TypeScript glue:
+//!
+//! ```text
+//! sortTrace("quick", [3, 1, 2])
+//!   -> {"algorithm":"quick","input":[3,1,2],"sorted":[1,2,3],
+//!       "steps":[{"op":"compare","i":0,"j":2},{"op":"compare","i":1,"j":2},
+//!                {"op":"swap","i":0,"j":1},{"op":"swap","i":1,"j":2}]}
+//!
+//! graphTrace("bfs", "A-B A-C B-D", "A")
+//!   -> {"traversal":"bfs","start":"A","vertices":["A","B","C","D"],
+//!       "edges":[["A","B"],["A","C"],["B","D"]],
+//!       "frames":[{"vertex":"A","frontier":[],"visited":["A"]},...]}
+//! ```
+//!
+//! Build with `wasm-pack build snippets/algorithms --target web --features wasm`
+//! (see `playground/README.md`).
+
+use crate::graph_traversal::{parse_edges, Graph, Traversal, TraversalFrame};
+use crate::sorting_algorithms::{sort_traced, SortAlgorithm, SortStep};
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+// ========== Exports ==========
+
+/// Names accepted by `sortTrace`.
+#[wasm_bindgen(js_name = sortAlgorithms)]
+pub fn sort_algorithms() -> Vec<String> {
+    SortAlgorithm::ALL.iter().map(|a| a.name().to_string()).collect()
+}
+
+/// Names accepted by `graphTrace`.
+#[wasm_bindgen(js_name = traversals)]
+pub fn traversals() -> Vec<String> {
+    Traversal::ALL.iter().map(|t| t.name().to_string()).collect()
+}
+
+/// The edges of `create_sample_graph`, in the format `graphTrace` expects.
+#[wasm_bindgen(js_name = sampleGraphEdges)]
+pub fn sample_graph_edges() -> String {
+    "A-B A-C B-D B-E C-F E-F".to_string()
+}
+
+/// Sorts `values` with the named algorithm and returns the input, output and every step.
+#[wasm_bindgen(js_name = sortTrace)]
+pub fn sort_trace(algorithm: &str, values: Vec<i32>) -> Result<String, JsError> {
+    sort_trace_json(algorithm, &values).map_err(|e| JsError::new(&e))
+}
+
+/// Runs the named traversal over `edges` (`"A-B A-C"`, separated by spaces or commas)
+/// from `start` and returns the graph plus one frame per visited vertex.
+#[wasm_bindgen(js_name = graphTrace)]
+pub fn graph_trace(traversal: &str, edges: &str, start: &str) -> Result<String, JsError> {
+    graph_trace_json(traversal, edges, start).map_err(|e| JsError::new(&e))
+}
+
+// ========== JSON Rendering ==========
+
+fn sort_trace_json(algorithm: &str, values: &[i32]) -> Result<String, String> {
+    let algorithm =
+        SortAlgorithm::from_name(algorithm).ok_or_else(|| format!("unknown sort algorithm '{}'", algorithm))?;
+
+    let mut steps = Vec::new();
+    let sorted = sort_traced(algorithm, values, |step| steps.push(step));
+
+    let mut json = String::new();
+    write!(json, "{{\"algorithm\":{},", quote(algorithm.name())).unwrap();
+    write!(json, "\"input\":{},", int_array(values)).unwrap();
+    write!(json, "\"sorted\":{},", int_array(&sorted)).unwrap();
+    json.push_str("\"steps\":[");
+    for (k, step) in steps.iter().enumerate() {
+        if k > 0 {
+            json.push(',');
+        }
+        match *step {
+            SortStep::Compare(i, j) => write!(json, "{{\"op\":\"compare\",\"i\":{},\"j\":{}}}", i, j),
+            SortStep::Swap(i, j) => write!(json, "{{\"op\":\"swap\",\"i\":{},\"j\":{}}}", i, j),
+            SortStep::Set(i, value) => write!(json, "{{\"op\":\"set\",\"i\":{},\"value\":{}}}", i, value),
+        }
+        .unwrap();
+    }
+    json.push_str("]}");
+    Ok(json)
+}
+
+fn graph_trace_json(traversal: &str, edges: &str, start: &str) -> Result<String, String> {
+    let traversal = Traversal::from_name(traversal).ok_or_else(|| format!("unknown traversal '{}'", traversal))?;
+    let edges = parse_edges(edges)?;
+    let graph = Graph::from_edges(edges.iter().copied());
+    if !graph.contains(start) {
+        return Err(format!("start vertex '{}' is not in the graph", start));
+    }
+
+    let mut json = String::new();
+    write!(json, "{{\"traversal\":{},", quote(traversal.name())).unwrap();
+    write!(json, "\"start\":{},", quote(start)).unwrap();
+    write!(json, "\"vertices\":{},", string_array(&graph.vertices())).unwrap();
+    json.push_str("\"edges\":[");
+    for (k, (a, b)) in edges.iter().enumerate() {
+        if k > 0 {
+            json.push(',');
+        }
+        write!(json, "[{},{}]", quote(a), quote(b)).unwrap();
+    }
+    json.push_str("],\"frames\":[");
+    for (k, frame) in graph.trace(traversal, start).iter().enumerate() {
+        if k > 0 {
+            json.push(',');
+        }
+        json.push_str(&frame_json(frame));
+    }
+    json.push_str("]}");
+    Ok(json)
+}
+
+fn frame_json(frame: &TraversalFrame) -> String {
+    format!(
+        "{{\"vertex\":{},\"frontier\":{},\"visited\":{}}}",
+        quote(&frame.vertex),
+        string_array(&frame.frontier),
+        string_array(&frame.visited)
+    )
+}
+
+fn int_array(values: &[i32]) -> String {
+    let items: Vec<String> = values.iter().map(i32::to_string).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn string_array(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|v| quote(v)).collect();
+    format!("[{}]", items.join(","))
+}
+
+/// A JSON string literal for `s`.
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_trace_lists_every_step() {
+        let json = sort_trace_json("bubble", &[2, 1]).unwrap();
+        assert_eq!(
+            json,
+            r#"{"algorithm":"bubble","input":[2,1],"sorted":[1,2],"steps":[{"op":"compare","i":0,"j":1},{"op":"swap","i":0,"j":1}]}"#
+        );
+        let json = sort_trace_json("insertion", &[2, 1]).unwrap();
+        assert!(json.contains(r#"{"op":"set","i":0,"value":1}"#), "{}", json);
+    }
+
+    #[test]
+    fn sort_trace_rejects_unknown_algorithms() {
+        assert_eq!(sort_trace_json("bogo", &[1]).unwrap_err(), "unknown sort algorithm 'bogo'");
+    }
+
+    #[test]
+    fn graph_trace_renders_frames() {
+        let json = graph_trace_json("bfs", "A-B, A-C", "A").unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"traversal":"bfs","start":"A","vertices":["A","B","C"],"edges":[["A","B"],["A","C"]],"frames":["#,
+                r#"{"vertex":"A","frontier":[],"visited":["A"]},"#,
+                r#"{"vertex":"B","frontier":["C"],"visited":["A","B"]},"#,
+                r#"{"vertex":"C","frontier":[],"visited":["A","B","C"]}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn graph_trace_reports_bad_input() {
+        assert!(graph_trace_json("bfs", "A-B", "Z").unwrap_err().contains("'Z'"));
+        assert!(graph_trace_json("bfs", "A-B AC", "A").unwrap_err().contains("'AC'"));
+        assert!(graph_trace_json("walk", "A-B", "A").unwrap_err().contains("'walk'"));
+    }
+
+    #[test]
+    fn sample_edges_match_the_sample_graph() {
+        let edges = sample_graph_edges();
+        let parsed = Graph::from_edges(parse_edges(&edges).unwrap());
+        let sample = crate::graph_traversal::create_sample_graph();
+        assert_eq!(parsed.vertices(), sample.vertices());
+        for vertex in sample.vertices() {
+            assert_eq!(parsed.get_sorted_neighbors(&vertex), sample.get_sorted_neighbors(&vertex));
+        }
+    }
+
+    #[test]
+    fn quote_escapes_json_specials() {
+        assert_eq!(quote(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(quote("tab\t"), r#""tab\u0009""#);
+    }
+}
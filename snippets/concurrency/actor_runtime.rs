@@ -0,0 +1,568 @@
+//! A Mini Actor Runtime over std Threads
+//!
+//! In the actor model, state lives inside an actor and is only touched by the actor itself.
+//! Everyone else talks to it by sending messages to its mailbox; the actor handles them
+//! one at a time, so no locks are needed around its state.
+//!
+//! This file builds a tiny runtime:
+//! - An `Actor` trait with an associated (typed) message type
+//! - `Addr<A>` handles that can be cloned and shared between threads
+//! - `tell` (fire-and-forget) and `ask` (request-response through a reply channel)
+//! - Supervision: a supervised actor that panics is rebuilt from its factory after an
+//!   exponential backoff, keeping its mailbox and address, up to a restart limit
+
+use demo::{Demo, Printer, ThreadOutput};
+use std::fmt;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// ========== Actor Trait ==========
+
+/// An isolated unit of state that reacts to messages
+pub trait Actor: Send + 'static {
+    type Message: Send + 'static;
+
+    /// Called once when the actor starts (and again after every restart)
+    fn started(&mut self, _ctx: &mut Context) {}
+
+    /// Handle a single message
+    fn handle(&mut self, message: Self::Message, ctx: &mut Context);
+
+    /// Called when the actor stops for good
+    fn stopped(&mut self) {}
+}
+
+/// Per-actor runtime information passed to every handler
+pub struct Context {
+    restarts: u32,
+    stop_requested: bool,
+}
+
+impl Context {
+    /// How many times this actor has been restarted by its supervisor
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Stop after the current message
+    pub fn stop(&mut self) {
+        self.stop_requested = true;
+    }
+}
+
+// ========== Errors and Replies ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActorError {
+    /// The actor has stopped and its mailbox no longer accepts messages
+    MailboxClosed,
+    /// The actor dropped the reply channel (for example because it panicked)
+    NoReply,
+    /// No reply arrived within the requested time
+    Timeout,
+}
+
+impl fmt::Display for ActorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActorError::MailboxClosed => write!(f, "actor mailbox is closed"),
+            ActorError::NoReply => write!(f, "actor dropped the request without replying"),
+            ActorError::Timeout => write!(f, "timed out waiting for a reply"),
+        }
+    }
+}
+
+impl std::error::Error for ActorError {}
+
+/// One-shot channel an actor uses to answer an `ask`
+pub struct Reply<T>(Sender<T>);
+
+impl<T> Reply<T> {
+    pub fn send(self, value: T) {
+        // The asker may have timed out and gone away; that's not the actor's problem
+        let _ = self.0.send(value);
+    }
+}
+
+// ========== Supervision ==========
+
+/// How a supervised actor is restarted after a panic
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    pub max_restarts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Delay before restart number `attempt` (1-based): doubles each time, capped
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+// ========== Address ==========
+
+enum Envelope<M> {
+    Message(M),
+    Stop,
+}
+
+struct Shared {
+    restarts: AtomicU32,
+    alive: AtomicBool,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Cloneable handle for sending messages to an actor
+pub struct Addr<A: Actor> {
+    mailbox: Sender<Envelope<A::Message>>,
+    shared: Arc<Shared>,
+}
+
+impl<A: Actor> Clone for Addr<A> {
+    fn clone(&self) -> Self {
+        Addr {
+            mailbox: self.mailbox.clone(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<A: Actor> Addr<A> {
+    /// Fire-and-forget
+    pub fn tell(&self, message: A::Message) -> Result<(), ActorError> {
+        self.mailbox
+            .send(Envelope::Message(message))
+            .map_err(|_| ActorError::MailboxClosed)
+    }
+
+    /// Send a request built around a fresh reply channel and wait for the answer
+    pub fn ask<R, F>(&self, make_message: F) -> Result<R, ActorError>
+    where
+        F: FnOnce(Reply<R>) -> A::Message,
+    {
+        let rx = self.send_request(make_message)?;
+        rx.recv().map_err(|_| ActorError::NoReply)
+    }
+
+    /// Like `ask`, but give up after `timeout`
+    pub fn ask_timeout<R, F>(&self, make_message: F, timeout: Duration) -> Result<R, ActorError>
+    where
+        F: FnOnce(Reply<R>) -> A::Message,
+    {
+        let rx = self.send_request(make_message)?;
+        rx.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => ActorError::Timeout,
+            RecvTimeoutError::Disconnected => ActorError::NoReply,
+        })
+    }
+
+    fn send_request<R, F>(&self, make_message: F) -> Result<Receiver<R>, ActorError>
+    where
+        F: FnOnce(Reply<R>) -> A::Message,
+    {
+        let (tx, rx) = mpsc::channel();
+        self.tell(make_message(Reply(tx)))?;
+        Ok(rx)
+    }
+
+    /// Number of supervisor restarts so far
+    pub fn restarts(&self) -> u32 {
+        self.shared.restarts.load(Ordering::SeqCst)
+    }
+
+    /// Whether the actor's thread is still processing its mailbox
+    pub fn is_alive(&self) -> bool {
+        self.shared.alive.load(Ordering::SeqCst)
+    }
+
+    /// Ask the actor to stop after the messages already queued, and wait for it
+    pub fn stop(&self) {
+        let _ = self.mailbox.send(Envelope::Stop);
+        self.join();
+    }
+
+    /// Wait for the actor's thread to finish (only the first caller actually blocks)
+    pub fn join(&self) {
+        let handle = self.shared.thread.lock().unwrap().take();
+        if let Some(handle) = handle {
+            if handle.thread().id() != thread::current().id() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+// ========== Spawning ==========
+
+/// Start an unsupervised actor: a panic stops it permanently
+pub fn spawn<A: Actor>(actor: A) -> Addr<A> {
+    let mut actor = Some(actor);
+    let policy = RestartPolicy {
+        max_restarts: 0,
+        ..RestartPolicy::default()
+    };
+    // The factory is only called once, for the initial instance
+    spawn_supervised(move || actor.take().expect("unsupervised actor cannot restart"), policy)
+}
+
+/// Start an actor built by `factory`, rebuilding it with the same factory after panics
+pub fn spawn_supervised<A, F>(mut factory: F, policy: RestartPolicy) -> Addr<A>
+where
+    A: Actor,
+    F: FnMut() -> A + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let shared = Arc::new(Shared {
+        restarts: AtomicU32::new(0),
+        alive: AtomicBool::new(true),
+        thread: Mutex::new(None),
+    });
+
+    let runtime_shared = Arc::clone(&shared);
+    let handle = thread::spawn(move || {
+        let mut ctx = Context {
+            restarts: 0,
+            stop_requested: false,
+        };
+        let mut actor = factory();
+        actor.started(&mut ctx);
+
+        for envelope in rx.iter() {
+            let message = match envelope {
+                Envelope::Message(message) => message,
+                Envelope::Stop => break,
+            };
+
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| actor.handle(message, &mut ctx)));
+            if outcome.is_err() {
+                if ctx.restarts >= policy.max_restarts {
+                    break;
+                }
+                ctx.restarts += 1;
+                runtime_shared.restarts.store(ctx.restarts, Ordering::SeqCst);
+                thread::sleep(policy.backoff(ctx.restarts));
+                actor = factory();
+                actor.started(&mut ctx);
+            }
+            if ctx.stop_requested {
+                break;
+            }
+        }
+
+        actor.stopped();
+        runtime_shared.alive.store(false, Ordering::SeqCst);
+        // Dropping `rx` here makes every later `tell` fail with MailboxClosed
+    });
+
+    *shared.thread.lock().unwrap() = Some(handle);
+    Addr { mailbox: tx, shared }
+}
+
+// ========== Example Actors ==========
+
+/// Messages understood by the counter actor
+pub enum CounterMsg {
+    Add(u64),
+    Get(Reply<u64>),
+    /// Simulates a bug that crashes the actor
+    Crash,
+}
+
+/// Holds a running total; state is lost on restart
+#[derive(Default)]
+pub struct Counter {
+    total: u64,
+}
+
+impl Actor for Counter {
+    type Message = CounterMsg;
+
+    fn handle(&mut self, message: CounterMsg, _ctx: &mut Context) {
+        match message {
+            CounterMsg::Add(n) => self.total += n,
+            CounterMsg::Get(reply) => reply.send(self.total),
+            CounterMsg::Crash => panic!("counter crashed with total {}", self.total),
+        }
+    }
+}
+
+/// Messages understood by the greeter actor
+pub enum GreeterMsg {
+    Greet(String, Reply<String>),
+    Shutdown,
+}
+
+/// Answers greetings and stops itself on request
+pub struct Greeter {
+    greeting: String,
+    greeted: usize,
+    printer: Printer,
+}
+
+impl Actor for Greeter {
+    type Message = GreeterMsg;
+
+    fn handle(&mut self, message: GreeterMsg, ctx: &mut Context) {
+        match message {
+            GreeterMsg::Greet(name, reply) => {
+                self.greeted += 1;
+                reply.send(format!("{}, {}! (#{})", self.greeting, name, self.greeted));
+            }
+            GreeterMsg::Shutdown => ctx.stop(),
+        }
+    }
+
+    fn stopped(&mut self) {
+        self.printer.println(format!("Greeter stopping after {} greetings", self.greeted));
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ActorRuntimeDemo;
+
+impl Demo for ActorRuntimeDemo {
+    fn name(&self) -> &'static str {
+        "actor_runtime"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Typed mailboxes, ask/tell and supervised restarts"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Tell and Ask =====")?;
+        let output = ThreadOutput::new();
+        let greeter = spawn(Greeter {
+            greeting: "Hello".to_string(),
+            greeted: 0,
+            printer: output.printer(),
+        });
+        for name in ["Alice", "Bob"] {
+            let answer = greeter.ask(|reply| GreeterMsg::Greet(name.to_string(), reply));
+            writeln!(out, "{:?}", answer)?;
+        }
+        greeter.tell(GreeterMsg::Shutdown).unwrap();
+        greeter.join();
+        output.drain_into(out)?;
+        writeln!(out, "Greeter alive? {}", greeter.is_alive())?;
+        writeln!(out, "Ask after shutdown: {:?}", greeter.ask(|r| GreeterMsg::Greet("Carol".into(), r)))?;
+
+        writeln!(out, "\n===== Supervision =====")?;
+        // Silence panic output from the intentional crashes
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+
+        let counter = spawn_supervised(Counter::default, RestartPolicy::default());
+        counter.tell(CounterMsg::Add(5)).unwrap();
+        writeln!(out, "Total before crash: {:?}", counter.ask(CounterMsg::Get))?;
+        counter.tell(CounterMsg::Crash).unwrap();
+        writeln!(out, "Total after restart: {:?} (state was reset)", counter.ask(CounterMsg::Get))?;
+        writeln!(out, "Restarts: {}", counter.restarts())?;
+
+        for _ in 0..3 {
+            counter.tell(CounterMsg::Crash).unwrap();
+        }
+        counter.join();
+        writeln!(out, "After exceeding the restart limit, alive? {}", counter.is_alive())?;
+        panic::set_hook(default_hook);
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ActorRuntimeDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn fast_policy(max_restarts: u32) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn tell_then_ask_sees_every_message_in_order() {
+        let counter = spawn(Counter::default());
+        for n in 1..=100 {
+            counter.tell(CounterMsg::Add(n)).unwrap();
+        }
+        assert_eq!(counter.ask(CounterMsg::Get), Ok(5050));
+        counter.stop();
+        assert!(!counter.is_alive());
+    }
+
+    #[test]
+    fn request_response_from_many_threads() {
+        let counter = spawn(Counter::default());
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                let counter = counter.clone();
+                scope.spawn(move || {
+                    for _ in 0..50 {
+                        counter.tell(CounterMsg::Add(1)).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.ask(CounterMsg::Get), Ok(400));
+    }
+
+    #[test]
+    fn ask_after_stop_fails() {
+        let counter = spawn(Counter::default());
+        counter.stop();
+        assert_eq!(counter.tell(CounterMsg::Add(1)), Err(ActorError::MailboxClosed));
+        assert_eq!(counter.ask(CounterMsg::Get), Err(ActorError::MailboxClosed));
+    }
+
+    #[test]
+    fn actor_can_stop_itself() {
+        let output = ThreadOutput::new();
+        let greeter = spawn(Greeter {
+            greeting: "Hi".to_string(),
+            greeted: 0,
+            printer: output.printer(),
+        });
+        assert_eq!(
+            greeter.ask(|r| GreeterMsg::Greet("Ann".to_string(), r)),
+            Ok("Hi, Ann! (#1)".to_string())
+        );
+        greeter.tell(GreeterMsg::Shutdown).unwrap();
+        greeter.join();
+        assert!(!greeter.is_alive());
+
+        let mut farewell = Vec::new();
+        output.drain_into(&mut farewell).unwrap();
+        assert_eq!(farewell, b"Greeter stopping after 1 greetings\n");
+    }
+
+    #[test]
+    fn ask_timeout_expires() {
+        struct Silent;
+        impl Actor for Silent {
+            type Message = Reply<()>;
+            fn handle(&mut self, reply: Reply<()>, _ctx: &mut Context) {
+                // Hold on to the reply without answering for a while
+                thread::sleep(Duration::from_millis(200));
+                drop(reply);
+            }
+        }
+        let silent = spawn(Silent);
+        assert_eq!(silent.ask_timeout(|r| r, Duration::from_millis(10)), Err(ActorError::Timeout));
+    }
+
+    #[test]
+    fn supervised_actor_restarts_with_fresh_state() {
+        let counter = spawn_supervised(Counter::default, fast_policy(3));
+        counter.tell(CounterMsg::Add(10)).unwrap();
+        assert_eq!(counter.ask(CounterMsg::Get), Ok(10));
+
+        counter.tell(CounterMsg::Crash).unwrap();
+        assert_eq!(counter.ask(CounterMsg::Get), Ok(0));
+        assert_eq!(counter.restarts(), 1);
+        assert!(counter.is_alive());
+
+        // Messages queued behind the crash are still delivered to the new instance
+        counter.tell(CounterMsg::Crash).unwrap();
+        counter.tell(CounterMsg::Add(7)).unwrap();
+        assert_eq!(counter.ask(CounterMsg::Get), Ok(7));
+        assert_eq!(counter.restarts(), 2);
+    }
+
+    #[test]
+    fn ask_that_crashes_the_actor_gets_no_reply() {
+        struct Fragile;
+        impl Actor for Fragile {
+            type Message = Reply<u8>;
+            fn handle(&mut self, _reply: Reply<u8>, _ctx: &mut Context) {
+                panic!("fragile");
+            }
+        }
+        let fragile = spawn_supervised(|| Fragile, fast_policy(1));
+        assert_eq!(fragile.ask(|r| r), Err(ActorError::NoReply));
+        // The restarted instance crashes too, which exhausts the restart budget
+        assert_eq!(fragile.ask(|r| r), Err(ActorError::NoReply));
+        fragile.join();
+        assert_eq!(fragile.restarts(), 1);
+    }
+
+    #[test]
+    fn actor_stops_after_restart_limit() {
+        let counter = spawn_supervised(Counter::default, fast_policy(2));
+        for _ in 0..3 {
+            counter.tell(CounterMsg::Crash).unwrap();
+        }
+        counter.join();
+        assert!(!counter.is_alive());
+        assert_eq!(counter.restarts(), 2);
+        assert_eq!(counter.tell(CounterMsg::Add(1)), Err(ActorError::MailboxClosed));
+    }
+
+    #[test]
+    fn unsupervised_actor_dies_on_first_panic() {
+        let counter = spawn(Counter::default());
+        counter.tell(CounterMsg::Crash).unwrap();
+        counter.join();
+        assert!(!counter.is_alive());
+        assert_eq!(counter.restarts(), 0);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_and_is_capped() {
+        let policy = RestartPolicy {
+            max_restarts: 10,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        };
+        let delays: Vec<u128> = (1..=5).map(|n| policy.backoff(n).as_millis()).collect();
+        assert_eq!(delays, vec![10, 20, 40, 50, 50]);
+    }
+
+    #[test]
+    fn restarts_wait_for_backoff() {
+        let policy = RestartPolicy {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_secs(1),
+        };
+        let counter = spawn_supervised(Counter::default, policy);
+        let start = Instant::now();
+        for _ in 0..3 {
+            counter.tell(CounterMsg::Crash).unwrap();
+        }
+        assert_eq!(counter.ask(CounterMsg::Get), Ok(0));
+        // 20ms + 40ms + 80ms of backoff before the final instance answered
+        assert!(start.elapsed() >= Duration::from_millis(140));
+        assert_eq!(counter.restarts(), 3);
+    }
+}
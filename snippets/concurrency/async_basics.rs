@@ -0,0 +1,339 @@
+//! Async/Await Fundamentals in Rust (with Tokio)
+//!
+//! An `async fn` returns a future: a value that describes work but does nothing until an
+//! executor polls it. Tokio is the most widely used executor. It runs many futures on a
+//! small number of threads, switching between them whenever one is waiting on I/O or a
+//! timer.
+//!
+//! This file demonstrates:
+//! - Spawning tasks with `tokio::spawn` and awaiting their `JoinHandle`s
+//! - Running futures concurrently with `join!` and racing them with `select!`
+//! - Bounding an operation with `tokio::time::timeout`
+//! - Cooperative cancellation through a `CancellationToken`-style flag
+//! - Graceful shutdown of a task group: signal, wait for a grace period, then abort
+//!
+//! Built with the crate's `tokio` feature (on by default). The tests use tokio's
+//! `test-util` feature for paused time.
+
+use demo::{Demo, ThreadOutput};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Instant};
+
+// ========== Spawning Tasks ==========
+
+/// Simulate an I/O-bound call that takes `millis` milliseconds
+pub async fn fetch(id: u64, millis: u64) -> u64 {
+    sleep(Duration::from_millis(millis)).await;
+    id * 10
+}
+
+/// Spawn one task per id and collect the results in spawn order
+pub async fn spawn_all(ids: Vec<u64>) -> Vec<u64> {
+    let handles: Vec<JoinHandle<u64>> = ids
+        .into_iter()
+        // Higher ids finish first; the results still come back in spawn order
+        .map(|id| tokio::spawn(fetch(id, 100 - id.min(99))))
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.expect("task panicked"));
+    }
+    results
+}
+
+// ========== join! and select! ==========
+
+/// Run two fetches concurrently: total time is the max, not the sum
+pub async fn fetch_both() -> (u64, u64) {
+    tokio::join!(fetch(1, 200), fetch(2, 300))
+}
+
+/// Which branch of a race won
+#[derive(Debug, PartialEq, Eq)]
+pub enum Winner {
+    Primary(u64),
+    Fallback(u64),
+}
+
+/// Race a primary source against a fallback; the losing future is dropped (cancelled)
+pub async fn race(primary_millis: u64, fallback_millis: u64) -> Winner {
+    tokio::select! {
+        value = fetch(1, primary_millis) => Winner::Primary(value),
+        value = fetch(2, fallback_millis) => Winner::Fallback(value),
+    }
+}
+
+// ========== Timeouts ==========
+
+/// Give up on a fetch that takes longer than `limit`
+pub async fn fetch_with_timeout(millis: u64, limit: Duration) -> Result<u64, String> {
+    timeout(limit, fetch(7, millis))
+        .await
+        .map_err(|_| format!("fetch did not finish within {:?}", limit))
+}
+
+// ========== Cancellation ==========
+
+/// Minimal cancellation token: a flag plus a `Notify` to wake waiters
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenInner>,
+}
+
+#[derive(Default)]
+struct TokenInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation and wake every task waiting in `cancelled()`
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolve once `cancel()` has been called
+    pub async fn cancelled(&self) {
+        loop {
+            // Register interest before checking the flag so a concurrent cancel
+            // between the check and the await cannot be missed
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Loop doing one unit of work per tick until cancelled; returns the units completed
+pub async fn cancellable_worker(token: CancellationToken, tick: Duration) -> u32 {
+    let mut completed = 0;
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return completed,
+            _ = sleep(tick) => completed += 1,
+        }
+    }
+}
+
+// ========== Graceful Shutdown ==========
+
+/// Outcome of shutting down a task group
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Tasks that noticed the signal and exited within the grace period
+    pub completed: usize,
+    /// Tasks that ignored the signal and had to be aborted
+    pub aborted: usize,
+}
+
+/// A set of tasks that share one cancellation token
+#[derive(Default)]
+pub struct TaskGroup {
+    token: CancellationToken,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl TaskGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn a task that receives a clone of the group's token
+    pub fn spawn<F, Fut>(&mut self, make_task: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.push(tokio::spawn(make_task(self.token.clone())));
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Signal every task, wait up to `grace` for all of them, then abort stragglers
+    pub async fn shutdown(self, grace: Duration) -> ShutdownReport {
+        self.token.cancel();
+        let deadline = Instant::now() + grace;
+        let mut report = ShutdownReport::default();
+
+        for mut task in self.tasks {
+            match tokio::time::timeout_at(deadline, &mut task).await {
+                Ok(_) => report.completed += 1,
+                Err(_) => {
+                    task.abort();
+                    report.aborted += 1;
+                }
+            }
+        }
+        report
+    }
+}
+
+// ========== Demo Code ==========
+
+async fn run_async(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "===== Spawning Tasks =====")?;
+    writeln!(out, "Results in spawn order: {:?}", spawn_all(vec![1, 2, 3, 4]).await)?;
+
+    writeln!(out, "\n===== join! =====")?;
+    let start = Instant::now();
+    let both = fetch_both().await;
+    writeln!(out, "Got {:?} in {:?} (sequential would take ~500ms)", both, start.elapsed())?;
+
+    writeln!(out, "\n===== select! =====")?;
+    writeln!(out, "Fast primary: {:?}", race(50, 200).await)?;
+    writeln!(out, "Slow primary: {:?}", race(300, 100).await)?;
+
+    writeln!(out, "\n===== Timeouts =====")?;
+    writeln!(out, "Quick fetch: {:?}", fetch_with_timeout(50, Duration::from_millis(100)).await)?;
+    writeln!(out, "Slow fetch:  {:?}", fetch_with_timeout(500, Duration::from_millis(100)).await)?;
+
+    writeln!(out, "\n===== Cancellation =====")?;
+    let token = CancellationToken::new();
+    let worker = tokio::spawn(cancellable_worker(token.clone(), Duration::from_millis(20)));
+    sleep(Duration::from_millis(110)).await;
+    token.cancel();
+    writeln!(out, "Worker completed {} ticks before cancellation", worker.await.unwrap())?;
+
+    writeln!(out, "\n===== Graceful Shutdown =====")?;
+    let mut group = TaskGroup::new();
+    let output = ThreadOutput::new();
+    for id in 0..3 {
+        let printer = output.printer();
+        group.spawn(move |token| async move {
+            token.cancelled().await;
+            printer.println(format!("task {} cleaning up", id));
+        });
+    }
+    group.spawn(|_token| async move {
+        // Ignores the token entirely, so it will be aborted
+        sleep(Duration::from_secs(3600)).await;
+    });
+    let report = group.shutdown(Duration::from_millis(200)).await;
+    output.drain_into(out)?;
+    writeln!(out, "Shutdown report: {:?}", report)?;
+    Ok(())
+}
+
+/// Registry entry for this module's demo; `run` drives it on a fresh tokio runtime.
+pub struct AsyncBasicsDemo;
+
+impl Demo for AsyncBasicsDemo {
+    fn name(&self) -> &'static str {
+        "async_basics"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "tokio tasks, select!, timeouts and graceful shutdown"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        tokio::runtime::Runtime::new()?.block_on(run_async(out))
+    }
+}
+
+/// The demo against stdout, for callers that already run inside a tokio runtime.
+pub async fn run_demo() {
+    run_async(&mut io::stdout()).await.expect("failed to write demo output");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn spawned_results_keep_spawn_order() {
+        assert_eq!(spawn_all(vec![5, 1, 9, 3]).await, vec![50, 10, 90, 30]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn join_runs_futures_concurrently() {
+        let start = Instant::now();
+        assert_eq!(fetch_both().await, (10, 20));
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(300));
+        assert!(elapsed < Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn select_returns_first_finisher() {
+        assert_eq!(race(10, 100).await, Winner::Primary(10));
+        assert_eq!(race(100, 10).await, Winner::Fallback(20));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timeout_distinguishes_fast_and_slow() {
+        assert_eq!(fetch_with_timeout(10, Duration::from_millis(50)).await, Ok(70));
+        assert!(fetch_with_timeout(100, Duration::from_millis(50)).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancellation_stops_worker() {
+        let token = CancellationToken::new();
+        let worker = tokio::spawn(cancellable_worker(token.clone(), Duration::from_millis(10)));
+        sleep(Duration::from_millis(55)).await;
+        token.cancel();
+        assert_eq!(worker.await.unwrap(), 5);
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn cancelled_resolves_immediately_after_cancel() {
+        let token = CancellationToken::new();
+        token.cancel();
+        timeout(Duration::from_secs(1), token.cancelled())
+            .await
+            .expect("already-cancelled token should resolve at once");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_aborts_tasks_that_ignore_the_signal() {
+        let mut group = TaskGroup::new();
+        for _ in 0..3 {
+            group.spawn(|token| async move { token.cancelled().await });
+        }
+        group.spawn(|_| async move { sleep(Duration::from_secs(60)).await });
+        assert_eq!(group.len(), 4);
+
+        let report = group.shutdown(Duration::from_millis(100)).await;
+        assert_eq!(report, ShutdownReport { completed: 3, aborted: 1 });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_waits_for_cleanup_within_grace_period() {
+        let mut group = TaskGroup::new();
+        group.spawn(|token| async move {
+            token.cancelled().await;
+            // Cleanup that takes less than the grace period
+            sleep(Duration::from_millis(50)).await;
+        });
+        let report = group.shutdown(Duration::from_millis(100)).await;
+        assert_eq!(report, ShutdownReport { completed: 1, aborted: 0 });
+    }
+}
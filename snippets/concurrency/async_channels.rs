@@ -0,0 +1,370 @@
+//! Async Channels in Tokio: mpsc, oneshot, watch and broadcast
+//!
+//! Tokio ships four channel flavors, each suited to a different communication shape:
+//!
+//! - `mpsc`: many producers, one consumer, bounded so fast producers feel backpressure
+//! - `oneshot`: exactly one value, typically the reply to a request
+//! - `watch`: a single "latest value" cell; receivers skip intermediate updates
+//! - `broadcast`: every receiver sees every value, unless it falls too far behind, in
+//!   which case it is told how many messages it missed (`RecvError::Lagged`)
+//!
+//! This file demonstrates them together in a small metrics hub:
+//!
+//! ```text
+//! sources --mpsc--> hub task --broadcast--> consumers (dashboard, slow alerting)
+//!                      |  \--watch--> latest summary for status displays
+//!                      \<--oneshot-- on-demand snapshot requests
+//! ```
+//!
+//! Built with the crate's `tokio` feature (on by default). The tests use tokio's
+//! `test-util` feature for paused time.
+
+use demo::{Demo, ThreadOutput};
+use std::io::{self, Write};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+// ========== Messages ==========
+
+/// One measurement emitted by a source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    pub source: usize,
+    pub seq: u64,
+    pub value: f64,
+}
+
+/// Running totals over every metric the hub has seen
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Summary {
+    pub count: u64,
+    pub sum: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    fn record(&mut self, metric: &Metric) {
+        self.count += 1;
+        self.sum += metric.value;
+        self.max = if self.count == 1 { metric.value } else { self.max.max(metric.value) };
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+}
+
+/// Requests sent to the hub over its mpsc channel
+#[derive(Debug)]
+pub enum Command {
+    Record(Metric),
+    /// Ask for the current summary; the hub answers on the enclosed oneshot
+    Snapshot(oneshot::Sender<Summary>),
+}
+
+// ========== The Hub ==========
+
+/// Owns the aggregation task and the channel ends used to talk to it
+pub struct MetricsHub {
+    commands: mpsc::Sender<Command>,
+    fanout: broadcast::Sender<Metric>,
+    latest: watch::Receiver<Summary>,
+    task: JoinHandle<Summary>,
+}
+
+impl MetricsHub {
+    /// `command_capacity` bounds the mpsc queue (backpressure on sources);
+    /// `broadcast_capacity` is how far a consumer may fall behind before it lags
+    pub fn spawn(command_capacity: usize, broadcast_capacity: usize) -> Self {
+        let (commands, mut inbox) = mpsc::channel(command_capacity);
+        let (fanout, _) = broadcast::channel(broadcast_capacity);
+        let (latest_tx, latest) = watch::channel(Summary::default());
+
+        let forward = fanout.clone();
+        let task = tokio::spawn(async move {
+            let mut summary = Summary::default();
+            // Ends once every mpsc sender has been dropped
+            while let Some(command) = inbox.recv().await {
+                match command {
+                    Command::Record(metric) => {
+                        summary.record(&metric);
+                        // An error only means nobody is subscribed right now
+                        let _ = forward.send(metric);
+                        latest_tx.send_replace(summary.clone());
+                    }
+                    Command::Snapshot(reply) => {
+                        // The requester may have given up waiting; that's fine
+                        let _ = reply.send(summary.clone());
+                    }
+                }
+            }
+            summary
+        });
+
+        MetricsHub { commands, fanout, latest, task }
+    }
+
+    /// A handle sources can clone to send metrics
+    pub fn recorder(&self) -> mpsc::Sender<Command> {
+        self.commands.clone()
+    }
+
+    /// Receive every metric from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Metric> {
+        self.fanout.subscribe()
+    }
+
+    /// Observe the most recent summary
+    pub fn watch(&self) -> watch::Receiver<Summary> {
+        self.latest.clone()
+    }
+
+    /// Round-trip a request through the hub. Because it travels on the same mpsc queue,
+    /// the answer includes every metric this handle's sender queued before it.
+    pub async fn snapshot(&self) -> Option<Summary> {
+        let (reply, response) = oneshot::channel();
+        self.commands.send(Command::Snapshot(reply)).await.ok()?;
+        response.await.ok()
+    }
+
+    /// Close the hub's own channel ends and wait for the task to drain. The task only
+    /// finishes once every recorder handed out has been dropped as well.
+    pub async fn shutdown(self) -> Summary {
+        let MetricsHub { commands, fanout, task, .. } = self;
+        drop(commands);
+        drop(fanout);
+        task.await.expect("hub task panicked")
+    }
+}
+
+// ========== Producers ==========
+
+/// Emit `count` metrics from one source, pausing `interval` between them
+pub async fn run_source(
+    source: usize,
+    count: u64,
+    interval: Duration,
+    recorder: mpsc::Sender<Command>,
+) {
+    for seq in 0..count {
+        let value = ((source as u64 + 1) * 10 + seq % 7) as f64;
+        // `send` waits while the queue is full: this is the backpressure
+        if recorder.send(Command::Record(Metric { source, seq, value })).await.is_err() {
+            return;
+        }
+        sleep(interval).await;
+    }
+}
+
+// ========== Consumers ==========
+
+/// What one broadcast subscriber ended up seeing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerReport {
+    pub name: &'static str,
+    pub received: u64,
+    /// Messages overwritten before this consumer got to them
+    pub skipped: u64,
+}
+
+/// Drain a broadcast receiver, spending `work` on each message. A consumer that is
+/// too slow gets `Lagged(n)`, records the gap and carries on from the oldest message
+/// still buffered instead of failing.
+pub async fn consume(
+    name: &'static str,
+    mut metrics: broadcast::Receiver<Metric>,
+    work: Duration,
+) -> ConsumerReport {
+    let mut report = ConsumerReport { name, received: 0, skipped: 0 };
+    loop {
+        match metrics.recv().await {
+            Ok(_metric) => {
+                report.received += 1;
+                if !work.is_zero() {
+                    sleep(work).await;
+                }
+            }
+            Err(RecvError::Lagged(missed)) => report.skipped += missed,
+            Err(RecvError::Closed) => return report,
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+async fn run_async(out: &mut dyn Write) -> io::Result<()> {
+    let hub = MetricsHub::spawn(16, 8);
+
+    writeln!(out, "===== Metrics hub: 3 sources x 20 metrics, 2 subscribers =====")?;
+    let dashboard = tokio::spawn(consume("dashboard", hub.subscribe(), Duration::ZERO));
+    let alerting = tokio::spawn(consume("alerting", hub.subscribe(), Duration::from_millis(25)));
+
+    let mut status = hub.watch();
+    let output = ThreadOutput::new();
+    let printer = output.printer();
+    let status_task = tokio::spawn(async move {
+        let mut updates_seen = 0;
+        // Sleeping between reads means several updates collapse into one
+        while status.changed().await.is_ok() {
+            updates_seen += 1;
+            let summary = status.borrow_and_update().clone();
+            printer.println(format!("  watch status: {} metrics, mean {:.1}", summary.count, summary.mean()));
+            sleep(Duration::from_millis(40)).await;
+        }
+        updates_seen
+    });
+
+    let sources: Vec<_> = (0..3)
+        .map(|source| tokio::spawn(run_source(source, 20, Duration::from_millis(5), hub.recorder())))
+        .collect();
+
+    sleep(Duration::from_millis(50)).await;
+    output.drain_into(out)?;
+    writeln!(out, "  oneshot snapshot: {:?}", hub.snapshot().await)?;
+
+    for source in sources {
+        source.await.unwrap();
+    }
+    let summary = hub.shutdown().await;
+    let redraws = status_task.await.unwrap();
+    output.drain_into(out)?;
+    writeln!(out, "\nFinal summary: {:?} (mean {:.2})", summary, summary.mean())?;
+    writeln!(out, "Status line redrew {} times for {} updates", redraws, summary.count)?;
+    writeln!(out, "{:?}", dashboard.await.unwrap())?;
+    writeln!(out, "{:?}", alerting.await.unwrap())?;
+    Ok(())
+}
+
+/// Registry entry for this module's demo; `run` drives it on a fresh tokio runtime.
+pub struct AsyncChannelsDemo;
+
+impl Demo for AsyncChannelsDemo {
+    fn name(&self) -> &'static str {
+        "async_channels"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "mpsc, broadcast, watch and oneshot around a metrics hub"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        tokio::runtime::Runtime::new()?.block_on(run_async(out))
+    }
+}
+
+/// The demo against stdout, for callers that already run inside a tokio runtime.
+pub async fn run_demo() {
+    run_async(&mut io::stdout()).await.expect("failed to write demo output");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(seq: u64, value: f64) -> Command {
+        Command::Record(Metric { source: 0, seq, value })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn mpsc_delivers_every_metric_in_per_source_order() {
+        let (tx, mut rx) = mpsc::channel(2);
+        for source in 0..3 {
+            tokio::spawn(run_source(source, 10, Duration::from_millis(1), tx.clone()));
+        }
+        drop(tx);
+
+        let mut next_seq = [0u64; 3];
+        let mut total = 0;
+        while let Some(Command::Record(m)) = rx.recv().await {
+            assert_eq!(m.seq, next_seq[m.source], "source {} out of order", m.source);
+            next_seq[m.source] += 1;
+            total += 1;
+        }
+        assert_eq!(total, 30);
+    }
+
+    #[tokio::test]
+    async fn bounded_mpsc_applies_backpressure() {
+        let (tx, _rx) = mpsc::channel(2);
+        assert!(tx.try_send(metric(0, 1.0)).is_ok());
+        assert!(tx.try_send(metric(1, 1.0)).is_ok());
+        assert!(matches!(tx.try_send(metric(2, 1.0)), Err(mpsc::error::TrySendError::Full(_))));
+    }
+
+    #[tokio::test]
+    async fn oneshot_snapshot_sees_prior_records() {
+        let hub = MetricsHub::spawn(8, 8);
+        let recorder = hub.recorder();
+        for (seq, value) in [(0, 2.0), (1, 8.0), (2, 5.0)] {
+            recorder.send(metric(seq, value)).await.unwrap();
+        }
+        drop(recorder);
+
+        // The snapshot queues behind the records, so it includes all three
+        let snapshot = hub.snapshot().await.unwrap();
+        assert_eq!(snapshot, Summary { count: 3, sum: 15.0, max: 8.0 });
+        assert_eq!(snapshot.mean(), 5.0);
+        assert_eq!(hub.shutdown().await, snapshot);
+    }
+
+    #[tokio::test]
+    async fn dropped_oneshot_sender_is_reported() {
+        let (reply, response) = oneshot::channel::<Summary>();
+        drop(reply);
+        assert!(response.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn watch_keeps_only_the_latest_value() {
+        let hub = MetricsHub::spawn(8, 8);
+        let mut latest = hub.watch();
+        assert_eq!(latest.borrow().count, 0);
+
+        let recorder = hub.recorder();
+        for seq in 0..5 {
+            recorder.send(metric(seq, 1.0)).await.unwrap();
+        }
+        drop(recorder);
+        let final_summary = hub.shutdown().await;
+
+        // Five updates happened, but the receiver observes one change with the last value
+        assert_eq!(*latest.borrow_and_update(), final_summary);
+        assert!(latest.changed().await.is_err(), "sender dropped with the hub task");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn broadcast_delivers_everything_to_a_consumer_that_keeps_up() {
+        let hub = MetricsHub::spawn(4, 64);
+        let consumer = tokio::spawn(consume("fast", hub.subscribe(), Duration::ZERO));
+        run_source(0, 40, Duration::from_millis(1), hub.recorder()).await;
+        hub.shutdown().await;
+
+        let report = consumer.await.unwrap();
+        assert_eq!(report, ConsumerReport { name: "fast", received: 40, skipped: 0 });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_broadcast_consumer_lags_but_accounts_for_every_message() {
+        let hub = MetricsHub::spawn(4, 4);
+        let slow = tokio::spawn(consume("slow", hub.subscribe(), Duration::from_millis(50)));
+        let fast = tokio::spawn(consume("fast", hub.subscribe(), Duration::ZERO));
+        run_source(0, 40, Duration::from_millis(1), hub.recorder()).await;
+        hub.shutdown().await;
+
+        let slow = slow.await.unwrap();
+        assert!(slow.skipped > 0, "slow consumer should have lagged: {:?}", slow);
+        assert_eq!(slow.received + slow.skipped, 40);
+        assert_eq!(fast.await.unwrap().received, 40);
+    }
+}
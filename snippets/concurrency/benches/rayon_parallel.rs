@@ -0,0 +1,42 @@
+//! Criterion benchmark pairing every recipe in `rayon_parallel.rs` with its sequential
+//! baseline
+//!
+//! Run with `cargo bench -p concurrency --bench rayon_parallel` (needs the `rayon` feature).
+
+use concurrency::rayon_parallel::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn bench_map_reduce(c: &mut Criterion) {
+    let data: Vec<u64> = (0..1_000_000).collect();
+    let mut group = c.benchmark_group("sum_of_squares");
+    group.bench_function("sequential", |b| b.iter(|| sum_of_squares_sequential(&data)));
+    group.bench_function("parallel", |b| b.iter(|| sum_of_squares_parallel(&data)));
+    group.finish();
+}
+
+fn bench_word_count(c: &mut Criterion) {
+    let lines = sample_lines(20_000);
+    let mut group = c.benchmark_group("word_count");
+    group.bench_function("sequential", |b| b.iter(|| word_count_sequential(&lines)));
+    group.bench_function("parallel", |b| b.iter(|| word_count_parallel(&lines)));
+    group.finish();
+}
+
+fn bench_matmul(c: &mut Criterion) {
+    let a = Matrix::from_fn(128, 128, |r, c| (r + c) as f64);
+    let b = Matrix::from_fn(128, 128, |r, c| (r * c % 5) as f64);
+    let mut group = c.benchmark_group("matmul_128");
+    group.bench_function("sequential", |bench| bench.iter(|| matmul_sequential(&a, &b)));
+    group.bench_function("parallel", |bench| bench.iter(|| matmul_parallel(&a, &b)));
+    group.finish();
+}
+
+fn bench_collatz(c: &mut Criterion) {
+    let mut group = c.benchmark_group("longest_collatz_100k");
+    group.bench_function("sequential", |b| b.iter(|| longest_collatz_sequential(100_000)));
+    group.bench_function("parallel", |b| b.iter(|| longest_collatz_parallel(100_000)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_map_reduce, bench_word_count, bench_matmul, bench_collatz);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+//! Criterion benchmark for the shared-state aggregators in `sync_primitives.rs`
+//!
+//! Every implementation runs the same workload at several reader/writer ratios so the
+//! cost of locking, cache-line contention and shard merging shows up in the numbers.
+//!
+//! Run with `cargo bench -p concurrency --bench sync_primitives`.
+
+use concurrency::sync_primitives::{all_aggregators, run_workload};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const THREADS: usize = 4;
+const OPS_PER_THREAD: u64 = 10_000;
+
+fn bench_read_ratios(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stats_aggregator");
+    group.throughput(Throughput::Elements(THREADS as u64 * OPS_PER_THREAD));
+
+    for read_percent in [0u64, 50, 90, 99] {
+        for aggregator in all_aggregators() {
+            group.bench_with_input(
+                BenchmarkId::new(aggregator.name(), format!("{}%-reads", read_percent)),
+                &read_percent,
+                |b, &read_percent| {
+                    b.iter(|| run_workload(aggregator.as_ref(), THREADS, OPS_PER_THREAD, read_percent))
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_read_ratios);
+criterion_main!(benches);
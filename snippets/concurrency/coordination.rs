@@ -0,0 +1,461 @@
+//! Thread Coordination: Barrier, Condvar and a Countdown Latch
+//!
+//! Locks protect data; coordination primitives make threads wait for each other. This file
+//! builds three common patterns on top of the standard library:
+//!
+//! - `Barrier`: every worker finishes phase N before anyone starts phase N+1. Used here for
+//!   a double-buffered 1D heat-diffusion simulation.
+//! - `Condvar`: a bounded blocking queue where producers wait while it is full and
+//!   consumers wait while it is empty. Every wait sits in a loop (`wait_while`) so spurious
+//!   wakeups and stolen items are handled correctly.
+//! - `CountDownLatch`: a one-shot gate (like Java's) that opens once N events have happened.
+//!
+//! The classic bug with condition variables is a missed notification: checking the
+//! condition, then waiting, while another thread changes it in between. Holding the mutex
+//! across the check and the wait is what rules that out.
+
+use demo::{Demo, ThreadOutput};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Barrier, Condvar, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+// ========== Barrier: Phased Computation ==========
+
+/// Run `workers` threads through `phases` phases and record `(phase, worker)` in the order
+/// the work happened. The barrier guarantees the log is grouped by phase.
+pub fn run_phases(workers: usize, phases: usize) -> Vec<(usize, usize)> {
+    let barrier = Barrier::new(workers);
+    let log = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for worker in 0..workers {
+            let (barrier, log) = (&barrier, &log);
+            scope.spawn(move || {
+                for phase in 0..phases {
+                    log.lock().unwrap().push((phase, worker));
+                    barrier.wait();
+                }
+            });
+        }
+    });
+
+    log.into_inner().unwrap()
+}
+
+/// One explicit diffusion step: each interior cell moves towards the mean of its neighbours.
+/// The ends are fixed boundary conditions.
+fn diffuse_cell(current: &[f64], i: usize) -> f64 {
+    if i == 0 || i == current.len() - 1 {
+        current[i]
+    } else {
+        current[i] + 0.25 * (current[i - 1] - 2.0 * current[i] + current[i + 1])
+    }
+}
+
+pub fn diffuse_sequential(mut cells: Vec<f64>, steps: usize) -> Vec<f64> {
+    for _ in 0..steps {
+        cells = (0..cells.len()).map(|i| diffuse_cell(&cells, i)).collect();
+    }
+    cells
+}
+
+/// Parallel diffusion. Each worker owns a contiguous range of cells; per step it reads the
+/// current buffer and writes its range of the next one. Two barrier waits per step:
+/// the first ensures every range is written, then the leader swaps the buffers, and the
+/// second ensures nobody reads the new current buffer before the swap.
+pub fn diffuse_parallel(cells: Vec<f64>, steps: usize, workers: usize) -> Vec<f64> {
+    let len = cells.len();
+    let workers = workers.clamp(1, len.max(1));
+    let chunk = len.div_ceil(workers);
+    let current = RwLock::new(cells);
+    let next = Mutex::new(vec![0.0; len]);
+    let barrier = Barrier::new(workers);
+
+    thread::scope(|scope| {
+        for worker in 0..workers {
+            let (current, next, barrier) = (&current, &next, &barrier);
+            let range = (worker * chunk).min(len)..((worker + 1) * chunk).min(len);
+            scope.spawn(move || {
+                for _ in 0..steps {
+                    let computed: Vec<f64> = {
+                        let current = current.read().unwrap();
+                        range.clone().map(|i| diffuse_cell(&current, i)).collect()
+                    };
+                    next.lock().unwrap()[range.clone()].copy_from_slice(&computed);
+
+                    if barrier.wait().is_leader() {
+                        std::mem::swap(&mut *current.write().unwrap(), &mut *next.lock().unwrap());
+                    }
+                    barrier.wait();
+                }
+            });
+        }
+    });
+
+    current.into_inner().unwrap()
+}
+
+// ========== Condvar: Bounded Blocking Queue ==========
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+/// A fixed-capacity FIFO queue that blocks producers when full and consumers when empty
+pub struct BoundedQueue<T> {
+    state: Mutex<QueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "queue capacity must be at least 1");
+        BoundedQueue {
+            state: Mutex::new(QueueState { items: VecDeque::with_capacity(capacity), closed: false }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Block until there is room, then enqueue. Gives the item back if the queue is closed.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let guard = self.state.lock().unwrap();
+        let mut state = self
+            .not_full
+            .wait_while(guard, |s| !s.closed && s.items.len() == self.capacity)
+            .unwrap();
+        if state.closed {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Enqueue without blocking; gives the item back if the queue is full or closed
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed || state.items.len() == self.capacity {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Block until an item is available. Returns `None` once the queue is closed and drained.
+    pub fn pop(&self) -> Option<T> {
+        let guard = self.state.lock().unwrap();
+        let mut state = self.not_empty.wait_while(guard, |s| !s.closed && s.items.is_empty()).unwrap();
+        let item = state.items.pop_front();
+        drop(state);
+        if item.is_some() {
+            self.not_full.notify_one();
+        }
+        item
+    }
+
+    /// Like `pop`, but gives up after `timeout`
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let guard = self.state.lock().unwrap();
+        let (mut state, _) = self
+            .not_empty
+            .wait_timeout_while(guard, timeout, |s| !s.closed && s.items.is_empty())
+            .unwrap();
+        let item = state.items.pop_front();
+        drop(state);
+        if item.is_some() {
+            self.not_full.notify_one();
+        }
+        item
+    }
+
+    /// Reject further pushes and wake every blocked thread. Items already queued can
+    /// still be popped.
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+// ========== CountDownLatch ==========
+
+/// A gate that opens once `count_down` has been called `count` times. It cannot be reset.
+pub struct CountDownLatch {
+    remaining: Mutex<usize>,
+    released: Condvar,
+}
+
+impl CountDownLatch {
+    pub fn new(count: usize) -> Self {
+        CountDownLatch { remaining: Mutex::new(count), released: Condvar::new() }
+    }
+
+    /// Decrement the count, releasing every waiter when it reaches zero. Extra calls are ignored.
+    pub fn count_down(&self) {
+        let mut remaining = self.remaining.lock().unwrap();
+        if *remaining > 0 {
+            *remaining -= 1;
+            if *remaining == 0 {
+                self.released.notify_all();
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        *self.remaining.lock().unwrap()
+    }
+
+    /// Block until the count reaches zero (returns immediately if it already has)
+    pub fn wait(&self) {
+        let guard = self.remaining.lock().unwrap();
+        let _guard = self.released.wait_while(guard, |remaining| *remaining > 0).unwrap();
+    }
+
+    /// Block for at most `timeout`; returns true if the latch opened
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let guard = self.remaining.lock().unwrap();
+        let (remaining, _) = self
+            .released
+            .wait_timeout_while(guard, timeout, |remaining| *remaining > 0)
+            .unwrap();
+        *remaining == 0
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct CoordinationDemo;
+
+impl Demo for CoordinationDemo {
+    fn name(&self) -> &'static str {
+        "coordination"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Barrier phases, Condvar bounded queue and a countdown latch"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Barrier: phases =====")?;
+        let log = run_phases(3, 3);
+        for phase in 0..3 {
+            let workers: Vec<usize> = log.iter().filter(|(p, _)| *p == phase).map(|(_, w)| *w).collect();
+            writeln!(out, "phase {}: workers finished in order {:?}", phase, workers)?;
+        }
+
+        writeln!(out, "\n===== Barrier: heat diffusion =====")?;
+        let mut rod = vec![0.0; 12];
+        rod[0] = 100.0;
+        let result = diffuse_parallel(rod.clone(), 50, 4);
+        let formatted: Vec<String> = result.iter().map(|t| format!("{:.1}", t)).collect();
+        writeln!(out, "after 50 steps: [{}]", formatted.join(", "))?;
+        writeln!(out, "matches sequential: {}", result == diffuse_sequential(rod, 50))?;
+
+        writeln!(out, "\n===== Condvar: bounded queue =====")?;
+        let queue = BoundedQueue::new(2);
+        let output = ThreadOutput::new();
+        thread::scope(|scope| {
+            let (queue, printer) = (&queue, output.printer());
+            scope.spawn(move || {
+                for i in 1..=5 {
+                    queue.push(i).unwrap();
+                    printer.println(format!("produced {} (queue len {})", i, queue.len()));
+                }
+                queue.close();
+            });
+            let printer = output.printer();
+            scope.spawn(move || {
+                while let Some(item) = queue.pop() {
+                    printer.println(format!("  consumed {}", item));
+                    thread::sleep(Duration::from_millis(20));
+                }
+                printer.println("  queue closed and drained");
+            });
+        });
+        output.drain_into(out)?;
+
+        writeln!(out, "\n===== CountDownLatch =====")?;
+        let latch = CountDownLatch::new(3);
+        thread::scope(|scope| {
+            for service in ["database", "cache", "config"] {
+                let (latch, printer) = (&latch, output.printer());
+                scope.spawn(move || {
+                    thread::sleep(Duration::from_millis(30));
+                    printer.println(format!("{} ready", service));
+                    latch.count_down();
+                });
+            }
+            latch.wait();
+            output.printer().println("all services ready, starting server");
+        });
+        output.drain_into(out)
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&CoordinationDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn barrier_keeps_phases_in_lockstep() {
+        let (workers, phases) = (4, 5);
+        let log = run_phases(workers, phases);
+        assert_eq!(log.len(), workers * phases);
+        // No entry for phase N+1 may appear before the last entry for phase N
+        for (i, &(phase, _)) in log.iter().enumerate() {
+            assert_eq!(phase, i / workers, "log out of phase order: {:?}", log);
+        }
+    }
+
+    #[test]
+    fn parallel_diffusion_matches_sequential() {
+        let mut rod = vec![0.0; 37];
+        rod[0] = 100.0;
+        rod[36] = 50.0;
+        let expected = diffuse_sequential(rod.clone(), 40);
+        for workers in [1, 3, 8] {
+            assert_eq!(diffuse_parallel(rod.clone(), 40, workers), expected);
+        }
+    }
+
+    #[test]
+    fn queue_is_fifo_and_respects_capacity() {
+        let queue = BoundedQueue::new(2);
+        assert!(queue.try_push(1).is_ok());
+        assert!(queue.try_push(2).is_ok());
+        assert_eq!(queue.try_push(3), Err(3));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        assert!(queue.is_empty());
+        assert_eq!(queue.capacity(), 2);
+    }
+
+    #[test]
+    fn no_items_lost_with_many_producers_and_consumers() {
+        let queue = BoundedQueue::new(1);
+        let consumed = AtomicUsize::new(0);
+        let sum = AtomicUsize::new(0);
+        let done = CountDownLatch::new(4);
+
+        thread::scope(|scope| {
+            for p in 0..4 {
+                let (queue, done) = (&queue, &done);
+                scope.spawn(move || {
+                    for i in 0..500 {
+                        queue.push(p * 500 + i).unwrap();
+                    }
+                    done.count_down();
+                });
+            }
+            for _ in 0..4 {
+                let (queue, consumed, sum) = (&queue, &consumed, &sum);
+                scope.spawn(move || {
+                    while let Some(item) = queue.pop() {
+                        consumed.fetch_add(1, Ordering::Relaxed);
+                        sum.fetch_add(item, Ordering::Relaxed);
+                    }
+                });
+            }
+            done.wait();
+            queue.close();
+        });
+
+        assert_eq!(consumed.load(Ordering::Relaxed), 2_000);
+        assert_eq!(sum.load(Ordering::Relaxed), (0..2_000).sum::<usize>());
+    }
+
+    #[test]
+    fn close_wakes_blocked_consumers_and_producers() {
+        let empty: BoundedQueue<u32> = BoundedQueue::new(1);
+        let full = BoundedQueue::new(1);
+        full.push(0).unwrap();
+
+        thread::scope(|scope| {
+            let consumer = scope.spawn(|| empty.pop());
+            let producer = scope.spawn(|| full.push(1));
+            thread::sleep(Duration::from_millis(50));
+            empty.close();
+            full.close();
+            assert_eq!(consumer.join().unwrap(), None);
+            assert_eq!(producer.join().unwrap(), Err(1));
+        });
+        // Items queued before close are still delivered
+        assert_eq!(full.pop(), Some(0));
+        assert_eq!(full.pop(), None);
+    }
+
+    #[test]
+    fn pop_timeout_gives_up_on_empty_queue() {
+        let queue: BoundedQueue<u8> = BoundedQueue::new(4);
+        assert_eq!(queue.pop_timeout(Duration::from_millis(20)), None);
+        queue.push(7).unwrap();
+        assert_eq!(queue.pop_timeout(Duration::from_millis(20)), Some(7));
+    }
+
+    #[test]
+    fn latch_releases_every_waiter() {
+        let latch = CountDownLatch::new(3);
+        let released = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..5 {
+                let (latch, released) = (&latch, &released);
+                scope.spawn(move || {
+                    latch.wait();
+                    released.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(released.load(Ordering::SeqCst), 0);
+            for _ in 0..3 {
+                latch.count_down();
+            }
+        });
+
+        assert_eq!(released.load(Ordering::SeqCst), 5);
+        assert_eq!(latch.count(), 0);
+    }
+
+    #[test]
+    fn latch_counted_down_before_wait_does_not_block() {
+        let latch = CountDownLatch::new(1);
+        latch.count_down();
+        latch.count_down();
+        assert_eq!(latch.count(), 0);
+        latch.wait();
+        assert!(latch.wait_timeout(Duration::ZERO));
+        assert!(!CountDownLatch::new(1).wait_timeout(Duration::from_millis(10)));
+    }
+}
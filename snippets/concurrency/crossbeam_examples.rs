@@ -0,0 +1,470 @@
+//! Crossbeam Showcase: Channels with select!, Epoch GC and AtomicCell
+//!
+//! Crossbeam fills gaps in `std::sync` with tools for lock-free and message-passing code:
+//!
+//! - `crossbeam::channel`: MPMC channels (std's mpsc is single-consumer) plus `select!`
+//!   to wait on several channels and timers at once
+//! - `crossbeam::epoch`: epoch-based memory reclamation. A node removed from a lock-free
+//!   structure is only freed once no thread can still be reading it, which is the problem
+//!   `lock_free.rs` solves by hand with hazard pointers.
+//! - `crossbeam::atomic::AtomicCell`: an atomic wrapper around any `Copy` type, lock-free
+//!   when the type fits in a native atomic and falling back to a striped lock otherwise
+//!
+//! Built with the crate's `crossbeam` feature (on by default).
+
+use crossbeam::atomic::AtomicCell;
+use crossbeam::channel::{self, after, select, Receiver, RecvTimeoutError};
+use crossbeam::epoch::{self, Atomic, Owned};
+use demo::Demo;
+use std::io::{self, Write};
+use std::mem::ManuallyDrop;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+// ========== Channels and select! ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    High,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Shutdown,
+    Idle,
+    Disconnected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchLog {
+    pub handled: Vec<(Priority, u32)>,
+    pub stopped: StopReason,
+}
+
+/// Handle jobs from two queues until told to stop, the queues go quiet for `idle`,
+/// or every sender is gone.
+///
+/// `select!` picks fairly among ready channels, so high-priority work is drained with
+/// `try_recv` first and `select!` is only used to sleep until something arrives.
+pub fn dispatch(
+    high: &Receiver<u32>,
+    low: &Receiver<u32>,
+    shutdown: &Receiver<()>,
+    idle: Duration,
+) -> DispatchLog {
+    let mut handled = Vec::new();
+    let mut high_open = true;
+    let mut low_open = true;
+
+    let stopped = loop {
+        if shutdown.try_recv().is_ok() {
+            break StopReason::Shutdown;
+        }
+        if let Ok(job) = high.try_recv() {
+            handled.push((Priority::High, job));
+            continue;
+        }
+        if !high_open && !low_open {
+            break StopReason::Disconnected;
+        }
+
+        // A disconnected receiver is always "ready"; swap it for one that never fires
+        let never = channel::never();
+        let high_rx = if high_open { high } else { &never };
+        let low_rx = if low_open { low } else { &never };
+        select! {
+            recv(shutdown) -> _ => break StopReason::Shutdown,
+            recv(high_rx) -> msg => match msg {
+                Ok(job) => handled.push((Priority::High, job)),
+                Err(_) => high_open = false,
+            },
+            recv(low_rx) -> msg => match msg {
+                Ok(job) => handled.push((Priority::Low, job)),
+                Err(_) => low_open = false,
+            },
+            recv(after(idle)) -> _ => break StopReason::Idle,
+        }
+    };
+
+    DispatchLog { handled, stopped }
+}
+
+/// Multiple consumers on one channel: something `std::sync::mpsc` can't do
+pub fn mpmc_sum(jobs: u64, workers: usize) -> (u64, Vec<usize>) {
+    let (tx, rx) = channel::bounded::<u64>(16);
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let rx = rx.clone();
+                scope.spawn(move || {
+                    let mut sum = 0;
+                    let mut count = 0;
+                    loop {
+                        match rx.recv_timeout(Duration::from_secs(5)) {
+                            Ok(job) => {
+                                sum += job;
+                                count += 1;
+                            }
+                            Err(RecvTimeoutError::Disconnected) => break,
+                            Err(RecvTimeoutError::Timeout) => panic!("producer stalled"),
+                        }
+                    }
+                    (sum, count)
+                })
+            })
+            .collect();
+
+        for job in 1..=jobs {
+            tx.send(job).unwrap();
+        }
+        drop(tx);
+
+        let results: Vec<(u64, usize)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        (results.iter().map(|r| r.0).sum(), results.iter().map(|r| r.1).collect())
+    })
+}
+
+// ========== Epoch-Based Reclamation ==========
+
+struct Node<T> {
+    value: ManuallyDrop<T>,
+    next: Atomic<Node<T>>,
+}
+
+/// Treiber stack whose popped nodes are freed by crossbeam's epoch collector
+pub struct EpochStack<T> {
+    head: Atomic<Node<T>>,
+}
+
+impl<T> Default for EpochStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> EpochStack<T> {
+    pub fn new() -> Self {
+        EpochStack { head: Atomic::null() }
+    }
+
+    pub fn push(&self, value: T) {
+        let mut node = Owned::new(Node { value: ManuallyDrop::new(value), next: Atomic::null() });
+        // Pinning the thread marks it as active in the current epoch
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Relaxed, &guard);
+            node.next.store(head, Ordering::Relaxed);
+            match self.head.compare_exchange(head, node, Ordering::Release, Ordering::Relaxed, &guard) {
+                Ok(_) => return,
+                Err(failed) => node = failed.new,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            // Safety: `head` was loaded under `guard`, so it cannot be freed until the
+            // guard is dropped
+            let node = unsafe { head.as_ref() }?;
+            let next = node.next.load(Ordering::Relaxed, &guard);
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::Relaxed, Ordering::Relaxed, &guard)
+                .is_ok()
+            {
+                unsafe {
+                    // Safety: we unlinked the node, so we are the only thread taking its
+                    // value. Other threads may still read `next`, which is why freeing
+                    // is deferred until every pinned thread has moved on.
+                    let value = ManuallyDrop::into_inner(std::ptr::read(&node.value));
+                    guard.defer_destroy(head);
+                    return Some(value);
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        self.head.load(Ordering::Acquire, &guard).is_null()
+    }
+}
+
+impl<T> Drop for EpochStack<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+// ========== AtomicCell ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+/// Runtime-tunable settings that any thread can read or flip without a lock
+pub struct RuntimeConfig {
+    pub verbose: AtomicCell<bool>,
+    pub level: AtomicCell<LogLevel>,
+    pub max_connections: AtomicCell<u32>,
+    maintenance: AtomicCell<bool>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        RuntimeConfig {
+            verbose: AtomicCell::new(false),
+            level: AtomicCell::new(LogLevel::Info),
+            max_connections: AtomicCell::new(100),
+            maintenance: AtomicCell::new(false),
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Enter maintenance mode. Returns false if another thread already did.
+    pub fn begin_maintenance(&self) -> bool {
+        self.maintenance.compare_exchange(false, true).is_ok()
+    }
+
+    pub fn end_maintenance(&self) {
+        self.maintenance.store(false);
+    }
+
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance.load()
+    }
+
+    pub fn should_log(&self, level: LogLevel) -> bool {
+        level as u8 <= self.level.load() as u8
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct CrossbeamExamplesDemo;
+
+impl Demo for CrossbeamExamplesDemo {
+    fn name(&self) -> &'static str {
+        "crossbeam_examples"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "select!, epoch-reclaimed stack and AtomicCell config"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== select! with priorities =====")?;
+        let (high_tx, high) = channel::unbounded();
+        let (low_tx, low) = channel::unbounded();
+        let (_shutdown_tx, shutdown) = channel::bounded(1);
+        for job in 0..3 {
+            low_tx.send(job).unwrap();
+            high_tx.send(100 + job).unwrap();
+        }
+        let log = dispatch(&high, &low, &shutdown, Duration::from_millis(50));
+        writeln!(out, "handled {:?}", log.handled)?;
+        writeln!(out, "stopped because: {:?}", log.stopped)?;
+
+        writeln!(out, "\n===== MPMC: 4 workers share one channel =====")?;
+        let (sum, per_worker) = mpmc_sum(10_000, 4);
+        writeln!(out, "sum = {}, jobs per worker = {:?}", sum, per_worker)?;
+
+        writeln!(out, "\n===== Epoch-reclaimed stack =====")?;
+        let stack = EpochStack::new();
+        thread::scope(|scope| {
+            for t in 0..4 {
+                let stack = &stack;
+                scope.spawn(move || (0..1_000).for_each(|i| stack.push(t * 1_000 + i)));
+            }
+        });
+        let mut popped = 0;
+        while stack.pop().is_some() {
+            popped += 1;
+        }
+        writeln!(out, "pushed 4000 from 4 threads, popped {}", popped)?;
+
+        writeln!(out, "\n===== AtomicCell config =====")?;
+        let config = RuntimeConfig::default();
+        writeln!(out, 
+                 "lock-free: bool={}, LogLevel={}, u32={}",
+                 AtomicCell::<bool>::is_lock_free(),
+                 AtomicCell::<LogLevel>::is_lock_free(),
+                 AtomicCell::<u32>::is_lock_free()
+    )?;
+        writeln!(out, "debug logging enabled: {}", config.should_log(LogLevel::Debug))?;
+        config.level.store(LogLevel::Debug);
+        writeln!(out, "after raising level:   {}", config.should_log(LogLevel::Debug))?;
+        writeln!(out, "first maintenance request wins: {}", config.begin_maintenance())?;
+        writeln!(out, "second one is refused:         {}", !config.begin_maintenance())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&CrossbeamExamplesDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn dispatch_drains_high_priority_first() {
+        let (high_tx, high) = channel::unbounded();
+        let (low_tx, low) = channel::unbounded();
+        let (_shutdown_tx, shutdown) = channel::bounded::<()>(1);
+        for job in 0..3 {
+            low_tx.send(job).unwrap();
+            high_tx.send(10 + job).unwrap();
+        }
+        drop((high_tx, low_tx));
+
+        let log = dispatch(&high, &low, &shutdown, Duration::from_secs(5));
+        let priorities: Vec<Priority> = log.handled.iter().map(|(p, _)| *p).collect();
+        assert_eq!(&priorities[..3], &[Priority::High; 3]);
+        assert_eq!(log.handled.len(), 6);
+        assert_eq!(log.stopped, StopReason::Disconnected);
+    }
+
+    #[test]
+    fn dispatch_stops_when_idle() {
+        let (_high_tx, high) = channel::unbounded::<u32>();
+        let (_low_tx, low) = channel::unbounded::<u32>();
+        let (_shutdown_tx, shutdown) = channel::bounded::<()>(1);
+        let log = dispatch(&high, &low, &shutdown, Duration::from_millis(20));
+        assert_eq!(log, DispatchLog { handled: vec![], stopped: StopReason::Idle });
+    }
+
+    #[test]
+    fn dispatch_honours_shutdown_signal() {
+        let (_high_tx, high) = channel::unbounded::<u32>();
+        let (low_tx, low) = channel::unbounded();
+        let (shutdown_tx, shutdown) = channel::bounded(1);
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                low_tx.send(1).unwrap();
+                thread::sleep(Duration::from_millis(20));
+                shutdown_tx.send(()).unwrap();
+            });
+            let log = dispatch(&high, &low, &shutdown, Duration::from_secs(5));
+            assert_eq!(log.handled, vec![(Priority::Low, 1)]);
+            assert_eq!(log.stopped, StopReason::Shutdown);
+        });
+    }
+
+    #[test]
+    fn mpmc_delivers_each_job_exactly_once() {
+        let (sum, per_worker) = mpmc_sum(5_000, 3);
+        assert_eq!(sum, 5_000 * 5_001 / 2);
+        assert_eq!(per_worker.iter().sum::<usize>(), 5_000);
+    }
+
+    #[test]
+    fn epoch_stack_is_lifo() {
+        let stack = EpochStack::new();
+        assert!(stack.is_empty());
+        for i in 0..5 {
+            stack.push(i);
+        }
+        assert_eq!((0..5).map(|_| stack.pop().unwrap()).collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn epoch_stack_concurrent_push_pop_loses_nothing() {
+        let stack = EpochStack::new();
+        let popped = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for t in 0..4 {
+                let (stack, popped) = (&stack, &popped);
+                scope.spawn(move || {
+                    for i in 0..2_000 {
+                        stack.push(t * 2_000 + i);
+                        if i % 2 == 0 && stack.pop().is_some() {
+                            popped.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        let mut remaining = 0;
+        while stack.pop().is_some() {
+            remaining += 1;
+        }
+        assert_eq!(popped.load(Ordering::Relaxed) + remaining, 8_000);
+    }
+
+    #[test]
+    fn epoch_stack_drops_remaining_values() {
+        struct Counted<'a>(&'a AtomicUsize);
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        let stack = EpochStack::new();
+        for _ in 0..10 {
+            stack.push(Counted(&drops));
+        }
+        drop(stack.pop());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        drop(stack);
+        // Values are dropped eagerly on pop; only node memory is deferred
+        assert_eq!(drops.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn atomic_cell_config_updates_are_visible_across_threads() {
+        let config = RuntimeConfig::default();
+        assert!(!config.should_log(LogLevel::Debug));
+        assert!(config.should_log(LogLevel::Error));
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                config.verbose.store(true);
+                config.level.store(LogLevel::Debug);
+                config.max_connections.fetch_add(50);
+            });
+        });
+
+        assert!(config.verbose.load());
+        assert!(config.should_log(LogLevel::Debug));
+        assert_eq!(config.max_connections.load(), 150);
+    }
+
+    #[test]
+    fn only_one_thread_enters_maintenance() {
+        let config = RuntimeConfig::default();
+        let winners = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    if config.begin_maintenance() {
+                        winners.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        assert_eq!(winners.load(Ordering::Relaxed), 1);
+        assert!(config.in_maintenance());
+        config.end_maintenance();
+        assert!(!config.in_maintenance());
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::actor_runtime::run_demo();
+}
@@ -0,0 +1,4 @@
+#[tokio::main]
+async fn main() {
+    concurrency::async_basics::run_demo().await;
+}
@@ -0,0 +1,4 @@
+#[tokio::main]
+async fn main() {
+    concurrency::async_channels::run_demo().await;
+}
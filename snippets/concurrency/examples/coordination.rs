@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::coordination::run_demo();
+}
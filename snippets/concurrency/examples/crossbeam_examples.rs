@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::crossbeam_examples::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::futures_from_scratch::run_demo();
+}
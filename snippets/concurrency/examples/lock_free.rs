@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::lock_free::run_demo();
+}
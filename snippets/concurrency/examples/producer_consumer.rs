@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::producer_consumer::run_demo();
+}
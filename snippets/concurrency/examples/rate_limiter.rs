@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::rate_limiter::run_demo();
+}
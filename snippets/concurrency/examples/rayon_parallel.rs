@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::rayon_parallel::run_demo();
+}
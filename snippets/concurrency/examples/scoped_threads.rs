@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::scoped_threads::run_demo();
+}
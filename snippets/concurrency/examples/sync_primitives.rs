@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::sync_primitives::run_demo();
+}
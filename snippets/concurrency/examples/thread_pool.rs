@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::thread_pool::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    concurrency::work_stealing::run_demo();
+}
@@ -0,0 +1,467 @@
+//! Futures from Scratch: Future, Waker and a Mini Executor
+//!
+//! `async`/`await` is sugar. An `async fn` compiles to a state machine implementing
+//! `Future`, and something has to call `poll` on it. That something is an executor, and
+//! the `Waker` is how a future tells the executor "poll me again, I can make progress now".
+//!
+//! This file rebuilds the pieces using only the standard library:
+//! - `Delay`: a leaf future that starts a timer thread and wakes its task when time is up
+//! - `block_on`: parks the current thread between polls instead of busy-looping
+//! - `join`: a combinator that polls two futures until both are done
+//! - `SleepTwice`: the state machine the compiler generates for two sequential `.await`s,
+//!   written out by hand
+//! - `Executor`: a single-threaded run queue where each task's waker re-schedules it
+//!
+//! The poll counts printed by the demo show that nothing spins: a future is polled once to
+//! start, then only again after its waker fires.
+
+use demo::Demo;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{self, Write};
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+// ========== A Leaf Future: Delay ==========
+
+struct DelayState {
+    completed: bool,
+    waker: Option<Waker>,
+}
+
+/// Completes once `duration` has elapsed since the first poll
+pub struct Delay {
+    duration: Duration,
+    state: Option<Arc<Mutex<DelayState>>>,
+}
+
+impl Delay {
+    pub fn new(duration: Duration) -> Self {
+        Delay { duration, state: None }
+    }
+}
+
+/// `async fn`-style constructor
+pub fn sleep(duration: Duration) -> Delay {
+    Delay::new(duration)
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // Futures are lazy: the timer only starts on the first poll
+        let duration = self.duration;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new(Mutex::new(DelayState { completed: false, waker: None }));
+            let timer_state = Arc::clone(&state);
+            thread::spawn(move || {
+                thread::sleep(duration);
+                let mut state = timer_state.lock().unwrap();
+                state.completed = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+            state
+        });
+
+        let mut state = state.lock().unwrap();
+        if state.completed {
+            return Poll::Ready(());
+        }
+        // Store the *latest* waker: the future may have moved to a different task since
+        // the previous poll
+        match &state.waker {
+            Some(existing) if existing.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+// ========== block_on: a Thread-Parking Executor ==========
+
+/// Wakes the thread running `block_on`. The flag covers a wake that arrives before the
+/// thread parks, which would otherwise be lost.
+struct ThreadWaker {
+    thread: Thread,
+    notified: AtomicBool,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.notified.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+/// Run a future to completion on the current thread, returning its output and how many
+/// times it was polled
+pub fn block_on_counting<F: Future>(future: F) -> (F::Output, usize) {
+    let mut future = pin!(future);
+    let signal = Arc::new(ThreadWaker { thread: thread::current(), notified: AtomicBool::new(false) });
+    let waker = Waker::from(Arc::clone(&signal));
+    let mut cx = Context::from_waker(&waker);
+    let mut polls = 0;
+
+    loop {
+        polls += 1;
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return (output, polls);
+        }
+        // `park` can return spuriously, so wait until a wake actually happened
+        while !signal.notified.swap(false, Ordering::Acquire) {
+            thread::park();
+        }
+    }
+}
+
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    block_on_counting(future).0
+}
+
+// ========== Combinator: join ==========
+
+enum MaybeDone<F: Future> {
+    Pending(Pin<Box<F>>),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Poll if still running; returns true once an output is stored
+    fn poll(&mut self, cx: &mut Context<'_>) -> bool {
+        if let MaybeDone::Pending(future) = self {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(output) => *self = MaybeDone::Done(output),
+                Poll::Pending => return false,
+            }
+        }
+        true
+    }
+
+    fn take(&mut self) -> F::Output {
+        match std::mem::replace(self, MaybeDone::Taken) {
+            MaybeDone::Done(output) => output,
+            _ => panic!("output taken before the future completed"),
+        }
+    }
+}
+
+/// Polls both futures on every wake-up and completes when both have
+pub struct Join<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+}
+
+// The inner futures are boxed, so moving a `Join` never moves them
+impl<A: Future, B: Future> Unpin for Join<A, B> {}
+
+pub fn join<A: Future, B: Future>(a: A, b: B) -> Join<A, B> {
+    Join { a: MaybeDone::Pending(Box::pin(a)), b: MaybeDone::Pending(Box::pin(b)) }
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // Poll both every time: either may be the one whose waker fired
+        let a_done = this.a.poll(cx);
+        let b_done = this.b.poll(cx);
+        if a_done && b_done {
+            Poll::Ready((this.a.take(), this.b.take()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// ========== What async Desugars To ==========
+
+/// Hand-written equivalent of:
+///
+/// ```ignore
+/// async fn sleep_twice(first: Duration, second: Duration) -> u32 {
+///     sleep(first).await;
+///     sleep(second).await;
+///     2
+/// }
+/// ```
+///
+/// Each `.await` becomes a state holding the future being awaited.
+pub enum SleepTwice {
+    Start { first: Duration, second: Duration },
+    AwaitingFirst { delay: Delay, second: Duration },
+    AwaitingSecond { delay: Delay },
+    Done,
+}
+
+impl SleepTwice {
+    pub fn new(first: Duration, second: Duration) -> Self {
+        SleepTwice::Start { first, second }
+    }
+}
+
+impl Future for SleepTwice {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        let this = self.get_mut();
+        loop {
+            match this {
+                SleepTwice::Start { first, second } => {
+                    *this = SleepTwice::AwaitingFirst { delay: sleep(*first), second: *second };
+                }
+                SleepTwice::AwaitingFirst { delay, second } => {
+                    if Pin::new(delay).poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    *this = SleepTwice::AwaitingSecond { delay: sleep(*second) };
+                }
+                SleepTwice::AwaitingSecond { delay } => {
+                    if Pin::new(delay).poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    *this = SleepTwice::Done;
+                    return Poll::Ready(2);
+                }
+                SleepTwice::Done => panic!("SleepTwice polled after completion"),
+            }
+        }
+    }
+}
+
+// ========== A Run-Queue Executor ==========
+
+type BoxedTask = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A spawned task. Waking it pushes it back onto the executor's queue.
+struct Task {
+    future: Mutex<Option<BoxedTask>>,
+    queue: Sender<Arc<Task>>,
+    polls: AtomicUsize,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        // Fails only after the executor is gone, when there is nobody left to run it
+        let _ = self.queue.clone().send(self);
+    }
+}
+
+/// Runs spawned tasks on the calling thread. Blocking on the channel's `recv` parks the
+/// thread whenever every task is waiting.
+pub struct Executor {
+    queue: Sender<Arc<Task>>,
+    ready: std::sync::mpsc::Receiver<Arc<Task>>,
+    spawned: Vec<Arc<Task>>,
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Executor {
+    pub fn new() -> Self {
+        let (queue, ready) = channel();
+        Executor { queue, ready, spawned: Vec::new() }
+    }
+
+    pub fn spawn(&mut self, future: impl Future<Output = ()> + Send + 'static) {
+        let task = Arc::new(Task {
+            future: Mutex::new(Some(Box::pin(future))),
+            queue: self.queue.clone(),
+            polls: AtomicUsize::new(0),
+        });
+        self.spawned.push(Arc::clone(&task));
+        self.queue.send(task).expect("executor queue is open");
+    }
+
+    /// Run until every spawned task has completed. Returns the poll count of each task in
+    /// spawn order.
+    pub fn run(self) -> Vec<usize> {
+        let mut remaining = self.spawned.len();
+        let mut backlog = VecDeque::new();
+        while remaining > 0 {
+            let task = match backlog.pop_front() {
+                Some(task) => task,
+                None => self.ready.recv().expect("a pending task holds a sender"),
+            };
+            let mut slot = task.future.lock().unwrap();
+            // A task can be queued again after it finished; skip those stale wake-ups
+            let Some(mut future) = slot.take() else { continue };
+            task.polls.fetch_add(1, Ordering::Relaxed);
+            let waker = Waker::from(Arc::clone(&task));
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(()) => remaining -= 1,
+                Poll::Pending => *slot = Some(future),
+            }
+            drop(slot);
+            // Drain anything that became ready while polling before blocking again
+            backlog.extend(self.ready.try_iter());
+        }
+        self.spawned.iter().map(|t| t.polls.load(Ordering::Relaxed)).collect()
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FuturesFromScratchDemo;
+
+impl Demo for FuturesFromScratchDemo {
+    fn name(&self) -> &'static str {
+        "futures_from_scratch"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Hand-rolled Future, Waker, block_on and executor"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Delay + block_on =====")?;
+        let start = Instant::now();
+        let ((), polls) = block_on_counting(sleep(Duration::from_millis(100)));
+        writeln!(out, "slept {:?} with only {} polls", start.elapsed(), polls)?;
+
+        writeln!(out, "\n===== join =====")?;
+        let start = Instant::now();
+        let (a, b) = block_on(join(
+            async {
+                sleep(Duration::from_millis(150)).await;
+                "left"
+            },
+            async {
+                sleep(Duration::from_millis(100)).await;
+                "right"
+            },
+        ));
+        writeln!(out, "joined ({}, {}) in {:?} (sequential would be ~250ms)", a, b, start.elapsed())?;
+
+        writeln!(out, "\n===== Hand-written state machine =====")?;
+        let start = Instant::now();
+        let (value, polls) = block_on_counting(SleepTwice::new(Duration::from_millis(50), Duration::from_millis(50)));
+        writeln!(out, "SleepTwice returned {} after {:?} and {} polls", value, start.elapsed(), polls)?;
+
+        writeln!(out, "\n===== Executor with a run queue =====")?;
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = Executor::new();
+        for (name, millis) in [("slow", 120), ("fast", 40), ("medium", 80)] {
+            let finished = Arc::clone(&finished);
+            executor.spawn(async move {
+                sleep(Duration::from_millis(millis)).await;
+                finished.lock().unwrap().push(name);
+            });
+        }
+        let polls = executor.run();
+        writeln!(out, "finish order: {:?}, polls per task: {:?}", finished.lock().unwrap(), polls)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FuturesFromScratchDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready_future_is_polled_once() {
+        assert_eq!(block_on_counting(async { 7 }), (7, 1));
+    }
+
+    #[test]
+    fn delay_waits_without_busy_polling() {
+        let start = Instant::now();
+        let ((), polls) = block_on_counting(sleep(Duration::from_millis(30)));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        // One poll to start the timer, one after the wake (plus slack for spurious unparks)
+        assert!((2..=3).contains(&polls), "polled {} times", polls);
+    }
+
+    #[test]
+    fn async_blocks_compose_with_hand_written_futures() {
+        let result = block_on(async {
+            sleep(Duration::from_millis(5)).await;
+            let inner = async { 20 }.await;
+            inner + 1
+        });
+        assert_eq!(result, 21);
+    }
+
+    #[test]
+    fn join_returns_outputs_in_argument_order() {
+        let (a, b) = block_on(join(
+            async {
+                sleep(Duration::from_millis(20)).await;
+                1
+            },
+            async { "ready immediately" },
+        ));
+        assert_eq!((a, b), (1, "ready immediately"));
+    }
+
+    #[test]
+    fn join_runs_futures_concurrently() {
+        let start = Instant::now();
+        block_on(join(sleep(Duration::from_millis(80)), sleep(Duration::from_millis(80))));
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(80));
+        assert!(elapsed < Duration::from_millis(150), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn hand_written_state_machine_matches_async_version() {
+        async fn sleep_twice(first: Duration, second: Duration) -> u32 {
+            sleep(first).await;
+            sleep(second).await;
+            2
+        }
+        let (d1, d2) = (Duration::from_millis(5), Duration::from_millis(5));
+        assert_eq!(block_on(SleepTwice::new(d1, d2)), block_on(sleep_twice(d1, d2)));
+    }
+
+    #[test]
+    fn executor_completes_tasks_in_wake_order() {
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = Executor::new();
+        for (id, millis) in [(0, 60), (1, 10), (2, 35), (3, 0)] {
+            let finished = Arc::clone(&finished);
+            executor.spawn(async move {
+                if millis > 0 {
+                    sleep(Duration::from_millis(millis)).await;
+                }
+                finished.lock().unwrap().push(id);
+            });
+        }
+        let polls = executor.run();
+        assert_eq!(*finished.lock().unwrap(), vec![3, 1, 2, 0]);
+        // The task without an await finishes on its first poll
+        assert_eq!(polls[3], 1);
+        assert!(polls.iter().all(|&p| p <= 3), "unexpected polls: {:?}", polls);
+    }
+
+    #[test]
+    fn executor_with_no_tasks_returns_immediately() {
+        assert!(Executor::new().run().is_empty());
+    }
+}
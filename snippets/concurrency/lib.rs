@@ -0,0 +1,50 @@
+//! Concurrency snippets from the tech notes.
+//!
+//! The std-only modules are always built; the ones that lean on an external
+//! crate sit behind a feature of the same name (all on by default). Every module's
+//! `Demo` implementation is registered in `DEMOS`; the gated ones only when their
+//! feature is on.
+
+pub mod actor_runtime;
+pub mod coordination;
+pub mod futures_from_scratch;
+pub mod lock_free;
+pub mod producer_consumer;
+pub mod rate_limiter;
+pub mod scoped_threads;
+pub mod sync_primitives;
+pub mod thread_pool;
+pub mod work_stealing;
+
+#[cfg(feature = "tokio")]
+pub mod async_basics;
+#[cfg(feature = "tokio")]
+pub mod async_channels;
+#[cfg(feature = "crossbeam")]
+pub mod crossbeam_examples;
+#[cfg(feature = "rayon")]
+pub mod rayon_parallel;
+
+use demo::Demo;
+
+/// Every demo in this crate, for `tech-notes` and the other front ends.
+pub static DEMOS: &[&dyn Demo] = &[
+    &actor_runtime::ActorRuntimeDemo,
+    #[cfg(feature = "tokio")]
+    &async_basics::AsyncBasicsDemo,
+    #[cfg(feature = "tokio")]
+    &async_channels::AsyncChannelsDemo,
+    &coordination::CoordinationDemo,
+    #[cfg(feature = "crossbeam")]
+    &crossbeam_examples::CrossbeamExamplesDemo,
+    &futures_from_scratch::FuturesFromScratchDemo,
+    &lock_free::LockFreeDemo,
+    &producer_consumer::ProducerConsumerDemo,
+    &rate_limiter::RateLimiterDemo,
+    #[cfg(feature = "rayon")]
+    &rayon_parallel::RayonParallelDemo,
+    &scoped_threads::ScopedThreadsDemo,
+    &sync_primitives::SyncPrimitivesDemo,
+    &thread_pool::ThreadPoolDemo,
+    &work_stealing::WorkStealingDemo,
+];
@@ -0,0 +1,654 @@
+//! Lock-Free Data Structures in Rust: Treiber Stack and Michael-Scott Queue
+//!
+//! Lock-free structures replace locks with compare-and-swap (CAS) loops on atomic pointers:
+//! a thread reads the current state, prepares a change, and publishes it only if nobody
+//! else changed the state in the meantime. No thread ever blocks another.
+//!
+//! The hard part is memory reclamation. After a thread unlinks a node, another thread may
+//! still be reading it, so it cannot be freed immediately. This file uses a small
+//! "hazard pointer lite" scheme:
+//! - Before dereferencing a shared node, a thread publishes its address in a hazard slot
+//! - Unlinked nodes are retired instead of freed
+//! - Retired nodes are only freed once no hazard slot points at them
+//!
+//! Because a node cannot be freed (and its address reused) while protected, this also
+//! prevents the ABA problem that plagues naive CAS-based stacks.
+//!
+//! This file demonstrates:
+//! - A hazard pointer domain with per-thread records and a retire list
+//! - The Treiber stack (CAS on the head pointer)
+//! - The Michael-Scott queue (dummy head node, lagging tail that threads help advance)
+//! - Multi-threaded stress tests and drop counting to catch leaks and double frees
+//!
+//! This is an advanced note: production code should reach for crossbeam instead.
+
+use demo::Demo;
+use std::io::{self, Write};
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+// ========== Hazard Pointer Domain ==========
+
+/// Number of threads that can hold a hazard record at the same time
+const MAX_RECORDS: usize = 64;
+/// Hazard pointers per record (the MS queue needs two: head and head.next)
+const SLOTS_PER_RECORD: usize = 2;
+/// Scan the retire list once it grows past this many nodes
+const RETIRE_THRESHOLD: usize = 2 * MAX_RECORDS * SLOTS_PER_RECORD;
+
+struct HazardRecord {
+    active: AtomicBool,
+    slots: [AtomicPtr<()>; SLOTS_PER_RECORD],
+}
+
+/// A node that has been unlinked but may still be read by another thread
+struct Retired {
+    ptr: *mut (),
+    free: unsafe fn(*mut ()),
+}
+
+// Retired nodes are only touched under the domain's mutex
+unsafe impl Send for Retired {}
+
+pub struct HazardDomain {
+    records: Box<[HazardRecord]>,
+    retired: Mutex<Vec<Retired>>,
+}
+
+impl HazardDomain {
+    pub fn new() -> Self {
+        HazardDomain {
+            records: (0..MAX_RECORDS)
+                .map(|_| HazardRecord {
+                    active: AtomicBool::new(false),
+                    slots: [AtomicPtr::new(ptr::null_mut()), AtomicPtr::new(ptr::null_mut())],
+                })
+                .collect(),
+            retired: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Claim a free hazard record for the duration of one operation
+    fn acquire(&self) -> HazardGuard<'_> {
+        loop {
+            for record in self.records.iter() {
+                if record
+                    .active
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    return HazardGuard { record };
+                }
+            }
+            // Every record is busy: more threads than MAX_RECORDS are mid-operation
+            thread::yield_now();
+        }
+    }
+
+    /// Hand over an unlinked node; it is freed once no hazard pointer protects it
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must come from `Box::into_raw`, be unreachable from the data structure and
+    /// not be retired twice.
+    unsafe fn retire<T>(&self, ptr: *mut T) {
+        unsafe fn free_box<T>(ptr: *mut ()) {
+            drop(Box::from_raw(ptr as *mut T));
+        }
+
+        let mut retired = self.retired.lock().unwrap();
+        retired.push(Retired {
+            ptr: ptr as *mut (),
+            free: free_box::<T>,
+        });
+        if retired.len() >= RETIRE_THRESHOLD {
+            self.scan(&mut retired);
+        }
+    }
+
+    /// Free every retired node that no thread currently protects
+    fn scan(&self, retired: &mut Vec<Retired>) {
+        let protected: Vec<*mut ()> = self
+            .records
+            .iter()
+            .flat_map(|record| record.slots.iter().map(|slot| slot.load(Ordering::SeqCst)))
+            .filter(|p| !p.is_null())
+            .collect();
+
+        retired.retain(|node| {
+            if protected.contains(&node.ptr) {
+                true
+            } else {
+                unsafe { (node.free)(node.ptr) };
+                false
+            }
+        });
+    }
+
+    /// Number of nodes waiting to be freed
+    pub fn retired_count(&self) -> usize {
+        self.retired.lock().unwrap().len()
+    }
+}
+
+impl Default for HazardDomain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for HazardDomain {
+    fn drop(&mut self) {
+        // No operation can be in flight once the owning structure is being dropped
+        for node in self.retired.get_mut().unwrap().drain(..) {
+            unsafe { (node.free)(node.ptr) };
+        }
+    }
+}
+
+/// A claimed hazard record; released (and cleared) on drop
+struct HazardGuard<'a> {
+    record: &'a HazardRecord,
+}
+
+impl HazardGuard<'_> {
+    /// Load `src` and publish it in hazard slot `slot`, retrying until the published
+    /// value is still current. After this returns, the node cannot be freed.
+    fn protect<T>(&self, slot: usize, src: &AtomicPtr<T>) -> *mut T {
+        let mut ptr = src.load(Ordering::SeqCst);
+        loop {
+            self.record.slots[slot].store(ptr as *mut (), Ordering::SeqCst);
+            let current = src.load(Ordering::SeqCst);
+            if current == ptr {
+                return ptr;
+            }
+            ptr = current;
+        }
+    }
+}
+
+impl Drop for HazardGuard<'_> {
+    fn drop(&mut self) {
+        for slot in &self.record.slots {
+            slot.store(ptr::null_mut(), Ordering::SeqCst);
+        }
+        self.record.active.store(false, Ordering::Release);
+    }
+}
+
+// ========== Treiber Stack ==========
+
+struct StackNode<T> {
+    // Moved out by `pop`, so the node itself must not drop it again
+    value: ManuallyDrop<T>,
+    next: *mut StackNode<T>,
+}
+
+/// Lock-free LIFO stack: a single CAS on `head` publishes every push and pop
+pub struct TreiberStack<T> {
+    head: AtomicPtr<StackNode<T>>,
+    domain: HazardDomain,
+}
+
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> Self {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            domain: HazardDomain::new(),
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(StackNode {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            // The node is still private, so a plain write is fine
+            unsafe { (*node).next = head };
+            if self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = self.domain.acquire();
+        loop {
+            let head = guard.protect(0, &self.head);
+            if head.is_null() {
+                return None;
+            }
+
+            // Safe to read: `head` is protected and cannot be freed under us
+            let next = unsafe { (*head).next };
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                // We unlinked the node, so we alone own its value
+                let value = unsafe { ManuallyDrop::take(&mut (*head).value) };
+                drop(guard);
+                unsafe { self.domain.retire(head) };
+                return Some(value);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Nodes popped but not yet freed
+    pub fn pending_reclamation(&self) -> usize {
+        self.domain.retired_count()
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut current = *self.head.get_mut();
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ManuallyDrop::drop(&mut node.value) };
+            current = node.next;
+        }
+    }
+}
+
+// ========== Michael-Scott Queue ==========
+
+struct QueueNode<T> {
+    // Uninitialized in the dummy node; moved out by the dequeuer that unlinks its predecessor
+    value: MaybeUninit<T>,
+    next: AtomicPtr<QueueNode<T>>,
+}
+
+impl<T> QueueNode<T> {
+    fn new(value: MaybeUninit<T>) -> *mut Self {
+        Box::into_raw(Box::new(QueueNode {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+/// Lock-free FIFO queue. `head` always points at a dummy node whose successor holds the
+/// front value; `tail` may lag one node behind and any thread that notices helps move it.
+pub struct MsQueue<T> {
+    head: AtomicPtr<QueueNode<T>>,
+    tail: AtomicPtr<QueueNode<T>>,
+    domain: HazardDomain,
+}
+
+unsafe impl<T: Send> Send for MsQueue<T> {}
+unsafe impl<T: Send> Sync for MsQueue<T> {}
+
+impl<T> MsQueue<T> {
+    pub fn new() -> Self {
+        let dummy = QueueNode::new(MaybeUninit::uninit());
+        MsQueue {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+            domain: HazardDomain::new(),
+        }
+    }
+
+    pub fn enqueue(&self, value: T) {
+        let node = QueueNode::new(MaybeUninit::new(value));
+        let guard = self.domain.acquire();
+
+        loop {
+            let tail = guard.protect(0, &self.tail);
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+
+            if next.is_null() {
+                // Tail really is last: try to link the new node after it
+                let linked = unsafe {
+                    (*tail)
+                        .next
+                        .compare_exchange(next, node, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                };
+                if linked {
+                    // Swing tail forward; failure means another thread already helped
+                    let _ = self
+                        .tail
+                        .compare_exchange(tail, node, Ordering::AcqRel, Ordering::Relaxed);
+                    return;
+                }
+            } else {
+                // Tail is lagging: help advance it before retrying
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Ordering::AcqRel, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn dequeue(&self) -> Option<T> {
+        let guard = self.domain.acquire();
+
+        loop {
+            let head = guard.protect(0, &self.head);
+            let next = guard.protect(1, unsafe { &(*head).next });
+            // `next` is only known to be live if `head` was still the head after
+            // protecting it
+            if self.head.load(Ordering::SeqCst) != head {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+
+            let tail = self.tail.load(Ordering::Acquire);
+            if head == tail {
+                // Tail is lagging behind a completed link: help it along
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Ordering::AcqRel, Ordering::Relaxed);
+                continue;
+            }
+
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                // `next` is the new dummy; only the thread that won the CAS reads its value
+                let value = unsafe { ptr::read((*next).value.as_ptr()) };
+                drop(guard);
+                unsafe { self.domain.retire(head) };
+                return Some(value);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let head = self.head.load(Ordering::Acquire);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+
+    /// Nodes dequeued but not yet freed
+    pub fn pending_reclamation(&self) -> usize {
+        self.domain.retired_count()
+    }
+}
+
+impl<T> Default for MsQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MsQueue<T> {
+    fn drop(&mut self) {
+        // The dummy's value is uninitialized; every later node still owns its value
+        let dummy = unsafe { Box::from_raw(*self.head.get_mut()) };
+        let mut current = dummy.next.load(Ordering::Relaxed);
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { node.value.assume_init_drop() };
+            current = node.next.load(Ordering::Relaxed);
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct LockFreeDemo;
+
+impl Demo for LockFreeDemo {
+    fn name(&self) -> &'static str {
+        "lock_free"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Treiber stack and Michael-Scott queue with hazard pointers"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Treiber Stack =====")?;
+        let stack = TreiberStack::new();
+        for i in 1..=3 {
+            stack.push(i);
+        }
+        writeln!(out, "Popped in LIFO order: {:?} {:?} {:?}", stack.pop(), stack.pop(), stack.pop())?;
+        writeln!(out, "Empty now? {}", stack.is_empty())?;
+
+        writeln!(out, "\n===== Michael-Scott Queue =====")?;
+        let queue = MsQueue::new();
+        for word in ["first", "second", "third"] {
+            queue.enqueue(word);
+        }
+        writeln!(out, "Dequeued in FIFO order: {:?} {:?} {:?}", queue.dequeue(), queue.dequeue(), queue.dequeue())?;
+
+        writeln!(out, "\n===== Concurrent Stress =====")?;
+        let queue = MsQueue::new();
+        let per_thread = 50_000u64;
+        let total: u64 = thread::scope(|scope| {
+            for t in 0..4 {
+                let queue = &queue;
+                scope.spawn(move || {
+                    for i in 0..per_thread {
+                        queue.enqueue(t * per_thread + i);
+                    }
+                });
+            }
+            let consumers: Vec<_> = (0..4)
+                .map(|_| {
+                    let queue = &queue;
+                    scope.spawn(move || {
+                        let mut sum = 0;
+                        let mut taken = 0;
+                        while taken < per_thread {
+                            if let Some(v) = queue.dequeue() {
+                                sum += v;
+                                taken += 1;
+                            }
+                        }
+                        sum
+                    })
+                })
+                .collect();
+            consumers.into_iter().map(|c| c.join().unwrap()).sum()
+        });
+        let n = 4 * per_thread;
+        writeln!(out, "Sum of dequeued values: {} (expected {})", total, n * (n - 1) / 2)?;
+        writeln!(out, "Nodes awaiting reclamation: {}", queue.pending_reclamation())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&LockFreeDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    /// Counts drops so tests can detect leaks and double frees
+    struct Tracked {
+        value: u64,
+        drops: Arc<AtomicUsize>,
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.drops.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn stack_is_lifo() {
+        let stack = TreiberStack::new();
+        assert!(stack.is_empty());
+        for i in 0..5 {
+            stack.push(i);
+        }
+        let popped: Vec<_> = std::iter::from_fn(|| stack.pop()).collect();
+        assert_eq!(popped, vec![4, 3, 2, 1, 0]);
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn queue_is_fifo() {
+        let queue = MsQueue::new();
+        assert!(queue.is_empty());
+        for i in 0..5 {
+            queue.enqueue(i);
+        }
+        assert!(!queue.is_empty());
+        let dequeued: Vec<_> = std::iter::from_fn(|| queue.dequeue()).collect();
+        assert_eq!(dequeued, vec![0, 1, 2, 3, 4]);
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn stack_concurrent_push_pop_loses_nothing() {
+        let stack = TreiberStack::new();
+        let threads = 4u64;
+        let per_thread = 20_000u64;
+
+        let mut popped: Vec<u64> = thread::scope(|scope| {
+            for t in 0..threads {
+                let stack = &stack;
+                scope.spawn(move || {
+                    for i in 0..per_thread {
+                        stack.push(t * per_thread + i);
+                    }
+                });
+            }
+            let poppers: Vec<_> = (0..threads)
+                .map(|_| {
+                    let stack = &stack;
+                    scope.spawn(move || {
+                        let mut got = Vec::new();
+                        while (got.len() as u64) < per_thread {
+                            if let Some(v) = stack.pop() {
+                                got.push(v);
+                            }
+                        }
+                        got
+                    })
+                })
+                .collect();
+            poppers.into_iter().flat_map(|p| p.join().unwrap()).collect()
+        });
+
+        popped.sort_unstable();
+        assert_eq!(popped, (0..threads * per_thread).collect::<Vec<_>>());
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn queue_concurrent_producers_and_consumers_see_each_item_once() {
+        let queue = MsQueue::new();
+        let producers = 4u64;
+        let per_producer = 20_000u64;
+
+        let mut received: Vec<u64> = thread::scope(|scope| {
+            for p in 0..producers {
+                let queue = &queue;
+                scope.spawn(move || {
+                    for i in 0..per_producer {
+                        queue.enqueue(p * per_producer + i);
+                    }
+                });
+            }
+            let consumers: Vec<_> = (0..producers)
+                .map(|_| {
+                    let queue = &queue;
+                    scope.spawn(move || {
+                        let mut got = Vec::new();
+                        while (got.len() as u64) < per_producer {
+                            if let Some(v) = queue.dequeue() {
+                                got.push(v);
+                            }
+                        }
+                        // Per-producer FIFO order must survive concurrency
+                        for p in 0..producers {
+                            let mine: Vec<_> = got.iter().filter(|v| **v / per_producer == p).collect();
+                            assert!(mine.windows(2).all(|w| w[0] < w[1]));
+                        }
+                        got
+                    })
+                })
+                .collect();
+            consumers.into_iter().flat_map(|c| c.join().unwrap()).collect()
+        });
+
+        received.sort_unstable();
+        assert_eq!(received, (0..producers * per_producer).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn every_value_is_dropped_exactly_once() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let make = |value| Tracked { value, drops: Arc::clone(&drops) };
+
+        {
+            let stack = TreiberStack::new();
+            let queue = MsQueue::new();
+            for i in 0..1_000 {
+                stack.push(make(i));
+                queue.enqueue(make(i));
+            }
+            // Pop half (dropped here) and leave half for the structures' Drop impls
+            for _ in 0..500 {
+                assert!(stack.pop().is_some());
+                assert!(queue.dequeue().is_some());
+            }
+            assert_eq!(drops.load(Ordering::SeqCst), 1_000);
+        }
+        assert_eq!(drops.load(Ordering::SeqCst), 2_000);
+    }
+
+    #[test]
+    fn popped_values_are_intact() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let queue = MsQueue::new();
+        queue.enqueue(Tracked { value: 42, drops: Arc::clone(&drops) });
+        let item = queue.dequeue().unwrap();
+        assert_eq!(item.value, 42);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn retired_nodes_are_reclaimed_in_batches() {
+        let stack = TreiberStack::new();
+        for i in 0..(RETIRE_THRESHOLD * 3) {
+            stack.push(i);
+        }
+        while stack.pop().is_some() {}
+        // Scans run whenever the retire list reaches the threshold, so it stays bounded
+        assert!(stack.pending_reclamation() < RETIRE_THRESHOLD);
+    }
+}
@@ -0,0 +1,281 @@
+//! Producer-Consumer Pipelines in Rust
+//!
+//! The producer-consumer pattern decouples code that creates work from code that processes
+//! it by putting a queue between them. Rust's `std::sync::mpsc` channels are that queue.
+//! A bounded `sync_channel(n)` makes `send` block once `n` items are waiting, which is
+//! backpressure: a fast producer is slowed down to the pace of the slowest consumer
+//! instead of filling memory.
+//!
+//! This file demonstrates:
+//! - Single producer / single consumer over a bounded channel
+//! - Multi-producer fan-in, where cloned senders feed one consumer
+//! - A multi-stage pipeline (generate -> transform on N workers -> collect)
+//! - Measuring backpressure: items in flight stay pinned near the channel bound
+
+use demo::Demo;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// ========== Backpressure Tracking ==========
+
+/// Counts items sent but not yet received and remembers the peak
+#[derive(Default)]
+pub struct InFlightTracker {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl InFlightTracker {
+    fn sent(&self) {
+        let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+    }
+
+    fn received(&self) {
+        self.current.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Largest number of items that were ever queued at once
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+}
+
+// ========== Single Producer / Single Consumer ==========
+
+/// Send `items` through a bounded channel to a slow consumer and return what it received
+/// together with the peak number of in-flight items
+pub fn single_producer_single_consumer(
+    items: Vec<u64>,
+    capacity: usize,
+    consumer_delay: Duration,
+) -> (Vec<u64>, usize) {
+    let (tx, rx) = mpsc::sync_channel(capacity);
+    let tracker = Arc::new(InFlightTracker::default());
+
+    let producer_tracker = Arc::clone(&tracker);
+    let producer = thread::spawn(move || {
+        for item in items {
+            // Count before sending: once `send` returns the consumer may already have it
+            producer_tracker.sent();
+            // Blocks while `capacity` items are already buffered
+            tx.send(item).unwrap();
+        }
+        // `tx` is dropped here, which ends the consumer's loop
+    });
+
+    let consumer_tracker = Arc::clone(&tracker);
+    let consumer = thread::spawn(move || {
+        let mut received = Vec::new();
+        for item in rx {
+            consumer_tracker.received();
+            thread::sleep(consumer_delay);
+            received.push(item);
+        }
+        received
+    });
+
+    producer.join().unwrap();
+    let received = consumer.join().unwrap();
+    (received, tracker.peak())
+}
+
+// ========== Multi-Producer Fan-In ==========
+
+/// Spawn `producers` threads that each send `items_per_producer` tagged items into one
+/// bounded channel; the single consumer returns `(producer_id, item)` pairs
+pub fn fan_in(producers: usize, items_per_producer: u64, capacity: usize) -> Vec<(usize, u64)> {
+    let (tx, rx) = mpsc::sync_channel(capacity);
+
+    let handles: Vec<_> = (0..producers)
+        .map(|id| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for item in 0..items_per_producer {
+                    tx.send((id, item)).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    // Drop the original sender so the channel closes when the producers finish
+    drop(tx);
+
+    let consumer = thread::spawn(move || rx.into_iter().collect::<Vec<_>>());
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    consumer.join().unwrap()
+}
+
+// ========== Multi-Stage Pipeline ==========
+
+/// Output of the pipeline's transform stage
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Processed {
+    pub input: u64,
+    pub squared: u64,
+    pub worker: usize,
+}
+
+/// Three-stage pipeline: a generator feeds `workers` transform threads that share one
+/// receiver, and their output is collected by a final stage.
+/// Every channel is bounded by `capacity`, so a slow stage throttles the ones before it.
+pub fn run_pipeline(inputs: Vec<u64>, workers: usize, capacity: usize) -> Vec<Processed> {
+    let (raw_tx, raw_rx) = mpsc::sync_channel::<u64>(capacity);
+    let (out_tx, out_rx) = mpsc::sync_channel::<Processed>(capacity);
+
+    // Stage 1: generate
+    let generator = thread::spawn(move || {
+        for value in inputs {
+            raw_tx.send(value).unwrap();
+        }
+    });
+
+    // Stage 2: transform with several workers competing for the same receiver
+    let raw_rx = Arc::new(Mutex::new(raw_rx));
+    let transformers: Vec<_> = (0..workers)
+        .map(|worker| {
+            let raw_rx = Arc::clone(&raw_rx);
+            let out_tx: SyncSender<Processed> = out_tx.clone();
+            thread::spawn(move || loop {
+                let next = raw_rx.lock().unwrap().recv();
+                match next {
+                    Ok(input) => out_tx
+                        .send(Processed {
+                            input,
+                            squared: input * input,
+                            worker,
+                        })
+                        .unwrap(),
+                    Err(_) => break,
+                }
+            })
+        })
+        .collect();
+    drop(out_tx);
+
+    // Stage 3: collect
+    let collector = thread::spawn(move || collect_all(out_rx));
+
+    generator.join().unwrap();
+    for transformer in transformers {
+        transformer.join().unwrap();
+    }
+    collector.join().unwrap()
+}
+
+fn collect_all<T>(rx: Receiver<T>) -> Vec<T> {
+    rx.into_iter().collect()
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ProducerConsumerDemo;
+
+impl Demo for ProducerConsumerDemo {
+    fn name(&self) -> &'static str {
+        "producer_consumer"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "mpsc pipelines with bounded-channel backpressure"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Single Producer / Single Consumer =====")?;
+        let (received, peak) =
+            single_producer_single_consumer((1..=10).collect(), 2, Duration::from_millis(20));
+        writeln!(out, "Received: {:?}", received)?;
+        writeln!(out, "Peak items in flight with capacity 2: {}", peak)?;
+
+        writeln!(out, "\n===== Multi-Producer Fan-In =====")?;
+        let items = fan_in(3, 4, 2);
+        for (producer, item) in &items {
+            writeln!(out, "producer {} -> item {}", producer, item)?;
+        }
+        writeln!(out, "Total items: {}", items.len())?;
+
+        writeln!(out, "\n===== Multi-Stage Pipeline =====")?;
+        let mut results = run_pipeline((1..=12).collect(), 3, 4);
+        results.sort();
+        for result in &results {
+            writeln!(out, "{:>3}^2 = {:>4} (worker {})", result.input, result.squared, result.worker)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ProducerConsumerDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn spsc_preserves_order_and_processes_everything_once() {
+        let items: Vec<u64> = (0..200).collect();
+        let (received, _) = single_producer_single_consumer(items.clone(), 4, Duration::ZERO);
+        assert_eq!(received, items);
+    }
+
+    #[test]
+    fn bounded_channel_limits_items_in_flight() {
+        let capacity = 3;
+        let (received, peak) =
+            single_producer_single_consumer((0..30).collect(), capacity, Duration::from_millis(2));
+        assert_eq!(received.len(), 30);
+        // `capacity` buffered items, plus one the producer is blocked handing over and one
+        // the consumer has taken but not yet counted
+        assert!(peak <= capacity + 2, "peak {} exceeded bound", peak);
+        assert!(peak >= capacity, "slow consumer should fill the buffer, peak {}", peak);
+    }
+
+    #[test]
+    fn fan_in_delivers_each_item_exactly_once() {
+        let producers = 5;
+        let per_producer = 100;
+        let items = fan_in(producers, per_producer, 8);
+        assert_eq!(items.len(), producers * per_producer as usize);
+
+        let unique: HashSet<_> = items.iter().copied().collect();
+        assert_eq!(unique.len(), items.len());
+        for id in 0..producers {
+            let from_producer: Vec<u64> =
+                items.iter().filter(|(p, _)| *p == id).map(|(_, i)| *i).collect();
+            // A single producer's items stay in order within the shared channel
+            assert_eq!(from_producer, (0..per_producer).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn pipeline_processes_each_input_exactly_once() {
+        let inputs: Vec<u64> = (0..500).collect();
+        let results = run_pipeline(inputs.clone(), 4, 2);
+        assert_eq!(results.len(), inputs.len());
+
+        let mut seen: Vec<u64> = results.iter().map(|r| r.input).collect();
+        seen.sort();
+        assert_eq!(seen, inputs);
+        assert!(results.iter().all(|r| r.squared == r.input * r.input));
+        assert!(results.iter().all(|r| r.worker < 4));
+    }
+
+    #[test]
+    fn pipeline_with_empty_input_terminates() {
+        assert!(run_pipeline(Vec::new(), 3, 1).is_empty());
+    }
+}
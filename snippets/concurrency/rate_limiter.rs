@@ -0,0 +1,432 @@
+//! Semaphores and Rate Limiters
+//!
+//! Two related ways of protecting a resource:
+//! - A counting semaphore caps how many callers use it *at the same time*
+//! - A rate limiter caps how many calls happen *per unit of time*
+//!
+//! This file demonstrates:
+//! - A counting semaphore built from `Mutex` + `Condvar`, with an RAII permit that
+//!   releases itself on drop
+//! - A token-bucket limiter: allows bursts up to the bucket size, then a steady refill rate
+//! - A sliding-window-log limiter: at most N requests in any window of the given length
+//! - An injectable `Clock` so time-based logic can be tested deterministically with a
+//!   manual clock instead of real sleeps
+
+use demo::{Demo, ThreadOutput};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// ========== Clock ==========
+
+/// A source of monotonic time, measured from an arbitrary starting point
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+/// Real time, measured from when the clock was created
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Simulated time that only moves when told to
+#[derive(Default)]
+pub struct ManualClock {
+    now: Mutex<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+// ========== Counting Semaphore ==========
+
+pub struct Semaphore {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+/// Holds one permit; returning it to the semaphore happens on drop
+pub struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        *self.semaphore.available.lock().unwrap() += 1;
+        self.semaphore.released.notify_one();
+    }
+}
+
+impl Semaphore {
+    pub fn new(permits: usize) -> Self {
+        Semaphore { available: Mutex::new(permits), released: Condvar::new() }
+    }
+
+    /// Block until a permit is free
+    pub fn acquire(&self) -> Permit<'_> {
+        let guard = self.available.lock().unwrap();
+        let mut available = self.released.wait_while(guard, |n| *n == 0).unwrap();
+        *available -= 1;
+        Permit { semaphore: self }
+    }
+
+    /// Take a permit only if one is free right now
+    pub fn try_acquire(&self) -> Option<Permit<'_>> {
+        let mut available = self.available.lock().unwrap();
+        if *available == 0 {
+            return None;
+        }
+        *available -= 1;
+        Some(Permit { semaphore: self })
+    }
+
+    pub fn available(&self) -> usize {
+        *self.available.lock().unwrap()
+    }
+}
+
+// ========== Rate Limiters ==========
+
+/// Decides whether a request arriving now may proceed
+pub trait RateLimiter: Send + Sync {
+    fn try_acquire(&self) -> bool;
+}
+
+/// Token amounts are kept in nano-tokens so refills are exact integer arithmetic
+const NANOS_PER_TOKEN: u128 = 1_000_000_000;
+
+struct BucketState {
+    nano_tokens: u128,
+    last_refill: Duration,
+}
+
+/// Starts full with `capacity` tokens, gains `refill_per_sec` tokens per second up to
+/// `capacity`, and every request spends one token
+pub struct TokenBucket {
+    capacity: u64,
+    refill_per_sec: u64,
+    clock: Arc<dyn Clock>,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u64, refill_per_sec: u64, clock: Arc<dyn Clock>) -> Self {
+        let last_refill = clock.now();
+        TokenBucket {
+            capacity,
+            refill_per_sec,
+            clock,
+            state: Mutex::new(BucketState {
+                nano_tokens: capacity as u128 * NANOS_PER_TOKEN,
+                last_refill,
+            }),
+        }
+    }
+
+    fn refill(&self, state: &mut BucketState) {
+        let now = self.clock.now();
+        let elapsed = now.saturating_sub(state.last_refill).as_nanos();
+        let max = self.capacity as u128 * NANOS_PER_TOKEN;
+        state.nano_tokens = (state.nano_tokens + elapsed * self.refill_per_sec as u128).min(max);
+        state.last_refill = now;
+    }
+
+    /// Whole tokens currently available
+    pub fn available(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        (state.nano_tokens / NANOS_PER_TOKEN) as u64
+    }
+
+    /// Spend `n` tokens if they are all available
+    pub fn try_acquire_n(&self, n: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        let cost = n as u128 * NANOS_PER_TOKEN;
+        if state.nano_tokens >= cost {
+            state.nano_tokens -= cost;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl RateLimiter for TokenBucket {
+    fn try_acquire(&self) -> bool {
+        self.try_acquire_n(1)
+    }
+}
+
+/// Remembers the time of every admitted request in the last `window` and admits a new one
+/// only while fewer than `limit` remain. Unlike a fixed window, there is no boundary where
+/// two full windows' worth of requests can squeeze through back to back.
+pub struct SlidingWindowLog {
+    limit: usize,
+    window: Duration,
+    clock: Arc<dyn Clock>,
+    log: Mutex<VecDeque<Duration>>,
+}
+
+impl SlidingWindowLog {
+    pub fn new(limit: usize, window: Duration, clock: Arc<dyn Clock>) -> Self {
+        SlidingWindowLog { limit, window, clock, log: Mutex::new(VecDeque::with_capacity(limit)) }
+    }
+
+    fn evict_expired(&self, log: &mut VecDeque<Duration>, now: Duration) {
+        while log.front().is_some_and(|&t| now.saturating_sub(t) >= self.window) {
+            log.pop_front();
+        }
+    }
+
+    /// Requests admitted within the current window
+    pub fn in_window(&self) -> usize {
+        let mut log = self.log.lock().unwrap();
+        self.evict_expired(&mut log, self.clock.now());
+        log.len()
+    }
+}
+
+impl RateLimiter for SlidingWindowLog {
+    fn try_acquire(&self) -> bool {
+        let now = self.clock.now();
+        let mut log = self.log.lock().unwrap();
+        self.evict_expired(&mut log, now);
+        if log.len() < self.limit {
+            log.push_back(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// ========== Simulation ==========
+
+/// Offer one request every `interval` for `duration` of simulated time and count how many
+/// the limiter admits. The first request arrives at time zero.
+pub fn simulate(limiter: &dyn RateLimiter, clock: &ManualClock, interval: Duration, duration: Duration) -> usize {
+    let mut admitted = 0;
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        if limiter.try_acquire() {
+            admitted += 1;
+        }
+        clock.advance(interval);
+        elapsed += interval;
+    }
+    admitted
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct RateLimiterDemo;
+
+impl Demo for RateLimiterDemo {
+    fn name(&self) -> &'static str {
+        "rate_limiter"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Semaphore, token bucket and sliding-window log"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Semaphore: 2 concurrent downloads =====")?;
+        let semaphore = Semaphore::new(2);
+        let start = Instant::now();
+        let output = ThreadOutput::new();
+        thread::scope(|scope| {
+            for id in 0..6 {
+                let (semaphore, printer) = (&semaphore, output.printer());
+                scope.spawn(move || {
+                    let _permit = semaphore.acquire();
+                    printer.println(format!("[{:>4}ms] download {} started", start.elapsed().as_millis(), id));
+                    thread::sleep(Duration::from_millis(100));
+                });
+            }
+        });
+        output.drain_into(out)?;
+        writeln!(out, "6 downloads of 100ms with 2 permits took {}ms", start.elapsed().as_millis())?;
+
+        writeln!(out, "\n===== Token bucket: capacity 5, 2 tokens/s =====")?;
+        let clock = Arc::new(ManualClock::new());
+        let bucket = TokenBucket::new(5, 2, clock.clone());
+        let burst = (0..10).filter(|_| bucket.try_acquire()).count();
+        writeln!(out, "burst of 10 at t=0: {} admitted", burst)?;
+        clock.advance(Duration::from_millis(1500));
+        writeln!(out, "after 1.5s idle: {} tokens available", bucket.available())?;
+        let admitted = simulate(&bucket, &clock, Duration::from_millis(100), Duration::from_secs(10));
+        writeln!(out, "10 req/s for 10s: {} of 100 admitted", admitted)?;
+
+        writeln!(out, "\n===== Sliding window: 3 per second =====")?;
+        let clock = Arc::new(ManualClock::new());
+        let window = SlidingWindowLog::new(3, Duration::from_secs(1), clock.clone());
+        for _ in 0..8 {
+            let t = clock.now();
+            writeln!(out, "t={:>4}ms admitted={}", t.as_millis(), window.try_acquire())?;
+            clock.advance(Duration::from_millis(250));
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&RateLimiterDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn semaphore_never_exceeds_permit_count() {
+        let semaphore = Semaphore::new(3);
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..12 {
+                scope.spawn(|| {
+                    let _permit = semaphore.acquire();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(5));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(semaphore.available(), 3);
+    }
+
+    #[test]
+    fn permits_return_on_drop() {
+        let semaphore = Semaphore::new(1);
+        let permit = semaphore.try_acquire().expect("one permit is free");
+        assert!(semaphore.try_acquire().is_none());
+        drop(permit);
+        assert!(semaphore.try_acquire().is_some());
+    }
+
+    #[test]
+    fn blocked_acquire_wakes_when_permit_released() {
+        let semaphore = Semaphore::new(1);
+        let held = semaphore.acquire();
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| {
+                let _permit = semaphore.acquire();
+            });
+            thread::sleep(Duration::from_millis(20));
+            assert!(!waiter.is_finished());
+            drop(held);
+        });
+        assert_eq!(semaphore.available(), 1);
+    }
+
+    #[test]
+    fn token_bucket_allows_initial_burst_then_refills() {
+        let clock = Arc::new(ManualClock::new());
+        let bucket = TokenBucket::new(5, 2, clock.clone());
+        assert_eq!((0..10).filter(|_| bucket.try_acquire()).count(), 5);
+        assert!(!bucket.try_acquire());
+
+        clock.advance(Duration::from_millis(500));
+        assert!(bucket.try_acquire(), "half a second at 2/s refills one token");
+        assert!(!bucket.try_acquire());
+    }
+
+    #[test]
+    fn token_bucket_never_exceeds_capacity() {
+        let clock = Arc::new(ManualClock::new());
+        let bucket = TokenBucket::new(3, 100, clock.clone());
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(bucket.available(), 3);
+        assert!(bucket.try_acquire_n(3));
+        assert!(!bucket.try_acquire_n(1));
+    }
+
+    #[test]
+    fn token_bucket_admits_burst_plus_rate_over_simulated_time() {
+        let clock = Arc::new(ManualClock::new());
+        let bucket = TokenBucket::new(5, 10, clock.clone());
+        // 100 req/s offered for 10s against a 10/s limit with a burst of 5. The last
+        // request arrives at 9.99s, by which point 99 tokens have been refilled.
+        let admitted = simulate(&bucket, &clock, Duration::from_millis(10), Duration::from_secs(10));
+        assert_eq!(admitted, 5 + 99);
+    }
+
+    #[test]
+    fn sliding_window_admits_limit_per_window() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = SlidingWindowLog::new(3, Duration::from_secs(1), clock.clone());
+        let admitted = simulate(&limiter, &clock, Duration::from_millis(100), Duration::from_secs(10));
+        assert_eq!(admitted, 3 * 10);
+    }
+
+    #[test]
+    fn sliding_window_frees_slots_as_requests_age_out() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = SlidingWindowLog::new(2, Duration::from_secs(1), clock.clone());
+        assert!(limiter.try_acquire()); // t = 0
+        clock.advance(Duration::from_millis(600));
+        assert!(limiter.try_acquire()); // t = 600ms
+        assert!(!limiter.try_acquire());
+        clock.advance(Duration::from_millis(400));
+        // t = 1000ms: the request from t = 0 has left the window
+        assert_eq!(limiter.in_window(), 1);
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+    }
+
+    #[test]
+    fn sliding_window_blocks_boundary_bursts() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = SlidingWindowLog::new(5, Duration::from_secs(1), clock.clone());
+        clock.advance(Duration::from_millis(900));
+        assert_eq!((0..5).filter(|_| limiter.try_acquire()).count(), 5);
+        // A fixed window resetting at t = 1s would admit 5 more here
+        clock.advance(Duration::from_millis(200));
+        assert_eq!((0..5).filter(|_| limiter.try_acquire()).count(), 0);
+    }
+}
@@ -0,0 +1,305 @@
+//! Data Parallelism with Rayon
+//!
+//! Rayon turns sequential iterator chains into parallel ones: `iter()` becomes
+//! `par_iter()` and a work-stealing thread pool splits the data across all cores. Because
+//! the borrow checker already proves there are no data races, the change is usually one
+//! method name.
+//!
+//! Every recipe below comes with a sequential baseline so results can be compared for
+//! correctness, and `benches/rayon_parallel.rs` measures the speedup:
+//! - Map-reduce over a large vector (sum of squares)
+//! - Word count with per-thread hash maps merged in a reduce step
+//! - Matrix multiplication parallelized over output rows
+//! - Custom parallel iterators: `IntoParallelIterator` for a user type, and
+//!   `rayon::iter::split` for a divide-and-conquer range
+//!
+//! Built with the crate's `rayon` feature (on by default).
+
+use demo::Demo;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+// ========== Map-Reduce ==========
+
+pub fn sum_of_squares_sequential(data: &[u64]) -> u64 {
+    data.iter().map(|x| x * x).sum()
+}
+
+pub fn sum_of_squares_parallel(data: &[u64]) -> u64 {
+    data.par_iter().map(|x| x * x).sum()
+}
+
+// ========== Word Count ==========
+
+fn normalize(word: &str) -> Option<String> {
+    let cleaned: String = word
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+pub fn word_count_sequential(lines: &[String]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in lines.iter().flat_map(|line| line.split_whitespace()).filter_map(normalize) {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Each rayon job folds into its own map; the maps are then merged pairwise
+pub fn word_count_parallel(lines: &[String]) -> HashMap<String, usize> {
+    lines
+        .par_iter()
+        .fold(HashMap::new, |mut counts, line| {
+            for word in line.split_whitespace().filter_map(normalize) {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+            counts
+        })
+        .reduce(HashMap::new, |mut a, b| {
+            for (word, count) in b {
+                *a.entry(word).or_insert(0) += count;
+            }
+            a
+        })
+}
+
+// ========== Matrix Multiply ==========
+
+/// Dense row-major matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> f64) -> Self {
+        let data = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Matrix { rows, cols, data }
+    }
+
+    pub fn row(&self, r: usize) -> &[f64] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    fn transpose(&self) -> Matrix {
+        Matrix::from_fn(self.cols, self.rows, |r, c| self.data[c * self.cols + r])
+    }
+}
+
+/// Compute one output row; `bt` is `b` transposed so both operands are read row-wise
+fn multiply_row(a_row: &[f64], bt: &Matrix, out: &mut [f64]) {
+    for (j, cell) in out.iter_mut().enumerate() {
+        *cell = a_row.iter().zip(bt.row(j)).map(|(x, y)| x * y).sum();
+    }
+}
+
+pub fn matmul_sequential(a: &Matrix, b: &Matrix) -> Matrix {
+    assert_eq!(a.cols, b.rows, "dimension mismatch");
+    let bt = b.transpose();
+    let mut out = Matrix::from_fn(a.rows, b.cols, |_, _| 0.0);
+    for (i, out_row) in out.data.chunks_mut(b.cols).enumerate() {
+        multiply_row(a.row(i), &bt, out_row);
+    }
+    out
+}
+
+/// Output rows are disjoint `&mut` chunks, so each can be filled on a different thread
+pub fn matmul_parallel(a: &Matrix, b: &Matrix) -> Matrix {
+    assert_eq!(a.cols, b.rows, "dimension mismatch");
+    let bt = b.transpose();
+    let mut out = Matrix::from_fn(a.rows, b.cols, |_, _| 0.0);
+    out.data
+        .par_chunks_mut(b.cols)
+        .enumerate()
+        .for_each(|(i, out_row)| multiply_row(a.row(i), &bt, out_row));
+    out
+}
+
+// ========== Custom Parallel Iterators ==========
+
+/// Rows of a matrix, exposed as a parallel iterator via `IntoParallelIterator`
+impl<'a> IntoParallelIterator for &'a Matrix {
+    type Iter = rayon::slice::Chunks<'a, f64>;
+    type Item = &'a [f64];
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.data.par_chunks(self.cols)
+    }
+}
+
+/// Row sums using the custom `IntoParallelIterator` impl
+pub fn row_sums(m: &Matrix) -> Vec<f64> {
+    m.into_par_iter().map(|row| row.iter().sum()).collect()
+}
+
+/// Number of Collatz steps needed to reach 1
+pub fn collatz_steps(mut n: u64) -> u32 {
+    let mut steps = 0;
+    while n != 1 {
+        n = if n & 1 == 0 { n / 2 } else { 3 * n + 1 };
+        steps += 1;
+    }
+    steps
+}
+
+pub fn longest_collatz_sequential(limit: u64) -> (u64, u32) {
+    (1..limit)
+        .map(|n| (n, collatz_steps(n)))
+        .max_by_key(|&(n, steps)| (steps, std::cmp::Reverse(n)))
+        .unwrap_or((0, 0))
+}
+
+/// A half-open range that rayon splits in two until the pieces are small enough.
+/// `rayon::iter::split` builds a parallel iterator from any splittable value.
+pub fn longest_collatz_parallel(limit: u64) -> (u64, u32) {
+    const MIN_CHUNK: u64 = 1_024;
+
+    rayon::iter::split((1u64, limit), |(start, end)| {
+        if end - start <= MIN_CHUNK {
+            ((start, end), None)
+        } else {
+            let mid = start + (end - start) / 2;
+            ((start, mid), Some((mid, end)))
+        }
+    })
+    .map(|(start, end)| {
+        (start..end)
+            .map(|n| (n, collatz_steps(n)))
+            .max_by_key(|&(n, steps)| (steps, std::cmp::Reverse(n)))
+            .unwrap_or((0, 0))
+    })
+    .max_by_key(|&(n, steps)| (steps, std::cmp::Reverse(n)))
+    .unwrap_or((0, 0))
+}
+
+// ========== Demo Code ==========
+
+fn time<T>(out: &mut dyn Write, label: &str, f: impl FnOnce() -> T) -> io::Result<T> {
+    let start = std::time::Instant::now();
+    let result = f();
+    writeln!(out, "  {:<11} {:>10.2?}", label, start.elapsed())?;
+    Ok(result)
+}
+
+/// Deterministic filler text for the word-count demo
+pub fn sample_lines(count: usize) -> Vec<String> {
+    const WORDS: [&str; 8] = ["rust", "rayon", "thread", "Data", "parallel,", "iterator", "fast!", "safe"];
+    (0..count)
+        .map(|i| (0..12).map(|j| WORDS[(i * 7 + j * 3) % WORDS.len()]).collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct RayonParallelDemo;
+
+impl Demo for RayonParallelDemo {
+    fn name(&self) -> &'static str {
+        "rayon_parallel"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Data-parallel recipes with sequential baselines"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Rayon is using {} threads\n", rayon::current_num_threads())?;
+
+        writeln!(out, "===== Map-Reduce: sum of squares =====")?;
+        // Small enough that the sum of squares still fits in a u64 (about 3.3e17)
+        let data: Vec<u64> = (0..1_000_000).collect();
+        let s = time(out, "sequential", || sum_of_squares_sequential(&data))?;
+        let p = time(out, "parallel", || sum_of_squares_parallel(&data))?;
+        writeln!(out, "  equal: {}\n", s == p)?;
+
+        writeln!(out, "===== Word Count =====")?;
+        let lines = sample_lines(200_000);
+        let s = time(out, "sequential", || word_count_sequential(&lines))?;
+        let p = time(out, "parallel", || word_count_parallel(&lines))?;
+        writeln!(out, "  equal: {}, 'rust' appears {} times\n", s == p, p["rust"])?;
+
+        writeln!(out, "===== Matrix Multiply (256x256) =====")?;
+        let a = Matrix::from_fn(256, 256, |r, c| (r + c) as f64 / 256.0);
+        let b = Matrix::from_fn(256, 256, |r, c| (r * c % 7) as f64);
+        let s = time(out, "sequential", || matmul_sequential(&a, &b))?;
+        let p = time(out, "parallel", || matmul_parallel(&a, &b))?;
+        writeln!(out, "  equal: {}\n", s == p)?;
+
+        writeln!(out, "===== Custom Parallel Iterators =====")?;
+        writeln!(out, "  first row sums: {:?}", &row_sums(&a)[..3])?;
+        let s = time(out, "sequential", || longest_collatz_sequential(1_000_000))?;
+        let p = time(out, "parallel", || longest_collatz_parallel(1_000_000))?;
+        writeln!(out, "  longest Collatz chain below 1,000,000 starts at {} ({} steps), equal: {}", p.0, p.1, s == p)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&RayonParallelDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_reduce_matches_sequential() {
+        let data: Vec<u64> = (0..100_000).collect();
+        assert_eq!(sum_of_squares_parallel(&data), sum_of_squares_sequential(&data));
+        assert_eq!(sum_of_squares_parallel(&[]), 0);
+    }
+
+    #[test]
+    fn word_count_matches_sequential() {
+        let lines = sample_lines(5_000);
+        let parallel = word_count_parallel(&lines);
+        assert_eq!(parallel, word_count_sequential(&lines));
+        // Punctuation is stripped and case folded
+        assert!(parallel.contains_key("parallel"));
+        assert!(parallel.contains_key("data"));
+        assert!(!parallel.contains_key("Data"));
+    }
+
+    #[test]
+    fn word_count_small_example() {
+        let lines = vec!["The cat".to_string(), "the CAT sat.".to_string()];
+        let counts = word_count_parallel(&lines);
+        assert_eq!(counts["the"], 2);
+        assert_eq!(counts["cat"], 2);
+        assert_eq!(counts["sat"], 1);
+    }
+
+    #[test]
+    fn matmul_matches_sequential_and_known_result() {
+        let a = Matrix { rows: 2, cols: 3, data: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0] };
+        let b = Matrix { rows: 3, cols: 2, data: vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0] };
+        let expected = Matrix { rows: 2, cols: 2, data: vec![58.0, 64.0, 139.0, 154.0] };
+        assert_eq!(matmul_parallel(&a, &b), expected);
+        assert_eq!(matmul_sequential(&a, &b), expected);
+
+        let big_a = Matrix::from_fn(64, 48, |r, c| (r * 3 + c) as f64);
+        let big_b = Matrix::from_fn(48, 32, |r, c| (r + 2 * c) as f64);
+        assert_eq!(matmul_parallel(&big_a, &big_b), matmul_sequential(&big_a, &big_b));
+    }
+
+    #[test]
+    fn custom_into_parallel_iterator_yields_rows() {
+        let m = Matrix::from_fn(3, 2, |r, c| (r * 2 + c) as f64);
+        assert_eq!(row_sums(&m), vec![1.0, 5.0, 9.0]);
+    }
+
+    #[test]
+    fn split_based_iterator_matches_sequential() {
+        assert_eq!(longest_collatz_parallel(10), (9, 19));
+        assert_eq!(longest_collatz_parallel(100_000), longest_collatz_sequential(100_000));
+    }
+}
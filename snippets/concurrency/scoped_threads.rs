@@ -0,0 +1,257 @@
+//! Scoped Threads and Structured Concurrency
+//!
+//! `thread::spawn` requires its closure to be `'static`: the new thread might outlive the
+//! caller, so it cannot borrow anything from the caller's stack. The usual workaround is to
+//! clone the data into an `Arc` before spawning.
+//!
+//! `thread::scope` (stable since Rust 1.63) removes that restriction. Every thread spawned
+//! inside the scope is joined before `scope` returns, so the threads may borrow local
+//! slices directly, even mutably, as long as the borrows don't overlap. This is structured
+//! concurrency: the lifetime of the threads is tied to a lexical block.
+//!
+//! This file demonstrates:
+//! - Chunked parallel sum over a borrowed slice, next to the `'static` + `Arc` version
+//! - Parallel search that stops the other workers early once a match is found
+//! - Mutating disjoint halves of one buffer from two threads with `split_at_mut`
+//! - Panics in scoped threads propagating to the caller
+
+use demo::Demo;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// ========== Chunked Parallel Sum ==========
+
+fn chunk_size(len: usize, threads: usize) -> usize {
+    len.div_ceil(threads.max(1)).max(1)
+}
+
+/// Sum a borrowed slice by splitting it into one chunk per thread.
+/// No cloning: every thread borrows its chunk of `data` directly.
+pub fn parallel_sum(data: &[u64], threads: usize) -> u64 {
+    thread::scope(|scope| {
+        let handles: Vec<_> = data
+            .chunks(chunk_size(data.len(), threads))
+            .map(|chunk| scope.spawn(move || chunk.iter().sum::<u64>()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+/// The same computation with `thread::spawn`. The closure must be `'static`, so the data
+/// has to be moved into an `Arc` and each thread works out its own range by index.
+pub fn parallel_sum_static(data: Arc<Vec<u64>>, threads: usize) -> u64 {
+    let size = chunk_size(data.len(), threads);
+    let handles: Vec<_> = (0..data.len().div_ceil(size))
+        .map(|i| {
+            let data = Arc::clone(&data);
+            thread::spawn(move || {
+                let end = ((i + 1) * size).min(data.len());
+                data[i * size..end].iter().sum::<u64>()
+            })
+        })
+        .collect();
+    handles.into_iter().map(|h| h.join().unwrap()).sum()
+}
+
+// ========== Parallel Search ==========
+
+/// Result of a parallel search, including how much work was done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOutcome {
+    /// Lowest index matching the predicate, if any
+    pub index: Option<usize>,
+    /// Number of elements the predicate was evaluated on
+    pub inspected: usize,
+}
+
+/// Find the first element matching `predicate`, checking chunks in parallel.
+/// A shared `found` flag lets workers stop early once someone has a match; the lowest
+/// matching index is tracked with `fetch_min`, so the answer is the same as a
+/// sequential search.
+pub fn parallel_find<T, F>(data: &[T], threads: usize, predicate: F) -> SearchOutcome
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    let best = AtomicUsize::new(usize::MAX);
+    let found = AtomicBool::new(false);
+    let inspected = AtomicUsize::new(0);
+    let size = chunk_size(data.len(), threads);
+
+    thread::scope(|scope| {
+        for (chunk_index, chunk) in data.chunks(size).enumerate() {
+            let (best, found, inspected, predicate) = (&best, &found, &inspected, &predicate);
+            scope.spawn(move || {
+                let offset = chunk_index * size;
+                let mut local = 0;
+                for (i, item) in chunk.iter().enumerate() {
+                    // A match in an earlier chunk beats anything this chunk could find
+                    if found.load(Ordering::Relaxed) && best.load(Ordering::Relaxed) < offset {
+                        break;
+                    }
+                    local += 1;
+                    if predicate(item) {
+                        best.fetch_min(offset + i, Ordering::Relaxed);
+                        found.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                }
+                inspected.fetch_add(local, Ordering::Relaxed);
+            });
+        }
+    });
+
+    let best = best.into_inner();
+    SearchOutcome {
+        index: (best != usize::MAX).then_some(best),
+        inspected: inspected.into_inner(),
+    }
+}
+
+// ========== Disjoint Mutable Borrows ==========
+
+/// Normalize each half of `values` to its own maximum, in parallel.
+/// `split_at_mut` proves to the compiler that the two `&mut` halves don't overlap.
+pub fn normalize_halves(values: &mut [f64]) {
+    fn normalize(part: &mut [f64]) {
+        let max = part.iter().cloned().fold(f64::MIN, f64::max);
+        if max > 0.0 {
+            part.iter_mut().for_each(|v| *v /= max);
+        }
+    }
+
+    let mid = values.len() / 2;
+    let (left, right) = values.split_at_mut(mid);
+    thread::scope(|scope| {
+        scope.spawn(|| normalize(left));
+        scope.spawn(|| normalize(right));
+    });
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ScopedThreadsDemo;
+
+impl Demo for ScopedThreadsDemo {
+    fn name(&self) -> &'static str {
+        "scoped_threads"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Borrowing threads, parallel sum and early-exit search"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let data: Vec<u64> = (1..=1_000_000).collect();
+
+        writeln!(out, "===== Chunked Parallel Sum =====")?;
+        writeln!(out, "scoped (borrowed slice): {}", parallel_sum(&data, 4))?;
+        writeln!(out, "'static (Arc<Vec>):      {}", parallel_sum_static(Arc::new(data.clone()), 4))?;
+        // `data` is still ours: the scoped version only borrowed it
+        writeln!(out, "data still owned here, len = {}", data.len())?;
+
+        writeln!(out, "\n===== Parallel Search =====")?;
+        let outcome = parallel_find(&data, 4, |&x| x % 250_001 == 0);
+        writeln!(out, 
+                 "first multiple of 250001 at index {:?}, inspected {} of {} elements",
+                 outcome.index,
+                 outcome.inspected,
+                 data.len()
+    )?;
+
+        writeln!(out, "\n===== Disjoint Mutable Borrows =====")?;
+        let mut values = vec![1.0, 2.0, 4.0, 10.0, 20.0, 40.0];
+        normalize_halves(&mut values);
+        writeln!(out, "normalized halves: {:?}", values)?;
+
+        writeln!(out, "\n===== Panics Propagate =====")?;
+        // Silence the default panic message for the intentional panic below
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        let result = std::panic::catch_unwind(|| {
+            thread::scope(|scope| {
+                scope.spawn(|| panic!("worker failed"));
+            });
+        });
+        std::panic::set_hook(default_hook);
+        writeln!(out, "scope re-raised the worker panic: {}", result.is_err())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ScopedThreadsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_sum_matches_sequential() {
+        let data: Vec<u64> = (0..10_001).collect();
+        let expected: u64 = data.iter().sum();
+        for threads in [1, 2, 3, 7, 64] {
+            assert_eq!(parallel_sum(&data, threads), expected);
+            assert_eq!(parallel_sum_static(Arc::new(data.clone()), threads), expected);
+        }
+    }
+
+    #[test]
+    fn parallel_sum_handles_tiny_inputs() {
+        assert_eq!(parallel_sum(&[], 4), 0);
+        assert_eq!(parallel_sum(&[5], 4), 5);
+        assert_eq!(parallel_sum_static(Arc::new(vec![]), 4), 0);
+        assert_eq!(parallel_sum(&[1, 2], 0), 3);
+    }
+
+    #[test]
+    fn parallel_find_returns_lowest_match() {
+        let data: Vec<u32> = (0..1_000).map(|i| i % 100).collect();
+        for threads in [1, 3, 8] {
+            // 42 first appears at index 42, again at 142, 242, ...
+            assert_eq!(parallel_find(&data, threads, |&x| x == 42).index, Some(42));
+        }
+    }
+
+    #[test]
+    fn parallel_find_reports_missing_and_empty() {
+        let data: Vec<u32> = (0..500).collect();
+        let outcome = parallel_find(&data, 4, |&x| x > 1_000);
+        assert_eq!(outcome.index, None);
+        assert_eq!(outcome.inspected, data.len());
+        assert_eq!(parallel_find(&[] as &[u32], 4, |_| true).index, None);
+    }
+
+    #[test]
+    fn single_threaded_search_stops_at_first_match() {
+        let data: Vec<u32> = (0..1_000).collect();
+        let outcome = parallel_find(&data, 1, |&x| x == 10);
+        assert_eq!(outcome, SearchOutcome { index: Some(10), inspected: 11 });
+    }
+
+    #[test]
+    fn halves_are_normalized_independently() {
+        let mut values = vec![2.0, 4.0, 5.0, 10.0];
+        normalize_halves(&mut values);
+        assert_eq!(values, vec![0.5, 1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn scoped_threads_can_mutate_borrowed_state() {
+        let mut counts = [0usize; 4];
+        thread::scope(|scope| {
+            for (i, slot) in counts.iter_mut().enumerate() {
+                scope.spawn(move || *slot = i * 10);
+            }
+        });
+        assert_eq!(counts, [0, 10, 20, 30]);
+    }
+}
@@ -0,0 +1,352 @@
+//! Shared-State Synchronization in Rust: Mutex vs RwLock vs Atomics vs Sharding
+//!
+//! The same statistics aggregator (count, sum, max of recorded values) is implemented four
+//! ways so the trade-offs can be compared side by side:
+//!
+//! - `Mutex`: simplest and always consistent, but every reader and writer serializes
+//! - `RwLock`: readers proceed in parallel, writers are still exclusive
+//! - Atomics: lock-free updates per field, but a snapshot may mix fields from different
+//!   moments (count from before a write, sum from after it)
+//! - Sharded atomics: each thread writes to its own cache-line-padded shard, so writers
+//!   stop fighting over one cache line; reads pay by summing every shard
+//!
+//! The companion benchmark in `benches/sync_primitives.rs` measures them under different
+//! reader/writer ratios.
+
+use demo::Demo;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::thread;
+
+// ========== Common Interface ==========
+
+/// Aggregated view of every recorded value
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub count: u64,
+    pub sum: u64,
+    pub max: u64,
+}
+
+impl Stats {
+    fn record(&mut self, value: u64) {
+        self.count += 1;
+        self.sum += value;
+        self.max = self.max.max(value);
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum as f64 / self.count as f64
+        }
+    }
+}
+
+/// A statistics aggregator that many threads can update and read concurrently
+pub trait StatsAggregator: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn record(&self, value: u64);
+    fn snapshot(&self) -> Stats;
+}
+
+// ========== Mutex ==========
+
+#[derive(Default)]
+pub struct MutexStats {
+    inner: Mutex<Stats>,
+}
+
+impl StatsAggregator for MutexStats {
+    fn name(&self) -> &'static str {
+        "mutex"
+    }
+
+    fn record(&self, value: u64) {
+        self.inner.lock().unwrap().record(value);
+    }
+
+    fn snapshot(&self) -> Stats {
+        *self.inner.lock().unwrap()
+    }
+}
+
+// ========== RwLock ==========
+
+#[derive(Default)]
+pub struct RwLockStats {
+    inner: RwLock<Stats>,
+}
+
+impl StatsAggregator for RwLockStats {
+    fn name(&self) -> &'static str {
+        "rwlock"
+    }
+
+    fn record(&self, value: u64) {
+        self.inner.write().unwrap().record(value);
+    }
+
+    fn snapshot(&self) -> Stats {
+        *self.inner.read().unwrap()
+    }
+}
+
+// ========== Atomics ==========
+
+#[derive(Default)]
+pub struct AtomicStats {
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+impl StatsAggregator for AtomicStats {
+    fn name(&self) -> &'static str {
+        "atomic"
+    }
+
+    fn record(&self, value: u64) {
+        // Relaxed is enough: each counter is independent and we only need the totals
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.max.fetch_max(value, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> Stats {
+        // Three separate loads: not a consistent cut while writers are active
+        Stats {
+            count: self.count.load(Ordering::Relaxed),
+            sum: self.sum.load(Ordering::Relaxed),
+            max: self.max.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// ========== Sharded Atomics ==========
+
+/// One shard per cache line so neighbouring shards don't false-share
+#[repr(align(64))]
+#[derive(Default)]
+struct Shard {
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+pub struct ShardedStats {
+    shards: Vec<Shard>,
+}
+
+impl ShardedStats {
+    pub fn new(shards: usize) -> Self {
+        ShardedStats {
+            shards: (0..shards.max(1)).map(|_| Shard::default()).collect(),
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Each thread consistently maps to the same shard
+    fn shard_index(&self) -> usize {
+        THREAD_SLOT.with(|slot| *slot) % self.shards.len()
+    }
+}
+
+/// Hands out a distinct slot number to every thread the first time it records a value
+static NEXT_THREAD_SLOT: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_SLOT: usize = NEXT_THREAD_SLOT.fetch_add(1, Ordering::Relaxed);
+}
+
+impl Default for ShardedStats {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl StatsAggregator for ShardedStats {
+    fn name(&self) -> &'static str {
+        "sharded"
+    }
+
+    fn record(&self, value: u64) {
+        let shard = &self.shards[self.shard_index()];
+        shard.count.fetch_add(1, Ordering::Relaxed);
+        shard.sum.fetch_add(value, Ordering::Relaxed);
+        shard.max.fetch_max(value, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> Stats {
+        self.shards.iter().fold(Stats::default(), |acc, shard| Stats {
+            count: acc.count + shard.count.load(Ordering::Relaxed),
+            sum: acc.sum + shard.sum.load(Ordering::Relaxed),
+            max: acc.max.max(shard.max.load(Ordering::Relaxed)),
+        })
+    }
+}
+
+// ========== Workload ==========
+
+/// Run `threads` threads doing `ops_per_thread` operations each, where `read_percent` of
+/// operations take a snapshot and the rest record a value. Returns the final snapshot.
+pub fn run_workload(
+    aggregator: &dyn StatsAggregator,
+    threads: usize,
+    ops_per_thread: u64,
+    read_percent: u64,
+) -> Stats {
+    thread::scope(|scope| {
+        for t in 0..threads as u64 {
+            scope.spawn(move || {
+                let mut observed = 0;
+                for op in 0..ops_per_thread {
+                    if (op * 7 + t) % 100 < read_percent {
+                        // Keep the read from being optimized away
+                        observed += aggregator.snapshot().count;
+                    } else {
+                        aggregator.record(t * ops_per_thread + op);
+                    }
+                }
+                std::hint::black_box(observed);
+            });
+        }
+    });
+    aggregator.snapshot()
+}
+
+/// All four implementations, boxed for uniform iteration
+pub fn all_aggregators() -> Vec<Box<dyn StatsAggregator>> {
+    vec![
+        Box::new(MutexStats::default()),
+        Box::new(RwLockStats::default()),
+        Box::new(AtomicStats::default()),
+        Box::new(ShardedStats::default()),
+    ]
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct SyncPrimitivesDemo;
+
+impl Demo for SyncPrimitivesDemo {
+    fn name(&self) -> &'static str {
+        "sync_primitives"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mutex, RwLock, atomic and sharded aggregators"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let threads = 4;
+        let ops = 200_000;
+
+        for read_percent in [0, 50, 90] {
+            writeln!(out, "===== {}% reads, {} threads x {} ops =====", read_percent, threads, ops)?;
+            for aggregator in all_aggregators() {
+                let start = std::time::Instant::now();
+                let stats = run_workload(aggregator.as_ref(), threads, ops, read_percent);
+                writeln!(out, 
+                         "{:>8}: {:>9?}  count={} mean={:.1} max={}",
+                         aggregator.name(),
+                         start.elapsed(),
+                         stats.count,
+                         stats.mean(),
+                         stats.max
+    )?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&SyncPrimitivesDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected_stats(threads: u64, ops: u64, read_percent: u64) -> Stats {
+        let mut stats = Stats::default();
+        for t in 0..threads {
+            for op in 0..ops {
+                if (op * 7 + t) % 100 >= read_percent {
+                    stats.record(t * ops + op);
+                }
+            }
+        }
+        stats
+    }
+
+    #[test]
+    fn all_implementations_agree_on_write_only_workload() {
+        let expected = expected_stats(4, 5_000, 0);
+        for aggregator in all_aggregators() {
+            let stats = run_workload(aggregator.as_ref(), 4, 5_000, 0);
+            assert_eq!(stats, expected, "{} disagrees", aggregator.name());
+        }
+    }
+
+    #[test]
+    fn all_implementations_agree_on_mixed_workload() {
+        let expected = expected_stats(8, 2_000, 75);
+        for aggregator in all_aggregators() {
+            let stats = run_workload(aggregator.as_ref(), 8, 2_000, 75);
+            assert_eq!(stats, expected, "{} disagrees", aggregator.name());
+        }
+    }
+
+    #[test]
+    fn empty_aggregators_report_zero() {
+        for aggregator in all_aggregators() {
+            assert_eq!(aggregator.snapshot(), Stats::default());
+            assert_eq!(aggregator.snapshot().mean(), 0.0);
+        }
+    }
+
+    #[test]
+    fn sharded_stats_merge_every_shard() {
+        let stats = ShardedStats::new(4);
+        assert_eq!(stats.shard_count(), 4);
+        thread::scope(|scope| {
+            for value in [3, 9, 27] {
+                let stats = &stats;
+                scope.spawn(move || stats.record(value));
+            }
+        });
+        assert_eq!(stats.snapshot(), Stats { count: 3, sum: 39, max: 27 });
+    }
+
+    #[test]
+    fn snapshots_never_go_backwards() {
+        let stats = AtomicStats::default();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 0..10_000 {
+                    stats.record(i);
+                }
+            });
+            let mut last = 0;
+            for _ in 0..1_000 {
+                let count = stats.snapshot().count;
+                assert!(count >= last);
+                last = count;
+            }
+        });
+        assert_eq!(stats.snapshot().count, 10_000);
+    }
+}
@@ -0,0 +1,360 @@
+//! Thread Pool Implementation in Rust
+//!
+//! A thread pool keeps a fixed set of worker threads alive and feeds them jobs through a
+//! shared queue. Creating threads is expensive, so reusing a handful of workers is much
+//! cheaper than spawning a thread per task.
+//!
+//! This file demonstrates:
+//! - A job queue built on `mpsc` with the receiver shared behind `Arc<Mutex<_>>`
+//! - `spawn` returning a `TaskHandle<T>` that can be joined for the task's result
+//! - Per-worker panic recovery: a panicking job is reported through its handle and the
+//!   worker keeps serving the queue
+//! - Graceful shutdown on drop: queued jobs are drained before the workers are joined
+
+use demo::{Demo, ThreadOutput};
+use std::fmt;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+// ========== Task Handle ==========
+
+/// Error returned when joining a task whose closure panicked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskPanicked {
+    pub message: String,
+}
+
+impl fmt::Display for TaskPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task panicked: {}", self.message)
+    }
+}
+
+impl std::error::Error for TaskPanicked {}
+
+/// Join-handle-like receiver for the result of a spawned task
+pub struct TaskHandle<T> {
+    receiver: Receiver<Result<T, TaskPanicked>>,
+}
+
+impl<T> TaskHandle<T> {
+    /// Block until the task finishes and return its result
+    pub fn join(self) -> Result<T, TaskPanicked> {
+        self.receiver.recv().unwrap_or_else(|_| {
+            Err(TaskPanicked {
+                message: "pool shut down before the task ran".to_string(),
+            })
+        })
+    }
+
+    /// Take the result if the task has already finished, without blocking.
+    /// Once this returns `Some`, a later `join` has nothing left to receive.
+    pub fn try_join(&self) -> Option<Result<T, TaskPanicked>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+// ========== Worker ==========
+
+struct Worker {
+    id: usize,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(id: usize, receiver: Arc<Mutex<Receiver<Job>>>) -> Self {
+        let thread = thread::Builder::new()
+            .name(format!("pool-worker-{}", id))
+            .spawn(move || loop {
+                // Hold the lock only while receiving, never while running the job
+                let message = receiver.lock().unwrap().recv();
+                match message {
+                    Ok(job) => job(),
+                    // Sender dropped: the pool is shutting down
+                    Err(_) => break,
+                }
+            })
+            .expect("failed to spawn worker thread");
+
+        Worker {
+            id,
+            thread: Some(thread),
+        }
+    }
+}
+
+// ========== Thread Pool ==========
+
+/// A fixed-size pool of worker threads
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: Option<Sender<Job>>,
+}
+
+impl ThreadPool {
+    /// Create a pool with `size` workers
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "thread pool needs at least one worker");
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..size)
+            .map(|id| Worker::new(id, Arc::clone(&receiver)))
+            .collect();
+
+        ThreadPool {
+            workers,
+            sender: Some(sender),
+        }
+    }
+
+    /// Number of worker threads
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queue a closure and get a handle for its result
+    pub fn spawn<F, T>(&self, f: F) -> TaskHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (result_tx, result_rx) = mpsc::channel();
+        let job: Job = Box::new(move || {
+            // Catch the panic here so the worker thread survives it
+            let result = panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+                TaskPanicked {
+                    message: panic_message(payload.as_ref()),
+                }
+            });
+            // The caller may have dropped the handle; that's fine
+            let _ = result_tx.send(result);
+        });
+
+        self.sender
+            .as_ref()
+            .expect("pool is shutting down")
+            .send(job)
+            .expect("all workers have exited");
+
+        TaskHandle {
+            receiver: result_rx,
+        }
+    }
+
+    /// Queue a closure whose result is not needed
+    pub fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        drop(self.spawn(f));
+    }
+
+    /// Stop accepting work, let the queue drain and join every worker
+    pub fn shutdown(mut self) {
+        self.shutdown_inner();
+    }
+
+    fn shutdown_inner(&mut self) {
+        // Dropping the sender makes `recv` fail once the queue is empty
+        drop(self.sender.take());
+
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                if thread.join().is_err() {
+                    eprintln!("worker {} exited abnormally", worker.id);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shutdown_inner();
+    }
+}
+
+/// Extract a readable message from a panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ThreadPoolDemo;
+
+impl Demo for ThreadPoolDemo {
+    fn name(&self) -> &'static str {
+        "thread_pool"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fixed-size thread pool with graceful shutdown"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let pool = ThreadPool::new(4);
+        writeln!(out, "Created a pool with {} workers", pool.size())?;
+
+        writeln!(out, "\n===== Spawning Tasks =====")?;
+        let output = ThreadOutput::new();
+        let handles: Vec<_> = (1..=8u64)
+            .map(|n| {
+                let printer = output.printer();
+                pool.spawn(move || {
+                    // Later tasks finish first to show results don't depend on completion order
+                    thread::sleep(Duration::from_millis(80 - n * 10));
+                    printer.println(format!("[{}] computed {}^2", thread::current().name().unwrap_or("?"), n));
+                    n * n
+                })
+            })
+            .collect();
+
+        let squares: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        output.drain_into(out)?;
+        writeln!(out, "Squares in submission order: {:?}", squares)?;
+
+        writeln!(out, "\n===== Panic Recovery =====")?;
+        // Silence the default panic message for the intentional panic below
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let failing = pool.spawn(|| -> u32 { panic!("something went wrong") });
+        writeln!(out, "Failing task: {:?}", failing.join())?;
+        panic::set_hook(default_hook);
+
+        let after = pool.spawn(|| "workers are still alive");
+        writeln!(out, "Next task: {:?}", after.join())?;
+
+        writeln!(out, "\n===== Graceful Shutdown =====")?;
+        for i in 0..3 {
+            let printer = output.printer();
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(50));
+                printer.println(format!("Queued job {} finished before shutdown completed", i));
+            });
+        }
+        drop(pool);
+        output.drain_into(out)?;
+        writeln!(out, "Pool dropped, all workers joined")?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ThreadPoolDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn results_match_submission_order_regardless_of_completion() {
+        let pool = ThreadPool::new(4);
+        let handles: Vec<_> = (0..16u64)
+            .map(|i| {
+                pool.spawn(move || {
+                    thread::sleep(Duration::from_millis((16 - i) * 2));
+                    i * 10
+                })
+            })
+            .collect();
+        let results: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results, (0..16).map(|i| i * 10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn every_job_runs_exactly_once() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let pool = ThreadPool::new(3);
+        for _ in 0..100 {
+            let counter = Arc::clone(&counter);
+            pool.execute(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        pool.shutdown();
+        assert_eq!(counter.load(Ordering::SeqCst), 100);
+    }
+
+    #[test]
+    fn drop_drains_queue_before_joining() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        {
+            let pool = ThreadPool::new(1);
+            for _ in 0..10 {
+                let counter = Arc::clone(&counter);
+                pool.execute(move || {
+                    thread::sleep(Duration::from_millis(5));
+                    counter.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+        }
+        assert_eq!(counter.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn panicking_task_is_reported_and_worker_survives() {
+        let pool = ThreadPool::new(1);
+        let failing = pool.spawn(|| -> i32 { panic!("boom") });
+        assert_eq!(
+            failing.join(),
+            Err(TaskPanicked {
+                message: "boom".to_string()
+            })
+        );
+
+        // The single worker must still be alive to run this
+        let ok = pool.spawn(|| 42);
+        assert_eq!(ok.join(), Ok(42));
+    }
+
+    #[test]
+    fn formatted_panic_messages_are_captured() {
+        let pool = ThreadPool::new(2);
+        let failing = pool.spawn(|| -> u8 { panic!("bad value {}", 7) });
+        assert_eq!(failing.join().unwrap_err().message, "bad value 7");
+    }
+
+    #[test]
+    fn try_join_does_not_block() {
+        let pool = ThreadPool::new(1);
+        let (gate_tx, gate_rx) = mpsc::channel::<()>();
+        let handle = pool.spawn(move || {
+            gate_rx.recv().unwrap();
+            "done"
+        });
+        assert!(handle.try_join().is_none());
+        gate_tx.send(()).unwrap();
+        assert_eq!(handle.join(), Ok("done"));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn zero_sized_pool_is_rejected() {
+        ThreadPool::new(0);
+    }
+}
@@ -0,0 +1,326 @@
+//! Work-Stealing Scheduler in Rust
+//!
+//! In a work-stealing scheduler every worker owns a double-ended queue of tasks. The owner
+//! pushes and pops at the back (LIFO, which keeps recently split work hot in cache), while
+//! idle workers steal from the front (FIFO, which hands them the oldest and usually largest
+//! chunks). Rayon, Tokio and Go's runtime all use variations of this idea.
+//!
+//! Production deques such as Chase-Lev are lock-free. To keep the idea readable this file
+//! uses a simplified deque guarded by a `Mutex`; the scheduling logic is the same.
+//!
+//! This file demonstrates:
+//! - A per-worker deque with owner `push`/`pop` and thief `steal`
+//! - Workers that run their own tasks first and steal from siblings when idle
+//! - Fork-join tasks that spawn subtasks onto the current worker's deque
+//! - A parallel sum built on top, with per-worker execution and steal statistics
+
+use demo::Demo;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// ========== Work-Stealing Deque ==========
+
+/// Mutex-guarded deque: the owner works at the back, thieves take from the front
+pub struct WorkStealingDeque<T> {
+    inner: Mutex<VecDeque<T>>,
+}
+
+impl<T> WorkStealingDeque<T> {
+    pub fn new() -> Self {
+        WorkStealingDeque {
+            inner: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Owner pushes newly created work onto the back
+    pub fn push(&self, item: T) {
+        self.inner.lock().unwrap().push_back(item);
+    }
+
+    /// Owner takes the most recently pushed item
+    pub fn pop(&self) -> Option<T> {
+        self.inner.lock().unwrap().pop_back()
+    }
+
+    /// Another worker takes the oldest item
+    pub fn steal(&self) -> Option<T> {
+        self.inner.lock().unwrap().pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for WorkStealingDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== Scheduler ==========
+
+type Task = Box<dyn FnOnce(&WorkerContext) + Send + 'static>;
+
+struct Shared {
+    deques: Vec<WorkStealingDeque<Task>>,
+    /// Tasks spawned but not yet finished; workers exit when this reaches zero
+    pending: AtomicUsize,
+}
+
+/// Handle given to every running task so it can fork more work
+pub struct WorkerContext {
+    index: usize,
+    shared: Arc<Shared>,
+}
+
+impl WorkerContext {
+    /// Index of the worker running the current task
+    pub fn worker_index(&self) -> usize {
+        self.index
+    }
+
+    /// Fork a subtask onto this worker's deque, where siblings may steal it
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: FnOnce(&WorkerContext) + Send + 'static,
+    {
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+        self.shared.deques[self.index].push(Box::new(task));
+    }
+}
+
+/// What each worker did during a run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkerStats {
+    pub executed: usize,
+    pub stolen: usize,
+}
+
+/// Fixed set of workers sharing work through per-worker deques
+pub struct Scheduler {
+    workers: usize,
+}
+
+impl Scheduler {
+    pub fn new(workers: usize) -> Self {
+        assert!(workers > 0, "scheduler needs at least one worker");
+        Scheduler { workers }
+    }
+
+    /// Run `root` on worker 0 and return once it and every task it forked have finished
+    pub fn run<F>(&self, root: F) -> Vec<WorkerStats>
+    where
+        F: FnOnce(&WorkerContext) + Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            deques: (0..self.workers).map(|_| WorkStealingDeque::new()).collect(),
+            pending: AtomicUsize::new(1),
+        });
+        shared.deques[0].push(Box::new(root));
+
+        let handles: Vec<_> = (0..self.workers)
+            .map(|index| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || worker_loop(WorkerContext { index, shared }))
+            })
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    }
+}
+
+fn worker_loop(ctx: WorkerContext) -> WorkerStats {
+    let mut stats = WorkerStats::default();
+    let count = ctx.shared.deques.len();
+
+    loop {
+        // Own work first, then try each sibling starting with the next one
+        let mut task = ctx.shared.deques[ctx.index].pop();
+        if task.is_none() {
+            task = (1..count)
+                .map(|offset| (ctx.index + offset) % count)
+                .find_map(|victim| ctx.shared.deques[victim].steal());
+            if task.is_some() {
+                stats.stolen += 1;
+            }
+        }
+
+        match task {
+            Some(task) => {
+                task(&ctx);
+                stats.executed += 1;
+                ctx.shared.pending.fetch_sub(1, Ordering::SeqCst);
+            }
+            None if ctx.shared.pending.load(Ordering::SeqCst) == 0 => break,
+            None => thread::yield_now(),
+        }
+    }
+    stats
+}
+
+// ========== Fork-Join Parallel Sum ==========
+
+/// Sum `data[range]`: split in half until at most `threshold` items remain, forking the
+/// right half as a stealable task and continuing with the left half inline
+fn sum_range(
+    ctx: &WorkerContext,
+    data: Arc<Vec<u64>>,
+    start: usize,
+    end: usize,
+    threshold: usize,
+    total: Arc<AtomicU64>,
+) {
+    if end - start <= threshold {
+        let partial: u64 = data[start..end].iter().sum();
+        total.fetch_add(partial, Ordering::SeqCst);
+        return;
+    }
+
+    let mid = start + (end - start) / 2;
+    let (right_data, right_total) = (Arc::clone(&data), Arc::clone(&total));
+    ctx.spawn(move |ctx| sum_range(ctx, right_data, mid, end, threshold, right_total));
+    sum_range(ctx, data, start, mid, threshold, total);
+}
+
+/// Sum a vector on `workers` threads, returning the sum and per-worker statistics
+pub fn parallel_sum(data: Vec<u64>, workers: usize, threshold: usize) -> (u64, Vec<WorkerStats>) {
+    let data = Arc::new(data);
+    let total = Arc::new(AtomicU64::new(0));
+    let len = data.len();
+
+    let root_total = Arc::clone(&total);
+    let stats = Scheduler::new(workers).run(move |ctx| {
+        sum_range(ctx, data, 0, len, threshold.max(1), root_total)
+    });
+
+    (total.load(Ordering::SeqCst), stats)
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct WorkStealingDemo;
+
+impl Demo for WorkStealingDemo {
+    fn name(&self) -> &'static str {
+        "work_stealing"
+    }
+
+    fn category(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn description(&self) -> &'static str {
+        "Per-worker deques with stealing"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Deque Semantics =====")?;
+        let deque = WorkStealingDeque::new();
+        for task in ["task-1", "task-2", "task-3"] {
+            deque.push(task);
+        }
+        writeln!(out, "Owner pops newest: {:?}", deque.pop())?;
+        writeln!(out, "Thief steals oldest: {:?}", deque.steal())?;
+        writeln!(out, "Remaining: {}", deque.len())?;
+
+        writeln!(out, "\n===== Parallel Sum =====")?;
+        let n = 20_000_000u64;
+        let data: Vec<u64> = (1..=n).collect();
+        let (sum, stats) = parallel_sum(data, 4, 10_000);
+        writeln!(out, "Sum of 1..={} = {} (expected {})", n, sum, n * (n + 1) / 2)?;
+        for (worker, s) in stats.iter().enumerate() {
+            writeln!(out, "worker {}: executed {:>4} tasks, stole {:>3}", worker, s.executed, s.stolen)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&WorkStealingDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_is_lifo_and_thief_is_fifo() {
+        let deque = WorkStealingDeque::new();
+        for i in 1..=4 {
+            deque.push(i);
+        }
+        assert_eq!(deque.pop(), Some(4));
+        assert_eq!(deque.steal(), Some(1));
+        assert_eq!(deque.pop(), Some(3));
+        assert_eq!(deque.steal(), Some(2));
+        assert!(deque.is_empty());
+        assert_eq!(deque.pop(), None);
+        assert_eq!(deque.steal(), None);
+    }
+
+    #[test]
+    fn parallel_sum_matches_sequential() {
+        for &(n, workers, threshold) in &[(0u64, 2, 10), (1, 1, 1), (1_000, 3, 7), (100_000, 4, 1_000)] {
+            let data: Vec<u64> = (1..=n).collect();
+            let (sum, _) = parallel_sum(data, workers, threshold);
+            assert_eq!(sum, n * (n + 1) / 2, "n={} workers={}", n, workers);
+        }
+    }
+
+    #[test]
+    fn every_forked_task_runs_exactly_once() {
+        let executed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&executed);
+
+        // Build a binary tree of tasks 10 levels deep: 2^11 - 1 tasks in total
+        fn fork(ctx: &WorkerContext, depth: u32, counter: Arc<AtomicUsize>) {
+            counter.fetch_add(1, Ordering::SeqCst);
+            if depth == 0 {
+                return;
+            }
+            for _ in 0..2 {
+                let counter = Arc::clone(&counter);
+                ctx.spawn(move |ctx| fork(ctx, depth - 1, counter));
+            }
+        }
+
+        let stats = Scheduler::new(4).run(move |ctx| fork(ctx, 10, counter));
+        let expected = (1 << 11) - 1;
+        assert_eq!(executed.load(Ordering::SeqCst), expected);
+        assert_eq!(stats.iter().map(|s| s.executed).sum::<usize>(), expected);
+        assert!(stats.iter().map(|s| s.stolen).sum::<usize>() <= expected);
+    }
+
+    #[test]
+    fn single_worker_never_steals() {
+        let data: Vec<u64> = (0..10_000).collect();
+        let (sum, stats) = parallel_sum(data, 1, 16);
+        assert_eq!(sum, (0..10_000u64).sum::<u64>());
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].stolen, 0);
+    }
+
+    #[test]
+    fn tasks_report_their_worker_index() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let root_seen = Arc::clone(&seen);
+        Scheduler::new(3).run(move |ctx| {
+            for _ in 0..30 {
+                let seen = Arc::clone(&root_seen);
+                ctx.spawn(move |ctx| seen.lock().unwrap().push(ctx.worker_index()));
+            }
+        });
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 30);
+        assert!(seen.iter().all(|&w| w < 3));
+    }
+}
@@ -0,0 +1,485 @@
+//! Cuckoo Hashing Implementation in Rust
+//!
+//! Cuckoo hashing keeps every key in one of exactly two candidate slots, one per table.
+//! Lookups and removals therefore cost at most two probes (plus a scan of a tiny stash).
+//! Insertion places the new key in its first slot and, if that slot is taken, "kicks out"
+//! the occupant to its alternate slot in the other table, repeating until every key has a
+//! home or a displacement limit is hit.
+//!
+//! This file demonstrates:
+//! - Two-table cuckoo hashing with independently seeded hash functions
+//! - The displacement (kick-out) loop with a bounded number of moves to detect cycles
+//! - A small stash that absorbs the occasional key that cannot be placed
+//! - Rehashing with fresh seeds (and growing) when both the loop and the stash fail
+//! - How insertion failures explode once the load factor passes 50%
+
+use demo::Demo;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+
+// ========== Configuration and Statistics ==========
+
+/// Tuning knobs for the cuckoo table
+#[derive(Debug, Clone, Copy)]
+pub struct CuckooConfig {
+    /// Maximum number of kick-outs before an insertion is treated as a cycle
+    pub max_displacements: usize,
+    /// Number of entries the overflow stash may hold
+    pub stash_capacity: usize,
+    /// Rehash attempts with fresh seeds before the tables are doubled
+    pub max_rehashes: usize,
+    /// Seed for the generator that picks hash function seeds
+    pub seed: u64,
+}
+
+impl Default for CuckooConfig {
+    fn default() -> Self {
+        CuckooConfig {
+            max_displacements: 32,
+            stash_capacity: 4,
+            max_rehashes: 4,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+}
+
+/// Counters describing what the table had to do to place keys
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CuckooStats {
+    pub displacements: usize,
+    pub stash_insertions: usize,
+    pub rehashes: usize,
+    pub resizes: usize,
+}
+
+// ========== Cuckoo Hash Map ==========
+
+/// A two-table cuckoo hash map with a stash fallback
+pub struct CuckooHashMap<K, V> {
+    tables: [Vec<Option<(K, V)>>; 2],
+    seeds: [u64; 2],
+    stash: Vec<(K, V)>,
+    len: usize,
+    config: CuckooConfig,
+    rng_state: u64,
+    stats: CuckooStats,
+}
+
+impl<K: Hash + Eq, V> CuckooHashMap<K, V> {
+    /// Create an empty map with a small default capacity
+    pub fn new() -> Self {
+        Self::with_config(16, CuckooConfig::default())
+    }
+
+    /// Create an empty map where each of the two tables has `capacity` slots
+    pub fn with_config(capacity: usize, config: CuckooConfig) -> Self {
+        let capacity = capacity.max(1);
+        let mut map = CuckooHashMap {
+            tables: [empty_table(capacity), empty_table(capacity)],
+            seeds: [0, 0],
+            stash: Vec::with_capacity(config.stash_capacity),
+            len: 0,
+            config,
+            rng_state: config.seed | 1,
+            stats: CuckooStats::default(),
+        };
+        map.seeds = [map.next_seed(), map.next_seed()];
+        map
+    }
+
+    /// Number of stored entries
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the map holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Total number of table slots (the stash is not counted)
+    pub fn capacity(&self) -> usize {
+        self.tables[0].len() + self.tables[1].len()
+    }
+
+    /// Fraction of table slots that are occupied
+    pub fn load_factor(&self) -> f64 {
+        (self.len - self.stash.len()) as f64 / self.capacity() as f64
+    }
+
+    /// Number of entries currently parked in the stash
+    pub fn stash_len(&self) -> usize {
+        self.stash.len()
+    }
+
+    /// Counters describing displacement, stash and rehash activity
+    pub fn stats(&self) -> CuckooStats {
+        self.stats
+    }
+
+    /// Look up a value: at most two table probes plus the stash scan
+    pub fn get(&self, key: &K) -> Option<&V> {
+        for t in 0..2 {
+            let idx = self.slot(t, key);
+            if let Some((k, v)) = &self.tables[t][idx] {
+                if k == key {
+                    return Some(v);
+                }
+            }
+        }
+        self.stash.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Whether the key is present
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Remove a key, returning its value if it was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        for t in 0..2 {
+            let idx = self.slot(t, key);
+            if matches!(&self.tables[t][idx], Some((k, _)) if k == key) {
+                self.len -= 1;
+                return self.tables[t][idx].take().map(|(_, v)| v);
+            }
+        }
+        let pos = self.stash.iter().position(|(k, _)| k == key)?;
+        self.len -= 1;
+        Some(self.stash.swap_remove(pos).1)
+    }
+
+    /// Insert a key/value pair, rehashing or growing the tables as needed.
+    /// Returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let value = match self.replace_existing(&key, value) {
+            Ok(old) => return Some(old),
+            Err(value) => value,
+        };
+
+        let mut homeless = match self.place(key, value) {
+            Ok(()) => {
+                self.len += 1;
+                return None;
+            }
+            Err(entry) => entry,
+        };
+
+        // Cycle detected and the stash is full: pick new hash functions and retry,
+        // doubling the tables if several fresh seeds still cannot place everything
+        let mut capacity = self.tables[0].len();
+        let mut attempts = 0;
+        loop {
+            if attempts == self.config.max_rehashes {
+                capacity *= 2;
+                attempts = 0;
+                self.stats.resizes += 1;
+            }
+            attempts += 1;
+            match self.rebuild(capacity, homeless) {
+                Ok(()) => {
+                    self.len += 1;
+                    return None;
+                }
+                Err(entry) => homeless = entry,
+            }
+        }
+    }
+
+    /// Insert without ever rehashing or growing.
+    ///
+    /// On failure the displacement loop has already reshuffled the tables, so the entry
+    /// handed back is whichever key was left without a slot (not necessarily `key`).
+    /// This is the primitive used to measure failure rates at a given load factor.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        let value = match self.replace_existing(&key, value) {
+            Ok(old) => return Ok(Some(old)),
+            Err(value) => value,
+        };
+        self.place(key, value)?;
+        self.len += 1;
+        Ok(None)
+    }
+
+    /// Overwrite the value if the key is already stored, returning the old one.
+    /// Hands the value back untouched (as `Err`) when the key is new.
+    fn replace_existing(&mut self, key: &K, value: V) -> Result<V, V> {
+        for t in 0..2 {
+            let idx = self.slot(t, key);
+            if let Some((k, v)) = &mut self.tables[t][idx] {
+                if k == key {
+                    return Ok(std::mem::replace(v, value));
+                }
+            }
+        }
+        match self.stash.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => Ok(std::mem::replace(v, value)),
+            None => Err(value),
+        }
+    }
+
+    /// Run the displacement loop for a key known to be absent.
+    /// Falls back to the stash and reports the homeless entry if that is full too.
+    fn place(&mut self, key: K, value: V) -> Result<(), (K, V)> {
+        let mut entry = (key, value);
+        let mut table = 0;
+
+        for _ in 0..=self.config.max_displacements {
+            let idx = self.slot(table, &entry.0);
+            match self.tables[table][idx].replace(entry) {
+                None => return Ok(()),
+                Some(evicted) => {
+                    self.stats.displacements += 1;
+                    entry = evicted;
+                    table = 1 - table;
+                }
+            }
+        }
+
+        if self.stash.len() < self.config.stash_capacity {
+            self.stats.stash_insertions += 1;
+            self.stash.push(entry);
+            return Ok(());
+        }
+        Err(entry)
+    }
+
+    /// Re-insert every entry (plus `extra`) into fresh tables with new seeds
+    fn rebuild(&mut self, capacity: usize, extra: (K, V)) -> Result<(), (K, V)> {
+        self.stats.rehashes += 1;
+        let old_tables = std::mem::replace(
+            &mut self.tables,
+            [empty_table(capacity), empty_table(capacity)],
+        );
+        let old_stash = std::mem::take(&mut self.stash);
+        self.seeds = [self.next_seed(), self.next_seed()];
+
+        let entries = old_tables
+            .into_iter()
+            .flatten()
+            .flatten()
+            .chain(old_stash)
+            .chain(std::iter::once(extra));
+
+        // Keep placing the remaining entries even after a failure so nothing is lost;
+        // only the first homeless entry is reported and the rest go to the stash.
+        let mut failed = None;
+        for (k, v) in entries {
+            if let Err(entry) = self.place(k, v) {
+                match failed {
+                    None => failed = Some(entry),
+                    Some(_) => self.stash.push(entry),
+                }
+            }
+        }
+        match failed {
+            None => Ok(()),
+            Some(entry) => Err(entry),
+        }
+    }
+
+    /// Slot index of `key` in table `t`
+    fn slot(&self, t: usize, key: &K) -> usize {
+        let mut hasher = DefaultHasher::new();
+        self.seeds[t].hash(&mut hasher);
+        key.hash(&mut hasher);
+        (hasher.finish() % self.tables[t].len() as u64) as usize
+    }
+
+    /// xorshift64 step used to draw new hash seeds
+    fn next_seed(&mut self) -> u64 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        x
+    }
+}
+
+impl<K: Hash + Eq, V> Default for CuckooHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn empty_table<K, V>(capacity: usize) -> Vec<Option<(K, V)>> {
+    (0..capacity).map(|_| None).collect()
+}
+
+// ========== Load Factor Experiment ==========
+
+/// Fill a fixed-size table (no rehashing) up to `load_factor` with sequential keys and
+/// return how many `try_insert` calls failed along the way
+fn count_failures(slots_per_table: usize, load_factor: f64, config: CuckooConfig) -> usize {
+    let mut map: CuckooHashMap<u64, u64> = CuckooHashMap::with_config(slots_per_table, config);
+    let target = (2.0 * slots_per_table as f64 * load_factor) as u64;
+    let mut failures = 0;
+
+    for key in 0..target {
+        if map.try_insert(key, key).is_err() {
+            failures += 1;
+        }
+    }
+    failures
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct CuckooHashDemo;
+
+impl Demo for CuckooHashDemo {
+    fn name(&self) -> &'static str {
+        "cuckoo_hash"
+    }
+
+    fn category(&self) -> &'static str {
+        "data-structures"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cuckoo hash table with load-factor experiments"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Basic Operations =====")?;
+        let mut map = CuckooHashMap::new();
+        map.insert("apple", 3);
+        map.insert("banana", 5);
+        map.insert("cherry", 7);
+        writeln!(out, "banana -> {:?}", map.get(&"banana"))?;
+        writeln!(out, "Replaced apple, old value: {:?}", map.insert("apple", 4))?;
+        writeln!(out, "Removed cherry: {:?}", map.remove(&"cherry"))?;
+        writeln!(out, "Length: {}, capacity: {}", map.len(), map.capacity())?;
+
+        writeln!(out, "\n===== Growing Under Load =====")?;
+        let mut numbers = CuckooHashMap::new();
+        for i in 0..10_000u64 {
+            numbers.insert(i, i * i);
+        }
+        writeln!(out, "Stored {} entries in {} slots (load factor {:.2})",
+                      numbers.len(), numbers.capacity(), numbers.load_factor())?;
+        writeln!(out, "Stats: {:?}", numbers.stats())?;
+
+        writeln!(out, "\n===== Insertion Failures vs Load Factor =====")?;
+        let no_stash = CuckooConfig { stash_capacity: 0, ..CuckooConfig::default() };
+        writeln!(out, "{:>12} {:>16} {:>16}", "load factor", "fails (stash 0)", "fails (stash 4)")?;
+        for &lf in &[0.30, 0.40, 0.45, 0.50, 0.55, 0.60, 0.75, 0.90] {
+            writeln!(out, "{:>12.2} {:>16} {:>16}",
+                          lf,
+                          count_failures(1024, lf, no_stash),
+                          count_failures(1024, lf, CuckooConfig::default()))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&CuckooHashDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = CuckooHashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("b", 2), None);
+        assert_eq!(map.get(&"a"), Some(&1));
+        assert_eq!(map.get(&"b"), Some(&2));
+        assert_eq!(map.get(&"c"), None);
+        assert_eq!(map.remove(&"a"), Some(1));
+        assert_eq!(map.remove(&"a"), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn insert_overwrites_existing_key() {
+        let mut map = CuckooHashMap::new();
+        map.insert(7, "old");
+        assert_eq!(map.insert(7, "new"), Some("old"));
+        assert_eq!(map.get(&7), Some(&"new"));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn growing_keeps_every_entry() {
+        let mut map = CuckooHashMap::with_config(4, CuckooConfig::default());
+        for i in 0..5_000u32 {
+            map.insert(i, i * 2);
+        }
+        assert_eq!(map.len(), 5_000);
+        assert!(map.stats().rehashes > 0);
+        assert!(map.load_factor() <= 1.0);
+        for i in 0..5_000u32 {
+            assert_eq!(map.get(&i), Some(&(i * 2)), "missing key {}", i);
+        }
+        for i in (0..5_000u32).step_by(2) {
+            assert_eq!(map.remove(&i), Some(i * 2));
+        }
+        assert_eq!(map.len(), 2_500);
+        assert!(!map.contains_key(&0));
+        assert!(map.contains_key(&1));
+    }
+
+    #[test]
+    fn stash_absorbs_entries_that_cannot_be_placed() {
+        // A single slot per table means the third key can never find a table home
+        let config = CuckooConfig { stash_capacity: 2, ..CuckooConfig::default() };
+        let mut map = CuckooHashMap::with_config(1, config);
+        for key in 0..4 {
+            assert!(map.try_insert(key, key).is_ok());
+        }
+        assert_eq!(map.stash_len(), 2);
+        assert_eq!(map.stats().stash_insertions, 2);
+        for key in 0..4 {
+            assert_eq!(map.get(&key), Some(&key));
+        }
+        for key in 0..4 {
+            assert_eq!(map.remove(&key), Some(key));
+        }
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn try_insert_reports_homeless_entry_when_full() {
+        let config = CuckooConfig { stash_capacity: 0, ..CuckooConfig::default() };
+        let mut map = CuckooHashMap::with_config(1, config);
+        assert!(map.try_insert(1, 'a').is_ok());
+        assert!(map.try_insert(2, 'b').is_ok());
+        let (key, _) = map.try_insert(3, 'c').unwrap_err();
+        assert!((1..=3).contains(&key));
+        assert_eq!(map.len(), 2);
+        assert!(!map.contains_key(&key));
+    }
+
+    #[test]
+    fn failures_are_rare_below_half_load() {
+        let config = CuckooConfig { stash_capacity: 0, ..CuckooConfig::default() };
+        assert_eq!(count_failures(4096, 0.30, config), 0);
+        assert!(count_failures(4096, 0.40, config) <= 2);
+    }
+
+    #[test]
+    fn failures_explode_past_half_load() {
+        let config = CuckooConfig { stash_capacity: 0, ..CuckooConfig::default() };
+        let low = count_failures(4096, 0.40, config);
+        let high = count_failures(4096, 0.70, config);
+        let very_high = count_failures(4096, 0.90, config);
+        assert!(high > 100, "expected many failures at 70% load, got {}", high);
+        assert!(very_high > high);
+        assert!(high > low);
+    }
+
+    #[test]
+    fn stash_reduces_failures_near_threshold() {
+        let no_stash = CuckooConfig { stash_capacity: 0, ..CuckooConfig::default() };
+        let stash = CuckooConfig { stash_capacity: 8, ..CuckooConfig::default() };
+        assert!(count_failures(4096, 0.48, stash) <= count_failures(4096, 0.48, no_stash));
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    data_structures::cuckoo_hash::run_demo();
+}
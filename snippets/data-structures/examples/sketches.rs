@@ -0,0 +1,3 @@
+fn main() {
+    data_structures::sketches::run_demo();
+}
@@ -0,0 +1,15 @@
+//! Data structure snippets from the tech notes.
+//!
+//! Each module mirrors one topic directory and exposes its types plus a `Demo`
+//! implementation, registered in `DEMOS`, whose `run_demo` wrapper the matching example
+//! binary calls.
+
+#[path = "cuckoo-hashing/cuckoo_hash.rs"]
+pub mod cuckoo_hash;
+#[path = "probabilistic-counting/sketches.rs"]
+pub mod sketches;
+
+use demo::Demo;
+
+/// Every demo in this crate, for `tech-notes` and the other front ends.
+pub static DEMOS: &[&dyn Demo] = &[&cuckoo_hash::CuckooHashDemo, &sketches::SketchesDemo];
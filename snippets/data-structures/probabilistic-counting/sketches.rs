@@ -0,0 +1,407 @@
+//! Probabilistic Counting Sketches in Rust
+//!
+//! Sketches trade exactness for tiny, fixed memory. Instead of storing every item they keep
+//! a compact summary that answers approximate queries with provable error bounds.
+//!
+//! This file demonstrates two classic sketches:
+//! - Count-Min Sketch: estimates how often an item appeared in a stream. It never
+//!   underestimates, and with probability 1 - delta it overestimates by at most
+//!   epsilon * N, where N is the total number of items added.
+//! - HyperLogLog: estimates how many distinct items a stream contained. With 2^p registers
+//!   the relative standard error is about 1.04 / sqrt(2^p).
+
+use demo::Demo;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+
+/// Hash an item together with a seed so one hasher can act as a family of hash functions
+fn seeded_hash<T: Hash + ?Sized>(item: &T, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+// ========== Count-Min Sketch ==========
+
+/// Frequency estimator backed by a `depth x width` grid of counters
+#[derive(Debug, Clone)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counters: Vec<u64>,
+    total: u64,
+}
+
+impl CountMinSketch {
+    /// Create a sketch with explicit dimensions
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0, "sketch dimensions must be positive");
+        CountMinSketch {
+            width,
+            depth,
+            counters: vec![0; width * depth],
+            total: 0,
+        }
+    }
+
+    /// Size the sketch so estimates exceed the true count by at most `epsilon * N`
+    /// with probability at least `1 - delta`
+    pub fn with_error_bounds(epsilon: f64, delta: f64) -> Self {
+        assert!(epsilon > 0.0 && delta > 0.0 && delta < 1.0, "invalid error bounds");
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth = (1.0 / delta).ln().ceil() as usize;
+        Self::new(width, depth.max(1))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total number of occurrences added so far (N)
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Additive error guaranteed (with probability 1 - delta) for the current stream
+    pub fn error_bound(&self) -> f64 {
+        std::f64::consts::E / self.width as f64 * self.total as f64
+    }
+
+    /// Record `count` occurrences of an item
+    pub fn add<T: Hash + ?Sized>(&mut self, item: &T, count: u64) {
+        for row in 0..self.depth {
+            let idx = self.index(item, row);
+            self.counters[idx] += count;
+        }
+        self.total += count;
+    }
+
+    /// Estimated number of occurrences: the minimum over all rows
+    pub fn estimate<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        (0..self.depth)
+            .map(|row| self.counters[self.index(item, row)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Combine another sketch with identical dimensions into this one
+    pub fn merge(&mut self, other: &CountMinSketch) -> Result<(), String> {
+        if self.width != other.width || self.depth != other.depth {
+            return Err(format!(
+                "cannot merge {}x{} sketch into {}x{} sketch",
+                other.depth, other.width, self.depth, self.width
+            ));
+        }
+        for (a, b) in self.counters.iter_mut().zip(&other.counters) {
+            *a += b;
+        }
+        self.total += other.total;
+        Ok(())
+    }
+
+    fn index<T: Hash + ?Sized>(&self, item: &T, row: usize) -> usize {
+        let column = (seeded_hash(item, row as u64) % self.width as u64) as usize;
+        row * self.width + column
+    }
+}
+
+// ========== HyperLogLog ==========
+
+/// Cardinality estimator with `2^precision` six-bit registers
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Create an estimator; `precision` must be between 4 and 16 inclusive
+    pub fn new(precision: u8) -> Result<Self, String> {
+        if !(4..=16).contains(&precision) {
+            return Err(format!("precision must be in 4..=16, got {}", precision));
+        }
+        Ok(HyperLogLog {
+            precision,
+            registers: vec![0; 1 << precision],
+        })
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Number of registers (m = 2^precision)
+    pub fn register_count(&self) -> usize {
+        self.registers.len()
+    }
+
+    /// Theoretical relative standard error: 1.04 / sqrt(m)
+    pub fn standard_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    /// Record an item; duplicates do not change the estimate
+    pub fn add<T: Hash + ?Sized>(&mut self, item: &T) {
+        let hash = seeded_hash(item, 0);
+        let p = self.precision as u32;
+        // The first p bits pick the register, the rest feed the leading-zero count
+        let index = (hash >> (64 - p)) as usize;
+        let remaining = hash << p;
+        let rank = (remaining.leading_zeros() + 1).min(64 - p + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Estimated number of distinct items
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        // Small-range correction: fall back to linear counting while registers are empty
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+
+    /// Union with another estimator of the same precision
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), String> {
+        if self.precision != other.precision {
+            return Err(format!(
+                "cannot merge precision {} into precision {}",
+                other.precision, self.precision
+            ));
+        }
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            *a = (*a).max(b);
+        }
+        Ok(())
+    }
+}
+
+// ========== Synthetic Streams ==========
+
+/// Small xorshift generator so the demo and tests are reproducible
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Skewed stream over `distinct` items: item i appears roughly proportional to 1/(i+1)
+fn skewed_stream(distinct: u64, length: usize, seed: u64) -> Vec<u64> {
+    let mut rng = XorShift(seed | 1);
+    let harmonic: f64 = (1..=distinct).map(|i| 1.0 / i as f64).sum();
+    (0..length)
+        .map(|_| {
+            let mut target = (rng.next() as f64 / u64::MAX as f64) * harmonic;
+            for i in 0..distinct {
+                target -= 1.0 / (i + 1) as f64;
+                if target <= 0.0 {
+                    return i;
+                }
+            }
+            distinct - 1
+        })
+        .collect()
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct SketchesDemo;
+
+impl Demo for SketchesDemo {
+    fn name(&self) -> &'static str {
+        "sketches"
+    }
+
+    fn category(&self) -> &'static str {
+        "data-structures"
+    }
+
+    fn description(&self) -> &'static str {
+        "HyperLogLog and count-min sketch"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Count-Min Sketch =====")?;
+        let stream = skewed_stream(1_000, 100_000, 42);
+        let mut cms = CountMinSketch::with_error_bounds(0.001, 0.01);
+        let mut exact = std::collections::HashMap::new();
+        for item in &stream {
+            cms.add(item, 1);
+            *exact.entry(*item).or_insert(0u64) += 1;
+        }
+        writeln!(out, "Sketch size: {} rows x {} columns = {} counters",
+                      cms.depth(), cms.width(), cms.depth() * cms.width())?;
+        writeln!(out, "Error bound (epsilon * N): {:.1}", cms.error_bound())?;
+        for item in [0u64, 1, 10, 100, 999] {
+            writeln!(out, "item {:>4}: exact {:>6}, estimate {:>6}",
+                          item, exact.get(&item).copied().unwrap_or(0), cms.estimate(&item))?;
+        }
+
+        writeln!(out, "\n===== HyperLogLog =====")?;
+        for precision in [6u8, 10, 14] {
+            let mut hll = HyperLogLog::new(precision).unwrap();
+            let n = 250_000u64;
+            for i in 0..n {
+                hll.add(&i);
+                hll.add(&i); // duplicates are ignored
+            }
+            let estimate = hll.estimate();
+            writeln!(out, "precision {:>2} ({:>5} registers): estimate {:>9.0} for {} distinct (error {:+.2}%, expected ±{:.2}%)",
+                          precision, hll.register_count(), estimate, n,
+                          (estimate - n as f64) / n as f64 * 100.0,
+                          hll.standard_error() * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&SketchesDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn count_min_dimensions_follow_error_bounds() {
+        let cms = CountMinSketch::with_error_bounds(0.01, 0.01);
+        assert_eq!(cms.width(), 272); // ceil(e / 0.01)
+        assert_eq!(cms.depth(), 5); // ceil(ln(100))
+    }
+
+    #[test]
+    fn count_min_never_underestimates() {
+        let stream = skewed_stream(500, 20_000, 7);
+        let mut cms = CountMinSketch::new(64, 4);
+        let mut exact: HashMap<u64, u64> = HashMap::new();
+        for item in &stream {
+            cms.add(item, 1);
+            *exact.entry(*item).or_insert(0) += 1;
+        }
+        for (item, count) in &exact {
+            assert!(cms.estimate(item) >= *count);
+        }
+        assert_eq!(cms.total(), 20_000);
+    }
+
+    #[test]
+    fn count_min_error_within_bound_with_high_probability() {
+        let epsilon = 0.005;
+        let delta = 0.01;
+        let stream = skewed_stream(2_000, 50_000, 99);
+        let mut cms = CountMinSketch::with_error_bounds(epsilon, delta);
+        let mut exact: HashMap<u64, u64> = HashMap::new();
+        for item in &stream {
+            cms.add(item, 1);
+            *exact.entry(*item).or_insert(0) += 1;
+        }
+
+        let bound = epsilon * stream.len() as f64;
+        let violations = (0..2_000u64)
+            .filter(|item| {
+                let truth = exact.get(item).copied().unwrap_or(0);
+                (cms.estimate(item) - truth) as f64 > bound
+            })
+            .count();
+        // At most a delta fraction of queries may exceed the bound
+        assert!(violations as f64 <= delta * 2_000.0, "{} violations", violations);
+    }
+
+    #[test]
+    fn count_min_merge_adds_counts() {
+        let mut a = CountMinSketch::new(128, 3);
+        let mut b = CountMinSketch::new(128, 3);
+        a.add("x", 5);
+        b.add("x", 7);
+        a.merge(&b).unwrap();
+        assert!(a.estimate("x") >= 12);
+        assert_eq!(a.total(), 12);
+        assert!(a.merge(&CountMinSketch::new(64, 3)).is_err());
+    }
+
+    #[test]
+    fn hyperloglog_rejects_bad_precision() {
+        assert!(HyperLogLog::new(3).is_err());
+        assert!(HyperLogLog::new(17).is_err());
+        assert!(HyperLogLog::new(4).is_ok());
+    }
+
+    #[test]
+    fn hyperloglog_empty_estimate_is_zero() {
+        let hll = HyperLogLog::new(10).unwrap();
+        assert_eq!(hll.estimate(), 0.0);
+    }
+
+    #[test]
+    fn hyperloglog_estimates_within_three_standard_errors() {
+        for precision in [8u8, 11, 14] {
+            for &n in &[500u64, 10_000, 200_000] {
+                let mut hll = HyperLogLog::new(precision).unwrap();
+                for i in 0..n {
+                    hll.add(&(i, "item"));
+                }
+                let relative_error = (hll.estimate() - n as f64).abs() / n as f64;
+                assert!(
+                    relative_error <= 3.0 * hll.standard_error(),
+                    "p={} n={} error={:.4} limit={:.4}",
+                    precision, n, relative_error, 3.0 * hll.standard_error()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hyperloglog_ignores_duplicates() {
+        let stream = skewed_stream(300, 30_000, 3);
+        let mut hll = HyperLogLog::new(12).unwrap();
+        for item in &stream {
+            hll.add(item);
+        }
+        let distinct = stream.iter().collect::<std::collections::HashSet<_>>().len() as f64;
+        assert!((hll.estimate() - distinct).abs() / distinct <= 3.0 * hll.standard_error());
+    }
+
+    #[test]
+    fn hyperloglog_merge_matches_union() {
+        let mut a = HyperLogLog::new(12).unwrap();
+        let mut b = HyperLogLog::new(12).unwrap();
+        for i in 0..20_000u64 {
+            a.add(&i);
+        }
+        for i in 10_000..30_000u64 {
+            b.add(&i);
+        }
+        a.merge(&b).unwrap();
+        let error = (a.estimate() - 30_000.0).abs() / 30_000.0;
+        assert!(error <= 3.0 * a.standard_error());
+        assert!(a.merge(&HyperLogLog::new(10).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! The `Demo` trait that makes every snippet demo discoverable.
+//!
+//! Each snippet crate implements `Demo` once per module and lists the implementations in a
+//! `pub static DEMOS: &[&dyn Demo]` in its `lib.rs`. Front ends (the `tech-notes` CLI,
+//! tests) pass those slices to `collect` instead of keeping their own table, so adding a
+//! demo means registering it in exactly one place.
+//!
+//! Demos print only through the sink `run` is given. Threads that can't borrow it print
+//! through a `ThreadOutput` instead, which the demo forwards to the sink once they are
+//! joined.
+//!
+//! Algorithms that can narrate themselves take an `ExecutionMode`: library callers pass
+//! `Silent` and get just the result, visualizers pass `Traced`, and demos pass `Animated`.
+
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// A runnable demo from one of the snippet modules.
+pub trait Demo: Sync {
+    /// Module name, e.g. `sorting_algorithms`
+    fn name(&self) -> &'static str;
+
+    /// Snippet directory the module lives in, e.g. `algorithms`
+    fn category(&self) -> &'static str;
+
+    /// One-line summary for listings
+    fn description(&self) -> &'static str;
+
+    /// Runs the demo, writing what it shows to `out`.
+    fn run(&self, out: &mut dyn Write) -> io::Result<()>;
+
+    /// What the demo computes, as JSON, for `tech-notes run --json`. `None` for demos that
+    /// only narrate.
+    #[cfg(feature = "json")]
+    fn results(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// The `<category>/<name>` id used on the command line.
+    fn id(&self) -> String {
+        format!("{}/{}", self.category(), self.name())
+    }
+
+    /// Case-insensitive substring match against the id.
+    fn matches(&self, filter: &str) -> bool {
+        self.id().to_lowercase().contains(&filter.to_lowercase())
+    }
+}
+
+/// A crate's list of demos.
+pub type Registry = &'static [&'static dyn Demo];
+
+/// Every demo from `registries`, sorted by id.
+pub fn collect(registries: &[Registry]) -> Vec<&'static dyn Demo> {
+    let mut demos: Vec<&'static dyn Demo> = registries.iter().flat_map(|r| r.iter().copied()).collect();
+    demos.sort_by_key(|demo| demo.id());
+    demos
+}
+
+/// Runs `demo` against stdout; what the example binaries' `run_demo` wrappers call.
+pub fn run_to_stdout(demo: &dyn Demo) {
+    demo.run(&mut io::stdout()).expect("failed to write demo output");
+}
+
+// ========== Output from worker threads ==========
+
+/// Collects the lines worker threads print, in the order they print them.
+pub struct ThreadOutput {
+    sender: Sender<String>,
+    lines: Receiver<String>,
+}
+
+/// A cloneable, `Send` handle threads use to print into a `ThreadOutput`.
+#[derive(Clone)]
+pub struct Printer(Sender<String>);
+
+impl ThreadOutput {
+    pub fn new() -> Self {
+        let (sender, lines) = mpsc::channel();
+        ThreadOutput { sender, lines }
+    }
+
+    /// A new handle to give to a thread.
+    pub fn printer(&self) -> Printer {
+        Printer(self.sender.clone())
+    }
+
+    /// Writes everything printed so far to `out`, one line each.
+    pub fn drain_into(&self, out: &mut dyn Write) -> io::Result<()> {
+        for line in self.lines.try_iter() {
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ThreadOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Printer {
+    /// Queues one line for the owning `ThreadOutput`.
+    pub fn println(&self, line: impl Into<String>) {
+        // Only fails once the ThreadOutput is gone, when nobody would read the line anyway
+        let _ = self.0.send(line.into());
+    }
+}
+
+// ========== Execution mode ==========
+
+/// How an algorithm that reports steps of type `S` should run.
+pub enum ExecutionMode<'a, S> {
+    /// Compute the result with no side effects
+    Silent,
+    /// Hand every step to the callback
+    Traced(&'a mut dyn FnMut(&S)),
+    /// Write every step to the sink, pausing for the delay after each one
+    Animated(&'a mut dyn Write, Duration),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo(&'static str, &'static str);
+
+    impl Demo for Echo {
+        fn name(&self) -> &'static str {
+            self.1
+        }
+        fn category(&self) -> &'static str {
+            self.0
+        }
+        fn description(&self) -> &'static str {
+            "writes its id"
+        }
+        fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+            writeln!(out, "{}", self.id())
+        }
+    }
+
+    static FIRST: &[&dyn Demo] = &[&Echo("b", "two"), &Echo("a", "one")];
+    static SECOND: &[&dyn Demo] = &[&Echo("a", "Zero")];
+
+    #[test]
+    fn collect_sorts_across_registries() {
+        let ids: Vec<String> = collect(&[FIRST, SECOND]).iter().map(|d| d.id()).collect();
+        assert_eq!(ids, ["a/Zero", "a/one", "b/two"]);
+    }
+
+    #[test]
+    fn matches_ignores_case() {
+        assert!(Echo("algorithms", "graph_traversal").matches("ALGORITHMS/GRAPH"));
+        assert!(!Echo("algorithms", "graph_traversal").matches("sorting"));
+    }
+
+    #[test]
+    fn run_writes_to_the_given_sink() {
+        let mut out = Vec::new();
+        SECOND[0].run(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a/Zero\n");
+    }
+
+    #[test]
+    fn thread_output_keeps_print_order() {
+        let output = ThreadOutput::new();
+        let printer = output.printer();
+        std::thread::spawn(move || {
+            printer.println("first");
+            printer.println(format!("second {}", 2));
+        })
+        .join()
+        .unwrap();
+        output.printer().println("third");
+
+        let mut out = Vec::new();
+        output.drain_into(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "first\nsecond 2\nthird\n");
+    }
+}
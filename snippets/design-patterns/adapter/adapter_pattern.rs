@@ -0,0 +1,388 @@
+//! Adapter Pattern Implementation in Rust
+//!
+//! The Adapter Pattern is a structural design pattern that lets code written against one
+//! interface use an object with a different, incompatible one, by putting a thin
+//! translating layer between them instead of changing either side.
+//!
+//! This example is a rental counter that only knows the `Rentable` trait, fed from
+//! sources that were never written for it:
+//! - `LegacyAdapter<L>` wraps anything implementing the old `LegacyRental` trait, which
+//!   describes itself differently and prices by the week
+//! - a blanket `impl<V: Vehicle> Rentable for V` makes every vehicle from the factory
+//!   snippet rentable with no wrapper at all, but can only use what `Vehicle` offers,
+//!   so every vehicle gets the same standard rate
+//! - `VehicleRental` is an object adapter around the factory's `Box<dyn Vehicle>`
+//!   output, carrying the price that `Vehicle` itself knows nothing about
+//! - `FleetVehicle` adapts a C-style API, with status codes, out-parameters and a
+//!   caller-owned buffer, into a safe constructor returning `Result`
+
+use crate::factory_pattern::{Car, Motorcycle, Vehicle, VehicleFactory};
+use demo::Demo;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Target Interface ==========
+
+/// What the rental counter works with
+pub trait Rentable {
+    fn listing(&self) -> String;
+
+    fn daily_rate_cents(&self) -> u32;
+
+    fn quote_cents(&self, days: u32) -> u32 {
+        self.daily_rate_cents() * days
+    }
+}
+
+/// `1234` as `$12.34`
+pub fn format_cents(cents: u32) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+// ========== Trait-to-Trait Adapter ==========
+
+/// The interface the old booking system was written against
+pub trait LegacyRental {
+    fn get_description(&self) -> String;
+
+    /// Dollars per week
+    fn weekly_price(&self) -> f64;
+}
+
+pub struct OldScooter {
+    pub code: String,
+    pub weekly_price: f64,
+}
+
+impl LegacyRental for OldScooter {
+    fn get_description(&self) -> String {
+        format!("SCOOTER/{}", self.code)
+    }
+
+    fn weekly_price(&self) -> f64 {
+        self.weekly_price
+    }
+}
+
+/// Presents any `LegacyRental` as a `Rentable`
+pub struct LegacyAdapter<L> {
+    legacy: L,
+}
+
+impl<L: LegacyRental> LegacyAdapter<L> {
+    pub fn new(legacy: L) -> Self {
+        LegacyAdapter { legacy }
+    }
+
+    pub fn into_inner(self) -> L {
+        self.legacy
+    }
+}
+
+impl<L: LegacyRental> Rentable for LegacyAdapter<L> {
+    fn listing(&self) -> String {
+        let description = self.legacy.get_description();
+        let (kind, code) = description.split_once('/').unwrap_or(("ITEM", &description));
+        let mut letters = kind.chars();
+        let kind: String = letters.next().into_iter().chain(letters.flat_map(char::to_lowercase)).collect();
+        format!("{} {}", kind, code)
+    }
+
+    fn daily_rate_cents(&self) -> u32 {
+        (self.legacy.weekly_price() * 100.0 / 7.0).round() as u32
+    }
+}
+
+// ========== Blanket and Object Adapters for Vehicle ==========
+
+/// What a vehicle costs when nothing more is known about it
+pub const STANDARD_DAILY_RATE_CENTS: u32 = 4_500;
+
+/// Every `Vehicle` is `Rentable`, at the standard rate
+impl<V: Vehicle> Rentable for V {
+    fn listing(&self) -> String {
+        self.get_info()
+    }
+
+    fn daily_rate_cents(&self) -> u32 {
+        STANDARD_DAILY_RATE_CENTS
+    }
+}
+
+/// A factory-made vehicle with its own price
+pub struct VehicleRental {
+    vehicle: Box<dyn Vehicle>,
+    daily_rate_cents: u32,
+}
+
+impl VehicleRental {
+    pub fn new(vehicle: Box<dyn Vehicle>, daily_rate_cents: u32) -> Self {
+        VehicleRental { vehicle, daily_rate_cents }
+    }
+}
+
+impl Rentable for VehicleRental {
+    fn listing(&self) -> String {
+        self.vehicle.get_info()
+    }
+
+    fn daily_rate_cents(&self) -> u32 {
+        self.daily_rate_cents
+    }
+}
+
+// ========== FFI-Style Adapter ==========
+
+pub mod fleet_c {
+    //! Stands in for a C fleet-management library, with the usual C conventions: a
+    //! status code return, results through out-parameters, and strings copied into a
+    //! buffer the caller owns, with the size it needs reported when it is too small.
+
+    use std::ffi::{c_char, c_int, CStr};
+    use std::ptr;
+
+    pub const FLEET_OK: c_int = 0;
+    pub const FLEET_NULL_POINTER: c_int = 1;
+    pub const FLEET_NOT_FOUND: c_int = 2;
+    pub const FLEET_BUFFER_TOO_SMALL: c_int = 3;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct FleetUnit {
+        pub cents_per_hour: u32,
+        pub seats: u8,
+    }
+
+    /// Plate, model, cents per hour and seats
+    static UNITS: [(&CStr, &CStr, u32, u8); 3] = [
+        (c"TN-101", c"Vespa Primavera", 150, 2),
+        (c"TN-202", c"Ford Transit Custom Double Cab-in-Van", 700, 6),
+        (c"TN-303", c"Toyota Corolla", 300, 5),
+    ];
+
+    /// Looks up the unit registered under `plate`.
+    ///
+    /// # Safety
+    ///
+    /// `plate` must be a NUL-terminated string, `unit` and `needed` must be valid for
+    /// writes, and `name` must be null or point to `name_len` writable bytes.
+    pub unsafe extern "C" fn fleet_lookup(
+        plate: *const c_char,
+        unit: *mut FleetUnit,
+        name: *mut c_char,
+        name_len: usize,
+        needed: *mut usize,
+    ) -> c_int {
+        if plate.is_null() || unit.is_null() || needed.is_null() {
+            return FLEET_NULL_POINTER;
+        }
+        let plate = CStr::from_ptr(plate);
+        let Some(&(_, model, cents_per_hour, seats)) = UNITS.iter().find(|(registered, ..)| *registered == plate) else {
+            return FLEET_NOT_FOUND;
+        };
+        let model = model.to_bytes_with_nul();
+        *needed = model.len();
+        if name.is_null() || name_len < model.len() {
+            return FLEET_BUFFER_TOO_SMALL;
+        }
+        ptr::copy_nonoverlapping(model.as_ptr().cast::<c_char>(), name, model.len());
+        *unit = FleetUnit { cents_per_hour, seats };
+        FLEET_OK
+    }
+}
+
+/// Why a fleet lookup failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FleetError {
+    /// The plate contains a NUL byte, so it can't be passed to C
+    InvalidPlate,
+    NotFound(String),
+    /// A status code the adapter has no better answer for
+    Status(i32),
+}
+
+impl fmt::Display for FleetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FleetError::InvalidPlate => write!(f, "plate contains a NUL byte"),
+            FleetError::NotFound(plate) => write!(f, "no vehicle registered as {}", plate),
+            FleetError::Status(code) => write!(f, "fleet library returned status {}", code),
+        }
+    }
+}
+
+impl std::error::Error for FleetError {}
+
+/// How much room the first lookup leaves for the model name
+pub const NAME_BUFFER: usize = 16;
+
+/// A vehicle from the C fleet library, as owned Rust data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetVehicle {
+    pub plate: String,
+    pub model: String,
+    pub seats: u8,
+    pub cents_per_hour: u32,
+}
+
+impl FleetVehicle {
+    /// Calls `fleet_lookup`, growing the name buffer once if the library asks for more
+    pub fn lookup(plate: &str) -> Result<Self, FleetError> {
+        let c_plate = CString::new(plate).map_err(|_| FleetError::InvalidPlate)?;
+        let mut unit = fleet_c::FleetUnit::default();
+        let mut name = vec![0u8; NAME_BUFFER];
+        let mut needed = 0;
+        loop {
+            // SAFETY: `c_plate` is NUL-terminated, `unit` and `needed` are live locals
+            // and `name` has exactly `name.len()` writable bytes
+            let status = unsafe {
+                fleet_c::fleet_lookup(c_plate.as_ptr(), &mut unit, name.as_mut_ptr().cast(), name.len(), &mut needed)
+            };
+            match status {
+                fleet_c::FLEET_OK => break,
+                fleet_c::FLEET_BUFFER_TOO_SMALL if needed > name.len() => name.resize(needed, 0),
+                fleet_c::FLEET_NOT_FOUND => return Err(FleetError::NotFound(plate.to_string())),
+                other => return Err(FleetError::Status(other)),
+            }
+        }
+        let model = CStr::from_bytes_until_nul(&name).expect("fleet_lookup writes a NUL-terminated name on success");
+        Ok(FleetVehicle {
+            plate: plate.to_string(),
+            model: model.to_string_lossy().into_owned(),
+            seats: unit.seats,
+            cents_per_hour: unit.cents_per_hour,
+        })
+    }
+}
+
+/// The C side bills by the hour; a rental day is 24 of them
+impl Rentable for FleetVehicle {
+    fn listing(&self) -> String {
+        format!("{} ({}, {} seats)", self.model, self.plate, self.seats)
+    }
+
+    fn daily_rate_cents(&self) -> u32 {
+        self.cents_per_hour * 24
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct AdapterPatternDemo;
+
+impl Demo for AdapterPatternDemo {
+    fn name(&self) -> &'static str {
+        "adapter_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Legacy trait, factory vehicles and a C-style API adapted to one Rentable trait"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Trait-to-Trait Adapter =====")?;
+        let scooter = OldScooter { code: "S-17".to_string(), weekly_price: 99.0 };
+        writeln!(out, "Legacy says: {} at ${:.2}/week", scooter.get_description(), scooter.weekly_price())?;
+        let adapted = LegacyAdapter::new(scooter);
+        writeln!(out, "Adapted:     {} at {}/day", adapted.listing(), format_cents(adapted.daily_rate_cents()))?;
+
+        writeln!(out, "\n===== Blanket Impl for Every Vehicle =====")?;
+        let car = Car::new("Honda", "Civic", 2022, 4);
+        let bike = Motorcycle::new("Ducati", "Monster", 2021, 937);
+        writeln!(out, "{} at {}/day", car.listing(), format_cents(car.daily_rate_cents()))?;
+        writeln!(out, "{} at {}/day", bike.listing(), format_cents(bike.daily_rate_cents()))?;
+
+        writeln!(out, "\n===== Object Adapter Around Factory Output =====")?;
+        let truck = VehicleRental::new(VehicleFactory::create_truck("Volvo", "FH16", 2020, 20.0), 18_000);
+        writeln!(out, "{} at {}/day", truck.listing(), format_cents(truck.daily_rate_cents()))?;
+
+        writeln!(out, "\n===== FFI-Style Adapter =====")?;
+        for plate in ["TN-101", "TN-202", "XX-000", "TN-101\0"] {
+            let shown = plate.escape_debug().to_string();
+            match FleetVehicle::lookup(plate) {
+                Ok(vehicle) => writeln!(out, "{:<8} -> {}", shown, vehicle.listing())?,
+                Err(error) => writeln!(out, "{:<8} -> error: {}", shown, error)?,
+            }
+        }
+
+        writeln!(out, "\n===== One Counter, Every Source =====")?;
+        let mut counter: Vec<Box<dyn Rentable>> = vec![
+            Box::new(adapted),
+            Box::new(car),
+            Box::new(truck),
+            Box::new(FleetVehicle::lookup("TN-303").expect("TN-303 is in the fleet")),
+        ];
+        counter.sort_by_key(|item| item.daily_rate_cents());
+        for item in &counter {
+            writeln!(out, "{:>8} for 3 days  {}", format_cents(item.quote_cents(3)), item.listing())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&AdapterPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory_pattern::Truck;
+
+    #[test]
+    fn legacy_adapter_translates_description_and_weekly_price() {
+        let adapted = LegacyAdapter::new(OldScooter { code: "S-1".to_string(), weekly_price: 70.0 });
+        assert_eq!(adapted.listing(), "Scooter S-1");
+        assert_eq!(adapted.daily_rate_cents(), 1_000);
+        assert_eq!(adapted.quote_cents(3), 3_000);
+        // The adapter gives back what it wrapped
+        assert_eq!(adapted.into_inner().weekly_price(), 70.0);
+    }
+
+    #[test]
+    fn every_vehicle_is_rentable_through_the_blanket_impl() {
+        fn rate_of(item: &impl Rentable) -> u32 {
+            item.daily_rate_cents()
+        }
+        let truck = Truck::new("MAN", "TGX", 2019, 18.0);
+        assert_eq!(rate_of(&truck), STANDARD_DAILY_RATE_CENTS);
+        assert_eq!(truck.listing(), truck.get_info());
+    }
+
+    #[test]
+    fn vehicle_rental_prices_factory_output_itself() {
+        let rental = VehicleRental::new(VehicleFactory::create_car("Kia", "Rio", 2024, 4), 3_250);
+        assert_eq!(rental.listing(), "2024 Kia Rio (4-door car)");
+        assert_eq!(rental.quote_cents(2), 6_500);
+        assert_eq!(format_cents(rental.quote_cents(2)), "$65.00");
+    }
+
+    #[test]
+    fn fleet_lookup_grows_the_name_buffer_when_asked() {
+        let short = FleetVehicle::lookup("TN-101").unwrap();
+        assert_eq!((short.model.as_str(), short.seats, short.daily_rate_cents()), ("Vespa Primavera", 2, 3_600));
+
+        let long = FleetVehicle::lookup("TN-202").unwrap();
+        assert!(long.model.len() >= NAME_BUFFER, "the model should not fit the first buffer");
+        assert_eq!(long.model, "Ford Transit Custom Double Cab-in-Van");
+        assert_eq!(long.listing(), "Ford Transit Custom Double Cab-in-Van (TN-202, 6 seats)");
+    }
+
+    #[test]
+    fn fleet_lookup_turns_status_codes_into_errors() {
+        assert_eq!(FleetVehicle::lookup("XX-000"), Err(FleetError::NotFound("XX-000".to_string())));
+        assert_eq!(FleetVehicle::lookup("TN-101\0"), Err(FleetError::InvalidPlate));
+
+        // SAFETY: null is exactly what is being tested, and the library checks for it
+        let status = unsafe {
+            fleet_c::fleet_lookup(std::ptr::null(), std::ptr::null_mut(), std::ptr::null_mut(), 0, std::ptr::null_mut())
+        };
+        assert_eq!(status, fleet_c::FLEET_NULL_POINTER);
+    }
+}
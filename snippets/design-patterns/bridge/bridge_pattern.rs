@@ -0,0 +1,491 @@
+//! Bridge Pattern Implementation in Rust
+//!
+//! The Bridge Pattern is a structural design pattern that splits one concept into two
+//! hierarchies, an abstraction and an implementation, that can vary independently. The
+//! abstraction holds a reference to an implementor and forwards the low-level work to it,
+//! so adding a new remote does not mean writing one per device, and vice versa.
+//!
+//! This example pairs remote controls with devices:
+//! - Implementor: the `Device` trait, with `Tv` and `Radio` as implementations
+//! - Abstraction: the `Remote` trait, whose provided methods (power, volume, channel)
+//!   are written only in terms of `Device`
+//! - Refined abstractions: `BasicRemote<D>` and `AdvancedRemote<D>`, which adds mute and
+//!   favourites on top
+//!
+//! With generics (`BasicRemote<Tv>`) the bridge is resolved at compile time and costs
+//! nothing. `Device` is also implemented for `Box<dyn Device>`, so the same remotes can be
+//! pointed at a device chosen at runtime, as in `BasicRemote<Box<dyn Device>>`.
+
+use demo::Demo;
+use std::io::{self, Write};
+
+// ========== Implementor ==========
+
+pub trait Device {
+    fn name(&self) -> &str;
+    fn is_enabled(&self) -> bool;
+    fn enable(&mut self);
+    fn disable(&mut self);
+    fn volume(&self) -> u8;
+    fn max_volume(&self) -> u8;
+    /// Implementations clamp to `max_volume`
+    fn set_volume(&mut self, volume: u8);
+    fn channel(&self) -> u16;
+    /// Implementations wrap around their own channel range
+    fn set_channel(&mut self, channel: u16);
+    /// Next and previous channels, as the device numbers them
+    fn channel_after(&self, channel: u16, forward: bool) -> u16;
+
+    fn status(&self) -> String {
+        if self.is_enabled() {
+            format!("{} on, volume {}/{}, channel {}", self.name(), self.volume(), self.max_volume(), self.channel())
+        } else {
+            format!("{} off", self.name())
+        }
+    }
+}
+
+impl<D: Device + ?Sized> Device for Box<D> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn is_enabled(&self) -> bool {
+        (**self).is_enabled()
+    }
+
+    fn enable(&mut self) {
+        (**self).enable()
+    }
+
+    fn disable(&mut self) {
+        (**self).disable()
+    }
+
+    fn volume(&self) -> u8 {
+        (**self).volume()
+    }
+
+    fn max_volume(&self) -> u8 {
+        (**self).max_volume()
+    }
+
+    fn set_volume(&mut self, volume: u8) {
+        (**self).set_volume(volume)
+    }
+
+    fn channel(&self) -> u16 {
+        (**self).channel()
+    }
+
+    fn set_channel(&mut self, channel: u16) {
+        (**self).set_channel(channel)
+    }
+
+    fn channel_after(&self, channel: u16, forward: bool) -> u16 {
+        (**self).channel_after(channel, forward)
+    }
+
+    fn status(&self) -> String {
+        (**self).status()
+    }
+}
+
+/// Numbered channels 1 to 99
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tv {
+    on: bool,
+    volume: u8,
+    channel: u16,
+}
+
+impl Tv {
+    pub const CHANNELS: u16 = 99;
+}
+
+impl Default for Tv {
+    fn default() -> Self {
+        Tv { on: false, volume: 20, channel: 1 }
+    }
+}
+
+impl Device for Tv {
+    fn name(&self) -> &str {
+        "TV"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.on
+    }
+
+    fn enable(&mut self) {
+        self.on = true;
+    }
+
+    fn disable(&mut self) {
+        self.on = false;
+    }
+
+    fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    fn max_volume(&self) -> u8 {
+        100
+    }
+
+    fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(self.max_volume());
+    }
+
+    fn channel(&self) -> u16 {
+        self.channel
+    }
+
+    fn set_channel(&mut self, channel: u16) {
+        self.channel = (channel.max(1) - 1) % Self::CHANNELS + 1;
+    }
+
+    fn channel_after(&self, channel: u16, forward: bool) -> u16 {
+        match (forward, channel) {
+            (true, Self::CHANNELS) => 1,
+            (true, _) => channel + 1,
+            (false, 1) => Self::CHANNELS,
+            (false, _) => channel - 1,
+        }
+    }
+}
+
+/// Tuned by frequency in tenths of a megahertz, stepping 0.2 MHz across the FM band
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Radio {
+    on: bool,
+    volume: u8,
+    frequency: u16,
+}
+
+impl Radio {
+    pub const LOWEST: u16 = 875;
+    pub const HIGHEST: u16 = 1079;
+    pub const STEP: u16 = 2;
+}
+
+impl Default for Radio {
+    fn default() -> Self {
+        Radio { on: false, volume: 5, frequency: 1001 }
+    }
+}
+
+impl Device for Radio {
+    fn name(&self) -> &str {
+        "Radio"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.on
+    }
+
+    fn enable(&mut self) {
+        self.on = true;
+    }
+
+    fn disable(&mut self) {
+        self.on = false;
+    }
+
+    fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    fn max_volume(&self) -> u8 {
+        10
+    }
+
+    fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(self.max_volume());
+    }
+
+    fn channel(&self) -> u16 {
+        self.frequency
+    }
+
+    fn set_channel(&mut self, frequency: u16) {
+        self.frequency = frequency.clamp(Self::LOWEST, Self::HIGHEST);
+    }
+
+    fn channel_after(&self, frequency: u16, forward: bool) -> u16 {
+        match forward {
+            true if frequency + Self::STEP > Self::HIGHEST => Self::LOWEST,
+            true => frequency + Self::STEP,
+            false if frequency < Self::LOWEST + Self::STEP => Self::HIGHEST,
+            false => frequency - Self::STEP,
+        }
+    }
+}
+
+// ========== Abstraction ==========
+
+/// Everything a remote does, written against `Device` alone
+pub trait Remote {
+    type Target: Device;
+
+    fn device(&self) -> &Self::Target;
+    fn device_mut(&mut self) -> &mut Self::Target;
+
+    fn toggle_power(&mut self) {
+        let device = self.device_mut();
+        if device.is_enabled() {
+            device.disable();
+        } else {
+            device.enable();
+        }
+    }
+
+    fn volume_up(&mut self) {
+        let device = self.device_mut();
+        device.set_volume(device.volume().saturating_add(1));
+    }
+
+    fn volume_down(&mut self) {
+        let device = self.device_mut();
+        device.set_volume(device.volume().saturating_sub(1));
+    }
+
+    fn channel_up(&mut self) {
+        let device = self.device_mut();
+        device.set_channel(device.channel_after(device.channel(), true));
+    }
+
+    fn channel_down(&mut self) {
+        let device = self.device_mut();
+        device.set_channel(device.channel_after(device.channel(), false));
+    }
+}
+
+// ========== Refined Abstractions ==========
+
+pub struct BasicRemote<D: Device> {
+    device: D,
+}
+
+impl<D: Device> BasicRemote<D> {
+    pub fn new(device: D) -> Self {
+        BasicRemote { device }
+    }
+
+    pub fn into_device(self) -> D {
+        self.device
+    }
+}
+
+impl<D: Device> Remote for BasicRemote<D> {
+    type Target = D;
+
+    fn device(&self) -> &D {
+        &self.device
+    }
+
+    fn device_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+}
+
+/// Adds mute and a favourite channel, still through the same `Device` interface
+pub struct AdvancedRemote<D: Device> {
+    device: D,
+    muted_at: Option<u8>,
+    favourite: Option<u16>,
+}
+
+impl<D: Device> AdvancedRemote<D> {
+    pub fn new(device: D) -> Self {
+        AdvancedRemote { device, muted_at: None, favourite: None }
+    }
+
+    /// Mutes, or restores the volume from before muting
+    pub fn toggle_mute(&mut self) {
+        match self.muted_at.take() {
+            Some(volume) => self.device.set_volume(volume),
+            None => {
+                self.muted_at = Some(self.device.volume());
+                self.device.set_volume(0);
+            }
+        }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted_at.is_some()
+    }
+
+    pub fn save_favourite(&mut self) {
+        self.favourite = Some(self.device.channel());
+    }
+
+    /// Returns false when nothing has been saved yet
+    pub fn go_to_favourite(&mut self) -> bool {
+        match self.favourite {
+            Some(channel) => {
+                self.device.set_channel(channel);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn into_device(self) -> D {
+        self.device
+    }
+}
+
+impl<D: Device> Remote for AdvancedRemote<D> {
+    type Target = D;
+
+    fn device(&self) -> &D {
+        &self.device
+    }
+
+    fn device_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Pressing volume up while muted unmutes first, like most real remotes
+    fn volume_up(&mut self) {
+        if self.is_muted() {
+            self.toggle_mute();
+        }
+        self.device.set_volume(self.device.volume().saturating_add(1));
+    }
+}
+
+/// Same button presses against any remote, to show the two sides vary independently
+pub fn evening_routine<R: Remote>(remote: &mut R) -> String {
+    remote.toggle_power();
+    remote.channel_up();
+    remote.channel_up();
+    remote.volume_up();
+    remote.device().status()
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct BridgePatternDemo;
+
+impl Demo for BridgePatternDemo {
+    fn name(&self) -> &'static str {
+        "bridge_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remotes and devices combined freely through a Device implementor trait"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Every Remote With Every Device =====")?;
+        writeln!(out, "BasicRemote<Tv>:       {}", evening_routine(&mut BasicRemote::new(Tv::default())))?;
+        writeln!(out, "BasicRemote<Radio>:    {}", evening_routine(&mut BasicRemote::new(Radio::default())))?;
+        writeln!(out, "AdvancedRemote<Tv>:    {}", evening_routine(&mut AdvancedRemote::new(Tv::default())))?;
+        writeln!(out, "AdvancedRemote<Radio>: {}", evening_routine(&mut AdvancedRemote::new(Radio::default())))?;
+
+        writeln!(out, "\n===== Device Rules Stay in the Device =====")?;
+        let mut radio = BasicRemote::new(Radio { on: true, volume: 10, frequency: Radio::HIGHEST });
+        radio.volume_up();
+        radio.channel_up();
+        writeln!(out, "Radio at max volume and top of the band, then up: {}", radio.device().status())?;
+        let mut tv = BasicRemote::new(Tv { on: true, volume: 20, channel: 1 });
+        tv.channel_down();
+        writeln!(out, "TV on channel 1, then down: {}", tv.device().status())?;
+
+        writeln!(out, "\n===== Advanced Features =====")?;
+        let mut remote = AdvancedRemote::new(Tv::default());
+        remote.toggle_power();
+        remote.device_mut().set_channel(42);
+        remote.save_favourite();
+        remote.channel_up();
+        remote.toggle_mute();
+        writeln!(out, "Saved 42, zapped and muted: {}", remote.device().status())?;
+        remote.volume_up();
+        remote.go_to_favourite();
+        writeln!(out, "Volume up unmutes, back to favourite: {}", remote.device().status())?;
+
+        writeln!(out, "\n===== Devices Chosen at Runtime =====")?;
+        let devices: Vec<Box<dyn Device>> = vec![Box::new(Tv::default()), Box::new(Radio::default())];
+        for device in devices {
+            let mut remote = AdvancedRemote::new(device);
+            remote.toggle_power();
+            remote.toggle_mute();
+            writeln!(out, "AdvancedRemote<Box<dyn Device>>: {}", remote.device().status())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&BridgePatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_remotes_work_with_both_devices() {
+        assert_eq!(evening_routine(&mut BasicRemote::new(Tv::default())), "TV on, volume 21/100, channel 3");
+        assert_eq!(evening_routine(&mut AdvancedRemote::new(Tv::default())), "TV on, volume 21/100, channel 3");
+        assert_eq!(evening_routine(&mut BasicRemote::new(Radio::default())), "Radio on, volume 6/10, channel 1005");
+        assert_eq!(evening_routine(&mut AdvancedRemote::new(Radio::default())), "Radio on, volume 6/10, channel 1005");
+    }
+
+    #[test]
+    fn devices_enforce_their_own_limits() {
+        let mut tv = BasicRemote::new(Tv { on: true, volume: 100, channel: Tv::CHANNELS });
+        tv.volume_up();
+        tv.channel_up();
+        assert_eq!((tv.device().volume(), tv.device().channel()), (100, 1));
+
+        let mut radio = BasicRemote::new(Radio { on: true, volume: 0, frequency: Radio::LOWEST });
+        radio.volume_down();
+        radio.channel_down();
+        assert_eq!((radio.device().volume(), radio.device().channel()), (0, Radio::HIGHEST));
+        radio.device_mut().set_channel(2000);
+        assert_eq!(radio.into_device().channel(), Radio::HIGHEST);
+    }
+
+    #[test]
+    fn advanced_remote_mutes_and_restores() {
+        let mut remote = AdvancedRemote::new(Radio::default());
+        remote.toggle_mute();
+        assert!(remote.is_muted());
+        assert_eq!(remote.device().volume(), 0);
+        remote.toggle_mute();
+        assert_eq!(remote.device().volume(), 5);
+
+        remote.toggle_mute();
+        remote.volume_up();
+        assert!(!remote.is_muted());
+        assert_eq!(remote.device().volume(), 6);
+    }
+
+    #[test]
+    fn favourites_need_saving_first() {
+        let mut remote = AdvancedRemote::new(Tv::default());
+        assert!(!remote.go_to_favourite());
+        remote.device_mut().set_channel(7);
+        remote.save_favourite();
+        remote.channel_up();
+        assert!(remote.go_to_favourite());
+        assert_eq!(remote.into_device().channel(), 7);
+    }
+
+    #[test]
+    fn boxed_devices_plug_into_the_same_remotes() {
+        let devices: Vec<Box<dyn Device>> = vec![Box::new(Tv::default()), Box::new(Radio::default())];
+        let statuses: Vec<String> =
+            devices.into_iter().map(|device| evening_routine(&mut BasicRemote::new(device))).collect();
+        assert_eq!(statuses, ["TV on, volume 21/100, channel 3", "Radio on, volume 6/10, channel 1005"]);
+    }
+}
@@ -0,0 +1,452 @@
+//! Builder Pattern Implementation in Rust
+//!
+//! The Builder Pattern is a creational design pattern that constructs a complex object
+//! step by step, so the caller names only the parts it cares about and the rest fall
+//! back to defaults. Rust has no named or default arguments, which makes builders the
+//! usual answer to a constructor with many optional parameters.
+//!
+//! This example shows two kinds:
+//! - A classic fluent builder for a `ServerConfig`. Its setters take `&mut self`, so a
+//!   builder can be configured conditionally and reused, and `build` checks the result
+//!   at runtime, returning a `ConfigError` when a value is missing or out of range.
+//! - A typestate builder for an `HttpRequest`. The URL and method are required, and the
+//!   builder's type records whether each has been set: `build` only exists on
+//!   `HttpRequestBuilder<HasUrl, HasMethod>`, so forgetting one is a compile error
+//!   rather than a runtime one. The markers are zero-sized, so the tracking costs nothing.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Duration;
+
+// ========== Classic Builder ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Info,
+    Debug,
+}
+
+/// The finished product: every field is set and valid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub workers: usize,
+    pub log_level: LogLevel,
+    /// Certificate path when TLS is on
+    pub tls_cert: Option<String>,
+}
+
+impl ServerConfig {
+    pub fn builder() -> ServerConfigBuilder {
+        ServerConfigBuilder::default()
+    }
+}
+
+/// Why `ServerConfigBuilder::build` refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    MissingHost,
+    ZeroPort,
+    /// Workers must be between 1 and `MAX_WORKERS`
+    WorkersOutOfRange(usize),
+    TlsWithoutCert,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::MissingHost => write!(f, "a host is required"),
+            ConfigError::ZeroPort => write!(f, "port 0 is not a real port"),
+            ConfigError::WorkersOutOfRange(n) => write!(f, "{} workers is outside 1..={}", n, MAX_WORKERS),
+            ConfigError::TlsWithoutCert => write!(f, "TLS is enabled but no certificate was given"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+pub const MAX_WORKERS: usize = 256;
+
+/// Collects settings; only `host` has no default
+#[derive(Debug, Clone)]
+pub struct ServerConfigBuilder {
+    host: Option<String>,
+    port: u16,
+    workers: usize,
+    log_level: LogLevel,
+    tls: bool,
+    tls_cert: Option<String>,
+}
+
+impl Default for ServerConfigBuilder {
+    fn default() -> Self {
+        ServerConfigBuilder {
+            host: None,
+            port: 8080,
+            workers: 4,
+            log_level: LogLevel::Info,
+            tls: false,
+            tls_cert: None,
+        }
+    }
+}
+
+impl ServerConfigBuilder {
+    pub fn host(&mut self, host: &str) -> &mut Self {
+        self.host = Some(host.to_string());
+        self
+    }
+
+    pub fn port(&mut self, port: u16) -> &mut Self {
+        self.port = port;
+        self
+    }
+
+    pub fn workers(&mut self, workers: usize) -> &mut Self {
+        self.workers = workers;
+        self
+    }
+
+    pub fn log_level(&mut self, level: LogLevel) -> &mut Self {
+        self.log_level = level;
+        self
+    }
+
+    pub fn tls(&mut self, enabled: bool) -> &mut Self {
+        self.tls = enabled;
+        self
+    }
+
+    pub fn tls_cert(&mut self, path: &str) -> &mut Self {
+        self.tls_cert = Some(path.to_string());
+        self
+    }
+
+    /// Validates the settings; the builder is left as it was, ready to build again
+    pub fn build(&self) -> Result<ServerConfig, ConfigError> {
+        let host = self.host.clone().ok_or(ConfigError::MissingHost)?;
+        if self.port == 0 {
+            return Err(ConfigError::ZeroPort);
+        }
+        if !(1..=MAX_WORKERS).contains(&self.workers) {
+            return Err(ConfigError::WorkersOutOfRange(self.workers));
+        }
+        let tls_cert = match (self.tls, &self.tls_cert) {
+            (true, None) => return Err(ConfigError::TlsWithoutCert),
+            (true, Some(cert)) => Some(cert.clone()),
+            (false, _) => None,
+        };
+        Ok(ServerConfig { host, port: self.port, workers: self.workers, log_level: self.log_level, tls_cert })
+    }
+}
+
+// ========== Typestate Builder ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn builder() -> HttpRequestBuilder<Missing, Missing> {
+        HttpRequestBuilder { url: Missing, method: Missing, headers: Vec::new(), body: None, timeout: None }
+    }
+}
+
+impl fmt::Display for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        for (name, value) in &self.headers {
+            write!(f, "\n{}: {}", name, value)?;
+        }
+        if let Some(timeout) = self.timeout {
+            write!(f, "\n(timeout {} ms)", timeout.as_millis())?;
+        }
+        if let Some(body) = &self.body {
+            write!(f, "\n\n{}", body)?;
+        }
+        Ok(())
+    }
+}
+
+/// A required field that has not been set yet
+#[derive(Debug, Clone, Copy)]
+pub struct Missing;
+
+/// The URL has been set
+#[derive(Debug, Clone)]
+pub struct HasUrl(String);
+
+/// The method has been set
+#[derive(Debug, Clone, Copy)]
+pub struct HasMethod(Method);
+
+/// Builds an `HttpRequest`; `U` and `M` record whether the URL and method are set.
+///
+/// `build` only exists once both are:
+///
+/// ```compile_fail,E0599
+/// use patterns::builder_pattern::HttpRequest;
+///
+/// let request = HttpRequest::builder().url("https://example.com").build();
+/// ```
+///
+/// and each can only be set once:
+///
+/// ```compile_fail,E0599
+/// use patterns::builder_pattern::HttpRequest;
+///
+/// let request = HttpRequest::builder().get().url("https://a.example").url("https://b.example").build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpRequestBuilder<U, M> {
+    url: U,
+    method: M,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    timeout: Option<Duration>,
+}
+
+impl<M> HttpRequestBuilder<Missing, M> {
+    pub fn url(self, url: &str) -> HttpRequestBuilder<HasUrl, M> {
+        HttpRequestBuilder {
+            url: HasUrl(url.to_string()),
+            method: self.method,
+            headers: self.headers,
+            body: self.body,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<U> HttpRequestBuilder<U, Missing> {
+    pub fn method(self, method: Method) -> HttpRequestBuilder<U, HasMethod> {
+        HttpRequestBuilder {
+            url: self.url,
+            method: HasMethod(method),
+            headers: self.headers,
+            body: self.body,
+            timeout: self.timeout,
+        }
+    }
+
+    pub fn get(self) -> HttpRequestBuilder<U, HasMethod> {
+        self.method(Method::Get)
+    }
+
+    pub fn post(self) -> HttpRequestBuilder<U, HasMethod> {
+        self.method(Method::Post)
+    }
+}
+
+/// Optional parts can be added in any state
+impl<U, M> HttpRequestBuilder<U, M> {
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl HttpRequestBuilder<HasUrl, HasMethod> {
+    /// Cannot fail: the type already proves the required fields are there
+    pub fn build(self) -> HttpRequest {
+        HttpRequest {
+            method: self.method.0,
+            url: self.url.0,
+            headers: self.headers,
+            body: self.body,
+            timeout: self.timeout,
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct BuilderPatternDemo;
+
+impl Demo for BuilderPatternDemo {
+    fn name(&self) -> &'static str {
+        "builder_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fluent server-config builder and a typestate HTTP request builder"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Classic Fluent Builder =====")?;
+        let config = ServerConfig::builder().host("localhost").build();
+        writeln!(out, "Defaults: {:?}", config)?;
+
+        // `&mut self` setters let the configuration depend on runtime conditions
+        let production = true;
+        let mut builder = ServerConfig::builder();
+        builder.host("api.example.com").port(443).workers(16);
+        if production {
+            builder.log_level(LogLevel::Error).tls(true).tls_cert("/etc/tls/api.pem");
+        }
+        writeln!(out, "Production: {:?}", builder.build())?;
+
+        // The same builder can be adjusted and built again
+        builder.port(8443).log_level(LogLevel::Debug);
+        writeln!(out, "Staging: {:?}", builder.build())?;
+
+        writeln!(out, "\nValidation happens in build():")?;
+        let mut no_cert = ServerConfig::builder();
+        no_cert.host("example.com").tls(true);
+        let mut too_many = ServerConfig::builder();
+        too_many.host("example.com").workers(1000);
+        for (label, builder) in [
+            ("no host", ServerConfig::builder()),
+            ("port 0", ServerConfig::builder().host("example.com").port(0).clone()),
+            ("1000 workers", too_many),
+            ("TLS, no cert", no_cert),
+        ] {
+            match builder.build() {
+                Ok(config) => writeln!(out, "{:<13} -> built {:?}", label, config)?,
+                Err(error) => writeln!(out, "{:<13} -> error: {}", label, error)?,
+            }
+        }
+
+        writeln!(out, "\n===== Typestate Builder =====")?;
+        let request = HttpRequest::builder()
+            .url("https://api.example.com/users")
+            .post()
+            .header("Content-Type", "application/json")
+            .body(r#"{"name": "Ada"}"#)
+            .timeout(Duration::from_millis(2500))
+            .build();
+        writeln!(out, "{}", request)?;
+
+        // Required fields can come in any order, and optional ones at any point
+        let request = HttpRequest::builder().header("Accept", "text/html").get().url("https://example.com/").build();
+        writeln!(out, "\n{}", request)?;
+
+        writeln!(out, "\nThe state lives only in the type:")?;
+        writeln!(out, "size_of::<Missing>() = {}", std::mem::size_of::<Missing>())?;
+        let optional = std::mem::size_of::<(Vec<(String, String)>, Option<String>, Option<Duration>)>();
+        writeln!(
+            out,
+            "An empty builder is exactly as big as its optional fields: {}",
+            std::mem::size_of::<HttpRequestBuilder<Missing, Missing>>() == optional
+        )?;
+        writeln!(out, "HttpRequest::builder().url(..).build() does not compile: no method `build`")?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&BuilderPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_fields_take_their_defaults() {
+        let config = ServerConfig::builder().host("localhost").build().unwrap();
+        assert_eq!(
+            config,
+            ServerConfig { host: "localhost".to_string(), port: 8080, workers: 4, log_level: LogLevel::Info, tls_cert: None }
+        );
+    }
+
+    #[test]
+    fn build_rejects_invalid_settings() {
+        let cases = [
+            (ServerConfig::builder().port(80).clone(), ConfigError::MissingHost),
+            (ServerConfig::builder().host("h").port(0).clone(), ConfigError::ZeroPort),
+            (ServerConfig::builder().host("h").workers(0).clone(), ConfigError::WorkersOutOfRange(0)),
+            (ServerConfig::builder().host("h").workers(MAX_WORKERS + 1).clone(), ConfigError::WorkersOutOfRange(257)),
+            (ServerConfig::builder().host("h").tls(true).clone(), ConfigError::TlsWithoutCert),
+        ];
+        for (builder, expected) in cases {
+            assert_eq!(builder.build(), Err(expected));
+        }
+    }
+
+    #[test]
+    fn a_builder_can_be_built_again_after_changes() {
+        let mut builder = ServerConfig::builder();
+        builder.host("h").tls_cert("cert.pem");
+        let plain = builder.build().unwrap();
+        let secure = builder.tls(true).port(443).build().unwrap();
+        // The certificate only counts once TLS is on
+        assert_eq!((plain.port, plain.tls_cert), (8080, None));
+        assert_eq!((secure.port, secure.tls_cert.as_deref()), (443, Some("cert.pem")));
+    }
+
+    #[test]
+    fn typestate_builder_accepts_required_fields_in_either_order() {
+        let a = HttpRequest::builder().url("https://x.example").method(Method::Delete).build();
+        let b = HttpRequest::builder().method(Method::Delete).url("https://x.example").build();
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), "DELETE https://x.example");
+    }
+
+    #[test]
+    fn optional_parts_survive_every_state_change() {
+        let request = HttpRequest::builder()
+            .header("A", "1")
+            .url("https://x.example/items")
+            .body("payload")
+            .post()
+            .header("B", "2")
+            .timeout(Duration::from_secs(1))
+            .build();
+        assert_eq!(request.headers, [("A".to_string(), "1".to_string()), ("B".to_string(), "2".to_string())]);
+        assert_eq!(request.to_string(), "POST https://x.example/items\nA: 1\nB: 2\n(timeout 1000 ms)\n\npayload");
+    }
+
+    #[test]
+    fn state_markers_take_no_space() {
+        assert_eq!(std::mem::size_of::<Missing>(), 0);
+        assert_eq!(
+            std::mem::size_of::<HttpRequestBuilder<Missing, Missing>>(),
+            std::mem::size_of::<(Vec<(String, String)>, Option<String>, Option<Duration>)>()
+        );
+    }
+}
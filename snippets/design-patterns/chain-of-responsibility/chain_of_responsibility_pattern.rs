@@ -0,0 +1,522 @@
+//! Chain of Responsibility Pattern Implementation in Rust
+//!
+//! The Chain of Responsibility Pattern is a behavioral design pattern that passes a
+//! request along a chain of handlers. Each handler either deals with the request itself,
+//! ending the chain, or forwards it to the next one, so the sender never knows which
+//! handler answered.
+//!
+//! This example is an HTTP-style middleware stack, auth → rate limit → logging → router,
+//! built two ways from the same checks:
+//! - Linked: every handler owns the rest of the chain as `Option<Box<dyn Handler>>` and
+//!   decides itself whether to call it, so it can also act on the response on the way
+//!   back, the way logging does
+//! - Pipeline: `Middleware` stages in a `Vec<Box<dyn Middleware>>` only say whether to
+//!   forward or respond, and the `Pipeline` walks them, which makes the order easy to
+//!   see and change at runtime
+//!
+//! In both, a rejected request short-circuits: nothing after the handler that answered
+//! runs, so an unauthenticated request never counts against the rate limit and never
+//! reaches the access log.
+
+use demo::Demo;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// ========== Requests and Responses ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: &'static str,
+    pub path: String,
+    pub client: String,
+    pub token: Option<String>,
+    /// Seconds since the server started
+    pub at: u64,
+}
+
+impl Request {
+    pub fn get(path: &str) -> Self {
+        Request { method: "GET", path: path.to_string(), client: "anonymous".to_string(), token: None, at: 0 }
+    }
+
+    pub fn from(mut self, client: &str) -> Self {
+        self.client = client.to_string();
+        self
+    }
+
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    pub fn at(mut self, seconds: u64) -> Self {
+        self.at = seconds;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+    /// Which link answered
+    pub handled_by: &'static str,
+}
+
+impl Response {
+    pub fn new(status: u16, body: &str, handled_by: &'static str) -> Self {
+        Response { status, body: body.to_string(), handled_by }
+    }
+}
+
+// ========== The Checks ==========
+
+/// Accepts requests carrying one of a fixed set of tokens
+pub struct Auth {
+    tokens: HashSet<String>,
+}
+
+impl Auth {
+    pub fn new(tokens: &[&str]) -> Self {
+        Auth { tokens: tokens.iter().map(|token| token.to_string()).collect() }
+    }
+
+    pub fn check(&self, request: &Request) -> Result<(), Response> {
+        match &request.token {
+            None => Err(Response::new(401, "missing token", "auth")),
+            Some(token) if !self.tokens.contains(token) => Err(Response::new(403, "unknown token", "auth")),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// At most `limit` requests per client in each fixed window of `window` seconds
+pub struct RateLimit {
+    limit: u32,
+    window: u64,
+    /// Per client: the window it was last seen in, and its requests there
+    seen: HashMap<String, (u64, u32)>,
+}
+
+impl RateLimit {
+    pub fn new(limit: u32, window: u64) -> Self {
+        RateLimit { limit, window, seen: HashMap::new() }
+    }
+
+    pub fn check(&mut self, request: &Request) -> Result<(), Response> {
+        let window = request.at / self.window;
+        let (seen_window, count) = self.seen.entry(request.client.clone()).or_insert((window, 0));
+        if *seen_window != window {
+            (*seen_window, *count) = (window, 0);
+        }
+        if *count == self.limit {
+            return Err(Response::new(429, "too many requests", "rate-limit"));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Lines shared with whoever built the chain, so they can be read after it has been moved
+pub type Log = Rc<RefCell<Vec<String>>>;
+
+pub struct AccessLog {
+    log: Log,
+}
+
+impl AccessLog {
+    pub fn new(log: Log) -> Self {
+        AccessLog { log }
+    }
+
+    pub fn record(&self, request: &Request, response: &Response) {
+        let line = format!("{} {} {} -> {}", request.method, request.path, request.client, response.status);
+        self.log.borrow_mut().push(line);
+    }
+}
+
+/// The end of the chain, which always answers
+pub fn route(request: &Request) -> Response {
+    match (request.method, request.path.as_str()) {
+        ("GET", "/health") => Response::new(200, "ok", "router"),
+        ("GET", "/users") => Response::new(200, "alice, bob", "router"),
+        _ => Response::new(404, "not found", "router"),
+    }
+}
+
+// ========== Linked Chain ==========
+
+pub trait Handler {
+    fn handle(&mut self, request: &Request) -> Response;
+}
+
+/// Passes `request` on, or answers for a chain that ends without a handler
+fn forward(next: &mut Option<Box<dyn Handler>>, request: &Request) -> Response {
+    match next {
+        Some(next) => next.handle(request),
+        None => Response::new(500, "no handler at the end of the chain", "chain"),
+    }
+}
+
+pub struct AuthHandler {
+    auth: Auth,
+    next: Option<Box<dyn Handler>>,
+}
+
+impl AuthHandler {
+    pub fn new(auth: Auth) -> Self {
+        AuthHandler { auth, next: None }
+    }
+
+    pub fn then(mut self, next: impl Handler + 'static) -> Self {
+        self.next = Some(Box::new(next));
+        self
+    }
+}
+
+impl Handler for AuthHandler {
+    fn handle(&mut self, request: &Request) -> Response {
+        match self.auth.check(request) {
+            Ok(()) => forward(&mut self.next, request),
+            Err(response) => response,
+        }
+    }
+}
+
+pub struct RateLimitHandler {
+    limit: RateLimit,
+    next: Option<Box<dyn Handler>>,
+}
+
+impl RateLimitHandler {
+    pub fn new(limit: RateLimit) -> Self {
+        RateLimitHandler { limit, next: None }
+    }
+
+    pub fn then(mut self, next: impl Handler + 'static) -> Self {
+        self.next = Some(Box::new(next));
+        self
+    }
+}
+
+impl Handler for RateLimitHandler {
+    fn handle(&mut self, request: &Request) -> Response {
+        match self.limit.check(request) {
+            Ok(()) => forward(&mut self.next, request),
+            Err(response) => response,
+        }
+    }
+}
+
+/// Always forwards, and records the response on its way back
+pub struct LoggingHandler {
+    log: AccessLog,
+    next: Option<Box<dyn Handler>>,
+}
+
+impl LoggingHandler {
+    pub fn new(log: AccessLog) -> Self {
+        LoggingHandler { log, next: None }
+    }
+
+    pub fn then(mut self, next: impl Handler + 'static) -> Self {
+        self.next = Some(Box::new(next));
+        self
+    }
+}
+
+impl Handler for LoggingHandler {
+    fn handle(&mut self, request: &Request) -> Response {
+        let response = forward(&mut self.next, request);
+        self.log.record(request, &response);
+        response
+    }
+}
+
+pub struct Router;
+
+impl Handler for Router {
+    fn handle(&mut self, request: &Request) -> Response {
+        route(request)
+    }
+}
+
+/// auth → rate limit → logging → router, as nested links
+pub fn linked_chain(tokens: &[&str], limit: RateLimit, log: Log) -> Box<dyn Handler> {
+    Box::new(
+        AuthHandler::new(Auth::new(tokens))
+            .then(RateLimitHandler::new(limit).then(LoggingHandler::new(AccessLog::new(log)).then(Router))),
+    )
+}
+
+// ========== Vec Pipeline ==========
+
+/// What a stage decided
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Forward,
+    Respond(Response),
+}
+
+pub trait Middleware {
+    fn before(&mut self, request: &Request) -> Step;
+
+    /// Runs on the way back, for stages that forwarded the request
+    fn after(&mut self, _request: &Request, _response: &Response) {}
+}
+
+impl Middleware for Auth {
+    fn before(&mut self, request: &Request) -> Step {
+        self.check(request).map_or_else(Step::Respond, |()| Step::Forward)
+    }
+}
+
+impl Middleware for RateLimit {
+    fn before(&mut self, request: &Request) -> Step {
+        self.check(request).map_or_else(Step::Respond, |()| Step::Forward)
+    }
+}
+
+impl Middleware for AccessLog {
+    fn before(&mut self, _request: &Request) -> Step {
+        Step::Forward
+    }
+
+    fn after(&mut self, request: &Request, response: &Response) {
+        self.record(request, response);
+    }
+}
+
+pub struct Pipeline {
+    stages: Vec<Box<dyn Middleware>>,
+    endpoint: fn(&Request) -> Response,
+}
+
+impl Pipeline {
+    pub fn new(endpoint: fn(&Request) -> Response) -> Self {
+        Pipeline { stages: Vec::new(), endpoint }
+    }
+
+    pub fn with(mut self, stage: impl Middleware + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Runs `before` in order until a stage responds, falling through to the endpoint,
+    /// then `after` in reverse on every stage that forwarded
+    pub fn handle(&mut self, request: &Request) -> Response {
+        let mut forwarded = 0;
+        let mut answer = None;
+        for stage in &mut self.stages {
+            match stage.before(request) {
+                Step::Forward => forwarded += 1,
+                Step::Respond(response) => {
+                    answer = Some(response);
+                    break;
+                }
+            }
+        }
+        let response = answer.unwrap_or_else(|| (self.endpoint)(request));
+        for stage in self.stages[..forwarded].iter_mut().rev() {
+            stage.after(request, &response);
+        }
+        response
+    }
+}
+
+impl Handler for Pipeline {
+    fn handle(&mut self, request: &Request) -> Response {
+        Pipeline::handle(self, request)
+    }
+}
+
+/// The same stack as `linked_chain`, as a pipeline
+pub fn pipeline(tokens: &[&str], limit: RateLimit, log: Log) -> Pipeline {
+    Pipeline::new(route).with(Auth::new(tokens)).with(limit).with(AccessLog::new(log))
+}
+
+// ========== Demo Code ==========
+
+fn sample_requests() -> Vec<Request> {
+    vec![
+        Request::get("/health").from("alice").token("secret-a"),
+        Request::get("/users").from("mallory"),
+        Request::get("/users").from("mallory").token("guess"),
+        Request::get("/users").from("bob").token("secret-b").at(1),
+        Request::get("/users").from("bob").token("secret-b").at(2),
+        Request::get("/users").from("bob").token("secret-b").at(3),
+        Request::get("/admin").from("alice").token("secret-a").at(4),
+        Request::get("/users").from("bob").token("secret-b").at(10),
+    ]
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ChainOfResponsibilityPatternDemo;
+
+impl Demo for ChainOfResponsibilityPatternDemo {
+    fn name(&self) -> &'static str {
+        "chain_of_responsibility_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Auth, rate-limit and logging middleware as linked handlers and as a Vec pipeline"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let tokens = ["secret-a", "secret-b"];
+
+        writeln!(out, "===== Linked Handlers =====")?;
+        let linked_log = Log::default();
+        let mut chain = linked_chain(&tokens, RateLimit::new(2, 10), linked_log.clone());
+        let mut answers = Vec::new();
+        for request in sample_requests() {
+            let response = chain.handle(&request);
+            writeln!(
+                out,
+                "{:<7} {:<7} {:<8} {} {:<18} (from {})",
+                request.client,
+                request.path,
+                request.token.as_deref().unwrap_or("-"),
+                response.status,
+                response.body,
+                response.handled_by
+            )?;
+            answers.push(response);
+        }
+        writeln!(out, "Access log, which only saw what got past auth and the rate limit:")?;
+        for line in linked_log.borrow().iter() {
+            writeln!(out, "  {}", line)?;
+        }
+
+        writeln!(out, "\n===== Vec Pipeline =====")?;
+        let pipeline_log = Log::default();
+        let mut stages = pipeline(&tokens, RateLimit::new(2, 10), pipeline_log.clone());
+        let same = sample_requests().iter().zip(&answers).all(|(request, answer)| stages.handle(request) == *answer);
+        writeln!(out, "{} stages, same responses as the linked chain: {}", stages.len(), same)?;
+        writeln!(out, "Same access log: {}", *pipeline_log.borrow() == *linked_log.borrow())?;
+
+        writeln!(out, "\n===== Reordering at Runtime =====")?;
+        let reordered_log = Log::default();
+        let mut logged_first = Pipeline::new(route).with(AccessLog::new(reordered_log.clone())).with(Auth::new(&tokens));
+        logged_first.handle(&Request::get("/users").from("mallory"));
+        writeln!(out, "With logging first, a rejected request is logged too: {:?}", reordered_log.borrow())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ChainOfResponsibilityPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKENS: [&str; 1] = ["t0ken"];
+
+    fn both_chains(log: &Log) -> [Box<dyn Handler>; 2] {
+        [
+            linked_chain(&TOKENS, RateLimit::new(1, 60), log.clone()),
+            Box::new(pipeline(&TOKENS, RateLimit::new(1, 60), log.clone())),
+        ]
+    }
+
+    #[test]
+    fn authorized_requests_reach_the_router_and_the_log() {
+        for mut chain in both_chains(&Log::default()) {
+            let response = chain.handle(&Request::get("/users").from("ann").token("t0ken"));
+            assert_eq!(response, Response::new(200, "alice, bob", "router"));
+        }
+        let log = Log::default();
+        for mut chain in both_chains(&log) {
+            chain.handle(&Request::get("/nope").from("ann").token("t0ken"));
+        }
+        assert_eq!(*log.borrow(), ["GET /nope ann -> 404", "GET /nope ann -> 404"]);
+    }
+
+    #[test]
+    fn auth_short_circuits_everything_after_it() {
+        let log = Log::default();
+        for mut chain in both_chains(&log) {
+            // Far more than the limit of one, yet none of them count against it
+            for _ in 0..3 {
+                assert_eq!(chain.handle(&Request::get("/users").from("eve")).status, 401);
+                assert_eq!(chain.handle(&Request::get("/users").from("eve").token("nope")).status, 403);
+            }
+            let first_real = chain.handle(&Request::get("/users").from("eve").token("t0ken"));
+            assert_eq!(first_real.status, 200);
+        }
+        assert_eq!(log.borrow().len(), 2, "only the authorized requests were logged");
+    }
+
+    #[test]
+    fn rate_limit_counts_per_client_and_resets_each_window() {
+        let mut limit = RateLimit::new(2, 10);
+        let request = |client: &str, at: u64| Request::get("/").from(client).at(at);
+        assert!(limit.check(&request("a", 0)).is_ok());
+        assert!(limit.check(&request("a", 5)).is_ok());
+        assert_eq!(limit.check(&request("a", 9)).unwrap_err().status, 429);
+        assert!(limit.check(&request("b", 9)).is_ok(), "another client has its own quota");
+        assert!(limit.check(&request("a", 10)).is_ok(), "a new window starts over");
+    }
+
+    #[test]
+    fn linked_and_pipeline_chains_answer_the_same_script() {
+        let (linked_log, pipeline_log) = (Log::default(), Log::default());
+        let mut linked = linked_chain(&["secret-a", "secret-b"], RateLimit::new(2, 10), linked_log.clone());
+        let mut stages = pipeline(&["secret-a", "secret-b"], RateLimit::new(2, 10), pipeline_log.clone());
+        let handled_by: Vec<&str> = sample_requests()
+            .iter()
+            .map(|request| {
+                let response = linked.handle(request);
+                assert_eq!(stages.handle(request), response, "{:?}", request);
+                response.handled_by
+            })
+            .collect();
+        assert_eq!(handled_by, ["router", "auth", "auth", "router", "router", "rate-limit", "router", "router"]);
+        assert_eq!(*linked_log.borrow(), *pipeline_log.borrow());
+    }
+
+    #[test]
+    fn pipeline_runs_after_hooks_in_reverse_for_stages_that_forwarded() {
+        struct Trace(&'static str, Log, Step);
+        impl Middleware for Trace {
+            fn before(&mut self, _request: &Request) -> Step {
+                self.1.borrow_mut().push(format!("before {}", self.0));
+                self.2.clone()
+            }
+            fn after(&mut self, _request: &Request, response: &Response) {
+                self.1.borrow_mut().push(format!("after {} {}", self.0, response.status));
+            }
+        }
+
+        let log = Log::default();
+        let teapot = Step::Respond(Response::new(418, "short and stout", "c"));
+        let mut stages = Pipeline::new(route)
+            .with(Trace("a", log.clone(), Step::Forward))
+            .with(Trace("b", log.clone(), Step::Forward))
+            .with(Trace("c", log.clone(), teapot))
+            .with(Trace("d", log.clone(), Step::Forward));
+        assert_eq!(stages.handle(&Request::get("/health")).status, 418);
+        assert_eq!(*log.borrow(), ["before a", "before b", "before c", "after b 418", "after a 418"]);
+
+        let mut empty = Pipeline::new(route);
+        assert!(empty.is_empty());
+        assert_eq!(empty.handle(&Request::get("/health")).status, 200);
+        assert_eq!(forward(&mut None, &Request::get("/health")).status, 500);
+    }
+}
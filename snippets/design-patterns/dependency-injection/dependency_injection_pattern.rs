@@ -0,0 +1,394 @@
+//! Dependency Injection Pattern Implementation in Rust
+//!
+//! Dependency Injection is a design pattern where an object is given the things it
+//! depends on instead of constructing them itself. The object only names what it needs,
+//! usually as a trait, and whoever builds it decides which implementation to pass in:
+//! the real one in production, a mock in tests.
+//!
+//! This example sends account emails, injected three ways:
+//! - Constructor injection with generics: `SignupService<E: EmailSender>` is monomorphized
+//!   for its sender, so calls are static and the type says exactly what it uses
+//! - Trait-object injection: `PasswordResetService` holds an `Rc<dyn EmailSender>`, which
+//!   lets several services share one sender picked at runtime, at the cost of dynamic
+//!   dispatch
+//! - A service container: `Container` maps each `TypeId` to a `Box<dyn Any>` and hands
+//!   services out by type, so wiring happens in one place. Mistakes show up as a
+//!   `MissingService` error at runtime rather than at compile time, which is the usual
+//!   trade for that convenience
+//!
+//! `EmailSender` is implemented for `&E` and `Rc<E>`, so a test can inject a borrowed
+//! `MockEmailSender` and still inspect what was sent afterwards.
+
+use demo::Demo;
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// ========== The Dependency ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendError(pub String);
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not send email: {}", self.0)
+    }
+}
+
+impl std::error::Error for SendError {}
+
+pub trait EmailSender {
+    fn send(&self, email: Email) -> Result<(), SendError>;
+}
+
+impl<E: EmailSender + ?Sized> EmailSender for &E {
+    fn send(&self, email: Email) -> Result<(), SendError> {
+        (**self).send(email)
+    }
+}
+
+impl<E: EmailSender + ?Sized> EmailSender for Rc<E> {
+    fn send(&self, email: Email) -> Result<(), SendError> {
+        (**self).send(email)
+    }
+}
+
+/// The production sender; here it only records the SMTP conversation it would have had
+pub struct SmtpEmailSender {
+    host: String,
+    transcript: RefCell<Vec<String>>,
+}
+
+impl SmtpEmailSender {
+    pub fn new(host: &str) -> Self {
+        SmtpEmailSender { host: host.to_string(), transcript: RefCell::new(Vec::new()) }
+    }
+
+    pub fn transcript(&self) -> Vec<String> {
+        self.transcript.borrow().clone()
+    }
+}
+
+impl EmailSender for SmtpEmailSender {
+    fn send(&self, email: Email) -> Result<(), SendError> {
+        if !email.to.contains('@') {
+            return Err(SendError(format!("{} rejected recipient {:?}", self.host, email.to)));
+        }
+        let mut transcript = self.transcript.borrow_mut();
+        transcript.push(format!("{}: RCPT TO:<{}>", self.host, email.to));
+        transcript.push(format!("{}: Subject: {}", self.host, email.subject));
+        Ok(())
+    }
+}
+
+/// Test double: keeps every email, and can be told to fail for one recipient
+#[derive(Default)]
+pub struct MockEmailSender {
+    sent: RefCell<Vec<Email>>,
+    fail_for: Option<String>,
+}
+
+impl MockEmailSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn failing_for(recipient: &str) -> Self {
+        MockEmailSender { sent: RefCell::new(Vec::new()), fail_for: Some(recipient.to_string()) }
+    }
+
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent.borrow().clone()
+    }
+}
+
+impl EmailSender for MockEmailSender {
+    fn send(&self, email: Email) -> Result<(), SendError> {
+        if self.fail_for.as_deref() == Some(email.to.as_str()) {
+            return Err(SendError(format!("mock refused {}", email.to)));
+        }
+        self.sent.borrow_mut().push(email);
+        Ok(())
+    }
+}
+
+// ========== Constructor Injection With Generics ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignupError {
+    InvalidAddress(String),
+    AlreadyRegistered(String),
+    Email(SendError),
+}
+
+impl fmt::Display for SignupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignupError::InvalidAddress(address) => write!(f, "{:?} is not an email address", address),
+            SignupError::AlreadyRegistered(address) => write!(f, "{} is already registered", address),
+            SignupError::Email(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SignupError {}
+
+pub struct SignupService<E: EmailSender> {
+    sender: E,
+    users: Vec<String>,
+}
+
+impl<E: EmailSender> SignupService<E> {
+    pub fn new(sender: E) -> Self {
+        SignupService { sender, users: Vec::new() }
+    }
+
+    /// The user is only kept if the welcome email went out
+    pub fn register(&mut self, address: &str) -> Result<usize, SignupError> {
+        if !address.contains('@') {
+            return Err(SignupError::InvalidAddress(address.to_string()));
+        }
+        if self.users.iter().any(|user| user == address) {
+            return Err(SignupError::AlreadyRegistered(address.to_string()));
+        }
+        let welcome = Email {
+            to: address.to_string(),
+            subject: "Welcome!".to_string(),
+            body: format!("You are user number {}.", self.users.len() + 1),
+        };
+        self.sender.send(welcome).map_err(SignupError::Email)?;
+        self.users.push(address.to_string());
+        Ok(self.users.len())
+    }
+
+    pub fn users(&self) -> &[String] {
+        &self.users
+    }
+}
+
+// ========== Trait-Object Injection ==========
+
+pub struct PasswordResetService {
+    sender: Rc<dyn EmailSender>,
+    issued: Cell<u32>,
+}
+
+impl PasswordResetService {
+    pub fn new(sender: Rc<dyn EmailSender>) -> Self {
+        PasswordResetService { sender, issued: Cell::new(0) }
+    }
+
+    /// Returns the reset token that was emailed
+    pub fn request_reset(&self, address: &str) -> Result<String, SendError> {
+        let token = format!("reset-{}", self.issued.get() + 1);
+        self.sender.send(Email {
+            to: address.to_string(),
+            subject: "Reset your password".to_string(),
+            body: format!("Your token is {}.", token),
+        })?;
+        self.issued.set(self.issued.get() + 1);
+        Ok(token)
+    }
+}
+
+// ========== A Tiny Service Container ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingService(pub &'static str);
+
+impl fmt::Display for MissingService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no service of type {} is registered", self.0)
+    }
+}
+
+impl std::error::Error for MissingService {}
+
+/// One instance per type, looked up by `TypeId`
+#[derive(Default)]
+pub struct Container {
+    services: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Container {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces anything registered under the same type
+    pub fn register<T: 'static>(&mut self, service: T) -> &mut Self {
+        self.services.insert(TypeId::of::<T>(), Box::new(service));
+        self
+    }
+
+    pub fn resolve<T: 'static>(&self) -> Result<&T, MissingService> {
+        self.services
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref::<T>())
+            .ok_or(MissingService(type_name::<T>()))
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.services.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// Wiring in one place: every service that needs a sender gets the registered one
+pub fn password_resets(container: &Container) -> Result<PasswordResetService, MissingService> {
+    let sender = container.resolve::<Rc<dyn EmailSender>>()?;
+    Ok(PasswordResetService::new(Rc::clone(sender)))
+}
+
+pub fn signups(container: &Container) -> Result<SignupService<Rc<dyn EmailSender>>, MissingService> {
+    let sender = container.resolve::<Rc<dyn EmailSender>>()?;
+    Ok(SignupService::new(Rc::clone(sender)))
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct DependencyInjectionPatternDemo;
+
+impl Demo for DependencyInjectionPatternDemo {
+    fn name(&self) -> &'static str {
+        "dependency_injection_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Constructor, trait-object and container injection of an email sender, with a mock swapped in"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Constructor Injection With Generics =====")?;
+        let mut signups_smtp = SignupService::new(SmtpEmailSender::new("smtp.example.com"));
+        writeln!(out, "ana@example.com registered as user {}", signups_smtp.register("ana@example.com").unwrap())?;
+        for address in ["ana@example.com", "not-an-address"] {
+            writeln!(out, "{}: {}", address, signups_smtp.register(address).unwrap_err())?;
+        }
+        for line in signups_smtp.sender.transcript() {
+            writeln!(out, "  {}", line)?;
+        }
+
+        writeln!(out, "\n===== Swapping in a Mock =====")?;
+        let mock = MockEmailSender::failing_for("bounce@example.com");
+        let mut signups_mock = SignupService::new(&mock);
+        signups_mock.register("ben@example.com").unwrap();
+        writeln!(out, "bounce@example.com: {}", signups_mock.register("bounce@example.com").unwrap_err())?;
+        writeln!(out, "Registered: {:?}", signups_mock.users())?;
+        for email in mock.sent() {
+            writeln!(out, "  mock saw {:?} to {}: {}", email.subject, email.to, email.body)?;
+        }
+
+        writeln!(out, "\n===== Trait Objects Shared Between Services =====")?;
+        let shared: Rc<dyn EmailSender> = Rc::new(MockEmailSender::new());
+        let resets = PasswordResetService::new(Rc::clone(&shared));
+        let mut signups_shared = SignupService::new(Rc::clone(&shared));
+        signups_shared.register("cy@example.com").unwrap();
+        writeln!(out, "Token sent to cy: {}", resets.request_reset("cy@example.com").unwrap())?;
+        writeln!(out, "Rc<dyn EmailSender> held by {} owners", Rc::strong_count(&shared))?;
+
+        writeln!(out, "\n===== A Service Container =====")?;
+        let mut container = Container::new();
+        writeln!(out, "Before wiring, password_resets fails: {}", password_resets(&container).is_err())?;
+        let smtp = Rc::new(SmtpEmailSender::new("mail.internal"));
+        container.register::<Rc<dyn EmailSender>>(smtp.clone()).register(String::from("tech-notes"));
+        let resets = password_resets(&container).expect("sender registered");
+        let mut signups_wired = signups(&container).expect("sender registered");
+        signups_wired.register("di@example.com").unwrap();
+        resets.request_reset("di@example.com").unwrap();
+        writeln!(out, "App name from the container: {}", container.resolve::<String>().unwrap())?;
+        writeln!(out, "Registered a u32? {}", container.contains::<u32>())?;
+        writeln!(out, "resolve::<u32>(): {}", container.resolve::<u32>().unwrap_err())?;
+        for line in smtp.transcript() {
+            writeln!(out, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&DependencyInjectionPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signup_sends_a_welcome_email_through_the_injected_mock() {
+        let mock = MockEmailSender::new();
+        let mut service = SignupService::new(&mock);
+        assert_eq!(service.register("ana@example.com"), Ok(1));
+        assert_eq!(service.register("ben@example.com"), Ok(2));
+        assert_eq!(
+            mock.sent()[1],
+            Email { to: "ben@example.com".into(), subject: "Welcome!".into(), body: "You are user number 2.".into() }
+        );
+    }
+
+    #[test]
+    fn a_failed_send_keeps_the_user_out() {
+        let mock = MockEmailSender::failing_for("bounce@example.com");
+        let mut service = SignupService::new(&mock);
+        assert_eq!(
+            service.register("bounce@example.com"),
+            Err(SignupError::Email(SendError("mock refused bounce@example.com".into())))
+        );
+        assert!(service.users().is_empty());
+        assert_eq!(service.register("ok@example.com"), Ok(1));
+        assert_eq!(service.register("ok@example.com"), Err(SignupError::AlreadyRegistered("ok@example.com".into())));
+        assert_eq!(mock.sent().len(), 1);
+    }
+
+    #[test]
+    fn the_real_sender_plugs_into_the_same_service() {
+        let smtp = SmtpEmailSender::new("smtp.test");
+        let mut service = SignupService::new(&smtp);
+        service.register("ana@example.com").unwrap();
+        assert_eq!(smtp.transcript(), ["smtp.test: RCPT TO:<ana@example.com>", "smtp.test: Subject: Welcome!"]);
+        assert_eq!(service.register("nope"), Err(SignupError::InvalidAddress("nope".into())));
+    }
+
+    #[test]
+    fn trait_object_services_share_one_sender() {
+        let mock = Rc::new(MockEmailSender::new());
+        let shared: Rc<dyn EmailSender> = mock.clone();
+        let resets = PasswordResetService::new(Rc::clone(&shared));
+        let mut signups = SignupService::new(shared);
+        signups.register("ana@example.com").unwrap();
+        assert_eq!(resets.request_reset("ana@example.com"), Ok("reset-1".into()));
+        assert_eq!(resets.request_reset("ana@example.com"), Ok("reset-2".into()));
+        let subjects: Vec<String> = mock.sent().into_iter().map(|email| email.subject).collect();
+        assert_eq!(subjects, ["Welcome!", "Reset your password", "Reset your password"]);
+    }
+
+    #[test]
+    fn container_resolves_by_type_and_reports_what_is_missing() {
+        let mut container = Container::new();
+        let missing = password_resets(&container).err().unwrap();
+        assert!(missing.0.contains("EmailSender"), "{}", missing);
+        let mock = Rc::new(MockEmailSender::new());
+        container.register::<Rc<dyn EmailSender>>(mock.clone()).register(42u32);
+        assert_eq!(container.resolve::<u32>(), Ok(&42));
+        assert_eq!(container.resolve::<u64>(), Err(MissingService("u64")));
+        container.register(7u32);
+        assert_eq!(container.resolve::<u32>(), Ok(&7), "registering again replaces");
+
+        password_resets(&container).unwrap().request_reset("ana@example.com").unwrap();
+        assert_eq!(mock.sent()[0].to, "ana@example.com");
+    }
+}
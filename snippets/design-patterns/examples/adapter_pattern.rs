@@ -0,0 +1,3 @@
+fn main() {
+    patterns::adapter_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::bridge_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::builder_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::chain_of_responsibility_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::dependency_injection_pattern::run_demo();
+}
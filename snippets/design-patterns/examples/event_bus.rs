@@ -0,0 +1,3 @@
+fn main() {
+    patterns::event_bus::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::facade_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::factory_pattern::run_demo();
+}
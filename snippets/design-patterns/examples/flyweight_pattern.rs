@@ -0,0 +1,3 @@
+fn main() {
+    patterns::flyweight_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::interpreter_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::iterator_pattern::run_demo();
+}
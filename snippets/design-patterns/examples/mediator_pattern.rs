@@ -0,0 +1,3 @@
+fn main() {
+    patterns::mediator_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::memento_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::object_pool_pattern::run_demo();
+}
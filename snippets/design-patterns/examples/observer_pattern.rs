@@ -0,0 +1,3 @@
+fn main() {
+    patterns::observer_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::prototype_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::proxy_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::repository_pattern::run_demo();
+}
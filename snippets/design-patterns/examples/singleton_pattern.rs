@@ -0,0 +1,3 @@
+fn main() {
+    patterns::singleton_pattern::run_demo();
+}
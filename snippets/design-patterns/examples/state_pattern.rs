@@ -0,0 +1,3 @@
+fn main() {
+    patterns::state_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::strategy_pattern::run_demo();
+}
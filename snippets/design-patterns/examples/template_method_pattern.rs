@@ -0,0 +1,3 @@
+fn main() {
+    patterns::template_method_pattern::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    patterns::threaded_observer_pattern::run_demo();
+}
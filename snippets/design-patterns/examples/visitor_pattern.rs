@@ -0,0 +1,3 @@
+fn main() {
+    patterns::visitor_pattern::run_demo();
+}
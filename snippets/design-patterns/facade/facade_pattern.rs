@@ -0,0 +1,420 @@
+//! Facade Pattern Implementation in Rust
+//!
+//! The Facade Pattern is a structural design pattern that puts one simple interface in
+//! front of a set of subsystems. Clients call a handful of high-level methods, and the
+//! facade knows which subsystems to drive and in what order.
+//!
+//! This example is a home theater:
+//! - Subsystems: `Lights`, `Screen`, `Projector`, `Amplifier` and `StreamingPlayer`, each
+//!   with its own small API and no knowledge of the others
+//! - `HomeTheaterFacade` owns them and offers `watch_movie()` and `end_movie()`
+//! - Every subsystem call is written to a shared `EventLog`, which the tests inject to
+//!   check the exact order of calls
+//!
+//! The facade does not lock the subsystems away: `amplifier()` still gives direct
+//! access for the one caller who wants to turn the volume up. And when the player
+//! cannot find a title, `watch_movie()` undoes the steps it already took, which is the
+//! kind of sequencing the facade exists to keep out of client code.
+
+use demo::Demo;
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Shared record of every subsystem call, in order
+pub type EventLog = Rc<RefCell<Vec<String>>>;
+
+fn record(log: &EventLog, event: impl Into<String>) {
+    log.borrow_mut().push(event.into());
+}
+
+// ========== Subsystems ==========
+
+pub struct Lights {
+    log: EventLog,
+}
+
+impl Lights {
+    pub fn new(log: EventLog) -> Self {
+        Lights { log }
+    }
+
+    pub fn dim(&mut self, percent: u8) {
+        record(&self.log, format!("lights: dim to {}%", percent));
+    }
+
+    pub fn on(&mut self) {
+        record(&self.log, "lights: on");
+    }
+}
+
+pub struct Screen {
+    log: EventLog,
+}
+
+impl Screen {
+    pub fn new(log: EventLog) -> Self {
+        Screen { log }
+    }
+
+    pub fn lower(&mut self) {
+        record(&self.log, "screen: lower");
+    }
+
+    pub fn raise(&mut self) {
+        record(&self.log, "screen: raise");
+    }
+}
+
+pub struct Projector {
+    log: EventLog,
+}
+
+impl Projector {
+    pub fn new(log: EventLog) -> Self {
+        Projector { log }
+    }
+
+    pub fn on(&mut self) {
+        record(&self.log, "projector: on");
+    }
+
+    pub fn set_input(&mut self, input: &str) {
+        record(&self.log, format!("projector: input {}", input));
+    }
+
+    pub fn wide_screen_mode(&mut self) {
+        record(&self.log, "projector: wide screen mode");
+    }
+
+    pub fn off(&mut self) {
+        record(&self.log, "projector: off");
+    }
+}
+
+pub struct Amplifier {
+    log: EventLog,
+    volume: u8,
+}
+
+impl Amplifier {
+    pub const MAX_VOLUME: u8 = 30;
+
+    pub fn new(log: EventLog) -> Self {
+        Amplifier { log, volume: 0 }
+    }
+
+    pub fn on(&mut self) {
+        record(&self.log, "amplifier: on");
+    }
+
+    pub fn set_input(&mut self, input: &str) {
+        record(&self.log, format!("amplifier: input {}", input));
+    }
+
+    pub fn surround_sound(&mut self) {
+        record(&self.log, "amplifier: surround sound");
+    }
+
+    /// Clamps to `MAX_VOLUME`
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(Self::MAX_VOLUME);
+        record(&self.log, format!("amplifier: volume {}", self.volume));
+    }
+
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    pub fn off(&mut self) {
+        record(&self.log, "amplifier: off");
+    }
+}
+
+pub struct StreamingPlayer {
+    log: EventLog,
+    catalogue: Vec<String>,
+    playing: Option<String>,
+}
+
+impl StreamingPlayer {
+    pub fn new(log: EventLog, catalogue: &[&str]) -> Self {
+        StreamingPlayer { log, catalogue: catalogue.iter().map(|title| title.to_string()).collect(), playing: None }
+    }
+
+    pub fn on(&mut self) {
+        record(&self.log, "player: on");
+    }
+
+    pub fn play(&mut self, title: &str) -> Result<(), TheaterError> {
+        if !self.catalogue.iter().any(|known| known == title) {
+            record(&self.log, format!("player: {:?} not found", title));
+            return Err(TheaterError::NotInCatalogue(title.to_string()));
+        }
+        record(&self.log, format!("player: play {:?}", title));
+        self.playing = Some(title.to_string());
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(title) = self.playing.take() {
+            record(&self.log, format!("player: stop {:?}", title));
+        }
+    }
+
+    pub fn playing(&self) -> Option<&str> {
+        self.playing.as_deref()
+    }
+
+    pub fn off(&mut self) {
+        record(&self.log, "player: off");
+    }
+}
+
+// ========== Facade ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TheaterError {
+    NotInCatalogue(String),
+    AlreadyPlaying(String),
+    NothingPlaying,
+}
+
+impl fmt::Display for TheaterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TheaterError::NotInCatalogue(title) => write!(f, "{:?} is not in the catalogue", title),
+            TheaterError::AlreadyPlaying(title) => write!(f, "already playing {:?}", title),
+            TheaterError::NothingPlaying => write!(f, "nothing is playing"),
+        }
+    }
+}
+
+impl std::error::Error for TheaterError {}
+
+pub struct HomeTheaterFacade {
+    lights: Lights,
+    screen: Screen,
+    projector: Projector,
+    amplifier: Amplifier,
+    player: StreamingPlayer,
+}
+
+impl HomeTheaterFacade {
+    pub const MOVIE_VOLUME: u8 = 12;
+
+    pub fn new(
+        lights: Lights,
+        screen: Screen,
+        projector: Projector,
+        amplifier: Amplifier,
+        player: StreamingPlayer,
+    ) -> Self {
+        HomeTheaterFacade { lights, screen, projector, amplifier, player }
+    }
+
+    /// Builds every subsystem against the same log
+    pub fn with_log(log: &EventLog, catalogue: &[&str]) -> Self {
+        Self::new(
+            Lights::new(log.clone()),
+            Screen::new(log.clone()),
+            Projector::new(log.clone()),
+            Amplifier::new(log.clone()),
+            StreamingPlayer::new(log.clone(), catalogue),
+        )
+    }
+
+    /// Brings the room up for `title`; if the player cannot start it, the room is put back
+    pub fn watch_movie(&mut self, title: &str) -> Result<(), TheaterError> {
+        if let Some(current) = self.player.playing() {
+            return Err(TheaterError::AlreadyPlaying(current.to_string()));
+        }
+        self.lights.dim(10);
+        self.screen.lower();
+        self.projector.on();
+        self.projector.set_input("streaming");
+        self.projector.wide_screen_mode();
+        self.amplifier.on();
+        self.amplifier.set_input("streaming");
+        self.amplifier.surround_sound();
+        self.amplifier.set_volume(Self::MOVIE_VOLUME);
+        self.player.on();
+        if let Err(error) = self.player.play(title) {
+            self.shut_down();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    pub fn end_movie(&mut self) -> Result<(), TheaterError> {
+        if self.player.playing().is_none() {
+            return Err(TheaterError::NothingPlaying);
+        }
+        self.player.stop();
+        self.shut_down();
+        Ok(())
+    }
+
+    pub fn now_playing(&self) -> Option<&str> {
+        self.player.playing()
+    }
+
+    /// Direct access for callers who need more than the facade offers
+    pub fn amplifier(&mut self) -> &mut Amplifier {
+        &mut self.amplifier
+    }
+
+    /// Reverse of the start-up order
+    fn shut_down(&mut self) {
+        self.player.off();
+        self.amplifier.off();
+        self.projector.off();
+        self.screen.raise();
+        self.lights.on();
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FacadePatternDemo;
+
+impl FacadePatternDemo {
+    fn drain(log: &EventLog, out: &mut dyn Write) -> io::Result<()> {
+        for event in log.borrow_mut().drain(..) {
+            writeln!(out, "  {}", event)?;
+        }
+        Ok(())
+    }
+}
+
+impl Demo for FacadePatternDemo {
+    fn name(&self) -> &'static str {
+        "facade_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Home theater facade sequencing lights, screen, projector, amplifier and player"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let log = EventLog::default();
+        let mut theater = HomeTheaterFacade::with_log(&log, &["Metropolis", "Sunrise"]);
+
+        writeln!(out, "===== Watching a Movie =====")?;
+        theater.watch_movie("Metropolis").expect("title is in the catalogue");
+        Self::drain(&log, out)?;
+        writeln!(out, "Now playing: {}", theater.now_playing().unwrap_or("nothing"))?;
+
+        writeln!(out, "\n===== Reaching Past the Facade =====")?;
+        theater.amplifier().set_volume(40);
+        Self::drain(&log, out)?;
+        if let Err(error) = theater.watch_movie("Sunrise") {
+            writeln!(out, "watch_movie(\"Sunrise\"): {}", error)?;
+        }
+
+        writeln!(out, "\n===== Ending the Movie =====")?;
+        theater.end_movie().expect("a movie is playing");
+        Self::drain(&log, out)?;
+        if let Err(error) = theater.end_movie() {
+            writeln!(out, "end_movie() again: {}", error)?;
+        }
+
+        writeln!(out, "\n===== A Title That Is Not There =====")?;
+        if let Err(error) = theater.watch_movie("Nosferatu") {
+            Self::drain(&log, out)?;
+            writeln!(out, "watch_movie(\"Nosferatu\"): {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FacadePatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theater() -> (EventLog, HomeTheaterFacade) {
+        let log = EventLog::default();
+        let theater = HomeTheaterFacade::with_log(&log, &["Metropolis"]);
+        (log, theater)
+    }
+
+    fn take(log: &EventLog) -> Vec<String> {
+        log.borrow_mut().drain(..).collect()
+    }
+
+    const SHUT_DOWN: [&str; 5] = ["player: off", "amplifier: off", "projector: off", "screen: raise", "lights: on"];
+
+    #[test]
+    fn watch_movie_drives_the_subsystems_in_order() {
+        let (log, mut theater) = theater();
+        theater.watch_movie("Metropolis").unwrap();
+        assert_eq!(
+            take(&log),
+            [
+                "lights: dim to 10%",
+                "screen: lower",
+                "projector: on",
+                "projector: input streaming",
+                "projector: wide screen mode",
+                "amplifier: on",
+                "amplifier: input streaming",
+                "amplifier: surround sound",
+                "amplifier: volume 12",
+                "player: on",
+                "player: play \"Metropolis\"",
+            ]
+        );
+        assert_eq!(theater.now_playing(), Some("Metropolis"));
+    }
+
+    #[test]
+    fn end_movie_stops_playback_then_shuts_down_in_reverse() {
+        let (log, mut theater) = theater();
+        theater.watch_movie("Metropolis").unwrap();
+        take(&log);
+        theater.end_movie().unwrap();
+        let mut expected = vec!["player: stop \"Metropolis\""];
+        expected.extend(SHUT_DOWN);
+        assert_eq!(take(&log), expected);
+        assert_eq!(theater.now_playing(), None);
+    }
+
+    #[test]
+    fn unknown_title_rolls_the_room_back() {
+        let (log, mut theater) = theater();
+        assert_eq!(theater.watch_movie("Nosferatu"), Err(TheaterError::NotInCatalogue("Nosferatu".into())));
+        let events = take(&log);
+        let failed = events.iter().position(|event| event == "player: \"Nosferatu\" not found").unwrap();
+        assert_eq!(events[failed + 1..], SHUT_DOWN);
+        assert_eq!(theater.now_playing(), None);
+    }
+
+    #[test]
+    fn misuse_is_rejected_without_touching_any_subsystem() {
+        let (log, mut theater) = theater();
+        assert_eq!(theater.end_movie(), Err(TheaterError::NothingPlaying));
+        assert!(take(&log).is_empty());
+        theater.watch_movie("Metropolis").unwrap();
+        take(&log);
+        assert_eq!(theater.watch_movie("Metropolis"), Err(TheaterError::AlreadyPlaying("Metropolis".into())));
+        assert!(take(&log).is_empty());
+    }
+
+    #[test]
+    fn subsystems_stay_reachable_through_the_facade() {
+        let (log, mut theater) = theater();
+        theater.amplifier().set_volume(99);
+        assert_eq!(theater.amplifier().volume(), Amplifier::MAX_VOLUME);
+        assert_eq!(take(&log), ["amplifier: volume 30"]);
+    }
+}
@@ -1,28 +1,36 @@
-/**
- * Factory Pattern Implementation in Rust
- *
- * The Factory Pattern is a creational design pattern that provides an interface for creating
- * objects in a superclass, but allows subclasses to alter the type of objects that will be created.
- *
- * This example demonstrates a Vehicle Factory that can create different types of vehicles
- * (Car, Motorcycle, Truck) based on the client's requirements.
- */
-
-use std::fmt;
-
-// Abstract Product - Vehicle Trait
-trait Vehicle {
-    fn get_info(&self) -> String;
-    fn start(&self) -> String {
-        format!("{} is starting...", self.get_info())
-    }
-    fn stop(&self) -> String {
-        format!("{} is stopping...", self.get_info())
-    }
+//! Factory Pattern Implementation in Rust
+//!
+//! The Factory Pattern is a creational design pattern that provides an interface for creating
+//! objects in a superclass, but allows subclasses to alter the type of objects that will be created.
+//!
+//! This example demonstrates a Vehicle Factory that can create different types of vehicles
+//! (Car, Motorcycle, Truck) based on the client's requirements.
+//!
+//! The products derive `Describe` from the `proc-macros` crate, which lists their fields,
+//! and the simple factory is wrapped in `#[trace]`, which logs each call.
+
+use demo::Demo;
+use proc_macros::{trace, Describe};
+use std::cell::RefCell;
+use std::io::{self, Write};
+
+thread_local! {
+    /// Lines logged by `#[trace]`, until the demo prints them
+    static TRACE: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn trace_line(line: &str) {
+    TRACE.with(|trace| trace.borrow_mut().push(line.to_string()));
+}
+
+/// Takes the lines `#[trace]` has logged on this thread so far
+pub fn take_trace() -> Vec<String> {
+    TRACE.with(|trace| trace.take())
 }
 
 // Concrete Products
-struct Car {
+#[derive(Describe)]
+pub struct Car {
     make: String,
     model: String,
     year: u32,
@@ -30,7 +38,7 @@ struct Car {
 }
 
 impl Car {
-    fn new(make: &str, model: &str, year: u32, doors: u32) -> Self {
+    pub fn new(make: &str, model: &str, year: u32, doors: u32) -> Self {
         Car {
             make: make.to_string(),
             model: model.to_string(),
@@ -39,7 +47,7 @@ impl Car {
         }
     }
 
-    fn drive(&self) -> String {
+    pub fn drive(&self) -> String {
         format!("{} is driving on the road.", self.get_info())
     }
 }
@@ -50,7 +58,8 @@ impl Vehicle for Car {
     }
 }
 
-struct Motorcycle {
+#[derive(Describe)]
+pub struct Motorcycle {
     make: String,
     model: String,
     year: u32,
@@ -58,7 +67,7 @@ struct Motorcycle {
 }
 
 impl Motorcycle {
-    fn new(make: &str, model: &str, year: u32, engine_size: u32) -> Self {
+    pub fn new(make: &str, model: &str, year: u32, engine_size: u32) -> Self {
         Motorcycle {
             make: make.to_string(),
             model: model.to_string(),
@@ -67,7 +76,7 @@ impl Motorcycle {
         }
     }
 
-    fn ride(&self) -> String {
+    pub fn ride(&self) -> String {
         format!("{} is riding at high speed.", self.get_info())
     }
 }
@@ -81,7 +90,8 @@ impl Vehicle for Motorcycle {
     }
 }
 
-struct Truck {
+#[derive(Describe)]
+pub struct Truck {
     make: String,
     model: String,
     year: u32,
@@ -89,7 +99,7 @@ struct Truck {
 }
 
 impl Truck {
-    fn new(make: &str, model: &str, year: u32, capacity: f64) -> Self {
+    pub fn new(make: &str, model: &str, year: u32, capacity: f64) -> Self {
         Truck {
             make: make.to_string(),
             model: model.to_string(),
@@ -98,7 +108,7 @@ impl Truck {
         }
     }
 
-    fn haul(&self) -> String {
+    pub fn haul(&self) -> String {
         format!("{} is hauling cargo.", self.get_info())
     }
 }
@@ -113,28 +123,30 @@ impl Vehicle for Truck {
 }
 
 // Simple Factory
-enum VehicleType {
+#[derive(Debug)]
+pub enum VehicleType {
     Car,
     Motorcycle,
     Truck,
 }
 
-struct VehicleFactory;
+pub struct VehicleFactory;
 
 impl VehicleFactory {
-    fn create_car(make: &str, model: &str, year: u32, doors: u32) -> Box<dyn Vehicle> {
+    pub fn create_car(make: &str, model: &str, year: u32, doors: u32) -> Box<dyn Vehicle> {
         Box::new(Car::new(make, model, year, doors))
     }
 
-    fn create_motorcycle(make: &str, model: &str, year: u32, engine_size: u32) -> Box<dyn Vehicle> {
+    pub fn create_motorcycle(make: &str, model: &str, year: u32, engine_size: u32) -> Box<dyn Vehicle> {
         Box::new(Motorcycle::new(make, model, year, engine_size))
     }
 
-    fn create_truck(make: &str, model: &str, year: u32, capacity: f64) -> Box<dyn Vehicle> {
+    pub fn create_truck(make: &str, model: &str, year: u32, capacity: f64) -> Box<dyn Vehicle> {
         Box::new(Truck::new(make, model, year, capacity))
     }
 
-    fn create_vehicle(
+    #[trace(sink = trace_line)]
+    pub fn create_vehicle(
         vehicle_type: VehicleType,
         make: &str,
         model: &str,
@@ -159,20 +171,27 @@ impl VehicleFactory {
 }
 
 // Factory Method Pattern Implementation
-trait VehicleFactoryMethod {
+pub trait VehicleFactoryMethod {
     fn create_vehicle(&self, make: &str, model: &str, year: u32, options: &[f64]) -> Box<dyn Vehicle>;
 
-    fn register_vehicle(&self, make: &str, model: &str, year: u32, options: &[f64]) -> Box<dyn Vehicle> {
+    fn register_vehicle(
+        &self,
+        make: &str,
+        model: &str,
+        year: u32,
+        options: &[f64],
+        log: &mut dyn Write,
+    ) -> io::Result<Box<dyn Vehicle>> {
         // Common operations for all vehicles
         let vehicle = self.create_vehicle(make, model, year, options);
-        println!("Registering {}", vehicle.get_info());
-        println!("Assigning license plate");
-        vehicle
+        writeln!(log, "Registering {}", vehicle.get_info())?;
+        writeln!(log, "Assigning license plate")?;
+        Ok(vehicle)
     }
 }
 
 // Concrete Factories
-struct CarFactory;
+pub struct CarFactory;
 
 impl VehicleFactoryMethod for CarFactory {
     fn create_vehicle(&self, make: &str, model: &str, year: u32, options: &[f64]) -> Box<dyn Vehicle> {
@@ -181,7 +200,7 @@ impl VehicleFactoryMethod for CarFactory {
     }
 }
 
-struct MotorcycleFactory;
+pub struct MotorcycleFactory;
 
 impl VehicleFactoryMethod for MotorcycleFactory {
     fn create_vehicle(&self, make: &str, model: &str, year: u32, options: &[f64]) -> Box<dyn Vehicle> {
@@ -190,7 +209,7 @@ impl VehicleFactoryMethod for MotorcycleFactory {
     }
 }
 
-struct TruckFactory;
+pub struct TruckFactory;
 
 impl VehicleFactoryMethod for TruckFactory {
     fn create_vehicle(&self, make: &str, model: &str, year: u32, options: &[f64]) -> Box<dyn Vehicle> {
@@ -201,38 +220,40 @@ impl VehicleFactoryMethod for TruckFactory {
 
 // Abstract Factory Pattern Implementation
 // Parts
-struct Engine {
+#[derive(Describe)]
+pub struct Engine {
     engine_type: String,
     horsepower: u32,
 }
 
 impl Engine {
-    fn new(engine_type: &str, horsepower: u32) -> Self {
+    pub fn new(engine_type: &str, horsepower: u32) -> Self {
         Engine {
             engine_type: engine_type.to_string(),
             horsepower,
         }
     }
 
-    fn get_specs(&self) -> String {
+    pub fn get_specs(&self) -> String {
         format!("{} engine with {}hp", self.engine_type, self.horsepower)
     }
 }
 
-struct Transmission {
+#[derive(Describe)]
+pub struct Transmission {
     transmission_type: String,
     gears: u32,
 }
 
 impl Transmission {
-    fn new(transmission_type: &str, gears: u32) -> Self {
+    pub fn new(transmission_type: &str, gears: u32) -> Self {
         Transmission {
             transmission_type: transmission_type.to_string(),
             gears,
         }
     }
 
-    fn get_specs(&self) -> String {
+    pub fn get_specs(&self) -> String {
         format!(
             "{} transmission with {} gears",
             self.transmission_type, self.gears
@@ -240,33 +261,34 @@ impl Transmission {
     }
 }
 
-struct Chassis {
+#[derive(Describe)]
+pub struct Chassis {
     material: String,
     weight: f64,
 }
 
 impl Chassis {
-    fn new(material: &str, weight: f64) -> Self {
+    pub fn new(material: &str, weight: f64) -> Self {
         Chassis {
             material: material.to_string(),
             weight,
         }
     }
 
-    fn get_specs(&self) -> String {
+    pub fn get_specs(&self) -> String {
         format!("{} chassis weighing {}kg", self.material, self.weight)
     }
 }
 
 // Abstract Factory
-trait VehiclePartsFactory {
+pub trait VehiclePartsFactory {
     fn create_engine(&self) -> Engine;
     fn create_transmission(&self) -> Transmission;
     fn create_chassis(&self) -> Chassis;
 }
 
 // Concrete Abstract Factories
-struct SportVehiclePartsFactory;
+pub struct SportVehiclePartsFactory;
 
 impl VehiclePartsFactory for SportVehiclePartsFactory {
     fn create_engine(&self) -> Engine {
@@ -282,7 +304,7 @@ impl VehiclePartsFactory for SportVehiclePartsFactory {
     }
 }
 
-struct EconomyVehiclePartsFactory;
+pub struct EconomyVehiclePartsFactory;
 
 impl VehiclePartsFactory for EconomyVehiclePartsFactory {
     fn create_engine(&self) -> Engine {
@@ -298,7 +320,7 @@ impl VehiclePartsFactory for EconomyVehiclePartsFactory {
     }
 }
 
-struct HeavyDutyVehiclePartsFactory;
+pub struct HeavyDutyVehiclePartsFactory;
 
 impl VehiclePartsFactory for HeavyDutyVehiclePartsFactory {
     fn create_engine(&self) -> Engine {
@@ -315,30 +337,30 @@ impl VehiclePartsFactory for HeavyDutyVehiclePartsFactory {
 }
 
 // Vehicle Assembler - Uses the Abstract Factory
-struct VehicleAssembler<T: VehiclePartsFactory> {
+pub struct VehicleAssembler<T: VehiclePartsFactory> {
     parts_factory: T,
 }
 
 impl<T: VehiclePartsFactory> VehicleAssembler<T> {
-    fn new(parts_factory: T) -> Self {
+    pub fn new(parts_factory: T) -> Self {
         VehicleAssembler { parts_factory }
     }
 
-    fn assemble_vehicle(&self) {
+    pub fn assemble_vehicle(&self, out: &mut dyn Write) -> io::Result<()> {
         let engine = self.parts_factory.create_engine();
         let transmission = self.parts_factory.create_transmission();
         let chassis = self.parts_factory.create_chassis();
 
-        println!("Assembling vehicle with:");
-        println!("- {}", engine.get_specs());
-        println!("- {}", transmission.get_specs());
-        println!("- {}", chassis.get_specs());
+        writeln!(out, "Assembling vehicle with:")?;
+        writeln!(out, "- {}", engine.get_specs())?;
+        writeln!(out, "- {}", transmission.get_specs())?;
+        writeln!(out, "- {}", chassis.get_specs())
     }
 }
 
 // Client code demonstration
-fn client_code() {
-    println!("===== Simple Factory Pattern =====");
+fn client_code(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "===== Simple Factory Pattern =====")?;
 
     let car = VehicleFactory::create_vehicle(
         VehicleType::Car,
@@ -362,59 +384,80 @@ fn client_code() {
         &[3.0],
     );
 
-    println!("{}", car.get_info());
-    // We need to downcast to call specific methods
-    if let Some(car) = car.as_any().downcast_ref::<Car>() {
-        println!("{}", car.drive());
+    writeln!(out, "{}", car.get_info())?;
+    // We need to downcast to call specific methods (deref first, see AsAny below)
+    if let Some(car) = (*car).as_any().downcast_ref::<Car>() {
+        writeln!(out, "{}", car.drive())?;
+    }
+
+    writeln!(out, "{}", motorcycle.get_info())?;
+    if let Some(motorcycle) = (*motorcycle).as_any().downcast_ref::<Motorcycle>() {
+        writeln!(out, "{}", motorcycle.ride())?;
     }
 
-    println!("{}", motorcycle.get_info());
-    if let Some(motorcycle) = motorcycle.as_any().downcast_ref::<Motorcycle>() {
-        println!("{}", motorcycle.ride());
+    writeln!(out, "{}", truck.get_info())?;
+    if let Some(truck) = (*truck).as_any().downcast_ref::<Truck>() {
+        writeln!(out, "{}", truck.haul())?;
     }
 
-    println!("{}", truck.get_info());
-    if let Some(truck) = truck.as_any().downcast_ref::<Truck>() {
-        println!("{}", truck.haul());
+    writeln!(out, "\nCalls traced by #[trace]:")?;
+    for line in take_trace() {
+        writeln!(out, "{}", line)?;
     }
 
-    println!("\n===== Factory Method Pattern =====");
+    writeln!(out, "\n===== Factory Method Pattern =====")?;
 
     let car_factory = CarFactory;
     let motorcycle_factory = MotorcycleFactory;
     let truck_factory = TruckFactory;
 
-    let new_car = car_factory.register_vehicle("BMW", "3 Series", 2023, &[2.0]);
-    let new_motorcycle = motorcycle_factory.register_vehicle("Ducati", "Monster", 2023, &[821.0]);
-    let new_truck = truck_factory.register_vehicle("Volvo", "VNL", 2023, &[20.0]);
+    let new_car = car_factory.register_vehicle("BMW", "3 Series", 2023, &[2.0], out)?;
+    let new_motorcycle = motorcycle_factory.register_vehicle("Ducati", "Monster", 2023, &[821.0], out)?;
+    let new_truck = truck_factory.register_vehicle("Volvo", "VNL", 2023, &[20.0], out)?;
 
-    if let Some(car) = new_car.as_any().downcast_ref::<Car>() {
-        println!("{}", car.drive());
+    if let Some(car) = (*new_car).as_any().downcast_ref::<Car>() {
+        writeln!(out, "{}", car.drive())?;
     }
-    if let Some(motorcycle) = new_motorcycle.as_any().downcast_ref::<Motorcycle>() {
-        println!("{}", motorcycle.ride());
+    if let Some(motorcycle) = (*new_motorcycle).as_any().downcast_ref::<Motorcycle>() {
+        writeln!(out, "{}", motorcycle.ride())?;
     }
-    if let Some(truck) = new_truck.as_any().downcast_ref::<Truck>() {
-        println!("{}", truck.haul());
+    if let Some(truck) = (*new_truck).as_any().downcast_ref::<Truck>() {
+        writeln!(out, "{}", truck.haul())?;
     }
 
-    println!("\n===== Abstract Factory Pattern =====");
+    writeln!(out, "\n===== Abstract Factory Pattern =====")?;
 
-    println!("Building a sports car:");
+    writeln!(out, "Building a sports car:")?;
     let sport_car_assembler = VehicleAssembler::new(SportVehiclePartsFactory);
-    sport_car_assembler.assemble_vehicle();
+    sport_car_assembler.assemble_vehicle(out)?;
 
-    println!("\nBuilding an economy car:");
+    writeln!(out, "\nBuilding an economy car:")?;
     let economy_car_assembler = VehicleAssembler::new(EconomyVehiclePartsFactory);
-    economy_car_assembler.assemble_vehicle();
+    economy_car_assembler.assemble_vehicle(out)?;
 
-    println!("\nBuilding a heavy duty truck:");
+    writeln!(out, "\nBuilding a heavy duty truck:")?;
     let heavy_duty_truck_assembler = VehicleAssembler::new(HeavyDutyVehiclePartsFactory);
-    heavy_duty_truck_assembler.assemble_vehicle();
-}
-
-// Extension trait to allow downcasting
-trait AsAny {
+    heavy_duty_truck_assembler.assemble_vehicle(out)?;
+
+    writeln!(out, "\n===== Products and Parts, via #[derive(Describe)] =====")?;
+    for description in [
+        Car::describe(),
+        Motorcycle::describe(),
+        Truck::describe(),
+        Engine::describe(),
+        Transmission::describe(),
+        Chassis::describe(),
+    ] {
+        writeln!(out, "{}", description)?;
+    }
+    Ok(())
+}
+
+// Extension trait to allow downcasting.
+// Call it on the trait object, `(*boxed).as_any()`: the blanket impl also covers
+// `Box<dyn Vehicle>` itself, so `boxed.as_any()` would hand back the Box as `Any`
+// and every `downcast_ref` would fail.
+pub trait AsAny {
     fn as_any(&self) -> &dyn std::any::Any;
 }
 
@@ -425,13 +468,13 @@ impl<T: 'static> AsAny for T {
 }
 
 // Extend Vehicle trait to include AsAny
-trait VehicleExt: Vehicle + AsAny {}
+pub trait VehicleExt: Vehicle + AsAny {}
 
 // Implement VehicleExt for all types that implement Vehicle
 impl<T: Vehicle + AsAny> VehicleExt for T {}
 
-// Update the Vehicle trait to include AsAny functionality
-trait Vehicle: AsAny {
+// Abstract Product - Vehicle trait, with AsAny so callers can downcast
+pub trait Vehicle: AsAny {
     fn get_info(&self) -> String;
     fn start(&self) -> String {
         format!("{} is starting...", self.get_info())
@@ -441,7 +484,133 @@ trait Vehicle: AsAny {
     }
 }
 
-fn main() {
-    // Run the example
-    client_code();
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FactoryPatternDemo;
+
+impl Demo for FactoryPatternDemo {
+    fn name(&self) -> &'static str {
+        "factory_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Simple factory, factory method and abstract factory"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        // Run the example
+        client_code(out)
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FactoryPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_factory_builds_the_requested_type() {
+        let car = VehicleFactory::create_vehicle(VehicleType::Car, "Toyota", "Camry", 2023, &[2.0]);
+        let bike =
+            VehicleFactory::create_vehicle(VehicleType::Motorcycle, "Honda", "CBR", 2023, &[600.0]);
+        let truck = VehicleFactory::create_vehicle(VehicleType::Truck, "Ford", "F-150", 2023, &[3.5]);
+
+        assert!((*car).as_any().downcast_ref::<Car>().is_some());
+        assert!((*bike).as_any().downcast_ref::<Motorcycle>().is_some());
+        assert!((*truck).as_any().downcast_ref::<Truck>().is_some());
+        assert!((*car).as_any().downcast_ref::<Truck>().is_none());
+
+        assert_eq!(car.get_info(), "2023 Toyota Camry (2-door car)");
+        assert_eq!(bike.get_info(), "2023 Honda CBR (600cc motorcycle)");
+        assert_eq!(truck.get_info(), "2023 Ford F-150 (3.5 ton truck)");
+    }
+
+    #[test]
+    fn simple_factory_falls_back_to_defaults_without_options() {
+        let car = VehicleFactory::create_vehicle(VehicleType::Car, "VW", "Golf", 2020, &[]);
+        let bike = VehicleFactory::create_vehicle(VehicleType::Motorcycle, "KTM", "Duke", 2020, &[]);
+        let truck = VehicleFactory::create_vehicle(VehicleType::Truck, "MAN", "TGX", 2020, &[]);
+
+        assert_eq!(car.get_info(), "2020 VW Golf (4-door car)");
+        assert_eq!(bike.get_info(), "2020 KTM Duke (250cc motorcycle)");
+        assert_eq!(truck.get_info(), "2020 MAN TGX (5 ton truck)");
+    }
+
+    #[test]
+    fn default_trait_methods_use_get_info() {
+        let car = VehicleFactory::create_car("BMW", "M3", 2024, 2);
+        assert_eq!(car.start(), "2024 BMW M3 (2-door car) is starting...");
+        assert_eq!(car.stop(), "2024 BMW M3 (2-door car) is stopping...");
+    }
+
+    #[test]
+    fn concrete_product_methods_survive_the_downcast() {
+        let truck = VehicleFactory::create_truck("Volvo", "VNL", 2023, 20.0);
+        let truck = (*truck).as_any().downcast_ref::<Truck>().unwrap();
+        assert_eq!(truck.haul(), "2023 Volvo VNL (20 ton truck) is hauling cargo.");
+    }
+
+    #[test]
+    fn factory_methods_create_their_own_product() {
+        let factories: [(&dyn VehicleFactoryMethod, &str); 3] = [
+            (&CarFactory, "(3-door car)"),
+            (&MotorcycleFactory, "(3cc motorcycle)"),
+            (&TruckFactory, "(3 ton truck)"),
+        ];
+        for (factory, suffix) in factories {
+            let vehicle = factory.create_vehicle("Make", "Model", 2000, &[3.0]);
+            assert!(vehicle.get_info().ends_with(suffix), "{}", vehicle.get_info());
+        }
+
+        let mut log = Vec::new();
+        let registered = MotorcycleFactory
+            .register_vehicle("Ducati", "Monster", 2023, &[821.0], &mut log)
+            .unwrap();
+        assert!((*registered).as_any().downcast_ref::<Motorcycle>().is_some());
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "Registering 2023 Ducati Monster (821cc motorcycle)\nAssigning license plate\n"
+        );
+    }
+
+    #[test]
+    fn create_vehicle_is_traced() {
+        take_trace();
+        VehicleFactory::create_vehicle(VehicleType::Truck, "MAN", "TGX", 2020, &[]);
+        assert_eq!(
+            take_trace(),
+            [
+                "-> create_vehicle(vehicle_type = Truck, make = \"MAN\", model = \"TGX\", year = 2020, options = [])",
+                "<- create_vehicle"
+            ]
+        );
+    }
+
+    #[test]
+    fn products_describe_their_fields() {
+        assert_eq!(Car::describe(), "Car { make: String, model: String, year: u32, doors: u32 }");
+        assert_eq!(Chassis::FIELDS, [("material", "String"), ("weight", "f64")]);
+    }
+
+    #[test]
+    fn parts_factories_produce_matching_families() {
+        let sport = SportVehiclePartsFactory;
+        assert_eq!(sport.create_engine().get_specs(), "V8 engine with 450hp");
+        assert_eq!(sport.create_transmission().get_specs(), "Manual transmission with 6 gears");
+        assert_eq!(sport.create_chassis().get_specs(), "Carbon Fiber chassis weighing 120kg");
+
+        let economy = EconomyVehiclePartsFactory;
+        assert_eq!(economy.create_engine().get_specs(), "Inline-4 engine with 180hp");
+        assert_eq!(economy.create_transmission().get_specs(), "Automatic transmission with 5 gears");
+
+        let heavy = HeavyDutyVehiclePartsFactory;
+        assert_eq!(heavy.create_engine().get_specs(), "Diesel V6 engine with 350hp");
+        assert_eq!(heavy.create_chassis().get_specs(), "Reinforced Steel chassis weighing 800kg");
+    }
 }
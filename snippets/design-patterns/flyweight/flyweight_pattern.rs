@@ -0,0 +1,345 @@
+//! Flyweight Pattern Implementation in Rust
+//!
+//! The Flyweight Pattern is a structural design pattern that saves memory when a program
+//! needs huge numbers of similar objects. The state they have in common (intrinsic) is
+//! stored once and shared; the state that differs per object (extrinsic) is kept by the
+//! caller or passed in.
+//!
+//! This example typesets text the way an editor might:
+//! - `StringPool` interns strings, so every font name is allocated once and handed out
+//!   as a shared `Arc<str>`
+//! - `GlyphFactory` is the flyweight factory: a `Glyph` (character, font, size and its
+//!   rasterized bitmap) is intrinsic and built once per distinct combination
+//! - a `Document` stores only a pointer to the shared glyph plus its position, the
+//!   extrinsic state, for each character
+//!
+//! `Arc` keeps the shared glyphs `Send + Sync`, so a finished document can be rendered
+//! from other threads; a single-threaded editor could use `Rc` the same way. The demo
+//! compares the document's footprint with a naive one that owns a glyph per character.
+
+use demo::Demo;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::mem;
+use std::sync::Arc;
+
+// ========== String Interning ==========
+
+/// One shared allocation per distinct string
+#[derive(Debug, Default)]
+pub struct StringPool {
+    strings: HashSet<Arc<str>>,
+}
+
+impl StringPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, text: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(text) {
+            return Arc::clone(existing);
+        }
+        let interned: Arc<str> = Arc::from(text);
+        self.strings.insert(Arc::clone(&interned));
+        interned
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+// ========== Flyweights ==========
+
+/// What makes two glyphs interchangeable
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlyphKey {
+    pub ch: char,
+    pub font: Arc<str>,
+    pub size: u16,
+}
+
+/// Intrinsic state: identical wherever the character appears
+#[derive(Debug, PartialEq, Eq)]
+pub struct Glyph {
+    pub key: GlyphKey,
+    /// One bit per pixel of a `size / 2` by `size` cell
+    pub bitmap: Vec<u8>,
+}
+
+impl Glyph {
+    /// Stands in for running the font rasterizer, the expensive part
+    pub fn rasterize(key: GlyphKey) -> Self {
+        let pixels = usize::from(key.size / 2) * usize::from(key.size);
+        let seed = u32::from(key.ch).wrapping_mul(2_654_435_761);
+        let bitmap = (0..pixels.div_ceil(8)).map(|i| (seed.rotate_left(i as u32 % 32) & 0xff) as u8).collect();
+        Glyph { key, bitmap }
+    }
+
+    /// Bytes this glyph owns, counting the font name as if it were its own copy
+    pub fn unshared_bytes(&self) -> usize {
+        mem::size_of::<Glyph>() + self.bitmap.len() + self.key.font.len()
+    }
+}
+
+/// Hands out shared glyphs, rasterizing each distinct one only once
+#[derive(Debug, Default)]
+pub struct GlyphFactory {
+    fonts: StringPool,
+    glyphs: HashMap<GlyphKey, Arc<Glyph>>,
+}
+
+impl GlyphFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn glyph(&mut self, ch: char, font: &str, size: u16) -> Arc<Glyph> {
+        let key = GlyphKey { ch, font: self.fonts.intern(font), size };
+        Arc::clone(self.glyphs.entry(key.clone()).or_insert_with(|| Arc::new(Glyph::rasterize(key))))
+    }
+
+    /// Distinct glyphs rasterized so far
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    pub fn fonts(&self) -> usize {
+        self.fonts.len()
+    }
+}
+
+// ========== Documents ==========
+
+/// Extrinsic state: where one occurrence sits
+#[derive(Debug, Clone)]
+pub struct PlacedGlyph {
+    pub glyph: Arc<Glyph>,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A run of text in one font and size
+pub type Run<'a> = (&'a str, &'a str, u16);
+
+/// Text laid out as line and column positions; a newline starts a new line
+#[derive(Debug, Default)]
+pub struct Document {
+    pub glyphs: Vec<PlacedGlyph>,
+}
+
+/// How many allocations something holds and roughly how many bytes they take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Footprint {
+    pub glyph_objects: usize,
+    pub bytes: usize,
+}
+
+/// Walks `runs`, calling `place` for every visible character with its position
+fn lay_out(runs: &[Run], mut place: impl FnMut(char, &str, u16, u32, u32)) {
+    let (mut line, mut column) = (0, 0);
+    for &(text, font, size) in runs {
+        for ch in text.chars() {
+            if ch == '\n' {
+                (line, column) = (line + 1, 0);
+                continue;
+            }
+            place(ch, font, size, line, column);
+            column += 1;
+        }
+    }
+}
+
+impl Document {
+    pub fn typeset(runs: &[Run], factory: &mut GlyphFactory) -> Self {
+        let mut glyphs = Vec::new();
+        lay_out(runs, |ch, font, size, line, column| {
+            glyphs.push(PlacedGlyph { glyph: factory.glyph(ch, font, size), line, column });
+        });
+        Document { glyphs }
+    }
+
+    /// Counts each shared glyph once, however many places point at it
+    pub fn footprint(&self) -> Footprint {
+        let mut seen = HashSet::new();
+        let mut fonts = HashSet::new();
+        let mut bytes = self.glyphs.len() * mem::size_of::<PlacedGlyph>();
+        for placed in &self.glyphs {
+            if seen.insert(Arc::as_ptr(&placed.glyph)) {
+                bytes += mem::size_of::<Glyph>() + placed.glyph.bitmap.len();
+                if fonts.insert(Arc::as_ptr(&placed.glyph.key.font)) {
+                    bytes += placed.glyph.key.font.len();
+                }
+            }
+        }
+        Footprint { glyph_objects: seen.len(), bytes }
+    }
+
+    /// Reassembles the text from the positions alone
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        let mut line = 0;
+        for placed in &self.glyphs {
+            while line < placed.line {
+                text.push('\n');
+                line += 1;
+            }
+            text.push(placed.glyph.key.ch);
+        }
+        text
+    }
+}
+
+/// The same layout without the pool: every character owns a freshly rasterized glyph
+#[derive(Debug, Default)]
+pub struct NaiveDocument {
+    pub glyphs: Vec<(Glyph, u32, u32)>,
+}
+
+impl NaiveDocument {
+    pub fn typeset(runs: &[Run]) -> Self {
+        let mut glyphs = Vec::new();
+        lay_out(runs, |ch, font, size, line, column| {
+            glyphs.push((Glyph::rasterize(GlyphKey { ch, font: Arc::from(font), size }), line, column));
+        });
+        NaiveDocument { glyphs }
+    }
+
+    pub fn footprint(&self) -> Footprint {
+        let bytes = self.glyphs.iter().map(|(glyph, ..)| glyph.unshared_bytes() + 2 * mem::size_of::<u32>()).sum();
+        Footprint { glyph_objects: self.glyphs.len(), bytes }
+    }
+}
+
+// ========== Demo Code ==========
+
+const HEADING: &str = "Flyweight\n";
+const BODY: &str = "Use sharing to support large numbers of fine-grained objects efficiently.\n\
+                    A flyweight is a shared object that can be used in multiple contexts simultaneously.\n";
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FlyweightPatternDemo;
+
+impl Demo for FlyweightPatternDemo {
+    fn name(&self) -> &'static str {
+        "flyweight_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Shared glyphs and interned font names versus one glyph object per character"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Interning Strings =====")?;
+        let mut pool = StringPool::new();
+        let names: Vec<Arc<str>> = ["Serif", "Sans", "Serif", "Mono", "Sans", "Serif"].iter().map(|name| pool.intern(name)).collect();
+        writeln!(out, "{} names interned into {} allocations", names.len(), pool.len())?;
+        writeln!(out, "names[0] and names[2] share one: {}", Arc::ptr_eq(&names[0], &names[2]))?;
+
+        writeln!(out, "\n===== Typesetting With the Factory =====")?;
+        let mut factory = GlyphFactory::new();
+        let runs: [Run; 2] = [(HEADING, "Serif", 24), (BODY, "Sans", 12)];
+        let document = Document::typeset(&runs, &mut factory);
+        writeln!(out, "{} characters, {} distinct glyphs, {} fonts", document.glyphs.len(), factory.len(), factory.fonts())?;
+        let e = factory.glyph('e', "Sans", 12);
+        writeln!(out, "'e' in Sans 12 is shared by {} places (plus the factory and this handle)", Arc::strong_count(&e) - 2)?;
+        let third = &document.glyphs[document.glyphs.len() - 3];
+        writeln!(out, "Third from last: {:?} at line {}, column {}", third.glyph.key.ch, third.line, third.column)?;
+        writeln!(out, "Round trip keeps the text: {}", document.text() == (HEADING.to_string() + BODY).trim_end())?;
+
+        writeln!(out, "\n===== With and Without the Pool =====")?;
+        let naive = NaiveDocument::typeset(&runs);
+        let (shared, owned) = (document.footprint(), naive.footprint());
+        writeln!(out, "{:<16} {:>8} {:>10}", "", "glyphs", "bytes")?;
+        writeln!(out, "{:<16} {:>8} {:>10}", "one per char", owned.glyph_objects, owned.bytes)?;
+        writeln!(out, "{:<16} {:>8} {:>10}", "flyweights", shared.glyph_objects, shared.bytes)?;
+        writeln!(out, "Saved {:.0}% of the memory", 100.0 * (1.0 - shared.bytes as f64 / owned.bytes as f64))?;
+
+        let mut factory = GlyphFactory::new();
+        let long: String = BODY.repeat(50);
+        let long_runs: [Run; 1] = [(&long, "Sans", 12)];
+        let (shared, owned) = (Document::typeset(&long_runs, &mut factory).footprint(), NaiveDocument::typeset(&long_runs).footprint());
+        writeln!(out, "50 times the body: {} vs {} glyphs, {} vs {} bytes", owned.glyph_objects, shared.glyph_objects, owned.bytes, shared.bytes)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FlyweightPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_pool_hands_out_one_allocation_per_distinct_string() {
+        let mut pool = StringPool::new();
+        let a = pool.intern("Helvetica");
+        let b = pool.intern(&String::from("Helvetica"));
+        let c = pool.intern("Times");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(pool.len(), 2);
+        assert_eq!(Arc::strong_count(&a), 3, "the pool, a and b");
+    }
+
+    #[test]
+    fn factory_rasterizes_each_combination_once() {
+        let mut factory = GlyphFactory::new();
+        let first = factory.glyph('g', "Sans", 12);
+        let again = factory.glyph('g', "Sans", 12);
+        let bigger = factory.glyph('g', "Sans", 16);
+        let serif = factory.glyph('g', "Serif", 12);
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(!Arc::ptr_eq(&first, &bigger) && !Arc::ptr_eq(&first, &serif));
+        assert_eq!((factory.len(), factory.fonts()), (3, 2));
+        assert_eq!(first.bitmap.len(), 6 * 12 / 8);
+        assert!(Arc::ptr_eq(&first.key.font, &bigger.key.font), "font names are interned");
+    }
+
+    #[test]
+    fn documents_share_glyphs_but_keep_their_own_positions() {
+        let mut factory = GlyphFactory::new();
+        let document = Document::typeset(&[("aba\nba", "Mono", 10)], &mut factory);
+        let positions: Vec<(char, u32, u32)> =
+            document.glyphs.iter().map(|placed| (placed.glyph.key.ch, placed.line, placed.column)).collect();
+        assert_eq!(positions, [('a', 0, 0), ('b', 0, 1), ('a', 0, 2), ('b', 1, 0), ('a', 1, 1)]);
+        assert!(Arc::ptr_eq(&document.glyphs[0].glyph, &document.glyphs[4].glyph));
+        assert_eq!(factory.len(), 2);
+        assert_eq!(document.text(), "aba\nba");
+    }
+
+    #[test]
+    fn flyweights_use_far_less_memory_on_repetitive_text() {
+        let text = "the quick brown fox jumps over the lazy dog ".repeat(20);
+        let runs: [Run; 1] = [(&text, "Sans", 12)];
+        let shared = Document::typeset(&runs, &mut GlyphFactory::new()).footprint();
+        let owned = NaiveDocument::typeset(&runs).footprint();
+        assert_eq!(owned.glyph_objects, text.chars().count());
+        assert_eq!(shared.glyph_objects, 27, "26 letters and a space");
+        assert!(shared.bytes * 3 < owned.bytes, "{:?} vs {:?}", shared, owned);
+    }
+
+    #[test]
+    fn shared_glyphs_can_be_read_from_other_threads() {
+        let mut factory = GlyphFactory::new();
+        let document = Document::typeset(&[("ok", "Sans", 8)], &mut factory);
+        let glyph = Arc::clone(&document.glyphs[0].glyph);
+        let size = std::thread::spawn(move || glyph.bitmap.len()).join().unwrap();
+        assert_eq!(size, document.glyphs[1].glyph.bitmap.len());
+    }
+}
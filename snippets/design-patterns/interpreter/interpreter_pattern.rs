@@ -0,0 +1,535 @@
+//! Interpreter Pattern Implementation in Rust
+//!
+//! The Interpreter Pattern is a behavioral design pattern that represents the grammar of
+//! a small language as a tree of expression types, each of which knows how to evaluate
+//! itself against a context holding the variables.
+//!
+//! This example is a rule language for things like discount eligibility,
+//! `age >= 65 || (member && total > 100)`:
+//! - `tokenize` turns source text into `Token`s with their byte offsets
+//! - `parse` is a recursive-descent parser, one function per precedence level, that builds
+//!   an `Expr` tree
+//! - `Expr::interpret(&Context)` evaluates the tree; `&&` and `||` short-circuit, and
+//!   arithmetic is checked, so overflow and division by zero are errors rather than panics
+//!
+//! Grammar, loosest binding first:
+//!
+//! ```text
+//! or         := and ("||" and)*
+//! and        := equality ("&&" equality)*
+//! equality   := comparison (("==" | "!=") comparison)*
+//! comparison := term (("<" | "<=" | ">" | ">=") term)*
+//! term       := factor (("+" | "-") factor)*
+//! factor     := unary (("*" | "/" | "%") unary)*
+//! unary      := ("!" | "-") unary | primary
+//! primary    := integer | "true" | "false" | identifier | "(" or ")"
+//! ```
+
+use demo::Demo;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Values and Errors ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+}
+
+impl Value {
+    fn type_name(self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Bool(_) => "bool",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Positions are byte offsets into the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxError {
+    UnexpectedChar { at: usize, found: char },
+    IntegerTooLarge { at: usize },
+    Expected { at: usize, expected: &'static str, found: String },
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxError::UnexpectedChar { at, found } => write!(f, "unexpected {:?} at {}", found, at),
+            SyntaxError::IntegerTooLarge { at } => write!(f, "integer at {} does not fit in 64 bits", at),
+            SyntaxError::Expected { at, expected, found } => write!(f, "expected {} at {}, found {}", expected, at, found),
+        }
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownVariable(String),
+    TypeMismatch { operator: &'static str, expected: &'static str, found: &'static str },
+    DivisionByZero,
+    Overflow,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "unknown variable {:?}", name),
+            EvalError::TypeMismatch { operator, expected, found } => {
+                write!(f, "{} expects {}, found {}", operator, expected, found)
+            }
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Overflow => write!(f, "integer overflow"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+// ========== Tokenizer ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Int(i64),
+    Ident(String),
+    True,
+    False,
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Int(n) => write!(f, "{}", n),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::True => write!(f, "true"),
+            Token::False => write!(f, "false"),
+            Token::Op(op) => write!(f, "{:?}", op),
+            Token::LParen => write!(f, "\"(\""),
+            Token::RParen => write!(f, "\")\""),
+        }
+    }
+}
+
+/// Longest first, so `<=` wins over `<`
+const OPERATORS: [&str; 15] = ["&&", "||", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!", "="];
+
+pub fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut rest = source.char_indices().peekable();
+    while let Some(&(at, c)) = rest.peek() {
+        if c.is_whitespace() {
+            rest.next();
+        } else if c.is_ascii_digit() {
+            let end = source[at..].find(|c: char| !c.is_ascii_digit()).map_or(source.len(), |len| at + len);
+            let n = source[at..end].parse().map_err(|_| SyntaxError::IntegerTooLarge { at })?;
+            tokens.push((at, Token::Int(n)));
+            rest.nth(end - at - 1);
+        } else if c.is_alphabetic() || c == '_' {
+            let end = source[at..].find(|c: char| !(c.is_alphanumeric() || c == '_')).map_or(source.len(), |len| at + len);
+            let token = match &source[at..end] {
+                "true" => Token::True,
+                "false" => Token::False,
+                name => Token::Ident(name.to_string()),
+            };
+            tokens.push((at, token));
+            while rest.peek().is_some_and(|&(i, _)| i < end) {
+                rest.next();
+            }
+        } else if c == '(' || c == ')' {
+            tokens.push((at, if c == '(' { Token::LParen } else { Token::RParen }));
+            rest.next();
+        } else {
+            // A lone `=` is tokenized only so the parser can point at it
+            let op = OPERATORS.iter().find(|op| source[at..].starts_with(**op));
+            let op = op.ok_or(SyntaxError::UnexpectedChar { at, found: c })?;
+            tokens.push((at, Token::Op(op)));
+            rest.nth(op.len() - 1);
+        }
+    }
+    Ok(tokens)
+}
+
+// ========== Abstract Syntax Tree ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Literal(Value),
+    Variable(String),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, &'static str, Box<Expr>),
+}
+
+/// Fully parenthesized, to show how the parser grouped things
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Literal(value) => write!(f, "{}", value),
+            Expr::Variable(name) => write!(f, "{}", name),
+            Expr::Not(inner) => write!(f, "!{}", inner),
+            Expr::Negate(inner) => write!(f, "-{}", inner),
+            Expr::Binary(left, op, right) => write!(f, "({} {} {})", left, op, right),
+        }
+    }
+}
+
+// ========== Parser ==========
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.end, |&(at, _)| at)
+    }
+
+    fn error(&self, expected: &'static str) -> SyntaxError {
+        let found = self.peek().map_or_else(|| "the end".to_string(), Token::to_string);
+        SyntaxError::Expected { at: self.offset(), expected, found }
+    }
+
+    /// Consumes the next token if it is one of `ops`
+    fn operator(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.position += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    /// One left-associative precedence level
+    fn binary(&mut self, ops: &[&'static str], next: fn(&mut Self) -> Result<Expr, SyntaxError>) -> Result<Expr, SyntaxError> {
+        let mut left = next(self)?;
+        while let Some(op) = self.operator(ops) {
+            left = Expr::Binary(Box::new(left), op, Box::new(next(self)?));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(&["||"], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(&["&&"], Self::equality)
+    }
+
+    fn equality(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(&["==", "!="], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(&["<", "<=", ">", ">="], Self::term)
+    }
+
+    fn term(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(&["+", "-"], Self::factor)
+    }
+
+    fn factor(&mut self) -> Result<Expr, SyntaxError> {
+        self.binary(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, SyntaxError> {
+        match self.operator(&["!", "-"]) {
+            Some("!") => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(_) => Ok(Expr::Negate(Box::new(self.unary()?))),
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, SyntaxError> {
+        let expr = match self.peek() {
+            Some(Token::Int(n)) => Expr::Literal(Value::Int(*n)),
+            Some(Token::True) => Expr::Literal(Value::Bool(true)),
+            Some(Token::False) => Expr::Literal(Value::Bool(false)),
+            Some(Token::Ident(name)) => Expr::Variable(name.clone()),
+            Some(Token::LParen) => {
+                self.position += 1;
+                let inner = self.or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(self.error("\")\""));
+                }
+                inner
+            }
+            _ => return Err(self.error("a value")),
+        };
+        self.position += 1;
+        Ok(expr)
+    }
+}
+
+pub fn parse(source: &str) -> Result<Expr, SyntaxError> {
+    let mut parser = Parser { tokens: tokenize(source)?, position: 0, end: source.len() };
+    let expr = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("an operator"));
+    }
+    Ok(expr)
+}
+
+// ========== Interpreter ==========
+
+/// Variable bindings the expression is evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    variables: HashMap<String, Value>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: &str, value: Value) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.variables.get(name).copied()
+    }
+}
+
+fn int(operator: &'static str, value: Value) -> Result<i64, EvalError> {
+    match value {
+        Value::Int(n) => Ok(n),
+        other => Err(EvalError::TypeMismatch { operator, expected: "int", found: other.type_name() }),
+    }
+}
+
+fn boolean(operator: &'static str, value: Value) -> Result<bool, EvalError> {
+    match value {
+        Value::Bool(b) => Ok(b),
+        other => Err(EvalError::TypeMismatch { operator, expected: "bool", found: other.type_name() }),
+    }
+}
+
+impl Expr {
+    pub fn interpret(&self, context: &Context) -> Result<Value, EvalError> {
+        match self {
+            Expr::Literal(value) => Ok(*value),
+            Expr::Variable(name) => context.get(name).ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Not(inner) => Ok(Value::Bool(!boolean("!", inner.interpret(context)?)?)),
+            Expr::Negate(inner) => int("-", inner.interpret(context)?)?.checked_neg().map(Value::Int).ok_or(EvalError::Overflow),
+            Expr::Binary(left, op @ ("&&" | "||"), right) => {
+                let left = boolean(op, left.interpret(context)?)?;
+                if left == (*op == "||") {
+                    return Ok(Value::Bool(left));
+                }
+                Ok(Value::Bool(boolean(op, right.interpret(context)?)?))
+            }
+            Expr::Binary(left, op @ ("==" | "!="), right) => {
+                let (left, right) = (left.interpret(context)?, right.interpret(context)?);
+                if left.type_name() != right.type_name() {
+                    return Err(EvalError::TypeMismatch { operator: op, expected: left.type_name(), found: right.type_name() });
+                }
+                Ok(Value::Bool((left == right) == (*op == "==")))
+            }
+            Expr::Binary(left, op, right) => {
+                let (a, b) = (int(op, left.interpret(context)?)?, int(op, right.interpret(context)?)?);
+                let number = |result: Option<i64>| result.map(Value::Int).ok_or(EvalError::Overflow);
+                match *op {
+                    "<" => Ok(Value::Bool(a < b)),
+                    "<=" => Ok(Value::Bool(a <= b)),
+                    ">" => Ok(Value::Bool(a > b)),
+                    ">=" => Ok(Value::Bool(a >= b)),
+                    "+" => number(a.checked_add(b)),
+                    "-" => number(a.checked_sub(b)),
+                    "*" => number(a.checked_mul(b)),
+                    "/" | "%" if b == 0 => Err(EvalError::DivisionByZero),
+                    "/" => number(a.checked_div(b)),
+                    "%" => number(a.checked_rem(b)),
+                    other => unreachable!("the parser never produces {:?}", other),
+                }
+            }
+        }
+    }
+
+    /// Variable names in order of first use
+    pub fn variables(&self) -> Vec<&str> {
+        fn collect<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+            match expr {
+                Expr::Literal(_) => {}
+                Expr::Variable(name) => {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name);
+                    }
+                }
+                Expr::Not(inner) | Expr::Negate(inner) => collect(inner, names),
+                Expr::Binary(left, _, right) => {
+                    collect(left, names);
+                    collect(right, names);
+                }
+            }
+        }
+        let mut names = Vec::new();
+        collect(self, &mut names);
+        names
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct InterpreterPatternDemo;
+
+impl Demo for InterpreterPatternDemo {
+    fn name(&self) -> &'static str {
+        "interpreter_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tokenizer, recursive-descent parser and AST evaluator for a small boolean/arithmetic rule language"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Tokens =====")?;
+        let source = "total >= 100 && !expired";
+        let tokens = tokenize(source).expect("valid tokens");
+        let shown: Vec<String> = tokens.iter().map(|(at, token)| format!("{}@{}", token, at)).collect();
+        writeln!(out, "{}", shown.join(" "))?;
+
+        writeln!(out, "\n===== Parsing and Precedence =====")?;
+        for source in ["1 + 2 * 3", "(1 + 2) * 3", "10 - 4 - 3", "-2 * -3 == 6 || !true && false"] {
+            let expr = parse(source).expect("valid expression");
+            let value = expr.interpret(&Context::new()).expect("no variables");
+            writeln!(out, "{:<32} → {:<40} = {}", source, expr.to_string(), value)?;
+        }
+
+        writeln!(out, "\n===== One Rule, Several Customers =====")?;
+        let rule = parse("age >= 65 || (member && total - discount > 100)").expect("valid rule");
+        writeln!(out, "Rule uses: {}", rule.variables().join(", "))?;
+        let customers = [
+            ("Ana", 70, false, 20),
+            ("Ben", 30, true, 150),
+            ("Cy", 30, true, 105),
+            ("Di", 30, false, 500),
+        ];
+        for (name, age, member, total) in customers {
+            let context = Context::new()
+                .with("age", Value::Int(age))
+                .with("member", Value::Bool(member))
+                .with("total", Value::Int(total))
+                .with("discount", Value::Int(10));
+            writeln!(out, "{:<4} eligible: {}", name, rule.interpret(&context).expect("all variables bound"))?;
+        }
+
+        writeln!(out, "\n===== Errors =====")?;
+        for source in ["1 +", "(1 + 2", "a = 1", "3 # 4", "1 2"] {
+            writeln!(out, "{:<12} {}", source, parse(source).unwrap_err())?;
+        }
+        let context = Context::new().with("n", Value::Int(0));
+        for source in ["10 / n", "n + true", "missing > 1", "9223372036854775807 + 1", "n == 0 || 1 / n > 0"] {
+            let result = parse(source).expect("valid expression").interpret(&context);
+            let shown = result.map_or_else(|error| error.to_string(), |value| value.to_string());
+            writeln!(out, "{:<26} {}", source, shown)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&InterpreterPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str) -> Result<Value, EvalError> {
+        parse(source).unwrap().interpret(&Context::new())
+    }
+
+    #[test]
+    fn tokenizer_records_offsets_and_prefers_longer_operators() {
+        let tokens = tokenize("a<=10&&!b_2").unwrap();
+        assert_eq!(
+            tokens,
+            [
+                (0, Token::Ident("a".into())),
+                (1, Token::Op("<=")),
+                (3, Token::Int(10)),
+                (5, Token::Op("&&")),
+                (7, Token::Op("!")),
+                (8, Token::Ident("b_2".into())),
+            ]
+        );
+        assert_eq!(tokenize("1 $ 2"), Err(SyntaxError::UnexpectedChar { at: 2, found: '$' }));
+        assert_eq!(tokenize("99999999999999999999"), Err(SyntaxError::IntegerTooLarge { at: 0 }));
+    }
+
+    #[test]
+    fn parser_respects_precedence_and_left_associativity() {
+        assert_eq!(parse("1 + 2 * 3").unwrap().to_string(), "(1 + (2 * 3))");
+        assert_eq!(parse("8 / 4 / 2").unwrap().to_string(), "((8 / 4) / 2)");
+        assert_eq!(parse("a || b && !c == d").unwrap().to_string(), "(a || (b && (!c == d)))");
+        assert_eq!(eval("10 - 4 - 3"), Ok(Value::Int(3)));
+        assert_eq!(eval("-(2 + 3) * 4 % 7"), Ok(Value::Int(-6)));
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_offending_token() {
+        let expected = |at, expected, found: &str| SyntaxError::Expected { at, expected, found: found.into() };
+        assert_eq!(parse("1 +"), Err(expected(3, "a value", "the end")));
+        assert_eq!(parse("(1 + 2"), Err(expected(6, "\")\"", "the end")));
+        assert_eq!(parse("a = 1"), Err(expected(2, "an operator", "\"=\"")));
+        assert_eq!(parse("1 2"), Err(expected(2, "an operator", "2")));
+    }
+
+    #[test]
+    fn variables_come_from_the_context() {
+        let rule = parse("age >= 65 || (member && total > 100)").unwrap();
+        assert_eq!(rule.variables(), ["age", "member", "total"]);
+        let senior = Context::new().with("age", Value::Int(70));
+        assert_eq!(rule.interpret(&senior), Ok(Value::Bool(true)), "|| never reads member or total");
+        let mut member = Context::new().with("age", Value::Int(30)).with("member", Value::Bool(true));
+        assert_eq!(rule.interpret(&member), Err(EvalError::UnknownVariable("total".into())));
+        member.set("total", Value::Int(101));
+        assert_eq!(rule.interpret(&member), Ok(Value::Bool(true)));
+    }
+
+    #[test]
+    fn evaluation_errors_are_reported_not_panicked() {
+        assert_eq!(eval("1 / 0"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("false && 1 / 0 == 0"), Ok(Value::Bool(false)));
+        assert_eq!(eval("-9223372036854775807 - 2"), Err(EvalError::Overflow));
+        assert_eq!(eval("1 + true"), Err(EvalError::TypeMismatch { operator: "+", expected: "int", found: "bool" }));
+        assert_eq!(eval("1 == true"), Err(EvalError::TypeMismatch { operator: "==", expected: "int", found: "bool" }));
+        assert_eq!(eval("!5"), Err(EvalError::TypeMismatch { operator: "!", expected: "bool", found: "int" }));
+    }
+}
@@ -0,0 +1,452 @@
+//! Iterator Pattern Implementation in Rust
+//!
+//! The Iterator Pattern is a behavioral design pattern that walks the elements of a
+//! collection without exposing how it stores them. The classic object-oriented version
+//! is a cursor object with `has_next()` and `next()`, handed out by the collection.
+//!
+//! Rust builds the pattern into the language instead, and it differs in three ways:
+//! - `Iterator::next` returns `Option<Item>`, so "is there another?" and "give it to me"
+//!   are one call and an exhausted iterator can't be read past
+//! - the borrow checker tracks what an iterator may do to its collection. A collection
+//!   usually offers three iterators, selected by what `for` loops over: `T` moves the
+//!   elements out, `&T` borrows them and `&mut T` borrows them mutably, through
+//!   `IntoIterator` impls for all three
+//! - implementing `next` is enough to get every adapter in `std` (`map`, `filter`,
+//!   `zip`, ...), and new adapters are ordinary structs wrapping another iterator
+//!
+//! This example is `TreeMap`, an unbalanced binary search tree, with an in-order
+//! iterator of each kind, all without `unsafe`, and `RunLengths`, a custom adapter that
+//! collapses runs of equal items into `(item, count)` pairs.
+
+use demo::Demo;
+use rust_lang::iterators::IterExt;
+use std::cmp::Ordering;
+use std::io::{self, Write};
+use std::iter::{FusedIterator, Peekable};
+use std::mem;
+
+// ========== The Collection ==========
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+/// A binary search tree map; sorted input degrades it to a linked list, which is fine
+/// for showing iteration but not for real lookups
+pub struct TreeMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K: Ord, V> TreeMap<K, V> {
+    pub fn new() -> Self {
+        TreeMap { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value `key` had before, if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut link = &mut self.root;
+        while let Some(node) = link {
+            match key.cmp(&node.key) {
+                Ordering::Less => link = &mut node.left,
+                Ordering::Greater => link = &mut node.right,
+                Ordering::Equal => return Some(mem::replace(&mut node.value, value)),
+            }
+        }
+        *link = Some(Box::new(Node { key, value, left: None, right: None }));
+        self.len += 1;
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            match key.cmp(&node.key) {
+                Ordering::Less => link = &node.left,
+                Ordering::Greater => link = &node.right,
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+        None
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter { stack: Vec::new(), remaining: self.len };
+        iter.push_left_spine(&self.root);
+        iter
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let mut iter = IterMut { stack: Vec::new(), remaining: self.len };
+        iter.push_left_spine(&mut self.root);
+        iter
+    }
+
+    /// Keys in order; the other iterators' adapters come for free
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl ExactSizeIterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Ord, V> Default for TreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Frees nodes from an explicit stack: the default recursive drop of a degenerate tree
+/// as deep as it is long would overflow the call stack
+impl<K, V> Drop for TreeMap<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for TreeMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = TreeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for TreeMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+// ========== Borrowing Iterator ==========
+
+/// In-order over `&TreeMap`. The stack holds the nodes whose left subtree is being
+/// walked: the top is always the next smallest key
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left_spine(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(&node.right);
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<'a, K: Ord, V> IntoIterator for &'a TreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// ========== Mutable Iterator ==========
+
+/// In-order over `&mut TreeMap`. Keys stay shared, since changing one would break the
+/// search order; each node is split into its parts as it is pushed, so the value can be
+/// lent out while the right subtree is still waiting on the stack
+pub struct IterMut<'a, K, V> {
+    stack: Vec<(&'a K, &'a mut V, &'a mut Link<K, V>)>,
+    remaining: usize,
+}
+
+impl<'a, K, V> IterMut<'a, K, V> {
+    fn push_left_spine(&mut self, mut link: &'a mut Link<K, V>) {
+        while let Some(node) = link {
+            let Node { key, value, left, right } = &mut **node;
+            self.stack.push((key, value, right));
+            link = left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value, right) = self.stack.pop()?;
+        self.push_left_spine(right);
+        self.remaining -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<'a, K: Ord, V> IntoIterator for &'a mut TreeMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// ========== Owning Iterator ==========
+
+/// In-order over `TreeMap` by value. Each node's left child is detached as it is pushed
+/// and its right child as it is popped, so every node is taken apart exactly once
+pub struct IntoIter<K, V> {
+    stack: Vec<Box<Node<K, V>>>,
+    remaining: usize,
+}
+
+impl<K, V> IntoIter<K, V> {
+    fn push_left_spine(&mut self, mut link: Link<K, V>) {
+        while let Some(mut node) = link {
+            link = node.left.take();
+            self.stack.push(node);
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut node = self.stack.pop()?;
+        self.push_left_spine(node.right.take());
+        self.remaining -= 1;
+        let Node { key, value, .. } = *node;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+impl<K: Ord, V> IntoIterator for TreeMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut iter = IntoIter { stack: Vec::new(), remaining: mem::take(&mut self.len) };
+        iter.push_left_spine(self.root.take());
+        iter
+    }
+}
+
+// ========== A Custom Adapter ==========
+
+/// Each run of equal consecutive items as the first of them and the run's length
+pub struct RunLengths<I: Iterator> {
+    inner: Peekable<I>,
+}
+
+impl<I> Iterator for RunLengths<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = (I::Item, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.inner.next()?;
+        let mut count = 1;
+        while self.inner.next_if(|item| *item == first).is_some() {
+            count += 1;
+        }
+        Some((first, count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.inner.size_hint();
+        (low.min(1), high)
+    }
+}
+
+/// Adds `run_lengths` to every iterator, the way `Iterator`'s own methods add `map`
+pub trait RunLengthsExt: Iterator + Sized {
+    fn run_lengths(self) -> RunLengths<Self>
+    where
+        Self::Item: PartialEq,
+    {
+        RunLengths { inner: self.peekable() }
+    }
+}
+
+impl<I: Iterator> RunLengthsExt for I {}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct IteratorPatternDemo;
+
+impl Demo for IteratorPatternDemo {
+    fn name(&self) -> &'static str {
+        "iterator_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "A tree map with owning, borrowing and mutable iterators, plus a custom adapter"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Collecting Into a Custom Collection =====")?;
+        let scores = [("mia", 72), ("ben", 88), ("zoe", 95), ("ada", 88), ("kim", 61), ("ben", 91)];
+        let mut tree: TreeMap<&str, u32> = scores.into_iter().collect();
+        writeln!(out, "{} inserts, {} keys (ben was replaced): ben = {:?}", scores.len(), tree.len(), tree.get(&"ben"))?;
+
+        writeln!(out, "\n===== for (k, v) in &tree =====")?;
+        for (name, score) in &tree {
+            writeln!(out, "{:<4} {}", name, score)?;
+        }
+
+        writeln!(out, "\n===== for (k, v) in &mut tree =====")?;
+        for (_, score) in &mut tree {
+            *score = (*score + 5).min(100);
+        }
+        writeln!(out, "Curved by 5, capped at 100: {:?}", tree.values().collect::<Vec<_>>())?;
+
+        writeln!(out, "\n===== Adapters Compose =====")?;
+        let passing = tree.iter().filter(|(_, &score)| score >= 90).map(|(name, _)| *name).collect::<Vec<_>>();
+        writeln!(out, "90 and over: {:?}", passing)?;
+        writeln!(out, "keys() is an ExactSizeIterator: len {}", tree.keys().len())?;
+        let neighbours: Vec<String> = tree.keys().windows(2).map(|pair| format!("{}<{}", pair[0], pair[1])).collect();
+        writeln!(out, "With IterExt::windows from rust_lang::iterators: {}", neighbours.join(" "))?;
+
+        writeln!(out, "\n===== A Custom Adapter =====")?;
+        let runs: Vec<String> = "aaabccdddd".chars().run_lengths().map(|(c, n)| format!("{}{}", n, c)).collect();
+        writeln!(out, "aaabccdddd -> {}", runs.concat())?;
+        let mut bands: Vec<u32> = tree.values().map(|score| score / 10 * 10).collect();
+        bands.sort_unstable();
+        writeln!(out, "Scores per band: {:?}", bands.into_iter().run_lengths().collect::<Vec<_>>())?;
+
+        writeln!(out, "\n===== for (k, v) in tree =====")?;
+        let owned: Vec<(String, u32)> = tree.into_iter().map(|(name, score)| (name.to_uppercase(), score)).collect();
+        writeln!(out, "Moved out, tree consumed: {:?}", owned)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&IteratorPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TreeMap<i32, String> {
+        [50, 30, 70, 20, 40, 60, 80, 35].into_iter().map(|key| (key, format!("v{}", key))).collect()
+    }
+
+    #[test]
+    fn iter_walks_keys_in_order_and_knows_its_length() {
+        let tree = sample();
+        let mut iter = tree.iter();
+        assert_eq!(iter.len(), 8);
+        assert_eq!(iter.next(), Some((&20, &"v20".to_string())));
+        assert_eq!(iter.len(), 7);
+        let rest: Vec<i32> = iter.map(|(key, _)| *key).collect();
+        assert_eq!(rest, [30, 35, 40, 50, 60, 70, 80]);
+        assert_eq!(TreeMap::<i32, ()>::new().iter().next(), None);
+    }
+
+    #[test]
+    fn iter_mut_changes_values_in_place() {
+        let mut tree = sample();
+        for (key, value) in &mut tree {
+            value.push_str(if key % 20 == 0 { "!" } else { "" });
+        }
+        assert_eq!(tree.get(&40).map(String::as_str), Some("v40!"));
+        assert_eq!(tree.get(&35).map(String::as_str), Some("v35"));
+        assert_eq!(tree.iter_mut().len(), 8);
+    }
+
+    #[test]
+    fn into_iter_moves_every_entry_out_in_order() {
+        let mut iter = sample().into_iter();
+        assert_eq!(iter.size_hint(), (8, Some(8)));
+        let owned: Vec<(i32, String)> = iter.by_ref().take(3).collect();
+        assert_eq!(owned, [(20, "v20".to_string()), (30, "v30".to_string()), (35, "v35".to_string())]);
+        assert_eq!(iter.len(), 5);
+        // Dropping a half-used iterator frees the rest
+        drop(iter);
+    }
+
+    #[test]
+    fn insert_replaces_existing_keys_and_extend_keeps_the_last_value() {
+        let mut tree = TreeMap::new();
+        assert_eq!(tree.insert("b", 1), None);
+        assert_eq!(tree.insert("b", 2), Some(1));
+        tree.extend([("a", 0), ("c", 3), ("a", 9)]);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.into_iter().collect::<Vec<_>>(), [("a", 9), ("b", 2), ("c", 3)]);
+
+        // Sorted input makes the deepest tree possible; it still iterates and drops
+        let chain: TreeMap<u32, ()> = (0..5_000).map(|key| (key, ())).collect();
+        assert!(chain.keys().copied().eq(0..5_000));
+    }
+
+    #[test]
+    fn run_lengths_collapses_consecutive_equal_items() {
+        let runs: Vec<(char, usize)> = "aabccca".chars().run_lengths().collect();
+        assert_eq!(runs, [('a', 2), ('b', 1), ('c', 3), ('a', 1)]);
+        assert_eq!(std::iter::empty::<u8>().run_lengths().next(), None);
+        assert_eq!([7; 4].into_iter().run_lengths().collect::<Vec<_>>(), [(7, 4)]);
+        // Lazy: an endless source is fine as long as the runs end
+        let thirds = (0..).map(|n| n / 3).run_lengths().take(2).collect::<Vec<_>>();
+        assert_eq!(thirds, [(0, 3), (1, 3)]);
+    }
+}
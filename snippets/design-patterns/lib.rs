@@ -0,0 +1,84 @@
+//! Design pattern snippets from the tech notes.
+//!
+//! Each module mirrors one pattern directory and exposes its types plus a `Demo`
+//! implementation, registered in `DEMOS`, whose `run_demo` wrapper the matching example
+//! binary calls.
+
+#[path = "adapter/adapter_pattern.rs"]
+pub mod adapter_pattern;
+#[path = "bridge/bridge_pattern.rs"]
+pub mod bridge_pattern;
+#[path = "builder/builder_pattern.rs"]
+pub mod builder_pattern;
+#[path = "chain-of-responsibility/chain_of_responsibility_pattern.rs"]
+pub mod chain_of_responsibility_pattern;
+#[path = "dependency-injection/dependency_injection_pattern.rs"]
+pub mod dependency_injection_pattern;
+#[path = "pub-sub/event_bus.rs"]
+pub mod event_bus;
+#[path = "facade/facade_pattern.rs"]
+pub mod facade_pattern;
+#[path = "factory/factory_pattern.rs"]
+pub mod factory_pattern;
+#[path = "flyweight/flyweight_pattern.rs"]
+pub mod flyweight_pattern;
+#[path = "interpreter/interpreter_pattern.rs"]
+pub mod interpreter_pattern;
+#[path = "iterator/iterator_pattern.rs"]
+pub mod iterator_pattern;
+#[path = "mediator/mediator_pattern.rs"]
+pub mod mediator_pattern;
+#[path = "memento/memento_pattern.rs"]
+pub mod memento_pattern;
+#[path = "object-pool/object_pool_pattern.rs"]
+pub mod object_pool_pattern;
+#[path = "observer/observer_pattern.rs"]
+pub mod observer_pattern;
+#[path = "prototype/prototype_pattern.rs"]
+pub mod prototype_pattern;
+#[path = "proxy/proxy_pattern.rs"]
+pub mod proxy_pattern;
+#[path = "repository/repository_pattern.rs"]
+pub mod repository_pattern;
+#[path = "singleton/singleton_pattern.rs"]
+pub mod singleton_pattern;
+#[path = "state/state_pattern.rs"]
+pub mod state_pattern;
+#[path = "strategy/strategy_pattern.rs"]
+pub mod strategy_pattern;
+#[path = "template-method/template_method_pattern.rs"]
+pub mod template_method_pattern;
+#[path = "observer/threaded_observer_pattern.rs"]
+pub mod threaded_observer_pattern;
+#[path = "visitor/visitor_pattern.rs"]
+pub mod visitor_pattern;
+
+use demo::Demo;
+
+/// Every demo in this crate, for `tech-notes` and the other front ends.
+pub static DEMOS: &[&dyn Demo] = &[
+    &adapter_pattern::AdapterPatternDemo,
+    &bridge_pattern::BridgePatternDemo,
+    &builder_pattern::BuilderPatternDemo,
+    &chain_of_responsibility_pattern::ChainOfResponsibilityPatternDemo,
+    &dependency_injection_pattern::DependencyInjectionPatternDemo,
+    &event_bus::EventBusDemo,
+    &facade_pattern::FacadePatternDemo,
+    &factory_pattern::FactoryPatternDemo,
+    &flyweight_pattern::FlyweightPatternDemo,
+    &interpreter_pattern::InterpreterPatternDemo,
+    &iterator_pattern::IteratorPatternDemo,
+    &mediator_pattern::MediatorPatternDemo,
+    &memento_pattern::MementoPatternDemo,
+    &object_pool_pattern::ObjectPoolPatternDemo,
+    &observer_pattern::ObserverPatternDemo,
+    &prototype_pattern::PrototypePatternDemo,
+    &proxy_pattern::ProxyPatternDemo,
+    &repository_pattern::RepositoryPatternDemo,
+    &singleton_pattern::SingletonPatternDemo,
+    &state_pattern::StatePatternDemo,
+    &strategy_pattern::StrategyPatternDemo,
+    &template_method_pattern::TemplateMethodPatternDemo,
+    &threaded_observer_pattern::ThreadedObserverPatternDemo,
+    &visitor_pattern::VisitorPatternDemo,
+];
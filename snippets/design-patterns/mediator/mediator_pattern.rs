@@ -0,0 +1,420 @@
+//! Mediator Pattern Implementation in Rust
+//!
+//! The Mediator Pattern is a behavioral design pattern that stops a group of objects
+//! (colleagues) from talking to each other directly. They talk to one mediator instead,
+//! which knows who is there and decides who receives what, so colleagues stay unaware
+//! of one another.
+//!
+//! This example is a chat room. Users send direct messages and broadcasts, and can mute
+//! other users; the room does the routing and applies the mute lists. The classic shape
+//! has the mediator own its colleagues while every colleague also points back at the
+//! mediator, a reference cycle Rust will not build with plain references. Two ways out:
+//! - `ChatRoom` and `User`: the room owns `Rc<User>`s and each user keeps a
+//!   `Weak<ChatRoom>`. Users can act on their own (`alice.send("bob", ..)`) and no cycle
+//!   keeps the room alive, at the price of `RefCell` runtime borrow checks and a link
+//!   that may be dead by the time it is used
+//! - `by_id::Room`: the room owns plain member data and hands out `UserId`s. Everything
+//!   goes through `&mut Room`, so the borrow checker does all the work and there is no
+//!   shared ownership, but callers must hold the room to do anything and a stale id is
+//!   only caught at runtime
+//!
+//! Either way, muting is the mediator's job: senders are not told that a message was
+//! dropped, they only see how many people it reached.
+
+use demo::Demo;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::{Rc, Weak};
+
+// ========== Messages and Errors ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub from: String,
+    /// `None` for a broadcast
+    pub to: Option<String>,
+    pub text: String,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.to {
+            Some(_) => write!(f, "{} (direct): {}", self.from, self.text),
+            None => write!(f, "{}: {}", self.from, self.text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatError {
+    NameTaken(String),
+    UnknownUser(String),
+    /// The user left, or the room itself is gone
+    NotInRoom(String),
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatError::NameTaken(name) => write!(f, "the name {:?} is taken", name),
+            ChatError::UnknownUser(name) => write!(f, "no user called {:?} in this room", name),
+            ChatError::NotInRoom(name) => write!(f, "{} is not in a room", name),
+        }
+    }
+}
+
+impl std::error::Error for ChatError {}
+
+// ========== Mediator With Weak Back-References ==========
+
+pub struct ChatRoom {
+    name: String,
+    members: RefCell<Vec<Rc<User>>>,
+}
+
+pub struct User {
+    name: String,
+    room: RefCell<Weak<ChatRoom>>,
+    inbox: RefCell<Vec<Message>>,
+    muted: RefCell<HashSet<String>>,
+}
+
+impl ChatRoom {
+    pub fn new(name: &str) -> Rc<Self> {
+        Rc::new(ChatRoom { name: name.to_string(), members: RefCell::new(Vec::new()) })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The room keeps the strong reference; the user only gets a `Weak` back to it
+    pub fn join(self: &Rc<Self>, name: &str) -> Result<Rc<User>, ChatError> {
+        if self.find(name).is_some() {
+            return Err(ChatError::NameTaken(name.to_string()));
+        }
+        let user = Rc::new(User {
+            name: name.to_string(),
+            room: RefCell::new(Rc::downgrade(self)),
+            inbox: RefCell::new(Vec::new()),
+            muted: RefCell::new(HashSet::new()),
+        });
+        self.members.borrow_mut().push(Rc::clone(&user));
+        Ok(user)
+    }
+
+    pub fn leave(&self, name: &str) -> Result<(), ChatError> {
+        let mut members = self.members.borrow_mut();
+        let index = members.iter().position(|user| user.name == name).ok_or_else(|| ChatError::UnknownUser(name.to_string()))?;
+        let user = members.remove(index);
+        *user.room.borrow_mut() = Weak::new();
+        Ok(())
+    }
+
+    pub fn members(&self) -> Vec<String> {
+        self.members.borrow().iter().map(|user| user.name.clone()).collect()
+    }
+
+    fn find(&self, name: &str) -> Option<Rc<User>> {
+        self.members.borrow().iter().find(|user| user.name == name).cloned()
+    }
+
+    /// Delivers to everyone it is meant for who has not muted the sender
+    fn route(&self, message: Message) -> Result<usize, ChatError> {
+        let recipients: Vec<Rc<User>> = match &message.to {
+            Some(to) => vec![self.find(to).ok_or_else(|| ChatError::UnknownUser(to.clone()))?],
+            None => self.members.borrow().iter().filter(|user| user.name != message.from).cloned().collect(),
+        };
+        let mut delivered = 0;
+        for user in recipients {
+            if !user.muted.borrow().contains(&message.from) {
+                user.inbox.borrow_mut().push(message.clone());
+                delivered += 1;
+            }
+        }
+        Ok(delivered)
+    }
+}
+
+impl User {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Fails once the user has left or the room has been dropped
+    fn room(&self) -> Result<Rc<ChatRoom>, ChatError> {
+        self.room.borrow().upgrade().ok_or_else(|| ChatError::NotInRoom(self.name.clone()))
+    }
+
+    /// Returns how many users received it: 0 if `to` has muted this user
+    pub fn send(&self, to: &str, text: &str) -> Result<usize, ChatError> {
+        self.room()?.route(Message { from: self.name.clone(), to: Some(to.to_string()), text: text.to_string() })
+    }
+
+    pub fn broadcast(&self, text: &str) -> Result<usize, ChatError> {
+        self.room()?.route(Message { from: self.name.clone(), to: None, text: text.to_string() })
+    }
+
+    pub fn mute(&self, name: &str) {
+        self.muted.borrow_mut().insert(name.to_string());
+    }
+
+    pub fn unmute(&self, name: &str) {
+        self.muted.borrow_mut().remove(name);
+    }
+
+    pub fn take_inbox(&self) -> Vec<Message> {
+        self.inbox.take()
+    }
+}
+
+// ========== Mediator With IDs ==========
+
+pub mod by_id {
+    use super::{ChatError, Message};
+    use std::collections::HashSet;
+
+    /// Index into the room; never reused, so a departed user's id stays invalid
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct UserId(usize);
+
+    struct Member {
+        name: String,
+        inbox: Vec<Message>,
+        muted: HashSet<UserId>,
+    }
+
+    #[derive(Default)]
+    pub struct Room {
+        members: Vec<Option<Member>>,
+    }
+
+    impl Room {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn join(&mut self, name: &str) -> Result<UserId, ChatError> {
+            if self.id_of(name).is_some() {
+                return Err(ChatError::NameTaken(name.to_string()));
+            }
+            self.members.push(Some(Member { name: name.to_string(), inbox: Vec::new(), muted: HashSet::new() }));
+            Ok(UserId(self.members.len() - 1))
+        }
+
+        pub fn leave(&mut self, id: UserId) -> Result<(), ChatError> {
+            self.member(id)?;
+            self.members[id.0] = None;
+            Ok(())
+        }
+
+        pub fn id_of(&self, name: &str) -> Option<UserId> {
+            self.members.iter().position(|slot| slot.as_ref().is_some_and(|member| member.name == name)).map(UserId)
+        }
+
+        fn member(&self, id: UserId) -> Result<&Member, ChatError> {
+            self.members.get(id.0).and_then(Option::as_ref).ok_or_else(|| ChatError::NotInRoom(format!("user #{}", id.0)))
+        }
+
+        fn member_mut(&mut self, id: UserId) -> Result<&mut Member, ChatError> {
+            self.members.get_mut(id.0).and_then(Option::as_mut).ok_or_else(|| ChatError::NotInRoom(format!("user #{}", id.0)))
+        }
+
+        pub fn send(&mut self, from: UserId, to: UserId, text: &str) -> Result<usize, ChatError> {
+            let message =
+                Message { from: self.member(from)?.name.clone(), to: Some(self.member(to)?.name.clone()), text: text.to_string() };
+            Ok(self.deliver(from, [to], message))
+        }
+
+        pub fn broadcast(&mut self, from: UserId, text: &str) -> Result<usize, ChatError> {
+            let message = Message { from: self.member(from)?.name.clone(), to: None, text: text.to_string() };
+            let everyone: Vec<UserId> =
+                (0..self.members.len()).map(UserId).filter(|&id| id != from && self.member(id).is_ok()).collect();
+            Ok(self.deliver(from, everyone, message))
+        }
+
+        fn deliver(&mut self, from: UserId, recipients: impl IntoIterator<Item = UserId>, message: Message) -> usize {
+            let mut delivered = 0;
+            for id in recipients {
+                if let Ok(member) = self.member_mut(id) {
+                    if !member.muted.contains(&from) {
+                        member.inbox.push(message.clone());
+                        delivered += 1;
+                    }
+                }
+            }
+            delivered
+        }
+
+        pub fn mute(&mut self, who: UserId, whom: UserId) -> Result<(), ChatError> {
+            self.member(whom)?;
+            self.member_mut(who)?.muted.insert(whom);
+            Ok(())
+        }
+
+        pub fn take_inbox(&mut self, id: UserId) -> Result<Vec<Message>, ChatError> {
+            Ok(std::mem::take(&mut self.member_mut(id)?.inbox))
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct MediatorPatternDemo;
+
+impl MediatorPatternDemo {
+    fn show_inbox(user: &User, out: &mut dyn Write) -> io::Result<()> {
+        let inbox = user.take_inbox();
+        writeln!(out, "{}'s inbox ({}):", user.name(), inbox.len())?;
+        for message in inbox {
+            writeln!(out, "  {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Demo for MediatorPatternDemo {
+    fn name(&self) -> &'static str {
+        "mediator_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Chat room mediator with direct messages, broadcasts and mute lists, via Weak links and via ids"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Users Holding a Weak Room =====")?;
+        let room = ChatRoom::new("rustaceans");
+        let alice = room.join("alice").expect("fresh name");
+        let bob = room.join("bob").expect("fresh name");
+        let carol = room.join("carol").expect("fresh name");
+        writeln!(out, "#{} members: {}", room.name(), room.members().join(", "))?;
+        if let Err(error) = room.join("bob") {
+            writeln!(out, "Joining as bob again: {}", error)?;
+        }
+
+        let reached = alice.broadcast("hello everyone").unwrap();
+        writeln!(out, "alice broadcasts, reaching {}", reached)?;
+        bob.send("alice", "hi alice").unwrap();
+        carol.mute("bob");
+        let reached = bob.broadcast("who wants to pair on lifetimes?").unwrap();
+        writeln!(out, "carol mutes bob; bob broadcasts, reaching {}", reached)?;
+        writeln!(out, "bob direct to carol reaches {}", bob.send("carol", "you there?").unwrap())?;
+        if let Err(error) = alice.send("dave", "hi") {
+            writeln!(out, "alice to dave: {}", error)?;
+        }
+        for user in [&alice, &bob, &carol] {
+            Self::show_inbox(user, out)?;
+        }
+
+        writeln!(out, "\n===== When the Link Breaks =====")?;
+        room.leave("carol").unwrap();
+        if let Err(error) = carol.broadcast("bye") {
+            writeln!(out, "carol after leaving: {}", error)?;
+        }
+        writeln!(out, "Strong references to the room: {}, weak: {}", Rc::strong_count(&room), Rc::weak_count(&room))?;
+        drop(room);
+        if let Err(error) = alice.send("bob", "still there?") {
+            writeln!(out, "alice after the room is dropped: {}", error)?;
+        }
+
+        writeln!(out, "\n===== Users as Ids =====")?;
+        let mut room = by_id::Room::new();
+        let [alice, bob, carol] = ["alice", "bob", "carol"].map(|name| room.join(name).expect("fresh name"));
+        room.mute(carol, bob).unwrap();
+        writeln!(out, "alice broadcasts, reaching {}", room.broadcast(alice, "hello everyone").unwrap())?;
+        writeln!(out, "bob broadcasts, reaching {}", room.broadcast(bob, "anyone?").unwrap())?;
+        room.leave(bob).unwrap();
+        if let Err(error) = room.send(alice, bob, "hi bob") {
+            writeln!(out, "alice to bob's old id: {}", error)?;
+        }
+        for message in room.take_inbox(carol).unwrap() {
+            writeln!(out, "  carol got {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&MediatorPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(messages: Vec<Message>) -> Vec<String> {
+        messages.into_iter().map(|message| message.text).collect()
+    }
+
+    #[test]
+    fn direct_messages_reach_only_the_recipient() {
+        let room = ChatRoom::new("test");
+        let [alice, bob, carol] = ["alice", "bob", "carol"].map(|name| room.join(name).unwrap());
+        assert_eq!(alice.send("bob", "psst"), Ok(1));
+        assert_eq!(bob.take_inbox(), [Message { from: "alice".into(), to: Some("bob".into()), text: "psst".into() }]);
+        assert!(alice.take_inbox().is_empty() && carol.take_inbox().is_empty());
+        assert_eq!(alice.send("dave", "hi"), Err(ChatError::UnknownUser("dave".into())));
+        assert_eq!(room.join("carol").err(), Some(ChatError::NameTaken("carol".into())));
+    }
+
+    #[test]
+    fn broadcasts_skip_the_sender_and_anyone_who_muted_them() {
+        let room = ChatRoom::new("test");
+        let [alice, bob, carol] = ["alice", "bob", "carol"].map(|name| room.join(name).unwrap());
+        carol.mute("alice");
+        assert_eq!(alice.broadcast("one"), Ok(1));
+        assert_eq!(alice.send("carol", "two"), Ok(0));
+        carol.unmute("alice");
+        assert_eq!(alice.broadcast("three"), Ok(2));
+        assert_eq!(texts(bob.take_inbox()), ["one", "three"]);
+        assert_eq!(texts(carol.take_inbox()), ["three"]);
+        assert!(alice.take_inbox().is_empty());
+    }
+
+    #[test]
+    fn leaving_or_dropping_the_room_breaks_the_weak_link() {
+        let room = ChatRoom::new("test");
+        let [alice, bob] = ["alice", "bob"].map(|name| room.join(name).unwrap());
+        room.leave("bob").unwrap();
+        assert_eq!(bob.broadcast("hi"), Err(ChatError::NotInRoom("bob".into())));
+        assert_eq!(room.members(), ["alice"]);
+
+        drop(room);
+        assert_eq!(alice.send("bob", "hi"), Err(ChatError::NotInRoom("alice".into())));
+        assert_eq!(Rc::strong_count(&alice), 1, "the dropped room released its members");
+    }
+
+    #[test]
+    fn id_room_routes_and_mutes_the_same_way() {
+        let mut room = by_id::Room::new();
+        let [alice, bob, carol] = ["alice", "bob", "carol"].map(|name| room.join(name).unwrap());
+        room.mute(carol, alice).unwrap();
+        assert_eq!(room.broadcast(alice, "one"), Ok(1));
+        assert_eq!(room.send(alice, carol, "two"), Ok(0));
+        assert_eq!(room.send(bob, carol, "three"), Ok(1));
+        assert_eq!(texts(room.take_inbox(bob).unwrap()), ["one"]);
+        assert_eq!(texts(room.take_inbox(carol).unwrap()), ["three"]);
+        assert_eq!(room.id_of("bob"), Some(bob));
+    }
+
+    #[test]
+    fn stale_ids_are_rejected_and_never_reused() {
+        let mut room = by_id::Room::new();
+        let alice = room.join("alice").unwrap();
+        let bob = room.join("bob").unwrap();
+        room.leave(bob).unwrap();
+        assert_eq!(room.send(alice, bob, "hi"), Err(ChatError::NotInRoom("user #1".into())));
+        assert_eq!(room.leave(bob), Err(ChatError::NotInRoom("user #1".into())));
+        let new_bob = room.join("bob").unwrap();
+        assert_ne!(new_bob, bob);
+        assert_eq!(room.broadcast(alice, "welcome back"), Ok(1));
+    }
+}
@@ -0,0 +1,395 @@
+//! Memento Pattern Implementation in Rust
+//!
+//! The Memento Pattern is a behavioral design pattern that captures an object's internal
+//! state so it can be restored later, without exposing that state to whoever keeps the
+//! copy.
+//!
+//! This example is a text editor with undo and redo:
+//! - Originator: `Editor`, which creates a `Snapshot` of itself and restores from one
+//! - Memento: `Snapshot`, whose fields are private to this module; outside it a snapshot
+//!   can be stored, labelled and handed back, but not read or changed
+//! - Caretaker: `History`, undo and redo stacks of snapshots with a size limit. It never
+//!   looks inside them
+//!
+//! Because a snapshot owns a full copy of the text, it stays valid however the editor
+//! changes afterwards. With `--features serde`, snapshots and the history derive
+//! `Serialize`/`Deserialize`, and `persistence` saves a whole session as JSON so undo
+//! survives a restart. Restoring always clamps the cursor, since a snapshot read back
+//! from disk may have been edited by hand.
+
+use demo::Demo;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
+// ========== Originator ==========
+
+/// Text with a cursor, counted in characters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Editor {
+    text: String,
+    cursor: usize,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text.char_indices().nth(chars).map_or(self.text.len(), |(index, _)| index)
+    }
+
+    /// Inserts at the cursor and moves past the new text
+    pub fn type_text(&mut self, text: &str) {
+        let at = self.byte_index(self.cursor);
+        self.text.insert_str(at, text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Deletes up to `count` characters before the cursor
+    pub fn backspace(&mut self, count: usize) {
+        let start = self.cursor.saturating_sub(count);
+        let range = self.byte_index(start)..self.byte_index(self.cursor);
+        self.text.replace_range(range, "");
+        self.cursor = start;
+    }
+
+    /// Clamps to the end of the text
+    pub fn move_cursor(&mut self, to: usize) {
+        self.cursor = to.min(self.text.chars().count());
+    }
+
+    pub fn save(&self, label: &str) -> Snapshot {
+        Snapshot { label: label.to_string(), text: self.text.clone(), cursor: self.cursor }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.text.clone_from(&snapshot.text);
+        self.move_cursor(snapshot.cursor);
+    }
+}
+
+// ========== Memento ==========
+
+/// Opaque outside this module: only `Editor` reads the fields back
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    label: String,
+    text: String,
+    cursor: usize,
+}
+
+impl Snapshot {
+    /// What the change after this snapshot was, for menus like "Undo typing"
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+// ========== Caretaker ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    limit: usize,
+}
+
+impl History {
+    /// Keeps at most `limit` undo steps, dropping the oldest
+    pub fn new(limit: usize) -> Self {
+        History { undo: VecDeque::new(), redo: Vec::new(), limit }
+    }
+
+    /// Snapshots `editor`, then applies `change`; a new edit clears the redo stack
+    pub fn edit(&mut self, editor: &mut Editor, label: &str, change: impl FnOnce(&mut Editor)) {
+        self.undo.push_back(editor.save(label));
+        if self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+        change(editor);
+    }
+
+    /// Returns the label of the undone change, or `None` when there is nothing to undo
+    pub fn undo(&mut self, editor: &mut Editor) -> Option<String> {
+        let snapshot = self.undo.pop_back()?;
+        self.redo.push(editor.save(snapshot.label()));
+        editor.restore(&snapshot);
+        Some(snapshot.label)
+    }
+
+    pub fn redo(&mut self, editor: &mut Editor) -> Option<String> {
+        let snapshot = self.redo.pop()?;
+        self.undo.push_back(editor.save(snapshot.label()));
+        editor.restore(&snapshot);
+        Some(snapshot.label)
+    }
+
+    pub fn undo_labels(&self) -> Vec<&str> {
+        self.undo.iter().rev().map(Snapshot::label).collect()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+}
+
+// ========== Persistence ==========
+
+/// JSON save and load for a whole editing session
+#[cfg(feature = "serde")]
+pub mod persistence {
+    use super::{Editor, History, Snapshot};
+    use std::fmt;
+    use std::fs;
+    use std::path::Path;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Session {
+        current: Snapshot,
+        history: History,
+    }
+
+    #[derive(Debug)]
+    pub enum PersistError {
+        Io(std::io::Error),
+        Json(serde_json::Error),
+    }
+
+    impl fmt::Display for PersistError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PersistError::Io(error) => write!(f, "could not access the session file: {}", error),
+                PersistError::Json(error) => write!(f, "the session file is not valid: {}", error),
+            }
+        }
+    }
+
+    impl std::error::Error for PersistError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                PersistError::Io(error) => Some(error),
+                PersistError::Json(error) => Some(error),
+            }
+        }
+    }
+
+    impl From<std::io::Error> for PersistError {
+        fn from(error: std::io::Error) -> Self {
+            PersistError::Io(error)
+        }
+    }
+
+    impl From<serde_json::Error> for PersistError {
+        fn from(error: serde_json::Error) -> Self {
+            PersistError::Json(error)
+        }
+    }
+
+    pub fn to_json(editor: &Editor, history: &History) -> Result<String, PersistError> {
+        let session = Session { current: editor.save("current"), history: history.clone() };
+        Ok(serde_json::to_string_pretty(&session)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<(Editor, History), PersistError> {
+        let session: Session = serde_json::from_str(json)?;
+        let mut editor = Editor::new();
+        editor.restore(&session.current);
+        Ok((editor, session.history))
+    }
+
+    pub fn save(path: &Path, editor: &Editor, history: &History) -> Result<(), PersistError> {
+        Ok(fs::write(path, to_json(editor, history)?)?)
+    }
+
+    pub fn load(path: &Path) -> Result<(Editor, History), PersistError> {
+        from_json(&fs::read_to_string(path)?)
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct MementoPatternDemo;
+
+impl MementoPatternDemo {
+    fn show(editor: &Editor, out: &mut dyn Write) -> io::Result<()> {
+        let (before, after) = editor.text().split_at(editor.byte_index(editor.cursor()));
+        writeln!(out, "  {:?}", format!("{}|{}", before, after))
+    }
+}
+
+impl Demo for MementoPatternDemo {
+    fn name(&self) -> &'static str {
+        "memento_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Editor snapshots kept by an undo/redo history, optionally persisted as JSON"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let mut editor = Editor::new();
+        let mut history = History::new(10);
+
+        writeln!(out, "===== Editing =====")?;
+        history.edit(&mut editor, "typing", |editor| editor.type_text("Hello world"));
+        Self::show(&editor, out)?;
+        history.edit(&mut editor, "insert", |editor| {
+            editor.move_cursor(5);
+            editor.type_text(", brave new");
+        });
+        Self::show(&editor, out)?;
+        history.edit(&mut editor, "delete", |editor| editor.backspace(4));
+        Self::show(&editor, out)?;
+        writeln!(out, "Undo stack, newest first: {:?}", history.undo_labels())?;
+
+        writeln!(out, "\n===== Undo and Redo =====")?;
+        while let Some(label) = history.undo(&mut editor) {
+            write!(out, "undo {:<7}", label)?;
+            Self::show(&editor, out)?;
+        }
+        for _ in 0..2 {
+            let label = history.redo(&mut editor).expect("two undone changes to redo");
+            write!(out, "redo {:<7}", label)?;
+            Self::show(&editor, out)?;
+        }
+        history.edit(&mut editor, "typing", |editor| editor.type_text("!"));
+        Self::show(&editor, out)?;
+        writeln!(out, "A new edit clears the redo stack: {} left", history.redo_len())?;
+
+        writeln!(out, "\n===== A Bounded History =====")?;
+        let mut short = History::new(3);
+        let mut counter = Editor::new();
+        for digit in ["1", "2", "3", "4", "5"] {
+            short.edit(&mut counter, digit, |editor| editor.type_text(digit));
+        }
+        let mut undone = 0;
+        while short.undo(&mut counter).is_some() {
+            undone += 1;
+        }
+        writeln!(out, "Five edits, limit 3: undid {} and stopped at {:?}", undone, counter.text())?;
+
+        // Enable with `--features serde`
+        #[cfg(feature = "serde")]
+        {
+            writeln!(out, "\n===== Saving the Session =====")?;
+            let json = persistence::to_json(&editor, &history).expect("snapshots serialize");
+            writeln!(out, "{}", json)?;
+            let (mut reopened, mut reopened_history) = persistence::from_json(&json).expect("round trip");
+            reopened_history.undo(&mut reopened);
+            writeln!(out, "Reloaded and undone once:")?;
+            Self::show(&reopened, out)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&MementoPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edited() -> (Editor, History) {
+        let mut editor = Editor::new();
+        let mut history = History::new(10);
+        history.edit(&mut editor, "typing", |editor| editor.type_text("abc"));
+        history.edit(&mut editor, "move", |editor| editor.move_cursor(1));
+        history.edit(&mut editor, "typing", |editor| editor.type_text("é"));
+        (editor, history)
+    }
+
+    #[test]
+    fn snapshots_restore_text_and_cursor_exactly() {
+        let mut editor = Editor::new();
+        editor.type_text("naïve");
+        editor.move_cursor(3);
+        let snapshot = editor.save("before");
+        editor.backspace(10);
+        editor.type_text("x");
+        assert_eq!((editor.text(), editor.cursor()), ("xve", 1));
+        editor.restore(&snapshot);
+        assert_eq!((editor.text(), editor.cursor()), ("naïve", 3));
+        assert_eq!(snapshot.label(), "before");
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_history_in_order() {
+        let (mut editor, mut history) = edited();
+        assert_eq!(editor.text(), "aébc");
+        assert_eq!(history.undo_labels(), ["typing", "move", "typing"]);
+        assert_eq!(history.undo(&mut editor).as_deref(), Some("typing"));
+        assert_eq!((editor.text(), editor.cursor()), ("abc", 1));
+        assert_eq!(history.undo(&mut editor).as_deref(), Some("move"));
+        assert_eq!(editor.cursor(), 3);
+        assert_eq!(history.redo(&mut editor).as_deref(), Some("move"));
+        assert_eq!(history.redo(&mut editor).as_deref(), Some("typing"));
+        assert_eq!((editor.text(), editor.cursor()), ("aébc", 2));
+        assert_eq!(history.redo(&mut editor), None);
+    }
+
+    #[test]
+    fn a_new_edit_discards_the_redo_stack() {
+        let (mut editor, mut history) = edited();
+        history.undo(&mut editor);
+        history.undo(&mut editor);
+        assert_eq!(history.redo_len(), 2);
+        history.edit(&mut editor, "typing", |editor| editor.type_text("z"));
+        assert_eq!(history.redo_len(), 0);
+        assert_eq!(history.redo(&mut editor), None);
+        assert_eq!(editor.text(), "abcz");
+    }
+
+    #[test]
+    fn the_limit_drops_the_oldest_snapshots() {
+        let mut editor = Editor::new();
+        let mut history = History::new(2);
+        for text in ["a", "b", "c"] {
+            history.edit(&mut editor, text, |editor| editor.type_text(text));
+        }
+        assert_eq!(history.undo_labels(), ["c", "b"]);
+        while history.undo(&mut editor).is_some() {}
+        assert_eq!(editor.text(), "a");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn sessions_survive_a_round_trip_through_a_file() {
+        let (mut editor, mut history) = edited();
+        history.undo(&mut editor);
+        let path = std::env::temp_dir().join(format!("memento-session-{}.json", std::process::id()));
+        persistence::save(&path, &editor, &history).unwrap();
+        let (loaded, loaded_history) = persistence::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, editor);
+        assert_eq!(loaded_history, history);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn restoring_hand_edited_json_clamps_the_cursor() {
+        let json = r#"{"current":{"label":"current","text":"hi","cursor":99},"history":{"undo":[],"redo":[],"limit":5}}"#;
+        let (editor, history) = persistence::from_json(json).unwrap();
+        assert_eq!((editor.text(), editor.cursor()), ("hi", 2));
+        assert_eq!(history, History::new(5));
+        assert!(matches!(persistence::from_json("{}"), Err(persistence::PersistError::Json(_))));
+    }
+}
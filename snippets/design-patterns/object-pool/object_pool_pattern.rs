@@ -0,0 +1,405 @@
+//! Object Pool Pattern Implementation in Rust
+//!
+//! The Object Pool Pattern is a creational design pattern that keeps expensive objects,
+//! such as connections, buffers or parsers, around for reuse. Callers check an object
+//! out, use it and hand it back instead of creating and destroying one each time.
+//!
+//! This example is a thread-safe generic `Pool<T>`:
+//! - Objects are created lazily by a factory closure, never more than `capacity` at once
+//! - `acquire()` blocks until an object is free, `try_acquire()` returns `None` instead,
+//!   and `acquire_timeout()` gives up after a while
+//! - Each returns a `Pooled<T>` guard that derefs to the object and returns it to the
+//!   pool on drop (RAII), so a checkout cannot leak on an early return or a panic
+//! - An optional reset hook clears leftover state before an object is reused
+//!
+//! The pool is an `Arc` around a `Mutex` and a `Condvar`; guards hold their own `Arc`,
+//! so they can be sent to other threads and outlive the `Pool` handle they came from.
+
+use demo::Demo;
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+// ========== The Pool ==========
+
+type Factory<T> = Box<dyn Fn() -> T + Send + Sync>;
+type Reset<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+struct State<T> {
+    idle: Vec<T>,
+    /// Objects that exist right now, idle or checked out
+    live: usize,
+    created: usize,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    returned: Condvar,
+    capacity: usize,
+    factory: Factory<T>,
+    reset: Option<Reset<T>>,
+}
+
+impl<T> Shared<T> {
+    /// Nothing panics while the lock is held, so a poisoned lock still guards valid state
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Counts at one moment; other threads may change them straight after
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub idle: usize,
+    pub in_use: usize,
+    pub created: usize,
+}
+
+pub struct Pool<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Self {
+        Pool { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T: Send + 'static> Pool<T> {
+    /// # Panics
+    /// If `capacity` is zero, since nothing could ever be acquired
+    pub fn new(capacity: usize, factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        assert!(capacity > 0, "a pool needs room for at least one object");
+        Self::build(capacity, Box::new(factory), None)
+    }
+
+    /// Like `new`, but runs `reset` on every object as it comes back, before anyone else can get it
+    pub fn with_reset(
+        capacity: usize,
+        factory: impl Fn() -> T + Send + Sync + 'static,
+        reset: impl Fn(&mut T) + Send + Sync + 'static,
+    ) -> Self {
+        assert!(capacity > 0, "a pool needs room for at least one object");
+        Self::build(capacity, Box::new(factory), Some(Box::new(reset)))
+    }
+
+    fn build(capacity: usize, factory: Factory<T>, reset: Option<Reset<T>>) -> Self {
+        let state = Mutex::new(State { idle: Vec::new(), live: 0, created: 0 });
+        Pool { shared: Arc::new(Shared { state, returned: Condvar::new(), capacity, factory, reset }) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        let state = self.shared.lock();
+        PoolStats { idle: state.idle.len(), in_use: state.live - state.idle.len(), created: state.created }
+    }
+
+    /// Takes an idle object, or reserves a slot for a new one; `None` when the pool is full
+    fn checkout(&self, state: &mut State<T>) -> Option<Option<T>> {
+        if let Some(object) = state.idle.pop() {
+            return Some(Some(object));
+        }
+        if state.live < self.shared.capacity {
+            state.live += 1;
+            state.created += 1;
+            return Some(None);
+        }
+        None
+    }
+
+    /// Runs the factory outside the lock, so a slow constructor does not stall returns
+    fn guard(&self, checked_out: Option<T>) -> Pooled<T> {
+        let object = checked_out.unwrap_or_else(|| {
+            let reservation = Reservation(&self.shared);
+            let object = (self.shared.factory)();
+            std::mem::forget(reservation);
+            object
+        });
+        Pooled { object: Some(object), shared: Arc::clone(&self.shared) }
+    }
+
+    pub fn try_acquire(&self) -> Option<Pooled<T>> {
+        let checked_out = self.checkout(&mut self.shared.lock())?;
+        Some(self.guard(checked_out))
+    }
+
+    /// Blocks until an object is free
+    pub fn acquire(&self) -> Pooled<T> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(checked_out) = self.checkout(&mut state) {
+                drop(state);
+                return self.guard(checked_out);
+            }
+            state = self.shared.returned.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    pub fn acquire_timeout(&self, timeout: Duration) -> Option<Pooled<T>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(checked_out) = self.checkout(&mut state) {
+                drop(state);
+                return Some(self.guard(checked_out));
+            }
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            state = self.shared.returned.wait_timeout(state, remaining).unwrap_or_else(PoisonError::into_inner).0;
+        }
+    }
+}
+
+/// Gives a reserved slot back if the factory panics, so the pool does not shrink
+struct Reservation<'a, T>(&'a Shared<T>);
+
+impl<T> Drop for Reservation<'_, T> {
+    fn drop(&mut self) {
+        self.0.lock().live -= 1;
+        self.0.returned.notify_one();
+    }
+}
+
+// ========== The Guard ==========
+
+/// A checked-out object; goes back to the pool when dropped
+pub struct Pooled<T> {
+    object: Option<T>,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Pooled<T> {
+    /// Keeps the object for good; its slot is freed so the pool can create a replacement
+    pub fn detach(mut self) -> T {
+        let object = self.object.take().expect("present until dropped or detached");
+        self.shared.lock().live -= 1;
+        self.shared.returned.notify_one();
+        object
+    }
+}
+
+impl<T> Deref for Pooled<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.object.as_ref().expect("present until dropped or detached")
+    }
+}
+
+impl<T> DerefMut for Pooled<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.object.as_mut().expect("present until dropped or detached")
+    }
+}
+
+impl<T> Drop for Pooled<T> {
+    fn drop(&mut self) {
+        if let Some(mut object) = self.object.take() {
+            if let Some(reset) = &self.shared.reset {
+                reset(&mut object);
+            }
+            self.shared.lock().idle.push(object);
+            self.shared.returned.notify_one();
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Stands in for something expensive to open
+#[derive(Debug)]
+pub struct Connection {
+    pub id: usize,
+    pub queries: Vec<String>,
+}
+
+fn connection_pool(capacity: usize) -> Pool<Connection> {
+    let next_id = std::sync::atomic::AtomicUsize::new(1);
+    Pool::with_reset(
+        capacity,
+        move || Connection { id: next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed), queries: Vec::new() },
+        |connection| connection.queries.clear(),
+    )
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ObjectPoolPatternDemo;
+
+impl Demo for ObjectPoolPatternDemo {
+    fn name(&self) -> &'static str {
+        "object_pool_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generic thread-safe pool with blocking and non-blocking checkout and RAII return guards"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Checkout and Return =====")?;
+        let pool = connection_pool(2);
+        let mut first = pool.acquire();
+        first.queries.push("SELECT 1".to_string());
+        let second = pool.acquire();
+        writeln!(out, "Checked out connections {} and {}: {:?}", first.id, second.id, pool.stats())?;
+        writeln!(out, "try_acquire on a full pool: {}", pool.try_acquire().map_or("none free".to_string(), |c| c.id.to_string()))?;
+        drop(first);
+        let reused = pool.try_acquire().expect("one was returned");
+        writeln!(out, "After returning one, try_acquire gets connection {} with {} queries left over", reused.id, reused.queries.len())?;
+        drop((second, reused));
+        writeln!(out, "All returned: {:?}", pool.stats())?;
+
+        writeln!(out, "\n===== Detaching =====")?;
+        let kept = pool.acquire().detach();
+        writeln!(out, "Kept connection {} for good: {:?}", kept.id, pool.stats())?;
+        let [a, b] = [pool.acquire(), pool.acquire()];
+        writeln!(out, "The freed slot is refilled with a new connection: {} and {}", a.id, b.id)?;
+        drop((a, b));
+
+        writeln!(out, "\n===== Many Threads, Three Connections =====")?;
+        let pool = connection_pool(3);
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let pool = pool.clone();
+                std::thread::spawn(move || {
+                    for query in 0..25 {
+                        let mut connection = pool.acquire();
+                        connection.queries.push(format!("worker {} query {}", worker, query));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("worker finished");
+        }
+        let stats = pool.stats();
+        writeln!(out, "8 workers × 25 queries done")?;
+        writeln!(out, "Never more than {} connections: {}", pool.capacity(), stats.created <= pool.capacity())?;
+        writeln!(out, "Every connection back in the pool: {}", stats.in_use == 0 && stats.idle == stats.created)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ObjectPoolPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+
+    #[test]
+    fn returned_objects_are_reused_and_reset() {
+        let pool = connection_pool(4);
+        let mut connection = pool.acquire();
+        connection.queries.push("SELECT 1".into());
+        let id = connection.id;
+        drop(connection);
+        let again = pool.acquire();
+        assert_eq!(again.id, id);
+        assert!(again.queries.is_empty(), "reset ran on return");
+        assert_eq!(pool.stats(), PoolStats { idle: 0, in_use: 1, created: 1 });
+    }
+
+    #[test]
+    fn try_acquire_respects_the_capacity() {
+        let pool = Pool::new(2, || 0u8);
+        let held = [pool.try_acquire().unwrap(), pool.try_acquire().unwrap()];
+        assert!(pool.try_acquire().is_none());
+        assert!(pool.acquire_timeout(Duration::from_millis(10)).is_none());
+        drop(held);
+        assert_eq!(pool.stats(), PoolStats { idle: 2, in_use: 0, created: 2 });
+        assert!(pool.try_acquire().is_some());
+    }
+
+    #[test]
+    fn detaching_frees_the_slot_for_a_new_object() {
+        let pool = connection_pool(1);
+        let kept = pool.acquire().detach();
+        let replacement = pool.try_acquire().expect("the slot was freed");
+        assert_ne!(replacement.id, kept.id);
+        assert_eq!(pool.stats().created, 2);
+    }
+
+    #[test]
+    fn a_panicking_factory_does_not_use_up_a_slot() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let pool = {
+            let attempts = Arc::clone(&attempts);
+            Pool::new(1, move || {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("could not connect");
+                }
+                "connected"
+            })
+        };
+        let failed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.acquire()));
+        assert!(failed.is_err());
+        assert_eq!(*pool.try_acquire().expect("the slot was given back"), "connected");
+    }
+
+    #[test]
+    fn blocking_acquire_wakes_when_another_thread_returns_one() {
+        let pool = connection_pool(1);
+        let held = pool.acquire();
+        let (started, waiting) = mpsc::channel();
+        let waiter = {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                started.send(()).unwrap();
+                pool.acquire().id
+            })
+        };
+        waiting.recv().unwrap();
+        let id = held.id;
+        drop(held);
+        assert_eq!(waiter.join().unwrap(), id);
+    }
+
+    #[test]
+    fn many_threads_never_exceed_the_capacity() {
+        let in_use = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let pool = Pool::new(3, Vec::<usize>::new);
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let (pool, in_use, peak) = (pool.clone(), Arc::clone(&in_use), Arc::clone(&peak));
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        let mut object = if worker % 2 == 0 {
+                            pool.acquire()
+                        } else {
+                            loop {
+                                if let Some(object) = pool.try_acquire() {
+                                    break object;
+                                }
+                                std::thread::yield_now();
+                            }
+                        };
+                        let now = in_use.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        object.push(worker);
+                        in_use.fetch_sub(1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        let stats = pool.stats();
+        assert!(stats.created <= 3);
+        assert_eq!((stats.in_use, stats.idle), (0, stats.created));
+        let total: usize = (0..stats.created).map(|_| pool.try_acquire().unwrap().detach().len()).sum();
+        assert_eq!(total, 8 * 50, "no checkout was lost");
+    }
+}
@@ -1,24 +1,46 @@
-/**
- * Observer Pattern Implementation in Rust
- *
- * The Observer Pattern is a behavioral design pattern that defines a one-to-many dependency
- * between objects so that when one object changes state, all its dependents are notified
- * and updated automatically.
- *
- * This example demonstrates a simple weather station (subject) that notifies
- * multiple display devices (observers) when weather data changes.
- */
-
-use std::cell::RefCell;
+//! Observer Pattern Implementation in Rust
+//!
+//! The Observer Pattern is a behavioral design pattern that defines a one-to-many dependency
+//! between objects so that when one object changes state, all its dependents are notified
+//! and updated automatically.
+//!
+//! This example demonstrates a simple weather station (subject) that notifies
+//! multiple display devices (observers) when weather data changes.
+//!
+//! `Subject<E>` and `Observer<E>` are generic over the event they carry. The weather station
+//! publishes `WeatherEvent`s, and a stock ticker reuses the same traits (and the shared
+//! `Observers<E>` list) to publish `PriceChange`s.
+//!
+//! For a one-off reaction, a trait object is more ceremony than needed. `WeatherData::subscribe`
+//! takes any `FnMut(&WeatherEvent)` closure and returns a `SubscriptionId` for `unsubscribe`.
+
+use demo::Demo;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
 use std::rc::{Rc, Weak};
-use std::fmt;
+
+/// Where the station and its displays print. Shared, because every observer writes
+/// to the same screen as the subject that notifies it.
+pub type Console = Rc<RefCell<dyn Write>>;
+
+/// A console backed by the process's stdout.
+pub fn stdout_console() -> Console {
+    Rc::new(RefCell::new(io::stdout()))
+}
+
+/// Writes one line to `console`. Like `println!`, a failed write is a bug in the demo,
+/// not something an observer can recover from.
+fn say(console: &Console, line: std::fmt::Arguments) {
+    writeln!(console.borrow_mut(), "{}", line).expect("failed to write to the console");
+}
 
 // ========== Observer Trait ==========
 
-/// Observer trait to be implemented by all display devices
-trait Observer {
+/// Observer trait to be implemented by anything that reacts to events of type `E`
+pub trait Observer<E> {
     /// Update method called by the subject when state changes
-    fn update(&mut self, temperature: f32, humidity: f32, pressure: f32);
+    fn update(&mut self, event: &E);
 
     /// Get the name of the observer for identification
     fn name(&self) -> &str;
@@ -26,126 +48,202 @@ trait Observer {
 
 // ========== Subject Trait ==========
 
-/// Subject trait to be implemented by objects that notify observers
-trait Subject {
+/// Subject trait to be implemented by objects that notify observers of `E`
+pub trait Subject<E> {
     /// Register an observer to be notified of changes
-    fn register_observer(&mut self, observer: Rc<RefCell<dyn Observer>>);
+    fn register_observer(&mut self, observer: Rc<RefCell<dyn Observer<E>>>);
 
     /// Remove an observer from the notification list
-    fn remove_observer(&mut self, observer: &Rc<RefCell<dyn Observer>>);
+    fn remove_observer(&mut self, observer: &Rc<RefCell<dyn Observer<E>>>);
 
     /// Notify all registered observers of state changes
     fn notify_observers(&self);
 }
 
+/// The bookkeeping every subject needs, whatever it publishes. Observers are held weakly,
+/// so dropping one is as good as removing it.
+pub struct Observers<E> {
+    observers: Vec<Weak<RefCell<dyn Observer<E>>>>,
+    console: Console,
+}
+
+impl<E> Observers<E> {
+    /// An empty list that reports registrations and removals to `console`
+    pub fn new(console: Console) -> Self {
+        Observers { observers: Vec::new(), console }
+    }
+
+    pub fn register(&mut self, observer: Rc<RefCell<dyn Observer<E>>>) {
+        let observer_name = observer.borrow().name().to_string();
+        self.observers.push(Rc::downgrade(&observer));
+        say(&self.console, format_args!("Observer '{}' registered", observer_name));
+    }
+
+    pub fn remove(&mut self, observer_to_remove: &Rc<RefCell<dyn Observer<E>>>) {
+        let observer_name = observer_to_remove.borrow().name().to_string();
+        let initial_count = self.observers.len();
+
+        // Filter out the observer to remove and any weak references that can't be upgraded
+        self.observers.retain(|weak_observer| match weak_observer.upgrade() {
+            Some(observer) => !Rc::ptr_eq(&observer, observer_to_remove),
+            None => false,
+        });
+
+        if self.observers.len() < initial_count {
+            say(&self.console, format_args!("Observer '{}' removed", observer_name));
+        }
+    }
+
+    /// Hands `event` to every observer that is still alive, in registration order
+    pub fn notify(&self, event: &E) {
+        for observer in self.observers.iter().filter_map(Weak::upgrade) {
+            observer.borrow_mut().update(event);
+        }
+    }
+}
+
 // ========== Weather Data Implementation ==========
 
-/// WeatherData struct implements the Subject trait
-struct WeatherData {
-    observers: Vec<Weak<RefCell<dyn Observer>>>,
-    temperature: f32,
-    humidity: f32,
-    pressure: f32,
+/// One set of measurements, published by `WeatherData` whenever they change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherEvent {
+    pub temperature: f32,
+    pub humidity: f32,
+    pub pressure: f32,
+}
+
+impl WeatherEvent {
+    pub fn new(temperature: f32, humidity: f32, pressure: f32) -> Self {
+        WeatherEvent { temperature, humidity, pressure }
+    }
+}
+
+/// Returned by `WeatherData::subscribe`, for `WeatherData::unsubscribe`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Callback = Box<dyn FnMut(&WeatherEvent)>;
+
+/// WeatherData struct implements the Subject trait for `WeatherEvent`, and also accepts
+/// plain closures through `subscribe`
+pub struct WeatherData {
+    observers: Observers<WeatherEvent>,
+    // A RefCell because notify_observers only gets &self, but FnMut needs &mut to call
+    callbacks: RefCell<Vec<(SubscriptionId, Callback)>>,
+    next_subscription: u64,
+    measurements: WeatherEvent,
 }
 
 impl WeatherData {
-    /// Create a new WeatherData instance
-    fn new() -> Self {
+    /// Create a new WeatherData instance that reports to stdout
+    pub fn new() -> Self {
+        Self::with_console(stdout_console())
+    }
+
+    /// Create a new WeatherData instance that reports to `console`
+    pub fn with_console(console: Console) -> Self {
         WeatherData {
-            observers: Vec::new(),
-            temperature: 0.0,
-            humidity: 0.0,
-            pressure: 0.0,
+            observers: Observers::new(console),
+            callbacks: RefCell::new(Vec::new()),
+            next_subscription: 0,
+            measurements: WeatherEvent::new(0.0, 0.0, 0.0),
         }
     }
 
+    /// The most recent measurements
+    pub fn measurements(&self) -> WeatherEvent {
+        self.measurements
+    }
+
+    /// Call `callback` with every new set of measurements, after the registered observers.
+    /// Ids are never reused, so a stale id cannot unsubscribe someone else.
+    pub fn subscribe(&mut self, callback: impl FnMut(&WeatherEvent) + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_subscription);
+        self.next_subscription += 1;
+        self.callbacks.get_mut().push((id, Box::new(callback)));
+        id
+    }
+
+    /// Stop calling the closure behind `id`. Returns false if it was already unsubscribed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let callbacks = self.callbacks.get_mut();
+        let initial_count = callbacks.len();
+        callbacks.retain(|(subscribed, _)| *subscribed != id);
+        callbacks.len() < initial_count
+    }
+
     /// Called when measurements have been updated
-    fn measurements_changed(&self) {
+    pub fn measurements_changed(&self) {
         self.notify_observers();
     }
 
     /// Set new weather measurements
-    fn set_measurements(&mut self, temperature: f32, humidity: f32, pressure: f32) {
-        self.temperature = temperature;
-        self.humidity = humidity;
-        self.pressure = pressure;
+    pub fn set_measurements(&mut self, temperature: f32, humidity: f32, pressure: f32) {
+        self.measurements = WeatherEvent::new(temperature, humidity, pressure);
         self.measurements_changed();
     }
 }
 
-impl Subject for WeatherData {
-    fn register_observer(&mut self, observer: Rc<RefCell<dyn Observer>>) {
-        let observer_name = observer.borrow().name().to_string();
-        self.observers.push(Rc::downgrade(&observer));
-        println!("Observer '{}' registered", observer_name);
+impl Default for WeatherData {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn remove_observer(&mut self, observer_to_remove: &Rc<RefCell<dyn Observer>>) {
-        let observer_name = observer_to_remove.borrow().name().to_string();
-        let initial_count = self.observers.len();
-
-        // Filter out the observer to remove and any weak references that can't be upgraded
-        self.observers.retain(|weak_observer| {
-            if let Some(observer) = weak_observer.upgrade() {
-                // Keep if it's not the one we want to remove
-                !Rc::ptr_eq(&observer, observer_to_remove)
-            } else {
-                // Remove if the weak reference can't be upgraded
-                false
-            }
-        });
+impl Subject<WeatherEvent> for WeatherData {
+    fn register_observer(&mut self, observer: Rc<RefCell<dyn Observer<WeatherEvent>>>) {
+        self.observers.register(observer);
+    }
 
-        if self.observers.len() < initial_count {
-            println!("Observer '{}' removed", observer_name);
-        }
+    fn remove_observer(&mut self, observer: &Rc<RefCell<dyn Observer<WeatherEvent>>>) {
+        self.observers.remove(observer);
     }
 
     fn notify_observers(&self) {
-        // Create a new vector to hold valid observers
-        let mut valid_observers = Vec::new();
-
-        // Process each observer
-        for weak_observer in &self.observers {
-            if let Some(observer) = weak_observer.upgrade() {
-                // Notify the observer
-                observer.borrow_mut().update(self.temperature, self.humidity, self.pressure);
-                // Keep this observer
-                valid_observers.push(Weak::clone(weak_observer));
-            }
+        self.observers.notify(&self.measurements);
+        for (_, callback) in self.callbacks.borrow_mut().iter_mut() {
+            callback(&self.measurements);
         }
     }
 }
 
 // ========== Display Implementations ==========
 
-/// CurrentConditionsDisplay implements the Observer trait
-struct CurrentConditionsDisplay {
+/// CurrentConditionsDisplay observes `WeatherEvent`s
+pub struct CurrentConditionsDisplay {
     name: String,
+    console: Console,
     temperature: f32,
     humidity: f32,
 }
 
 impl CurrentConditionsDisplay {
-    /// Create a new CurrentConditionsDisplay instance
-    fn new(name: &str) -> Self {
+    /// Create a new CurrentConditionsDisplay instance that prints to stdout
+    pub fn new(name: &str) -> Self {
+        Self::with_console(name, stdout_console())
+    }
+
+    /// Create a new CurrentConditionsDisplay instance that prints to `console`
+    pub fn with_console(name: &str, console: Console) -> Self {
         CurrentConditionsDisplay {
             name: name.to_string(),
+            console,
             temperature: 0.0,
             humidity: 0.0,
         }
     }
 
     /// Display the current conditions
-    fn display(&self) {
-        println!("[{}] Current conditions: {:.1}°F and {:.1}% humidity",
-                 self.name, self.temperature, self.humidity);
+    pub fn display(&self) {
+        say(&self.console, format_args!("[{}] Current conditions: {:.1}°F and {:.1}% humidity",
+                                        self.name, self.temperature, self.humidity));
     }
 }
 
-impl Observer for CurrentConditionsDisplay {
-    fn update(&mut self, temperature: f32, humidity: f32, _pressure: f32) {
-        self.temperature = temperature;
-        self.humidity = humidity;
+impl Observer<WeatherEvent> for CurrentConditionsDisplay {
+    fn update(&mut self, event: &WeatherEvent) {
+        self.temperature = event.temperature;
+        self.humidity = event.humidity;
         self.display();
     }
 
@@ -154,9 +252,10 @@ impl Observer for CurrentConditionsDisplay {
     }
 }
 
-/// StatisticsDisplay implements the Observer trait
-struct StatisticsDisplay {
+/// StatisticsDisplay observes `WeatherEvent`s
+pub struct StatisticsDisplay {
     name: String,
+    console: Console,
     max_temp: f32,
     min_temp: f32,
     temp_sum: f32,
@@ -164,10 +263,16 @@ struct StatisticsDisplay {
 }
 
 impl StatisticsDisplay {
-    /// Create a new StatisticsDisplay instance
-    fn new(name: &str) -> Self {
+    /// Create a new StatisticsDisplay instance that prints to stdout
+    pub fn new(name: &str) -> Self {
+        Self::with_console(name, stdout_console())
+    }
+
+    /// Create a new StatisticsDisplay instance that prints to `console`
+    pub fn with_console(name: &str, console: Console) -> Self {
         StatisticsDisplay {
             name: name.to_string(),
+            console,
             max_temp: 0.0,
             min_temp: 200.0, // Start with a high value
             temp_sum: 0.0,
@@ -176,15 +281,16 @@ impl StatisticsDisplay {
     }
 
     /// Display the statistics
-    fn display(&self) {
+    pub fn display(&self) {
         let avg_temp = self.temp_sum / self.num_readings as f32;
-        println!("[{}] Avg/Max/Min temperature: {:.1}/{:.1}/{:.1}",
-                 self.name, avg_temp, self.max_temp, self.min_temp);
+        say(&self.console, format_args!("[{}] Avg/Max/Min temperature: {:.1}/{:.1}/{:.1}",
+                                        self.name, avg_temp, self.max_temp, self.min_temp));
     }
 }
 
-impl Observer for StatisticsDisplay {
-    fn update(&mut self, temperature: f32, _humidity: f32, _pressure: f32) {
+impl Observer<WeatherEvent> for StatisticsDisplay {
+    fn update(&mut self, event: &WeatherEvent) {
+        let temperature = event.temperature;
         self.temp_sum += temperature;
         self.num_readings += 1;
 
@@ -204,41 +310,47 @@ impl Observer for StatisticsDisplay {
     }
 }
 
-/// ForecastDisplay implements the Observer trait
-struct ForecastDisplay {
+/// ForecastDisplay observes `WeatherEvent`s
+pub struct ForecastDisplay {
     name: String,
+    console: Console,
     current_pressure: f32,
     last_pressure: f32,
 }
 
 impl ForecastDisplay {
-    /// Create a new ForecastDisplay instance
-    fn new(name: &str) -> Self {
+    /// Create a new ForecastDisplay instance that prints to stdout
+    pub fn new(name: &str) -> Self {
+        Self::with_console(name, stdout_console())
+    }
+
+    /// Create a new ForecastDisplay instance that prints to `console`
+    pub fn with_console(name: &str, console: Console) -> Self {
         ForecastDisplay {
             name: name.to_string(),
+            console,
             current_pressure: 29.92, // Default starting pressure
             last_pressure: 0.0,
         }
     }
 
     /// Display the forecast
-    fn display(&self) {
-        print!("[{}] Forecast: ", self.name);
-
-        if self.current_pressure > self.last_pressure {
-            println!("Improving weather on the way!");
+    pub fn display(&self) {
+        let forecast = if self.current_pressure > self.last_pressure {
+            "Improving weather on the way!"
         } else if self.current_pressure == self.last_pressure {
-            println!("More of the same");
+            "More of the same"
         } else {
-            println!("Watch out for cooler, rainy weather");
-        }
+            "Watch out for cooler, rainy weather"
+        };
+        say(&self.console, format_args!("[{}] Forecast: {}", self.name, forecast));
     }
 }
 
-impl Observer for ForecastDisplay {
-    fn update(&mut self, _temperature: f32, _humidity: f32, pressure: f32) {
+impl Observer<WeatherEvent> for ForecastDisplay {
+    fn update(&mut self, event: &WeatherEvent) {
         self.last_pressure = self.current_pressure;
-        self.current_pressure = pressure;
+        self.current_pressure = event.pressure;
         self.display();
     }
 
@@ -247,23 +359,30 @@ impl Observer for ForecastDisplay {
     }
 }
 
-/// HeatIndexDisplay implements the Observer trait
-struct HeatIndexDisplay {
+/// HeatIndexDisplay observes `WeatherEvent`s
+pub struct HeatIndexDisplay {
     name: String,
+    console: Console,
     heat_index: f32,
 }
 
 impl HeatIndexDisplay {
-    /// Create a new HeatIndexDisplay instance
-    fn new(name: &str) -> Self {
+    /// Create a new HeatIndexDisplay instance that prints to stdout
+    pub fn new(name: &str) -> Self {
+        Self::with_console(name, stdout_console())
+    }
+
+    /// Create a new HeatIndexDisplay instance that prints to `console`
+    pub fn with_console(name: &str, console: Console) -> Self {
         HeatIndexDisplay {
             name: name.to_string(),
+            console,
             heat_index: 0.0,
         }
     }
 
     /// Compute the heat index based on temperature and humidity
-    fn compute_heat_index(t: f32, rh: f32) -> f32 {
+    pub fn compute_heat_index(t: f32, rh: f32) -> f32 {
         // This is a simplified formula for heat index
         (16.923 + (0.185212 * t) + (5.37941 * rh) - (0.100254 * t * rh) +
          (0.00941695 * (t * t)) + (0.00728898 * (rh * rh)) +
@@ -276,14 +395,14 @@ impl HeatIndexDisplay {
     }
 
     /// Display the heat index
-    fn display(&self) {
-        println!("[{}] Heat index: {:.1}", self.name, self.heat_index);
+    pub fn display(&self) {
+        say(&self.console, format_args!("[{}] Heat index: {:.1}", self.name, self.heat_index));
     }
 }
 
-impl Observer for HeatIndexDisplay {
-    fn update(&mut self, temperature: f32, humidity: f32, _pressure: f32) {
-        self.heat_index = Self::compute_heat_index(temperature, humidity);
+impl Observer<WeatherEvent> for HeatIndexDisplay {
+    fn update(&mut self, event: &WeatherEvent) {
+        self.heat_index = Self::compute_heat_index(event.temperature, event.humidity);
         self.display();
     }
 
@@ -292,18 +411,159 @@ impl Observer for HeatIndexDisplay {
     }
 }
 
+// ========== Stock Ticker Implementation ==========
+
+/// A price move, published by `StockTicker`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceChange {
+    pub symbol: String,
+    pub previous: Option<f64>,
+    pub price: f64,
+}
+
+/// A second subject on the same traits, this time publishing `PriceChange`s
+pub struct StockTicker {
+    observers: Observers<PriceChange>,
+    prices: BTreeMap<String, f64>,
+    last_change: Option<PriceChange>,
+}
+
+impl StockTicker {
+    /// Create a new StockTicker instance that reports to stdout
+    pub fn new() -> Self {
+        Self::with_console(stdout_console())
+    }
+
+    /// Create a new StockTicker instance that reports to `console`
+    pub fn with_console(console: Console) -> Self {
+        StockTicker {
+            observers: Observers::new(console),
+            prices: BTreeMap::new(),
+            last_change: None,
+        }
+    }
+
+    /// The latest price of `symbol`, if it has traded
+    pub fn price(&self, symbol: &str) -> Option<f64> {
+        self.prices.get(symbol).copied()
+    }
+
+    /// Record a trade and notify observers of the move
+    pub fn set_price(&mut self, symbol: &str, price: f64) {
+        let previous = self.prices.insert(symbol.to_string(), price);
+        self.last_change = Some(PriceChange { symbol: symbol.to_string(), previous, price });
+        self.notify_observers();
+    }
+}
+
+impl Default for StockTicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Subject<PriceChange> for StockTicker {
+    fn register_observer(&mut self, observer: Rc<RefCell<dyn Observer<PriceChange>>>) {
+        self.observers.register(observer);
+    }
+
+    fn remove_observer(&mut self, observer: &Rc<RefCell<dyn Observer<PriceChange>>>) {
+        self.observers.remove(observer);
+    }
+
+    fn notify_observers(&self) {
+        if let Some(change) = &self.last_change {
+            self.observers.notify(change);
+        }
+    }
+}
+
+/// TickerTapeDisplay prints every `PriceChange`
+pub struct TickerTapeDisplay {
+    name: String,
+    console: Console,
+}
+
+impl TickerTapeDisplay {
+    /// Create a new TickerTapeDisplay instance that prints to `console`
+    pub fn with_console(name: &str, console: Console) -> Self {
+        TickerTapeDisplay { name: name.to_string(), console }
+    }
+}
+
+impl Observer<PriceChange> for TickerTapeDisplay {
+    fn update(&mut self, change: &PriceChange) {
+        match change.previous {
+            Some(previous) => say(&self.console, format_args!("[{}] {} {:.2} ({:+.1}%)", self.name, change.symbol,
+                                                              change.price, (change.price - previous) / previous * 100.0)),
+            None => say(&self.console, format_args!("[{}] {} {:.2} (opening)", self.name, change.symbol, change.price)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// PriceAlertDisplay speaks up when one symbol rises through a threshold
+pub struct PriceAlertDisplay {
+    name: String,
+    console: Console,
+    symbol: String,
+    threshold: f64,
+    above: bool,
+    alerts: u32,
+}
+
+impl PriceAlertDisplay {
+    /// Create a new PriceAlertDisplay instance that prints to `console`
+    pub fn with_console(name: &str, symbol: &str, threshold: f64, console: Console) -> Self {
+        PriceAlertDisplay {
+            name: name.to_string(),
+            console,
+            symbol: symbol.to_string(),
+            threshold,
+            above: false,
+            alerts: 0,
+        }
+    }
+
+    /// How many times the threshold has been crossed upwards
+    pub fn alerts(&self) -> u32 {
+        self.alerts
+    }
+}
+
+impl Observer<PriceChange> for PriceAlertDisplay {
+    fn update(&mut self, change: &PriceChange) {
+        if change.symbol != self.symbol {
+            return;
+        }
+        let above = change.price >= self.threshold;
+        if above && !self.above {
+            self.alerts += 1;
+            say(&self.console, format_args!("[{}] {} crossed {:.2}, now {:.2}", self.name, self.symbol, self.threshold, change.price));
+        }
+        self.above = above;
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 // ========== Demo Code ==========
 
-/// Run the weather station demo
-fn run_weather_station() {
+/// Run the weather station demo, with the station and every display sharing `console`
+fn run_weather_station(console: &Console) {
     // Create the WeatherData subject
-    let mut weather_data = WeatherData::new();
+    let mut weather_data = WeatherData::with_console(Rc::clone(console));
 
     // Create display devices (observers)
-    let current_display = Rc::new(RefCell::new(CurrentConditionsDisplay::new("Current Display")));
-    let stats_display = Rc::new(RefCell::new(StatisticsDisplay::new("Statistics Display")));
-    let forecast_display = Rc::new(RefCell::new(ForecastDisplay::new("Forecast Display")));
-    let heat_index_display = Rc::new(RefCell::new(HeatIndexDisplay::new("Heat Index Display")));
+    let current_display: Rc<RefCell<dyn Observer<WeatherEvent>>> = Rc::new(RefCell::new(CurrentConditionsDisplay::with_console("Current Display", Rc::clone(console))));
+    let stats_display: Rc<RefCell<dyn Observer<WeatherEvent>>> = Rc::new(RefCell::new(StatisticsDisplay::with_console("Statistics Display", Rc::clone(console))));
+    let forecast_display: Rc<RefCell<dyn Observer<WeatherEvent>>> = Rc::new(RefCell::new(ForecastDisplay::with_console("Forecast Display", Rc::clone(console))));
+    let heat_index_display: Rc<RefCell<dyn Observer<WeatherEvent>>> = Rc::new(RefCell::new(HeatIndexDisplay::with_console("Heat Index Display", Rc::clone(console))));
 
     // Register observers
     weather_data.register_observer(Rc::clone(&current_display));
@@ -311,28 +571,295 @@ fn run_weather_station() {
     weather_data.register_observer(Rc::clone(&forecast_display));
     weather_data.register_observer(Rc::clone(&heat_index_display));
 
-    println!("\n=== First Weather Update ===");
+    say(console, format_args!("\n=== First Weather Update ==="));
     // Simulate new weather measurements
     weather_data.set_measurements(80.0, 65.0, 30.4);
 
-    println!("\n=== Second Weather Update ===");
+    say(console, format_args!("\n=== Second Weather Update ==="));
     // Simulate new weather measurements
     weather_data.set_measurements(82.0, 70.0, 29.2);
 
-    println!("\n=== Third Weather Update ===");
+    say(console, format_args!("\n=== Third Weather Update ==="));
     // Simulate new weather measurements
     weather_data.set_measurements(78.0, 90.0, 29.2);
 
-    println!("\n=== Removing an Observer ===");
+    say(console, format_args!("\n=== Removing an Observer ==="));
     // Remove an observer
     weather_data.remove_observer(&forecast_display);
 
-    println!("\n=== Fourth Weather Update ===");
+    say(console, format_args!("\n=== Fourth Weather Update ==="));
     // One more measurement after removing an observer
     weather_data.set_measurements(75.0, 60.0, 30.1);
+
+    say(console, format_args!("\n=== Closure Subscribers ==="));
+    // No Observer impl needed: a closure captures whatever state it needs
+    let logger = {
+        let console = Rc::clone(console);
+        weather_data.subscribe(move |event| say(&console, format_args!("[Logger] {:?}", event)))
+    };
+    let warmest = Rc::new(Cell::new(f32::MIN));
+    {
+        let warmest = Rc::clone(&warmest);
+        weather_data.subscribe(move |event| warmest.set(warmest.get().max(event.temperature)));
+    }
+    weather_data.set_measurements(77.0, 62.0, 30.0);
+    say(console, format_args!("Unsubscribing the logger: {}", weather_data.unsubscribe(logger)));
+    say(console, format_args!("Unsubscribing it again: {}", weather_data.unsubscribe(logger)));
+    weather_data.set_measurements(79.5, 58.0, 30.2);
+    say(console, format_args!("Warmest reading seen by the closure: {:.1}°F", warmest.get()));
+}
+
+/// Run the stock ticker demo: the same `Subject`/`Observer` traits over a different event
+fn run_stock_ticker(console: &Console) {
+    let mut ticker = StockTicker::with_console(Rc::clone(console));
+
+    let tape: Rc<RefCell<dyn Observer<PriceChange>>> = Rc::new(RefCell::new(TickerTapeDisplay::with_console("Ticker Tape", Rc::clone(console))));
+    let alert: Rc<RefCell<dyn Observer<PriceChange>>> = Rc::new(RefCell::new(PriceAlertDisplay::with_console("ACME Alert", "ACME", 120.0, Rc::clone(console))));
+    ticker.register_observer(Rc::clone(&tape));
+    ticker.register_observer(Rc::clone(&alert));
+
+    say(console, format_args!("\n=== Trading ==="));
+    ticker.set_price("ACME", 112.50);
+    ticker.set_price("GLOBEX", 48.00);
+    ticker.set_price("ACME", 121.25);
+    ticker.set_price("ACME", 119.80);
+    ticker.set_price("ACME", 124.00);
+
+    say(console, format_args!("\n=== Removing the Ticker Tape ==="));
+    ticker.remove_observer(&tape);
+    ticker.set_price("GLOBEX", 50.40);
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ObserverPatternDemo;
+
+impl Demo for ObserverPatternDemo {
+    fn name(&self) -> &'static str {
+        "observer_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Weather station and stock ticker notifying observers through generic Subject/Observer traits"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        // The observers need a 'static console, so buffer the run and hand it over at the end
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        let console: Console = buffer.clone();
+        run_weather_station(&console);
+        say(&console, format_args!("\n=== Stock Ticker ==="));
+        run_stock_ticker(&console);
+        out.write_all(&buffer.take())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ObserverPatternDemo);
 }
 
-fn main() {
-    // Run the demo
-    run_weather_station();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every event it is handed.
+    struct Recorder<E> {
+        name: String,
+        events: Vec<E>,
+    }
+
+    impl<E> Recorder<E> {
+        fn shared(name: &str) -> Rc<RefCell<Recorder<E>>> {
+            Rc::new(RefCell::new(Recorder { name: name.to_string(), events: Vec::new() }))
+        }
+    }
+
+    impl<E: Clone> Observer<E> for Recorder<E> {
+        fn update(&mut self, event: &E) {
+            self.events.push(event.clone());
+        }
+
+        fn name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    fn quiet_console() -> Console {
+        Rc::new(RefCell::new(io::sink()))
+    }
+
+    #[test]
+    fn every_registered_observer_sees_each_update() {
+        let mut station = WeatherData::new();
+        let first = Recorder::<WeatherEvent>::shared("first");
+        let second = Recorder::<WeatherEvent>::shared("second");
+        station.register_observer(first.clone());
+        station.register_observer(second.clone());
+
+        station.set_measurements(80.0, 65.0, 30.4);
+        station.set_measurements(82.0, 70.0, 29.2);
+
+        let expected = [WeatherEvent::new(80.0, 65.0, 30.4), WeatherEvent::new(82.0, 70.0, 29.2)];
+        assert_eq!(first.borrow().events, expected);
+        assert_eq!(second.borrow().events, expected);
+    }
+
+    #[test]
+    fn removed_observer_stops_receiving() {
+        let mut station = WeatherData::new();
+        let kept = Recorder::<WeatherEvent>::shared("kept");
+        let removed = Recorder::<WeatherEvent>::shared("removed");
+        let removed_dyn: Rc<RefCell<dyn Observer<WeatherEvent>>> = removed.clone();
+        station.register_observer(kept.clone());
+        station.register_observer(removed_dyn.clone());
+
+        station.set_measurements(70.0, 50.0, 30.0);
+        station.remove_observer(&removed_dyn);
+        station.set_measurements(71.0, 51.0, 30.1);
+
+        assert_eq!(kept.borrow().events.len(), 2);
+        assert_eq!(removed.borrow().events, [WeatherEvent::new(70.0, 50.0, 30.0)]);
+    }
+
+    #[test]
+    fn dropped_observer_is_skipped() {
+        let mut station = WeatherData::new();
+        let survivor = Recorder::<WeatherEvent>::shared("survivor");
+        station.register_observer(survivor.clone());
+        station.register_observer(Recorder::<WeatherEvent>::shared("dropped"));
+
+        // The subject only holds a Weak, so the second recorder is already gone.
+        station.set_measurements(60.0, 40.0, 29.9);
+        assert_eq!(survivor.borrow().events.len(), 1);
+    }
+
+    #[test]
+    fn statistics_display_tracks_min_max_and_sum() {
+        let mut stats = StatisticsDisplay::new("stats");
+        for t in [80.0, 82.0, 78.0] {
+            stats.update(&WeatherEvent::new(t, 0.0, 0.0));
+        }
+        assert_eq!(stats.max_temp, 82.0);
+        assert_eq!(stats.min_temp, 78.0);
+        assert_eq!(stats.temp_sum / stats.num_readings as f32, 80.0);
+    }
+
+    #[test]
+    fn forecast_compares_against_previous_pressure() {
+        let mut forecast = ForecastDisplay::new("forecast");
+        forecast.update(&WeatherEvent::new(0.0, 0.0, 30.4));
+        assert!(forecast.current_pressure > forecast.last_pressure);
+        forecast.update(&WeatherEvent::new(0.0, 0.0, 29.2));
+        assert_eq!(forecast.last_pressure, 30.4);
+        assert!(forecast.current_pressure < forecast.last_pressure);
+    }
+
+    #[test]
+    fn heat_index_matches_reference_values() {
+        let index = HeatIndexDisplay::compute_heat_index(80.0, 65.0);
+        assert!((index - 82.96).abs() < 0.1, "{}", index);
+        let index = HeatIndexDisplay::compute_heat_index(75.0, 60.0);
+        assert!((index - 77.7).abs() < 0.1, "{}", index);
+    }
+
+    #[test]
+    fn stock_ticker_reuses_the_same_traits() {
+        let mut ticker = StockTicker::with_console(quiet_console());
+        let recorder = Recorder::<PriceChange>::shared("recorder");
+        ticker.register_observer(recorder.clone());
+
+        ticker.set_price("ACME", 100.0);
+        ticker.set_price("ACME", 105.0);
+
+        let change = |previous, price| PriceChange { symbol: "ACME".to_string(), previous, price };
+        assert_eq!(recorder.borrow().events, [change(None, 100.0), change(Some(100.0), 105.0)]);
+        assert_eq!(ticker.price("ACME"), Some(105.0));
+    }
+
+    #[test]
+    fn price_alert_fires_once_per_upward_crossing() {
+        let alert = Rc::new(RefCell::new(PriceAlertDisplay::with_console("alert", "ACME", 120.0, quiet_console())));
+        let mut ticker = StockTicker::with_console(quiet_console());
+        ticker.register_observer(alert.clone());
+        for (symbol, price) in [("ACME", 110.0), ("ACME", 121.0), ("ACME", 125.0), ("GLOBEX", 130.0), ("ACME", 119.0), ("ACME", 120.0)] {
+            ticker.set_price(symbol, price);
+        }
+        assert_eq!(alert.borrow().alerts(), 2);
+    }
+
+    #[test]
+    fn closure_subscribers_stop_firing_after_unsubscribe() {
+        let mut station = WeatherData::with_console(quiet_console());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let kept = Rc::new(Cell::new(0));
+        let id = {
+            let seen = Rc::clone(&seen);
+            station.subscribe(move |event| seen.borrow_mut().push(event.temperature))
+        };
+        {
+            let kept = Rc::clone(&kept);
+            station.subscribe(move |_| kept.set(kept.get() + 1));
+        }
+
+        station.set_measurements(70.0, 50.0, 30.0);
+        assert!(station.unsubscribe(id));
+        station.set_measurements(71.0, 51.0, 30.1);
+
+        assert_eq!(*seen.borrow(), [70.0]);
+        assert_eq!(kept.get(), 2);
+    }
+
+    #[test]
+    fn unsubscribe_ids_are_never_reused() {
+        let mut station = WeatherData::with_console(quiet_console());
+        let first = station.subscribe(|_| {});
+        assert!(station.unsubscribe(first));
+        assert!(!station.unsubscribe(first));
+
+        let calls = Rc::new(Cell::new(0));
+        let second = {
+            let calls = Rc::clone(&calls);
+            station.subscribe(move |_| calls.set(calls.get() + 1))
+        };
+        assert_ne!(first, second);
+        assert!(!station.unsubscribe(first));
+        station.set_measurements(60.0, 40.0, 29.9);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn closures_run_after_trait_observers_and_keep_their_state() {
+        /// Notes its name in a log it shares with the closure
+        struct Logger(Rc<RefCell<Vec<String>>>);
+
+        impl Observer<WeatherEvent> for Logger {
+            fn update(&mut self, _: &WeatherEvent) {
+                self.0.borrow_mut().push("recorder".to_string());
+            }
+
+            fn name(&self) -> &str {
+                "recorder"
+            }
+        }
+
+        let mut station = WeatherData::with_console(quiet_console());
+        let log = Rc::new(RefCell::new(Vec::new()));
+        {
+            let log = Rc::clone(&log);
+            let mut count = 0;
+            station.subscribe(move |_| {
+                count += 1;
+                log.borrow_mut().push(format!("closure #{}", count));
+            });
+        }
+        // Registered after the closure, yet still notified before it
+        let recorder = Rc::new(RefCell::new(Logger(Rc::clone(&log))));
+        station.register_observer(recorder.clone());
+        station.set_measurements(80.0, 65.0, 30.4);
+        station.set_measurements(81.0, 66.0, 30.3);
+        assert_eq!(*log.borrow(), ["recorder", "closure #1", "recorder", "closure #2"]);
+    }
 }
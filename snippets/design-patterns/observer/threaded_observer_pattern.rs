@@ -0,0 +1,394 @@
+//! Thread-Safe Observer Pattern Implementation in Rust
+//!
+//! The weather station in `observer_pattern` is single-threaded: observers live in
+//! `Rc<RefCell<_>>` and run on the caller's stack. This variant keeps the same
+//! `Observer<E>` trait and `WeatherEvent`, but lets any number of producer threads publish
+//! and runs every observer on a worker thread of its own.
+//!
+//! - Observers are shared as `Arc<Mutex<dyn Observer<E> + Send>>`, so the code that
+//!   registered one can still lock it and read its state
+//! - Each registration spawns a worker that owns the receiving end of an `mpsc` channel
+//!   and calls `update` for every event it receives
+//! - `publish` clones the event into each channel while holding the subscriber list's
+//!   lock, so every observer sees the same events in the same order, whichever threads
+//!   they came from
+//! - `remove` and `Drop` close the channel and join the worker, so events already
+//!   published are always delivered before either returns
+
+use crate::observer_pattern::{Observer, WeatherEvent};
+use demo::Demo;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+// ========== Threaded Subject ==========
+
+/// An observer that can be handed to a worker thread
+pub type SharedObserver<E> = Arc<Mutex<dyn Observer<E> + Send>>;
+
+/// Identifies a registration, for `ThreadedSubject::remove`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+struct Subscriber<E> {
+    id: ObserverId,
+    name: String,
+    sender: Sender<E>,
+    worker: JoinHandle<()>,
+}
+
+impl<E> Subscriber<E> {
+    /// Closes the channel, then waits for the worker to drain it
+    fn close(self) {
+        drop(self.sender);
+        // A panicking observer has already reported itself on stderr; the others carry on
+        let _ = self.worker.join();
+    }
+}
+
+struct Subscribers<E> {
+    next_id: u64,
+    list: Vec<Subscriber<E>>,
+}
+
+/// A subject that can be shared between threads (for example in an `Arc`) and published to
+/// from any of them
+pub struct ThreadedSubject<E> {
+    subscribers: Mutex<Subscribers<E>>,
+}
+
+impl<E: Clone + Send + 'static> ThreadedSubject<E> {
+    pub fn new() -> Self {
+        ThreadedSubject { subscribers: Mutex::new(Subscribers { next_id: 0, list: Vec::new() }) }
+    }
+
+    /// Starts a worker thread that feeds `observer` every event published from now on
+    pub fn register(&self, observer: SharedObserver<E>) -> ObserverId {
+        let name = observer.lock().unwrap().name().to_string();
+        let (sender, receiver) = mpsc::channel::<E>();
+        let worker = thread::Builder::new()
+            .name(format!("observer-{}", name))
+            .spawn(move || {
+                for event in receiver {
+                    observer.lock().unwrap().update(&event);
+                }
+            })
+            .expect("failed to spawn an observer worker");
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let id = ObserverId(subscribers.next_id);
+        subscribers.next_id += 1;
+        subscribers.list.push(Subscriber { id, name, sender, worker });
+        id
+    }
+
+    /// Stops notifying `id` once it has processed everything already published to it.
+    /// Returns false if `id` was not registered.
+    pub fn remove(&self, id: ObserverId) -> bool {
+        let removed = {
+            let mut subscribers = self.subscribers.lock().unwrap();
+            let position = subscribers.list.iter().position(|subscriber| subscriber.id == id);
+            position.map(|index| subscribers.list.remove(index))
+        };
+        // Join outside the lock, so publishers are not held up by a slow observer
+        match removed {
+            Some(subscriber) => {
+                subscriber.close();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Queues `event` for every observer and returns how many accepted it
+    pub fn publish(&self, event: E) -> usize {
+        let subscribers = self.subscribers.lock().unwrap();
+        // A send only fails if that observer's worker has panicked
+        subscribers.list.iter().filter(|subscriber| subscriber.sender.send(event.clone()).is_ok()).count()
+    }
+
+    /// Names of the registered observers, in registration order
+    pub fn observer_names(&self) -> Vec<String> {
+        self.subscribers.lock().unwrap().list.iter().map(|subscriber| subscriber.name.clone()).collect()
+    }
+}
+
+impl<E: Clone + Send + 'static> Default for ThreadedSubject<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Drop for ThreadedSubject<E> {
+    fn drop(&mut self) {
+        let subscribers = self.subscribers.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for subscriber in subscribers.list.drain(..) {
+            subscriber.close();
+        }
+    }
+}
+
+// ========== Thread-Safe Observers ==========
+
+/// Keeps running statistics; the result does not depend on the order readings arrive in
+#[derive(Debug, Default)]
+pub struct StatisticsObserver {
+    name: String,
+    readings: u32,
+    min_temp: Option<f32>,
+    max_temp: Option<f32>,
+    temp_sum: f64,
+}
+
+impl StatisticsObserver {
+    pub fn new(name: &str) -> Self {
+        StatisticsObserver { name: name.to_string(), ..Self::default() }
+    }
+
+    pub fn readings(&self) -> u32 {
+        self.readings
+    }
+
+    /// Average, max and min temperature, if there have been any readings
+    pub fn summary(&self) -> Option<(f64, f32, f32)> {
+        let (min, max) = (self.min_temp?, self.max_temp?);
+        Some((self.temp_sum / f64::from(self.readings), max, min))
+    }
+}
+
+impl Observer<WeatherEvent> for StatisticsObserver {
+    fn update(&mut self, event: &WeatherEvent) {
+        self.readings += 1;
+        self.temp_sum += f64::from(event.temperature);
+        self.min_temp = Some(self.min_temp.map_or(event.temperature, |min| min.min(event.temperature)));
+        self.max_temp = Some(self.max_temp.map_or(event.temperature, |max| max.max(event.temperature)));
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Counts readings with humidity at or above a threshold
+#[derive(Debug)]
+pub struct HumidityAlarm {
+    name: String,
+    threshold: f32,
+    alarms: u32,
+}
+
+impl HumidityAlarm {
+    pub fn new(name: &str, threshold: f32) -> Self {
+        HumidityAlarm { name: name.to_string(), threshold, alarms: 0 }
+    }
+
+    pub fn alarms(&self) -> u32 {
+        self.alarms
+    }
+}
+
+impl Observer<WeatherEvent> for HumidityAlarm {
+    fn update(&mut self, event: &WeatherEvent) {
+        if event.humidity >= self.threshold {
+            self.alarms += 1;
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Temperature, humidity and pressure
+type Reading = (f32, f32, f32);
+
+/// Readings from three stations (north, harbour, airport), each published from its own
+/// producer thread
+const STATIONS: [[Reading; 4]; 3] = [
+    [(61.0, 55.0, 30.1), (62.5, 58.0, 30.0), (60.0, 71.0, 29.8), (59.5, 80.0, 29.6)],
+    [(66.0, 82.0, 29.9), (67.0, 85.0, 29.9), (68.5, 79.0, 30.0), (70.0, 74.0, 30.1)],
+    [(72.0, 40.0, 30.2), (74.5, 38.0, 30.2), (75.0, 42.0, 30.1), (73.0, 45.0, 30.0)],
+];
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ThreadedObserverPatternDemo;
+
+impl Demo for ThreadedObserverPatternDemo {
+    fn name(&self) -> &'static str {
+        "threaded_observer_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Observers on worker threads fed through mpsc channels by several producer threads"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Observers on Worker Threads =====")?;
+        let subject = ThreadedSubject::<WeatherEvent>::new();
+        let stats = Arc::new(Mutex::new(StatisticsObserver::new("Statistics")));
+        let alarm = Arc::new(Mutex::new(HumidityAlarm::new("Humidity Alarm", 75.0)));
+        subject.register(stats.clone());
+        let alarm_id = subject.register(alarm.clone());
+        writeln!(out, "Registered: {}", subject.observer_names().join(", "))?;
+
+        let delivered: usize = thread::scope(|scope| {
+            let producers: Vec<_> = STATIONS
+                .iter()
+                .map(|readings| {
+                    let subject = &subject;
+                    scope.spawn(move || readings.iter().map(|&(t, h, p)| subject.publish(WeatherEvent::new(t, h, p))).sum::<usize>())
+                })
+                .collect();
+            producers.into_iter().map(|producer| producer.join().unwrap()).sum()
+        });
+        writeln!(out, "{} producer threads published {} readings, {} deliveries queued", STATIONS.len(), STATIONS.len() * 4, delivered)?;
+
+        writeln!(out, "\n===== Removing an Observer =====")?;
+        subject.remove(alarm_id);
+        writeln!(out, "Humidity alarm drained before removal: {} readings at or above 75%", alarm.lock().unwrap().alarms())?;
+        subject.publish(WeatherEvent::new(64.0, 95.0, 29.5));
+        writeln!(out, "After one more humid reading the removed alarm still counts {}", alarm.lock().unwrap().alarms())?;
+        writeln!(out, "Registered: {}", subject.observer_names().join(", "))?;
+
+        writeln!(out, "\n===== Shutting Down =====")?;
+        drop(subject);
+        let stats = stats.lock().unwrap();
+        if let Some((avg, max, min)) = stats.summary() {
+            writeln!(out, "Statistics saw {} readings", stats.readings())?;
+            writeln!(out, "Avg/Max/Min temperature: {:.1}/{:.1}/{:.1}", avg, max, min)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ThreadedObserverPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every event it is handed, in arrival order.
+    struct Recorder {
+        events: Vec<u32>,
+    }
+
+    impl Observer<u32> for Recorder {
+        fn update(&mut self, event: &u32) {
+            self.events.push(*event);
+        }
+
+        fn name(&self) -> &str {
+            "recorder"
+        }
+    }
+
+    fn recorder() -> Arc<Mutex<Recorder>> {
+        Arc::new(Mutex::new(Recorder { events: Vec::new() }))
+    }
+
+    #[test]
+    fn updates_from_many_producer_threads_all_arrive_in_one_order() {
+        let subject = Arc::new(ThreadedSubject::<u32>::new());
+        let (first, second) = (recorder(), recorder());
+        subject.register(first.clone());
+        subject.register(second.clone());
+
+        let producers: Vec<_> = (0..4)
+            .map(|producer| {
+                let subject = Arc::clone(&subject);
+                thread::spawn(move || (0..250).for_each(|n| assert_eq!(subject.publish(producer * 1000 + n), 2)))
+            })
+            .collect();
+        producers.into_iter().for_each(|producer| producer.join().unwrap());
+        drop(subject);
+
+        let (first, second) = (first.lock().unwrap(), second.lock().unwrap());
+        assert_eq!(first.events.len(), 1000);
+        assert_eq!(first.events, second.events);
+        for producer in 0..4 {
+            let own: Vec<u32> = first.events.iter().copied().filter(|event| event / 1000 == producer).collect();
+            assert_eq!(own, (0..250).map(|n| producer * 1000 + n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn remove_drains_pending_events_then_stops_delivery() {
+        let subject = ThreadedSubject::new();
+        let removed = recorder();
+        let id = subject.register(removed.clone());
+        (1..=3).for_each(|n| {
+            subject.publish(n);
+        });
+        assert!(subject.remove(id));
+        assert_eq!(removed.lock().unwrap().events, [1, 2, 3]);
+        assert_eq!(subject.publish(4), 0);
+        assert!(!subject.remove(id));
+        assert_eq!(removed.lock().unwrap().events, [1, 2, 3]);
+    }
+
+    #[test]
+    fn a_panicking_observer_does_not_stop_the_others() {
+        struct Fragile;
+        impl Observer<u32> for Fragile {
+            fn update(&mut self, event: &u32) {
+                assert_ne!(*event, 2, "fragile observer gave up");
+            }
+
+            fn name(&self) -> &str {
+                "fragile"
+            }
+        }
+
+        let subject = ThreadedSubject::new();
+        let healthy = recorder();
+        let fragile = subject.register(Arc::new(Mutex::new(Fragile)));
+        subject.register(healthy.clone());
+        (1..=3).for_each(|n| {
+            subject.publish(n);
+        });
+        assert!(subject.remove(fragile));
+        assert_eq!(subject.publish(4), 1);
+        drop(subject);
+        assert_eq!(healthy.lock().unwrap().events, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn weather_observers_summarise_readings_from_every_station() {
+        let subject = ThreadedSubject::new();
+        let stats = Arc::new(Mutex::new(StatisticsObserver::new("stats")));
+        let alarm = Arc::new(Mutex::new(HumidityAlarm::new("alarm", 75.0)));
+        subject.register(stats.clone());
+        subject.register(alarm.clone());
+        thread::scope(|scope| {
+            for readings in &STATIONS {
+                let subject = &subject;
+                scope.spawn(move || readings.iter().for_each(|&(t, h, p)| {
+                    subject.publish(WeatherEvent::new(t, h, p));
+                }));
+            }
+        });
+        drop(subject);
+
+        let stats = stats.lock().unwrap();
+        assert_eq!(stats.readings(), 12);
+        let (avg, max, min) = stats.summary().unwrap();
+        assert_eq!((max, min), (75.0, 59.5));
+        assert!((avg - 809.0 / 12.0).abs() < 1e-9, "{}", avg);
+        assert_eq!(alarm.lock().unwrap().alarms(), 4);
+    }
+
+    #[test]
+    fn subject_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ThreadedSubject<WeatherEvent>>();
+    }
+}
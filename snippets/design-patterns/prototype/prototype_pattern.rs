@@ -0,0 +1,475 @@
+//! Prototype Pattern Implementation in Rust
+//!
+//! The Prototype Pattern is a creational design pattern that makes new objects by copying
+//! an existing, configured instance instead of building them from scratch, so callers
+//! need neither the concrete type nor its constructor arguments.
+//!
+//! In Rust, copying is `Clone`, and for a concrete type that is the whole pattern. The
+//! interesting case is a prototype behind a trait object: `Clone::clone` returns `Self`,
+//! whose size isn't known for `dyn Shape`, so `Clone` can't be a supertrait of an
+//! object-safe trait. The usual workaround, written out here:
+//! - a helper trait, `ShapeClone`, with `clone_box(&self) -> Box<dyn Shape>`, which is
+//!   object safe because it returns a box
+//! - a blanket impl of it for every `Shape + Clone`, so no shape writes it by hand
+//! - `impl Clone for Box<dyn Shape>` calling `clone_box`, which lets anything holding
+//!   boxed shapes, like a `Vec` in a group, simply `#[derive(Clone)]`
+//!
+//! The `dyn-clone` crate packages the same trick; with the `dyn-clone` feature,
+//! `with_dyn_clone` shows it. `PrototypeRegistry` keeps named prototypes and spawns
+//! customized copies of them.
+
+use demo::Demo;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// ========== Prototype Interface ==========
+
+pub trait Shape: ShapeClone + fmt::Debug {
+    fn kind(&self) -> &'static str;
+    fn area(&self) -> f64;
+    fn position(&self) -> (f64, f64);
+    fn color(&self) -> &str;
+    fn set_color(&mut self, color: &str);
+    fn move_by(&mut self, dx: f64, dy: f64);
+}
+
+/// The hand-rolled `dyn Clone`
+pub trait ShapeClone {
+    fn clone_box(&self) -> Box<dyn Shape>;
+}
+
+impl<T: Shape + Clone + 'static> ShapeClone for T {
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Shape> {
+    fn clone(&self) -> Self {
+        // `Box<dyn Shape>` is not itself a `Shape`, so this resolves to the boxed value's
+        // `clone_box` rather than recursing into this impl
+        self.clone_box()
+    }
+}
+
+// ========== Concrete Prototypes ==========
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
+    pub center: (f64, f64),
+    pub radius: f64,
+    pub color: String,
+}
+
+impl Shape for Circle {
+    fn kind(&self) -> &'static str {
+        "circle"
+    }
+
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn position(&self) -> (f64, f64) {
+        self.center
+    }
+
+    fn color(&self) -> &str {
+        &self.color
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.center = (self.center.0 + dx, self.center.1 + dy);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rectangle {
+    pub origin: (f64, f64),
+    pub width: f64,
+    pub height: f64,
+    pub color: String,
+}
+
+impl Shape for Rectangle {
+    fn kind(&self) -> &'static str {
+        "rectangle"
+    }
+
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn position(&self) -> (f64, f64) {
+        self.origin
+    }
+
+    fn color(&self) -> &str {
+        &self.color
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.origin = (self.origin.0 + dx, self.origin.1 + dy);
+    }
+}
+
+/// Pixels that are expensive to produce and never change, so copies share them: cloning
+/// an `Rc` is a pointer copy, which is why a prototype can be cheaper than a constructor
+#[derive(Debug, Clone)]
+pub struct Picture {
+    pub position: (f64, f64),
+    pub size: (usize, usize),
+    pub pixels: Rc<[u8]>,
+    pub tint: String,
+}
+
+impl Picture {
+    /// Renders a checkerboard, standing in for decoding an image file
+    pub fn checkerboard(size: usize) -> Self {
+        let pixels: Rc<[u8]> = (0..size * size).map(|i| if (i / size + i % size) % 2 == 0 { 255 } else { 0 }).collect();
+        Picture { position: (0.0, 0.0), size: (size, size), pixels, tint: "none".to_string() }
+    }
+}
+
+impl Shape for Picture {
+    fn kind(&self) -> &'static str {
+        "picture"
+    }
+
+    fn area(&self) -> f64 {
+        (self.size.0 * self.size.1) as f64
+    }
+
+    fn position(&self) -> (f64, f64) {
+        self.position
+    }
+
+    fn color(&self) -> &str {
+        &self.tint
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.tint = color.to_string();
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.position = (self.position.0 + dx, self.position.1 + dy);
+    }
+}
+
+/// A composite; `#[derive(Clone)]` works because `Box<dyn Shape>` is `Clone`, and it
+/// copies every child, so a cloned group is fully independent of the original
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub children: Vec<Box<dyn Shape>>,
+}
+
+impl Shape for Group {
+    fn kind(&self) -> &'static str {
+        "group"
+    }
+
+    fn area(&self) -> f64 {
+        self.children.iter().map(|child| child.area()).sum()
+    }
+
+    /// The first child's position, or the origin for an empty group
+    fn position(&self) -> (f64, f64) {
+        self.children.first().map_or((0.0, 0.0), |child| child.position())
+    }
+
+    /// The children's color when they agree, otherwise `mixed`
+    fn color(&self) -> &str {
+        let mut colors = self.children.iter().map(|child| child.color());
+        match colors.next() {
+            Some(first) if colors.all(|color| color == first) => first,
+            Some(_) => "mixed",
+            None => "none",
+        }
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.children.iter_mut().for_each(|child| child.set_color(color));
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.children.iter_mut().for_each(|child| child.move_by(dx, dy));
+    }
+}
+
+/// One line about any shape
+pub fn describe(shape: &dyn Shape) -> String {
+    let (x, y) = shape.position();
+    format!("{} at ({}, {}), {}, area {:.1}", shape.kind(), x, y, shape.color(), shape.area())
+}
+
+// ========== Prototype Registry ==========
+
+/// Named, preconfigured shapes to copy from
+#[derive(Default)]
+pub struct PrototypeRegistry {
+    prototypes: BTreeMap<String, Box<dyn Shape>>,
+}
+
+impl PrototypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces any prototype already registered under `name`
+    pub fn register(&mut self, name: &str, prototype: Box<dyn Shape>) {
+        self.prototypes.insert(name.to_string(), prototype);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.prototypes.keys().map(String::as_str)
+    }
+
+    pub fn prototype(&self, name: &str) -> Option<&dyn Shape> {
+        self.prototypes.get(name).map(|prototype| &**prototype)
+    }
+
+    /// A fresh copy of the prototype called `name`
+    pub fn spawn(&self, name: &str) -> Option<Box<dyn Shape>> {
+        self.prototypes.get(name).cloned()
+    }
+
+    /// A copy, adjusted by `customize` before it is handed out
+    pub fn spawn_with(&self, name: &str, customize: impl FnOnce(&mut dyn Shape)) -> Option<Box<dyn Shape>> {
+        let mut shape = self.spawn(name)?;
+        customize(&mut *shape);
+        Some(shape)
+    }
+}
+
+// ========== dyn-clone ==========
+
+/// The same pattern with `dyn_clone::DynClone` in place of `ShapeClone`
+#[cfg(feature = "dyn-clone")]
+pub mod with_dyn_clone {
+    use dyn_clone::DynClone;
+    use std::fmt;
+
+    pub trait Widget: DynClone + fmt::Debug {
+        fn label(&self) -> &str;
+        fn set_label(&mut self, label: &str);
+    }
+
+    // Writes `impl Clone for Box<dyn Widget>`, the impl written by hand for shapes above
+    dyn_clone::clone_trait_object!(Widget);
+
+    #[derive(Debug, Clone)]
+    pub struct Button {
+        pub label: String,
+        pub width: u32,
+    }
+
+    impl Widget for Button {
+        fn label(&self) -> &str {
+            &self.label
+        }
+
+        fn set_label(&mut self, label: &str) {
+            self.label = label.to_string();
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Slider {
+        pub label: String,
+        pub range: (i32, i32),
+    }
+
+    impl Widget for Slider {
+        fn label(&self) -> &str {
+            &self.label
+        }
+
+        fn set_label(&mut self, label: &str) {
+            self.label = label.to_string();
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+fn default_registry() -> PrototypeRegistry {
+    let mut registry = PrototypeRegistry::new();
+    registry.register("dot", Box::new(Circle { center: (0.0, 0.0), radius: 1.0, color: "black".to_string() }));
+    registry.register(
+        "button",
+        Box::new(Rectangle { origin: (0.0, 0.0), width: 8.0, height: 2.0, color: "grey".to_string() }),
+    );
+    registry.register("texture", Box::new(Picture::checkerboard(64)));
+    registry.register(
+        "badge",
+        Box::new(Group {
+            children: vec![
+                Box::new(Circle { center: (0.0, 0.0), radius: 3.0, color: "gold".to_string() }),
+                Box::new(Rectangle { origin: (-1.0, -4.0), width: 2.0, height: 3.0, color: "gold".to_string() }),
+            ],
+        }),
+    );
+    registry
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct PrototypePatternDemo;
+
+impl Demo for PrototypePatternDemo {
+    fn name(&self) -> &'static str {
+        "prototype_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "A prototype registry cloning Box<dyn Shape> through a hand-rolled clone_box"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Registered Prototypes =====")?;
+        let registry = default_registry();
+        for name in registry.names() {
+            let prototype = registry.prototype(name).expect("listed names are registered");
+            writeln!(out, "{:<8} {}", name, describe(prototype))?;
+        }
+
+        writeln!(out, "\n===== Spawning Customized Copies =====")?;
+        let red_dot = registry.spawn_with("dot", |shape| shape.set_color("red")).expect("dot is registered");
+        let row: Vec<Box<dyn Shape>> = (1..=3)
+            .filter_map(|i| registry.spawn_with("button", |shape| shape.move_by(10.0 * f64::from(i), 0.0)))
+            .collect();
+        let mut badge = registry.spawn("badge").expect("badge is registered");
+        badge.move_by(5.0, 5.0);
+        badge.set_color("silver");
+        for shape in std::iter::once(&red_dot).chain(&row).chain(std::iter::once(&badge)) {
+            writeln!(out, "{}", describe(&**shape))?;
+        }
+        writeln!(out, "The prototypes are untouched:")?;
+        for name in ["dot", "badge"] {
+            writeln!(out, "  {:<6} {}", name, describe(registry.prototype(name).expect("registered")))?;
+        }
+        writeln!(out, "unknown prototype: {:?}", registry.spawn("hexagon").map(|shape| shape.kind()))?;
+
+        writeln!(out, "\n===== Deep and Shared Parts =====")?;
+        let copies: Vec<Box<dyn Shape>> = (0..3).filter_map(|_| registry.spawn("texture")).collect();
+        let texture = Picture::checkerboard(4);
+        let shared = texture.clone();
+        writeln!(
+            out,
+            "{} textures spawned; a Picture and its clone share pixels: {} (Rc count {})",
+            copies.len(),
+            Rc::ptr_eq(&texture.pixels, &shared.pixels),
+            Rc::strong_count(&texture.pixels)
+        )?;
+        let row_copy = row.clone();
+        let same_areas = row.iter().zip(&row_copy).all(|(a, b)| a.area() == b.area());
+        writeln!(out, "Cloning a Vec<Box<dyn Shape>> copies each shape: {} shapes, same areas: {}", row_copy.len(), same_areas)?;
+
+        // Enable with `--features dyn-clone`
+        #[cfg(feature = "dyn-clone")]
+        {
+            use with_dyn_clone::{Button, Slider, Widget};
+            writeln!(out, "\n===== With the dyn-clone Crate =====")?;
+            let toolbar: Vec<Box<dyn Widget>> = vec![
+                Box::new(Button { label: "Save".to_string(), width: 80 }),
+                Box::new(Slider { label: "Zoom".to_string(), range: (10, 400) }),
+            ];
+            let mut copy = toolbar.clone();
+            copy[0].set_label("Save As");
+            writeln!(out, "original: {:?}", toolbar.iter().map(|widget| widget.label()).collect::<Vec<_>>())?;
+            writeln!(out, "copy:     {:?}", copy.iter().map(|widget| widget.label()).collect::<Vec<_>>())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&PrototypePatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(color: &str) -> Box<dyn Shape> {
+        Box::new(Circle { center: (1.0, 2.0), radius: 2.0, color: color.to_string() })
+    }
+
+    #[test]
+    fn clone_box_copies_the_concrete_type_independently() {
+        let original = circle("blue");
+        let mut copy = original.clone();
+        copy.set_color("green");
+        copy.move_by(1.0, 1.0);
+        assert_eq!(copy.kind(), "circle");
+        assert_eq!(copy.area(), original.area());
+        assert_eq!((original.color(), original.position()), ("blue", (1.0, 2.0)));
+        assert_eq!((copy.color(), copy.position()), ("green", (2.0, 3.0)));
+    }
+
+    #[test]
+    fn cloning_a_group_copies_every_child() {
+        let group = Group { children: vec![circle("red"), circle("red")] };
+        let mut copy = group.clone();
+        copy.set_color("teal");
+        copy.children[1].set_color("pink");
+        assert_eq!(group.color(), "red");
+        assert_eq!(copy.color(), "mixed");
+        assert_eq!(Group { children: Vec::new() }.color(), "none");
+        assert!((copy.area() - group.area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pictures_share_pixels_but_not_their_tint() {
+        let original = Picture::checkerboard(3);
+        let mut copy = original.clone();
+        copy.set_color("sepia");
+        assert!(Rc::ptr_eq(&original.pixels, &copy.pixels));
+        assert_eq!(Rc::strong_count(&original.pixels), 2);
+        assert_eq!(&original.pixels[..3], [255, 0, 255]);
+        assert_eq!(original.tint, "none");
+    }
+
+    #[test]
+    fn registry_spawns_customized_copies_and_leaves_prototypes_alone() {
+        let registry = default_registry();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["badge", "button", "dot", "texture"]);
+
+        let moved = registry.spawn_with("button", |shape| shape.move_by(3.0, 4.0)).unwrap();
+        assert_eq!(moved.position(), (3.0, 4.0));
+        assert_eq!(registry.prototype("button").unwrap().position(), (0.0, 0.0));
+
+        let mut badge = registry.spawn("badge").unwrap();
+        badge.set_color("bronze");
+        assert_eq!(registry.prototype("badge").unwrap().color(), "gold");
+        assert!(registry.spawn("hexagon").is_none());
+
+        let mut registry = registry;
+        registry.register("dot", circle("white"));
+        assert_eq!(registry.spawn("dot").unwrap().color(), "white");
+    }
+
+    #[cfg(feature = "dyn-clone")]
+    #[test]
+    fn dyn_clone_gives_boxed_widgets_clone() {
+        use with_dyn_clone::{Button, Widget};
+        let original: Box<dyn Widget> = Box::new(Button { label: "OK".to_string(), width: 40 });
+        let mut copy = original.clone();
+        copy.set_label("Cancel");
+        assert_eq!((original.label(), copy.label()), ("OK", "Cancel"));
+    }
+}
@@ -0,0 +1,384 @@
+//! Proxy Pattern Implementation in Rust
+//!
+//! The Proxy Pattern is a structural design pattern that puts a stand-in with the same
+//! interface in front of an object, so the stand-in can control when, how often and for
+//! whom the real object is used, without callers noticing the difference.
+//!
+//! This example wraps `ImageLoader`, a slow service, in three proxies, each implementing
+//! the same `ImageService` trait:
+//! - `LazyImageLoader`, a virtual proxy: the loader is only created, in a `OnceCell`,
+//!   when the first image is requested
+//! - `CachingImageService`, a caching proxy: each path is loaded once and shared as an
+//!   `Rc<Image>` afterwards; failures are not cached, so a retry can succeed
+//! - `ProtectedImageService`, a protection proxy: the caller's role is checked against
+//!   the path before the request goes any further
+//!
+//! Because every proxy is generic over the `ImageService` it wraps, they stack, the same
+//! way decorators do. `LoaderStats` counts what reaches the real loader, which is how the
+//! demo and the tests show each proxy doing its job.
+
+use demo::Demo;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// ========== Subject ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    NotFound(String),
+    UnsupportedFormat(String),
+    Forbidden { user: String, path: String },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::NotFound(path) => write!(f, "{} not found", path),
+            ImageError::UnsupportedFormat(path) => write!(f, "{} is not a PNG or JPEG", path),
+            ImageError::Forbidden { user, path } => write!(f, "{} may not view {}", user, path),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+/// What the real subject and every proxy offer
+pub trait ImageService {
+    fn load(&self, path: &str) -> Result<Rc<Image>, ImageError>;
+}
+
+// ========== Real Subject ==========
+
+/// How often the real loader was created and called, shared so it can be read after the
+/// loader has been moved into a proxy
+#[derive(Debug, Default)]
+pub struct LoaderStats {
+    pub created: Cell<usize>,
+    pub loads: Cell<usize>,
+}
+
+/// Expensive to create (think: opening a codec library) and to call (decoding a file)
+pub struct ImageLoader {
+    stats: Rc<LoaderStats>,
+}
+
+impl ImageLoader {
+    pub fn new(stats: Rc<LoaderStats>) -> Self {
+        stats.created.set(stats.created.get() + 1);
+        ImageLoader { stats }
+    }
+}
+
+impl ImageService for ImageLoader {
+    fn load(&self, path: &str) -> Result<Rc<Image>, ImageError> {
+        self.stats.loads.set(self.stats.loads.get() + 1);
+        if !(path.ends_with(".png") || path.ends_with(".jpg")) {
+            return Err(ImageError::UnsupportedFormat(path.to_string()));
+        }
+        if path.contains("missing") {
+            return Err(ImageError::NotFound(path.to_string()));
+        }
+        // Stands in for reading the header: a size derived from the path
+        let seed = path.bytes().fold(7u32, |hash, byte| hash.wrapping_mul(31).wrapping_add(u32::from(byte)));
+        Ok(Rc::new(Image { path: path.to_string(), width: 320 + seed % 1600, height: 240 + seed % 900 }))
+    }
+}
+
+// ========== Virtual Proxy ==========
+
+/// Defers creating the `ImageLoader` until an image is actually needed
+pub struct LazyImageLoader {
+    stats: Rc<LoaderStats>,
+    real: OnceCell<ImageLoader>,
+}
+
+impl LazyImageLoader {
+    pub fn new(stats: Rc<LoaderStats>) -> Self {
+        LazyImageLoader { stats, real: OnceCell::new() }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.real.get().is_some()
+    }
+}
+
+impl ImageService for LazyImageLoader {
+    fn load(&self, path: &str) -> Result<Rc<Image>, ImageError> {
+        self.real.get_or_init(|| ImageLoader::new(Rc::clone(&self.stats))).load(path)
+    }
+}
+
+// ========== Caching Proxy ==========
+
+/// Remembers every image it has loaded successfully
+pub struct CachingImageService<S> {
+    inner: S,
+    cache: RefCell<HashMap<String, Rc<Image>>>,
+    hits: Cell<usize>,
+}
+
+impl<S: ImageService> CachingImageService<S> {
+    pub fn new(inner: S) -> Self {
+        CachingImageService { inner, cache: RefCell::new(HashMap::new()), hits: Cell::new(0) }
+    }
+
+    /// Requests answered without reaching the wrapped service
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    pub fn cached(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Forgets `path`, say because the file changed; returns whether it was cached
+    pub fn invalidate(&self, path: &str) -> bool {
+        self.cache.borrow_mut().remove(path).is_some()
+    }
+}
+
+impl<S: ImageService> ImageService for CachingImageService<S> {
+    fn load(&self, path: &str) -> Result<Rc<Image>, ImageError> {
+        if let Some(image) = self.cache.borrow().get(path) {
+            self.hits.set(self.hits.get() + 1);
+            return Ok(Rc::clone(image));
+        }
+        // The borrow above has ended, so a wrapped service that calls back into this one
+        // can't trip over it
+        let image = self.inner.load(path)?;
+        self.cache.borrow_mut().insert(path.to_string(), Rc::clone(&image));
+        Ok(image)
+    }
+}
+
+// ========== Protection Proxy ==========
+
+/// Ordered, so a higher role can see everything a lower one can
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Guest,
+    Member,
+    Admin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+    pub role: Role,
+}
+
+impl User {
+    pub fn new(name: &str, role: Role) -> Self {
+        User { name: name.to_string(), role }
+    }
+}
+
+/// The role a path needs, from its top-level directory
+pub fn required_role(path: &str) -> Role {
+    match path.split('/').next() {
+        Some("admin") => Role::Admin,
+        Some("members") => Role::Member,
+        _ => Role::Guest,
+    }
+}
+
+/// Loads on behalf of one user, refusing paths above their role
+pub struct ProtectedImageService<S> {
+    inner: S,
+    user: User,
+    denied: Cell<usize>,
+}
+
+impl<S: ImageService> ProtectedImageService<S> {
+    pub fn new(inner: S, user: User) -> Self {
+        ProtectedImageService { inner, user, denied: Cell::new(0) }
+    }
+
+    pub fn denied(&self) -> usize {
+        self.denied.get()
+    }
+
+    /// The same wrapped service, now acting for someone else
+    pub fn switch_user(&mut self, user: User) {
+        self.user = user;
+    }
+}
+
+impl<S: ImageService> ImageService for ProtectedImageService<S> {
+    fn load(&self, path: &str) -> Result<Rc<Image>, ImageError> {
+        if self.user.role < required_role(path) {
+            self.denied.set(self.denied.get() + 1);
+            return Err(ImageError::Forbidden { user: self.user.name.clone(), path: path.to_string() });
+        }
+        self.inner.load(path)
+    }
+}
+
+// ========== Demo Code ==========
+
+fn show(out: &mut dyn Write, service: &dyn ImageService, path: &str) -> io::Result<()> {
+    match service.load(path) {
+        Ok(image) => writeln!(out, "  {:<24} {}x{}", path, image.width, image.height),
+        Err(error) => writeln!(out, "  {:<24} error: {}", path, error),
+    }
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ProxyPatternDemo;
+
+impl Demo for ProxyPatternDemo {
+    fn name(&self) -> &'static str {
+        "proxy_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lazy, caching and protection proxies around an expensive image loader"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Virtual Proxy =====")?;
+        let stats = Rc::new(LoaderStats::default());
+        let lazy = LazyImageLoader::new(Rc::clone(&stats));
+        writeln!(out, "Proxy created; loader created: {} time(s)", stats.created.get())?;
+        show(out, &lazy, "public/logo.png")?;
+        show(out, &lazy, "public/banner.jpg")?;
+        writeln!(out, "After two loads; loader created: {} time(s), is_loaded: {}", stats.created.get(), lazy.is_loaded())?;
+
+        writeln!(out, "\n===== Caching Proxy =====")?;
+        let stats = Rc::new(LoaderStats::default());
+        let cache = CachingImageService::new(ImageLoader::new(Rc::clone(&stats)));
+        for path in ["public/logo.png", "public/logo.png", "public/missing.png", "public/missing.png", "public/logo.png"] {
+            show(out, &cache, path)?;
+        }
+        writeln!(out, "5 requests: {} reached the loader, {} cache hits", stats.loads.get(), cache.hits())?;
+        cache.invalidate("public/logo.png");
+        show(out, &cache, "public/logo.png")?;
+        writeln!(out, "After invalidating logo.png: {} loads", stats.loads.get())?;
+
+        writeln!(out, "\n===== Protection Proxy =====")?;
+        let stats = Rc::new(LoaderStats::default());
+        let mut guarded = ProtectedImageService::new(ImageLoader::new(Rc::clone(&stats)), User::new("guest", Role::Guest));
+        for user in [User::new("guest", Role::Guest), User::new("mia", Role::Member), User::new("root", Role::Admin)] {
+            writeln!(out, "{} ({:?}):", user.name, user.role)?;
+            guarded.switch_user(user);
+            for path in ["public/logo.png", "members/roster.png", "admin/audit.png"] {
+                show(out, &guarded, path)?;
+            }
+        }
+        writeln!(out, "{} denied before reaching the loader, {} loads", guarded.denied(), stats.loads.get())?;
+
+        writeln!(out, "\n===== Stacked: Protection -> Cache -> Lazy Loader =====")?;
+        let stats = Rc::new(LoaderStats::default());
+        let stacked = ProtectedImageService::new(
+            CachingImageService::new(LazyImageLoader::new(Rc::clone(&stats))),
+            User::new("mia", Role::Member),
+        );
+        show(out, &stacked, "admin/audit.png")?;
+        writeln!(out, "After a denied request: loader created {} time(s)", stats.created.get())?;
+        for _ in 0..3 {
+            show(out, &stacked, "members/roster.png")?;
+        }
+        writeln!(out, "After 3 allowed requests: created {}, loads {}", stats.created.get(), stats.loads.get())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ProxyPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lazy_proxy_creates_the_loader_on_first_use_only() {
+        let stats = Rc::new(LoaderStats::default());
+        let lazy = LazyImageLoader::new(Rc::clone(&stats));
+        assert_eq!((stats.created.get(), lazy.is_loaded()), (0, false));
+
+        let image = lazy.load("a.png").unwrap();
+        assert_eq!(image.path, "a.png");
+        assert!(lazy.load("b.txt").is_err());
+        lazy.load("c.jpg").unwrap();
+        assert_eq!((stats.created.get(), stats.loads.get()), (1, 3));
+        assert!(lazy.is_loaded());
+    }
+
+    #[test]
+    fn caching_proxy_loads_each_path_once_and_shares_the_result() {
+        let stats = Rc::new(LoaderStats::default());
+        let cache = CachingImageService::new(ImageLoader::new(Rc::clone(&stats)));
+        let first = cache.load("x.png").unwrap();
+        let second = cache.load("x.png").unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+        cache.load("y.png").unwrap();
+        assert_eq!((stats.loads.get(), cache.hits(), cache.cached()), (2, 1, 2));
+
+        assert!(cache.invalidate("x.png"));
+        assert!(!cache.invalidate("x.png"));
+        let reloaded = cache.load("x.png").unwrap();
+        assert!(!Rc::ptr_eq(&first, &reloaded));
+        assert_eq!(*first, *reloaded);
+        assert_eq!(stats.loads.get(), 3);
+    }
+
+    #[test]
+    fn caching_proxy_does_not_remember_failures() {
+        let stats = Rc::new(LoaderStats::default());
+        let cache = CachingImageService::new(ImageLoader::new(Rc::clone(&stats)));
+        for _ in 0..3 {
+            assert_eq!(cache.load("missing.png"), Err(ImageError::NotFound("missing.png".to_string())));
+        }
+        assert_eq!((stats.loads.get(), cache.hits(), cache.cached()), (3, 0, 0));
+    }
+
+    #[test]
+    fn protection_proxy_refuses_before_the_subject_is_touched() {
+        let stats = Rc::new(LoaderStats::default());
+        let mut guarded = ProtectedImageService::new(ImageLoader::new(Rc::clone(&stats)), User::new("ann", Role::Guest));
+        assert_eq!(
+            guarded.load("members/a.png"),
+            Err(ImageError::Forbidden { user: "ann".to_string(), path: "members/a.png".to_string() })
+        );
+        assert!(guarded.load("public/a.png").is_ok());
+        assert_eq!((guarded.denied(), stats.loads.get()), (1, 1));
+
+        guarded.switch_user(User::new("ann", Role::Member));
+        assert!(guarded.load("members/a.png").is_ok());
+        assert!(guarded.load("admin/a.png").is_err());
+        assert_eq!((guarded.denied(), stats.loads.get()), (2, 2));
+        assert_eq!([required_role("admin/x.png"), required_role("admin.png")], [Role::Admin, Role::Guest]);
+    }
+
+    #[test]
+    fn stacked_proxies_each_save_their_share_of_work() {
+        let stats = Rc::new(LoaderStats::default());
+        let stacked = ProtectedImageService::new(
+            CachingImageService::new(LazyImageLoader::new(Rc::clone(&stats))),
+            User::new("bo", Role::Member),
+        );
+        for _ in 0..5 {
+            assert!(stacked.load("admin/keys.png").is_err());
+        }
+        assert_eq!(stats.created.get(), 0, "denied requests never create the loader");
+        for _ in 0..5 {
+            stacked.load("members/team.jpg").unwrap();
+        }
+        assert_eq!((stats.created.get(), stats.loads.get()), (1, 1));
+    }
+}
@@ -0,0 +1,338 @@
+//! Publish–Subscribe Event Bus Implementation in Rust
+//!
+//! Publish–subscribe decouples the code that raises an event from the code that reacts to it.
+//! In the observer example the weather station holds its displays and calls them directly.
+//! Here publishers and subscribers only share a bus. Neither knows the other exists, and
+//! either side can have any number of members, including none.
+//!
+//! Topics are event types:
+//! - `subscribe::<E>` registers a closure for `E`, keyed by `TypeId::of::<E>()`, and
+//!   returns a `Subscription` handle
+//! - `publish(&event)` calls every closure subscribed to that event's type, in subscription
+//!   order, and reports how many ran
+//! - Dropping a `Subscription` unsubscribes; `Subscription::forever` opts out of that
+//! - A handler that publishes should hold a `WeakEventBus` from `downgrade`. Capturing a clone
+//!   of the bus would make the bus own a handler that owns the bus, so neither is ever freed
+//! - Handlers run against a snapshot of the subscriber list, so they may themselves
+//!   subscribe, unsubscribe or publish without tripping a `RefCell` borrow
+
+use demo::Demo;
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::rc::{Rc, Weak};
+
+// ========== Event Bus ==========
+
+type Handler = Rc<dyn Fn(&dyn Any)>;
+
+#[derive(Default)]
+struct Topics {
+    next_id: u64,
+    handlers: HashMap<TypeId, Vec<(u64, Handler)>>,
+}
+
+/// A single-threaded bus; clones share the same subscribers
+#[derive(Clone, Default)]
+pub struct EventBus {
+    topics: Rc<RefCell<Topics>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `handler` for every `E` published until the returned handle is dropped
+    #[must_use = "dropping the subscription unsubscribes immediately"]
+    pub fn subscribe<E: 'static>(&self, handler: impl Fn(&E) + 'static) -> Subscription {
+        let mut topics = self.topics.borrow_mut();
+        let id = topics.next_id;
+        topics.next_id += 1;
+        let handler: Handler = Rc::new(move |event: &dyn Any| {
+            if let Some(event) = event.downcast_ref::<E>() {
+                handler(event);
+            }
+        });
+        topics.handlers.entry(TypeId::of::<E>()).or_default().push((id, handler));
+        Subscription { topics: Rc::downgrade(&self.topics), topic: TypeId::of::<E>(), id }
+    }
+
+    /// Delivers `event` to the subscribers of `E` and returns how many there were
+    pub fn publish<E: 'static>(&self, event: &E) -> usize {
+        let snapshot: Vec<Handler> = match self.topics.borrow().handlers.get(&TypeId::of::<E>()) {
+            Some(handlers) => handlers.iter().map(|(_, handler)| Rc::clone(handler)).collect(),
+            None => return 0,
+        };
+        for handler in &snapshot {
+            handler(event);
+        }
+        snapshot.len()
+    }
+
+    pub fn subscriber_count<E: 'static>(&self) -> usize {
+        self.topics.borrow().handlers.get(&TypeId::of::<E>()).map_or(0, Vec::len)
+    }
+
+    /// A handle that doesn't keep the bus alive, for handlers that publish
+    pub fn downgrade(&self) -> WeakEventBus {
+        WeakEventBus { topics: Rc::downgrade(&self.topics) }
+    }
+}
+
+/// A non-owning `EventBus` handle; publishing through it does nothing once the bus is gone
+#[derive(Clone)]
+pub struct WeakEventBus {
+    topics: Weak<RefCell<Topics>>,
+}
+
+impl WeakEventBus {
+    /// Delivers `event` like `EventBus::publish`, or returns 0 if the bus was dropped
+    pub fn publish<E: 'static>(&self, event: &E) -> usize {
+        self.topics.upgrade().map_or(0, |topics| EventBus { topics }.publish(event))
+    }
+}
+
+/// Unsubscribes its handler when dropped
+pub struct Subscription {
+    topics: Weak<RefCell<Topics>>,
+    topic: TypeId,
+    id: u64,
+}
+
+impl Subscription {
+    /// Keeps the handler subscribed for as long as the bus lives. A handler that owns an
+    /// `EventBus` clone then keeps the bus alive forever; give it a `WeakEventBus` instead
+    pub fn forever(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // The bus may already be gone, in which case there is nothing to remove
+        let Some(topics) = self.topics.upgrade() else { return };
+        let removed: Vec<(u64, Handler)> = {
+            let mut topics = topics.borrow_mut();
+            let Some(handlers) = topics.handlers.get_mut(&self.topic) else { return };
+            let (removed, kept) = std::mem::take(handlers).into_iter().partition(|(id, _)| *id == self.id);
+            *handlers = kept;
+            if handlers.is_empty() {
+                topics.handlers.remove(&self.topic);
+            }
+            removed
+        };
+        // Dropped only now the borrow is released: the handler may own subscriptions of its own
+        drop(removed);
+    }
+}
+
+// ========== Events ==========
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderPlaced {
+    pub order: u32,
+    pub total_cents: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderShipped {
+    pub order: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowStock {
+    pub sku: &'static str,
+    pub remaining: u32,
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct EventBusDemo;
+
+impl Demo for EventBusDemo {
+    fn name(&self) -> &'static str {
+        "event_bus"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Topic-per-type publish/subscribe bus whose subscriptions unsubscribe when dropped"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let bus = EventBus::new();
+        let log = Rc::new(RefCell::new(Vec::<String>::new()));
+
+        writeln!(out, "===== Subscribing by Event Type =====")?;
+        let email = {
+            let log = Rc::clone(&log);
+            bus.subscribe(move |placed: &OrderPlaced| log.borrow_mut().push(format!("email: order #{} confirmed", placed.order)))
+        };
+        let revenue = Rc::new(Cell::new(0u64));
+        let accounting = {
+            let revenue = Rc::clone(&revenue);
+            bus.subscribe(move |placed: &OrderPlaced| revenue.set(revenue.get() + placed.total_cents))
+        };
+        let tracking = {
+            let log = Rc::clone(&log);
+            bus.subscribe(move |shipped: &OrderShipped| log.borrow_mut().push(format!("sms: order #{} is on its way", shipped.order)))
+        };
+        writeln!(out, "OrderPlaced subscribers: {}", bus.subscriber_count::<OrderPlaced>())?;
+        writeln!(out, "OrderShipped subscribers: {}", bus.subscriber_count::<OrderShipped>())?;
+
+        writeln!(out, "\n===== Publishing =====")?;
+        for (order, total_cents) in [(1001, 2_499), (1002, 12_000)] {
+            let delivered = bus.publish(&OrderPlaced { order, total_cents });
+            writeln!(out, "OrderPlaced #{} -> handlers run: {}", order, delivered)?;
+        }
+        writeln!(out, "OrderShipped #1001 -> handlers run: {}", bus.publish(&OrderShipped { order: 1001 }))?;
+        writeln!(out, "LowStock with no subscribers -> handlers run: {}", bus.publish(&LowStock { sku: "MUG-01", remaining: 2 }))?;
+        for line in log.borrow_mut().drain(..) {
+            writeln!(out, "  {}", line)?;
+        }
+        writeln!(out, "  accounting: ${}.{:02}", revenue.get() / 100, revenue.get() % 100)?;
+
+        writeln!(out, "\n===== Handlers That Publish =====")?;
+        {
+            let bus_for_handler = bus.downgrade();
+            bus.subscribe(move |placed: &OrderPlaced| {
+                if placed.total_cents >= 10_000 {
+                    bus_for_handler.publish(&LowStock { sku: "GIFT-WRAP", remaining: 0 });
+                }
+            })
+            .forever();
+        }
+        let restock = {
+            let log = Rc::clone(&log);
+            bus.subscribe(move |low: &LowStock| log.borrow_mut().push(format!("restock {} ({} left)", low.sku, low.remaining)))
+        };
+        bus.publish(&OrderPlaced { order: 1003, total_cents: 15_000 });
+        for line in log.borrow_mut().drain(..) {
+            writeln!(out, "  {}", line)?;
+        }
+
+        writeln!(out, "\n===== Dropping Subscriptions =====")?;
+        drop(email);
+        drop(accounting);
+        writeln!(out, "After dropping email and accounting, OrderPlaced subscribers: {}", bus.subscriber_count::<OrderPlaced>())?;
+        drop(tracking);
+        writeln!(out, "OrderShipped #1003 -> handlers run: {}", bus.publish(&OrderShipped { order: 1003 }))?;
+        drop(restock);
+        writeln!(out, "LowStock subscribers: {}", bus.subscriber_count::<LowStock>())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&EventBusDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder<E: Clone + 'static>(bus: &EventBus) -> (Rc<RefCell<Vec<E>>>, Subscription) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&seen);
+        let subscription = bus.subscribe(move |event: &E| sink.borrow_mut().push(event.clone()));
+        (seen, subscription)
+    }
+
+    #[test]
+    fn events_only_reach_subscribers_of_their_type() {
+        let bus = EventBus::new();
+        let (placed, _a) = recorder::<OrderPlaced>(&bus);
+        let (shipped, _b) = recorder::<OrderShipped>(&bus);
+        assert_eq!(bus.publish(&OrderShipped { order: 7 }), 1);
+        assert_eq!(bus.publish(&42u32), 0);
+        assert!(placed.borrow().is_empty());
+        assert_eq!(*shipped.borrow(), [OrderShipped { order: 7 }]);
+    }
+
+    #[test]
+    fn handlers_run_in_subscription_order() {
+        let bus = EventBus::new();
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let subscriptions: Vec<Subscription> = (0..3)
+            .map(|n| {
+                let order = Rc::clone(&order);
+                bus.subscribe(move |_: &OrderShipped| order.borrow_mut().push(n))
+            })
+            .collect();
+        assert_eq!(bus.publish(&OrderShipped { order: 1 }), subscriptions.len());
+        assert_eq!(*order.borrow(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn dropping_a_subscription_unsubscribes_only_that_handler() {
+        let bus = EventBus::new();
+        let (first, first_subscription) = recorder::<u8>(&bus);
+        let (second, _second_subscription) = recorder::<u8>(&bus);
+        bus.publish(&1u8);
+        drop(first_subscription);
+        assert_eq!(bus.subscriber_count::<u8>(), 1);
+        bus.publish(&2u8);
+        assert_eq!((first.borrow().clone(), second.borrow().clone()), (vec![1], vec![1, 2]));
+    }
+
+    #[test]
+    fn handlers_may_publish_and_unsubscribe_during_dispatch() {
+        let bus = EventBus::new();
+        let (low, _low_subscription) = recorder::<LowStock>(&bus);
+        let once: Rc<RefCell<Option<Subscription>>> = Rc::default();
+        let (bus_in_handler, slot) = (bus.downgrade(), Rc::clone(&once));
+        *once.borrow_mut() = Some(bus.subscribe(move |placed: &OrderPlaced| {
+            bus_in_handler.publish(&LowStock { sku: "X", remaining: placed.order });
+            slot.borrow_mut().take();
+        }));
+        assert_eq!(bus.publish(&OrderPlaced { order: 3, total_cents: 0 }), 1);
+        assert_eq!(bus.publish(&OrderPlaced { order: 4, total_cents: 0 }), 0);
+        assert_eq!(*low.borrow(), [LowStock { sku: "X", remaining: 3 }]);
+    }
+
+    #[test]
+    fn subscriptions_outliving_the_bus_drop_quietly() {
+        let bus = EventBus::new();
+        let (_, subscription) = recorder::<u8>(&bus);
+        drop(bus);
+        drop(subscription);
+    }
+
+    #[test]
+    fn dropping_a_handler_that_owns_a_subscription_unsubscribes_both() {
+        let bus = EventBus::new();
+        let (shipped, inner) = recorder::<OrderShipped>(&bus);
+        let outer = bus.subscribe(move |_: &OrderPlaced| {
+            let _keep = &inner;
+        });
+        assert_eq!(bus.subscriber_count::<OrderShipped>(), 1);
+        drop(outer);
+        assert_eq!((bus.subscriber_count::<OrderPlaced>(), bus.subscriber_count::<OrderShipped>()), (0, 0));
+        assert_eq!(bus.publish(&OrderShipped { order: 1 }), 0);
+        assert!(shipped.borrow().is_empty());
+    }
+
+    #[test]
+    fn forever_handlers_publishing_through_a_weak_bus_let_it_drop() {
+        let bus = EventBus::new();
+        let topics = Rc::downgrade(&bus.topics);
+        let weak = bus.downgrade();
+        let inner = weak.clone();
+        bus.subscribe(move |placed: &OrderPlaced| {
+            inner.publish(&OrderShipped { order: placed.order });
+        })
+        .forever();
+        let (shipped, _subscription) = recorder::<OrderShipped>(&bus);
+        bus.publish(&OrderPlaced { order: 5, total_cents: 0 });
+        assert_eq!(*shipped.borrow(), [OrderShipped { order: 5 }]);
+        drop(bus);
+        assert!(topics.upgrade().is_none());
+        assert_eq!(weak.publish(&OrderShipped { order: 6 }), 0);
+    }
+}
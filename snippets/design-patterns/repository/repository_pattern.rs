@@ -0,0 +1,374 @@
+//! Repository and Unit of Work Pattern Implementation in Rust
+//!
+//! The Repository Pattern puts a collection-like interface in front of storage, so code
+//! asks for "the user with id 7" instead of writing queries, and the storage can be
+//! swapped without touching it. The Unit of Work Pattern sits on top: it records the
+//! inserts, updates and deletes of one business operation and writes them all at once,
+//! or none of them.
+//!
+//! This example stores the `UserData` records from the singleton snippet's
+//! `UserManager`, keyed by the same `i32` ids:
+//! - `Repository<T, Id>`: the generic trait, returning owned values the way a database
+//!   would
+//! - `InMemoryRepository<T, Id>`: a `BTreeMap`-backed implementation, so iteration order
+//!   is stable
+//! - `UnitOfWork`: borrows a repository mutably, queues changes, lets reads see them
+//!   (`get` checks pending changes first), and `commit`s them in order. If one fails,
+//!   the ones already applied are reverted, newest first, and the repository is left
+//!   exactly as it was. Dropping a unit of work without committing discards it
+//!
+//! Undoing applied changes rather than checking everything first means `commit` works
+//! with any `Repository`, including ones where only the store knows whether a write
+//! will succeed.
+
+use crate::singleton_pattern::user_manager_singleton::UserData;
+use chrono::Local;
+use demo::Demo;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Repository ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryError<Id> {
+    NotFound(Id),
+    AlreadyExists(Id),
+}
+
+impl<Id: fmt::Debug> fmt::Display for RepositoryError<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepositoryError::NotFound(id) => write!(f, "no record with id {:?}", id),
+            RepositoryError::AlreadyExists(id) => write!(f, "a record with id {:?} already exists", id),
+        }
+    }
+}
+
+impl<Id: fmt::Debug> std::error::Error for RepositoryError<Id> {}
+
+pub trait Repository<T, Id> {
+    fn get(&self, id: &Id) -> Option<T>;
+    /// Every record, ordered by id
+    fn all(&self) -> Vec<(Id, T)>;
+    fn insert(&mut self, id: Id, item: T) -> Result<(), RepositoryError<Id>>;
+    /// Returns the record it replaced
+    fn update(&mut self, id: Id, item: T) -> Result<T, RepositoryError<Id>>;
+    /// Returns the removed record
+    fn delete(&mut self, id: &Id) -> Result<T, RepositoryError<Id>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct InMemoryRepository<T, Id> {
+    items: BTreeMap<Id, T>,
+}
+
+impl<T, Id: Ord> Default for InMemoryRepository<T, Id> {
+    fn default() -> Self {
+        InMemoryRepository { items: BTreeMap::new() }
+    }
+}
+
+impl<T, Id: Ord> InMemoryRepository<T, Id> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<T: Clone, Id: Ord + Clone> Repository<T, Id> for InMemoryRepository<T, Id> {
+    fn get(&self, id: &Id) -> Option<T> {
+        self.items.get(id).cloned()
+    }
+
+    fn all(&self) -> Vec<(Id, T)> {
+        self.items.iter().map(|(id, item)| (id.clone(), item.clone())).collect()
+    }
+
+    fn insert(&mut self, id: Id, item: T) -> Result<(), RepositoryError<Id>> {
+        if self.items.contains_key(&id) {
+            return Err(RepositoryError::AlreadyExists(id));
+        }
+        self.items.insert(id, item);
+        Ok(())
+    }
+
+    fn update(&mut self, id: Id, item: T) -> Result<T, RepositoryError<Id>> {
+        match self.items.get_mut(&id) {
+            Some(existing) => Ok(std::mem::replace(existing, item)),
+            None => Err(RepositoryError::NotFound(id)),
+        }
+    }
+
+    fn delete(&mut self, id: &Id) -> Result<T, RepositoryError<Id>> {
+        self.items.remove(id).ok_or_else(|| RepositoryError::NotFound(id.clone()))
+    }
+}
+
+// ========== Unit of Work ==========
+
+#[derive(Debug, Clone)]
+pub enum Change<T, Id> {
+    Insert(Id, T),
+    Update(Id, T),
+    Delete(Id),
+}
+
+/// The change at `index` failed; nothing was kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitError<Id> {
+    pub index: usize,
+    pub error: RepositoryError<Id>,
+}
+
+impl<Id: fmt::Debug> fmt::Display for CommitError<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "change {} failed ({}), all changes rolled back", self.index, self.error)
+    }
+}
+
+impl<Id: fmt::Debug> std::error::Error for CommitError<Id> {}
+
+pub struct UnitOfWork<'r, T, Id, R: Repository<T, Id>> {
+    repository: &'r mut R,
+    pending: Vec<Change<T, Id>>,
+}
+
+impl<'r, T: Clone, Id: Clone + PartialEq, R: Repository<T, Id>> UnitOfWork<'r, T, Id, R> {
+    pub fn new(repository: &'r mut R) -> Self {
+        UnitOfWork { repository, pending: Vec::new() }
+    }
+
+    pub fn register_new(&mut self, id: Id, item: T) -> &mut Self {
+        self.pending.push(Change::Insert(id, item));
+        self
+    }
+
+    pub fn register_dirty(&mut self, id: Id, item: T) -> &mut Self {
+        self.pending.push(Change::Update(id, item));
+        self
+    }
+
+    pub fn register_deleted(&mut self, id: Id) -> &mut Self {
+        self.pending.push(Change::Delete(id));
+        self
+    }
+
+    pub fn pending(&self) -> &[Change<T, Id>] {
+        &self.pending
+    }
+
+    /// Reads through the pending changes, newest first, before falling back to the repository
+    pub fn get(&self, id: &Id) -> Option<T> {
+        for change in self.pending.iter().rev() {
+            match change {
+                Change::Insert(changed, item) | Change::Update(changed, item) if changed == id => return Some(item.clone()),
+                Change::Delete(changed) if changed == id => return None,
+                _ => {}
+            }
+        }
+        self.repository.get(id)
+    }
+
+    /// Applies every change in order, or none of them
+    pub fn commit(self) -> Result<usize, CommitError<Id>> {
+        let count = self.pending.len();
+        let mut undo = Vec::with_capacity(count);
+        for (index, change) in self.pending.into_iter().enumerate() {
+            let applied = match change {
+                Change::Insert(id, item) => self.repository.insert(id.clone(), item).map(|()| Change::Delete(id)),
+                Change::Update(id, item) => self.repository.update(id.clone(), item).map(|old| Change::Update(id, old)),
+                Change::Delete(id) => self.repository.delete(&id).map(|old| Change::Insert(id, old)),
+            };
+            match applied {
+                Ok(inverse) => undo.push(inverse),
+                Err(error) => {
+                    for inverse in undo.into_iter().rev() {
+                        let reverted = match inverse {
+                            Change::Insert(id, item) => self.repository.insert(id, item),
+                            Change::Update(id, item) => self.repository.update(id, item).map(drop),
+                            Change::Delete(id) => self.repository.delete(&id).map(drop),
+                        };
+                        reverted.unwrap_or_else(|_| panic!("undoing a change this unit of work just made cannot fail"));
+                    }
+                    return Err(CommitError { index, error });
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+// ========== Users ==========
+
+pub type UserRepository = InMemoryRepository<UserData, i32>;
+
+pub fn new_user(name: &str, email: &str) -> UserData {
+    UserData { name: name.to_string(), email: email.to_string(), role: None, created_at: Local::now(), updated_at: None }
+}
+
+/// Copy of `user` with a new role and `updated_at` set
+pub fn with_role(user: &UserData, role: &str) -> UserData {
+    UserData { role: Some(role.to_string()), updated_at: Some(Local::now()), ..user.clone() }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct RepositoryPatternDemo;
+
+impl RepositoryPatternDemo {
+    fn show(users: &UserRepository, out: &mut dyn Write) -> io::Result<()> {
+        for (id, user) in users.all() {
+            writeln!(out, "  {}: {}", id, user)?;
+        }
+        Ok(())
+    }
+}
+
+impl Demo for RepositoryPatternDemo {
+    fn name(&self) -> &'static str {
+        "repository_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generic repository over an in-memory store with a unit of work that commits or rolls back as a whole"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Repository =====")?;
+        let mut users = UserRepository::new();
+        users.insert(1, new_user("Alice", "alice@example.com")).expect("fresh id");
+        users.insert(2, new_user("Bob", "bob@example.com")).expect("fresh id");
+        if let Err(error) = users.insert(1, new_user("Mallory", "mallory@example.com")) {
+            writeln!(out, "insert(1, ..): {}", error)?;
+        }
+        if let Err(error) = users.delete(&9) {
+            writeln!(out, "delete(9): {}", error)?;
+        }
+        Self::show(&users, out)?;
+
+        writeln!(out, "\n===== A Unit of Work That Commits =====")?;
+        {
+            let mut uow = UnitOfWork::new(&mut users);
+            uow.register_new(3, new_user("Carol", "carol@example.com"));
+            let alice = uow.get(&1).expect("alice exists");
+            uow.register_dirty(1, with_role(&alice, "admin")).register_deleted(2);
+            writeln!(out, "{} changes pending; Carol visible through the unit of work: {}", uow.pending().len(), uow.get(&3).is_some())?;
+            writeln!(out, "Bob visible through the unit of work: {}", uow.get(&2).is_some())?;
+            writeln!(out, "Committed {} changes", uow.commit().expect("all changes valid"))?;
+        }
+        Self::show(&users, out)?;
+        writeln!(out, "Alice updated_at set: {}", users.get(&1).is_some_and(|alice| alice.updated_at.is_some()))?;
+
+        writeln!(out, "\n===== A Unit of Work That Fails =====")?;
+        {
+            let mut uow = UnitOfWork::new(&mut users);
+            uow.register_new(4, new_user("Dave", "dave@example.com"))
+                .register_deleted(3)
+                .register_dirty(2, new_user("Bob", "bob@example.com"));
+            if let Err(error) = uow.commit() {
+                writeln!(out, "{}", error)?;
+            }
+        }
+        Self::show(&users, out)?;
+
+        writeln!(out, "\n===== A Unit of Work That Is Dropped =====")?;
+        {
+            let mut uow = UnitOfWork::new(&mut users);
+            uow.register_deleted(1).register_deleted(3);
+            writeln!(out, "{} deletes queued, then dropped without commit", uow.pending().len())?;
+        }
+        writeln!(out, "Still {} users", users.len())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&RepositoryPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(users: &UserRepository) -> Vec<(i32, String)> {
+        users.all().into_iter().map(|(id, user)| (id, user.name)).collect()
+    }
+
+    fn seeded() -> UserRepository {
+        let mut users = UserRepository::new();
+        users.insert(1, new_user("Alice", "alice@example.com")).unwrap();
+        users.insert(2, new_user("Bob", "bob@example.com")).unwrap();
+        users
+    }
+
+    #[test]
+    fn in_memory_repository_reports_missing_and_duplicate_ids() {
+        let mut users = seeded();
+        assert_eq!(users.insert(2, new_user("B", "b@x")).unwrap_err(), RepositoryError::AlreadyExists(2));
+        assert_eq!(users.update(7, new_user("G", "g@x")).unwrap_err(), RepositoryError::NotFound(7));
+        assert_eq!(users.delete(&7).unwrap_err(), RepositoryError::NotFound(7));
+        let old = users.update(2, new_user("Robert", "bob@example.com")).unwrap();
+        assert_eq!(old.name, "Bob");
+        assert_eq!(users.delete(&1).unwrap().name, "Alice");
+        assert_eq!(names(&users), [(2, "Robert".to_string())]);
+    }
+
+    #[test]
+    fn changes_are_invisible_to_the_repository_until_commit() {
+        let mut users = seeded();
+        let mut uow = UnitOfWork::new(&mut users);
+        uow.register_new(3, new_user("Carol", "carol@example.com")).register_deleted(1);
+        assert_eq!(uow.get(&3).map(|user| user.name).as_deref(), Some("Carol"));
+        assert!(uow.get(&1).is_none());
+        assert_eq!(uow.commit(), Ok(2));
+        assert_eq!(names(&users), [(2, "Bob".to_string()), (3, "Carol".to_string())]);
+    }
+
+    #[test]
+    fn a_failing_change_rolls_back_the_whole_batch() {
+        let mut users = seeded();
+        let bob_before = users.get(&2).unwrap();
+        let mut uow = UnitOfWork::new(&mut users);
+        uow.register_new(3, new_user("Carol", "carol@example.com"))
+            .register_dirty(2, with_role(&bob_before, "admin"))
+            .register_deleted(1)
+            .register_new(2, new_user("Bob again", "bob@example.com"));
+        assert_eq!(uow.commit(), Err(CommitError { index: 3, error: RepositoryError::AlreadyExists(2) }));
+        assert_eq!(names(&users), [(1, "Alice".to_string()), (2, "Bob".to_string())]);
+        assert_eq!(users.get(&2).unwrap().role, None);
+    }
+
+    #[test]
+    fn dropping_without_commit_discards_the_changes() {
+        let mut users = seeded();
+        {
+            let mut uow = UnitOfWork::new(&mut users);
+            uow.register_deleted(1).register_deleted(2);
+            assert_eq!(uow.pending().len(), 2);
+        }
+        assert_eq!(users.len(), 2);
+    }
+
+    #[test]
+    fn later_changes_to_the_same_id_win_within_a_unit_of_work() {
+        let mut repository = InMemoryRepository::<&str, u8>::new();
+        let mut uow = UnitOfWork::new(&mut repository);
+        uow.register_new(1, "draft").register_dirty(1, "final").register_new(2, "temp").register_deleted(2);
+        assert_eq!((uow.get(&1), uow.get(&2)), (Some("final"), None));
+        assert_eq!(uow.commit(), Ok(4));
+        assert_eq!(repository.all(), [(1, "final")]);
+    }
+}
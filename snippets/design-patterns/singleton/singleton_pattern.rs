@@ -1,26 +1,26 @@
-/**
- * Singleton Pattern Implementation in Rust
- *
- * The Singleton Pattern is a creational design pattern that ensures a class has only one instance
- * and provides a global point of access to it. This is useful when exactly one object is needed
- * to coordinate actions across the system.
- *
- * This file demonstrates several ways to implement the Singleton pattern in Rust.
- */
-
+//! Singleton Pattern Implementation in Rust
+//!
+//! The Singleton Pattern is a creational design pattern that ensures a class has only one instance
+//! and provides a global point of access to it. This is useful when exactly one object is needed
+//! to coordinate actions across the system.
+//!
+//! This file demonstrates several ways to implement the Singleton pattern in Rust.
+
+use demo::Demo;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex, Once};
-use std::time::{SystemTime, UNIX_EPOCH};
 use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, Once};
 
 // ========== Lazy Static Singleton Implementation ==========
 
 // Lazy static is a common way to implement singletons in Rust
-// This requires the lazy_static crate
+// Built with the crate's `lazy_static` feature
 #[cfg(feature = "lazy_static")]
-mod lazy_static_singleton {
+pub mod lazy_static_singleton {
     use super::*;
     use lazy_static::lazy_static;
+    use std::time::SystemTime;
 
     #[derive(Debug)]
     pub struct ClassicSingleton {
@@ -49,7 +49,6 @@ mod lazy_static_singleton {
         pub fn update_config(&self, key: &str, value: &str) {
             let mut config = self.config.lock().unwrap();
             config.insert(key.to_string(), value.to_string());
-            println!("Configuration updated: {} = {}", key, value);
         }
 
         pub fn get_timestamp(&self) -> SystemTime {
@@ -65,7 +64,7 @@ mod lazy_static_singleton {
 // ========== Once Cell Singleton Implementation ==========
 
 // Once Cell is a more modern approach in Rust's standard library
-mod once_cell_singleton {
+pub mod once_cell_singleton {
     use super::*;
     use std::sync::OnceLock;
 
@@ -85,44 +84,46 @@ mod once_cell_singleton {
             }
         }
 
-        pub fn connect(&self, connection_string: &str) -> bool {
+        /// Connects, or bumps the connection count if already connected; reports to `log`.
+        pub fn connect(&self, connection_string: &str, log: &mut dyn Write) -> io::Result<bool> {
             let mut is_connected = self.is_connected.lock().unwrap();
             let mut count = self.connection_count.lock().unwrap();
             let mut conn_str = self.connection_string.lock().unwrap();
 
             if *is_connected {
                 *count += 1;
-                println!("Already connected to database. Connection count: {}", *count);
-                return true;
+                writeln!(log, "Already connected to database. Connection count: {}", *count)?;
+                return Ok(true);
             }
 
             // Simulate connection
             *conn_str = connection_string.to_string();
             *is_connected = true;
             *count = 1;
-            println!("Connected to database: {}", connection_string);
-            true
+            writeln!(log, "Connected to database: {}", connection_string)?;
+            Ok(true)
         }
 
-        pub fn disconnect(&self) -> bool {
+        /// Drops one connection, disconnecting with the last one; reports to `log`.
+        pub fn disconnect(&self, log: &mut dyn Write) -> io::Result<bool> {
             let mut is_connected = self.is_connected.lock().unwrap();
             let mut count = self.connection_count.lock().unwrap();
             let conn_str = self.connection_string.lock().unwrap();
 
             if !*is_connected {
-                println!("Not connected to any database.");
-                return false;
+                writeln!(log, "Not connected to any database.")?;
+                return Ok(false);
             }
 
             *count -= 1;
             if *count == 0 {
                 *is_connected = false;
-                println!("Disconnected from database: {}", *conn_str);
+                writeln!(log, "Disconnected from database: {}", *conn_str)?;
             } else {
-                println!("Connection count decreased. Remaining connections: {}", *count);
+                writeln!(log, "Connection count decreased. Remaining connections: {}", *count)?;
             }
 
-            true
+            Ok(true)
         }
 
         pub fn is_connected(&self) -> bool {
@@ -138,34 +139,43 @@ mod once_cell_singleton {
 
     pub fn instance() -> &'static DatabaseConnection {
         static INSTANCE: OnceLock<DatabaseConnection> = OnceLock::new();
-        INSTANCE.get_or_init(|| DatabaseConnection::new())
+        INSTANCE.get_or_init(DatabaseConnection::new)
     }
 }
 
 // ========== Thread-Safe Singleton with Once ==========
 
 // Traditional thread-safe singleton using Once
-mod thread_safe_singleton {
+pub mod thread_safe_singleton {
     use super::*;
+    use rust_lang::testing_patterns::{Clock, SystemClock};
 
     pub struct Logger {
         logs: Mutex<Vec<String>>,
+        clock: Arc<dyn Clock>,
     }
 
     impl Logger {
         fn new() -> Self {
+            Self::with_clock(Arc::new(SystemClock))
+        }
+
+        /// A logger outside the singleton that stamps entries with `clock`'s time, so
+        /// tests can pin timestamps with a `FakeClock`
+        pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
             Logger {
                 logs: Mutex::new(Vec::new()),
+                clock,
             }
         }
 
         pub fn log(&self, message: &str) -> String {
-            let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            let now: chrono::DateTime<chrono::Local> = self.clock.now().into();
+            let timestamp = now.format("%Y-%m-%d %H:%M:%S").to_string();
             let log_entry = format!("{}: {}", timestamp, message);
 
             let mut logs = self.logs.lock().unwrap();
             logs.push(log_entry.clone());
-            println!("{}", log_entry);
 
             log_entry
         }
@@ -186,7 +196,6 @@ mod thread_safe_singleton {
         pub fn clear_logs(&self) -> &str {
             let mut logs = self.logs.lock().unwrap();
             logs.clear();
-            println!("Logs cleared");
             "Logs cleared"
         }
     }
@@ -196,12 +205,14 @@ mod thread_safe_singleton {
         static mut INSTANCE: Option<Logger> = None;
         static ONCE: Once = Once::new();
 
+        // SAFETY: INSTANCE is written exactly once, inside call_once, and only
+        // read after call_once has returned on this thread.
         unsafe {
             ONCE.call_once(|| {
                 INSTANCE = Some(Logger::new());
             });
 
-            INSTANCE.as_ref().unwrap()
+            (*std::ptr::addr_of!(INSTANCE)).as_ref().unwrap()
         }
     }
 }
@@ -209,7 +220,7 @@ mod thread_safe_singleton {
 // ========== Arc-Mutex Singleton Implementation ==========
 
 // A more idiomatic Rust approach using Arc and Mutex
-mod arc_mutex_singleton {
+pub mod arc_mutex_singleton {
     use super::*;
 
     #[derive(Debug, Clone)]
@@ -238,7 +249,6 @@ mod arc_mutex_singleton {
         pub fn set_config(&self, key: &str, value: &str) -> HashMap<String, String> {
             let mut config = self.config.lock().unwrap();
             config.insert(key.to_string(), value.to_string());
-            println!("Configuration updated: {} = {}", key, value);
             config.clone()
         }
 
@@ -249,7 +259,6 @@ mod arc_mutex_singleton {
             config.insert("language".to_string(), "en".to_string());
             config.insert("notifications".to_string(), "true".to_string());
             config.insert("auto_save".to_string(), "true".to_string());
-            println!("Configuration reset to defaults");
             config.clone()
         }
     }
@@ -259,14 +268,14 @@ mod arc_mutex_singleton {
 
     pub fn instance() -> &'static ConfigManager {
         static INSTANCE: OnceLock<ConfigManager> = OnceLock::new();
-        INSTANCE.get_or_init(|| ConfigManager::new())
+        INSTANCE.get_or_init(ConfigManager::new)
     }
 }
 
 // ========== User Manager Singleton ==========
 
 // User Manager Singleton implementation
-mod user_manager_singleton {
+pub mod user_manager_singleton {
     use super::*;
     use std::collections::HashMap;
     use chrono::{DateTime, Local};
@@ -375,92 +384,217 @@ mod user_manager_singleton {
 
     pub fn instance() -> &'static UserManager {
         static INSTANCE: OnceLock<UserManager> = OnceLock::new();
-        INSTANCE.get_or_init(|| UserManager::new())
+        INSTANCE.get_or_init(UserManager::new)
     }
 }
 
 // ========== Demo Code ==========
 
-fn demonstrate_singletons() {
-    // Note: In a real application, you'd need to add the lazy_static crate
-    // to your Cargo.toml and uncomment this section
-    /*
-    println!("===== Classic Singleton Demo =====");
-    let singleton1 = &lazy_static_singleton::INSTANCE;
-    let singleton2 = &lazy_static_singleton::INSTANCE;
+fn demonstrate_singletons(out: &mut dyn Write) -> io::Result<()> {
+    // Enable with `--features lazy_static`
+    #[cfg(feature = "lazy_static")]
+    {
+        writeln!(out, "===== Classic Singleton Demo =====")?;
+        let singleton1 = &lazy_static_singleton::INSTANCE;
+        let singleton2 = &lazy_static_singleton::INSTANCE;
 
-    println!("Are instances the same? {}", std::ptr::eq(singleton1, singleton2));
-    println!("Instance timestamp: {:?}", singleton1.get_timestamp());
+        writeln!(out, "Are instances the same? {}", std::ptr::eq(singleton1, singleton2))?;
+        writeln!(out, "Instance timestamp: {:?}", singleton1.get_timestamp())?;
 
-    let config = singleton1.get_config();
-    println!("Original config: api_url = {}", config.get("api_url").unwrap());
+        let config = singleton1.get_config();
+        writeln!(out, "Original config: api_url = {}", config.get("api_url").unwrap())?;
 
-    singleton2.update_config("timeout", "5000");
-    let config = singleton1.get_config();
-    println!("Updated config from singleton1: timeout = {}", config.get("timeout").unwrap());
-    */
+        singleton2.update_config("timeout", "5000");
+        writeln!(out, "Configuration updated: timeout = 5000")?;
+        let config = singleton1.get_config();
+        writeln!(out, "Updated config from singleton1: timeout = {}", config.get("timeout").unwrap())?;
+    }
 
-    println!("\n===== Once Cell Singleton Demo =====");
+    writeln!(out, "\n===== Once Cell Singleton Demo =====")?;
     let db1 = once_cell_singleton::instance();
     let db2 = once_cell_singleton::instance();
 
-    println!("Are instances the same? {}", std::ptr::eq(db1, db2));
+    writeln!(out, "Are instances the same? {}", std::ptr::eq(db1, db2))?;
 
-    db1.connect("mysql://localhost:3306/mydb");
-    db2.connect("mysql://localhost:3306/mydb");
-    println!("Connection count: {}", db1.connection_count());
+    db1.connect("mysql://localhost:3306/mydb", out)?;
+    db2.connect("mysql://localhost:3306/mydb", out)?;
+    writeln!(out, "Connection count: {}", db1.connection_count())?;
 
-    db1.disconnect();
-    println!("Still connected? {}", db2.is_connected());
+    db1.disconnect(out)?;
+    writeln!(out, "Still connected? {}", db2.is_connected())?;
 
-    println!("\n===== Thread-Safe Singleton Demo =====");
+    writeln!(out, "\n===== Thread-Safe Singleton Demo =====")?;
     let logger1 = thread_safe_singleton::get_instance();
     let logger2 = thread_safe_singleton::get_instance();
 
-    println!("Are instances the same? {}", std::ptr::eq(logger1, logger2));
+    writeln!(out, "Are instances the same? {}", std::ptr::eq(logger1, logger2))?;
 
-    logger1.log("Application started");
-    logger1.warn("Resource usage is high");
-    logger2.error("Failed to connect to service");
+    writeln!(out, "{}", logger1.log("Application started"))?;
+    writeln!(out, "{}", logger1.warn("Resource usage is high"))?;
+    writeln!(out, "{}", logger2.error("Failed to connect to service"))?;
 
     let logs = logger2.get_logs();
-    println!("Log entries: {}", logs.len());
+    writeln!(out, "Log entries: {}", logs.len())?;
 
-    println!("\n===== Arc-Mutex Singleton Demo =====");
+    writeln!(out, "\n===== Arc-Mutex Singleton Demo =====")?;
     let config1 = arc_mutex_singleton::instance();
     let config2 = arc_mutex_singleton::instance();
 
-    println!("Are instances the same? {}", std::ptr::eq(config1, config2));
+    writeln!(out, "Are instances the same? {}", std::ptr::eq(config1, config2))?;
 
     let config_settings = config1.get_config();
-    println!("Config value: theme = {}", config_settings.get("theme").unwrap());
+    writeln!(out, "Config value: theme = {}", config_settings.get("theme").unwrap())?;
 
     config2.set_config("theme", "dark");
+    writeln!(out, "Configuration updated: theme = dark")?;
     let config_settings = config1.get_config();
-    println!("Updated config from config1: theme = {}", config_settings.get("theme").unwrap());
+    writeln!(out, "Updated config from config1: theme = {}", config_settings.get("theme").unwrap())?;
 
-    println!("\n===== User Manager Singleton Demo =====");
+    writeln!(out, "\n===== User Manager Singleton Demo =====")?;
     let user_manager1 = user_manager_singleton::instance();
     let user_manager2 = user_manager_singleton::instance();
 
-    println!("Are instances the same? {}", std::ptr::eq(user_manager1, user_manager2));
+    writeln!(out, "Are instances the same? {}", std::ptr::eq(user_manager1, user_manager2))?;
 
     user_manager1.add_user(1, "Alice", "alice@example.com").unwrap();
     user_manager1.add_user(2, "Bob", "bob@example.com").unwrap();
 
-    println!("User count: {}", user_manager2.user_count());
+    writeln!(out, "User count: {}", user_manager2.user_count())?;
 
     if let Some(user) = user_manager2.get_user(1) {
-        println!("User #1: {}, {}", user.name, user.email);
+        writeln!(out, "User #1: {}, {}", user.name, user.email)?;
     }
 
     user_manager2.update_user(1, None, None, Some("admin")).unwrap();
     if let Some(user) = user_manager1.get_user(1) {
-        println!("Updated User #1: {}, {}, {:?}", user.name, user.email, user.role);
+        writeln!(out, "Updated User #1: {}, {}, {:?}", user.name, user.email, user.role)?;
+    }
+
+    Ok(())
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct SingletonPatternDemo;
+
+impl Demo for SingletonPatternDemo {
+    fn name(&self) -> &'static str {
+        "singleton_pattern"
     }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "OnceLock, Once and Arc-Mutex singletons"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        // Run the demo
+        demonstrate_singletons(out)
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&SingletonPatternDemo);
 }
 
-fn main() {
-    // Run the demo
-    demonstrate_singletons();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn once_lock_instance_is_shared() {
+        let a = once_cell_singleton::instance();
+        let b = once_cell_singleton::instance();
+        assert!(std::ptr::eq(a, b));
+    }
+
+    #[test]
+    fn logger_instance_is_shared_across_threads() {
+        let main = thread_safe_singleton::get_instance() as *const _ as usize;
+        let others: Vec<usize> = (0..4)
+            .map(|_| thread::spawn(|| thread_safe_singleton::get_instance() as *const _ as usize))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert!(others.iter().all(|&ptr| ptr == main));
+    }
+
+    #[test]
+    fn logger_prefixes_levels() {
+        let logger = thread_safe_singleton::get_instance();
+        assert!(logger.warn("disk almost full").ends_with(": WARNING: disk almost full"));
+        assert!(logger.error("disk full").ends_with(": ERROR: disk full"));
+        assert!(logger.get_logs().iter().any(|entry| entry.ends_with("ERROR: disk full")));
+    }
+
+    #[test]
+    fn logger_stamps_entries_from_its_clock() {
+        use rust_lang::testing_patterns::{Clock, FakeClock};
+        use std::time::Duration;
+
+        let clock = Arc::new(FakeClock::at_unix(1_700_000_000));
+        let logger = thread_safe_singleton::Logger::with_clock(clock.clone());
+        let stamp = |clock: &FakeClock| {
+            let now: chrono::DateTime<chrono::Local> = clock.now().into();
+            now.format("%Y-%m-%d %H:%M:%S").to_string()
+        };
+
+        let first = stamp(&clock);
+        assert_eq!(logger.log("started"), format!("{}: started", first));
+        clock.advance(Duration::from_secs(90));
+        let second = stamp(&clock);
+        assert_ne!(first, second);
+
+        // One row per level, all at the same frozen instant
+        let cases = [
+            (logger.log("ready"), "ready"),
+            (logger.warn("slow disk"), "WARNING: slow disk"),
+            (logger.error("disk gone"), "ERROR: disk gone"),
+        ];
+        for (entry, message) in cases {
+            assert_eq!(entry, format!("{}: {}", second, message));
+        }
+        assert_eq!(logger.get_logs().len(), 4);
+    }
+
+    #[test]
+    fn config_changes_are_visible_through_every_handle() {
+        let writer = arc_mutex_singleton::instance();
+        let reader = arc_mutex_singleton::instance();
+        assert!(std::ptr::eq(writer, reader));
+
+        writer.set_config("singleton_test_key", "on");
+        assert_eq!(reader.get_config()["singleton_test_key"], "on");
+    }
+
+    #[test]
+    fn user_manager_rejects_duplicates_and_unknown_ids() {
+        let users = user_manager_singleton::instance();
+        // Ids in the 9000 range so the shared instance doesn't collide with other tests.
+        users.add_user(9001, "Ada", "ada@example.com").unwrap();
+        assert!(users.add_user(9001, "Ada", "ada@example.com").is_err());
+        assert!(users.update_user(9999, Some("Nobody"), None, None).is_err());
+        assert!(users.delete_user(9999).is_err());
+
+        users.update_user(9001, None, None, Some("admin")).unwrap();
+        let ada = user_manager_singleton::instance().get_user(9001).unwrap();
+        assert_eq!(ada.role.as_deref(), Some("admin"));
+        assert!(ada.updated_at.is_some());
+
+        users.delete_user(9001).unwrap();
+        assert!(users.get_user(9001).is_none());
+    }
+
+    #[cfg(feature = "lazy_static")]
+    #[test]
+    fn lazy_static_instance_is_shared() {
+        let a: &lazy_static_singleton::ClassicSingleton = &lazy_static_singleton::INSTANCE;
+        let b: &lazy_static_singleton::ClassicSingleton = &lazy_static_singleton::INSTANCE;
+        assert!(std::ptr::eq(a, b));
+        assert_eq!(a.get_config()["retries"], "3");
+    }
 }
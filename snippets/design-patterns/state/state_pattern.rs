@@ -0,0 +1,571 @@
+//! State Pattern Implementation in Rust
+//!
+//! The State Pattern is a behavioral design pattern that lets an object change its
+//! behavior when its internal state changes, as if it had changed class. Each state
+//! decides which requests it accepts and which state comes next.
+//!
+//! This example is a vending machine, built three ways:
+//! - An enum, `VendingState`, with every transition in one `match` on (state, event).
+//!   The compiler checks that the table is complete; this is the usual Rust answer.
+//! - Trait objects, the classic form: each state is a type implementing `State`, the
+//!   machine holds a `Box<dyn State>` and replaces it after each accepted event. States
+//!   only override the events they accept; the trait's defaults reject the rest.
+//! - A typestate machine, `typestate::Vending<S>`: the state is a type parameter and
+//!   each transition consumes the machine and returns it in its next state, so an
+//!   invalid transition is a compile error. A transition whose outcome depends on data
+//!   (the last item sold out) returns an enum of the possible next machines.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== Shared Vocabulary ==========
+
+/// What happens to the machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    InsertCoin,
+    EjectCoin,
+    Select,
+    TakeItem,
+    Restock(u32),
+}
+
+/// An event the current state does not accept, and why
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rejected(pub &'static str);
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// What the machine holds, whichever state it is in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Inventory {
+    pub stock: u32,
+    /// Coins earned from sales
+    pub coins: u32,
+}
+
+// ========== Enum States ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VendingState {
+    Idle,
+    HasCoin,
+    Dispensing,
+    SoldOut,
+}
+
+impl VendingState {
+    /// Where a machine with `stock` items starts
+    pub fn initial(stock: u32) -> Self {
+        if stock > 0 {
+            VendingState::Idle
+        } else {
+            VendingState::SoldOut
+        }
+    }
+
+    /// The whole transition table
+    pub fn on(self, event: Event, inventory: &mut Inventory) -> Result<VendingState, Rejected> {
+        use Event::*;
+        use VendingState::*;
+        match (self, event) {
+            (Idle, InsertCoin) => Ok(HasCoin),
+            (HasCoin, EjectCoin) => Ok(Idle),
+            (HasCoin, Select) => {
+                inventory.stock -= 1;
+                inventory.coins += 1;
+                Ok(Dispensing)
+            }
+            (Dispensing, TakeItem) => Ok(VendingState::initial(inventory.stock)),
+            (Idle | SoldOut, Restock(items)) => {
+                inventory.stock += items;
+                Ok(VendingState::initial(inventory.stock))
+            }
+            (HasCoin, InsertCoin) => Err(Rejected("a coin is already in")),
+            (SoldOut, InsertCoin) => Err(Rejected("sold out, coin returned")),
+            (Idle | SoldOut, EjectCoin) => Err(Rejected("no coin to eject")),
+            (Idle | SoldOut, Select) => Err(Rejected("insert a coin first")),
+            (Dispensing, InsertCoin | EjectCoin | Select | Restock(_)) => Err(Rejected("take your item first")),
+            (Idle | HasCoin | SoldOut, TakeItem) => Err(Rejected("nothing to take")),
+            (HasCoin, Restock(_)) => Err(Rejected("cannot restock during a sale")),
+        }
+    }
+}
+
+/// The enum-based machine
+#[derive(Debug, Clone)]
+pub struct EnumMachine {
+    pub state: VendingState,
+    pub inventory: Inventory,
+}
+
+impl EnumMachine {
+    pub fn new(stock: u32) -> Self {
+        EnumMachine { state: VendingState::initial(stock), inventory: Inventory { stock, coins: 0 } }
+    }
+
+    /// Applies `event`; a rejected event leaves everything as it was
+    pub fn handle(&mut self, event: Event) -> Result<VendingState, Rejected> {
+        self.state = self.state.on(event, &mut self.inventory)?;
+        Ok(self.state)
+    }
+}
+
+// ========== Trait-Object States ==========
+
+pub mod dynamic {
+    //! The classic form: one type per state behind `Box<dyn State>`
+
+    use super::{Event, Inventory, Rejected, VendingState};
+
+    /// Each handler returns the next state; the defaults reject the event
+    pub trait State {
+        fn id(&self) -> VendingState;
+
+        fn insert_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("take your item first"))
+        }
+
+        fn eject_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("no coin to eject"))
+        }
+
+        fn select(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("insert a coin first"))
+        }
+
+        fn take_item(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("nothing to take"))
+        }
+
+        fn restock(&self, _inventory: &mut Inventory, _items: u32) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("take your item first"))
+        }
+    }
+
+    fn initial(stock: u32) -> Box<dyn State> {
+        if stock > 0 {
+            Box::new(Idle)
+        } else {
+            Box::new(SoldOut)
+        }
+    }
+
+    fn restock(inventory: &mut Inventory, items: u32) -> Result<Box<dyn State>, Rejected> {
+        inventory.stock += items;
+        Ok(initial(inventory.stock))
+    }
+
+    pub struct Idle;
+
+    impl State for Idle {
+        fn id(&self) -> VendingState {
+            VendingState::Idle
+        }
+
+        fn insert_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Ok(Box::new(HasCoin))
+        }
+
+        fn restock(&self, inventory: &mut Inventory, items: u32) -> Result<Box<dyn State>, Rejected> {
+            restock(inventory, items)
+        }
+    }
+
+    pub struct HasCoin;
+
+    impl State for HasCoin {
+        fn id(&self) -> VendingState {
+            VendingState::HasCoin
+        }
+
+        fn insert_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("a coin is already in"))
+        }
+
+        fn eject_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Ok(Box::new(Idle))
+        }
+
+        fn select(&self, inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            inventory.stock -= 1;
+            inventory.coins += 1;
+            Ok(Box::new(Dispensing))
+        }
+
+        fn restock(&self, _inventory: &mut Inventory, _items: u32) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("cannot restock during a sale"))
+        }
+    }
+
+    pub struct Dispensing;
+
+    impl State for Dispensing {
+        fn id(&self) -> VendingState {
+            VendingState::Dispensing
+        }
+
+        fn eject_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("take your item first"))
+        }
+
+        fn select(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("take your item first"))
+        }
+
+        fn take_item(&self, inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Ok(initial(inventory.stock))
+        }
+    }
+
+    pub struct SoldOut;
+
+    impl State for SoldOut {
+        fn id(&self) -> VendingState {
+            VendingState::SoldOut
+        }
+
+        fn insert_coin(&self, _inventory: &mut Inventory) -> Result<Box<dyn State>, Rejected> {
+            Err(Rejected("sold out, coin returned"))
+        }
+
+        fn restock(&self, inventory: &mut Inventory, items: u32) -> Result<Box<dyn State>, Rejected> {
+            restock(inventory, items)
+        }
+    }
+
+    /// The machine only forwards events to whatever state it holds
+    pub struct Machine {
+        state: Box<dyn State>,
+        pub inventory: Inventory,
+    }
+
+    impl Machine {
+        pub fn new(stock: u32) -> Self {
+            Machine { state: initial(stock), inventory: Inventory { stock, coins: 0 } }
+        }
+
+        pub fn state(&self) -> VendingState {
+            self.state.id()
+        }
+
+        pub fn handle(&mut self, event: Event) -> Result<VendingState, Rejected> {
+            let inventory = &mut self.inventory;
+            self.state = match event {
+                Event::InsertCoin => self.state.insert_coin(inventory),
+                Event::EjectCoin => self.state.eject_coin(inventory),
+                Event::Select => self.state.select(inventory),
+                Event::TakeItem => self.state.take_item(inventory),
+                Event::Restock(items) => self.state.restock(inventory, items),
+            }?;
+            Ok(self.state.id())
+        }
+    }
+}
+
+// ========== Typestate Machine ==========
+
+pub mod typestate {
+    //! The state as a type parameter: only valid transitions exist as methods.
+    //!
+    //! Selecting before paying does not compile:
+    //!
+    //! ```compile_fail,E0599
+    //! use patterns::state_pattern::typestate::Vending;
+    //! use std::num::NonZeroU32;
+    //!
+    //! let machine = Vending::new(NonZeroU32::new(3).unwrap());
+    //! let machine = machine.select();
+    //! ```
+    //!
+    //! and neither does using a machine after it has moved on:
+    //!
+    //! ```compile_fail,E0382
+    //! use patterns::state_pattern::typestate::Vending;
+    //! use std::num::NonZeroU32;
+    //!
+    //! let idle = Vending::new(NonZeroU32::new(3).unwrap());
+    //! let paid = idle.insert_coin();
+    //! let again = idle.insert_coin();
+    //! ```
+
+    use super::Inventory;
+    use std::num::NonZeroU32;
+
+    #[derive(Debug)]
+    pub struct Idle;
+    #[derive(Debug)]
+    pub struct HasCoin;
+    #[derive(Debug)]
+    pub struct Dispensing;
+    #[derive(Debug)]
+    pub struct SoldOut;
+
+    #[derive(Debug)]
+    pub struct Vending<S> {
+        inventory: Inventory,
+        _state: S,
+    }
+
+    impl<S> Vending<S> {
+        pub fn inventory(&self) -> Inventory {
+            self.inventory
+        }
+
+        fn into_state<T>(self, state: T) -> Vending<T> {
+            Vending { inventory: self.inventory, _state: state }
+        }
+    }
+
+    impl Vending<Idle> {
+        /// A stocked machine; an empty one starts from `Vending::empty`
+        pub fn new(stock: NonZeroU32) -> Self {
+            Vending { inventory: Inventory { stock: stock.get(), coins: 0 }, _state: Idle }
+        }
+
+        pub fn insert_coin(self) -> Vending<HasCoin> {
+            self.into_state(HasCoin)
+        }
+
+        pub fn restock(mut self, items: u32) -> Vending<Idle> {
+            self.inventory.stock += items;
+            self
+        }
+    }
+
+    impl Vending<HasCoin> {
+        pub fn eject_coin(self) -> Vending<Idle> {
+            self.into_state(Idle)
+        }
+
+        pub fn select(mut self) -> Vending<Dispensing> {
+            // Only an `Idle` machine takes a coin, and it is never idle with no stock
+            self.inventory.stock -= 1;
+            self.inventory.coins += 1;
+            self.into_state(Dispensing)
+        }
+    }
+
+    /// Where taking an item leads depends on what is left
+    #[derive(Debug)]
+    pub enum AfterTake {
+        Idle(Vending<Idle>),
+        SoldOut(Vending<SoldOut>),
+    }
+
+    impl Vending<Dispensing> {
+        pub fn take_item(self) -> AfterTake {
+            if self.inventory.stock > 0 {
+                AfterTake::Idle(self.into_state(Idle))
+            } else {
+                AfterTake::SoldOut(self.into_state(SoldOut))
+            }
+        }
+    }
+
+    impl Vending<SoldOut> {
+        pub fn empty() -> Self {
+            Vending { inventory: Inventory::default(), _state: SoldOut }
+        }
+
+        /// A non-zero count, so restocking always leaves the machine ready to sell
+        pub fn restock(mut self, items: NonZeroU32) -> Vending<Idle> {
+            self.inventory.stock += items.get();
+            self.into_state(Idle)
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct StatePatternDemo;
+
+impl Demo for StatePatternDemo {
+    fn name(&self) -> &'static str {
+        "state_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Vending machine as an enum, as trait-object states and as a typestate"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let script = [
+            Event::Select,
+            Event::InsertCoin,
+            Event::InsertCoin,
+            Event::Select,
+            Event::Restock(5),
+            Event::TakeItem,
+            Event::InsertCoin,
+            Event::EjectCoin,
+            Event::InsertCoin,
+            Event::Select,
+            Event::TakeItem,
+            Event::InsertCoin,
+            Event::Restock(1),
+        ];
+
+        writeln!(out, "===== Enum States =====")?;
+        let mut machine = EnumMachine::new(2);
+        for event in script {
+            let before = machine.state;
+            match machine.handle(event) {
+                Ok(after) => writeln!(out, "{:<11} {:?} -> {:?}", format!("{:?}", event), before, after)?,
+                Err(why) => writeln!(out, "{:<11} {:?}: rejected, {}", format!("{:?}", event), before, why)?,
+            }
+        }
+        writeln!(out, "Ends {:?} with {:?}", machine.state, machine.inventory)?;
+
+        writeln!(out, "\n===== Trait-Object States =====")?;
+        let mut boxed = dynamic::Machine::new(2);
+        let mut agree = true;
+        let mut enum_machine = EnumMachine::new(2);
+        for event in script {
+            agree &= boxed.handle(event) == enum_machine.handle(event);
+        }
+        writeln!(out, "Same script, same answers as the enum: {}", agree)?;
+        writeln!(out, "Ends {:?} with {:?}", boxed.state(), boxed.inventory)?;
+
+        writeln!(out, "\n===== Typestate =====")?;
+        let machine = typestate::Vending::new(std::num::NonZeroU32::MIN);
+        let machine = machine.insert_coin().eject_coin().insert_coin().select();
+        writeln!(out, "After paying and selecting: {:?}", machine)?;
+        match machine.take_item() {
+            typestate::AfterTake::Idle(idle) => writeln!(out, "Still stocked: {:?}", idle)?,
+            typestate::AfterTake::SoldOut(empty) => {
+                writeln!(out, "Sold out: {:?}", empty)?;
+                let idle = empty.restock(std::num::NonZeroU32::new(3).unwrap());
+                writeln!(out, "Restocked: {:?}", idle)?;
+            }
+        }
+        writeln!(out, "machine.select() on an Idle machine does not compile: no method `select`")?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&StatePatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU32;
+    use Event::*;
+    use VendingState::*;
+
+    /// Events that walk a machine stocked with 2 items from `Idle` to `state`
+    fn path_to(state: VendingState) -> (u32, &'static [Event]) {
+        match state {
+            Idle => (2, &[]),
+            HasCoin => (2, &[InsertCoin]),
+            Dispensing => (2, &[InsertCoin, Select]),
+            SoldOut => (0, &[]),
+        }
+    }
+
+    /// Every (state, event) pair and its outcome
+    fn transition_table() -> Vec<(VendingState, Event, Result<VendingState, &'static str>)> {
+        vec![
+            (Idle, InsertCoin, Ok(HasCoin)),
+            (Idle, EjectCoin, Err("no coin to eject")),
+            (Idle, Select, Err("insert a coin first")),
+            (Idle, TakeItem, Err("nothing to take")),
+            (Idle, Restock(1), Ok(Idle)),
+            (HasCoin, InsertCoin, Err("a coin is already in")),
+            (HasCoin, EjectCoin, Ok(Idle)),
+            (HasCoin, Select, Ok(Dispensing)),
+            (HasCoin, TakeItem, Err("nothing to take")),
+            (HasCoin, Restock(1), Err("cannot restock during a sale")),
+            (Dispensing, InsertCoin, Err("take your item first")),
+            (Dispensing, EjectCoin, Err("take your item first")),
+            (Dispensing, Select, Err("take your item first")),
+            (Dispensing, TakeItem, Ok(Idle)),
+            (Dispensing, Restock(1), Err("take your item first")),
+            (SoldOut, InsertCoin, Err("sold out, coin returned")),
+            (SoldOut, EjectCoin, Err("no coin to eject")),
+            (SoldOut, Select, Err("insert a coin first")),
+            (SoldOut, TakeItem, Err("nothing to take")),
+            (SoldOut, Restock(1), Ok(Idle)),
+        ]
+    }
+
+    #[test]
+    fn enum_machine_follows_the_transition_table() {
+        for (from, event, expected) in transition_table() {
+            let (stock, path) = path_to(from);
+            let mut machine = EnumMachine::new(stock);
+            for &step in path {
+                machine.handle(step).unwrap();
+            }
+            assert_eq!(machine.state, from);
+
+            let result = machine.handle(event);
+            assert_eq!(result.map_err(|why| why.0), expected, "{:?} on {:?}", from, event);
+            assert_eq!(machine.state, expected.unwrap_or(from), "{:?} on {:?}", from, event);
+        }
+    }
+
+    #[test]
+    fn trait_object_machine_follows_the_transition_table() {
+        for (from, event, expected) in transition_table() {
+            let (stock, path) = path_to(from);
+            let mut machine = dynamic::Machine::new(stock);
+            for &step in path {
+                machine.handle(step).unwrap();
+            }
+            assert_eq!(machine.state(), from);
+
+            let result = machine.handle(event);
+            assert_eq!(result.map_err(|why| why.0), expected, "{:?} on {:?}", from, event);
+            assert_eq!(machine.state(), expected.unwrap_or(from), "{:?} on {:?}", from, event);
+        }
+    }
+
+    #[test]
+    fn selling_the_last_item_sells_out() {
+        let sale = [InsertCoin, Select, TakeItem];
+        let mut by_enum = EnumMachine::new(1);
+        let mut by_trait = dynamic::Machine::new(1);
+        for event in sale {
+            assert_eq!(by_enum.handle(event), by_trait.handle(event));
+        }
+        assert_eq!((by_enum.state, by_trait.state()), (SoldOut, SoldOut));
+        assert_eq!(by_enum.inventory, Inventory { stock: 0, coins: 1 });
+        assert_eq!(by_trait.inventory, by_enum.inventory);
+        // Restocking nothing leaves it sold out
+        assert_eq!(by_enum.handle(Restock(0)), Ok(SoldOut));
+        assert_eq!(by_trait.handle(Restock(0)), Ok(SoldOut));
+    }
+
+    #[test]
+    fn typestate_machine_sells_until_empty_then_restocks() {
+        let mut machine = typestate::Vending::new(NonZeroU32::new(2).unwrap());
+        for expected_left in [1, 0] {
+            match machine.insert_coin().select().take_item() {
+                typestate::AfterTake::Idle(idle) => {
+                    assert_eq!(idle.inventory().stock, expected_left);
+                    machine = idle;
+                }
+                typestate::AfterTake::SoldOut(empty) => {
+                    assert_eq!(expected_left, 0);
+                    assert_eq!(empty.inventory(), Inventory { stock: 0, coins: 2 });
+                    machine = empty.restock(NonZeroU32::MIN);
+                }
+            }
+        }
+        assert_eq!(machine.inventory(), Inventory { stock: 1, coins: 2 });
+        assert_eq!(typestate::Vending::empty().inventory().stock, 0);
+    }
+}
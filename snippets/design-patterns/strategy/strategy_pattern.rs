@@ -0,0 +1,351 @@
+//! Strategy Pattern Implementation in Rust
+//!
+//! The Strategy Pattern is a behavioral design pattern that defines a family of
+//! algorithms, puts each behind a common interface and lets the client pick one at
+//! runtime, without the code that uses it knowing which.
+//!
+//! This example wraps the sorts from the `sorting-algorithms` crate behind a
+//! `SortStrategy` trait, with two kinds of context:
+//! - `Sorter` holds a `Box<dyn SortStrategy>`, so the strategy can be chosen from input
+//!   or configuration and swapped while the program runs, at the cost of a virtual call
+//! - `StaticSorter<S: SortStrategy>` fixes the strategy at compile time; each `S` gets
+//!   its own monomorphized copy and the call can be inlined
+//!
+//! A plain `fn(&[i32]) -> Vec<i32>` is already a strategy in Rust, so `FnStrategy`
+//! adapts any such function, and `StepCounting` decorates a traced sort to report how
+//! much work it did.
+
+use demo::Demo;
+use sorting_algorithms::{self as sorts, SortAlgorithm, SortStep};
+use std::cell::Cell;
+use std::io::{self, Write};
+
+// ========== Strategy Trait ==========
+
+/// One way of sorting
+pub trait SortStrategy {
+    fn name(&self) -> &str;
+
+    /// Returns a sorted copy, leaving `data` as it was
+    fn sort(&self, data: &[i32]) -> Vec<i32>;
+}
+
+// ========== Concrete Strategies ==========
+
+pub struct InsertionSort;
+
+impl SortStrategy for InsertionSort {
+    fn name(&self) -> &str {
+        "insertion"
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        sorts::insertion_sort(data)
+    }
+}
+
+pub struct MergeSort;
+
+impl SortStrategy for MergeSort {
+    fn name(&self) -> &str {
+        "merge"
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        sorts::merge_sort(data)
+    }
+}
+
+pub struct QuickSort;
+
+impl SortStrategy for QuickSort {
+    fn name(&self) -> &str {
+        "quick"
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        sorts::quick_sort(data)
+    }
+}
+
+/// Linear time, but memory grows with the range of the values rather than their count
+pub struct CountingSort;
+
+impl SortStrategy for CountingSort {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        sorts::counting_sort(data)
+    }
+}
+
+/// A strategy carrying its own configuration
+pub struct BucketSort {
+    pub buckets: usize,
+}
+
+impl SortStrategy for BucketSort {
+    fn name(&self) -> &str {
+        "bucket"
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        sorts::bucket_sort(data, self.buckets)
+    }
+}
+
+/// Any sorting function, with a name for reports
+pub struct FnStrategy {
+    pub name: &'static str,
+    pub sort: fn(&[i32]) -> Vec<i32>,
+}
+
+impl SortStrategy for FnStrategy {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        (self.sort)(data)
+    }
+}
+
+/// Runs a traced sort and remembers how many compares and writes the last call took
+pub struct StepCounting {
+    algorithm: SortAlgorithm,
+    compares: Cell<usize>,
+    writes: Cell<usize>,
+}
+
+impl StepCounting {
+    pub fn new(algorithm: SortAlgorithm) -> Self {
+        StepCounting { algorithm, compares: Cell::new(0), writes: Cell::new(0) }
+    }
+
+    /// Compares and swaps-or-sets made by the most recent `sort`
+    pub fn last_counts(&self) -> (usize, usize) {
+        (self.compares.get(), self.writes.get())
+    }
+}
+
+impl SortStrategy for StepCounting {
+    fn name(&self) -> &str {
+        self.algorithm.name()
+    }
+
+    fn sort(&self, data: &[i32]) -> Vec<i32> {
+        let (mut compares, mut writes) = (0, 0);
+        let sorted = sorts::sort_traced(self.algorithm, data, |step| match step {
+            SortStep::Compare(..) => compares += 1,
+            SortStep::Swap(..) | SortStep::Set(..) => writes += 1,
+        });
+        self.compares.set(compares);
+        self.writes.set(writes);
+        sorted
+    }
+}
+
+// ========== Contexts ==========
+
+/// Holds a strategy chosen at runtime and can switch to another
+pub struct Sorter {
+    strategy: Box<dyn SortStrategy>,
+}
+
+impl Sorter {
+    pub fn new(strategy: Box<dyn SortStrategy>) -> Self {
+        Sorter { strategy }
+    }
+
+    pub fn set_strategy(&mut self, strategy: Box<dyn SortStrategy>) {
+        self.strategy = strategy;
+    }
+
+    pub fn strategy_name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    pub fn sort(&self, data: &[i32]) -> Vec<i32> {
+        self.strategy.sort(data)
+    }
+}
+
+/// Holds a strategy fixed at compile time
+pub struct StaticSorter<S: SortStrategy> {
+    strategy: S,
+}
+
+impl<S: SortStrategy> StaticSorter<S> {
+    pub fn new(strategy: S) -> Self {
+        StaticSorter { strategy }
+    }
+
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    pub fn sort(&self, data: &[i32]) -> Vec<i32> {
+        self.strategy.sort(data)
+    }
+}
+
+/// Below this many values, insertion sort's low overhead beats the O(n log n) sorts
+pub const SMALL_INPUT: usize = 16;
+
+/// Picks a strategy from what the data looks like: insertion sort for short inputs,
+/// counting sort when the values span a range no wider than the input is long, and
+/// quicksort otherwise
+pub fn choose_strategy(data: &[i32]) -> Box<dyn SortStrategy> {
+    if data.len() < SMALL_INPUT {
+        return Box::new(InsertionSort);
+    }
+    let (min, max) = data.iter().fold((i32::MAX, i32::MIN), |(lo, hi), &x| (lo.min(x), hi.max(x)));
+    if (i64::from(max) - i64::from(min)) < data.len() as i64 {
+        Box::new(CountingSort)
+    } else {
+        Box::new(QuickSort)
+    }
+}
+
+// ========== Demo Code ==========
+
+/// The standard library's pattern-defeating quicksort, as one more strategy
+fn std_sort(data: &[i32]) -> Vec<i32> {
+    let mut sorted = data.to_vec();
+    sorted.sort_unstable();
+    sorted
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct StrategyPatternDemo;
+
+impl Demo for StrategyPatternDemo {
+    fn name(&self) -> &'static str {
+        "strategy_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Sorting strategies from sorting_algorithms behind dyn and generic contexts"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let data = [38, 27, 43, 3, 9, 82, 10];
+
+        writeln!(out, "===== Swapping Strategies at Runtime =====")?;
+        let mut sorter = Sorter::new(Box::new(QuickSort));
+        writeln!(out, "{:<9} {:?}", sorter.strategy_name(), sorter.sort(&data))?;
+        let strategies: Vec<Box<dyn SortStrategy>> = vec![
+            Box::new(MergeSort),
+            Box::new(BucketSort { buckets: 3 }),
+            Box::new(FnStrategy { name: "heap (fn)", sort: sorts::heap_sort }),
+            Box::new(FnStrategy { name: "std", sort: std_sort }),
+        ];
+        for strategy in strategies {
+            sorter.set_strategy(strategy);
+            writeln!(out, "{:<9} {:?}", sorter.strategy_name(), sorter.sort(&data))?;
+        }
+
+        writeln!(out, "\n===== Choosing From the Input =====")?;
+        let long_random: Vec<i32> = (0..40).map(|i| (i * 7919) % 1000).collect();
+        let narrow_range: Vec<i32> = (0..40).map(|i| i % 5).collect();
+        for (label, input) in [("7 values", data.to_vec()), ("40 spread out", long_random), ("40 in 0..5", narrow_range)] {
+            let sorter = Sorter::new(choose_strategy(&input));
+            let sorted = sorter.sort(&input);
+            writeln!(out, "{:<14} -> {:<9} sorted: {}", label, sorter.strategy_name(), sorted.is_sorted())?;
+        }
+
+        writeln!(out, "\n===== Generic Context =====")?;
+        let sorter = StaticSorter::new(MergeSort);
+        writeln!(out, "StaticSorter<MergeSort>: {:?}", sorter.sort(&data))?;
+        writeln!(
+            out,
+            "size_of StaticSorter<MergeSort> = {}, Sorter = {} (a fat pointer)",
+            std::mem::size_of::<StaticSorter<MergeSort>>(),
+            std::mem::size_of::<Sorter>()
+        )?;
+
+        writeln!(out, "\n===== Counting the Work =====")?;
+        let reversed: Vec<i32> = (1..=10).rev().collect();
+        for algorithm in [SortAlgorithm::Bubble, SortAlgorithm::Insertion, SortAlgorithm::Merge, SortAlgorithm::Quick] {
+            let sorter = StaticSorter::new(StepCounting::new(algorithm));
+            sorter.sort(&reversed);
+            let (compares, writes) = sorter.strategy().last_counts();
+            writeln!(out, "{:<9} on 10 reversed values: {:>2} compares, {:>2} writes", algorithm.name(), compares, writes)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&StrategyPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: [i32; 9] = [5, -3, 9, 0, 5, 12, -7, 1, 2];
+    const SORTED: [i32; 9] = [-7, -3, 0, 1, 2, 5, 5, 9, 12];
+
+    fn all_strategies() -> Vec<Box<dyn SortStrategy>> {
+        vec![
+            Box::new(InsertionSort),
+            Box::new(MergeSort),
+            Box::new(QuickSort),
+            Box::new(CountingSort),
+            Box::new(BucketSort { buckets: 4 }),
+            Box::new(FnStrategy { name: "radix", sort: sorts::radix_sort }),
+            Box::new(StepCounting::new(SortAlgorithm::Shell)),
+        ]
+    }
+
+    #[test]
+    fn every_strategy_sorts_the_same_input_the_same_way() {
+        for strategy in all_strategies() {
+            assert_eq!(strategy.sort(&INPUT), SORTED, "{}", strategy.name());
+        }
+    }
+
+    #[test]
+    fn sorter_switches_strategy_in_place() {
+        let mut sorter = Sorter::new(Box::new(InsertionSort));
+        assert_eq!(sorter.strategy_name(), "insertion");
+        sorter.set_strategy(Box::new(BucketSort { buckets: 2 }));
+        assert_eq!(sorter.strategy_name(), "bucket");
+        assert_eq!(sorter.sort(&INPUT), SORTED);
+    }
+
+    #[test]
+    fn static_sorter_uses_its_type_parameter() {
+        let sorter: StaticSorter<QuickSort> = StaticSorter::new(QuickSort);
+        assert_eq!(sorter.sort(&INPUT), SORTED);
+        assert_eq!(std::mem::size_of::<StaticSorter<QuickSort>>(), 0);
+    }
+
+    #[test]
+    fn choose_strategy_matches_the_input_shape() {
+        let spread: Vec<i32> = (0..100).map(|i| i * 1000).collect();
+        let narrow: Vec<i32> = (0..100).map(|i| i % 10 - 5).collect();
+        assert_eq!(choose_strategy(&[3, 1, 2]).name(), "insertion");
+        assert_eq!(choose_strategy(&spread).name(), "quick");
+        assert_eq!(choose_strategy(&narrow).name(), "counting");
+        // A wide range must not overflow the span computation
+        let extremes: Vec<i32> = (0..SMALL_INPUT as i32).map(|i| if i % 2 == 0 { i32::MIN } else { i32::MAX }).collect();
+        assert_eq!(choose_strategy(&extremes).name(), "quick");
+    }
+
+    #[test]
+    fn step_counting_reports_the_last_sort() {
+        let counter = StepCounting::new(SortAlgorithm::Bubble);
+        counter.sort(&[3, 2, 1]);
+        assert_eq!(counter.last_counts(), (3, 3));
+        counter.sort(&[1, 2, 3]);
+        assert_eq!(counter.last_counts().1, 0);
+    }
+}
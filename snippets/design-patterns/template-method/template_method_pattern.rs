@@ -0,0 +1,555 @@
+//! Template Method Pattern Implementation in Rust
+//!
+//! The Template Method Pattern is a behavioral design pattern that fixes the skeleton of
+//! an algorithm in one place and lets implementations fill in or adjust individual
+//! steps, without being able to reorder them.
+//!
+//! This example is a data pipeline, read → parse → validate → write:
+//! - `DataPipeline::run` is the template method, a provided trait method that calls the
+//!   steps in order and collects a `Report`
+//! - Required steps: `read`, `parse` and `write`, which every format has to supply
+//! - Hooks with defaults: `validate` (accept everything), `on_invalid` (skip the record)
+//!   and `on_step` (do nothing), overridden only when a pipeline needs to
+//! - `CsvPipeline` and `JsonPipeline` turn the same sensor readings into normalized output
+//!
+//! Rust has no `final`, so an implementation could still override `run` itself. When that
+//! matters, move the skeleton into a free function or a blanket-implemented extension
+//! trait; here it stays on the trait so the whole pattern reads in one place.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== The Template ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Read,
+    Parse,
+    Validate,
+    Write,
+}
+
+/// What to do with a record that failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnInvalid {
+    Skip,
+    Abort,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineError {
+    Read(String),
+    Parse { line: usize, reason: String },
+    Invalid { record: usize, reason: String },
+    Write(String),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::Read(reason) => write!(f, "read failed: {}", reason),
+            PipelineError::Parse { line, reason } => write!(f, "parse error on line {}: {}", line, reason),
+            PipelineError::Invalid { record, reason } => write!(f, "record {} is invalid: {}", record, reason),
+            PipelineError::Write(reason) => write!(f, "write failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub parsed: usize,
+    /// Record index and the reason it was skipped
+    pub skipped: Vec<(usize, String)>,
+    pub written: usize,
+}
+
+pub trait DataPipeline {
+    type Record;
+
+    fn read(&mut self) -> Result<String, PipelineError>;
+    fn parse(&self, raw: &str) -> Result<Vec<Self::Record>, PipelineError>;
+    /// Returns how many records were written
+    fn write(&mut self, records: &[Self::Record]) -> Result<usize, PipelineError>;
+
+    /// Hook: every record is valid unless overridden
+    fn validate(&self, _record: &Self::Record) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Hook: invalid records are skipped unless overridden
+    fn on_invalid(&mut self, _record: usize, _reason: &str) -> OnInvalid {
+        OnInvalid::Skip
+    }
+
+    /// Hook: called as each step starts
+    fn on_step(&mut self, _step: Step) {}
+
+    /// The template method; implementations supply the steps, not the order
+    fn run(&mut self) -> Result<Report, PipelineError> {
+        self.on_step(Step::Read);
+        let raw = self.read()?;
+
+        self.on_step(Step::Parse);
+        let records = self.parse(&raw)?;
+        let mut report = Report { parsed: records.len(), ..Report::default() };
+
+        self.on_step(Step::Validate);
+        let mut valid = Vec::with_capacity(records.len());
+        for (index, record) in records.into_iter().enumerate() {
+            match self.validate(&record) {
+                Ok(()) => valid.push(record),
+                Err(reason) => match self.on_invalid(index, &reason) {
+                    OnInvalid::Skip => report.skipped.push((index, reason)),
+                    OnInvalid::Abort => return Err(PipelineError::Invalid { record: index, reason }),
+                },
+            }
+        }
+
+        self.on_step(Step::Write);
+        report.written = self.write(&valid)?;
+        Ok(report)
+    }
+}
+
+// ========== Shared Record Type ==========
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub sensor: String,
+    pub celsius: f64,
+}
+
+/// Plausible for an indoor or outdoor sensor
+pub const CELSIUS_RANGE: std::ops::RangeInclusive<f64> = -50.0..=60.0;
+
+fn check_reading(reading: &Reading) -> Result<(), String> {
+    if reading.sensor.is_empty() {
+        return Err("missing sensor name".to_string());
+    }
+    if !CELSIUS_RANGE.contains(&reading.celsius) {
+        return Err(format!("{} °C is out of range", reading.celsius));
+    }
+    Ok(())
+}
+
+// ========== CSV Pipeline ==========
+
+/// Reads `sensor,celsius` rows; skips implausible readings
+pub struct CsvPipeline {
+    input: String,
+    pub output: Vec<String>,
+}
+
+impl CsvPipeline {
+    pub const HEADER: &'static str = "sensor,celsius";
+
+    pub fn new(input: &str) -> Self {
+        CsvPipeline { input: input.to_string(), output: Vec::new() }
+    }
+}
+
+impl DataPipeline for CsvPipeline {
+    type Record = Reading;
+
+    fn read(&mut self) -> Result<String, PipelineError> {
+        if self.input.trim().is_empty() {
+            return Err(PipelineError::Read("no input".to_string()));
+        }
+        Ok(self.input.clone())
+    }
+
+    fn parse(&self, raw: &str) -> Result<Vec<Reading>, PipelineError> {
+        let mut lines = raw.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+        match lines.next() {
+            Some((_, Self::HEADER)) => {}
+            _ => return Err(PipelineError::Parse { line: 1, reason: format!("expected header {:?}", Self::HEADER) }),
+        }
+        lines
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line, text)| {
+                let parse_error = |reason: String| PipelineError::Parse { line, reason };
+                let (sensor, celsius) = text.split_once(',').ok_or_else(|| parse_error("expected two columns".to_string()))?;
+                let celsius = celsius.trim().parse().map_err(|_| parse_error(format!("{:?} is not a number", celsius)))?;
+                Ok(Reading { sensor: sensor.trim().to_string(), celsius })
+            })
+            .collect()
+    }
+
+    fn validate(&self, reading: &Reading) -> Result<(), String> {
+        check_reading(reading)
+    }
+
+    fn write(&mut self, readings: &[Reading]) -> Result<usize, PipelineError> {
+        self.output.push(Self::HEADER.to_string());
+        self.output.extend(readings.iter().map(|reading| format!("{},{:.1}", reading.sensor, reading.celsius)));
+        Ok(readings.len())
+    }
+}
+
+// ========== JSON Pipeline ==========
+
+/// Reads an array of `{"sensor": .., "celsius": ..}` objects and writes JSON lines.
+/// Strict pipelines abort on the first invalid reading instead of skipping it
+pub struct JsonPipeline {
+    input: String,
+    strict: bool,
+    pub output: Vec<String>,
+}
+
+impl JsonPipeline {
+    pub fn new(input: &str) -> Self {
+        JsonPipeline { input: input.to_string(), strict: false, output: Vec::new() }
+    }
+
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl DataPipeline for JsonPipeline {
+    type Record = Reading;
+
+    fn read(&mut self) -> Result<String, PipelineError> {
+        Ok(self.input.clone())
+    }
+
+    fn parse(&self, raw: &str) -> Result<Vec<Reading>, PipelineError> {
+        let objects = json::parse_objects(raw).map_err(|(line, reason)| PipelineError::Parse { line, reason })?;
+        objects
+            .into_iter()
+            .map(|(line, fields)| {
+                let mut reading = Reading { sensor: String::new(), celsius: f64::NAN };
+                for (key, value) in fields {
+                    match (key.as_str(), value) {
+                        ("sensor", json::Value::String(sensor)) => reading.sensor = sensor,
+                        ("celsius", json::Value::Number(celsius)) => reading.celsius = celsius,
+                        (key, _) => return Err(PipelineError::Parse { line, reason: format!("unexpected field {:?}", key) }),
+                    }
+                }
+                if reading.celsius.is_nan() {
+                    return Err(PipelineError::Parse { line, reason: "missing \"celsius\"".to_string() });
+                }
+                Ok(reading)
+            })
+            .collect()
+    }
+
+    fn validate(&self, reading: &Reading) -> Result<(), String> {
+        check_reading(reading)
+    }
+
+    fn on_invalid(&mut self, _record: usize, _reason: &str) -> OnInvalid {
+        if self.strict {
+            OnInvalid::Abort
+        } else {
+            OnInvalid::Skip
+        }
+    }
+
+    fn write(&mut self, readings: &[Reading]) -> Result<usize, PipelineError> {
+        self.output.extend(
+            readings.iter().map(|reading| format!("{{\"sensor\":{:?},\"celsius\":{}}}", reading.sensor, reading.celsius)),
+        );
+        Ok(readings.len())
+    }
+}
+
+/// Just enough JSON for an array of flat objects with string and number values
+mod json {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Value {
+        String(String),
+        Number(f64),
+    }
+
+    /// Objects with the line each one starts on
+    pub type Objects = Vec<(usize, Vec<(String, Value)>)>;
+
+    struct Parser<'a> {
+        chars: std::iter::Peekable<std::str::Chars<'a>>,
+        line: usize,
+    }
+
+    impl Parser<'_> {
+        fn skip_whitespace(&mut self) {
+            while let Some(&c) = self.chars.peek() {
+                if !c.is_whitespace() {
+                    break;
+                }
+                if c == '\n' {
+                    self.line += 1;
+                }
+                self.chars.next();
+            }
+        }
+
+        fn next_token(&mut self) -> Option<char> {
+            self.skip_whitespace();
+            self.chars.next()
+        }
+
+        fn expect(&mut self, wanted: char) -> Result<(), (usize, String)> {
+            match self.next_token() {
+                Some(c) if c == wanted => Ok(()),
+                Some(c) => Err((self.line, format!("expected {:?}, found {:?}", wanted, c))),
+                None => Err((self.line, format!("expected {:?}, found the end", wanted))),
+            }
+        }
+
+        /// After the opening quote
+        fn string(&mut self) -> Result<String, (usize, String)> {
+            let mut text = String::new();
+            loop {
+                match self.chars.next() {
+                    Some('"') => return Ok(text),
+                    Some('\\') => match self.chars.next() {
+                        Some(c @ ('"' | '\\' | '/')) => text.push(c),
+                        Some('n') => text.push('\n'),
+                        other => return Err((self.line, format!("unsupported escape {:?}", other))),
+                    },
+                    Some(c) => text.push(c),
+                    None => return Err((self.line, "unterminated string".to_string())),
+                }
+            }
+        }
+
+        fn value(&mut self) -> Result<Value, (usize, String)> {
+            self.skip_whitespace();
+            if self.chars.peek() == Some(&'"') {
+                self.chars.next();
+                return self.string().map(Value::String);
+            }
+            let mut number = String::new();
+            while let Some(&c) = self.chars.peek() {
+                if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                    break;
+                }
+                number.push(c);
+                self.chars.next();
+            }
+            number.parse().map(Value::Number).map_err(|_| (self.line, format!("expected a string or number, found {:?}", number)))
+        }
+
+        fn object(&mut self) -> Result<Vec<(String, Value)>, (usize, String)> {
+            let mut fields = Vec::new();
+            self.skip_whitespace();
+            if self.chars.peek() == Some(&'}') {
+                self.chars.next();
+                return Ok(fields);
+            }
+            loop {
+                self.expect('"')?;
+                let key = self.string()?;
+                self.expect(':')?;
+                fields.push((key, self.value()?));
+                match self.next_token() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(fields),
+                    other => return Err((self.line, format!("expected ',' or '}}', found {:?}", other))),
+                }
+            }
+        }
+    }
+
+    pub fn parse_objects(text: &str) -> Result<Objects, (usize, String)> {
+        let mut parser = Parser { chars: text.chars().peekable(), line: 1 };
+        let mut objects = Vec::new();
+        parser.expect('[')?;
+        parser.skip_whitespace();
+        if parser.chars.peek() == Some(&']') {
+            parser.chars.next();
+        } else {
+            loop {
+                parser.expect('{')?;
+                let line = parser.line;
+                objects.push((line, parser.object()?));
+                match parser.next_token() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    other => return Err((parser.line, format!("expected ',' or ']', found {:?}", other))),
+                }
+            }
+        }
+        match parser.next_token() {
+            None => Ok(objects),
+            Some(c) => Err((parser.line, format!("unexpected {:?} after the array", c))),
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+const CSV_INPUT: &str = "sensor,celsius\nkitchen,21.456\ngarage,-3\nsauna,95\nattic,30.05\n";
+
+const JSON_INPUT: &str = r#"[
+  {"sensor": "kitchen", "celsius": 21.5},
+  {"sensor": "", "celsius": 18},
+  {"sensor": "cellar", "celsius": 12.25}
+]"#;
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct TemplateMethodPatternDemo;
+
+impl TemplateMethodPatternDemo {
+    fn show_report(report: &Report, output: &[String], out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "parsed {}, written {}", report.parsed, report.written)?;
+        for (index, reason) in &report.skipped {
+            writeln!(out, "  skipped record {}: {}", index, reason)?;
+        }
+        for line in output {
+            writeln!(out, "  > {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Demo for TemplateMethodPatternDemo {
+    fn name(&self) -> &'static str {
+        "template_method_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "read → parse → validate → write skeleton in a trait, with CSV and JSON pipelines filling in the steps"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== CSV Pipeline =====")?;
+        let mut csv = CsvPipeline::new(CSV_INPUT);
+        let report = csv.run().expect("well-formed CSV");
+        Self::show_report(&report, &csv.output, out)?;
+
+        writeln!(out, "\n===== JSON Pipeline =====")?;
+        let mut json = JsonPipeline::new(JSON_INPUT);
+        let report = json.run().expect("well-formed JSON");
+        Self::show_report(&report, &json.output, out)?;
+
+        writeln!(out, "\n===== Overriding a Hook =====")?;
+        let mut strict = JsonPipeline::new(JSON_INPUT).strict();
+        match strict.run() {
+            Ok(report) => writeln!(out, "unexpectedly wrote {}", report.written)?,
+            Err(error) => writeln!(out, "strict JSON: {} (nothing written: {})", error, strict.output.is_empty())?,
+        }
+
+        writeln!(out, "\n===== Failing Early =====")?;
+        for error in [
+            CsvPipeline::new("").run().unwrap_err(),
+            CsvPipeline::new("name,temp\na,1").run().unwrap_err(),
+            CsvPipeline::new("sensor,celsius\nporch,warm").run().unwrap_err(),
+            JsonPipeline::new("[{\"sensor\": \"a\"}]").run().unwrap_err(),
+        ] {
+            writeln!(out, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&TemplateMethodPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every step and hook call, and rejects odd numbers
+    #[derive(Default)]
+    struct Spy {
+        calls: Vec<String>,
+        abort_on_invalid: bool,
+    }
+
+    impl DataPipeline for Spy {
+        type Record = u32;
+
+        fn read(&mut self) -> Result<String, PipelineError> {
+            self.calls.push("read".into());
+            Ok("2 3 4".into())
+        }
+
+        fn parse(&self, raw: &str) -> Result<Vec<u32>, PipelineError> {
+            Ok(raw.split(' ').map(|n| n.parse().unwrap()).collect())
+        }
+
+        fn validate(&self, n: &u32) -> Result<(), String> {
+            if n % 2 == 0 {
+                Ok(())
+            } else {
+                Err(format!("{} is odd", n))
+            }
+        }
+
+        fn on_invalid(&mut self, record: usize, reason: &str) -> OnInvalid {
+            self.calls.push(format!("on_invalid({}, {})", record, reason));
+            if self.abort_on_invalid {
+                OnInvalid::Abort
+            } else {
+                OnInvalid::Skip
+            }
+        }
+
+        fn on_step(&mut self, step: Step) {
+            self.calls.push(format!("{:?}", step));
+        }
+
+        fn write(&mut self, records: &[u32]) -> Result<usize, PipelineError> {
+            self.calls.push(format!("write{:?}", records));
+            Ok(records.len())
+        }
+    }
+
+    #[test]
+    fn run_calls_steps_and_hooks_in_template_order() {
+        let mut spy = Spy::default();
+        let report = spy.run().unwrap();
+        assert_eq!(spy.calls, ["Read", "read", "Parse", "Validate", "on_invalid(1, 3 is odd)", "Write", "write[2, 4]"]);
+        assert_eq!(report, Report { parsed: 3, skipped: vec![(1, "3 is odd".into())], written: 2 });
+    }
+
+    #[test]
+    fn aborting_from_a_hook_skips_the_write_step() {
+        let mut spy = Spy { abort_on_invalid: true, ..Spy::default() };
+        assert_eq!(spy.run(), Err(PipelineError::Invalid { record: 1, reason: "3 is odd".into() }));
+        assert_eq!(spy.calls.last().map(String::as_str), Some("on_invalid(1, 3 is odd)"));
+        assert!(!spy.calls.iter().any(|call| call.starts_with("write") || call == "Write"));
+    }
+
+    #[test]
+    fn csv_pipeline_normalizes_and_skips_out_of_range_readings() {
+        let mut csv = CsvPipeline::new(CSV_INPUT);
+        let report = csv.run().unwrap();
+        assert_eq!(report.skipped, [(2, "95 °C is out of range".to_string())]);
+        assert_eq!(csv.output, ["sensor,celsius", "kitchen,21.5", "garage,-3.0", "attic,30.1"]);
+        assert_eq!(
+            CsvPipeline::new("sensor,celsius\nok,1\nporch").run(),
+            Err(PipelineError::Parse { line: 3, reason: "expected two columns".into() })
+        );
+    }
+
+    #[test]
+    fn json_pipeline_uses_the_same_skeleton_with_its_own_hooks() {
+        let mut lenient = JsonPipeline::new(JSON_INPUT);
+        let report = lenient.run().unwrap();
+        assert_eq!((report.parsed, report.written), (3, 2));
+        assert_eq!(lenient.output, [r#"{"sensor":"kitchen","celsius":21.5}"#, r#"{"sensor":"cellar","celsius":12.25}"#]);
+
+        let mut strict = JsonPipeline::new(JSON_INPUT).strict();
+        assert_eq!(strict.run(), Err(PipelineError::Invalid { record: 1, reason: "missing sensor name".into() }));
+        assert!(strict.output.is_empty());
+    }
+
+    #[test]
+    fn json_parse_errors_point_at_the_line() {
+        let error = JsonPipeline::new("[\n{\"sensor\": \"a\", \"celsius\": 1},\n{\"sensor\" \"b\"}\n]").run().unwrap_err();
+        assert_eq!(error, PipelineError::Parse { line: 3, reason: "expected ':', found '\"'".into() });
+        assert_eq!(JsonPipeline::new("[]").run().map(|report| report.written), Ok(0));
+        assert!(JsonPipeline::new("[] x").run().is_err());
+    }
+}
@@ -0,0 +1,809 @@
+//! Visitor Pattern Implementation in Rust
+//!
+//! The Visitor Pattern is a behavioral design pattern that separates operations from the
+//! object structure they run over. Each operation is a visitor with one method per kind
+//! of node, and each node "accepts" a visitor by calling the method for its own kind, so
+//! the code that runs depends on both the node and the visitor: double dispatch.
+//!
+//! This example is a small arithmetic and boolean expression language with three
+//! visitors, a pretty-printer, a type-checker and an evaluator, built two ways:
+//! - `Expr`, an enum, with a `Visitor` trait whose methods return an associated `Output`.
+//!   `Expr::accept` is the first dispatch, and it is nothing more than a `match`
+//! - `dynamic`, the classic object-oriented form: one struct per node behind
+//!   `Box<dyn Node>`, each calling back into a `&mut dyn NodeVisitor`
+//!
+//! Which is idiomatic? In Rust the enum wins for a syntax tree, because the set of node
+//! kinds is closed and known up front while new operations keep coming. A `match` is
+//! checked for exhaustiveness, so adding a variant points at every visitor that must
+//! handle it, and visitors can return values instead of stashing them in fields. The
+//! trait-object form pays off only when outside code must add node kinds without
+//! touching this module, which is the one thing an enum cannot offer.
+
+use demo::Demo;
+use std::fmt;
+use std::io::{self, Write};
+
+// ========== The AST ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Eq,
+    And,
+    Or,
+}
+
+impl BinOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Lt => "<",
+            BinOp::Eq => "==",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+        }
+    }
+
+    /// Higher binds tighter
+    fn precedence(self) -> u8 {
+        match self {
+            BinOp::Or => 1,
+            BinOp::And => 2,
+            BinOp::Lt | BinOp::Eq => 3,
+            BinOp::Add | BinOp::Sub => 4,
+            BinOp::Mul | BinOp::Div => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Int(i64),
+    Bool(bool),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn unary(op: UnOp, operand: Expr) -> Self {
+        Expr::Unary(op, Box::new(operand))
+    }
+
+    pub fn binary(op: BinOp, left: Expr, right: Expr) -> Self {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    pub fn if_then_else(condition: Expr, then: Expr, otherwise: Expr) -> Self {
+        Expr::If(Box::new(condition), Box::new(then), Box::new(otherwise))
+    }
+
+    /// The first half of double dispatch: pick the visitor method for this variant
+    pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Output {
+        match self {
+            Expr::Int(value) => visitor.visit_int(*value),
+            Expr::Bool(value) => visitor.visit_bool(*value),
+            Expr::Unary(op, operand) => visitor.visit_unary(*op, operand),
+            Expr::Binary(op, left, right) => visitor.visit_binary(*op, left, right),
+            Expr::If(condition, then, otherwise) => visitor.visit_if(condition, then, otherwise),
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::If(..) => 0,
+            Expr::Binary(op, ..) => op.precedence(),
+            Expr::Unary(..) => 6,
+            Expr::Int(_) | Expr::Bool(_) => 7,
+        }
+    }
+}
+
+/// One operation over every kind of `Expr`
+pub trait Visitor {
+    type Output;
+
+    fn visit_int(&mut self, value: i64) -> Self::Output;
+    fn visit_bool(&mut self, value: bool) -> Self::Output;
+    fn visit_unary(&mut self, op: UnOp, operand: &Expr) -> Self::Output;
+    fn visit_binary(&mut self, op: BinOp, left: &Expr, right: &Expr) -> Self::Output;
+    fn visit_if(&mut self, condition: &Expr, then: &Expr, otherwise: &Expr) -> Self::Output;
+}
+
+// ========== Pretty-Printer ==========
+
+/// Prints with only the parentheses the precedence rules need
+pub struct PrettyPrinter;
+
+impl PrettyPrinter {
+    pub fn print(expr: &Expr) -> String {
+        expr.accept(&mut PrettyPrinter)
+    }
+
+    fn operand(&mut self, expr: &Expr, min_precedence: u8) -> String {
+        let text = expr.accept(self);
+        if expr.precedence() < min_precedence {
+            format!("({})", text)
+        } else {
+            text
+        }
+    }
+}
+
+impl Visitor for PrettyPrinter {
+    type Output = String;
+
+    fn visit_int(&mut self, value: i64) -> String {
+        value.to_string()
+    }
+
+    fn visit_bool(&mut self, value: bool) -> String {
+        value.to_string()
+    }
+
+    fn visit_unary(&mut self, op: UnOp, operand: &Expr) -> String {
+        let symbol = if op == UnOp::Neg { "-" } else { "!" };
+        format!("{}{}", symbol, self.operand(operand, 6))
+    }
+
+    fn visit_binary(&mut self, op: BinOp, left: &Expr, right: &Expr) -> String {
+        let precedence = op.precedence();
+        // Left-associative: `a - b - c` needs no parentheses, `a - (b - c)` does, and
+        // comparisons don't chain at all
+        let left_min = if matches!(op, BinOp::Lt | BinOp::Eq) { precedence + 1 } else { precedence };
+        format!("{} {} {}", self.operand(left, left_min), op.symbol(), self.operand(right, precedence + 1))
+    }
+
+    fn visit_if(&mut self, condition: &Expr, then: &Expr, otherwise: &Expr) -> String {
+        format!("if {} then {} else {}", condition.accept(self), then.accept(self), otherwise.accept(self))
+    }
+}
+
+// ========== Type-Checker ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Int,
+    Bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeError {
+    /// `expr` has type `found` where `expected` was needed
+    Mismatch { expected: Type, found: Type, expr: String },
+    /// The two branches of an `if` disagree
+    Branches { then: Type, otherwise: Type },
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeError::Mismatch { expected, found, expr } => {
+                write!(f, "expected {:?}, found {:?} in `{}`", expected, found, expr)
+            }
+            TypeError::Branches { then, otherwise } => {
+                write!(f, "if branches differ: {:?} and {:?}", then, otherwise)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Works out an expression's type without running it
+pub struct TypeChecker;
+
+impl TypeChecker {
+    pub fn check(expr: &Expr) -> Result<Type, TypeError> {
+        expr.accept(&mut TypeChecker)
+    }
+
+    fn expect(&mut self, expr: &Expr, expected: Type) -> Result<(), TypeError> {
+        let found = expr.accept(self)?;
+        if found == expected {
+            Ok(())
+        } else {
+            Err(TypeError::Mismatch { expected, found, expr: PrettyPrinter::print(expr) })
+        }
+    }
+}
+
+impl Visitor for TypeChecker {
+    type Output = Result<Type, TypeError>;
+
+    fn visit_int(&mut self, _value: i64) -> Self::Output {
+        Ok(Type::Int)
+    }
+
+    fn visit_bool(&mut self, _value: bool) -> Self::Output {
+        Ok(Type::Bool)
+    }
+
+    fn visit_unary(&mut self, op: UnOp, operand: &Expr) -> Self::Output {
+        let ty = if op == UnOp::Neg { Type::Int } else { Type::Bool };
+        self.expect(operand, ty)?;
+        Ok(ty)
+    }
+
+    fn visit_binary(&mut self, op: BinOp, left: &Expr, right: &Expr) -> Self::Output {
+        match op {
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => {
+                self.expect(left, Type::Int)?;
+                self.expect(right, Type::Int)?;
+                Ok(Type::Int)
+            }
+            BinOp::Lt => {
+                self.expect(left, Type::Int)?;
+                self.expect(right, Type::Int)?;
+                Ok(Type::Bool)
+            }
+            BinOp::And | BinOp::Or => {
+                self.expect(left, Type::Bool)?;
+                self.expect(right, Type::Bool)?;
+                Ok(Type::Bool)
+            }
+            BinOp::Eq => {
+                let ty = left.accept(self)?;
+                self.expect(right, ty)?;
+                Ok(Type::Bool)
+            }
+        }
+    }
+
+    fn visit_if(&mut self, condition: &Expr, then: &Expr, otherwise: &Expr) -> Self::Output {
+        self.expect(condition, Type::Bool)?;
+        let (then, otherwise) = (then.accept(self)?, otherwise.accept(self)?);
+        if then == otherwise {
+            Ok(then)
+        } else {
+            Err(TypeError::Branches { then, otherwise })
+        }
+    }
+}
+
+// ========== Evaluator ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// What can still go wrong after type-checking, plus type errors for unchecked input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    DivisionByZero,
+    Overflow,
+    TypeMismatch,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Overflow => write!(f, "integer overflow"),
+            EvalError::TypeMismatch => write!(f, "operand of the wrong type"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Computes a value; `&&`, `||` and `if` only evaluate what they need
+pub struct Evaluator;
+
+impl Evaluator {
+    pub fn eval(expr: &Expr) -> Result<Value, EvalError> {
+        expr.accept(&mut Evaluator)
+    }
+
+    fn int(&mut self, expr: &Expr) -> Result<i64, EvalError> {
+        match expr.accept(self)? {
+            Value::Int(value) => Ok(value),
+            Value::Bool(_) => Err(EvalError::TypeMismatch),
+        }
+    }
+
+    fn bool(&mut self, expr: &Expr) -> Result<bool, EvalError> {
+        match expr.accept(self)? {
+            Value::Bool(value) => Ok(value),
+            Value::Int(_) => Err(EvalError::TypeMismatch),
+        }
+    }
+}
+
+impl Visitor for Evaluator {
+    type Output = Result<Value, EvalError>;
+
+    fn visit_int(&mut self, value: i64) -> Self::Output {
+        Ok(Value::Int(value))
+    }
+
+    fn visit_bool(&mut self, value: bool) -> Self::Output {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_unary(&mut self, op: UnOp, operand: &Expr) -> Self::Output {
+        match op {
+            UnOp::Neg => self.int(operand)?.checked_neg().map(Value::Int).ok_or(EvalError::Overflow),
+            UnOp::Not => Ok(Value::Bool(!self.bool(operand)?)),
+        }
+    }
+
+    fn visit_binary(&mut self, op: BinOp, left: &Expr, right: &Expr) -> Self::Output {
+        let arithmetic = |result: Option<i64>| result.map(Value::Int).ok_or(EvalError::Overflow);
+        match op {
+            BinOp::And => Ok(Value::Bool(self.bool(left)? && self.bool(right)?)),
+            BinOp::Or => Ok(Value::Bool(self.bool(left)? || self.bool(right)?)),
+            BinOp::Eq => Ok(Value::Bool(left.accept(self)? == right.accept(self)?)),
+            BinOp::Lt => Ok(Value::Bool(self.int(left)? < self.int(right)?)),
+            BinOp::Add => arithmetic(self.int(left)?.checked_add(self.int(right)?)),
+            BinOp::Sub => arithmetic(self.int(left)?.checked_sub(self.int(right)?)),
+            BinOp::Mul => arithmetic(self.int(left)?.checked_mul(self.int(right)?)),
+            BinOp::Div => match (self.int(left)?, self.int(right)?) {
+                (_, 0) => Err(EvalError::DivisionByZero),
+                (left, right) => arithmetic(left.checked_div(right)),
+            },
+        }
+    }
+
+    fn visit_if(&mut self, condition: &Expr, then: &Expr, otherwise: &Expr) -> Self::Output {
+        if self.bool(condition)? {
+            then.accept(self)
+        } else {
+            otherwise.accept(self)
+        }
+    }
+}
+
+// ========== No Visitor at All ==========
+
+/// For a one-off traversal over an enum, a recursive `match` is all it takes; the
+/// `Visitor` trait earns its place once several operations share the walking logic
+pub fn depth(expr: &Expr) -> usize {
+    1 + match expr {
+        Expr::Int(_) | Expr::Bool(_) => 0,
+        Expr::Unary(_, operand) => depth(operand),
+        Expr::Binary(_, left, right) => depth(left).max(depth(right)),
+        Expr::If(condition, then, otherwise) => depth(condition).max(depth(then)).max(depth(otherwise)),
+    }
+}
+
+// ========== Trait-Object AST ==========
+
+pub mod dynamic {
+    //! The classic form: every node is its own type behind `Box<dyn Node>`.
+    //!
+    //! `accept` is the first dispatch, through the node's vtable, and the `visit_*` call
+    //! it makes is the second, through the visitor's. `accept` can't be generic over the
+    //! visitor's result type, since generic methods would make `Node` unusable as a
+    //! trait object, so every visitor returns `()` and carries its results in fields: the
+    //! printer appends to a `String` and the evaluator keeps a value stack.
+
+    use super::{BinOp, EvalError, Expr, UnOp, Value};
+
+    pub trait Node {
+        fn accept(&self, visitor: &mut dyn NodeVisitor);
+    }
+
+    pub trait NodeVisitor {
+        fn visit_int(&mut self, node: &IntNode);
+        fn visit_bool(&mut self, node: &BoolNode);
+        fn visit_unary(&mut self, node: &UnaryNode);
+        fn visit_binary(&mut self, node: &BinaryNode);
+        fn visit_if(&mut self, node: &IfNode);
+    }
+
+    pub struct IntNode(pub i64);
+
+    pub struct BoolNode(pub bool);
+
+    pub struct UnaryNode {
+        pub op: UnOp,
+        pub operand: Box<dyn Node>,
+    }
+
+    pub struct BinaryNode {
+        pub op: BinOp,
+        pub left: Box<dyn Node>,
+        pub right: Box<dyn Node>,
+    }
+
+    pub struct IfNode {
+        pub condition: Box<dyn Node>,
+        pub then: Box<dyn Node>,
+        pub otherwise: Box<dyn Node>,
+    }
+
+    impl Node for IntNode {
+        fn accept(&self, visitor: &mut dyn NodeVisitor) {
+            visitor.visit_int(self)
+        }
+    }
+
+    impl Node for BoolNode {
+        fn accept(&self, visitor: &mut dyn NodeVisitor) {
+            visitor.visit_bool(self)
+        }
+    }
+
+    impl Node for UnaryNode {
+        fn accept(&self, visitor: &mut dyn NodeVisitor) {
+            visitor.visit_unary(self)
+        }
+    }
+
+    impl Node for BinaryNode {
+        fn accept(&self, visitor: &mut dyn NodeVisitor) {
+            visitor.visit_binary(self)
+        }
+    }
+
+    impl Node for IfNode {
+        fn accept(&self, visitor: &mut dyn NodeVisitor) {
+            visitor.visit_if(self)
+        }
+    }
+
+    /// Builds the object graph for an `Expr`, so both forms can run on the same input
+    pub fn from_expr(expr: &Expr) -> Box<dyn Node> {
+        match expr {
+            Expr::Int(value) => Box::new(IntNode(*value)),
+            Expr::Bool(value) => Box::new(BoolNode(*value)),
+            Expr::Unary(op, operand) => Box::new(UnaryNode { op: *op, operand: from_expr(operand) }),
+            Expr::Binary(op, left, right) => {
+                Box::new(BinaryNode { op: *op, left: from_expr(left), right: from_expr(right) })
+            }
+            Expr::If(condition, then, otherwise) => Box::new(IfNode {
+                condition: from_expr(condition),
+                then: from_expr(then),
+                otherwise: from_expr(otherwise),
+            }),
+        }
+    }
+
+    /// Parenthesizes every compound expression; knowing a child's precedence would take
+    /// another visitor, since a `dyn Node` can't be matched on
+    #[derive(Default)]
+    pub struct Printer {
+        pub text: String,
+    }
+
+    impl Printer {
+        pub fn print(node: &dyn Node) -> String {
+            let mut printer = Printer::default();
+            node.accept(&mut printer);
+            printer.text
+        }
+    }
+
+    impl NodeVisitor for Printer {
+        fn visit_int(&mut self, node: &IntNode) {
+            self.text += &node.0.to_string();
+        }
+
+        fn visit_bool(&mut self, node: &BoolNode) {
+            self.text += &node.0.to_string();
+        }
+
+        fn visit_unary(&mut self, node: &UnaryNode) {
+            self.text += if node.op == UnOp::Neg { "-" } else { "!" };
+            node.operand.accept(self);
+        }
+
+        fn visit_binary(&mut self, node: &BinaryNode) {
+            self.text += "(";
+            node.left.accept(self);
+            self.text += &format!(" {} ", node.op.symbol());
+            node.right.accept(self);
+            self.text += ")";
+        }
+
+        fn visit_if(&mut self, node: &IfNode) {
+            self.text += "(if ";
+            node.condition.accept(self);
+            self.text += " then ";
+            node.then.accept(self);
+            self.text += " else ";
+            node.otherwise.accept(self);
+            self.text += ")";
+        }
+    }
+
+    /// A stack machine: each visit pushes one value, or records the first error
+    #[derive(Default)]
+    pub struct Evaluator {
+        stack: Vec<Value>,
+        error: Option<EvalError>,
+    }
+
+    impl Evaluator {
+        pub fn eval(node: &dyn Node) -> Result<Value, EvalError> {
+            let mut evaluator = Evaluator::default();
+            node.accept(&mut evaluator);
+            match evaluator.error {
+                Some(error) => Err(error),
+                None => Ok(evaluator.stack.pop().expect("every visit pushes a value")),
+            }
+        }
+
+        /// Visits `node` and takes its value off the stack, or `None` after an error
+        fn value_of(&mut self, node: &dyn Node) -> Option<Value> {
+            if self.error.is_none() {
+                node.accept(self);
+            }
+            if self.error.is_some() {
+                return None;
+            }
+            self.stack.pop()
+        }
+
+        fn int_of(&mut self, node: &dyn Node) -> Option<i64> {
+            match self.value_of(node)? {
+                Value::Int(value) => Some(value),
+                Value::Bool(_) => self.fail(EvalError::TypeMismatch),
+            }
+        }
+
+        fn bool_of(&mut self, node: &dyn Node) -> Option<bool> {
+            match self.value_of(node)? {
+                Value::Bool(value) => Some(value),
+                Value::Int(_) => self.fail(EvalError::TypeMismatch),
+            }
+        }
+
+        fn fail<T>(&mut self, error: EvalError) -> Option<T> {
+            self.error.get_or_insert(error);
+            None
+        }
+
+        fn push(&mut self, value: Option<Value>, or: EvalError) {
+            match value {
+                Some(value) => self.stack.push(value),
+                None => {
+                    self.fail::<()>(or);
+                }
+            }
+        }
+    }
+
+    impl NodeVisitor for Evaluator {
+        fn visit_int(&mut self, node: &IntNode) {
+            self.stack.push(Value::Int(node.0));
+        }
+
+        fn visit_bool(&mut self, node: &BoolNode) {
+            self.stack.push(Value::Bool(node.0));
+        }
+
+        fn visit_unary(&mut self, node: &UnaryNode) {
+            let value = match node.op {
+                UnOp::Neg => self.int_of(&*node.operand).map(|value| value.checked_neg().map(Value::Int)),
+                UnOp::Not => self.bool_of(&*node.operand).map(|value| Some(Value::Bool(!value))),
+            };
+            if let Some(value) = value {
+                self.push(value, EvalError::Overflow);
+            }
+        }
+
+        fn visit_binary(&mut self, node: &BinaryNode) {
+            let (left, right) = (&*node.left, &*node.right);
+            let value = match node.op {
+                BinOp::And => self.bool_of(left).and_then(|l| if l { self.bool_of(right) } else { Some(false) }),
+                BinOp::Or => self.bool_of(left).and_then(|l| if l { Some(true) } else { self.bool_of(right) }),
+                BinOp::Eq => self.value_of(left).and_then(|l| self.value_of(right).map(|r| l == r)),
+                BinOp::Lt => self.int_of(left).and_then(|l| self.int_of(right).map(|r| l < r)),
+                op => {
+                    let Some((l, r)) = self.int_of(left).and_then(|l| self.int_of(right).map(|r| (l, r))) else {
+                        return;
+                    };
+                    if op == BinOp::Div && r == 0 {
+                        self.fail::<()>(EvalError::DivisionByZero);
+                        return;
+                    }
+                    let result = match op {
+                        BinOp::Add => l.checked_add(r),
+                        BinOp::Sub => l.checked_sub(r),
+                        BinOp::Mul => l.checked_mul(r),
+                        _ => l.checked_div(r),
+                    };
+                    self.push(result.map(Value::Int), EvalError::Overflow);
+                    return;
+                }
+            };
+            if let Some(value) = value {
+                self.stack.push(Value::Bool(value));
+            }
+        }
+
+        fn visit_if(&mut self, node: &IfNode) {
+            let branch = match self.bool_of(&*node.condition) {
+                Some(true) => &node.then,
+                Some(false) => &node.otherwise,
+                None => return,
+            };
+            branch.accept(self);
+        }
+    }
+}
+
+// ========== Demo Code ==========
+
+/// `if 1 + 2 * 3 < 10 then (8 - 2) / 3 else -(4 - 1)`
+fn sample() -> Expr {
+    use BinOp::*;
+    let int = Expr::Int;
+    Expr::if_then_else(
+        Expr::binary(Lt, Expr::binary(Add, int(1), Expr::binary(Mul, int(2), int(3))), int(10)),
+        Expr::binary(Div, Expr::binary(Sub, int(8), int(2)), int(3)),
+        Expr::unary(UnOp::Neg, Expr::binary(Sub, int(4), int(1))),
+    )
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct VisitorPatternDemo;
+
+impl Demo for VisitorPatternDemo {
+    fn name(&self) -> &'static str {
+        "visitor_pattern"
+    }
+
+    fn category(&self) -> &'static str {
+        "design-patterns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Printer, type-checker and evaluator visitors over an enum AST and a trait-object AST"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        use BinOp::*;
+        let int = Expr::Int;
+
+        writeln!(out, "===== Three Visitors, One Expression =====")?;
+        let expr = sample();
+        writeln!(out, "print: {}", PrettyPrinter::print(&expr))?;
+        writeln!(out, "type:  {:?}", TypeChecker::check(&expr).expect("sample is well typed"))?;
+        writeln!(out, "value: {}", Evaluator::eval(&expr).expect("sample evaluates"))?;
+        writeln!(out, "depth: {} (a plain match, no visitor)", depth(&expr))?;
+
+        writeln!(out, "\n===== Checked Before Running =====")?;
+        let cases = [
+            Expr::binary(Add, int(1), Expr::Bool(true)),
+            Expr::if_then_else(Expr::Bool(true), int(1), Expr::Bool(false)),
+            Expr::binary(Div, int(10), Expr::binary(Sub, int(5), int(5))),
+            Expr::binary(And, Expr::Bool(false), Expr::binary(Eq, Expr::binary(Div, int(1), int(0)), int(0))),
+        ];
+        for expr in &cases {
+            let verdict = match TypeChecker::check(expr) {
+                Err(error) => format!("type error: {}", error),
+                Ok(_) => match Evaluator::eval(expr) {
+                    Ok(value) => format!("= {}", value),
+                    Err(error) => format!("well typed, but {}", error),
+                },
+            };
+            writeln!(out, "{:<38} {}", PrettyPrinter::print(expr), verdict)?;
+        }
+
+        writeln!(out, "\n===== Trait-Object Nodes =====")?;
+        let nodes = dynamic::from_expr(&expr);
+        writeln!(out, "print: {}", dynamic::Printer::print(&*nodes))?;
+        writeln!(out, "value: {}", dynamic::Evaluator::eval(&*nodes).expect("sample evaluates"))?;
+        let agree = cases.iter().all(|expr| dynamic::Evaluator::eval(&*dynamic::from_expr(expr)) == Evaluator::eval(expr));
+        writeln!(out, "Both evaluators agree on every case above: {}", agree)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&VisitorPatternDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BinOp::*;
+
+    fn int(value: i64) -> Expr {
+        Expr::Int(value)
+    }
+
+    fn bin(op: BinOp, left: Expr, right: Expr) -> Expr {
+        Expr::binary(op, left, right)
+    }
+
+    #[test]
+    fn pretty_printer_adds_only_the_parentheses_precedence_needs() {
+        let cases = [
+            (bin(Sub, bin(Sub, int(1), int(2)), int(3)), "1 - 2 - 3"),
+            (bin(Sub, int(1), bin(Sub, int(2), int(3))), "1 - (2 - 3)"),
+            (bin(Mul, bin(Add, int(1), int(2)), int(3)), "(1 + 2) * 3"),
+            (bin(Add, int(1), bin(Mul, int(2), int(3))), "1 + 2 * 3"),
+            (bin(Eq, bin(Lt, int(1), int(2)), Expr::Bool(true)), "(1 < 2) == true"),
+            (Expr::unary(UnOp::Not, bin(Or, Expr::Bool(true), Expr::Bool(false))), "!(true || false)"),
+            (bin(Add, int(1), Expr::if_then_else(Expr::Bool(true), int(2), int(3))), "1 + (if true then 2 else 3)"),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(PrettyPrinter::print(&expr), expected);
+        }
+        assert_eq!(PrettyPrinter::print(&sample()), "if 1 + 2 * 3 < 10 then (8 - 2) / 3 else -(4 - 1)");
+    }
+
+    #[test]
+    fn type_checker_accepts_well_typed_and_names_the_culprit() {
+        assert_eq!(TypeChecker::check(&sample()), Ok(Type::Int));
+        assert_eq!(TypeChecker::check(&bin(Eq, Expr::Bool(true), Expr::Bool(false))), Ok(Type::Bool));
+        assert_eq!(
+            TypeChecker::check(&bin(Mul, int(2), bin(Lt, int(1), int(2)))),
+            Err(TypeError::Mismatch { expected: Type::Int, found: Type::Bool, expr: "1 < 2".to_string() })
+        );
+        assert_eq!(
+            TypeChecker::check(&Expr::if_then_else(Expr::Bool(true), int(1), Expr::Bool(false))),
+            Err(TypeError::Branches { then: Type::Int, otherwise: Type::Bool })
+        );
+        let mismatch = TypeChecker::check(&bin(Eq, int(1), Expr::Bool(true))).unwrap_err();
+        assert_eq!(mismatch.to_string(), "expected Int, found Bool in `true`");
+    }
+
+    #[test]
+    fn evaluator_follows_precedence_and_short_circuits() {
+        assert_eq!(Evaluator::eval(&sample()), Ok(Value::Int(2)));
+        let divide_by_zero = bin(Eq, bin(Div, int(1), int(0)), int(0));
+        assert_eq!(Evaluator::eval(&bin(And, Expr::Bool(false), divide_by_zero.clone())), Ok(Value::Bool(false)));
+        assert_eq!(Evaluator::eval(&bin(Or, Expr::Bool(true), divide_by_zero.clone())), Ok(Value::Bool(true)));
+        assert_eq!(Evaluator::eval(&Expr::if_then_else(Expr::Bool(false), divide_by_zero, int(7))), Ok(Value::Int(7)));
+    }
+
+    #[test]
+    fn evaluator_reports_what_the_type_checker_cannot_see() {
+        let zero = bin(Div, int(10), bin(Sub, int(5), int(5)));
+        assert_eq!(TypeChecker::check(&zero), Ok(Type::Int));
+        assert_eq!(Evaluator::eval(&zero), Err(EvalError::DivisionByZero));
+        assert_eq!(Evaluator::eval(&bin(Mul, int(i64::MAX), int(2))), Err(EvalError::Overflow));
+        assert_eq!(Evaluator::eval(&Expr::unary(UnOp::Neg, int(i64::MIN))), Err(EvalError::Overflow));
+        assert_eq!(Evaluator::eval(&bin(Add, int(1), Expr::Bool(true))), Err(EvalError::TypeMismatch));
+    }
+
+    #[test]
+    fn trait_object_visitors_agree_with_the_enum_visitors() {
+        let cases = [
+            sample(),
+            bin(And, Expr::Bool(false), bin(Eq, bin(Div, int(1), int(0)), int(0))),
+            bin(Div, int(10), bin(Sub, int(5), int(5))),
+            bin(Add, int(i64::MAX), int(1)),
+            bin(Lt, Expr::Bool(true), int(1)),
+            Expr::unary(UnOp::Not, bin(Eq, int(3), bin(Sub, int(4), int(1)))),
+        ];
+        for expr in &cases {
+            let nodes = dynamic::from_expr(expr);
+            assert_eq!(dynamic::Evaluator::eval(&*nodes), Evaluator::eval(expr), "{}", PrettyPrinter::print(expr));
+        }
+        assert_eq!(
+            dynamic::Printer::print(&*dynamic::from_expr(&sample())),
+            "(if ((1 + (2 * 3)) < 10) then ((8 - 2) / 3) else -(4 - 1))"
+        );
+        assert_eq!(depth(&sample()), 5);
+    }
+}
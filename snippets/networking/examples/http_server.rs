@@ -0,0 +1,3 @@
+fn main() {
+    networking::http_server::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    networking::tcp_echo::run_demo();
+}
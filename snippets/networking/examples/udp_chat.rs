@@ -0,0 +1,3 @@
+fn main() {
+    networking::udp_chat::run_demo();
+}
@@ -0,0 +1,830 @@
+//! A Minimal HTTP/1.1 Server in Rust
+//!
+//! HTTP/1.1 is text on top of TCP: a request line, header lines, a blank line, then a
+//! body whose length the headers announce. Everything here is parsed by hand with
+//! `BufRead`, which makes the protocol's rules, and what goes wrong when a client breaks
+//! them, easy to see.
+//!
+//! This file demonstrates:
+//! - Parsing the request line and headers with limits on line length and header count,
+//!   and reading a `Content-Length` body
+//! - Routing through a map from `(method, path)` to boxed closures, with `405 Method Not
+//!   Allowed` and an `Allow` header when only the method is wrong
+//! - Serving static files with a `Content-Type` picked from the extension, `index.html`
+//!   for directories, and no way out of the root through `..`
+//! - Keep-alive: HTTP/1.1 connections stay open for more requests unless a side says
+//!   `Connection: close`; HTTP/1.0 ones close unless the client asks otherwise
+//! - Errors as proper responses: 400, 403, 404, 405, 413, 431, 501 and 505, after which
+//!   the connection is closed because the request stream can no longer be trusted
+//! - Shutdown the same way as the echo server: stop accepting, then close connections as
+//!   they go idle
+
+use crate::tcp_echo::is_timeout;
+use demo::Demo;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Longest request line or header line accepted
+pub const MAX_LINE: usize = 8 * 1024;
+
+/// Most headers one request may carry
+pub const MAX_HEADERS: usize = 64;
+
+/// Largest request body accepted
+pub const MAX_BODY: usize = 1 << 20;
+
+/// How often an idle connection checks whether it should close
+const IDLE_POLL: Duration = Duration::from_millis(20);
+
+/// A keep-alive connection with no request for this long is closed
+const KEEP_ALIVE_IDLE: Duration = Duration::from_secs(5);
+
+/// How long the client waits for a response
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ========== Requests ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    Http10,
+    Http11,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Percent-decoded path, always starting with `/`
+    pub path: String,
+    pub query: Option<String>,
+    pub version: Version,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// The first header called `name`, compared case-insensitively as HTTP requires
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Whether the client wants the connection kept open after this request
+    pub fn keep_alive(&self) -> bool {
+        let connection = self.header("Connection").map(str::to_ascii_lowercase);
+        match self.version {
+            Version::Http11 => connection.as_deref() != Some("close"),
+            Version::Http10 => connection.as_deref() == Some("keep-alive"),
+        }
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+}
+
+/// Why a request couldn't be read; all but `Io` become an error response
+#[derive(Debug)]
+pub enum ParseError {
+    /// The socket failed or timed out; read timeouts only surface between requests
+    Io(io::Error),
+    BadRequest(String),
+    HeadersTooLarge,
+    BodyTooLarge,
+    NotImplemented(&'static str),
+    VersionNotSupported,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "i/o error: {}", err),
+            ParseError::BadRequest(why) => write!(f, "bad request: {}", why),
+            ParseError::HeadersTooLarge => write!(f, "request headers are too large"),
+            ParseError::BodyTooLarge => write!(f, "request body is too large"),
+            ParseError::NotImplemented(what) => write!(f, "{} is not supported", what),
+            ParseError::VersionNotSupported => write!(f, "only HTTP/1.0 and HTTP/1.1 are supported"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// The response to send before closing the connection, if there is anyone to send it to
+    pub fn response(&self) -> Option<Response> {
+        let status = match self {
+            ParseError::Io(_) => return None,
+            ParseError::BadRequest(_) => 400,
+            ParseError::HeadersTooLarge => 431,
+            ParseError::BodyTooLarge => 413,
+            ParseError::NotImplemented(_) => 501,
+            ParseError::VersionNotSupported => 505,
+        };
+        Some(Response::text(status, format!("{}\n", self)))
+    }
+}
+
+fn bad_request(why: impl Into<String>) -> ParseError {
+    ParseError::BadRequest(why.into())
+}
+
+/// Reads the next request, or `None` if the client closed the connection between requests
+pub fn parse_request(reader: &mut impl BufRead) -> Result<Option<Request>, ParseError> {
+    let mut first = Vec::new();
+    // Tolerate the stray blank lines some clients send between requests
+    loop {
+        if !read_line(reader, &mut first, true)? {
+            return Ok(None);
+        }
+        if !trim_line_ending(&first).is_empty() {
+            break;
+        }
+        first.clear();
+    }
+    let request_line = std::str::from_utf8(trim_line_ending(&first)).map_err(|_| bad_request("request line is not UTF-8"))?;
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return Err(bad_request(format!("malformed request line {:?}", request_line)));
+    };
+    if method.is_empty() || !method.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Err(bad_request(format!("invalid method {:?}", method)));
+    }
+    let version = match version {
+        "HTTP/1.1" => Version::Http11,
+        "HTTP/1.0" => Version::Http10,
+        v if v.starts_with("HTTP/") => return Err(ParseError::VersionNotSupported),
+        v => return Err(bad_request(format!("invalid version {:?}", v))),
+    };
+    if !target.starts_with('/') {
+        return Err(bad_request("request target must be an absolute path"));
+    }
+    let (raw_path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, Some(query.to_string())),
+        None => (target, None),
+    };
+    let path = percent_decode(raw_path).ok_or_else(|| bad_request("invalid percent-encoding in path"))?;
+
+    let mut headers = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        read_line(reader, &mut line, false)?;
+        let header = trim_line_ending(&line);
+        if header.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(ParseError::HeadersTooLarge);
+        }
+        let header = std::str::from_utf8(header).map_err(|_| bad_request("header is not UTF-8"))?;
+        let (name, value) = header.split_once(':').ok_or_else(|| bad_request(format!("header without a colon: {:?}", header)))?;
+        if name.is_empty() || name.bytes().any(|b| b.is_ascii_whitespace()) {
+            return Err(bad_request(format!("invalid header name {:?}", name)));
+        }
+        headers.push((name.to_string(), value.trim().to_string()));
+    }
+    if version == Version::Http11 && find_header(&headers, "Host").is_none() {
+        return Err(bad_request("HTTP/1.1 requests need a Host header"));
+    }
+    if find_header(&headers, "Transfer-Encoding").is_some() {
+        return Err(ParseError::NotImplemented("Transfer-Encoding"));
+    }
+
+    let length = match find_header(&headers, "Content-Length") {
+        Some(value) => value.parse::<usize>().map_err(|_| bad_request(format!("invalid Content-Length {:?}", value)))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(ParseError::BodyTooLarge);
+    }
+    let mut body = vec![0; length];
+    read_body(reader, &mut body)?;
+
+    Ok(Some(Request { method: method.to_string(), path, query, version, headers, body }))
+}
+
+/// Appends one `\n`-terminated line to `line`. `Ok(false)` means the stream ended before
+/// any byte. Read timeouts are returned only when `between_requests` and nothing has been
+/// read; inside a request they are retried.
+fn read_line(reader: &mut impl BufRead, line: &mut Vec<u8>, between_requests: bool) -> Result<bool, ParseError> {
+    loop {
+        let remaining = MAX_LINE.saturating_sub(line.len());
+        if remaining == 0 {
+            return Err(ParseError::HeadersTooLarge);
+        }
+        match reader.by_ref().take(remaining as u64).read_until(b'\n', line) {
+            Ok(0) if line.is_empty() && between_requests => return Ok(false),
+            Ok(0) => return Err(bad_request("connection closed mid-line")),
+            Ok(_) if line.ends_with(b"\n") => return Ok(true),
+            // Either the length limit was hit, caught at the top, or the stream ended,
+            // caught by the next read
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) if is_timeout(&err) && !(between_requests && line.is_empty()) => {}
+            Err(err) => return Err(ParseError::Io(err)),
+        }
+    }
+}
+
+fn read_body(reader: &mut impl Read, body: &mut [u8]) -> Result<(), ParseError> {
+    let mut filled = 0;
+    while filled < body.len() {
+        match reader.read(&mut body[filled..]) {
+            Ok(0) => return Err(bad_request("connection closed inside the body")),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted || is_timeout(&err) => {}
+            Err(err) => return Err(ParseError::Io(err)),
+        }
+    }
+    Ok(())
+}
+
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Decodes `%XX` escapes; `None` for a bad escape or a result that isn't UTF-8
+pub fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+// ========== Responses ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16) -> Self {
+        Response { status, headers: Vec::new(), body: Vec::new() }
+    }
+
+    /// A `text/plain` response
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self::new(status).with_body("text/plain; charset=utf-8", body.into().into_bytes())
+    }
+
+    pub fn with_body(self, content_type: &str, body: Vec<u8>) -> Self {
+        Response { body, ..self.with_header("Content-Type", content_type) }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Status line, headers with `Content-Length` and `Connection` filled in, blank line,
+    /// then the body unless this answers a `HEAD`
+    pub fn write_to(&self, writer: &mut impl Write, keep_alive: bool, include_body: bool) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason_phrase(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        head.push_str(if keep_alive { "Connection: keep-alive\r\n\r\n" } else { "Connection: close\r\n\r\n" });
+        // One write, so the head and a small body share a packet
+        let mut bytes = head.into_bytes();
+        if include_body {
+            bytes.extend_from_slice(&self.body);
+        }
+        writer.write_all(&bytes)?;
+        writer.flush()
+    }
+}
+
+pub fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        505 => "HTTP Version Not Supported",
+        _ => "Unknown",
+    }
+}
+
+/// `Content-Type` for a file, by extension
+pub fn content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("txt" | "md") => "text/plain; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("ico") => "image/x-icon",
+        Some("wasm") => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+// ========== Routing ==========
+
+pub type Handler = Box<dyn Fn(&Request) -> Response + Send + Sync>;
+
+/// Exact `(method, path)` routes, then static files under one prefix, then 404
+#[derive(Default)]
+pub struct Router {
+    routes: HashMap<(String, String), Handler>,
+    static_files: Option<(String, PathBuf)>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(mut self, method: &str, path: &str, handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> Self {
+        self.routes.insert((method.to_string(), path.to_string()), Box::new(handler));
+        self
+    }
+
+    /// Serves files under `root` for paths starting with `prefix`, e.g. `/static/`
+    pub fn static_dir(mut self, prefix: &str, root: impl Into<PathBuf>) -> Self {
+        self.static_files = Some((prefix.to_string(), root.into()));
+        self
+    }
+
+    pub fn handle(&self, request: &Request) -> Response {
+        // HEAD is GET without the body, which `write_to` leaves out
+        let method = if request.method == "HEAD" { "GET" } else { request.method.as_str() };
+        if let Some(handler) = self.routes.get(&(method.to_string(), request.path.clone())) {
+            return handler(request);
+        }
+
+        let mut allowed: Vec<&str> = self.routes.keys().filter(|(_, path)| *path == request.path).map(|(m, _)| m.as_str()).collect();
+        if let Some((prefix, root)) = &self.static_files {
+            if let Some(relative) = request.path.strip_prefix(prefix.as_str()) {
+                if method == "GET" {
+                    return serve_file(root, relative);
+                }
+                allowed.push("GET");
+            }
+        }
+        if allowed.is_empty() {
+            return Response::text(404, format!("no route for {}\n", request.path));
+        }
+        allowed.sort_unstable();
+        allowed.dedup();
+        if allowed.contains(&"GET") {
+            allowed.push("HEAD");
+        }
+        Response::text(405, format!("{} is not allowed here\n", request.method)).with_header("Allow", &allowed.join(", "))
+    }
+}
+
+fn serve_file(root: &Path, relative: &str) -> Response {
+    let mut path = root.to_path_buf();
+    for segment in relative.split('/').filter(|segment| !segment.is_empty() && *segment != ".") {
+        // `..` would climb out of the root, and a backslash does the same on Windows
+        if segment == ".." || segment.contains('\\') {
+            return Response::text(403, "path escapes the static root\n");
+        }
+        path.push(segment);
+    }
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    match fs::read(&path) {
+        Ok(contents) => Response::new(200).with_body(content_type(&path), contents),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Response::text(404, format!("no file /{}\n", relative)),
+        Err(err) => Response::text(500, format!("could not read the file: {}\n", err)),
+    }
+}
+
+// ========== Server ==========
+
+#[derive(Default)]
+struct Shared {
+    shutdown: AtomicBool,
+    requests: AtomicU64,
+}
+
+/// A running HTTP server, one thread per connection; dropping it shuts it down
+pub struct HttpServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl HttpServer {
+    pub fn start(addr: impl ToSocketAddrs, router: Router) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || accept_loop(listener, Arc::new(router), shared))
+        };
+        Ok(HttpServer { addr, shared, acceptor: Some(acceptor) })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Requests answered so far, error responses included
+    pub fn requests_served(&self) -> u64 {
+        self.shared.requests.load(Ordering::SeqCst)
+    }
+
+    /// Stops accepting, waits for open connections to finish or go idle, and returns the
+    /// number of requests served
+    pub fn shutdown(mut self) -> u64 {
+        self.stop();
+        self.requests_served()
+    }
+
+    fn stop(&mut self) {
+        if let Some(acceptor) = self.acceptor.take() {
+            self.shared.shutdown.store(true, Ordering::SeqCst);
+            // Wake the blocking `accept`
+            let _ = TcpStream::connect(self.addr);
+            if acceptor.join().is_err() {
+                eprintln!("http server acceptor panicked");
+            }
+        }
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn accept_loop(listener: TcpListener, router: Arc<Router>, shared: Arc<Shared>) {
+    let mut handlers: Vec<JoinHandle<()>> = Vec::new();
+    for stream in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        let (router, shared) = (Arc::clone(&router), Arc::clone(&shared));
+        handlers.retain(|handler| !handler.is_finished());
+        handlers.push(thread::spawn(move || {
+            if let Err(err) = serve_connection(stream, &router, &shared) {
+                eprintln!("http connection failed: {}", err);
+            }
+        }));
+    }
+    drop(listener);
+    for handler in handlers {
+        let _ = handler.join();
+    }
+}
+
+fn serve_connection(stream: TcpStream, router: &Router, shared: &Shared) -> io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_POLL))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut idle = Duration::ZERO;
+    loop {
+        match parse_request(&mut reader) {
+            Ok(Some(request)) => {
+                idle = Duration::ZERO;
+                let response = router.handle(&request);
+                let keep_alive = request.keep_alive() && !shared.shutdown.load(Ordering::SeqCst);
+                response.write_to(&mut writer, keep_alive, request.method != "HEAD")?;
+                shared.requests.fetch_add(1, Ordering::SeqCst);
+                if !keep_alive {
+                    return close(reader, writer);
+                }
+            }
+            Ok(None) => return Ok(()),
+            Err(ParseError::Io(err)) if is_timeout(&err) => {
+                idle += IDLE_POLL;
+                if idle >= KEEP_ALIVE_IDLE || shared.shutdown.load(Ordering::SeqCst) {
+                    return close(reader, writer);
+                }
+            }
+            Err(ParseError::Io(err)) => return Err(err),
+            Err(err) => {
+                if let Some(response) = err.response() {
+                    response.write_to(&mut writer, false, true)?;
+                    shared.requests.fetch_add(1, Ordering::SeqCst);
+                }
+                return close(reader, writer);
+            }
+        }
+    }
+}
+
+/// Closes our side, then reads and drops whatever the client already sent. Closing a
+/// socket with unread input makes the kernel send a reset, which can destroy the
+/// response before the client reads it.
+fn close(mut reader: BufReader<TcpStream>, writer: TcpStream) -> io::Result<()> {
+    writer.shutdown(Shutdown::Write)?;
+    let mut discarded = 0;
+    let mut buf = [0u8; 4096];
+    // Stop at end of stream, at the first quiet poll, or after a generous amount of input
+    while discarded < MAX_BODY {
+        match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => discarded += n,
+        }
+    }
+    Ok(())
+}
+
+// ========== Client ==========
+
+/// One keep-alive connection that sends raw request text and parses the responses
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        Ok(Client { writer: stream.try_clone()?, reader: BufReader::new(stream) })
+    }
+
+    /// Sends `raw` as-is and reads one response
+    pub fn send_raw(&mut self, raw: &[u8]) -> io::Result<Response> {
+        self.writer.write_all(raw)?;
+        read_response(&mut self.reader, raw.starts_with(b"HEAD "))
+    }
+
+    /// A `GET` for `path` on this connection
+    pub fn get(&mut self, path: &str) -> io::Result<Response> {
+        self.send_raw(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+    }
+
+    /// True if the server has closed its side; an open connection takes a short wait to
+    /// tell apart from a slow one
+    pub fn is_closed(&mut self) -> bool {
+        let _ = self.reader.get_ref().set_read_timeout(Some(Duration::from_millis(200)));
+        let closed = matches!(self.reader.fill_buf(), Ok([]));
+        let _ = self.reader.get_ref().set_read_timeout(Some(CLIENT_TIMEOUT));
+        closed
+    }
+}
+
+/// Reads a response with a `Content-Length` body; `head` skips the body of a `HEAD` answer
+pub fn read_response(reader: &mut impl BufRead, head: bool) -> io::Result<Response> {
+    let invalid = |why: &str| io::Error::new(io::ErrorKind::InvalidData, why.to_string());
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "server closed the connection"));
+    }
+    let status = line.split(' ').nth(1).and_then(|code| code.parse().ok()).ok_or_else(|| invalid("bad status line"))?;
+    let mut response = Response::new(status);
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').ok_or_else(|| invalid("bad header"))?;
+        response.headers.push((name.to_string(), value.trim().to_string()));
+    }
+    if !head {
+        let length: usize = response.header("Content-Length").and_then(|len| len.parse().ok()).unwrap_or(0);
+        response.body = vec![0; length];
+        reader.read_exact(&mut response.body)?;
+    }
+    Ok(response)
+}
+
+// ========== Demo Code ==========
+
+/// The router the demo and the tests use, serving static files from `root`
+pub fn sample_router(root: impl Into<PathBuf>) -> Router {
+    Router::new()
+        .route("GET", "/", |_| Response::new(200).with_body("text/html; charset=utf-8", b"<h1>tech-notes</h1>\n".to_vec()))
+        .route("GET", "/hello", |request| {
+            let name = request.query.as_deref().and_then(|q| q.strip_prefix("name=")).unwrap_or("world");
+            Response::text(200, format!("hello, {}\n", name))
+        })
+        .route("POST", "/echo", |request| {
+            let content_type = request.header("Content-Type").unwrap_or("application/octet-stream");
+            Response::new(200).with_body(content_type, request.body.clone())
+        })
+        .static_dir("/static/", root)
+}
+
+/// A throwaway directory of files for the static route
+pub fn write_sample_site(root: &Path) -> io::Result<()> {
+    fs::create_dir_all(root.join("docs"))?;
+    fs::write(root.join("index.html"), "<p>static index</p>\n")?;
+    fs::write(root.join("style.css"), "body { margin: 0; }\n")?;
+    fs::write(root.join("data.json"), "{\"ok\": true}\n")?;
+    fs::write(root.join("docs").join("index.html"), "<p>docs</p>\n")?;
+    fs::write(root.join("notes.bin"), [0u8, 1, 2, 3])
+}
+
+fn summary(response: &Response) -> String {
+    let mut line = format!("{} {}", response.status, reason_phrase(response.status));
+    for name in ["Content-Type", "Allow", "Connection"] {
+        if let Some(value) = response.header(name) {
+            line.push_str(&format!(" | {}: {}", name, value));
+        }
+    }
+    line
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct HttpServerDemo;
+
+impl Demo for HttpServerDemo {
+    fn name(&self) -> &'static str {
+        "http_server"
+    }
+
+    fn category(&self) -> &'static str {
+        "networking"
+    }
+
+    fn description(&self) -> &'static str {
+        "Hand-parsed HTTP/1.1 with closure routes, static files, keep-alive and error responses"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let root = std::env::temp_dir().join(format!("tech-notes-http-demo-{}", std::process::id()));
+        write_sample_site(&root)?;
+        let server = HttpServer::start("127.0.0.1:0", sample_router(&root))?;
+
+        writeln!(out, "===== Routes =====")?;
+        let mut client = Client::connect(server.local_addr())?;
+        for path in ["/", "/hello", "/hello?name=rust", "/missing"] {
+            let response = client.get(path)?;
+            let label = format!("GET {}", path);
+            writeln!(out, "{:<20} -> {} | body {:?}", label, summary(&response), String::from_utf8_lossy(&response.body))?;
+        }
+        let response = client.send_raw(b"POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Type: text/plain\r\nContent-Length: 11\r\n\r\nping, pong!")?;
+        writeln!(out, "{:<20} -> {} | body {:?}", "POST /echo", summary(&response), String::from_utf8_lossy(&response.body))?;
+        let response = client.send_raw(b"DELETE /hello HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+        writeln!(out, "{:<20} -> {}", "DELETE /hello", summary(&response))?;
+
+        writeln!(out, "\n===== Static Files =====")?;
+        for path in ["/static/", "/static/style.css", "/static/data.json", "/static/docs", "/static/notes.bin", "/static/../Cargo.toml"] {
+            let response = client.get(path)?;
+            let label = format!("GET {}", path);
+            writeln!(out, "{:<25} -> {} | {} bytes", label, summary(&response), response.body.len())?;
+        }
+        let response = client.send_raw(b"HEAD /static/style.css HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+        writeln!(out, "{:<25} -> {} | Content-Length {:?}, body not sent", "HEAD /static/style.css", summary(&response), response.header("Content-Length").unwrap_or(""))?;
+
+        writeln!(out, "\n===== Keep-Alive =====")?;
+        writeln!(out, "all of the above used one connection; still open: {}", !client.is_closed())?;
+        let response = client.send_raw(b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")?;
+        writeln!(out, "request with Connection: close -> {}", summary(&response))?;
+        writeln!(out, "connection closed afterwards: {}", client.is_closed())?;
+        let mut old = Client::connect(server.local_addr())?;
+        let response = old.send_raw(b"GET /hello HTTP/1.0\r\n\r\n")?;
+        writeln!(out, "HTTP/1.0 without keep-alive -> {}; closed: {}", summary(&response), old.is_closed())?;
+
+        writeln!(out, "\n===== Error Responses =====")?;
+        let oversized_header = format!("GET / HTTP/1.1\r\nHost: localhost\r\nX-Big: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        let bad: [(&str, Vec<u8>); 6] = [
+            ("garbage request line", b"HELLO\r\n\r\n".to_vec()),
+            ("HTTP/1.1 without Host", b"GET / HTTP/1.1\r\n\r\n".to_vec()),
+            ("HTTP/2.0", b"GET / HTTP/2.0\r\nHost: localhost\r\n\r\n".to_vec()),
+            ("chunked body", b"POST /echo HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec()),
+            ("huge Content-Length", format!("POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1).into_bytes()),
+            ("8 KiB header line", oversized_header.into_bytes()),
+        ];
+        for (label, raw) in bad {
+            let mut client = Client::connect(server.local_addr())?;
+            let response = client.send_raw(&raw)?;
+            writeln!(out, "{:<22} -> {}; closed: {}", label, summary(&response), client.is_closed())?;
+        }
+
+        drop(client);
+        drop(old);
+        writeln!(out, "\nrequests served: {}", server.shutdown())?;
+        fs::remove_dir_all(&root)
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&HttpServerDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn parse(raw: &str) -> Result<Option<Request>, ParseError> {
+        parse_request(&mut Cursor::new(raw.as_bytes()))
+    }
+
+    #[test]
+    fn parses_request_line_headers_and_body() {
+        let request = parse("POST /a%20b?x=1 HTTP/1.1\r\nHost: h\r\ncontent-length: 5\r\nX-Pad:   spaced  \r\n\r\nhello")
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/a b");
+        assert_eq!(request.query.as_deref(), Some("x=1"));
+        assert_eq!(request.header("Content-Length"), Some("5"));
+        assert_eq!(request.header("x-pad"), Some("spaced"));
+        assert_eq!(request.body, b"hello");
+        assert!(request.keep_alive());
+    }
+
+    #[test]
+    fn back_to_back_requests_and_a_clean_end() {
+        let mut reader = Cursor::new(b"GET /1 HTTP/1.1\nHost: h\n\n\r\nGET /2 HTTP/1.0\r\n\r\n".to_vec());
+        let paths: Vec<String> = std::iter::from_fn(|| parse_request(&mut reader).unwrap()).map(|r| r.path).collect();
+        assert_eq!(paths, ["/1", "/2"]);
+    }
+
+    #[test]
+    fn malformed_requests_map_to_status_codes() {
+        let status = |raw: &str| parse(raw).unwrap_err().response().unwrap().status;
+        assert_eq!(status("GET /\r\n\r\n"), 400);
+        assert_eq!(status("get / HTTP/1.1\r\nHost: h\r\n\r\n"), 400);
+        assert_eq!(status("GET example.com HTTP/1.1\r\nHost: h\r\n\r\n"), 400);
+        assert_eq!(status("GET /%zz HTTP/1.1\r\nHost: h\r\n\r\n"), 400);
+        assert_eq!(status("GET / HTTP/1.1\r\n\r\n"), 400);
+        assert_eq!(status("GET / HTTP/1.1\r\nHost h\r\n\r\n"), 400);
+        assert_eq!(status("POST / HTTP/1.1\r\nHost: h\r\nContent-Length: 9\r\n\r\nshort"), 400);
+        assert_eq!(status("GET / HTTP/3\r\nHost: h\r\n\r\n"), 505);
+        assert_eq!(status("POST / HTTP/1.1\r\nHost: h\r\nTransfer-Encoding: chunked\r\n\r\n"), 501);
+        assert_eq!(status(&format!("POST / HTTP/1.1\r\nHost: h\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1)), 413);
+        assert_eq!(status(&format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE))), 431);
+        let many_headers: String = (0..=MAX_HEADERS).map(|i| format!("X-{}: v\r\n", i)).collect();
+        assert_eq!(status(&format!("GET / HTTP/1.1\r\nHost: h\r\n{}\r\n", many_headers)), 431);
+    }
+
+    #[test]
+    fn keep_alive_defaults_differ_by_version() {
+        let keep = |raw: &str| parse(raw).unwrap().unwrap().keep_alive();
+        assert!(keep("GET / HTTP/1.1\r\nHost: h\r\n\r\n"));
+        assert!(!keep("GET / HTTP/1.1\r\nHost: h\r\nConnection: Close\r\n\r\n"));
+        assert!(!keep("GET / HTTP/1.0\r\n\r\n"));
+        assert!(keep("GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n"));
+    }
+
+    #[test]
+    fn router_distinguishes_404_and_405() {
+        let router = Router::new().route("GET", "/a", |_| Response::text(200, "a")).route("PUT", "/a", |_| Response::new(204));
+        let request = |method: &str, path: &str| parse(&format!("{} {} HTTP/1.1\r\nHost: h\r\n\r\n", method, path)).unwrap().unwrap();
+
+        assert_eq!(router.handle(&request("GET", "/a")).body, b"a");
+        assert_eq!(router.handle(&request("HEAD", "/a")).status, 200);
+        assert_eq!(router.handle(&request("PUT", "/a")).status, 204);
+        let not_allowed = router.handle(&request("DELETE", "/a"));
+        assert_eq!((not_allowed.status, not_allowed.header("allow")), (405, Some("GET, PUT, HEAD")));
+        assert_eq!(router.handle(&request("GET", "/b")).status, 404);
+    }
+
+    #[test]
+    fn responses_serialize_with_length_and_connection() {
+        let mut wire = Vec::new();
+        Response::text(200, "hi").write_to(&mut wire, true, true).unwrap();
+        assert_eq!(
+            String::from_utf8(wire).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: 2\r\nConnection: keep-alive\r\n\r\nhi"
+        );
+        let mut wire = Vec::new();
+        Response::text(404, "gone").write_to(&mut wire, false, false).unwrap();
+        let parsed = read_response(&mut Cursor::new(wire), true).unwrap();
+        assert_eq!((parsed.status, parsed.header("Content-Length"), parsed.body.len()), (404, Some("4"), 0));
+    }
+}
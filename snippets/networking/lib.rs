@@ -0,0 +1,18 @@
+//! Networking snippets from the tech notes.
+//!
+//! Each module builds one protocol on `std::net` with plain threads, and its `Demo`
+//! implementation is registered in `DEMOS`, whose `run_demo` wrapper the matching
+//! example binary calls. The demos talk to themselves over `127.0.0.1`.
+
+pub mod http_server;
+pub mod tcp_echo;
+pub mod udp_chat;
+
+use demo::Demo;
+
+/// Every demo in this crate, for `tech-notes` and the other front ends.
+pub static DEMOS: &[&dyn Demo] = &[
+    &http_server::HttpServerDemo,
+    &tcp_echo::TcpEchoDemo,
+    &udp_chat::UdpChatDemo,
+];
@@ -0,0 +1,423 @@
+//! TCP Echo Server and Client in Rust
+//!
+//! TCP delivers a stream of bytes, not messages: one `write` can arrive as several
+//! `read`s and two writes can arrive as one. Anything message-shaped needs framing on
+//! top, and a server needs a plan for serving many clients and for stopping cleanly.
+//!
+//! This file demonstrates:
+//! - Length-prefixed framing: a 4-byte big-endian length, then the payload, with a size
+//!   cap so a bad header can't make the server allocate gigabytes
+//! - Two concurrency models: a thread per connection, and a fixed `ThreadPool` from the
+//!   concurrency crate where extra connections wait in the queue
+//! - Graceful shutdown: a flag stops the accept loop (woken by a connection of its own,
+//!   since `accept` blocks), then open connections are drained. Each one is served until
+//!   it has no request pending, checked with a short read timeout, and then closed
+//! - A client that sends frames and reads the echoes back
+
+use concurrency::thread_pool::ThreadPool;
+use demo::Demo;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// ========== Framing ==========
+
+/// Largest payload a frame may carry
+pub const MAX_FRAME: usize = 1 << 20;
+
+/// How often an idle connection checks whether the server is shutting down
+const IDLE_POLL: Duration = Duration::from_millis(20);
+
+/// How long the client waits for an echo before giving up
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Writes `payload` as one length-prefixed frame
+pub fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("frame of {} bytes is over the limit", payload.len())));
+    }
+    // One write per frame: with the header sent on its own, Nagle's algorithm holds the
+    // payload back until the peer ACKs the header, and delayed ACKs make that ~40ms
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    writer.write_all(&frame)?;
+    writer.flush()
+}
+
+/// Reads one frame, or `None` if the peer closed the connection between frames.
+///
+/// A read timeout before the first byte comes back as an error, so a server can check
+/// its shutdown flag between frames. Once a frame has started, timeouts are retried
+/// until the rest of it arrives.
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut header = [0u8; 4];
+    if !fill(reader, &mut header, true)? {
+        return Ok(None);
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("frame header claims {} bytes", len)));
+    }
+    let mut payload = vec![0; len];
+    fill(reader, &mut payload, false)?;
+    Ok(Some(payload))
+}
+
+/// Fills `buf`; `false` means a clean end of stream at a frame boundary
+fn fill(reader: &mut impl Read, buf: &mut [u8], at_boundary: bool) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if at_boundary && filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed mid-frame")),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) if is_timeout(&err) && !(at_boundary && filled == 0) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(true)
+}
+
+/// Read timeouts surface as `WouldBlock` on Unix and `TimedOut` on Windows
+pub fn is_timeout(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+// ========== Server ==========
+
+/// How the server runs its connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    ThreadPerConnection,
+    /// At most this many connections are served at once; the rest wait their turn
+    ThreadPool(usize),
+}
+
+/// Totals over the server's lifetime
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ServerStats {
+    pub connections: usize,
+    pub frames: u64,
+    pub bytes: u64,
+}
+
+#[derive(Default)]
+struct Shared {
+    shutdown: AtomicBool,
+    connections: AtomicUsize,
+    frames: AtomicU64,
+    bytes: AtomicU64,
+}
+
+/// A running echo server; dropping it shuts it down and waits for it
+pub struct EchoServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl EchoServer {
+    /// Binds `addr` (port 0 picks a free one) and starts accepting in the background
+    pub fn start(addr: impl ToSocketAddrs, mode: Mode) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || accept_loop(listener, mode, shared))
+        };
+        Ok(EchoServer { addr, shared, acceptor: Some(acceptor) })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stops accepting connections; open ones are drained in the background
+    pub fn request_shutdown(&self) {
+        if !self.shared.shutdown.swap(true, Ordering::SeqCst) {
+            // `accept` blocks, so wake it with a connection of our own. If this fails the
+            // acceptor has already stopped.
+            let _ = TcpStream::connect(self.addr);
+        }
+    }
+
+    /// Waits until the acceptor and every drained connection have finished
+    pub fn join(mut self) -> ServerStats {
+        self.wait();
+        self.stats()
+    }
+
+    /// `request_shutdown` then `join`
+    pub fn shutdown(self) -> ServerStats {
+        self.request_shutdown();
+        self.join()
+    }
+
+    pub fn stats(&self) -> ServerStats {
+        ServerStats {
+            connections: self.shared.connections.load(Ordering::SeqCst),
+            frames: self.shared.frames.load(Ordering::SeqCst),
+            bytes: self.shared.bytes.load(Ordering::SeqCst),
+        }
+    }
+
+    fn wait(&mut self) {
+        if let Some(acceptor) = self.acceptor.take() {
+            if acceptor.join().is_err() {
+                eprintln!("echo server acceptor panicked");
+            }
+        }
+    }
+}
+
+impl Drop for EchoServer {
+    fn drop(&mut self) {
+        if self.acceptor.is_some() {
+            self.request_shutdown();
+            self.wait();
+        }
+    }
+}
+
+fn accept_loop(listener: TcpListener, mode: Mode, shared: Arc<Shared>) {
+    let pool = match mode {
+        Mode::ThreadPool(size) => Some(ThreadPool::new(size)),
+        Mode::ThreadPerConnection => None,
+    };
+    let mut handlers: Vec<JoinHandle<()>> = Vec::new();
+
+    for stream in listener.incoming() {
+        // Checked after `accept` returns, which also catches the wake-up connection
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        shared.connections.fetch_add(1, Ordering::SeqCst);
+        let shared = Arc::clone(&shared);
+        let job = move || {
+            if let Err(err) = serve(stream, &shared) {
+                eprintln!("echo connection failed: {}", err);
+            }
+        };
+        match &pool {
+            Some(pool) => pool.execute(job),
+            None => {
+                handlers.retain(|handler| !handler.is_finished());
+                handlers.push(thread::spawn(job));
+            }
+        }
+    }
+
+    // New connections are refused from here on; wait for the accepted ones to drain
+    drop(listener);
+    for handler in handlers {
+        let _ = handler.join();
+    }
+    if let Some(pool) = pool {
+        pool.shutdown();
+    }
+}
+
+/// Echoes frames until the client leaves, or goes quiet after shutdown was requested
+fn serve(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_POLL))?;
+    loop {
+        match read_frame(&mut stream) {
+            Ok(Some(frame)) => {
+                write_frame(&mut stream, &frame)?;
+                shared.frames.fetch_add(1, Ordering::SeqCst);
+                shared.bytes.fetch_add(frame.len() as u64, Ordering::SeqCst);
+            }
+            Ok(None) => return Ok(()),
+            Err(err) if is_timeout(&err) => {
+                if shared.shutdown.load(Ordering::SeqCst) {
+                    return stream.shutdown(Shutdown::Both);
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// ========== Client ==========
+
+pub struct EchoClient {
+    stream: TcpStream,
+}
+
+impl EchoClient {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        Ok(EchoClient { stream })
+    }
+
+    pub fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        write_frame(&mut self.stream, payload)
+    }
+
+    /// The next echoed frame, or `None` once the server has closed the connection
+    pub fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        read_frame(&mut self.stream)
+    }
+
+    /// Sends one frame and waits for its echo
+    pub fn echo(&mut self, payload: &[u8]) -> io::Result<Vec<u8>> {
+        self.send(payload)?;
+        self.recv()?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "server closed the connection"))
+    }
+}
+
+// ========== Demo Code ==========
+
+fn demonstrate_server(out: &mut dyn Write, mode: Mode, clients: usize) -> io::Result<()> {
+    let server = EchoServer::start("127.0.0.1:0", mode)?;
+    // All clients stay connected at once, which a pool only allows up to its size
+    let mut open: Vec<EchoClient> = (0..clients).map(|_| EchoClient::connect(server.local_addr())).collect::<io::Result<_>>()?;
+    for (i, client) in open.iter_mut().enumerate() {
+        let message = format!("hello from client {}", i + 1);
+        let reply = client.echo(message.as_bytes())?;
+        writeln!(out, "client {} got {:?}", i + 1, String::from_utf8_lossy(&reply))?;
+    }
+    drop(open);
+    let stats = server.shutdown();
+    writeln!(out, "stats: {} connections, {} frames, {} payload bytes", stats.connections, stats.frames, stats.bytes)
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct TcpEchoDemo;
+
+impl Demo for TcpEchoDemo {
+    fn name(&self) -> &'static str {
+        "tcp_echo"
+    }
+
+    fn category(&self) -> &'static str {
+        "networking"
+    }
+
+    fn description(&self) -> &'static str {
+        "Length-prefixed echo server, thread-per-connection and pooled, with graceful shutdown"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Length-Prefixed Frames =====")?;
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"hi")?;
+        write_frame(&mut wire, b"")?;
+        writeln!(out, "\"hi\" then \"\" on the wire: {:?}", wire)?;
+        let mut reader = io::Cursor::new(&wire);
+        while let Some(frame) = read_frame(&mut reader)? {
+            writeln!(out, "read frame {:?}", String::from_utf8_lossy(&frame))?;
+        }
+        writeln!(out, "end of stream at a frame boundary")?;
+        let truncated = read_frame(&mut io::Cursor::new(&wire[..3])).unwrap_err();
+        writeln!(out, "three bytes of header: {}", truncated)?;
+
+        writeln!(out, "\n===== Thread per Connection =====")?;
+        demonstrate_server(out, Mode::ThreadPerConnection, 3)?;
+
+        writeln!(out, "\n===== Thread Pool (3 workers) =====")?;
+        demonstrate_server(out, Mode::ThreadPool(3), 3)?;
+
+        writeln!(out, "\n===== Graceful Shutdown =====")?;
+        let server = EchoServer::start("127.0.0.1:0", Mode::ThreadPerConnection)?;
+        let addr = server.local_addr();
+        let mut client = EchoClient::connect(addr)?;
+        client.echo(b"warm-up")?;
+        // Sent before the shutdown request, so the drain has to answer it
+        client.send(b"in flight during shutdown")?;
+        server.request_shutdown();
+        writeln!(out, "shutdown requested")?;
+        let reply = client.recv()?.unwrap_or_default();
+        writeln!(out, "still answered: {:?}", String::from_utf8_lossy(&reply))?;
+        let closed = client.recv()?.is_none();
+        writeln!(out, "then the server closed the idle connection: {}", closed)?;
+        let stats = server.join();
+        writeln!(out, "stats: {} connections, {} frames", stats.connections, stats.frames)?;
+        writeln!(out, "new connections refused: {}", TcpStream::connect(addr).is_err())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&TcpEchoDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn frames_round_trip_back_to_back() {
+        let mut wire = Vec::new();
+        for payload in [&b"one"[..], b"", b"three"] {
+            write_frame(&mut wire, payload).unwrap();
+        }
+        let mut reader = Cursor::new(wire);
+        let frames: Vec<Vec<u8>> = std::iter::from_fn(|| read_frame(&mut reader).unwrap()).collect();
+        assert_eq!(frames, [b"one".to_vec(), vec![], b"three".to_vec()]);
+    }
+
+    #[test]
+    fn truncated_frames_are_errors_but_a_clean_end_is_not() {
+        assert!(read_frame(&mut Cursor::new(Vec::<u8>::new())).unwrap().is_none());
+
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"cut short").unwrap();
+        for len in [2, 6] {
+            let err = read_frame(&mut Cursor::new(&wire[..len])).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+
+    #[test]
+    fn oversized_frames_are_rejected_both_ways() {
+        let err = write_frame(&mut Vec::new(), &vec![0; MAX_FRAME + 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let header = ((MAX_FRAME + 1) as u32).to_be_bytes();
+        let err = read_frame(&mut Cursor::new(header)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    /// Hands out its bytes one at a time with a timeout before each
+    struct Stuttering {
+        bytes: Vec<u8>,
+        next: usize,
+        timed_out: bool,
+    }
+
+    impl Read for Stuttering {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.timed_out = !self.timed_out;
+            if self.timed_out {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let Some(&byte) = self.bytes.get(self.next) else { return Ok(0) };
+            buf[0] = byte;
+            self.next += 1;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn timeouts_surface_only_between_frames() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"slow").unwrap();
+        let mut reader = Stuttering { bytes: wire, next: 0, timed_out: false };
+
+        // The first timeout comes before any byte, so the caller sees it
+        assert!(is_timeout(&read_frame(&mut reader).unwrap_err()));
+        // After that the frame has started and the rest of its timeouts are absorbed
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(b"slow".to_vec()));
+    }
+}
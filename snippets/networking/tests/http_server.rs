@@ -0,0 +1,116 @@
+//! Drives `http_server` through real `TcpStream`s on 127.0.0.1.
+
+use networking::http_server::{read_response, sample_router, write_sample_site, Client, HttpServer};
+use std::io::{BufReader, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// A server over a fresh copy of the sample site; the directory goes away on drop
+struct Site {
+    root: PathBuf,
+    server: Option<HttpServer>,
+}
+
+impl Site {
+    fn start(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("tech-notes-http-{}-{}", name, std::process::id()));
+        write_sample_site(&root).unwrap();
+        let server = HttpServer::start("127.0.0.1:0", sample_router(&root)).unwrap();
+        Site { root, server: Some(server) }
+    }
+
+    fn server(&self) -> &HttpServer {
+        self.server.as_ref().unwrap()
+    }
+
+    fn client(&self) -> Client {
+        Client::connect(self.server().local_addr()).unwrap()
+    }
+}
+
+impl Drop for Site {
+    fn drop(&mut self) {
+        drop(self.server.take());
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn routes_and_static_files_share_one_keep_alive_connection() {
+    let site = Site::start("routes");
+    let mut client = site.client();
+
+    let hello = client.get("/hello?name=test").unwrap();
+    assert_eq!((hello.status, hello.body.as_slice()), (200, &b"hello, test\n"[..]));
+    let css = client.get("/static/style.css").unwrap();
+    assert_eq!(css.header("Content-Type"), Some("text/css; charset=utf-8"));
+    assert_eq!(css.body, b"body { margin: 0; }\n");
+    let index = client.get("/static/docs/").unwrap();
+    assert_eq!((index.status, index.body.as_slice()), (200, &b"<p>docs</p>\n"[..]));
+    assert_eq!(client.get("/static/nope.txt").unwrap().status, 404);
+    assert_eq!(client.get("/static/%2e%2e/secret").unwrap().status, 403);
+
+    let echo = client.send_raw(b"POST /echo HTTP/1.1\r\nHost: x\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}").unwrap();
+    assert_eq!((echo.header("Content-Type"), echo.body.as_slice()), (Some("application/json"), &b"{}"[..]));
+    assert!(!client.is_closed());
+    assert_eq!(site.server().requests_served(), 6);
+}
+
+#[test]
+fn pipelined_requests_are_answered_in_order() {
+    let site = Site::start("pipelined");
+    let mut stream = TcpStream::connect(site.server().local_addr()).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    stream
+        .write_all(b"GET /hello HTTP/1.1\r\nHost: x\r\n\r\nGET /hello?name=two HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+        .unwrap();
+
+    let mut reader = BufReader::new(stream);
+    let first = read_response(&mut reader, false).unwrap();
+    let second = read_response(&mut reader, false).unwrap();
+    assert_eq!(first.body, b"hello, world\n");
+    assert_eq!(second.body, b"hello, two\n");
+    assert_eq!(second.header("Connection"), Some("close"));
+}
+
+#[test]
+fn a_request_trickling_in_over_several_polls_still_parses() {
+    let site = Site::start("trickle");
+    let mut stream = TcpStream::connect(site.server().local_addr()).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    // Gaps longer than the server's read timeout, mid-line, between headers and in the body
+    for chunk in ["POST /ec", "ho HTTP/1.1\r\nHost: x\r\n", "Content-Length: 4\r\n\r\nab", "cd"] {
+        stream.write_all(chunk.as_bytes()).unwrap();
+        thread::sleep(Duration::from_millis(60));
+    }
+    let response = read_response(&mut BufReader::new(stream), false).unwrap();
+    assert_eq!((response.status, response.body.as_slice()), (200, &b"abcd"[..]));
+}
+
+#[test]
+fn a_bad_request_closes_only_its_own_connection() {
+    let site = Site::start("bad");
+    let mut good = site.client();
+    let mut bad = site.client();
+
+    let response = bad.send_raw(b"GET / HTTP/1.1\r\nNo-Colon-Here\r\nHost: x\r\n\r\n").unwrap();
+    assert_eq!((response.status, response.header("Connection")), (400, Some("close")));
+    assert!(bad.is_closed());
+    assert_eq!(good.get("/").unwrap().status, 200);
+}
+
+#[test]
+fn shutdown_closes_idle_connections_and_refuses_new_ones() {
+    let site = Site::start("shutdown");
+    let mut idle = site.client();
+    assert_eq!(idle.get("/").unwrap().status, 200);
+    let addr = site.server().local_addr();
+
+    let mut site = site;
+    let served = site.server.take().unwrap().shutdown();
+    assert_eq!(served, 1);
+    assert!(idle.is_closed());
+    assert!(TcpStream::connect(addr).is_err());
+}
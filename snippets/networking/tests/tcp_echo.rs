@@ -0,0 +1,87 @@
+//! Drives `tcp_echo` over real sockets on 127.0.0.1.
+
+use networking::tcp_echo::{EchoClient, EchoServer, Mode, ServerStats, MAX_FRAME};
+use std::net::TcpStream;
+use std::thread;
+
+const CLIENTS: usize = 6;
+const MESSAGES: usize = 20;
+
+/// Every client echoes its own numbered messages from its own thread
+fn hammer(mode: Mode) -> ServerStats {
+    let server = EchoServer::start("127.0.0.1:0", mode).unwrap();
+    let addr = server.local_addr();
+    let clients: Vec<_> = (0..CLIENTS)
+        .map(|id| {
+            thread::spawn(move || {
+                let mut client = EchoClient::connect(addr).unwrap();
+                for n in 0..MESSAGES {
+                    let message = format!("client {} message {}", id, n);
+                    assert_eq!(client.echo(message.as_bytes()).unwrap(), message.as_bytes());
+                }
+            })
+        })
+        .collect();
+    for client in clients {
+        client.join().unwrap();
+    }
+    server.shutdown()
+}
+
+#[test]
+fn thread_per_connection_serves_concurrent_clients() {
+    let stats = hammer(Mode::ThreadPerConnection);
+    assert_eq!(stats.connections, CLIENTS);
+    assert_eq!(stats.frames, (CLIENTS * MESSAGES) as u64);
+}
+
+#[test]
+fn a_small_pool_serves_every_client_in_turn() {
+    // Two workers for six clients: the others wait in the queue until one disconnects
+    let stats = hammer(Mode::ThreadPool(2));
+    assert_eq!(stats.connections, CLIENTS);
+    assert_eq!(stats.frames, (CLIENTS * MESSAGES) as u64);
+}
+
+#[test]
+fn empty_and_large_frames_echo_intact() {
+    let server = EchoServer::start("127.0.0.1:0", Mode::ThreadPerConnection).unwrap();
+    let mut client = EchoClient::connect(server.local_addr()).unwrap();
+
+    assert_eq!(client.echo(b"").unwrap(), b"");
+    let large: Vec<u8> = (0..MAX_FRAME).map(|i| (i % 251) as u8).collect();
+    assert_eq!(client.echo(&large).unwrap(), large);
+    assert!(client.send(&vec![0; MAX_FRAME + 1]).is_err());
+
+    drop(client);
+    assert_eq!(server.shutdown().bytes, MAX_FRAME as u64);
+}
+
+#[test]
+fn shutdown_drains_pending_requests_then_closes() {
+    let server = EchoServer::start("127.0.0.1:0", Mode::ThreadPool(2)).unwrap();
+    let addr = server.local_addr();
+    let mut busy = EchoClient::connect(addr).unwrap();
+    let mut idle = EchoClient::connect(addr).unwrap();
+    busy.echo(b"first").unwrap();
+    idle.echo(b"hello").unwrap();
+
+    busy.send(b"pending").unwrap();
+    server.request_shutdown();
+    assert_eq!(busy.recv().unwrap(), Some(b"pending".to_vec()));
+    assert_eq!(busy.recv().unwrap(), None);
+    assert_eq!(idle.recv().unwrap(), None);
+
+    let stats = server.join();
+    assert_eq!((stats.connections, stats.frames), (2, 3));
+    assert!(TcpStream::connect(addr).is_err());
+}
+
+#[test]
+fn dropping_the_server_with_a_client_attached_does_not_hang() {
+    let server = EchoServer::start("127.0.0.1:0", Mode::ThreadPerConnection).unwrap();
+    let mut client = EchoClient::connect(server.local_addr()).unwrap();
+    client.echo(b"ping").unwrap();
+    drop(server);
+    assert_eq!(client.recv().unwrap(), None);
+}
@@ -0,0 +1,131 @@
+//! Drives `udp_chat` nodes over real `UdpSocket`s on 127.0.0.1.
+
+use networking::udp_chat::{Body, ChatNode, DecodeError, Event, Packet, PeerStats, PEER_TIMEOUT};
+use rust_lang::testing_patterns::FakeClock;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::Arc;
+use std::time::Duration;
+
+const POLL: Duration = Duration::from_millis(50);
+
+fn node(name: &str, clock: &Arc<FakeClock>) -> (ChatNode, SocketAddr) {
+    let node = ChatNode::bind("127.0.0.1:0", name, clock.clone()).unwrap();
+    let addr = node.local_addr().unwrap();
+    (node, addr)
+}
+
+fn stats(name: &str, received: u64, lost: u64, late: u64, duplicates: u64) -> PeerStats {
+    PeerStats { name: name.to_string(), received, lost, late, duplicates }
+}
+
+#[test]
+fn two_nodes_introduce_themselves_and_chat() {
+    let clock = Arc::new(FakeClock::at_unix(0));
+    let (mut alice, alice_addr) = node("alice", &clock);
+    let (mut bob, bob_addr) = node("bob", &clock);
+
+    alice.hello(bob_addr).unwrap();
+    alice.say(bob_addr, "one").unwrap();
+    alice.say(bob_addr, "two").unwrap();
+    bob.hello(alice_addr).unwrap();
+    bob.say(alice_addr, "back").unwrap();
+
+    assert_eq!(
+        bob.poll(POLL).unwrap(),
+        [
+            Event::Joined { name: "alice".to_string() },
+            Event::Message { from: "alice".to_string(), text: "one".to_string() },
+            Event::Message { from: "alice".to_string(), text: "two".to_string() },
+        ]
+    );
+    assert_eq!(alice.poll(POLL).unwrap().len(), 2);
+    assert_eq!(bob.peers(), [stats("alice", 3, 0, 0, 0)]);
+
+    alice.bye(bob_addr).unwrap();
+    assert_eq!(bob.poll(POLL).unwrap(), [Event::Left { stats: stats("alice", 4, 0, 0, 0) }]);
+    assert!(bob.peers().is_empty());
+}
+
+#[test]
+fn dropped_replayed_and_reordered_packets_are_accounted_for() {
+    let clock = Arc::new(FakeClock::at_unix(0));
+    let (mut alice, _) = node("alice", &clock);
+    let (mut bob, bob_addr) = node("bob", &clock);
+
+    // Every third packet after the hello is lost: 3, 6, 9 of 0..=10
+    alice.drop_outgoing(|seq| seq % 3 == 0 && seq > 0);
+    alice.hello(bob_addr).unwrap();
+    for n in 1..=10 {
+        alice.say(bob_addr, &n.to_string()).unwrap();
+    }
+    // 6 turns up late after all, and 10 arrives twice
+    alice.send_packet(bob_addr, &Packet { seq: 6, body: Body::Chat { text: "6".to_string() } }).unwrap();
+    alice.send_packet(bob_addr, &Packet { seq: 10, body: Body::Chat { text: "10".to_string() } }).unwrap();
+
+    let texts: Vec<String> = bob
+        .poll(POLL)
+        .unwrap()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Message { text, .. } => Some(text),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, ["1", "2", "4", "5", "7", "8", "10", "6"]);
+    assert_eq!(bob.peers(), [stats("alice", 9, 2, 1, 1)]);
+}
+
+#[test]
+fn heartbeats_keep_a_peer_alive_and_silence_times_it_out() {
+    let clock = Arc::new(FakeClock::at_unix(0));
+    let (mut alice, _) = node("alice", &clock);
+    let (mut carol, _) = node("carol", &clock);
+    let (mut bob, bob_addr) = node("bob", &clock);
+    alice.hello(bob_addr).unwrap();
+    carol.hello(bob_addr).unwrap();
+    bob.poll(POLL).unwrap();
+
+    let step = PEER_TIMEOUT / 2;
+    for _ in 0..2 {
+        clock.advance(step);
+        alice.heartbeat(bob_addr).unwrap();
+        bob.poll(POLL).unwrap();
+        assert!(bob.expire().is_empty());
+    }
+
+    clock.advance(step);
+    alice.heartbeat(bob_addr).unwrap();
+    bob.poll(POLL).unwrap();
+    assert_eq!(bob.expire(), [Event::TimedOut { stats: stats("carol", 1, 0, 0, 0) }]);
+    assert_eq!(bob.peers(), [stats("alice", 4, 0, 0, 0)]);
+
+    // A timed-out peer is a stranger again until it says hello
+    carol.say(bob_addr, "still here?").unwrap();
+    assert!(bob.poll(POLL).unwrap().is_empty());
+    carol.hello(bob_addr).unwrap();
+    assert_eq!(bob.poll(POLL).unwrap(), [Event::Joined { name: "carol".to_string() }]);
+}
+
+#[test]
+fn garbage_datagrams_are_reported_and_do_not_disturb_peers() {
+    let clock = Arc::new(FakeClock::at_unix(0));
+    let (mut alice, _) = node("alice", &clock);
+    let (mut bob, bob_addr) = node("bob", &clock);
+    alice.hello(bob_addr).unwrap();
+    bob.poll(POLL).unwrap();
+
+    let stranger = UdpSocket::bind("127.0.0.1:0").unwrap();
+    stranger.send_to(b"", bob_addr).unwrap();
+    stranger.send_to(b"TN\x01\x07\0\0\0\0", bob_addr).unwrap();
+    alice.say(bob_addr, "still fine").unwrap();
+
+    assert_eq!(
+        bob.poll(POLL).unwrap(),
+        [
+            Event::Malformed { error: DecodeError::TooShort },
+            Event::Malformed { error: DecodeError::UnknownKind(7) },
+            Event::Message { from: "alice".to_string(), text: "still fine".to_string() },
+        ]
+    );
+    assert_eq!(bob.peers(), [stats("alice", 2, 0, 0, 0)]);
+}
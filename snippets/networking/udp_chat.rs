@@ -0,0 +1,561 @@
+//! UDP Chat with Heartbeats in Rust
+//!
+//! UDP hands over whole datagrams but promises nothing else: packets can be lost,
+//! duplicated or reordered, and a peer that vanishes sends no goodbye. A protocol on top
+//! has to notice all of that itself.
+//!
+//! This file demonstrates:
+//! - A hand-rolled binary packet format: magic, version, kind, a sequence number and a
+//!   length-prefixed UTF-8 payload, decoded with an error for every way it can be wrong
+//! - Sequence numbers with a 64-packet sliding window (the replay window IPsec and DTLS
+//!   use) to classify each arrival as new, late or duplicate, and count what was lost
+//! - Heartbeats that keep a quiet peer alive, and a timeout that drops peers who stop
+//!   sending, driven by an injectable `Clock` so tests can skip ahead
+//! - Fault injection: a node can be told to drop some of its own outgoing packets
+
+use crate::tcp_echo::is_timeout;
+use demo::Demo;
+use rust_lang::testing_patterns::{Clock, FakeClock};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// ========== Wire Format ==========
+
+const MAGIC: [u8; 2] = *b"TN";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 8;
+
+/// Longest name or message, in bytes
+pub const MAX_TEXT: usize = 1024;
+
+/// Big enough for any valid packet
+pub const MAX_DATAGRAM: usize = HEADER_LEN + 2 + MAX_TEXT;
+
+/// A peer is dropped after this long without a packet from it
+pub const PEER_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Hello { name: String },
+    Chat { text: String },
+    Heartbeat,
+    Bye,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub seq: u32,
+    pub body: Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    TooShort,
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownKind(u8),
+    /// The length prefix disagrees with the datagram's size
+    BadLength,
+    TextTooLong,
+    NotUtf8,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::TooShort => write!(f, "datagram too short"),
+            DecodeError::BadMagic => write!(f, "not a chat packet"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            DecodeError::UnknownKind(k) => write!(f, "unknown packet kind {}", k),
+            DecodeError::BadLength => write!(f, "length prefix does not match the datagram"),
+            DecodeError::TextTooLong => write!(f, "text longer than {} bytes", MAX_TEXT),
+            DecodeError::NotUtf8 => write!(f, "text is not UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Packet {
+    /// `TN`, version, kind, big-endian sequence number, then `u16` length + text for
+    /// the kinds that carry some. Text is cut to `MAX_TEXT` bytes on a char boundary.
+    pub fn encode(&self) -> Vec<u8> {
+        let (kind, text) = match &self.body {
+            Body::Hello { name } => (1, Some(name.as_str())),
+            Body::Chat { text } => (2, Some(text.as_str())),
+            Body::Heartbeat => (3, None),
+            Body::Bye => (4, None),
+        };
+        let mut bytes = Vec::with_capacity(MAX_DATAGRAM);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(VERSION);
+        bytes.push(kind);
+        bytes.extend_from_slice(&self.seq.to_be_bytes());
+        if let Some(text) = text {
+            let mut end = text.len().min(MAX_TEXT);
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            bytes.extend_from_slice(&(end as u16).to_be_bytes());
+            bytes.extend_from_slice(&text.as_bytes()[..end]);
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Packet, DecodeError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(DecodeError::TooShort);
+        }
+        if bytes[..2] != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        if bytes[2] != VERSION {
+            return Err(DecodeError::UnsupportedVersion(bytes[2]));
+        }
+        let seq = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        let rest = &bytes[HEADER_LEN..];
+        let body = match bytes[3] {
+            1 => Body::Hello { name: decode_text(rest)? },
+            2 => Body::Chat { text: decode_text(rest)? },
+            3 | 4 if !rest.is_empty() => return Err(DecodeError::BadLength),
+            3 => Body::Heartbeat,
+            4 => Body::Bye,
+            kind => return Err(DecodeError::UnknownKind(kind)),
+        };
+        Ok(Packet { seq, body })
+    }
+}
+
+fn decode_text(bytes: &[u8]) -> Result<String, DecodeError> {
+    let (len, text) = bytes.split_first_chunk::<2>().ok_or(DecodeError::TooShort)?;
+    let len = u16::from_be_bytes(*len) as usize;
+    if len > MAX_TEXT {
+        return Err(DecodeError::TextTooLong);
+    }
+    if len != text.len() {
+        return Err(DecodeError::BadLength);
+    }
+    String::from_utf8(text.to_vec()).map_err(|_| DecodeError::NotUtf8)
+}
+
+// ========== Sequence Window ==========
+
+/// How a sequence number relates to what has arrived before
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrival {
+    /// Newer than anything so far
+    New,
+    /// Older than the newest, but not seen before: it was reordered, not lost
+    Late,
+    Duplicate,
+    /// Too far behind the window to tell; ignored
+    TooOld,
+}
+
+/// The newest sequence number plus one bit for each of the 63 before it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeqWindow {
+    first: Option<u32>,
+    highest: u32,
+    /// Bit `i` set means `highest - i` has arrived
+    seen: u64,
+    received: u64,
+}
+
+impl SeqWindow {
+    pub fn record(&mut self, seq: u32) -> Arrival {
+        let Some(first) = self.first else {
+            self.first = Some(seq);
+            self.highest = seq;
+            self.seen = 1;
+            self.received = 1;
+            return Arrival::New;
+        };
+        if seq > self.highest {
+            let shift = seq - self.highest;
+            self.seen = if shift >= 64 { 0 } else { self.seen << shift };
+            self.seen |= 1;
+            self.highest = seq;
+            self.received += 1;
+            return Arrival::New;
+        }
+        let age = self.highest - seq;
+        if age >= 64 {
+            return Arrival::TooOld;
+        }
+        if self.seen & (1 << age) != 0 {
+            return Arrival::Duplicate;
+        }
+        self.seen |= 1 << age;
+        self.received += 1;
+        if seq < first {
+            self.first = Some(seq);
+        }
+        Arrival::Late
+    }
+
+    /// Distinct packets that made it
+    pub fn received(&self) -> u64 {
+        self.received
+    }
+
+    /// Sequence numbers between the first and the newest that never arrived
+    pub fn lost(&self) -> u64 {
+        self.first.map_or(0, |first| u64::from(self.highest - first) + 1 - self.received)
+    }
+}
+
+// ========== Peers ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerStats {
+    pub name: String,
+    pub received: u64,
+    pub lost: u64,
+    pub late: u64,
+    pub duplicates: u64,
+}
+
+struct Peer {
+    name: String,
+    window: SeqWindow,
+    late: u64,
+    duplicates: u64,
+    last_seen: SystemTime,
+}
+
+impl Peer {
+    fn stats(&self) -> PeerStats {
+        PeerStats {
+            name: self.name.clone(),
+            received: self.window.received(),
+            lost: self.window.lost(),
+            late: self.late,
+            duplicates: self.duplicates,
+        }
+    }
+}
+
+/// What a node noticed while receiving
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Joined { name: String },
+    Message { from: String, text: String },
+    Left { stats: PeerStats },
+    TimedOut { stats: PeerStats },
+    Malformed { error: DecodeError },
+}
+
+// ========== Node ==========
+
+type DropFilter = Box<dyn Fn(u32) -> bool + Send>;
+
+/// One chat participant on one UDP socket
+pub struct ChatNode {
+    name: String,
+    socket: UdpSocket,
+    clock: Arc<dyn Clock>,
+    next_seq: u32,
+    peers: HashMap<SocketAddr, Peer>,
+    drop_outgoing: Option<DropFilter>,
+}
+
+impl ChatNode {
+    pub fn bind(addr: impl ToSocketAddrs, name: &str, clock: Arc<dyn Clock>) -> io::Result<Self> {
+        Ok(ChatNode {
+            name: name.to_string(),
+            socket: UdpSocket::bind(addr)?,
+            clock,
+            next_seq: 0,
+            peers: HashMap::new(),
+            drop_outgoing: None,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Silently skips sending any packet whose sequence number matches, as if the
+    /// network had lost it
+    pub fn drop_outgoing(&mut self, filter: impl Fn(u32) -> bool + Send + 'static) {
+        self.drop_outgoing = Some(Box::new(filter));
+    }
+
+    /// Introduces this node to `addr`, which starts tracking it
+    pub fn hello(&mut self, addr: SocketAddr) -> io::Result<u32> {
+        let name = self.name.clone();
+        self.send(addr, Body::Hello { name })
+    }
+
+    pub fn say(&mut self, addr: SocketAddr, text: &str) -> io::Result<u32> {
+        self.send(addr, Body::Chat { text: text.to_string() })
+    }
+
+    pub fn heartbeat(&mut self, addr: SocketAddr) -> io::Result<u32> {
+        self.send(addr, Body::Heartbeat)
+    }
+
+    pub fn bye(&mut self, addr: SocketAddr) -> io::Result<u32> {
+        self.send(addr, Body::Bye)
+    }
+
+    /// Sends `body` with the next sequence number, which is returned
+    fn send(&mut self, addr: SocketAddr, body: Body) -> io::Result<u32> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        if !self.drop_outgoing.as_ref().is_some_and(|filter| filter(seq)) {
+            self.send_packet(addr, &Packet { seq, body })?;
+        }
+        Ok(seq)
+    }
+
+    /// Sends a packet exactly as given, for replaying or reordering on purpose
+    pub fn send_packet(&self, addr: SocketAddr, packet: &Packet) -> io::Result<()> {
+        self.socket.send_to(&packet.encode(), addr).map(drop)
+    }
+
+    /// Receives until nothing arrives for `wait` (which must not be zero)
+    pub fn poll(&mut self, wait: Duration) -> io::Result<Vec<Event>> {
+        self.socket.set_read_timeout(Some(wait))?;
+        let mut events = Vec::new();
+        let mut buf = [0u8; MAX_DATAGRAM + 1];
+        loop {
+            match self.socket.recv_from(&mut buf) {
+                Ok((len, from)) => events.extend(self.handle(&buf[..len], from)),
+                Err(err) if is_timeout(&err) => return Ok(events),
+                // Windows reports an ICMP "port unreachable" from an earlier send this way
+                Err(err) if err.kind() == io::ErrorKind::ConnectionReset => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn handle(&mut self, bytes: &[u8], from: SocketAddr) -> Option<Event> {
+        let packet = match Packet::decode(bytes) {
+            Ok(packet) => packet,
+            Err(error) => return Some(Event::Malformed { error }),
+        };
+        let now = self.clock.now();
+        let joined = match (&packet.body, self.peers.contains_key(&from)) {
+            (Body::Hello { name }, false) => {
+                let peer = Peer { name: name.clone(), window: SeqWindow::default(), late: 0, duplicates: 0, last_seen: now };
+                self.peers.insert(from, peer);
+                true
+            }
+            // Anything else from a stranger is ignored until it says hello
+            (_, false) => return None,
+            (_, true) => false,
+        };
+
+        let peer = self.peers.get_mut(&from)?;
+        peer.last_seen = now;
+        match peer.window.record(packet.seq) {
+            Arrival::New => {}
+            Arrival::Late => peer.late += 1,
+            Arrival::Duplicate => {
+                peer.duplicates += 1;
+                return None;
+            }
+            Arrival::TooOld => return None,
+        }
+        match packet.body {
+            Body::Hello { name } if joined => Some(Event::Joined { name }),
+            Body::Chat { text } => Some(Event::Message { from: peer.name.clone(), text }),
+            Body::Bye => {
+                let stats = peer.stats();
+                self.peers.remove(&from);
+                Some(Event::Left { stats })
+            }
+            Body::Hello { .. } | Body::Heartbeat => None,
+        }
+    }
+
+    /// Drops every peer not heard from for longer than `PEER_TIMEOUT`
+    pub fn expire(&mut self) -> Vec<Event> {
+        let now = self.clock.now();
+        let silent = |peer: &Peer| now.duration_since(peer.last_seen).is_ok_and(|quiet| quiet > PEER_TIMEOUT);
+        let mut expired: Vec<PeerStats> = self.peers.values().filter(|peer| silent(peer)).map(Peer::stats).collect();
+        self.peers.retain(|_, peer| !silent(peer));
+        expired.sort_by(|a, b| a.name.cmp(&b.name));
+        expired.into_iter().map(|stats| Event::TimedOut { stats }).collect()
+    }
+
+    /// Everyone this node is tracking, by name
+    pub fn peers(&self) -> Vec<PeerStats> {
+        let mut stats: Vec<PeerStats> = self.peers.values().map(Peer::stats).collect();
+        stats.sort_by(|a, b| a.name.cmp(&b.name));
+        stats
+    }
+}
+
+// ========== Demo Code ==========
+
+const POLL: Duration = Duration::from_millis(30);
+
+fn print_events(out: &mut dyn Write, who: &str, events: &[Event]) -> io::Result<()> {
+    for event in events {
+        writeln!(out, "{} saw {:?}", who, event)?;
+    }
+    Ok(())
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct UdpChatDemo;
+
+impl Demo for UdpChatDemo {
+    fn name(&self) -> &'static str {
+        "udp_chat"
+    }
+
+    fn category(&self) -> &'static str {
+        "networking"
+    }
+
+    fn description(&self) -> &'static str {
+        "UDP chat with a hand-rolled packet format, heartbeats, peer timeouts and loss accounting"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Packet Format =====")?;
+        let packet = Packet { seq: 7, body: Body::Chat { text: "hi".to_string() } };
+        let bytes = packet.encode();
+        writeln!(out, "{:?}\n  -> {:?}", packet, bytes)?;
+        writeln!(out, "decodes back: {}", Packet::decode(&bytes).as_ref() == Ok(&packet))?;
+        for (label, bad) in [
+            ("truncated", &bytes[..5]),
+            ("wrong magic", &b"XX\x01\x03\x00\x00\x00\x01"[..]),
+            ("kind 9", &b"TN\x01\x09\x00\x00\x00\x01"[..]),
+            ("short text", &bytes[..bytes.len() - 1]),
+        ] {
+            writeln!(out, "{:<12} -> {}", label, Packet::decode(bad).unwrap_err())?;
+        }
+
+        writeln!(out, "\n===== Chat Over Loopback =====")?;
+        let clock = Arc::new(FakeClock::at_unix(1_700_000_000));
+        let mut alice = ChatNode::bind("127.0.0.1:0", "alice", clock.clone())?;
+        let mut bob = ChatNode::bind("127.0.0.1:0", "bob", clock.clone())?;
+        let (alice_addr, bob_addr) = (alice.local_addr()?, bob.local_addr()?);
+        alice.hello(bob_addr)?;
+        bob.hello(alice_addr)?;
+        alice.say(bob_addr, "hi bob")?;
+        bob.say(alice_addr, "hey alice")?;
+        print_events(out, "bob", &bob.poll(POLL)?)?;
+        print_events(out, "alice", &alice.poll(POLL)?)?;
+
+        writeln!(out, "\n===== Loss, Reordering and Duplicates =====")?;
+        // alice's packets 3, 5 and 6 never leave; 5 is then sent late by hand, 7 twice
+        alice.drop_outgoing(|seq| [3, 5, 6].contains(&seq));
+        for _ in 0..6 {
+            alice.heartbeat(bob_addr)?;
+        }
+        alice.send_packet(bob_addr, &Packet { seq: 5, body: Body::Heartbeat })?;
+        alice.send_packet(bob_addr, &Packet { seq: 7, body: Body::Heartbeat })?;
+        print_events(out, "bob", &bob.poll(POLL)?)?;
+        writeln!(out, "bob's view of alice: {:?}", bob.peers())?;
+
+        writeln!(out, "\n===== Heartbeats and Timeouts =====")?;
+        let mut carol = ChatNode::bind("127.0.0.1:0", "carol", clock.clone())?;
+        carol.hello(bob_addr)?;
+        print_events(out, "bob", &bob.poll(POLL)?)?;
+        for second in 1..=4 {
+            clock.advance(Duration::from_secs(1));
+            // alice keeps sending heartbeats; carol has gone quiet
+            alice.heartbeat(bob_addr)?;
+            bob.poll(POLL)?;
+            print_events(out, "bob", &bob.expire())?;
+            let tracked: Vec<String> = bob.peers().into_iter().map(|peer| peer.name).collect();
+            writeln!(out, "after second {}: bob tracks {:?}", second, tracked)?;
+        }
+        alice.bye(bob_addr)?;
+        print_events(out, "bob", &bob.poll(POLL)?)?;
+
+        writeln!(out, "\n===== Garbage on the Port =====")?;
+        let stranger = UdpSocket::bind("127.0.0.1:0")?;
+        stranger.send_to(b"GET / HTTP/1.1\r\n\r\n", bob_addr)?;
+        stranger.send_to(&Packet { seq: 0, body: Body::Chat { text: "who am I?".to_string() } }.encode(), bob_addr)?;
+        let events = bob.poll(POLL)?;
+        print_events(out, "bob", &events)?;
+        writeln!(out, "chat from a peer that never said hello is ignored: {}", events.len() == 1)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&UdpChatDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_body_round_trips() {
+        for body in [
+            Body::Hello { name: "ünïcode".to_string() },
+            Body::Chat { text: String::new() },
+            Body::Chat { text: "x".repeat(MAX_TEXT) },
+            Body::Heartbeat,
+            Body::Bye,
+        ] {
+            let packet = Packet { seq: u32::MAX, body };
+            assert_eq!(Packet::decode(&packet.encode()), Ok(packet));
+        }
+    }
+
+    #[test]
+    fn long_text_is_cut_on_a_char_boundary() {
+        let text = "é".repeat(MAX_TEXT);
+        let decoded = Packet::decode(&Packet { seq: 1, body: Body::Chat { text } }.encode()).unwrap();
+        let Body::Chat { text } = decoded.body else { panic!("not a chat packet") };
+        assert_eq!(text.len(), MAX_TEXT);
+    }
+
+    #[test]
+    fn malformed_datagrams_are_rejected() {
+        let chat = Packet { seq: 1, body: Body::Chat { text: "abc".to_string() } }.encode();
+        let mut long = chat.clone();
+        long.push(b'!');
+        let mut not_utf8 = chat.clone();
+        *not_utf8.last_mut().unwrap() = 0xff;
+        let mut huge = chat[..HEADER_LEN].to_vec();
+        huge.extend_from_slice(&((MAX_TEXT + 1) as u16).to_be_bytes());
+
+        assert_eq!(Packet::decode(&chat[..4]), Err(DecodeError::TooShort));
+        assert_eq!(Packet::decode(&chat[..HEADER_LEN + 1]), Err(DecodeError::TooShort));
+        assert_eq!(Packet::decode(b"TN\x02\x03\0\0\0\0"), Err(DecodeError::UnsupportedVersion(2)));
+        assert_eq!(Packet::decode(b"TN\x01\x03\0\0\0\0!"), Err(DecodeError::BadLength));
+        assert_eq!(Packet::decode(&long), Err(DecodeError::BadLength));
+        assert_eq!(Packet::decode(&not_utf8), Err(DecodeError::NotUtf8));
+        assert_eq!(Packet::decode(&huge), Err(DecodeError::TextTooLong));
+    }
+
+    #[test]
+    fn window_classifies_arrivals_and_counts_losses() {
+        let mut window = SeqWindow::default();
+        let arrivals: Vec<Arrival> = [10, 11, 14, 12, 12, 16].iter().map(|&seq| window.record(seq)).collect();
+        assert_eq!(
+            arrivals,
+            [Arrival::New, Arrival::New, Arrival::New, Arrival::Late, Arrival::Duplicate, Arrival::New]
+        );
+        // 10..=16 is seven numbers; 13 and 15 are missing
+        assert_eq!((window.received(), window.lost()), (5, 2));
+
+        assert_eq!(window.record(200), Arrival::New);
+        assert_eq!(window.record(100), Arrival::TooOld);
+        assert_eq!(window.lost(), 185);
+    }
+
+    #[test]
+    fn a_late_packet_older_than_the_first_extends_the_range() {
+        let mut window = SeqWindow::default();
+        window.record(5);
+        window.record(6);
+        assert_eq!(window.record(3), Arrival::Late);
+        assert_eq!((window.received(), window.lost()), (3, 1));
+    }
+}
@@ -0,0 +1,81 @@
+//! `#[derive(Describe)]`: walks the struct's fields and spells out their types.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Data, DeriveInput, Fields};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(data.enum_token.span, "Describe can only be derived for structs"));
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(data.union_token.span, "Describe can only be derived for structs"));
+        }
+    };
+
+    let entries: Vec<(String, String)> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = field.ident.as_ref().map_or_else(|| i.to_string(), ToString::to_string);
+            (name, source_text(&field.ty))
+        })
+        .collect();
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let title = format!("{}{}", name, source_text(&type_generics));
+    let description = match fields {
+        Fields::Named(_) => {
+            let list: Vec<String> = entries.iter().map(|(field, ty)| format!("{}: {}", field, ty)).collect();
+            if list.is_empty() {
+                format!("{} {{}}", title)
+            } else {
+                format!("{} {{ {} }}", title, list.join(", "))
+            }
+        }
+        Fields::Unnamed(_) => {
+            let list: Vec<&str> = entries.iter().map(|(_, ty)| ty.as_str()).collect();
+            format!("{}({})", title, list.join(", "))
+        }
+        Fields::Unit => title,
+    };
+    let (field_names, field_types): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+
+    Ok(quote! {
+        impl #impl_generics #name #type_generics #where_clause {
+            /// Each field's name and type as written in the source, in declaration order
+            pub const FIELDS: &'static [(&'static str, &'static str)] = &[#((#field_names, #field_types)),*];
+
+            /// The struct's name, generics and fields on one line
+            pub fn describe() -> &'static str {
+                #description
+            }
+        }
+    })
+}
+
+/// Tokens printed the way people write them: `Vec<u8>` rather than `Vec < u8 >`
+fn source_text(tokens: &impl ToTokens) -> String {
+    let spaced = tokens.to_token_stream().to_string();
+    let mut text = String::with_capacity(spaced.len());
+    let chars: Vec<char> = spaced.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let before = i.checked_sub(1).map(|j| chars[j]);
+            let after = chars.get(i + 1).copied();
+            // Drop the spaces `to_string` puts around brackets and paths, keeping `-> T`,
+            // `dyn A + B` and the like
+            let tight_after = matches!(before, Some('<' | '&' | '(' | '[' | ':'));
+            let tight_before = matches!(after, Some('<' | '>' | ',' | ';' | ')' | ']' | ':'));
+            let call = after == Some('(') && before.is_some_and(|b| b.is_alphanumeric() || b == '_');
+            if tight_after || tight_before || call {
+                continue;
+            }
+        }
+        text.push(c);
+    }
+    text
+}
@@ -0,0 +1,76 @@
+//! Procedural macro snippets from the tech notes.
+//!
+//! A proc-macro crate is a compiler plugin: each macro is a function from tokens to
+//! tokens, run at compile time. `syn` parses the input tokens into a syntax tree,
+//! `quote!` builds the output, and errors come back as a `syn::Error` turned into a
+//! `compile_error!` pointing at the offending span.
+//! - `#[derive(Describe)]` reads a struct's fields and emits `FIELDS` and `describe()`.
+//! - `#[trace]` rewrites a function so it logs its arguments on entry and its exit.
+//!
+//! `tests/ui` holds the trybuild cases: programs that must compile, and programs that
+//! must fail with the error in the matching `.stderr` file.
+
+use proc_macro::TokenStream;
+
+mod describe;
+mod trace;
+
+/// Adds `FIELDS`, each field's name and type as written, and `describe()`, the struct's
+/// shape as one line:
+///
+/// ```
+/// use proc_macros::Describe;
+///
+/// #[derive(Describe)]
+/// struct Point<T> {
+///     x: T,
+///     y: Option<Vec<T>>,
+/// }
+///
+/// assert_eq!(Point::<f64>::FIELDS, [("x", "T"), ("y", "Option<Vec<T>>")]);
+/// assert_eq!(Point::<f64>::describe(), "Point<T> { x: T, y: Option<Vec<T>> }");
+/// ```
+///
+/// Tuple struct fields are named by position, and unit structs have no fields. Enums and
+/// unions are rejected.
+#[proc_macro_derive(Describe)]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    describe::expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Logs `-> name(arg = value, ...)` when the function is called and `<- name` when it
+/// returns, including early returns and `?`; a panic logs `<- name (panicked)`.
+///
+/// ```
+/// use proc_macros::trace;
+///
+/// thread_local!(static LOG: std::cell::RefCell<Vec<String>> = Default::default());
+///
+/// fn record(line: &str) {
+///     LOG.with(|log| log.borrow_mut().push(line.to_string()));
+/// }
+///
+/// #[trace(sink = record, skip(secret))]
+/// fn add(a: i32, b: i32, secret: &str) -> i32 {
+///     a + b
+/// }
+///
+/// assert_eq!(add(2, 3, "hunter2"), 5);
+/// LOG.with(|log| assert_eq!(*log.borrow(), ["-> add(a = 2, b = 3)", "<- add"]));
+/// ```
+///
+/// Every logged argument must implement `Debug`. Options:
+/// - `sink = path`: a `fn(&str)` that receives each line; the default is `eprintln!`
+/// - `skip(a, b)`: arguments to leave out of the entry line
+///
+/// Receivers (`self`) and destructured arguments are never logged, and `async fn` is
+/// rejected because its body runs after the call returns.
+#[proc_macro_attribute]
+pub fn trace(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = trace::Options::default();
+    let parser = syn::meta::parser(|meta| options.parse(meta));
+    syn::parse_macro_input!(args with parser);
+    let function = syn::parse_macro_input!(item as syn::ItemFn);
+    trace::expand(options, function).unwrap_or_else(syn::Error::into_compile_error).into()
+}
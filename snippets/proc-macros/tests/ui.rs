@@ -0,0 +1,9 @@
+//! Compile-time behaviour of the macros, checked with trybuild: everything in `ui/pass`
+//! must build and run, everything in `ui/fail` must fail with its `.stderr` output.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,9 @@
+use proc_macros::Describe;
+
+#[derive(Describe)]
+enum Shape {
+    Circle(f64),
+    Square(f64),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use proc_macros::trace;
+
+#[trace]
+async fn fetch(url: &str) -> usize {
+    url.len()
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use proc_macros::trace;
+
+#[trace(level = "debug")]
+fn unknown_option() {}
+
+#[trace(skip(missing))]
+fn skips_a_missing_argument(present: u8) -> u8 {
+    present
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use proc_macros::trace;
+
+#[trace]
+struct Config {
+    verbose: bool,
+}
+
+fn main() {}
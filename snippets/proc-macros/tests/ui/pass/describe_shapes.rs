@@ -0,0 +1,48 @@
+use proc_macros::Describe;
+use std::collections::HashMap;
+
+#[derive(Describe)]
+struct Named {
+    id: u64,
+    tags: Vec<String>,
+    index: HashMap<String, Vec<(u8, char)>>,
+}
+
+#[derive(Describe)]
+struct Tuple(f64, [u8; 4], Option<Box<dyn Fn(u8) -> u8>>);
+
+#[derive(Describe)]
+struct Unit;
+
+#[derive(Describe)]
+struct Empty {}
+
+#[derive(Describe)]
+struct Borrowed<'a, T: Clone>
+where
+    T: Default,
+{
+    name: &'a str,
+    items: &'a mut [T],
+    path: std::path::PathBuf,
+}
+
+fn main() {
+    assert_eq!(
+        Named::FIELDS,
+        [("id", "u64"), ("tags", "Vec<String>"), ("index", "HashMap<String, Vec<(u8, char)>>")]
+    );
+    assert_eq!(Named::describe(), "Named { id: u64, tags: Vec<String>, index: HashMap<String, Vec<(u8, char)>> }");
+
+    assert_eq!(Tuple::FIELDS[1], ("1", "[u8; 4]"));
+    assert_eq!(Tuple::describe(), "Tuple(f64, [u8; 4], Option<Box<dyn Fn(u8) -> u8>>)");
+
+    assert!(Unit::FIELDS.is_empty());
+    assert_eq!(Unit::describe(), "Unit");
+    assert_eq!(Empty::describe(), "Empty {}");
+
+    assert_eq!(
+        Borrowed::<'static, u8>::describe(),
+        "Borrowed<'a, T> { name: &'a str, items: &'a mut [T], path: std::path::PathBuf }"
+    );
+}
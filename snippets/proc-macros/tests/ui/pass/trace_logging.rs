@@ -0,0 +1,74 @@
+use proc_macros::trace;
+use std::cell::RefCell;
+
+thread_local!(static LOG: RefCell<Vec<String>> = RefCell::new(Vec::new()));
+
+fn record(line: &str) {
+    LOG.with(|log| log.borrow_mut().push(line.to_string()));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+#[trace(sink = record)]
+fn parse_sum(a: &str, b: &str) -> Result<i32, std::num::ParseIntError> {
+    let a: i32 = a.parse()?;
+    if a == 0 {
+        return Ok(0);
+    }
+    Ok(a + b.parse::<i32>()?)
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    #[trace(sink = record, skip(label))]
+    fn bump(&mut self, by: u32, label: &str) -> u32 {
+        let _ = label;
+        self.count += by;
+        self.count
+    }
+}
+
+#[trace(sink = record)]
+fn first<T: std::fmt::Debug + Clone>((a, _): (T, T), fallback: T) -> T {
+    let _ = fallback;
+    a
+}
+
+// An argument named like the macro's own local is still the caller's variable
+#[trace(sink = record)]
+fn echo(line: u32) -> u32 {
+    line
+}
+
+#[trace(sink = record)]
+fn explode(depth: u8) {
+    panic!("boom at {}", depth);
+}
+
+fn main() {
+    assert_eq!(parse_sum("2", "40"), Ok(42));
+    assert_eq!(take_log(), ["-> parse_sum(a = \"2\", b = \"40\")", "<- parse_sum"]);
+
+    assert!(parse_sum("x", "1").is_err());
+    assert_eq!(parse_sum("0", "x"), Ok(0));
+    assert_eq!(take_log().len(), 4, "? and early return still log the exit");
+
+    let mut counter = Counter { count: 1 };
+    assert_eq!(counter.bump(2, "hidden"), 3);
+    assert_eq!(take_log(), ["-> bump(by = 2)", "<- bump"]);
+
+    assert_eq!(first(("a", "b"), "z"), "a");
+    assert_eq!(take_log(), ["-> first(fallback = \"z\")", "<- first"]);
+
+    assert_eq!(echo(7), 7);
+    assert_eq!(take_log(), ["-> echo(line = 7)", "<- echo"]);
+
+    std::panic::set_hook(Box::new(|_| {}));
+    assert!(std::panic::catch_unwind(|| explode(3)).is_err());
+    assert_eq!(take_log(), ["-> explode(depth = 3)", "<- explode (panicked)"]);
+}
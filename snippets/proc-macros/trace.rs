@@ -0,0 +1,89 @@
+//! `#[trace]`: wraps a function body in entry and exit logging.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{FnArg, Ident, ItemFn, Pat, Path};
+
+/// What goes inside `#[trace(...)]`
+#[derive(Default)]
+pub struct Options {
+    sink: Option<Path>,
+    skip: Vec<Ident>,
+}
+
+impl Options {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("sink") {
+            if self.sink.is_some() {
+                return Err(meta.error("`sink` given twice"));
+            }
+            self.sink = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            meta.parse_nested_meta(|arg| {
+                let ident = arg.path.require_ident()?;
+                self.skip.push(ident.clone());
+                Ok(())
+            })
+        } else {
+            Err(meta.error("unknown #[trace] option; expected `sink = path` or `skip(args)`"))
+        }
+    }
+}
+
+pub fn expand(options: Options, function: ItemFn) -> syn::Result<TokenStream> {
+    let sig = &function.sig;
+    if let Some(token) = &sig.asyncness {
+        return Err(syn::Error::new(token.span, "#[trace] does not support async fn"));
+    }
+
+    // Plain `name: Type` arguments, minus the skipped ones
+    let mut logged = Vec::new();
+    for input in &sig.inputs {
+        if let FnArg::Typed(arg) = input {
+            if let Pat::Ident(pat) = &*arg.pat {
+                logged.push(&pat.ident);
+            }
+        }
+    }
+    for skipped in &options.skip {
+        if !logged.contains(&skipped) {
+            return Err(syn::Error::new(skipped.span(), format!("`{}` is not an argument of this function", skipped)));
+        }
+    }
+    logged.retain(|ident| !options.skip.contains(ident));
+
+    let name = sig.ident.to_string();
+    let entry_format = {
+        let args: Vec<String> = logged.iter().map(|ident| format!("{} = {{:?}}", ident)).collect();
+        format!("-> {}({})", name, args.join(", "))
+    };
+    // Mixed-site hygiene keeps this local apart from any `line` the sink expression or
+    // the body might mention
+    let line = Ident::new("line", Span::mixed_site());
+    let write_line = match &options.sink {
+        Some(path) => quote!(#path(&#line)),
+        None => quote!(::std::eprintln!("{}", #line)),
+    };
+
+    let ItemFn { attrs, vis, block, .. } = &function;
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            // Dropped on every way out of the body, so early returns and `?` log too
+            struct TraceExit;
+            impl ::std::ops::Drop for TraceExit {
+                fn drop(&mut self) {
+                    let how = if ::std::thread::panicking() { " (panicked)" } else { "" };
+                    let #line = ::std::format!("<- {}{}", #name, how);
+                    #write_line;
+                }
+            }
+            let #line = ::std::format!(#entry_format, #(#logged),*);
+            #write_line;
+            let _trace_exit = TraceExit;
+            #block
+        }
+    })
+}
@@ -0,0 +1,30 @@
+//! Criterion benchmark for the three pipelines in `dispatch.rs`
+//!
+//! Each style runs the same four-stage pipeline over the same inputs, so the gap between
+//! them is the cost of the vtable call or the `match`, plus whatever inlining it prevents.
+//!
+//! Run with `cargo bench -p rust-lang --bench dispatch`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_lang::dispatch::*;
+
+fn bench_pipelines(c: &mut Criterion) {
+    let generic = static_pipeline();
+    let boxed = dyn_pipeline();
+    let enums = enum_pipeline();
+
+    let mut group = c.benchmark_group("pipeline");
+    for len in [1_000usize, 100_000] {
+        let data = sample_input(len, 42);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("generics", len), &data, |b, data| {
+            b.iter(|| run_static(&generic, data))
+        });
+        group.bench_with_input(BenchmarkId::new("dyn", len), &data, |b, data| b.iter(|| run_dyn(&boxed, data)));
+        group.bench_with_input(BenchmarkId::new("enum", len), &data, |b, data| b.iter(|| run_enum(&enums, data)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipelines);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+//! Criterion benchmark for the line sources in `file_io.rs`
+//!
+//! Every source counts the words of one generated fixture, 256 MB unless
+//! `FILE_IO_FIXTURE_MB` says otherwise. It is rewritten at the start of each run, which
+//! also leaves it in the page cache, so the numbers measure reading from memory rather
+//! than from the disk.
+//!
+//! Run with `cargo bench -p rust-lang --bench file_io --features mmap`; without the
+//! feature the memory-mapped source is left out.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_lang::file_io::*;
+use std::path::PathBuf;
+
+fn fixture() -> (PathBuf, Counts) {
+    let mb: u64 = std::env::var("FILE_IO_FIXTURE_MB").ok().and_then(|mb| mb.parse().ok()).unwrap_or(256);
+    let path = std::env::temp_dir().join(format!("tech-notes-file-io-bench-{}mb.txt", mb));
+    let expected = write_fixture(&path, mb * 1024 * 1024, 42).expect("write fixture");
+    (path, expected)
+}
+
+fn bench_word_count(c: &mut Criterion) {
+    let (path, expected) = fixture();
+    let len = std::fs::metadata(&path).expect("fixture metadata").len();
+
+    let mut group = c.benchmark_group("word_count");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(len));
+    for mut source in open_all(&path).expect("open fixture") {
+        assert_eq!(word_count(source.as_mut()).unwrap(), expected, "{} disagrees", source.name());
+        group.bench_function(BenchmarkId::from_parameter(source.name()), |b| {
+            b.iter(|| word_count(source.as_mut()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_word_count);
+criterion_main!(benches);
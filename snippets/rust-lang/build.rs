@@ -0,0 +1,104 @@
+//! Measures how code size grows with each dispatch style for `dispatch.rs`.
+//!
+//! For 1, 4 and 16 stage types, three probe crates are compiled to object files with the
+//! same `rustc` that builds this crate: one generic function instantiated per type, one
+//! function over `&dyn Stage`, and one `match` over an enum. Their sizes land in
+//! `$OUT_DIR/dispatch_sizes.rs`. If a probe cannot be built (no `rustc` on the path of a
+//! cross build, say), the table is simply empty.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const STAGE_COUNTS: [usize; 3] = [1, 4, 16];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo sets OUT_DIR"));
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+
+    let mut rows = String::new();
+    for count in STAGE_COUNTS {
+        let sizes: Option<Vec<u64>> = ["generic", "dyn", "enum"]
+            .iter()
+            .map(|style| probe_size(&rustc, &out_dir, style, count))
+            .collect();
+        if let Some(sizes) = sizes {
+            writeln!(
+                rows,
+                "    SizeNote {{ stage_types: {}, generic_bytes: {}, dyn_bytes: {}, enum_bytes: {} }},",
+                count, sizes[0], sizes[1], sizes[2]
+            )
+            .unwrap();
+        }
+    }
+    let table = format!("/// Object file sizes measured by `build.rs`\npub const SIZE_NOTES: &[SizeNote] = &[\n{}];\n", rows);
+    fs::write(out_dir.join("dispatch_sizes.rs"), table).expect("OUT_DIR is writable");
+}
+
+/// Compiles one probe and returns the size of its object file
+fn probe_size(rustc: &std::ffi::OsStr, out_dir: &Path, style: &str, count: usize) -> Option<u64> {
+    let name = format!("probe_{}_{}", style, count);
+    let source = out_dir.join(format!("{}.rs", name));
+    let object = out_dir.join(format!("{}.o", name));
+    fs::write(&source, probe_source(style, count)).ok()?;
+    let status = Command::new(rustc)
+        .args(["--edition=2021", "--crate-type=lib", "--emit=obj", "-Copt-level=3", "-Ccodegen-units=1", "-Cpanic=abort"])
+        .arg("--crate-name")
+        .arg(&name)
+        .arg("-o")
+        .arg(&object)
+        .arg(&source)
+        .status()
+        .ok()?;
+    if !status.success() {
+        return None;
+    }
+    fs::metadata(&object).ok().map(|meta| meta.len())
+}
+
+/// A crate with `count` stage types and one exported entry point per type, each running
+/// a small pipeline through the given dispatch style
+fn probe_source(style: &str, count: usize) -> String {
+    let mut src = String::from("pub trait Stage { fn apply(&self, x: i64) -> i64; }\n");
+    for i in 0..count {
+        writeln!(
+            src,
+            "pub struct S{i}(pub i64);\nimpl Stage for S{i} {{ fn apply(&self, x: i64) -> i64 {{ x.wrapping_mul({m}).wrapping_add(self.0) ^ {i} }} }}",
+            i = i,
+            m = i + 2
+        )
+        .unwrap();
+    }
+    match style {
+        "generic" => {
+            src.push_str("#[inline(never)] fn run<S: Stage>(s: &S, xs: &[i64]) -> i64 { xs.iter().map(|&x| s.apply(x)).fold(0, i64::wrapping_add) }\n");
+            for i in 0..count {
+                writeln!(src, "#[no_mangle] pub fn entry{i}(xs: &[i64]) -> i64 {{ run(&S{i}({i}), xs) }}", i = i).unwrap();
+            }
+        }
+        "dyn" => {
+            src.push_str("#[inline(never)] fn run(s: &dyn Stage, xs: &[i64]) -> i64 { xs.iter().map(|&x| s.apply(x)).fold(0, i64::wrapping_add) }\n");
+            for i in 0..count {
+                writeln!(src, "#[no_mangle] pub fn entry{i}(xs: &[i64]) -> i64 {{ run(&S{i}({i}), xs) }}", i = i).unwrap();
+            }
+        }
+        _ => {
+            let variants: Vec<String> = (0..count).map(|i| format!("V{i}(S{i})", i = i)).collect();
+            let arms: Vec<String> = (0..count).map(|i| format!("Kind::V{i}(s) => s.apply(x)", i = i)).collect();
+            writeln!(src, "pub enum Kind {{ {} }}", variants.join(", ")).unwrap();
+            writeln!(
+                src,
+                "#[inline(never)] fn run(k: &Kind, xs: &[i64]) -> i64 {{ xs.iter().map(|&x| match k {{ {} }}).fold(0, i64::wrapping_add) }}",
+                arms.join(", ")
+            )
+            .unwrap();
+            for i in 0..count {
+                writeln!(src, "#[no_mangle] pub fn entry{i}(xs: &[i64]) -> i64 {{ run(&Kind::V{i}(S{i}({i})), xs) }}", i = i).unwrap();
+            }
+        }
+    }
+    src
+}
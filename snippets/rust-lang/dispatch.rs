@@ -0,0 +1,315 @@
+//! Static vs Dynamic Dispatch in Rust
+//!
+//! One small number pipeline (scale, offset, clamp, absolute value) built three ways:
+//! - Generics: stages are composed into a single type, `Then<A, B>`, and every call is
+//!   resolved at compile time. The compiler emits one copy of the code per combination
+//! - `Box<dyn Stage>`: stages live in a `Vec` and every call goes through a vtable. One
+//!   copy of the code, any mix of stages at runtime, but no inlining across stages
+//! - Enum dispatch: the closed set of stages is an enum and each call is a `match`. No
+//!   heap allocation per stage and inlinable, but adding a stage means editing the enum
+//!
+//! The factory and strategy patterns pick between these every time they hand out a
+//! product or a strategy. `benches/dispatch.rs` measures the runtime side and `build.rs`
+//! measures the code-size side by compiling small probe crates for each style.
+
+use demo::Demo;
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::time::Instant;
+
+// ========== The Stage Trait ==========
+
+/// One step of the pipeline
+pub trait Stage {
+    fn apply(&self, x: i64) -> i64;
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale(pub i64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Offset(pub i64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clamp(pub i64, pub i64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Abs;
+
+impl Stage for Scale {
+    fn apply(&self, x: i64) -> i64 {
+        x.saturating_mul(self.0)
+    }
+    fn name(&self) -> &'static str {
+        "scale"
+    }
+}
+
+impl Stage for Offset {
+    fn apply(&self, x: i64) -> i64 {
+        x.saturating_add(self.0)
+    }
+    fn name(&self) -> &'static str {
+        "offset"
+    }
+}
+
+impl Stage for Clamp {
+    fn apply(&self, x: i64) -> i64 {
+        x.clamp(self.0, self.1)
+    }
+    fn name(&self) -> &'static str {
+        "clamp"
+    }
+}
+
+impl Stage for Abs {
+    fn apply(&self, x: i64) -> i64 {
+        x.saturating_abs()
+    }
+    fn name(&self) -> &'static str {
+        "abs"
+    }
+}
+
+// ========== 1. Generics ==========
+
+/// Runs `A` then `B`; nesting these builds the whole pipeline as one type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Then<A, B>(pub A, pub B);
+
+impl<A: Stage, B: Stage> Stage for Then<A, B> {
+    fn apply(&self, x: i64) -> i64 {
+        self.1.apply(self.0.apply(x))
+    }
+    fn name(&self) -> &'static str {
+        "then"
+    }
+}
+
+/// The demo pipeline as a single concrete type
+pub type StaticPipeline = Then<Then<Then<Scale, Offset>, Clamp>, Abs>;
+
+pub fn static_pipeline() -> StaticPipeline {
+    Then(Then(Then(Scale(3), Offset(-50)), Clamp(-100, 100)), Abs)
+}
+
+/// Sum of every input pushed through the pipeline; monomorphized for each `S`
+pub fn run_static<S: Stage>(pipeline: &S, input: &[i64]) -> i64 {
+    input.iter().map(|&x| pipeline.apply(x)).sum()
+}
+
+// ========== 2. Trait Objects ==========
+
+pub type DynPipeline = Vec<Box<dyn Stage>>;
+
+pub fn dyn_pipeline() -> DynPipeline {
+    vec![Box::new(Scale(3)), Box::new(Offset(-50)), Box::new(Clamp(-100, 100)), Box::new(Abs)]
+}
+
+/// Same sum, one vtable call per stage per element
+pub fn run_dyn(pipeline: &[Box<dyn Stage>], input: &[i64]) -> i64 {
+    input.iter().map(|&x| pipeline.iter().fold(x, |acc, stage| stage.apply(acc))).sum()
+}
+
+// ========== 3. Enum Dispatch ==========
+
+/// The closed set of stages, dispatched with a `match`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StageKind {
+    Scale(Scale),
+    Offset(Offset),
+    Clamp(Clamp),
+    Abs(Abs),
+}
+
+impl Stage for StageKind {
+    fn apply(&self, x: i64) -> i64 {
+        match self {
+            StageKind::Scale(s) => s.apply(x),
+            StageKind::Offset(s) => s.apply(x),
+            StageKind::Clamp(s) => s.apply(x),
+            StageKind::Abs(s) => s.apply(x),
+        }
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            StageKind::Scale(s) => s.name(),
+            StageKind::Offset(s) => s.name(),
+            StageKind::Clamp(s) => s.name(),
+            StageKind::Abs(s) => s.name(),
+        }
+    }
+}
+
+pub fn enum_pipeline() -> Vec<StageKind> {
+    vec![
+        StageKind::Scale(Scale(3)),
+        StageKind::Offset(Offset(-50)),
+        StageKind::Clamp(Clamp(-100, 100)),
+        StageKind::Abs(Abs),
+    ]
+}
+
+/// Same sum, one `match` per stage per element
+pub fn run_enum(pipeline: &[StageKind], input: &[i64]) -> i64 {
+    input.iter().map(|&x| pipeline.iter().fold(x, |acc, stage| stage.apply(acc))).sum()
+}
+
+// ========== Inputs and Code Size ==========
+
+/// Deterministic pseudo-random inputs in `-100..100` (xorshift, so no `rand` needed)
+pub fn sample_input(len: usize, seed: u64) -> Vec<i64> {
+    let mut state = seed.max(1);
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 200) as i64 - 100
+        })
+        .collect()
+}
+
+/// Object file sizes for one probe crate per dispatch style with `stage_types` stages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeNote {
+    pub stage_types: usize,
+    pub generic_bytes: u64,
+    pub dyn_bytes: u64,
+    pub enum_bytes: u64,
+}
+
+include!(concat!(env!("OUT_DIR"), "/dispatch_sizes.rs"));
+
+// ========== Demo ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct DispatchDemo;
+
+impl Demo for DispatchDemo {
+    fn name(&self) -> &'static str {
+        "dispatch"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "One pipeline via generics, Box<dyn Trait> and enum dispatch, with timing and code size"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== One Pipeline, Three Ways =====")?;
+        let input = [-40, -5, 0, 7, 30, 90];
+        let generic = static_pipeline();
+        let boxed = dyn_pipeline();
+        let enums = enum_pipeline();
+        let names: Vec<&str> = boxed.iter().map(|stage| stage.name()).collect();
+        writeln!(out, "Stages: {}", names.join(" -> "))?;
+        writeln!(out, "Input: {:?}", input)?;
+        let outputs: Vec<i64> = input.iter().map(|&x| generic.apply(x)).collect();
+        writeln!(out, "Output: {:?}", outputs)?;
+        writeln!(out, "generics: sum = {}", run_static(&generic, &input))?;
+        writeln!(out, "dyn:      sum = {}", run_dyn(&boxed, &input))?;
+        writeln!(out, "enum:     sum = {}", run_enum(&enums, &input))?;
+
+        writeln!(out, "\n===== What Each Value Costs =====")?;
+        writeln!(out, "size_of::<StaticPipeline>() = {} bytes", std::mem::size_of::<StaticPipeline>())?;
+        writeln!(out, "size_of::<Box<dyn Stage>>() = {} bytes (data + vtable pointer)", std::mem::size_of::<Box<dyn Stage>>())?;
+        writeln!(out, "size_of::<StageKind>()      = {} bytes (largest variant + tag)", std::mem::size_of::<StageKind>())?;
+        writeln!(out, "dyn pipeline heap allocations: {}", boxed.len())?;
+
+        writeln!(out, "\n===== Timing (one run, see benches/dispatch.rs for real numbers) =====")?;
+        let data = sample_input(100_000, 42);
+        let expected = run_static(&generic, &data);
+        time_run(out, "generics", expected, || run_static(black_box(&generic), black_box(&data)))?;
+        time_run(out, "dyn", expected, || run_dyn(black_box(&boxed), black_box(&data)))?;
+        time_run(out, "enum", expected, || run_enum(black_box(&enums), black_box(&data)))?;
+
+        writeln!(out, "\n===== Code Size (probe crates compiled by build.rs at opt-level 3) =====")?;
+        if SIZE_NOTES.is_empty() {
+            writeln!(out, "No measurements: the probe crates could not be compiled")?;
+        }
+        for note in SIZE_NOTES {
+            writeln!(
+                out,
+                "{} stage types: generic {} bytes, dyn {} bytes, enum {} bytes",
+                note.stage_types, note.generic_bytes, note.dyn_bytes, note.enum_bytes
+            )?;
+        }
+        writeln!(out, "Generic code is copied per stage type; dyn and enum code is shared.")?;
+        Ok(())
+    }
+}
+
+/// Times one pipeline run and checks it against the generic result
+fn time_run(out: &mut dyn Write, label: &str, expected: i64, run: impl Fn() -> i64) -> io::Result<()> {
+    let start = Instant::now();
+    let sum = run();
+    writeln!(out, "{:<9} matches generics: {}  {:?}", label, sum == expected, start.elapsed())
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&DispatchDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_three_pipelines_agree() {
+        let data = sample_input(1_000, 7);
+        let expected = run_static(&static_pipeline(), &data);
+        assert_eq!(run_dyn(&dyn_pipeline(), &data), expected);
+        assert_eq!(run_enum(&enum_pipeline(), &data), expected);
+    }
+
+    #[test]
+    fn pipeline_steps() {
+        let pipeline = static_pipeline();
+        // 3 * 10 - 50 = -20, inside the clamp, then abs
+        assert_eq!(pipeline.apply(10), 20);
+        // 3 * 90 - 50 = 220, clamped to 100
+        assert_eq!(pipeline.apply(90), 100);
+        assert_eq!(pipeline.apply(-40), 100);
+    }
+
+    #[test]
+    fn saturating_stages_do_not_overflow() {
+        assert_eq!(Scale(3).apply(i64::MAX), i64::MAX);
+        assert_eq!(Offset(-1).apply(i64::MIN), i64::MIN);
+        assert_eq!(Abs.apply(i64::MIN), i64::MAX);
+    }
+
+    #[test]
+    fn enum_is_smaller_than_a_box_plus_its_heap_data() {
+        // The enum holds its largest variant inline; a box adds a pointer pair on top of
+        // a heap allocation
+        assert!(std::mem::size_of::<StageKind>() <= std::mem::size_of::<Clamp>() + 8);
+        assert_eq!(std::mem::size_of::<Box<dyn Stage>>(), 2 * std::mem::size_of::<usize>());
+        assert_eq!(std::mem::size_of::<Then<Scale, Abs>>(), std::mem::size_of::<Scale>());
+    }
+
+    #[test]
+    fn sample_input_is_deterministic_and_in_range() {
+        let a = sample_input(500, 3);
+        assert_eq!(a, sample_input(500, 3));
+        assert_ne!(a, sample_input(500, 4));
+        assert!(a.iter().all(|x| (-100..100).contains(x)));
+    }
+
+    #[test]
+    fn size_notes_cover_each_stage_count() {
+        // Empty only when the probes could not be compiled
+        if SIZE_NOTES.is_empty() {
+            return;
+        }
+        let counts: Vec<usize> = SIZE_NOTES.iter().map(|note| note.stage_types).collect();
+        assert_eq!(counts, [1, 4, 16]);
+        assert!(SIZE_NOTES.iter().all(|note| note.generic_bytes > 0 && note.dyn_bytes > 0 && note.enum_bytes > 0));
+    }
+}
@@ -0,0 +1,565 @@
+//! Error Handling in Rust
+//!
+//! Errors are values, so the design questions are about types:
+//! - A library defines an error enum per module, one variant per thing that can go
+//!   wrong, with `Display` for people and `std::error::Error` for code. `source()`
+//!   links each error to the one that caused it, so a report can print the whole chain.
+//! - `From` conversions let `?` turn a low-level error into the caller's type on the
+//!   way out, without a `map_err` at every call.
+//! - What `thiserror` generates is exactly such an enum with its `Display`, `From` and
+//!   `source` impls written out by hand; the first half of this file is that code.
+//! - What `anyhow` offers is one boxed error type for applications, plus `.context()`
+//!   to wrap an error in a message saying what was being attempted. Callers that care
+//!   about the cause can still find it with `downcast_ref` along the chain.
+//! - Classifying errors as transient or permanent is what lets a retry loop with
+//!   exponential backoff try again only when another attempt could help.
+
+use demo::Demo;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::Duration;
+
+// ========== Error Types ==========
+
+/// Failures from a simulated network call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    Timeout { after_ms: u64 },
+    ConnectionRefused,
+    /// The server answered with an HTTP error status
+    Status(u16),
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::Timeout { after_ms } => write!(f, "timed out after {} ms", after_ms),
+            NetworkError::ConnectionRefused => write!(f, "connection refused"),
+            NetworkError::Status(code) => write!(f, "server returned status {}", code),
+        }
+    }
+}
+
+impl Error for NetworkError {}
+
+/// Why a `key = value` config text was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Syntax { line: usize },
+    MissingKey(&'static str),
+    /// A value that should be a number is not; the parse error is the source
+    InvalidNumber { key: String, line: usize, source: ParseIntError },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax { line } => write!(f, "line {}: expected `key = value`", line),
+            ConfigError::MissingKey(key) => write!(f, "missing required key `{}`", key),
+            // The cause is left to `source`, so a chain report does not say it twice
+            ConfigError::InvalidNumber { key, line, .. } => write!(f, "line {}: `{}` is not a number", line, key),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::InvalidNumber { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The top-level error of the small "load config, then fetch" application below
+#[derive(Debug)]
+pub enum AppError {
+    Config(ConfigError),
+    Network(NetworkError),
+    Io(io::Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Config(_) => write!(f, "invalid configuration"),
+            AppError::Network(_) => write!(f, "request failed"),
+            AppError::Io(_) => write!(f, "i/o error"),
+        }
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Config(e) => Some(e),
+            AppError::Network(e) => Some(e),
+            AppError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConfigError> for AppError {
+    fn from(e: ConfigError) -> Self {
+        AppError::Config(e)
+    }
+}
+
+impl From<NetworkError> for AppError {
+    fn from(e: NetworkError) -> Self {
+        AppError::Network(e)
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Io(e)
+    }
+}
+
+// ========== Classification ==========
+
+/// What a caller can do about an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Trying again later may work
+    Transient,
+    /// Trying again will fail the same way
+    Permanent,
+    /// The input has to change first
+    InvalidInput,
+}
+
+impl NetworkError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            NetworkError::Timeout { .. } | NetworkError::ConnectionRefused => ErrorClass::Transient,
+            NetworkError::Status(code) if *code == 429 || *code >= 500 => ErrorClass::Transient,
+            NetworkError::Status(_) => ErrorClass::Permanent,
+        }
+    }
+}
+
+impl AppError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            AppError::Config(_) => ErrorClass::InvalidInput,
+            AppError::Network(e) => e.class(),
+            AppError::Io(e) => io_class(e),
+        }
+    }
+}
+
+fn io_class(e: &io::Error) -> ErrorClass {
+    match e.kind() {
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => ErrorClass::Transient,
+        io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => ErrorClass::InvalidInput,
+        _ => ErrorClass::Permanent,
+    }
+}
+
+/// Classifies any error by looking for a known type along its chain, the way code using
+/// a boxed error type has to; unknown errors count as permanent
+pub fn classify(error: &(dyn Error + 'static)) -> ErrorClass {
+    for e in chain(error) {
+        if let Some(app) = e.downcast_ref::<AppError>() {
+            return app.class();
+        }
+        if let Some(network) = e.downcast_ref::<NetworkError>() {
+            return network.class();
+        }
+        if e.is::<ConfigError>() || e.is::<ParseIntError>() {
+            return ErrorClass::InvalidInput;
+        }
+        if let Some(io) = e.downcast_ref::<io::Error>() {
+            return io_class(io);
+        }
+    }
+    ErrorClass::Permanent
+}
+
+/// The error and every error behind it, outermost first
+pub fn chain<'a>(error: &'a (dyn Error + 'static)) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+    std::iter::successors(Some(error), |&e| e.source())
+}
+
+/// One line per error in the chain, the way an application's `main` reports failure
+pub fn report(error: &(dyn Error + 'static)) -> String {
+    let mut lines = vec![format!("error: {}", error)];
+    lines.extend(chain(error).skip(1).map(|e| format!("  caused by: {}", e)));
+    lines.join("\n")
+}
+
+// ========== Using `?` With `From` ==========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+    pub retries: u32,
+}
+
+/// Parses `key = value` lines; `#` starts a comment
+pub fn parse_config(text: &str) -> Result<Config, ConfigError> {
+    let (mut host, mut port, mut retries) = (None, None, 3);
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let content = raw.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+        let (key, value) = content.split_once('=').ok_or(ConfigError::Syntax { line })?;
+        let (key, value) = (key.trim(), value.trim());
+        match key {
+            "host" => host = Some(value.to_string()),
+            "port" => port = Some(parse_number(key, value, line)?),
+            "retries" => retries = parse_number(key, value, line)?,
+            _ => return Err(ConfigError::Syntax { line }),
+        }
+    }
+    Ok(Config {
+        host: host.ok_or(ConfigError::MissingKey("host"))?,
+        port: port.ok_or(ConfigError::MissingKey("port"))?,
+        retries,
+    })
+}
+
+fn parse_number<T: FromStr<Err = ParseIntError>>(key: &str, value: &str, line: usize) -> Result<T, ConfigError> {
+    value.parse().map_err(|source| ConfigError::InvalidNumber { key: key.to_string(), line, source })
+}
+
+/// Loads the config and makes one call; each `?` converts through `From<_> for AppError`
+pub fn load_and_fetch(
+    text: &str,
+    fetch: &mut dyn FnMut(&Config) -> Result<String, NetworkError>,
+) -> Result<String, AppError> {
+    let config = parse_config(text)?;
+    let body = fetch(&config)?;
+    Ok(body)
+}
+
+// ========== Boxed Errors With Context ==========
+
+/// Any error, boxed; what an application-level `Result` carries
+pub type BoxError = Box<dyn Error + Send + Sync + 'static>;
+
+/// An error wrapped in a note about what was being done when it happened
+#[derive(Debug)]
+pub struct Context {
+    message: String,
+    source: BoxError,
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for Context {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// `.context()` and `.with_context()` on any `Result` whose error can be boxed
+pub trait ResultExt<T> {
+    fn context(self, message: &str) -> Result<T, BoxError>;
+    fn with_context<F: FnOnce() -> String>(self, message: F) -> Result<T, BoxError>;
+}
+
+impl<T, E: Into<BoxError>> ResultExt<T> for Result<T, E> {
+    fn context(self, message: &str) -> Result<T, BoxError> {
+        self.with_context(|| message.to_string())
+    }
+
+    fn with_context<F: FnOnce() -> String>(self, message: F) -> Result<T, BoxError> {
+        self.map_err(|e| Box::new(Context { message: message(), source: e.into() }) as BoxError)
+    }
+}
+
+/// The same job as `load_and_fetch`, written application-style with one error type
+pub fn run_app(text: &str, fetch: &mut dyn FnMut(&Config) -> Result<String, NetworkError>) -> Result<usize, BoxError> {
+    let config = parse_config(text).context("could not load settings")?;
+    let body = fetch(&config).with_context(|| format!("could not reach {}:{}", config.host, config.port))?;
+    let count: usize = body.trim().parse().context("server sent a bad item count")?;
+    Ok(count)
+}
+
+// ========== Retry With Backoff ==========
+
+/// How many attempts to make and how long to wait between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub factor: u32,
+    pub max_delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            factor: 2,
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl Backoff {
+    /// The wait before attempt `n + 1`, for `n` from 1: doubling (by default) up to the cap
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + '_ {
+        std::iter::successors(Some(self.initial_delay.min(self.max_delay)), |d| {
+            Some(d.saturating_mul(self.factor).min(self.max_delay))
+        })
+        .take(self.max_attempts.saturating_sub(1) as usize)
+    }
+}
+
+/// Why `retry` gave up
+#[derive(Debug, PartialEq, Eq)]
+pub enum RetryError<E> {
+    /// The error was not worth retrying
+    Permanent { attempts: u32, error: E },
+    /// Every attempt failed with a transient error; this is the last one
+    Exhausted { attempts: u32, error: E },
+}
+
+impl<E> RetryError<E> {
+    pub fn attempts(&self) -> u32 {
+        match self {
+            RetryError::Permanent { attempts, .. } | RetryError::Exhausted { attempts, .. } => *attempts,
+        }
+    }
+
+    pub fn into_inner(self) -> E {
+        match self {
+            RetryError::Permanent { error, .. } | RetryError::Exhausted { error, .. } => error,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryError::Permanent { attempts, .. } => write!(f, "gave up after {} attempt(s): not retryable", attempts),
+            RetryError::Exhausted { attempts, .. } => write!(f, "gave up after {} attempts", attempts),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for RetryError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RetryError::Permanent { error, .. } | RetryError::Exhausted { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Calls `operation` (with the attempt number, from 1) until it succeeds, fails with an
+/// error `is_transient` rejects, or runs out of attempts, calling `sleep` between tries;
+/// real callers pass `std::thread::sleep`, tests record the delays instead
+pub fn retry<T, E>(
+    backoff: &Backoff,
+    is_transient: impl Fn(&E) -> bool,
+    mut sleep: impl FnMut(Duration),
+    mut operation: impl FnMut(u32) -> Result<T, E>,
+) -> Result<T, RetryError<E>> {
+    let mut delays = backoff.delays();
+    let mut attempt = 1;
+    loop {
+        match operation(attempt) {
+            Ok(value) => return Ok(value),
+            Err(error) if !is_transient(&error) => return Err(RetryError::Permanent { attempts: attempt, error }),
+            Err(error) => match delays.next() {
+                Some(delay) => sleep(delay),
+                None => return Err(RetryError::Exhausted { attempts: attempt, error }),
+            },
+        }
+        attempt += 1;
+    }
+}
+
+// ========== Demo Code ==========
+
+const GOOD_CONFIG: &str = "host = api.example.com\nport = 8080  # default is 80\nretries = 4\n";
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct ErrorHandlingDemo;
+
+impl Demo for ErrorHandlingDemo {
+    fn name(&self) -> &'static str {
+        "error_handling"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "Error enums, From and ?, error chains, context wrapping and retry with backoff"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Typed Errors and ? =====")?;
+        let configs = [
+            ("good", GOOD_CONFIG),
+            ("no port", "host = a.example\n"),
+            ("bad port", "host = a.example\nport = 99999\n"),
+            ("garbled", "host = a.example\nport 80\n"),
+        ];
+        for (label, text) in configs {
+            let mut fetch = |c: &Config| Ok(format!("hello from {}", c.host));
+            match load_and_fetch(text, &mut fetch) {
+                Ok(body) => writeln!(out, "{}: {}", label, body)?,
+                Err(e) => writeln!(out, "{} ({:?}):\n{}", label, e.class(), report(&e))?,
+            }
+        }
+
+        writeln!(out, "\n===== Boxed Errors With Context =====")?;
+        let cases: [(&str, Result<&str, NetworkError>); 3] = [
+            ("service down", Err(NetworkError::Status(503))),
+            ("bad payload", Ok("twelve")),
+            ("works", Ok("12")),
+        ];
+        for (label, response) in cases {
+            let mut fetch = |_: &Config| response.clone().map(str::to_string);
+            match run_app(GOOD_CONFIG, &mut fetch) {
+                Ok(count) => writeln!(out, "{}: {} items", label, count)?,
+                Err(e) => writeln!(out, "{} ({:?}):\n{}", label, classify(e.as_ref()), report(e.as_ref()))?,
+            }
+        }
+
+        writeln!(out, "\n===== Retry With Backoff =====")?;
+        let backoff = Backoff::default();
+        let delays: Vec<String> = backoff.delays().map(|d| format!("{}ms", d.as_millis())).collect();
+        writeln!(out, "policy: {} attempts, waits {}", backoff.max_attempts, delays.join(", "))?;
+        let scripts: [(&str, Vec<Result<&str, NetworkError>>); 3] = [
+            ("flaky", vec![Err(NetworkError::Timeout { after_ms: 500 }), Err(NetworkError::ConnectionRefused), Ok("ok")]),
+            ("not found", vec![Err(NetworkError::Status(404))]),
+            ("down", vec![Err(NetworkError::Status(503)); 5]),
+        ];
+        for (label, script) in scripts {
+            let mut waited = Vec::new();
+            let result = retry(
+                &backoff,
+                |e: &NetworkError| e.class() == ErrorClass::Transient,
+                |d| waited.push(d.as_millis()),
+                |attempt| script[attempt as usize - 1].clone(),
+            );
+            let waits: Vec<String> = waited.iter().map(|ms| format!("{}ms", ms)).collect();
+            match result {
+                Ok(body) => writeln!(out, "{}: {:?} after waiting {}", label, body, waits.join(" + "))?,
+                Err(e) => writeln!(out, "{}: {} (last error: {}), waited [{}]", label, e, e.source().unwrap(), waits.join(", "))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&ErrorHandlingDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok_fetch(_: &Config) -> Result<String, NetworkError> {
+        Ok("7".to_string())
+    }
+
+    #[test]
+    fn parses_a_config_and_rejects_bad_ones() {
+        assert_eq!(
+            parse_config(GOOD_CONFIG),
+            Ok(Config { host: "api.example.com".to_string(), port: 8080, retries: 4 })
+        );
+        assert_eq!(parse_config("host = h"), Err(ConfigError::MissingKey("port")));
+        assert_eq!(parse_config("port = 1\n\nnonsense"), Err(ConfigError::Syntax { line: 3 }));
+        let err = parse_config("host = h\nport = 70000").unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidNumber { line: 2, .. }), "{:?}", err);
+        assert!(err.source().is_some_and(|s| s.is::<ParseIntError>()));
+    }
+
+    #[test]
+    fn question_mark_converts_into_app_error() {
+        let err = load_and_fetch("port = x", &mut ok_fetch).unwrap_err();
+        assert!(matches!(err, AppError::Config(ConfigError::InvalidNumber { .. })));
+        assert_eq!(err.class(), ErrorClass::InvalidInput);
+
+        let err = load_and_fetch(GOOD_CONFIG, &mut |_| Err(NetworkError::Timeout { after_ms: 30 })).unwrap_err();
+        assert!(matches!(err, AppError::Network(NetworkError::Timeout { after_ms: 30 })));
+        assert_eq!(err.class(), ErrorClass::Transient);
+
+        let io_err: AppError = io::Error::new(io::ErrorKind::PermissionDenied, "no").into();
+        assert_eq!(io_err.class(), ErrorClass::Permanent);
+        assert_eq!(load_and_fetch(GOOD_CONFIG, &mut ok_fetch).unwrap(), "7");
+    }
+
+    #[test]
+    fn network_errors_are_classified() {
+        assert_eq!(NetworkError::ConnectionRefused.class(), ErrorClass::Transient);
+        assert_eq!(NetworkError::Status(429).class(), ErrorClass::Transient);
+        assert_eq!(NetworkError::Status(502).class(), ErrorClass::Transient);
+        assert_eq!(NetworkError::Status(404).class(), ErrorClass::Permanent);
+    }
+
+    #[test]
+    fn boxed_errors_keep_their_chain() {
+        let err = run_app(GOOD_CONFIG, &mut |_| Err(NetworkError::Status(503))).unwrap_err();
+        let messages: Vec<String> = chain(err.as_ref()).map(|e| e.to_string()).collect();
+        assert_eq!(messages, ["could not reach api.example.com:8080", "server returned status 503"]);
+        assert_eq!(classify(err.as_ref()), ErrorClass::Transient);
+
+        let err = run_app(GOOD_CONFIG, &mut |_| Ok("many".to_string())).unwrap_err();
+        assert_eq!(classify(err.as_ref()), ErrorClass::InvalidInput);
+        assert_eq!(report(err.as_ref()), "error: server sent a bad item count\n  caused by: invalid digit found in string");
+
+        let err = run_app("port = 1", &mut ok_fetch).unwrap_err();
+        assert_eq!(chain(err.as_ref()).count(), 2);
+        assert_eq!(classify(err.as_ref()), ErrorClass::InvalidInput);
+        assert_eq!(run_app(GOOD_CONFIG, &mut ok_fetch).unwrap(), 7);
+
+        let unknown: BoxError = "something odd".into();
+        assert_eq!(classify(unknown.as_ref()), ErrorClass::Permanent);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let backoff = Backoff { max_attempts: 7, ..Backoff::default() };
+        let millis: Vec<u128> = backoff.delays().map(|d| d.as_millis()).collect();
+        assert_eq!(millis, [100, 200, 400, 800, 1600, 2000]);
+        assert_eq!(Backoff { max_attempts: 1, ..Backoff::default() }.delays().count(), 0);
+    }
+
+    #[test]
+    fn retry_stops_on_success_permanent_errors_and_exhaustion() {
+        let backoff = Backoff::default();
+        let transient = |e: &NetworkError| e.class() == ErrorClass::Transient;
+
+        let mut slept = Vec::new();
+        let result = retry(&backoff, transient, |d| slept.push(d), |n| if n < 3 { Err(NetworkError::ConnectionRefused) } else { Ok(n) });
+        assert_eq!(result, Ok(3));
+        assert_eq!(slept, [Duration::from_millis(100), Duration::from_millis(200)]);
+
+        let mut calls = 0;
+        let result: Result<(), _> = retry(&backoff, transient, |_| {}, |_| {
+            calls += 1;
+            Err(NetworkError::Status(400))
+        });
+        assert_eq!(result, Err(RetryError::Permanent { attempts: 1, error: NetworkError::Status(400) }));
+        assert_eq!(calls, 1);
+
+        let result: Result<(), _> = retry(&backoff, transient, |_| {}, |_| Err(NetworkError::Status(503)));
+        let err = result.unwrap_err();
+        assert_eq!(err.attempts(), 5);
+        assert!(err.source().is_some_and(|s| s.is::<NetworkError>()));
+        assert_eq!(err.into_inner(), NetworkError::Status(503));
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::error_handling::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::file_io::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::iterators::run_demo();
+}
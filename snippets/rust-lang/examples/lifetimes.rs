@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::lifetimes::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::macros_declarative::run_demo();
+}
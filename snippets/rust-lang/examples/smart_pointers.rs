@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::smart_pointers::run_demo();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    rust_lang::testing_patterns::run_demo();
+}
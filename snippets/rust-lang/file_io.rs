@@ -0,0 +1,378 @@
+//! File I/O Patterns in Rust
+//!
+//! A `File` on its own makes one system call per `read`, so how a program pulls bytes
+//! off disk matters more than what it does with them. Three ways to feed the same
+//! word count:
+//! - `BufferedLines`: a `BufReader` and `read_until`, reusing one line buffer. The
+//!   common case; `lines()` would also work but allocates a `String` per line and
+//!   rejects non-UTF-8 input
+//! - `ChunkedLines`: raw `read`s into a fixed buffer, splitting lines by hand and
+//!   carrying the partial line at the end of each chunk over to the next
+//! - `MappedLines` (the `mmap` feature): `memmap2` maps the file into memory and lines are
+//!   slices of it, with no copying at all. The page cache does the reading, but the
+//!   mapping is `unsafe`: another process truncating the file turns reads into `SIGBUS`
+//!
+//! All three implement `LineSource`, so `word_count` and the benchmark in
+//! `benches/file_io.rs` treat them the same. `write_fixture` generates the input and
+//! returns the counts it should produce.
+
+use demo::Demo;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+// ========== Line Sources ==========
+
+/// Something that can walk a file line by line
+pub trait LineSource {
+    fn name(&self) -> &'static str;
+
+    /// Calls `f` with each line, without its `\n`, from the start of the file. A final
+    /// line with no `\n` still counts; a trailing `\n` does not start another line.
+    fn for_each_line(&mut self, f: &mut dyn FnMut(&[u8])) -> io::Result<()>;
+}
+
+pub struct BufferedLines {
+    reader: BufReader<File>,
+}
+
+impl BufferedLines {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(BufferedLines { reader: BufReader::new(File::open(path)?) })
+    }
+}
+
+impl LineSource for BufferedLines {
+    fn name(&self) -> &'static str {
+        "buffered"
+    }
+
+    fn for_each_line(&mut self, f: &mut dyn FnMut(&[u8])) -> io::Result<()> {
+        self.reader.rewind()?;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(());
+            }
+            f(line.strip_suffix(b"\n").unwrap_or(&line));
+        }
+    }
+}
+
+/// Default chunk size for `ChunkedLines`
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+pub struct ChunkedLines {
+    file: File,
+    chunk: Vec<u8>,
+}
+
+impl ChunkedLines {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::with_chunk_size(path, CHUNK_SIZE)
+    }
+
+    pub fn with_chunk_size(path: &Path, chunk_size: usize) -> io::Result<Self> {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Ok(ChunkedLines { file: File::open(path)?, chunk: vec![0; chunk_size] })
+    }
+}
+
+impl LineSource for ChunkedLines {
+    fn name(&self) -> &'static str {
+        "chunked"
+    }
+
+    fn for_each_line(&mut self, f: &mut dyn FnMut(&[u8])) -> io::Result<()> {
+        self.file.rewind()?;
+        // The start of a line whose end is in a later chunk
+        let mut carry = Vec::new();
+        loop {
+            let n = match self.file.read(&mut self.chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            let mut data = &self.chunk[..n];
+            while let Some(end) = data.iter().position(|&b| b == b'\n') {
+                if carry.is_empty() {
+                    f(&data[..end]);
+                } else {
+                    carry.extend_from_slice(&data[..end]);
+                    f(&carry);
+                    carry.clear();
+                }
+                data = &data[end + 1..];
+            }
+            carry.extend_from_slice(data);
+        }
+        if !carry.is_empty() {
+            f(&carry);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mmap")]
+pub struct MappedLines {
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedLines {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the map is only read, and nothing in this program writes to or
+        // truncates the file while it is mapped. Another process still could; that is
+        // the risk every mmap reader accepts.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(MappedLines { map })
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl LineSource for MappedLines {
+    fn name(&self) -> &'static str {
+        "mmap"
+    }
+
+    fn for_each_line(&mut self, f: &mut dyn FnMut(&[u8])) -> io::Result<()> {
+        let data = self.map.strip_suffix(b"\n").unwrap_or(&self.map);
+        if !data.is_empty() || self.map.len() == 1 {
+            data.split(|&b| b == b'\n').for_each(f);
+        }
+        Ok(())
+    }
+}
+
+/// Every source this build supports, each opened on `path`
+pub fn open_all(path: &Path) -> io::Result<Vec<Box<dyn LineSource>>> {
+    let sources: Vec<Box<dyn LineSource>> = vec![
+        Box::new(BufferedLines::open(path)?),
+        Box::new(ChunkedLines::open(path)?),
+        #[cfg(feature = "mmap")]
+        Box::new(MappedLines::open(path)?),
+    ];
+    Ok(sources)
+}
+
+// ========== Word Count ==========
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counts {
+    pub lines: u64,
+    pub words: u64,
+    /// In bytes, without the `\n`
+    pub longest_line: u64,
+}
+
+impl Counts {
+    fn add_line(&mut self, line: &[u8]) {
+        self.lines += 1;
+        self.words += line.split(u8::is_ascii_whitespace).filter(|word| !word.is_empty()).count() as u64;
+        self.longest_line = self.longest_line.max(line.len() as u64);
+    }
+}
+
+/// Lines, ASCII-whitespace-separated words and the longest line, like `wc -lw`
+pub fn word_count(source: &mut dyn LineSource) -> io::Result<Counts> {
+    let mut counts = Counts::default();
+    source.for_each_line(&mut |line| counts.add_line(line))?;
+    Ok(counts)
+}
+
+// ========== Fixture ==========
+
+const WORDS: &[&str] = &[
+    "the", "rust", "borrow", "checker", "lifetime", "trait", "memory", "page", "cache", "buffer", "syscall",
+    "kernel", "ownership", "slice", "iterator", "zero-copy", "tech", "notes",
+];
+
+/// Writes at least `min_bytes` of seeded pseudo-random text to `path` and returns the
+/// counts any correct `LineSource` should produce for it
+pub fn write_fixture(path: &Path, min_bytes: u64, seed: u64) -> io::Result<Counts> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut state = seed | 1;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut counts = Counts::default();
+    let mut written = 0;
+    let mut line = Vec::new();
+    while written < min_bytes {
+        line.clear();
+        // Mostly short lines, some empty, and every 2000th longer than a chunk
+        let words = match next() % 100 {
+            _ if counts.lines % 2000 == 1999 => 20_000,
+            0..=4 => 0,
+            _ => 1 + next() % 15,
+        };
+        for i in 0..words {
+            if i > 0 {
+                line.extend_from_slice(if next() % 8 == 0 { b"  \t" } else { b" " });
+            }
+            line.extend_from_slice(WORDS[(next() % WORDS.len() as u64) as usize].as_bytes());
+        }
+        counts.add_line(&line);
+        line.push(b'\n');
+        out.write_all(&line)?;
+        written += line.len() as u64;
+    }
+    out.flush()?;
+    Ok(counts)
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct FileIoDemo;
+
+impl Demo for FileIoDemo {
+    fn name(&self) -> &'static str {
+        "file_io"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "Buffered, chunked and memory-mapped reads behind one LineSource trait, on a word count"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("tech-notes-file-io-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let result = run_in(&dir, out);
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+}
+
+fn run_in(dir: &Path, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "===== Generated Fixture =====")?;
+    let path = dir.join("fixture.txt");
+    let expected = write_fixture(&path, 256 * 1024, 7)?;
+    writeln!(out, "{} bytes, expecting {:?}", std::fs::metadata(&path)?.len(), expected)?;
+
+    writeln!(out, "\n===== Word Count per Source =====")?;
+    for mut source in open_all(&path)? {
+        let counts = word_count(source.as_mut())?;
+        writeln!(out, "{:<9} {:?} matches: {}", source.name(), counts, counts == expected)?;
+    }
+    if cfg!(not(feature = "mmap")) {
+        writeln!(out, "(build with `--features mmap` to add the memory-mapped source)")?;
+    }
+
+    writeln!(out, "\n===== Lines Across Chunk Boundaries =====")?;
+    let small = dir.join("small.txt");
+    std::fs::write(&small, "alpha beta\ngamma\n\ndelta epsilon zeta\nno newline at the end")?;
+    let mut chunked = ChunkedLines::with_chunk_size(&small, 4)?;
+    let mut lines = Vec::new();
+    chunked.for_each_line(&mut |line| lines.push(String::from_utf8_lossy(line).into_owned()))?;
+    writeln!(out, "4-byte chunks still see whole lines: {:?}", lines)?;
+    writeln!(out, "same counts as buffered: {}", word_count(&mut chunked)? == word_count(&mut BufferedLines::open(&small)?)?)?;
+
+    writeln!(out, "\n===== Sources Rewind =====")?;
+    let mut buffered = BufferedLines::open(&small)?;
+    let first = word_count(&mut buffered)?;
+    writeln!(out, "second pass over one source gives {:?}: {}", first, word_count(&mut buffered)? == first)?;
+    Ok(())
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&FileIoDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A file in the temp directory that is removed on drop
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn with(name: &str, contents: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!("tech-notes-file-io-{}-{}", name, std::process::id()));
+            std::fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn lines_of(source: &mut dyn LineSource) -> Vec<Vec<u8>> {
+        let mut lines = Vec::new();
+        source.for_each_line(&mut |line| lines.push(line.to_vec())).unwrap();
+        lines
+    }
+
+    #[test]
+    fn sources_agree_on_line_splitting_edge_cases() {
+        // Name, file contents, expected lines
+        type Case = (&'static str, &'static [u8], &'static [&'static [u8]]);
+        let cases: &[Case] = &[
+            ("empty", b"", &[]),
+            ("one newline", b"\n", &[b""]),
+            ("no trailing newline", b"a b\nc", &[b"a b", b"c"]),
+            ("trailing newline", b"a b\nc\n", &[b"a b", b"c"]),
+            ("blank lines", b"\n\nx\n\n", &[b"", b"", b"x", b""]),
+            ("not utf-8", b"\xff\xfe\n", &[b"\xff\xfe"]),
+        ];
+        for (name, contents, expected) in cases {
+            let file = TempFile::with(name.replace(' ', "-").as_str(), contents);
+            let mut sources = open_all(&file.0).unwrap();
+            sources.push(Box::new(ChunkedLines::with_chunk_size(&file.0, 1).unwrap()));
+            for source in &mut sources {
+                assert_eq!(lines_of(source.as_mut()), *expected, "{} with {}", name, source.name());
+            }
+        }
+    }
+
+    #[test]
+    fn chunked_lines_reassemble_across_every_chunk_size() {
+        let contents = b"first line\nsecond\n\na much longer third line\nlast";
+        let file = TempFile::with("chunk-sizes", contents);
+        let expected = lines_of(&mut BufferedLines::open(&file.0).unwrap());
+        for size in 1..=contents.len() + 1 {
+            let mut chunked = ChunkedLines::with_chunk_size(&file.0, size).unwrap();
+            assert_eq!(lines_of(&mut chunked), expected, "chunk size {}", size);
+        }
+    }
+
+    #[test]
+    fn word_count_splits_on_any_ascii_whitespace() {
+        let file = TempFile::with("words", b"  one\ttwo  three \n\nfour\r\n");
+        let counts = word_count(&mut BufferedLines::open(&file.0).unwrap()).unwrap();
+        assert_eq!(counts, Counts { lines: 3, words: 4, longest_line: 17 });
+    }
+
+    #[test]
+    fn every_source_matches_the_fixture_counts() {
+        let file = TempFile::with("fixture", b"");
+        let expected = write_fixture(&file.0, 3 * CHUNK_SIZE as u64, 42).unwrap();
+        assert!(expected.longest_line > CHUNK_SIZE as u64, "fixture should have a line longer than a chunk");
+        for mut source in open_all(&file.0).unwrap() {
+            assert_eq!(word_count(source.as_mut()).unwrap(), expected, "{}", source.name());
+        }
+    }
+
+    #[test]
+    fn a_source_can_be_read_twice() {
+        let file = TempFile::with("twice", b"a b c\nd e\n");
+        for mut source in open_all(&file.0).unwrap() {
+            let first = word_count(source.as_mut()).unwrap();
+            assert_eq!(word_count(source.as_mut()).unwrap(), first, "{}", source.name());
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Iterator Adapters From Scratch in Rust
+//!
+//! Every combinator in `std::iter` is a struct that owns the iterator it wraps plus a
+//! little state, and whose `next` pulls from the inner iterator only as far as it must.
+//! Nothing runs until someone calls `next`, which is why `map` or `filter` alone does no
+//! work and why `take(3)` on an endless source finishes. The adapters here are plain
+//! structs built that way, each matching a std counterpart:
+//! - `ChunksExactish`: groups of `n` items, holding back a short tail (`chunks_exact`)
+//! - `Windows`: every run of `n` consecutive items, sliding by one (`windows`)
+//! - `Dedup`: drops items equal to the one before (`Vec::dedup`, but lazy)
+//! - `FlattenLite`: the items of each inner iterable in turn (`flatten`)
+//! - `ScanLite`: running state threaded through the items, stopping on `None` (`scan`)
+//!
+//! An extension trait, `IterExt`, adds them as methods to every iterator, the same way
+//! the `Iterator` trait's provided methods build std's adapters.
+
+use demo::Demo;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
+// ========== ChunksExactish ==========
+
+/// Non-overlapping groups of exactly `size` items; a final group that would be shorter is
+/// kept back and available from `remainder` once the iterator is done
+#[derive(Debug, Clone)]
+pub struct ChunksExactish<I: Iterator> {
+    inner: I,
+    size: usize,
+    remainder: Vec<I::Item>,
+}
+
+impl<I: Iterator> ChunksExactish<I> {
+    pub fn new(inner: I, size: usize) -> Self {
+        assert!(size > 0, "chunk size must be positive");
+        ChunksExactish { inner, size, remainder: Vec::new() }
+    }
+
+    /// The items left over after the last full chunk; empty until iteration has ended
+    pub fn remainder(&self) -> &[I::Item] {
+        &self.remainder
+    }
+}
+
+impl<I: Iterator> Iterator for ChunksExactish<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        let chunk: Vec<I::Item> = self.inner.by_ref().take(self.size).collect();
+        if chunk.len() == self.size {
+            Some(chunk)
+        } else {
+            self.remainder = chunk;
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.inner.size_hint();
+        (low / self.size, high.map(|h| h / self.size))
+    }
+}
+
+// ========== Windows ==========
+
+/// Every run of `size` consecutive items, each window one item further along; items are
+/// cloned because neighbouring windows share them
+#[derive(Debug, Clone)]
+pub struct Windows<I: Iterator> {
+    inner: I,
+    size: usize,
+    window: VecDeque<I::Item>,
+}
+
+impl<I: Iterator> Windows<I> {
+    pub fn new(inner: I, size: usize) -> Self {
+        assert!(size > 0, "window size must be positive");
+        Windows { inner, size, window: VecDeque::with_capacity(size) }
+    }
+}
+
+impl<I> Iterator for Windows<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        // The first call fills the whole window; later calls need one new item each
+        while self.window.len() < self.size {
+            self.window.push_back(self.inner.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.inner.size_hint();
+        // Windows still to come: one per remaining item, plus one more before the first
+        let buffered = self.window.len();
+        let count = |n: usize| {
+            if buffered == self.size {
+                n
+            } else {
+                (n + buffered + 1).saturating_sub(self.size)
+            }
+        };
+        (count(low), high.map(count))
+    }
+}
+
+// ========== Dedup ==========
+
+/// Skips every item equal to the item just before it, so each run of equal items
+/// comes out once
+#[derive(Debug, Clone)]
+pub struct Dedup<I: Iterator> {
+    inner: I,
+    last: Option<I::Item>,
+}
+
+impl<I: Iterator> Dedup<I> {
+    pub fn new(inner: I) -> Self {
+        Dedup { inner, last: None }
+    }
+}
+
+impl<I> Iterator for Dedup<I>
+where
+    I: Iterator,
+    I::Item: PartialEq + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let item = self.inner.next()?;
+            if self.last.as_ref() != Some(&item) {
+                self.last = Some(item.clone());
+                return Some(item);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.inner.size_hint();
+        // The rest may be one run equal to the last item yielded, which adds nothing
+        let low = if low > 0 && self.last.is_none() { 1 } else { 0 };
+        (low, high)
+    }
+}
+
+// ========== FlattenLite ==========
+
+/// The items of each inner iterable, one iterable after another
+pub struct FlattenLite<I>
+where
+    I: Iterator,
+    I::Item: IntoIterator,
+{
+    outer: I,
+    current: Option<<I::Item as IntoIterator>::IntoIter>,
+}
+
+impl<I> FlattenLite<I>
+where
+    I: Iterator,
+    I::Item: IntoIterator,
+{
+    pub fn new(outer: I) -> Self {
+        FlattenLite { outer, current: None }
+    }
+}
+
+impl<I> Iterator for FlattenLite<I>
+where
+    I: Iterator,
+    I::Item: IntoIterator,
+{
+    type Item = <I::Item as IntoIterator>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            // The current inner iterator is used up (or there was none yet); empty inner
+            // iterables are skipped by going round again
+            self.current = Some(self.outer.next()?.into_iter());
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.current.as_ref().map_or((0, Some(0)), Iterator::size_hint);
+        // Unknown once more inner iterables might follow
+        match self.outer.size_hint() {
+            (_, Some(0)) => (low, high),
+            _ => (low, None),
+        }
+    }
+}
+
+// ========== ScanLite ==========
+
+/// Threads a mutable state through the items; `step` sees the state and the next item
+/// and returns what to yield, or `None` to stop early
+#[derive(Debug, Clone)]
+pub struct ScanLite<I, S, F> {
+    inner: I,
+    state: S,
+    step: F,
+    done: bool,
+}
+
+impl<I, S, F> ScanLite<I, S, F> {
+    pub fn new(inner: I, state: S, step: F) -> Self {
+        ScanLite { inner, state, step, done: false }
+    }
+}
+
+impl<I, S, B, F> Iterator for ScanLite<I, S, F>
+where
+    I: Iterator,
+    F: FnMut(&mut S, I::Item) -> Option<B>,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<B> {
+        if self.done {
+            return None;
+        }
+        let item = self.inner.next()?;
+        let out = (self.step)(&mut self.state, item);
+        // Once `step` has said stop, stay stopped even if the source has more
+        self.done = out.is_none();
+        out
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        (0, self.inner.size_hint().1)
+    }
+}
+
+// ========== Extension Trait ==========
+
+/// The adapters above as iterator methods
+pub trait IterExt: Iterator + Sized {
+    fn chunks_exactish(self, size: usize) -> ChunksExactish<Self> {
+        ChunksExactish::new(self, size)
+    }
+
+    fn windows(self, size: usize) -> Windows<Self> {
+        Windows::new(self, size)
+    }
+
+    fn dedup(self) -> Dedup<Self> {
+        Dedup::new(self)
+    }
+
+    fn flatten_lite(self) -> FlattenLite<Self>
+    where
+        Self::Item: IntoIterator,
+    {
+        FlattenLite::new(self)
+    }
+
+    fn scan_lite<S, B, F>(self, state: S, step: F) -> ScanLite<Self, S, F>
+    where
+        F: FnMut(&mut S, Self::Item) -> Option<B>,
+    {
+        ScanLite::new(self, state, step)
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+// ========== Demo Code ==========
+
+fn show<T: std::fmt::Debug>(items: impl Iterator<Item = T>) -> String {
+    let shown: Vec<String> = items.map(|item| format!("{:?}", item)).collect();
+    shown.join(" ")
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct IteratorsDemo;
+
+impl Demo for IteratorsDemo {
+    fn name(&self) -> &'static str {
+        "iterators"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "Iterator adapters written from scratch and compared with std"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        let data = [3, 3, 1, 4, 4, 4, 1, 5, 9, 9];
+        writeln!(out, "===== Adapters Next to std =====")?;
+        writeln!(out, "input {:?}", data)?;
+
+        let mut chunks = data.iter().copied().chunks_exactish(3);
+        writeln!(out, "chunks_exactish(3): {}", show(chunks.by_ref()))?;
+        writeln!(out, "  remainder {:?}", chunks.remainder())?;
+        writeln!(out, "std chunks_exact:   {}", show(data.chunks_exact(3)))?;
+        writeln!(out, "  remainder {:?}", data.chunks_exact(3).remainder())?;
+
+        writeln!(out, "windows(4):   {}", show(data.iter().copied().windows(4)))?;
+        writeln!(out, "std windows:  {}", show(data.windows(4)))?;
+
+        let mut deduped = data.to_vec();
+        deduped.dedup();
+        writeln!(out, "dedup():      {}", show(data.iter().copied().dedup()))?;
+        writeln!(out, "Vec::dedup:   {}", show(deduped.iter()))?;
+
+        let nested = [vec!["a", "b"], vec![], vec!["c"], vec![], vec!["d", "e"]];
+        writeln!(out, "flatten_lite: {}", show(nested.iter().flatten_lite()))?;
+        writeln!(out, "std flatten:  {}", show(nested.iter().flatten()))?;
+
+        // Running totals that stop once they would pass 20
+        let capped = |total: &mut i32, x: i32| {
+            *total += x;
+            (*total <= 20).then_some(*total)
+        };
+        writeln!(out, "scan_lite:    {}", show(data.iter().copied().scan_lite(0, capped)))?;
+        writeln!(out, "std scan:     {}", show(data.iter().copied().scan(0, capped)))?;
+
+        writeln!(out, "\n===== Laziness =====")?;
+        let mut pulled = 0;
+        let first_two: Vec<Vec<u64>> = (1u64..)
+            .inspect(|_| pulled += 1)
+            .dedup()
+            .windows(3)
+            .take(2)
+            .collect();
+        writeln!(out, "first two windows of the endless 1.. : {:?}", first_two)?;
+        writeln!(out, "items pulled from the source: {}", pulled)?;
+        let mut pulled = 0;
+        let chunks: Vec<Vec<usize>> = (0..)
+            .inspect(|_| pulled += 1)
+            .map(|n| vec![n; n])
+            .flatten_lite()
+            .chunks_exactish(4)
+            .take(3)
+            .collect();
+        writeln!(out, "first three chunks of 4 from [], [1], [2, 2], ...: {:?}", chunks)?;
+        writeln!(out, "inner vecs pulled from the source: {}", pulled)?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&IteratorsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small deterministic inputs with plenty of repeats: lengths 0 to 40
+    fn inputs() -> Vec<Vec<u8>> {
+        (0..=40u32)
+            .map(|len| (0..len).map(|i| ((i * 7 + len * 3) % 11 / 3) as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn chunks_exactish_matches_chunks_exact() {
+        for data in inputs() {
+            for size in 1..=6 {
+                let mut ours = data.iter().copied().chunks_exactish(size);
+                let chunks: Vec<Vec<u8>> = ours.by_ref().collect();
+                let std_chunks: Vec<Vec<u8>> = data.chunks_exact(size).map(<[u8]>::to_vec).collect();
+                assert_eq!(chunks, std_chunks, "{:?} by {}", data, size);
+                assert_eq!(ours.remainder(), data.chunks_exact(size).remainder());
+            }
+        }
+    }
+
+    #[test]
+    fn windows_match_slice_windows_and_size_hints() {
+        for data in inputs() {
+            for size in 1..=6 {
+                let mut ours = data.iter().copied().windows(size);
+                let expected: Vec<&[u8]> = data.windows(size).collect();
+                assert_eq!(ours.size_hint(), (expected.len(), Some(expected.len())));
+                for window in &expected {
+                    assert_eq!(ours.next().as_deref(), Some(*window));
+                    let left = ours.size_hint();
+                    assert_eq!(left.0, left.1.unwrap());
+                }
+                assert_eq!(ours.next(), None);
+                assert_eq!(ours.size_hint(), (0, Some(0)));
+            }
+        }
+    }
+
+    #[test]
+    fn dedup_matches_vec_dedup() {
+        for data in inputs() {
+            let mut expected = data.clone();
+            expected.dedup();
+            let ours: Vec<u8> = data.iter().copied().dedup().collect();
+            assert_eq!(ours, expected, "{:?}", data);
+            let (low, high) = data.iter().dedup().size_hint();
+            assert!(low <= expected.len() && high >= Some(expected.len()));
+        }
+        let words = ["a", "a", "b", "a"].map(String::from);
+        assert_eq!(words.into_iter().dedup().collect::<Vec<_>>(), ["a", "b", "a"]);
+    }
+
+    #[test]
+    fn flatten_lite_matches_flatten() {
+        for data in inputs() {
+            // Split wherever a 0 appears, keeping empty groups
+            let groups: Vec<Vec<u8>> = data.split(|&x| x == 0).map(<[u8]>::to_vec).collect();
+            let ours: Vec<u8> = groups.iter().flatten_lite().copied().collect();
+            let std_flat: Vec<u8> = groups.iter().flatten().copied().collect();
+            assert_eq!(ours, std_flat);
+        }
+        let options = [Some(1), None, Some(3)];
+        assert_eq!(options.into_iter().flatten_lite().collect::<Vec<_>>(), [1, 3]);
+        let empty: Vec<Vec<u8>> = vec![vec![], vec![]];
+        assert_eq!(empty.into_iter().flatten_lite().next(), None);
+    }
+
+    #[test]
+    fn scan_lite_matches_scan_and_stays_stopped() {
+        for data in inputs() {
+            let step = |sum: &mut u32, x: u8| {
+                *sum += u32::from(x);
+                (*sum < 25).then_some(*sum)
+            };
+            let ours: Vec<u32> = data.iter().copied().scan_lite(0, step).collect();
+            let std_scan: Vec<u32> = data.iter().copied().scan(0, step).collect();
+            assert_eq!(ours, std_scan, "{:?}", data);
+        }
+        // Std's `scan` is not fused; ours stops for good at the first `None`
+        let mut odd_until_even = [1, 3, 4, 5, 7].into_iter().scan_lite((), |_, x| (x % 2 == 1).then_some(x));
+        assert_eq!((odd_until_even.next(), odd_until_even.next(), odd_until_even.next()), (Some(1), Some(3), None));
+        assert_eq!(odd_until_even.next(), None);
+    }
+
+    #[test]
+    fn adapters_are_lazy() {
+        let mut pulled = 0;
+        let windows: Vec<Vec<u32>> = (0..).inspect(|_| pulled += 1).windows(3).take(2).collect();
+        assert_eq!(windows, [[0, 1, 2], [1, 2, 3]]);
+        assert_eq!(pulled, 4);
+
+        let mut pulled = 0;
+        let chunk = (0..).inspect(|_| pulled += 1).chunks_exactish(5).next();
+        assert_eq!((chunk, pulled), (Some(vec![0, 1, 2, 3, 4]), 5));
+
+        let mut pulled = 0;
+        let first = (0..).inspect(|_| pulled += 1).map(|n| n / 10).dedup().nth(2);
+        assert_eq!((first, pulled), (Some(2), 21));
+    }
+}
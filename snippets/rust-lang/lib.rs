@@ -0,0 +1,28 @@
+//! Rust language snippets from the tech notes.
+//!
+//! Each module covers one language feature with plain std code, and its `Demo`
+//! implementation is registered in `DEMOS`, whose `run_demo` wrapper the matching
+//! example binary calls.
+
+pub mod dispatch;
+pub mod error_handling;
+pub mod file_io;
+pub mod iterators;
+pub mod lifetimes;
+pub mod macros_declarative;
+pub mod smart_pointers;
+pub mod testing_patterns;
+
+use demo::Demo;
+
+/// Every demo in this crate, for `tech-notes` and the other front ends.
+pub static DEMOS: &[&dyn Demo] = &[
+    &dispatch::DispatchDemo,
+    &error_handling::ErrorHandlingDemo,
+    &file_io::FileIoDemo,
+    &iterators::IteratorsDemo,
+    &lifetimes::LifetimesDemo,
+    &macros_declarative::MacrosDeclarativeDemo,
+    &smart_pointers::SmartPointersDemo,
+    &testing_patterns::TestingPatternsDemo,
+];
@@ -0,0 +1,409 @@
+//! Lifetimes and Borrowing Patterns in Rust
+//!
+//! A lifetime names how long a borrow must stay valid; the borrow checker proves every
+//! reference outlives its uses and that no `&mut` overlaps another borrow. Most of the
+//! friction comes from a few recurring shapes, each shown here working, with the broken
+//! version next to it as a `compile_fail` doc test:
+//! - A struct holding references, whose methods hand out data borrowed from the *input*
+//!   (`'a`) rather than from the struct itself (`&self`).
+//! - Split borrows: the checker sees `self.items` and `self.log` as separate places, but
+//!   a method call borrows all of `self`.
+//! - Methods returning iterators that borrow from `self`, spelled `+ '_`.
+//! - Threads: `thread::spawn` needs `'static` data, so it takes owned values; scoped
+//!   threads may borrow from the enclosing stack frame instead.
+//! - Interior mutability (`Cell`, `RefCell`, `OnceCell`): mutation behind `&self`, with
+//!   the exclusivity check moved from compile time to run time, or made unnecessary.
+
+use demo::Demo;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::thread;
+
+// ========== Structs Holding References ==========
+
+/// Splits a borrowed string into words without copying it.
+///
+/// `next_word` returns `&'a str`, borrowed from the input, not from the tokenizer, so
+/// earlier words stay usable while the tokenizer moves on. With the lifetime elided,
+/// the result would borrow `self` mutably for as long as the word is kept:
+///
+/// ```compile_fail,E0499
+/// struct Tokenizer<'a> {
+///     rest: &'a str,
+/// }
+///
+/// impl<'a> Tokenizer<'a> {
+///     // Elided: the returned `&str` borrows `*self`, not the input
+///     fn next_word(&mut self) -> Option<&str> {
+///         let text = self.rest.trim_start();
+///         let end = text.find(' ').unwrap_or(text.len());
+///         self.rest = &text[end..];
+///         (end > 0).then(|| &text[..end])
+///     }
+/// }
+///
+/// let mut words = Tokenizer { rest: "borrowed from the input" };
+/// let first = words.next_word();
+/// let second = words.next_word(); // second `&mut` borrow while `first` is alive
+/// println!("{:?} {:?}", first, second);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Tokenizer { rest: text }
+    }
+
+    pub fn next_word(&mut self) -> Option<&'a str> {
+        let text = self.rest.trim_start();
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        self.rest = &text[end..];
+        (end > 0).then(|| &text[..end])
+    }
+
+    /// What has not been consumed yet
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+}
+
+/// The longer of two strings; both inputs must live as long as the result is used.
+///
+/// ```compile_fail,E0597
+/// # use rust_lang::lifetimes::longest;
+/// let outer = String::from("long-lived");
+/// let result;
+/// {
+///     let inner = String::from("short");
+///     result = longest(&outer, &inner); // `inner` does not live long enough
+/// }
+/// println!("{}", result);
+/// ```
+pub fn longest<'a>(a: &'a str, b: &'a str) -> &'a str {
+    if b.len() > a.len() {
+        b
+    } else {
+        a
+    }
+}
+
+// ========== Split Borrows ==========
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub name: String,
+    pub stock: u32,
+}
+
+/// Stock levels plus a log of what changed.
+///
+/// `restock` mutates items and appends to the log in the same loop, which works because
+/// `self.items` and `self.log` are borrowed as separate fields. Calling a `&mut self`
+/// helper inside the loop borrows all of `self` while `self.items` is still borrowed:
+///
+/// ```compile_fail,E0499
+/// struct Inventory {
+///     items: Vec<u32>,
+///     log: Vec<String>,
+/// }
+///
+/// impl Inventory {
+///     fn note(&mut self, line: String) {
+///         self.log.push(line);
+///     }
+///
+///     fn restock(&mut self) {
+///         for stock in &mut self.items {
+///             *stock += 10;
+///             self.note(format!("now {}", stock)); // all of `self`, again
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    pub items: Vec<Item>,
+    pub log: Vec<String>,
+}
+
+impl Inventory {
+    /// Tops every item below `threshold` up to it, logging each change
+    pub fn restock(&mut self, threshold: u32) {
+        // Two disjoint field borrows, live at the same time
+        let log = &mut self.log;
+        for item in self.items.iter_mut().filter(|item| item.stock < threshold) {
+            log.push(format!("{}: {} -> {}", item.name, item.stock, threshold));
+            item.stock = threshold;
+        }
+    }
+
+    /// The same idea through a helper: it takes the fields it needs, not `&mut self`
+    pub fn sell(&mut self, name: &str, count: u32) -> bool {
+        fn note(log: &mut Vec<String>, line: String) {
+            log.push(line);
+        }
+        match self.items.iter_mut().find(|item| item.name == name) {
+            Some(item) if item.stock >= count => {
+                item.stock -= count;
+                note(&mut self.log, format!("sold {} {}", count, name));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Items whose stock is at most `limit`, borrowed from the inventory.
+    ///
+    /// The `'_` ties the iterator to `&self`, so the inventory cannot change while one
+    /// is in use:
+    ///
+    /// ```compile_fail,E0502
+    /// # use rust_lang::lifetimes::{Inventory, Item};
+    /// let mut inventory = Inventory::default();
+    /// inventory.items.push(Item { name: "bolt".into(), stock: 2 });
+    /// let low = inventory.low_stock(5);
+    /// inventory.restock(10); // mutable borrow while `low` still borrows immutably
+    /// println!("{}", low.count());
+    /// ```
+    pub fn low_stock(&self, limit: u32) -> impl Iterator<Item = &str> + '_ {
+        self.items.iter().filter(move |item| item.stock <= limit).map(|item| item.name.as_str())
+    }
+}
+
+// ========== Threads: 'static vs Borrowed ==========
+
+/// Sums chunks of `data` on spawned threads. `thread::spawn` may outlive this call, so
+/// each thread gets an owned chunk; borrowing `data` instead is rejected:
+///
+/// ```compile_fail,E0373
+/// use std::thread;
+///
+/// let data = vec![1u64, 2, 3];
+/// let handle = thread::spawn(|| data.iter().sum::<u64>()); // borrows a local
+/// println!("{}", handle.join().unwrap());
+/// ```
+pub fn sum_owned_chunks(data: &[u64], threads: usize) -> u64 {
+    let chunk = data.len().div_ceil(threads.max(1)).max(1);
+    let handles: Vec<_> = data
+        .chunks(chunk)
+        .map(|part| {
+            let owned = part.to_vec();
+            thread::spawn(move || owned.iter().sum::<u64>())
+        })
+        .collect();
+    handles.into_iter().map(|h| h.join().expect("worker panicked")).sum()
+}
+
+/// The same sum with scoped threads, which are joined before `scope` returns and so may
+/// borrow `data` without copying it
+pub fn sum_scoped(data: &[u64], threads: usize) -> u64 {
+    let chunk = data.len().div_ceil(threads.max(1)).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = data.chunks(chunk).map(|part| scope.spawn(move || part.iter().sum::<u64>())).collect();
+        handles.into_iter().map(|h| h.join().expect("worker panicked")).sum()
+    })
+}
+
+/// `&'static str` is fine to send anywhere: string literals live for the whole program
+pub fn greet_from_thread(greeting: &'static str) -> String {
+    thread::spawn(move || format!("{} from a spawned thread", greeting)).join().expect("worker panicked")
+}
+
+// ========== Interior Mutability ==========
+
+/// A memoising Fibonacci whose `get` takes `&self`: the cache is a `RefCell`, the hit
+/// counter a `Cell`, and the rarely needed summary a `OnceCell`.
+///
+/// Without them the `&self` method cannot write:
+///
+/// ```compile_fail,E0596
+/// use std::collections::HashMap;
+///
+/// struct Fib {
+///     cache: HashMap<u64, u64>,
+/// }
+///
+/// impl Fib {
+///     fn get(&self, n: u64) -> u64 {
+///         let value = if n < 2 { n } else { self.get(n - 1) + self.get(n - 2) };
+///         self.cache.insert(n, value); // `self` is only borrowed immutably
+///         value
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Fib {
+    cache: RefCell<HashMap<u64, u64>>,
+    hits: Cell<u32>,
+    summary: OnceCell<String>,
+}
+
+impl Fib {
+    pub fn new() -> Self {
+        Fib::default()
+    }
+
+    pub fn get(&self, n: u64) -> u64 {
+        if let Some(&value) = self.cache.borrow().get(&n) {
+            self.hits.set(self.hits.get() + 1);
+            return value;
+        }
+        // The `borrow()` above has ended; holding it across the recursion would make
+        // the `borrow_mut` below panic
+        let value = if n < 2 { n } else { self.get(n - 1) + self.get(n - 2) };
+        self.cache.borrow_mut().insert(n, value);
+        value
+    }
+
+    pub fn hits(&self) -> u32 {
+        self.hits.get()
+    }
+
+    pub fn cached(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Built on first call, then the same `&str` every time
+    pub fn summary(&self) -> &str {
+        self.summary.get_or_init(|| format!("{} values cached, {} cache hits", self.cached(), self.hits()))
+    }
+
+    /// `RefCell` checks at run time what the compiler would have rejected: a second
+    /// mutable borrow while the first is alive. `try_borrow_mut` reports it instead of
+    /// panicking.
+    pub fn overlapping_borrow_fails(&self) -> bool {
+        let _reading = self.cache.borrow();
+        self.cache.try_borrow_mut().is_err()
+    }
+}
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct LifetimesDemo;
+
+impl Demo for LifetimesDemo {
+    fn name(&self) -> &'static str {
+        "lifetimes"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "Borrowing structs, split borrows, borrowing iterators, threads and interior mutability"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Structs Holding References =====")?;
+        let text = String::from("  words borrowed   straight from the input ");
+        let mut tokens = Tokenizer::new(&text);
+        let first = tokens.next_word();
+        let second = tokens.next_word();
+        writeln!(out, "first {:?}, second {:?}, still unread {:?}", first, second, tokens.rest())?;
+        writeln!(out, "longest(\"borrow\", \"checker\") = {:?}", longest("borrow", "checker"))?;
+
+        writeln!(out, "\n===== Split Borrows =====")?;
+        let mut inventory = Inventory {
+            items: [("bolt", 2), ("nut", 40), ("washer", 7)]
+                .map(|(name, stock)| Item { name: name.to_string(), stock })
+                .to_vec(),
+            log: Vec::new(),
+        };
+        let low: Vec<&str> = inventory.low_stock(10).collect();
+        writeln!(out, "low stock before: {:?}", low)?;
+        inventory.restock(10);
+        inventory.sell("nut", 15);
+        let failed = !inventory.sell("bolt", 99);
+        for line in &inventory.log {
+            writeln!(out, "log: {}", line)?;
+        }
+        writeln!(out, "selling 99 bolts refused: {}", failed)?;
+        writeln!(out, "low stock after: {:?}", inventory.low_stock(10).collect::<Vec<_>>())?;
+
+        writeln!(out, "\n===== Threads: Owned, Scoped and 'static =====")?;
+        let data: Vec<u64> = (1..=1000).collect();
+        writeln!(out, "owned chunks on spawned threads: {}", sum_owned_chunks(&data, 4))?;
+        writeln!(out, "borrowed chunks on scoped threads: {}", sum_scoped(&data, 4))?;
+        writeln!(out, "{}", greet_from_thread("hello"))?;
+
+        writeln!(out, "\n===== Interior Mutability =====")?;
+        let fib = Fib::new();
+        writeln!(out, "fib(50) = {} through &self", fib.get(50))?;
+        writeln!(out, "fib(40) = {} from the cache", fib.get(40))?;
+        writeln!(out, "summary: {}", fib.summary())?;
+        fib.get(60);
+        writeln!(out, "summary again (OnceCell keeps the first): {}", fib.summary())?;
+        writeln!(out, "borrow_mut while borrowed is refused at run time: {}", fib.overlapping_borrow_fails())?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&LifetimesDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_outlive_the_tokenizer() {
+        let text = "one  two\tthree\n";
+        let words: Vec<&str> = {
+            let mut tokens = Tokenizer::new(text);
+            std::iter::from_fn(|| tokens.next_word()).collect()
+        };
+        assert_eq!(words, ["one", "two", "three"]);
+        assert_eq!(Tokenizer::new("   ").next_word(), None);
+        assert_eq!(longest("ab", "abc"), "abc");
+        assert_eq!(longest("tie", "abc"), "tie");
+    }
+
+    #[test]
+    fn split_borrows_restock_and_sell() {
+        let mut inventory = Inventory {
+            items: vec![Item { name: "a".into(), stock: 1 }, Item { name: "b".into(), stock: 9 }],
+            log: Vec::new(),
+        };
+        assert_eq!(inventory.low_stock(5).collect::<Vec<_>>(), ["a"]);
+        inventory.restock(5);
+        assert!(inventory.sell("b", 9));
+        assert!(!inventory.sell("b", 1));
+        assert!(!inventory.sell("missing", 1));
+        assert_eq!(inventory.log, ["a: 1 -> 5", "sold 9 b"]);
+        assert_eq!(inventory.low_stock(5).collect::<Vec<_>>(), ["a", "b"]);
+    }
+
+    #[test]
+    fn thread_sums_agree() {
+        for len in [0, 1, 7, 100] {
+            let data: Vec<u64> = (0..len).collect();
+            let expected: u64 = data.iter().sum();
+            for threads in [1, 3, 16] {
+                assert_eq!(sum_owned_chunks(&data, threads), expected);
+                assert_eq!(sum_scoped(&data, threads), expected);
+            }
+        }
+        assert_eq!(greet_from_thread("hi"), "hi from a spawned thread");
+    }
+
+    #[test]
+    fn interior_mutability_behind_shared_references() {
+        let fib = Fib::new();
+        assert_eq!(fib.get(90), 2_880_067_194_370_816_120);
+        assert_eq!(fib.cached(), 91);
+        // Each n from 2 to 90 asks for n - 2 after n - 1 has filled the cache
+        assert_eq!(fib.hits(), 88);
+        let summary = fib.summary().to_string();
+        fib.get(10);
+        assert_eq!(fib.summary(), summary);
+        assert!(fib.overlapping_borrow_fails());
+        assert_eq!(fib.get(91), 4_660_046_610_375_530_309);
+    }
+}
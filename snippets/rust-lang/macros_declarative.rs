@@ -0,0 +1,333 @@
+//! Declarative Macros in Rust
+//!
+//! `macro_rules!` matches token trees against patterns and pastes the matched pieces
+//! into a template, before type checking. The recipes get harder as they go:
+//! - `hashmap!` is a literal syntax: one repetition `$($key => $value),*`, plus a
+//!   `count!` helper that recurses to size the map up front.
+//! - `timeit!` wraps an expression and hands back its value with the elapsed time;
+//!   `stringify!` turns the expression itself into a label.
+//! - `max_of!` recurses on a variadic list, binding each argument once.
+//! - `record!` is a TT muncher: it eats one field at a time from the front of the
+//!   input and pushes it onto an accumulator, then emits a struct with defaults and a
+//!   field table once nothing is left. Parsing is by recursion because a single
+//!   repetition cannot express "`= default` is optional per field".
+//! - Hygiene: a `let` inside a macro cannot clash with the caller's variables, and the
+//!   macro can only touch a caller's variable whose name it was given. Fragments keep
+//!   their grouping, so `$e * $e` on an `expr` is not the C-preprocessor trap, but
+//!   re-pasting raw `tt`s is.
+//!
+//! The macros are `#[macro_export]`ed, so they live at the crate root and refer to
+//! their helpers through `$crate::`.
+
+use demo::Demo;
+use std::io::{self, Write};
+use std::time::Duration;
+
+// ========== Counting and Literals ==========
+
+/// Counts its comma-separated arguments at compile time
+#[macro_export]
+macro_rules! count {
+    () => { 0usize };
+    ($head:tt $(, $tail:tt)*) => { 1usize + $crate::count!($($tail),*) };
+}
+
+/// A `HashMap` literal: `hashmap! { "a" => 1, "b" => 2 }`, trailing comma allowed
+#[macro_export]
+macro_rules! hashmap {
+    () => { ::std::collections::HashMap::new() };
+    ($($key:expr => $value:expr),+ $(,)?) => {{
+        // The keys are counted as token trees, so each is wrapped in one group first
+        let mut map = ::std::collections::HashMap::with_capacity($crate::count!($(($key)),+));
+        $(map.insert($key, $value);)+
+        map
+    }};
+}
+
+// ========== Wrapping Expressions ==========
+
+/// What `timeit!` returns: the expression's value, plus how long it took
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timed<T> {
+    pub label: &'static str,
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+/// Evaluates an expression and times it; the label defaults to the expression's source
+#[macro_export]
+macro_rules! timeit {
+    ($label:expr, $body:expr) => {{
+        let start = ::std::time::Instant::now();
+        let value = $body;
+        $crate::macros_declarative::Timed { label: $label, value, elapsed: start.elapsed() }
+    }};
+    ($body:expr) => {
+        $crate::timeit!(stringify!($body), $body)
+    };
+}
+
+/// The largest of one or more expressions, each evaluated exactly once
+#[macro_export]
+macro_rules! max_of {
+    ($x:expr $(,)?) => { $x };
+    ($x:expr, $($rest:expr),+ $(,)?) => {{
+        let first = $x;
+        let rest = $crate::max_of!($($rest),+);
+        if first >= rest { first } else { rest }
+    }};
+}
+
+// ========== A TT Muncher ==========
+
+/// Declares a struct from `name: Type` fields with optional `= default` values:
+///
+/// ```
+/// rust_lang::record! {
+///     struct Server { host: String = "localhost".to_string(), port: u16 = 8080, tags: Vec<String> }
+/// }
+/// let server = Server::default();
+/// assert_eq!((server.port, server.tags.len()), (8080, 0));
+/// assert_eq!(Server::FIELDS[1], ("port", "u16"));
+/// ```
+///
+/// Fields without a default use `Default::default()`. The struct derives `Debug`,
+/// `Clone` and `PartialEq`, and gets `FIELDS`, the `(name, type)` of each field.
+#[macro_export]
+macro_rules! record {
+    // Entry point: start munching with an empty accumulator
+    ($(#[$meta:meta])* $vis:vis struct $name:ident { $($body:tt)* }) => {
+        $crate::record!(@munch [$(#[$meta])* $vis $name] [] $($body)*);
+    };
+
+    // `field: Type = default` with more fields after it
+    (@munch $head:tt [$($done:tt)*] $field:ident : $ty:ty = $default:expr, $($rest:tt)*) => {
+        $crate::record!(@munch $head [$($done)* ($field, $ty, $default)] $($rest)*);
+    };
+    // `field: Type = default` as the last field
+    (@munch $head:tt [$($done:tt)*] $field:ident : $ty:ty = $default:expr) => {
+        $crate::record!(@munch $head [$($done)* ($field, $ty, $default)]);
+    };
+    // `field: Type` with more fields after it
+    (@munch $head:tt [$($done:tt)*] $field:ident : $ty:ty, $($rest:tt)*) => {
+        $crate::record!(@munch $head [$($done)* ($field, $ty, ::std::default::Default::default())] $($rest)*);
+    };
+    // `field: Type` as the last field
+    (@munch $head:tt [$($done:tt)*] $field:ident : $ty:ty) => {
+        $crate::record!(@munch $head [$($done)* ($field, $ty, ::std::default::Default::default())]);
+    };
+
+    // Nothing left to munch: emit the struct from the accumulated fields
+    (@munch [$(#[$meta:meta])* $vis:vis $name:ident] [$(($field:ident, $ty:ty, $default:expr))*]) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $name {
+            $(pub $field: $ty,)*
+        }
+
+        impl ::std::default::Default for $name {
+            fn default() -> Self {
+                $name { $($field: $default,)* }
+            }
+        }
+
+        impl $name {
+            /// Each field's name and type, in declaration order
+            pub const FIELDS: &'static [(&'static str, &'static str)] = &[$((stringify!($field), stringify!($ty))),*];
+        }
+    };
+}
+
+// ========== Hygiene ==========
+
+/// Doubles an expression through a temporary named `x`; the caller's own `x` is a
+/// different variable, even though both are spelled the same
+#[macro_export]
+macro_rules! double_via_temp {
+    ($e:expr) => {{
+        let x = $e;
+        x * 2
+    }};
+}
+
+/// Resets a variable of the caller's; it has to be named in the call to be reachable
+#[macro_export]
+macro_rules! reset {
+    ($var:ident) => {
+        $var = Default::default()
+    };
+}
+
+/// Squares an `expr` fragment, which stays one parenthesised unit when pasted
+#[macro_export]
+macro_rules! square_expr {
+    ($e:expr) => {
+        $e * $e
+    };
+}
+
+/// Squares raw tokens, C-preprocessor style: `1 + 2` becomes `1 + 2 * 1 + 2`
+#[macro_export]
+macro_rules! square_tokens {
+    ($($t:tt)*) => {
+        $($t)* * $($t)*
+    };
+}
+
+// ========== Demo Code ==========
+
+record! {
+    /// The record the demo declares through `record!`
+    pub struct Endpoint {
+        host: String = "localhost".to_string(),
+        port: u16 = 8080,
+        path: String,
+        retries: u32 = 3
+    }
+}
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct MacrosDeclarativeDemo;
+
+impl Demo for MacrosDeclarativeDemo {
+    fn name(&self) -> &'static str {
+        "macros_declarative"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "macro_rules! recipes: map literals, timing wrappers, a TT muncher and hygiene"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== hashmap! and count! =====")?;
+        let ages = hashmap! { "ada" => 36, "alan" => 41, "grace" => 85 };
+        let mut entries: Vec<_> = ages.iter().collect();
+        entries.sort();
+        writeln!(out, "hashmap!: {:?}", entries)?;
+        writeln!(out, "count!(a, b, c, d) = {}", count!(a, b, c, d))?;
+        let empty: std::collections::HashMap<u8, u8> = hashmap! {};
+        writeln!(out, "hashmap!{{}} is empty: {}", empty.is_empty())?;
+
+        writeln!(out, "\n===== timeit! and max_of! =====")?;
+        let timed = timeit!((1..=1000u64).sum::<u64>());
+        writeln!(out, "timeit! label {:?} value {}", timed.label, timed.value)?;
+        let labelled = timeit!("squares", (1..=10u64).map(|n| n * n).collect::<Vec<_>>());
+        writeln!(out, "timeit! label {:?} value {:?}", labelled.label, labelled.value)?;
+        let mut calls = 0;
+        let mut next = |v: i32| {
+            calls += 1;
+            v
+        };
+        let biggest = max_of!(next(3), next(9), next(4), next(7));
+        writeln!(out, "max_of!(3, 9, 4, 7) = {} with {} calls", biggest, calls)?;
+
+        writeln!(out, "\n===== record! (TT muncher) =====")?;
+        let endpoint = Endpoint { path: "/health".to_string(), ..Endpoint::default() };
+        for (name, ty) in Endpoint::FIELDS {
+            writeln!(out, "field {:<8} {}", name, ty)?;
+        }
+        writeln!(out, "{:?}", endpoint)?;
+
+        writeln!(out, "\n===== Hygiene =====")?;
+        let x = 10;
+        writeln!(out, "with x = {}: double_via_temp!(x + 1) = {}, x is still {}", x, double_via_temp!(x + 1), x)?;
+        let mut counter = 41;
+        write!(out, "counter {} ", counter)?;
+        reset!(counter);
+        writeln!(out, "after reset!(counter): {}", counter)?;
+        writeln!(out, "square_expr!(1 + 2)   = {}", square_expr!(1 + 2))?;
+        writeln!(out, "square_tokens!(1 + 2) = {} ({})", square_tokens!(1 + 2), stringify!(1 + 2 * 1 + 2))?;
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&MacrosDeclarativeDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn hashmap_literal_builds_the_map() {
+        let map = hashmap! { 1 => "one", 2 => "two", 3 => "three", };
+        let expected: HashMap<_, _> = [(1, "one"), (2, "two"), (3, "three")].into_iter().collect();
+        assert_eq!(map, expected);
+        assert!(map.capacity() >= 3);
+        // Later duplicates win, as with repeated `insert`
+        assert_eq!(hashmap! { "k" => 1, "k" => 2 }, HashMap::from([("k", 2)]));
+        let empty: HashMap<String, u8> = hashmap! {};
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn count_is_a_constant() {
+        const N: usize = count!(a, (b + c), "d", [e, f]);
+        assert_eq!(N, 4);
+        assert_eq!(count!(), 0);
+    }
+
+    #[test]
+    fn timeit_returns_the_value_and_a_label() {
+        let timed = timeit!(2 + 3);
+        assert_eq!((timed.label, timed.value), ("2 + 3", 5));
+        let slept = timeit!("sleep", std::thread::sleep(Duration::from_millis(5)));
+        assert_eq!(slept.label, "sleep");
+        assert!(slept.elapsed >= Duration::from_millis(5));
+    }
+
+    #[test]
+    fn max_of_evaluates_each_argument_once() {
+        let mut seen = Vec::new();
+        let mut log = |v: i32| {
+            seen.push(v);
+            v
+        };
+        assert_eq!(max_of!(log(4), log(-2), log(11), log(11), log(0)), 11);
+        assert_eq!(seen, [4, -2, 11, 11, 0]);
+        assert_eq!(max_of!("pear", "apple",), "pear");
+        assert_eq!(max_of!(7), 7);
+    }
+
+    record! {
+        #[derive(Eq)]
+        struct Mixed { a: u8, b: Vec<u8> = vec![1, 2], c: Option<String>, d: bool = true }
+    }
+
+    record! {
+        struct Single { only: (u8, char) = (1, 'x') }
+    }
+
+    #[test]
+    fn record_munches_fields_with_and_without_defaults() {
+        let m = Mixed::default();
+        assert_eq!(m, Mixed { a: 0, b: vec![1, 2], c: None, d: true });
+        assert_eq!(Mixed::FIELDS, [("a", "u8"), ("b", "Vec<u8>"), ("c", "Option<String>"), ("d", "bool")]);
+        assert_eq!(Single::default().only, (1, 'x'));
+        assert_eq!(Single::FIELDS, [("only", "(u8, char)")]);
+        let endpoint = Endpoint::default();
+        assert_eq!((endpoint.host.as_str(), endpoint.port, endpoint.path.as_str()), ("localhost", 8080, ""));
+    }
+
+    #[test]
+    fn macros_are_hygienic_about_names_but_tokens_are_not_grouped() {
+        let x = 5;
+        assert_eq!(double_via_temp!(x * 3), 30);
+        assert_eq!(x, 5);
+
+        let mut name = String::from("set");
+        assert_eq!(name.len(), 3);
+        reset!(name);
+        assert!(name.is_empty());
+
+        assert_eq!(square_expr!(1 + 2), 9);
+        assert_eq!(square_tokens!(1 + 2), 5);
+        assert_eq!(square_tokens!((1 + 2)), 9);
+    }
+}
@@ -0,0 +1,575 @@
+//! Smart Pointers in Rust
+//!
+//! Each pointer type answers "who owns this, and when is it freed?" differently:
+//! - `Box<T>` is single ownership on the heap; it gives recursive types a known size
+//!   and lets a trait object outlive the expression that built it.
+//! - `Rc<T>` shares ownership within one thread and frees the value when the last
+//!   strong reference goes. Two values holding `Rc`s to each other never reach zero,
+//!   so a tree whose children point back at their parent with `Rc` leaks. The fix is
+//!   to make the back edge a `Weak`, which does not keep the value alive and has to be
+//!   upgraded before use.
+//! - `Arc<T>` is the same with an atomic count, so clones can move to other threads.
+//! - `Cow<'a, T>` borrows until a change is needed and only then clones, which keeps
+//!   the common "nothing to fix" path allocation-free.
+//! - `MyRc<T>` below is a from-scratch `Rc`: a heap block holding a count and the
+//!   value, a `Clone` that bumps the count and a `Drop` that frees the block at zero.
+
+use demo::Demo;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+// ========== Drop Counting ==========
+
+/// Counts how many `Tracked` values it handed out have been dropped
+#[derive(Debug, Clone, Default)]
+pub struct DropCounter(Arc<AtomicUsize>);
+
+impl DropCounter {
+    pub fn new() -> Self {
+        DropCounter::default()
+    }
+
+    pub fn track<T>(&self, value: T) -> Tracked<T> {
+        Tracked { value, counter: self.clone() }
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// A value that bumps its `DropCounter` when dropped
+#[derive(Debug)]
+pub struct Tracked<T> {
+    pub value: T,
+    counter: DropCounter,
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        self.counter.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// ========== Box ==========
+
+/// A cons list: without the `Box` the type would contain itself and have no size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum List {
+    Cons(i64, Box<List>),
+    Nil,
+}
+
+impl List {
+    pub fn from_slice(values: &[i64]) -> Self {
+        values.iter().rev().fold(List::Nil, |tail, &v| List::Cons(v, Box::new(tail)))
+    }
+
+    pub fn sum(&self) -> i64 {
+        let mut total = 0;
+        let mut node = self;
+        while let List::Cons(value, next) = node {
+            total += value;
+            node = next;
+        }
+        total
+    }
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> &'static str;
+}
+
+pub struct Square(pub f64);
+pub struct Circle(pub f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+
+    fn name(&self) -> &'static str {
+        "square"
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+}
+
+/// Shapes of different concrete types behind one `Box<dyn Shape>` each
+pub fn shapes() -> Vec<Box<dyn Shape>> {
+    vec![Box::new(Square(2.0)), Box::new(Circle(1.0)), Box::new(Square(0.5))]
+}
+
+// ========== Rc Cycles: The Wrong Way ==========
+
+/// A tree node whose parent link is a strong `Rc`: parent and child keep each other
+/// alive, so a tree built from these is never freed
+pub struct LeakyNode {
+    pub name: Tracked<String>,
+    parent: RefCell<Option<Rc<LeakyNode>>>,
+    children: RefCell<Vec<Rc<LeakyNode>>>,
+}
+
+impl LeakyNode {
+    pub fn new(name: &str, counter: &DropCounter) -> Rc<Self> {
+        Rc::new(LeakyNode {
+            name: counter.track(name.to_string()),
+            parent: RefCell::new(None),
+            children: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn add_child(parent: &Rc<Self>, child: Rc<Self>) {
+        *child.parent.borrow_mut() = Some(Rc::clone(parent));
+        parent.children.borrow_mut().push(child);
+    }
+
+    pub fn parent_name(&self) -> Option<String> {
+        self.parent.borrow().as_ref().map(|p| p.name.value.clone())
+    }
+
+    /// Breaks the cycles by hand; the only way to free a tree of these
+    pub fn detach_all(&self) {
+        for child in self.children.borrow_mut().drain(..) {
+            child.parent.borrow_mut().take();
+            child.detach_all();
+        }
+    }
+}
+
+// ========== Rc Cycles: The Right Way ==========
+
+/// A tree node that owns its children and only observes its parent through a `Weak`
+pub struct TreeNode {
+    pub name: Tracked<String>,
+    parent: RefCell<Weak<TreeNode>>,
+    children: RefCell<Vec<Rc<TreeNode>>>,
+}
+
+impl TreeNode {
+    pub fn new(name: &str, counter: &DropCounter) -> Rc<Self> {
+        Rc::new(TreeNode {
+            name: counter.track(name.to_string()),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn add_child(parent: &Rc<Self>, child: Rc<Self>) {
+        *child.parent.borrow_mut() = Rc::downgrade(parent);
+        parent.children.borrow_mut().push(child);
+    }
+
+    /// `None` both for the root and once the parent has been freed
+    pub fn parent(&self) -> Option<Rc<TreeNode>> {
+        self.parent.borrow().upgrade()
+    }
+
+    /// Names from this node up to the root
+    pub fn path(&self) -> Vec<String> {
+        let mut names = vec![self.name.value.clone()];
+        let mut current = self.parent();
+        while let Some(node) = current {
+            names.push(node.name.value.clone());
+            current = node.parent();
+        }
+        names
+    }
+
+    pub fn children(&self) -> Vec<Rc<TreeNode>> {
+        self.children.borrow().clone()
+    }
+}
+
+// ========== Arc Across Threads ==========
+
+/// Sums `data` on `threads` threads, each holding its own `Arc` clone of the same vector;
+/// also returns the strong count while every worker was still alive
+pub fn parallel_sum(data: Arc<Vec<u64>>, threads: usize) -> (u64, usize) {
+    let threads = threads.max(1);
+    let chunk = data.len().div_ceil(threads).max(1);
+    // Workers hold their clone until the count has been read, so it is the same every run
+    let counted = Arc::new(Barrier::new(threads + 1));
+    let handles: Vec<_> = (0..threads)
+        .map(|i| {
+            let data = Arc::clone(&data);
+            let counted = Arc::clone(&counted);
+            thread::spawn(move || {
+                let start = (i * chunk).min(data.len());
+                let end = (start + chunk).min(data.len());
+                let sum = data[start..end].iter().sum::<u64>();
+                counted.wait();
+                sum
+            })
+        })
+        .collect();
+    let peak = Arc::strong_count(&data);
+    counted.wait();
+    let total = handles.into_iter().map(|h| h.join().expect("worker panicked")).sum();
+    (total, peak)
+}
+
+// ========== Cow ==========
+
+/// Trims and collapses runs of whitespace to one space, borrowing the input when it is
+/// already clean
+pub fn normalize_whitespace(text: &str) -> Cow<'_, str> {
+    let clean = text.trim() == text
+        && !text.contains(|c: char| c.is_whitespace() && c != ' ')
+        && !text.contains("  ");
+    if clean {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Escapes `<`, `>` and `&`, allocating only when one of them is present
+pub fn escape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains(['<', '>', '&']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Clamps negative readings to zero in place; `to_mut` clones the slice on the first write only
+pub fn clamp_negatives(readings: &[i32]) -> Cow<'_, [i32]> {
+    let mut readings = Cow::Borrowed(readings);
+    for i in 0..readings.len() {
+        if readings[i] < 0 {
+            readings.to_mut()[i] = 0;
+        }
+    }
+    readings
+}
+
+// ========== MyRc From Scratch ==========
+
+struct RcBox<T> {
+    count: Cell<usize>,
+    value: T,
+}
+
+/// A minimal single-threaded reference-counted pointer, without weak references
+pub struct MyRc<T> {
+    ptr: NonNull<RcBox<T>>,
+    // Tells the drop checker that dropping a `MyRc<T>` may drop a `T`
+    _owns: PhantomData<RcBox<T>>,
+}
+
+impl<T> MyRc<T> {
+    pub fn new(value: T) -> Self {
+        let block = Box::new(RcBox { count: Cell::new(1), value });
+        MyRc { ptr: NonNull::from(Box::leak(block)), _owns: PhantomData }
+    }
+
+    fn block(&self) -> &RcBox<T> {
+        // The block stays allocated while any `MyRc` to it exists, and this is one
+        unsafe { self.ptr.as_ref() }
+    }
+
+    pub fn strong_count(this: &Self) -> usize {
+        this.block().count.get()
+    }
+
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        a.ptr == b.ptr
+    }
+
+    /// Mutable access, only when no other `MyRc` shares the value
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if MyRc::strong_count(this) == 1 {
+            // The count of 1 is us, and `&mut self` rules out other borrows through us
+            Some(unsafe { &mut this.ptr.as_mut().value })
+        } else {
+            None
+        }
+    }
+
+    /// The value back out, or the pointer unchanged when it is shared
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if MyRc::strong_count(&this) != 1 {
+            return Err(this);
+        }
+        let ptr = this.ptr;
+        // Skip our own `Drop`, which would decrement and free the block a second time
+        std::mem::forget(this);
+        // We were the only owner, so the block is ours to take apart
+        let block = unsafe { Box::from_raw(ptr.as_ptr()) };
+        Ok(block.value)
+    }
+}
+
+impl<T> Clone for MyRc<T> {
+    fn clone(&self) -> Self {
+        let count = &self.block().count;
+        count.set(count.get().checked_add(1).expect("reference count overflow"));
+        MyRc { ptr: self.ptr, _owns: PhantomData }
+    }
+}
+
+impl<T> Deref for MyRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.block().value
+    }
+}
+
+impl<T> Drop for MyRc<T> {
+    fn drop(&mut self) {
+        let count = &self.block().count;
+        count.set(count.get() - 1);
+        if count.get() == 0 {
+            // The last owner is going away; the block came from `Box::leak` in `new`
+            drop(unsafe { Box::from_raw(self.ptr.as_ptr()) });
+        }
+    }
+}
+
+// `NonNull` already makes `MyRc` neither `Send` nor `Sync`, which is what a
+// non-atomic count needs
+
+// ========== Demo Code ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct SmartPointersDemo;
+
+impl Demo for SmartPointersDemo {
+    fn name(&self) -> &'static str {
+        "smart_pointers"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "Box, Rc/Weak cycles and their fix, Arc across threads, Cow and a hand-written Rc"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Box =====")?;
+        let list = List::from_slice(&[1, 2, 3, 4]);
+        writeln!(out, "cons list {:?} sums to {}", list, list.sum())?;
+        for shape in shapes() {
+            writeln!(out, "{:<6} area {:.3}", shape.name(), shape.area())?;
+        }
+
+        writeln!(out, "\n===== Rc Parent Links: Leak =====")?;
+        let counter = DropCounter::new();
+        {
+            let root = LeakyNode::new("root", &counter);
+            for name in ["a", "b"] {
+                LeakyNode::add_child(&root, LeakyNode::new(name, &counter));
+            }
+            let child = Rc::clone(&root.children.borrow()[0]);
+            writeln!(out, "child 'a' has parent {:?}", child.parent_name())?;
+            writeln!(out, "root strong count: {} (our handle + one per child)", Rc::strong_count(&root))?;
+        }
+        writeln!(out, "after the tree goes out of scope: {} of 3 nodes dropped", counter.dropped())?;
+        let counter = DropCounter::new();
+        {
+            let root = LeakyNode::new("root", &counter);
+            LeakyNode::add_child(&root, LeakyNode::new("a", &counter));
+            root.detach_all();
+        }
+        writeln!(out, "a second tree, cycles broken by hand first: {} of 2 nodes dropped", counter.dropped())?;
+
+        writeln!(out, "\n===== Weak Parent Links: Fixed =====")?;
+        let counter = DropCounter::new();
+        {
+            let root = TreeNode::new("root", &counter);
+            let branch = TreeNode::new("branch", &counter);
+            TreeNode::add_child(&root, Rc::clone(&branch));
+            TreeNode::add_child(&branch, TreeNode::new("leaf", &counter));
+            let leaf = &branch.children()[0];
+            writeln!(out, "path from leaf: {}", leaf.path().join(" -> "))?;
+            writeln!(
+                out,
+                "root strong {} weak {}; branch strong {} weak {}",
+                Rc::strong_count(&root),
+                Rc::weak_count(&root),
+                Rc::strong_count(&branch),
+                Rc::weak_count(&branch)
+            )?;
+            drop(root);
+            writeln!(out, "after dropping the root, branch's parent is {:?}", branch.parent().map(|p| p.name.value.clone()))?;
+        }
+        writeln!(out, "after the tree goes out of scope: {} of 3 nodes dropped", counter.dropped())?;
+
+        writeln!(out, "\n===== Arc Across Threads =====")?;
+        let data = Arc::new((1..=10_000).collect::<Vec<u64>>());
+        let (sum, peak) = parallel_sum(Arc::clone(&data), 4);
+        writeln!(out, "sum of 1..=10000 on 4 threads: {}", sum)?;
+        writeln!(out, "strong count while they ran: {} (ours, the argument and one per worker)", peak)?;
+        writeln!(out, "strong count afterwards: {}", Arc::strong_count(&data))?;
+
+        writeln!(out, "\n===== Cow =====")?;
+        for text in ["already clean", "needs   some\tcleanup ", "a < b && c"] {
+            let spaced = normalize_whitespace(text);
+            let escaped = escape_html(text);
+            writeln!(out, "{:?}", text)?;
+            writeln!(out, "  whitespace: {:<8} {:?}", kind(matches!(spaced, Cow::Borrowed(_))), spaced)?;
+            writeln!(out, "  html:       {:<8} {:?}", kind(matches!(escaped, Cow::Borrowed(_))), escaped)?;
+        }
+        for readings in [&[3, 1, 4][..], &[3, -1, 4, -5][..]] {
+            let clamped = clamp_negatives(readings);
+            writeln!(out, "clamp {:?}: {} {:?}", readings, kind(matches!(clamped, Cow::Borrowed(_))), clamped)?;
+        }
+
+        writeln!(out, "\n===== MyRc =====")?;
+        let counter = DropCounter::new();
+        let a = MyRc::new(counter.track("shared"));
+        let b = MyRc::clone(&a);
+        let c = b.clone();
+        writeln!(out, "three handles: count {}, same block {}", MyRc::strong_count(&a), MyRc::ptr_eq(&a, &c))?;
+        drop(a);
+        drop(b);
+        writeln!(out, "two dropped: count {}, value dropped {} time(s)", MyRc::strong_count(&c), counter.dropped())?;
+        if let Ok(tracked) = MyRc::try_unwrap(c) {
+            writeln!(out, "last handle unwrapped to {:?}, value dropped {} time(s)", tracked.value, counter.dropped())?;
+            drop(tracked);
+        }
+        writeln!(out, "unwrapped value gone: dropped {} time(s)", counter.dropped())?;
+        Ok(())
+    }
+}
+
+fn kind(borrowed: bool) -> &'static str {
+    if borrowed {
+        "borrowed"
+    } else {
+        "owned"
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&SmartPointersDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxed_list_and_trait_objects() {
+        assert_eq!(List::from_slice(&[]), List::Nil);
+        assert_eq!(List::from_slice(&[5, -2, 7]).sum(), 10);
+        let total: f64 = shapes().iter().map(|s| s.area()).sum();
+        assert!((total - (4.0 + std::f64::consts::PI + 0.25)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn strong_parent_links_leak_until_broken() {
+        let counter = DropCounter::new();
+        {
+            let root = LeakyNode::new("root", &counter);
+            LeakyNode::add_child(&root, LeakyNode::new("child", &counter));
+            assert_eq!(Rc::strong_count(&root), 2);
+        }
+        assert_eq!(counter.dropped(), 0, "the cycle keeps both nodes alive");
+
+        {
+            let root = LeakyNode::new("root", &counter);
+            let mid = LeakyNode::new("mid", &counter);
+            LeakyNode::add_child(&root, Rc::clone(&mid));
+            LeakyNode::add_child(&mid, LeakyNode::new("leaf", &counter));
+            root.detach_all();
+        }
+        assert_eq!(counter.dropped(), 3);
+    }
+
+    #[test]
+    fn weak_parent_links_free_the_whole_tree() {
+        let counter = DropCounter::new();
+        let leaf_path;
+        {
+            let root = TreeNode::new("root", &counter);
+            let mid = TreeNode::new("mid", &counter);
+            TreeNode::add_child(&root, Rc::clone(&mid));
+            TreeNode::add_child(&mid, TreeNode::new("leaf", &counter));
+            leaf_path = mid.children()[0].path();
+            assert_eq!(Rc::weak_count(&root), 1);
+            assert_eq!(Rc::strong_count(&mid), 2);
+
+            drop(root);
+            assert!(mid.parent().is_none(), "the parent is gone, not kept alive");
+            assert_eq!(counter.dropped(), 1);
+        }
+        assert_eq!(leaf_path, ["leaf", "mid", "root"]);
+        assert_eq!(counter.dropped(), 3);
+    }
+
+    #[test]
+    fn arc_is_shared_across_threads_and_released() {
+        let data = Arc::new((0..1001).collect::<Vec<u64>>());
+        for threads in [1, 3, 8, 64] {
+            let (sum, peak) = parallel_sum(Arc::clone(&data), threads);
+            assert_eq!(sum, 500_500, "{} threads", threads);
+            assert_eq!(peak, threads + 2, "every worker holds a clone");
+        }
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn cow_borrows_unless_it_must_change() {
+        assert!(matches!(normalize_whitespace("a b c"), Cow::Borrowed("a b c")));
+        assert_eq!(normalize_whitespace(" a \n b\t\tc "), "a b c");
+        assert!(matches!(normalize_whitespace("a\nb"), Cow::Owned(_)));
+        assert!(matches!(normalize_whitespace(" a"), Cow::Owned(_)));
+        assert!(matches!(escape_html("plain"), Cow::Borrowed(_)));
+        assert_eq!(escape_html("<a href=\"x\">&</a>"), "&lt;a href=\"x\"&gt;&amp;&lt;/a&gt;");
+        assert!(matches!(clamp_negatives(&[1, 2]), Cow::Borrowed(_)));
+        assert_eq!(clamp_negatives(&[-1, 2, -3]).as_ref(), [0, 2, 0]);
+    }
+
+    #[test]
+    fn my_rc_counts_and_drops_exactly_once() {
+        let counter = DropCounter::new();
+        let a = MyRc::new(counter.track(vec![1, 2, 3]));
+        let handles: Vec<MyRc<_>> = (0..10).map(|_| a.clone()).collect();
+        assert_eq!(MyRc::strong_count(&a), 11);
+        assert!(handles.iter().all(|h| MyRc::ptr_eq(h, &a) && h.value == [1, 2, 3]));
+        drop(handles);
+        assert_eq!((MyRc::strong_count(&a), counter.dropped()), (1, 0));
+        drop(a);
+        assert_eq!(counter.dropped(), 1);
+
+        let mut solo = MyRc::new(counter.track(5));
+        MyRc::get_mut(&mut solo).unwrap().value += 1;
+        let other = solo.clone();
+        assert!(MyRc::get_mut(&mut solo).is_none());
+        let solo = MyRc::try_unwrap(solo).unwrap_err();
+        drop(other);
+        let tracked = MyRc::try_unwrap(solo).ok().unwrap();
+        assert_eq!((tracked.value, counter.dropped()), (6, 1));
+        drop(tracked);
+        assert_eq!(counter.dropped(), 2);
+    }
+}
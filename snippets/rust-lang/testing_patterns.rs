@@ -0,0 +1,418 @@
+//! Testing Patterns in Rust
+//!
+//! Code is easy to test when everything it depends on can be swapped for something the
+//! test controls. Rust has no mocking runtime, so the seams are ordinary traits:
+//! - A `Clock` trait instead of calling `SystemTime::now()` directly. Production code
+//!   gets `SystemClock`; tests get a `FakeClock` that only moves when told to, so
+//!   "three days later" takes no time and gives the same answer on every run
+//! - Hand-rolled mocks: a small struct implementing the dependency's trait that records
+//!   every call and can be scripted to fail, with `RefCell` because the trait takes `&self`
+//! - Builder fixtures: one function returns a valid default object and each test changes
+//!   only the fields it cares about, so adding a field touches one place
+//! - Table-driven tests: a list of named cases run through one loop, with the case name
+//!   in every assertion message
+//!
+//! The singleton Logger in `patterns::singleton_pattern` takes its timestamps from a
+//! `Clock` for the same reason, and its tests use `FakeClock`.
+
+use demo::Demo;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ========== Clock ==========
+
+/// A source of wall-clock time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// The real time, from the operating system
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Simulated time that stands still until `advance` or `set` moves it
+#[derive(Debug)]
+pub struct FakeClock {
+    now: Mutex<SystemTime>,
+}
+
+impl FakeClock {
+    pub fn new(start: SystemTime) -> Self {
+        FakeClock { now: Mutex::new(start) }
+    }
+
+    /// Starts at the given number of seconds after the Unix epoch
+    pub fn at_unix(seconds: u64) -> Self {
+        Self::new(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    pub fn set(&self, to: SystemTime) {
+        *self.now.lock().unwrap() = to;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Whole seconds since the Unix epoch, or 0 for earlier times
+pub fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+// ========== Code Under Test: Invoice Reminders ==========
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub id: u32,
+    pub email: String,
+    pub amount_cents: u64,
+    pub due: SystemTime,
+    pub paid: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Paid,
+    Current,
+    /// Past due, by this many whole days (0 means less than a day)
+    Overdue { days: u64 },
+}
+
+pub fn status(invoice: &Invoice, now: SystemTime) -> Status {
+    if invoice.paid {
+        return Status::Paid;
+    }
+    match now.duration_since(invoice.due) {
+        Ok(late) if !late.is_zero() => Status::Overdue { days: late.as_secs() / DAY.as_secs() },
+        _ => Status::Current,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailError(pub String);
+
+/// Outgoing mail, the dependency the mock stands in for
+pub trait Mailer {
+    fn send(&self, to: &str, subject: &str) -> Result<(), MailError>;
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReminderReport {
+    pub sent: Vec<u32>,
+    pub failed: Vec<(u32, MailError)>,
+    /// Paid, not yet due, or within the one-day grace period
+    pub skipped: usize,
+}
+
+/// Mails every invoice at least a full day overdue; a failed send doesn't stop the rest
+pub fn send_reminders(invoices: &[Invoice], clock: &dyn Clock, mailer: &dyn Mailer) -> ReminderReport {
+    let now = clock.now();
+    let mut report = ReminderReport::default();
+    for invoice in invoices {
+        match status(invoice, now) {
+            Status::Overdue { days } if days >= 1 => {
+                let subject = format!("Invoice #{} is {} day(s) overdue", invoice.id, days);
+                match mailer.send(&invoice.email, &subject) {
+                    Ok(()) => report.sent.push(invoice.id),
+                    Err(err) => report.failed.push((invoice.id, err)),
+                }
+            }
+            _ => report.skipped += 1,
+        }
+    }
+    report
+}
+
+// ========== Hand-Rolled Mock ==========
+
+/// Records every `send` and fails for the addresses it was told to
+#[derive(Debug, Default)]
+pub struct MockMailer {
+    sent: RefCell<Vec<(String, String)>>,
+    failing: Vec<String>,
+}
+
+impl MockMailer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every send to `address` returns an error
+    pub fn failing_for(mut self, address: &str) -> Self {
+        self.failing.push(address.to_string());
+        self
+    }
+
+    /// `(to, subject)` of each call, failed ones included, in call order
+    pub fn calls(&self) -> Vec<(String, String)> {
+        self.sent.borrow().clone()
+    }
+
+    pub fn subjects_to(&self, address: &str) -> Vec<String> {
+        self.sent.borrow().iter().filter(|(to, _)| to == address).map(|(_, subject)| subject.clone()).collect()
+    }
+}
+
+impl Mailer for MockMailer {
+    fn send(&self, to: &str, subject: &str) -> Result<(), MailError> {
+        self.sent.borrow_mut().push((to.to_string(), subject.to_string()));
+        if self.failing.iter().any(|address| address == to) {
+            return Err(MailError(format!("mailbox {} unavailable", to)));
+        }
+        Ok(())
+    }
+}
+
+// ========== Builder Fixtures ==========
+
+/// The fixed "now" fixtures are built around: 2023-11-14 22:13:20 UTC
+pub const FIXTURE_EPOCH: u64 = 1_700_000_000;
+
+/// A valid, unpaid invoice due on `FIXTURE_EPOCH`; change only what the test needs
+pub fn invoice() -> InvoiceBuilder {
+    InvoiceBuilder {
+        invoice: Invoice {
+            id: 1,
+            email: "billing@acme.test".to_string(),
+            amount_cents: 10_000,
+            due: UNIX_EPOCH + Duration::from_secs(FIXTURE_EPOCH),
+            paid: false,
+        },
+    }
+}
+
+pub struct InvoiceBuilder {
+    invoice: Invoice,
+}
+
+impl InvoiceBuilder {
+    pub fn id(mut self, id: u32) -> Self {
+        self.invoice.id = id;
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> Self {
+        self.invoice.email = email.to_string();
+        self
+    }
+
+    pub fn amount_cents(mut self, amount_cents: u64) -> Self {
+        self.invoice.amount_cents = amount_cents;
+        self
+    }
+
+    /// Due this many days after `FIXTURE_EPOCH`; negative means before it
+    pub fn due_in_days(mut self, days: i64) -> Self {
+        let epoch = UNIX_EPOCH + Duration::from_secs(FIXTURE_EPOCH);
+        let offset = DAY * days.unsigned_abs() as u32;
+        self.invoice.due = if days >= 0 { epoch + offset } else { epoch - offset };
+        self
+    }
+
+    pub fn paid(mut self) -> Self {
+        self.invoice.paid = true;
+        self
+    }
+
+    pub fn build(self) -> Invoice {
+        self.invoice
+    }
+}
+
+// ========== Table-Driven Cases ==========
+
+/// One row of the `status` table: a name, an invoice, how long after `FIXTURE_EPOCH`
+/// "now" is, and the expected answer
+pub struct StatusCase {
+    pub name: &'static str,
+    pub invoice: Invoice,
+    pub elapsed: Duration,
+    pub expected: Status,
+}
+
+/// The table shared by the unit test and the demo
+pub fn status_cases() -> Vec<StatusCase> {
+    let case = |name, invoice, elapsed, expected| StatusCase { name, invoice, elapsed, expected };
+    vec![
+        case("due tomorrow", invoice().due_in_days(1).build(), Duration::ZERO, Status::Current),
+        case("due right now", invoice().build(), Duration::ZERO, Status::Current),
+        case("one second late", invoice().build(), Duration::from_secs(1), Status::Overdue { days: 0 }),
+        case("just under a day late", invoice().build(), DAY - Duration::from_secs(1), Status::Overdue { days: 0 }),
+        case("three days late", invoice().due_in_days(-3).build(), Duration::ZERO, Status::Overdue { days: 3 }),
+        case("paid but long overdue", invoice().due_in_days(-90).paid().build(), Duration::ZERO, Status::Paid),
+    ]
+}
+
+// ========== Demo ==========
+
+/// Registry entry for this module's demo; `run_demo` runs it against stdout.
+pub struct TestingPatternsDemo;
+
+impl Demo for TestingPatternsDemo {
+    fn name(&self) -> &'static str {
+        "testing_patterns"
+    }
+
+    fn category(&self) -> &'static str {
+        "rust-lang"
+    }
+
+    fn description(&self) -> &'static str {
+        "A fake clock, a hand-rolled mock, builder fixtures and table-driven cases"
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "===== Fake Clock =====")?;
+        let clock = FakeClock::at_unix(FIXTURE_EPOCH);
+        writeln!(out, "now = {}", unix_seconds(clock.now()))?;
+        clock.advance(Duration::from_secs(90));
+        writeln!(out, "after advance(90 seconds): now = {}", unix_seconds(clock.now()))?;
+        clock.set(UNIX_EPOCH + Duration::from_secs(FIXTURE_EPOCH));
+        writeln!(out, "after set(FIXTURE_EPOCH): now = {}", unix_seconds(clock.now()))?;
+
+        writeln!(out, "\n===== Builder Fixtures =====")?;
+        let invoices = vec![
+            invoice().id(1).due_in_days(-2).build(),
+            invoice().id(2).email("ap@globex.test").due_in_days(-10).amount_cents(99_900).build(),
+            invoice().id(3).due_in_days(-5).paid().build(),
+            invoice().id(4).due_in_days(1).build(),
+        ];
+        for invoice in &invoices {
+            let due = unix_seconds(invoice.due) as i64 - FIXTURE_EPOCH as i64;
+            writeln!(
+                out,
+                "#{} {} {} cents, due {:+} days, paid: {}",
+                invoice.id,
+                invoice.email,
+                invoice.amount_cents,
+                due / DAY.as_secs() as i64,
+                invoice.paid
+            )?;
+        }
+
+        writeln!(out, "\n===== Hand-Rolled Mock =====")?;
+        let mailer = MockMailer::new().failing_for("ap@globex.test");
+        let report = send_reminders(&invoices, &clock, &mailer);
+        writeln!(out, "sent {:?}, failed {:?}, skipped {}", report.sent, report.failed, report.skipped)?;
+        for (to, subject) in mailer.calls() {
+            writeln!(out, "  mock saw: to={} subject={:?}", to, subject)?;
+        }
+
+        writeln!(out, "\n===== Three Days Later, Instantly =====")?;
+        clock.advance(DAY * 3);
+        let mailer = MockMailer::new();
+        let report = send_reminders(&invoices, &clock, &mailer);
+        writeln!(out, "sent {:?}, skipped {}", report.sent, report.skipped)?;
+        writeln!(out, "subjects to billing@acme.test: {:?}", mailer.subjects_to("billing@acme.test"))?;
+
+        writeln!(out, "\n===== Table-Driven Cases =====")?;
+        for case in status_cases() {
+            let got = status(&case.invoice, UNIX_EPOCH + Duration::from_secs(FIXTURE_EPOCH) + case.elapsed);
+            let verdict = if got == case.expected { "ok" } else { "MISMATCH" };
+            writeln!(out, "{:<22} {:<22} {}", case.name, format!("{:?}", got), verdict)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_demo() {
+    demo::run_to_stdout(&TestingPatternsDemo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(FIXTURE_EPOCH)
+    }
+
+    #[test]
+    fn fake_clock_moves_only_when_told() {
+        let clock = FakeClock::at_unix(100);
+        assert_eq!(clock.now(), clock.now());
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(unix_seconds(clock.now()), 105);
+        clock.set(UNIX_EPOCH);
+        assert_eq!(unix_seconds(clock.now()), 0);
+    }
+
+    #[test]
+    fn status_table() {
+        for case in status_cases() {
+            let got = status(&case.invoice, fixture_now() + case.elapsed);
+            assert_eq!(got, case.expected, "case {:?}", case.name);
+        }
+    }
+
+    #[test]
+    fn reminders_go_only_to_invoices_a_day_overdue() {
+        let invoices = [
+            invoice().id(1).due_in_days(-1).build(),
+            invoice().id(2).build(),
+            invoice().id(3).due_in_days(-4).paid().build(),
+        ];
+        let clock = FakeClock::new(fixture_now());
+        let mailer = MockMailer::new();
+
+        let report = send_reminders(&invoices, &clock, &mailer);
+        assert_eq!(report, ReminderReport { sent: vec![1], failed: vec![], skipped: 2 });
+        assert_eq!(
+            mailer.calls(),
+            [("billing@acme.test".to_string(), "Invoice #1 is 1 day(s) overdue".to_string())]
+        );
+    }
+
+    #[test]
+    fn advancing_the_clock_makes_invoices_overdue() {
+        let invoices = [invoice().due_in_days(2).build()];
+        let clock = FakeClock::new(fixture_now());
+        assert!(send_reminders(&invoices, &clock, &MockMailer::new()).sent.is_empty());
+
+        clock.advance(DAY * 3);
+        let mailer = MockMailer::new();
+        assert_eq!(send_reminders(&invoices, &clock, &mailer).sent, [1]);
+        assert_eq!(mailer.subjects_to("billing@acme.test"), ["Invoice #1 is 1 day(s) overdue"]);
+    }
+
+    #[test]
+    fn a_failed_send_is_reported_and_the_rest_still_go_out() {
+        let invoices = [
+            invoice().id(1).email("down@example.test").due_in_days(-2).build(),
+            invoice().id(2).due_in_days(-2).build(),
+        ];
+        let mailer = MockMailer::new().failing_for("down@example.test");
+        let report = send_reminders(&invoices, &FakeClock::new(fixture_now()), &mailer);
+
+        assert_eq!(report.sent, [2]);
+        assert_eq!(report.failed, [(1, MailError("mailbox down@example.test unavailable".to_string()))]);
+        assert_eq!(mailer.calls().len(), 2);
+    }
+
+    #[test]
+    fn builder_defaults_are_valid_and_overrides_stick() {
+        let default = invoice().build();
+        assert!(!default.paid && default.amount_cents > 0 && default.due == fixture_now());
+
+        let custom = invoice().id(7).amount_cents(5).due_in_days(-1).paid().build();
+        assert_eq!((custom.id, custom.amount_cents, custom.paid), (7, 5, true));
+        assert_eq!(fixture_now().duration_since(custom.due).unwrap(), DAY);
+    }
+}
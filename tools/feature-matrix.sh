@@ -0,0 +1,94 @@
+#!/bin/bash
+#
+# Rust Feature Matrix
+#
+# Runs clippy and the tests for every crate under each feature combination we
+# support, so an optional dependency can't quietly become required (or a gated
+# module stop compiling) without anyone noticing.
+#
+# Usage:
+#   ./tools/feature-matrix.sh            # clippy + tests for every combination
+#   ./tools/feature-matrix.sh --check    # clippy only, much faster
+#
+# Extra cargo flags (e.g. --offline) can be passed through CARGO_FLAGS.
+#
+
+set -euo pipefail
+
+cd "$(git rev-parse --show-toplevel)"
+
+# Colors for output
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+YELLOW='\033[0;33m'
+NC='\033[0m' # No Color
+
+CHECK_ONLY=false
+if [ "${1:-}" = "--check" ]; then
+    CHECK_ONLY=true
+fi
+
+# "<package> <cargo feature flags>", one combination per line
+MATRIX=(
+    "algorithms --no-default-features"
+    "algorithms --features serde"
+    "algorithms --features wasm"
+    "algorithms --features ffi"
+    "algorithms --all-features"
+    "sorting-algorithms --no-default-features"
+    "sorting-algorithms --features serde"
+    "data-structures --no-default-features"
+    "patterns --no-default-features"
+    "patterns --features dyn-clone"
+    "patterns --features lazy_static"
+    "patterns --features serde"
+    "networking"
+    "rust-lang"
+    "rust-lang --features mmap"
+    "proc-macros"
+    "concurrency --no-default-features"
+    "concurrency --no-default-features --features tokio"
+    "concurrency --no-default-features --features rayon"
+    "concurrency --no-default-features --features crossbeam"
+    "concurrency --all-features"
+    "tech-notes --no-default-features"
+    "tech-notes --all-features"
+    "tui-visualizer"
+    "calc"
+    "tech-notes-py"
+    "tech-notes-py --features python"
+    "demo"
+    "demo --features json"
+    "benches"
+)
+
+# Crates whose default build must not pull in any third-party dependency (workspace
+# path dependencies such as `demo` are fine)
+DEPENDENCY_FREE=(demo algorithms sorting-algorithms data-structures networking rust-lang)
+
+echo -e "${YELLOW}=== Rust Feature Matrix ===${NC}"
+
+for package in "${DEPENDENCY_FREE[@]}"; do
+    deps=$(cargo tree ${CARGO_FLAGS:-} -p "$package" -e normal --depth 1 --prefix none | tail -n +2 | grep -v ' (/' || true)
+    if [ -n "$deps" ]; then
+        echo -e "${RED}$package should build without dependencies, but pulls in:${NC}"
+        echo "$deps"
+        exit 1
+    fi
+    echo -e "${GREEN}✓ $package has no default dependencies${NC}"
+done
+
+for entry in "${MATRIX[@]}"; do
+    read -r package flags <<< "$entry"
+    echo -e "${YELLOW}--- $package ${flags} ---${NC}"
+
+    # shellcheck disable=SC2086
+    cargo clippy ${CARGO_FLAGS:-} -p "$package" $flags --all-targets -- -D warnings
+    if [ "$CHECK_ONLY" = false ]; then
+        # shellcheck disable=SC2086
+        cargo test ${CARGO_FLAGS:-} -p "$package" $flags
+    fi
+    echo -e "${GREEN}✓ $package ${flags}${NC}"
+done
+
+echo -e "${GREEN}All ${#MATRIX[@]} feature combinations passed${NC}"
@@ -0,0 +1,234 @@
+//! Frontier animation of `Graph::trace`.
+
+use crate::player::Player;
+use algorithms::graph_traversal::{Graph, Traversal, TraversalFrame};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::canvas::{self, Canvas};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::Frame;
+use std::f64::consts::PI;
+
+/// How a vertex is drawn at the current step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VertexState {
+    Unvisited,
+    Frontier,
+    Visited,
+    Current,
+}
+
+impl VertexState {
+    fn color(self) -> Color {
+        match self {
+            VertexState::Unvisited => Color::DarkGray,
+            VertexState::Frontier => Color::Yellow,
+            VertexState::Visited => Color::Green,
+            VertexState::Current => Color::Red,
+        }
+    }
+}
+
+pub struct GraphView {
+    graph: Graph,
+    vertices: Vec<String>,
+    /// Each undirected edge once, as `(smaller, larger)`
+    edges: Vec<(String, String)>,
+    traversal: Traversal,
+    start: String,
+    frames: Vec<TraversalFrame>,
+    pub player: Player,
+}
+
+impl GraphView {
+    /// `start` must be a vertex of `graph`.
+    pub fn new(graph: Graph, traversal: Traversal, start: &str) -> Self {
+        let vertices = graph.vertices();
+        let edges = vertices
+            .iter()
+            .flat_map(|v| {
+                graph
+                    .get_sorted_neighbors(v)
+                    .into_iter()
+                    .filter(move |n| v < n)
+                    .map(move |n| (v.clone(), n))
+            })
+            .collect();
+        let frames = graph.trace(traversal, start);
+
+        GraphView {
+            vertices,
+            edges,
+            traversal,
+            start: start.to_string(),
+            player: Player::new(frames.len()),
+            frames,
+            graph,
+        }
+    }
+
+    /// Switches to the next traversal in `Traversal::ALL` and rewinds.
+    pub fn next_traversal(&mut self) {
+        let index = Traversal::ALL.iter().position(|&t| t == self.traversal).unwrap_or(0);
+        self.traversal = Traversal::ALL[(index + 1) % Traversal::ALL.len()];
+        self.reload();
+    }
+
+    /// Starts from the next vertex in sorted order and rewinds.
+    pub fn next_start(&mut self) {
+        let index = self.vertices.iter().position(|v| *v == self.start).unwrap_or(0);
+        self.start = self.vertices[(index + 1) % self.vertices.len()].clone();
+        self.reload();
+    }
+
+    fn reload(&mut self) {
+        self.frames = self.graph.trace(self.traversal, &self.start);
+        self.player.load(self.frames.len());
+    }
+
+    /// The frame for the current position; `None` before the first visit.
+    fn frame(&self) -> Option<&TraversalFrame> {
+        self.player.position().checked_sub(1).map(|i| &self.frames[i])
+    }
+
+    fn state(&self, vertex: &str) -> VertexState {
+        let Some(frame) = self.frame() else {
+            return VertexState::Unvisited;
+        };
+        if frame.vertex == vertex {
+            VertexState::Current
+        } else if frame.frontier.iter().any(|v| v == vertex) {
+            VertexState::Frontier
+        } else if frame.visited.iter().any(|v| v == vertex) {
+            VertexState::Visited
+        } else {
+            VertexState::Unvisited
+        }
+    }
+
+    fn frontier_label(&self) -> &'static str {
+        match self.traversal {
+            Traversal::Bfs => "Queue",
+            Traversal::DfsRecursive => "Path",
+            Traversal::DfsIterative => "Stack",
+        }
+    }
+
+    fn side_panel(&self) -> Vec<Line<'static>> {
+        let list = |items: &[String]| format!("[{}]", items.join(", "));
+        let (vertex, frontier, visited) = match self.frame() {
+            Some(frame) => (frame.vertex.clone(), list(&frame.frontier), list(&frame.visited)),
+            None => ("-".to_string(), "[]".to_string(), "[]".to_string()),
+        };
+
+        vec![
+            Line::from(format!("{} from {}", self.traversal.name(), self.start)),
+            Line::from(format!("step {}/{}", self.player.position(), self.frames.len())),
+            Line::from(""),
+            Line::from(vec!["Visiting ".into(), Span::from(vertex).fg(VertexState::Current.color())]),
+            Line::from(vec![
+                format!("{} ", self.frontier_label()).into(),
+                Span::from(frontier).fg(VertexState::Frontier.color()),
+            ]),
+            Line::from(vec!["Visited ".into(), Span::from(visited).fg(VertexState::Visited.color())]),
+        ]
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let [canvas_area, panel_area] = Layout::horizontal([Constraint::Min(20), Constraint::Length(32)]).areas(area);
+        let positions = layout(self.vertices.len());
+        let position_of = |vertex: &str| positions[self.vertices.iter().position(|v| v == vertex).unwrap()];
+
+        let canvas = Canvas::default()
+            .block(Block::bordered().title(format!(" {} ", self.traversal.name())))
+            .marker(Marker::Braille)
+            .x_bounds([-1.3, 1.3])
+            .y_bounds([-1.3, 1.3])
+            .paint(|ctx| {
+                for (a, b) in &self.edges {
+                    let ((x1, y1), (x2, y2)) = (position_of(a), position_of(b));
+                    ctx.draw(&canvas::Line::new(x1, y1, x2, y2, Color::Gray));
+                }
+                ctx.layer();
+                for (vertex, &(x, y)) in self.vertices.iter().zip(&positions) {
+                    let style = Style::default().fg(Color::Black).bg(self.state(vertex).color());
+                    ctx.print(x, y, Span::styled(format!(" {} ", vertex), style));
+                }
+            });
+        frame.render_widget(canvas, canvas_area);
+
+        let panel = Paragraph::new(self.side_panel())
+            .block(Block::bordered().title(" trace "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(panel, panel_area);
+    }
+}
+
+/// `n` points evenly spaced on the unit circle, clockwise from the top.
+fn layout(n: usize) -> Vec<(f64, f64)> {
+    (0..n)
+        .map(|i| {
+            let angle = PI / 2.0 - 2.0 * PI * i as f64 / n as f64;
+            (angle.cos(), angle.sin())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algorithms::graph_traversal::create_sample_graph;
+
+    #[test]
+    fn edges_are_listed_once() {
+        let view = GraphView::new(create_sample_graph(), Traversal::Bfs, "A");
+        let edges: Vec<String> = view.edges.iter().map(|(a, b)| format!("{}-{}", a, b)).collect();
+        assert_eq!(edges, ["A-B", "A-C", "B-D", "B-E", "C-F", "E-F"]);
+    }
+
+    #[test]
+    fn vertex_states_follow_the_bfs_queue() {
+        let mut view = GraphView::new(create_sample_graph(), Traversal::Bfs, "A");
+        assert_eq!(view.state("A"), VertexState::Unvisited);
+
+        // Second visit: B comes off the queue with C still waiting behind it.
+        view.player.step_forward();
+        view.player.step_forward();
+        assert_eq!(view.state("B"), VertexState::Current);
+        assert_eq!(view.state("A"), VertexState::Visited);
+        assert_eq!(view.state("C"), VertexState::Frontier);
+        assert_eq!(view.state("D"), VertexState::Unvisited);
+
+        // Third visit: C, with B's neighbors D and E queued.
+        view.player.step_forward();
+        assert_eq!(view.state("D"), VertexState::Frontier);
+        assert_eq!(view.state("E"), VertexState::Frontier);
+        assert_eq!(view.state("F"), VertexState::Unvisited);
+    }
+
+    #[test]
+    fn cycling_traversal_and_start_rewinds() {
+        let mut view = GraphView::new(create_sample_graph(), Traversal::Bfs, "A");
+        view.player.step_forward();
+        view.next_traversal();
+        assert_eq!(view.traversal, Traversal::DfsRecursive);
+        assert_eq!(view.frontier_label(), "Path");
+        assert_eq!(view.player.position(), 0);
+
+        view.next_start();
+        assert_eq!(view.start, "B");
+        view.player.step_forward();
+        assert_eq!(view.state("B"), VertexState::Current);
+    }
+
+    #[test]
+    fn layout_is_on_the_unit_circle() {
+        let points = layout(4);
+        let expected = [(0.0, 1.0), (1.0, 0.0), (0.0, -1.0), (-1.0, 0.0)];
+        for ((x, y), (ex, ey)) in points.into_iter().zip(expected) {
+            assert!((x - ex).abs() < 1e-9 && (y - ey).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! Terminal animations of the sorting and graph traversal snippets.
+//!
+//! Both views replay traces recorded through the snippets' trace hooks
+//! (`sorting_algorithms::sort_steps` and `Graph::trace`), so playback speed, pausing and
+//! stepping backwards are all handled here instead of by sleeps inside the algorithms.
+//! The `tui-visualizer` binary is a thin wrapper over `run`, and `tech-notes visualize`
+//! embeds the same `Options` when built with its `visualization` feature.
+//!
+//! Usage:
+//!
+//! ```text
+//! tui-visualizer [sort|graph] [--algorithm quick] [--len 40] [--seed N]
+//!                [--traversal bfs] [--edges "A-B A-C ..."] [--start A]
+//! ```
+//!
+//! Keys: `space` play/pause, `→`/`l` step, `←`/`h` step back, `+`/`-` speed, `r` rewind,
+//! `a` next algorithm or traversal, `s` next start vertex, `n` new random input,
+//! `tab` switch view, `q` quit.
+
+mod graph_view;
+mod player;
+mod sort_view;
+
+use algorithms::graph_traversal::{create_sample_graph, parse_edges, Graph, Traversal};
+use algorithms::sorting_algorithms::SortAlgorithm;
+use clap::{Args, ValueEnum};
+use graph_view::GraphView;
+use player::Player;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::Tabs;
+use ratatui::{DefaultTerminal, Frame};
+use sort_view::SortView;
+use std::io;
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Command-line options shared by `tui-visualizer` and `tech-notes visualize`.
+#[derive(Debug, Args)]
+pub struct Options {
+    /// View to open first
+    #[arg(value_enum, default_value_t = View::Sort)]
+    pub view: View,
+    /// Sorting algorithm, e.g. `quick` or `heap`
+    #[arg(long, default_value = "quick", value_parser = parse_algorithm)]
+    pub algorithm: SortAlgorithm,
+    /// Number of values to sort
+    #[arg(long, default_value_t = 40, value_parser = clap::value_parser!(u16).range(2..=500))]
+    pub len: u16,
+    /// Seed for the random input; defaults to the current time
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Traversal: `bfs`, `dfs-recursive` or `dfs-iterative`
+    #[arg(long, default_value = "bfs", value_parser = parse_traversal)]
+    pub traversal: Traversal,
+    /// Edges such as "A-B A-C B-D"; defaults to the sample graph from the notes
+    #[arg(long)]
+    pub edges: Option<String>,
+    /// Vertex to start the traversal from
+    #[arg(long, default_value = "A")]
+    pub start: String,
+}
+
+/// Which view is on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum View {
+    Sort,
+    Graph,
+}
+
+fn parse_algorithm(name: &str) -> Result<SortAlgorithm, String> {
+    SortAlgorithm::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = SortAlgorithm::ALL.iter().map(|a| a.name()).collect();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+fn parse_traversal(name: &str) -> Result<Traversal, String> {
+    Traversal::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = Traversal::ALL.iter().map(|t| t.name()).collect();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+/// `len` values in `1..=100` from a xorshift generator, so a seed always gives the same bars.
+fn random_values(len: usize, seed: u64) -> Vec<i32> {
+    let mut state = seed | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 100) as i32 + 1
+        })
+        .collect()
+}
+
+fn time_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64)
+}
+
+// ========== App ==========
+
+struct App {
+    view: View,
+    sort: SortView,
+    graph: GraphView,
+    len: usize,
+    seed: u64,
+}
+
+impl App {
+    fn player(&mut self) -> &mut Player {
+        match self.view {
+            View::Sort => &mut self.sort.player,
+            View::Graph => &mut self.graph.player,
+        }
+    }
+
+    /// Runs `f` on the active player, keeping the sort view's values in step with it.
+    fn with_player(&mut self, f: impl FnOnce(&mut Player)) {
+        let before = self.player().position();
+        f(self.player());
+        if self.view == View::Sort {
+            self.sort.sync(before);
+        }
+    }
+
+    /// Handles one key press; returns false to quit.
+    fn on_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') => self.player().toggle(),
+            KeyCode::Right | KeyCode::Char('l') => self.with_player(|p| {
+                p.step_forward();
+            }),
+            KeyCode::Left | KeyCode::Char('h') => self.with_player(Player::step_back),
+            KeyCode::Char('r') => self.with_player(Player::reset),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.player().faster(),
+            KeyCode::Char('-') => self.player().slower(),
+            KeyCode::Tab => {
+                self.player().pause();
+                self.view = match self.view {
+                    View::Sort => View::Graph,
+                    View::Graph => View::Sort,
+                };
+            }
+            KeyCode::Char('a') => match self.view {
+                View::Sort => self.sort.next_algorithm(),
+                View::Graph => self.graph.next_traversal(),
+            },
+            KeyCode::Char('s') if self.view == View::Graph => self.graph.next_start(),
+            KeyCode::Char('n') if self.view == View::Sort => {
+                self.seed = self.seed.wrapping_add(1);
+                self.sort.set_input(random_values(self.len, self.seed));
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [tabs_area, body_area, help_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(5), Constraint::Length(1)]).areas(frame.area());
+
+        let selected = match self.view {
+            View::Sort => 0,
+            View::Graph => 1,
+        };
+        let tabs = Tabs::new(["Sorting", "Graph traversal"])
+            .select(selected)
+            .highlight_style(Style::default().bold().reversed());
+        frame.render_widget(tabs, tabs_area);
+
+        let player = match self.view {
+            View::Sort => {
+                self.sort.render(frame, body_area);
+                &self.sort.player
+            }
+            View::Graph => {
+                self.graph.render(frame, body_area);
+                &self.graph.player
+            }
+        };
+
+        let state = if player.is_playing() { "playing" } else { "paused" };
+        let help = format!(
+            "{} {}/s | space play  ←/→ step  +/- speed  r rewind  a algorithm  {}tab view  q quit",
+            state,
+            player.speed(),
+            match self.view {
+                View::Sort => "n input  ",
+                View::Graph => "s start  ",
+            }
+        );
+        frame.render_widget(Line::from(help).dim(), help_area);
+    }
+}
+
+/// Draws, waits for a key or the next tick, and advances the active player while playing.
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    let mut last_tick = Instant::now();
+    loop {
+        terminal.draw(|frame| app.render(frame))?;
+
+        let interval = app.player().interval();
+        let timeout = if app.player().is_playing() {
+            interval.saturating_sub(last_tick.elapsed())
+        } else {
+            Duration::from_millis(250)
+        };
+
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !app.on_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+
+        if app.player().is_playing() && last_tick.elapsed() >= interval {
+            app.with_player(|p| {
+                p.step_forward();
+            });
+            last_tick = Instant::now();
+        } else if !app.player().is_playing() {
+            last_tick = Instant::now();
+        }
+    }
+}
+
+/// Validates `options`, then takes over the terminal until the user quits.
+pub fn run(options: Options) -> ExitCode {
+    let graph = match &options.edges {
+        Some(spec) => match parse_edges(spec) {
+            Ok(edges) if !edges.is_empty() => Graph::from_edges(edges),
+            Ok(_) => {
+                eprintln!("--edges needs at least one edge");
+                return ExitCode::FAILURE;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => create_sample_graph(),
+    };
+    if !graph.contains(&options.start) {
+        eprintln!("start vertex '{}' is not in the graph", options.start);
+        return ExitCode::FAILURE;
+    }
+
+    let seed = options.seed.unwrap_or_else(time_seed);
+    let len = usize::from(options.len);
+    let app = App {
+        view: options.view,
+        sort: SortView::new(options.algorithm, random_values(len, seed)),
+        graph: GraphView::new(graph, options.traversal, &options.start),
+        len,
+        seed,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, app);
+    ratatui::restore();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("terminal error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn app(view: View) -> App {
+        App {
+            view,
+            sort: SortView::new(SortAlgorithm::Quick, random_values(12, 7)),
+            graph: GraphView::new(create_sample_graph(), Traversal::Bfs, "A"),
+            len: 12,
+            seed: 7,
+        }
+    }
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn random_values_are_seeded_and_in_range() {
+        assert_eq!(random_values(50, 3), random_values(50, 3));
+        assert_ne!(random_values(50, 3), random_values(50, 4));
+        assert!(random_values(500, 9).iter().all(|v| (1..=100).contains(v)));
+    }
+
+    #[test]
+    fn keys_drive_the_active_view() {
+        let mut app = app(View::Sort);
+        app.on_key(KeyCode::Right);
+        app.on_key(KeyCode::Right);
+        app.on_key(KeyCode::Left);
+        assert_eq!(app.sort.player.position(), 1);
+
+        app.on_key(KeyCode::Tab);
+        assert_eq!(app.view, View::Graph);
+        app.on_key(KeyCode::Char('l'));
+        assert_eq!(app.graph.player.position(), 1);
+        assert_eq!(app.sort.player.position(), 1);
+
+        assert!(!app.on_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn both_views_render() {
+        let mut app = app(View::Sort);
+        let sort = screen(&app);
+        assert!(sort.contains("quick sort"), "{}", sort);
+        assert!(sort.contains("step 0/"), "{}", sort);
+
+        app.view = View::Graph;
+        app.on_key(KeyCode::Right);
+        app.on_key(KeyCode::Right);
+        let graph = screen(&app);
+        assert!(graph.contains("bfs from A"), "{}", graph);
+        assert!(graph.contains("Visiting B"), "{}", graph);
+        assert!(graph.contains("Queue [C]"), "{}", graph);
+    }
+}
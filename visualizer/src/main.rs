@@ -0,0 +1,17 @@
+//! `tui-visualizer`: terminal animations of the sorting and graph traversal snippets.
+//!
+//! See the library docs for the options and key bindings.
+
+use clap::Parser;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "tui-visualizer", version, about = "Animates the sorting and graph traversal snippets")]
+struct Cli {
+    #[command(flatten)]
+    options: tui_visualizer::Options,
+}
+
+fn main() -> ExitCode {
+    tui_visualizer::run(Cli::parse().options)
+}
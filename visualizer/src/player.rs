@@ -0,0 +1,163 @@
+//! Playback position over a recorded trace.
+//!
+//! The player only tracks where we are and how fast we move; the views decide what a
+//! position looks like. Position 0 is the untouched input and position `len` is the
+//! state after the last step.
+
+use std::time::Duration;
+
+/// Selectable speeds, in steps per second.
+const SPEEDS: [u32; 9] = [1, 2, 4, 8, 15, 30, 60, 120, 240];
+
+/// Speed the player starts at.
+const DEFAULT_SPEED: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Player {
+    position: usize,
+    len: usize,
+    playing: bool,
+    speed: usize,
+}
+
+impl Player {
+    /// A paused player over `len` steps, at the start.
+    pub fn new(len: usize) -> Self {
+        Player {
+            position: 0,
+            len,
+            playing: false,
+            speed: DEFAULT_SPEED,
+        }
+    }
+
+    /// Rewinds to the start of a new trace of `len` steps, keeping the speed.
+    pub fn load(&mut self, len: usize) {
+        self.position = 0;
+        self.len = len;
+        self.playing = false;
+    }
+
+    /// Number of steps already applied.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.len
+    }
+
+    /// Starts or pauses playback. Playing from the end starts over.
+    pub fn toggle(&mut self) {
+        if !self.playing && self.is_finished() {
+            self.position = 0;
+        }
+        self.playing = !self.playing;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Moves one step forward; returns false (and stops playing) at the end.
+    pub fn step_forward(&mut self) -> bool {
+        if self.is_finished() {
+            self.playing = false;
+            return false;
+        }
+        self.position += 1;
+        if self.is_finished() {
+            self.playing = false;
+        }
+        true
+    }
+
+    /// Moves one step back and pauses.
+    pub fn step_back(&mut self) {
+        self.playing = false;
+        self.position = self.position.saturating_sub(1);
+    }
+
+    /// Back to the start, paused.
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.playing = false;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Steps per second.
+    pub fn speed(&self) -> u32 {
+        SPEEDS[self.speed]
+    }
+
+    /// Time between automatic steps while playing.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(1) / self.speed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playing_stops_at_the_end() {
+        let mut player = Player::new(2);
+        player.toggle();
+        assert!(player.step_forward());
+        assert!(player.is_playing());
+        assert!(player.step_forward());
+        assert!(player.is_finished());
+        assert!(!player.is_playing());
+        assert!(!player.step_forward());
+        assert_eq!(player.position(), 2);
+    }
+
+    #[test]
+    fn toggling_at_the_end_replays() {
+        let mut player = Player::new(1);
+        player.step_forward();
+        player.toggle();
+        assert!(player.is_playing());
+        assert_eq!(player.position(), 0);
+    }
+
+    #[test]
+    fn stepping_back_pauses_and_stops_at_zero() {
+        let mut player = Player::new(3);
+        player.step_forward();
+        player.toggle();
+        player.step_back();
+        assert!(!player.is_playing());
+        player.step_back();
+        assert_eq!(player.position(), 0);
+    }
+
+    #[test]
+    fn speed_is_clamped() {
+        let mut player = Player::new(0);
+        for _ in 0..20 {
+            player.slower();
+        }
+        assert_eq!(player.interval(), Duration::from_secs(1));
+        for _ in 0..20 {
+            player.faster();
+        }
+        assert_eq!(player.speed(), *SPEEDS.last().unwrap());
+
+        player.load(5);
+        assert_eq!(player.speed(), *SPEEDS.last().unwrap());
+        assert_eq!((player.position(), player.len), (0, 5));
+    }
+}
@@ -0,0 +1,206 @@
+//! Bar chart replay of `sorting_algorithms::sort_steps`.
+
+use crate::player::Player;
+use algorithms::sorting_algorithms::{sort_steps, SortAlgorithm, SortStep};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Paragraph};
+use ratatui::Frame;
+
+const COMPARE: Color = Color::Yellow;
+const SWAP: Color = Color::Red;
+const SET: Color = Color::Green;
+const IDLE: Color = Color::Cyan;
+
+pub struct SortView {
+    algorithm: SortAlgorithm,
+    input: Vec<i32>,
+    steps: Vec<SortStep>,
+    /// `input` with the first `player.position()` steps applied
+    values: Vec<i32>,
+    pub player: Player,
+}
+
+impl SortView {
+    pub fn new(algorithm: SortAlgorithm, input: Vec<i32>) -> Self {
+        let steps = sort_steps(algorithm, &input);
+        SortView {
+            algorithm,
+            values: input.clone(),
+            player: Player::new(steps.len()),
+            input,
+            steps,
+        }
+    }
+
+    /// Switches to the next algorithm in `SortAlgorithm::ALL` and rewinds.
+    pub fn next_algorithm(&mut self) {
+        let index = SortAlgorithm::ALL.iter().position(|&a| a == self.algorithm).unwrap_or(0);
+        self.algorithm = SortAlgorithm::ALL[(index + 1) % SortAlgorithm::ALL.len()];
+        self.reload();
+    }
+
+    /// Replaces the input and rewinds.
+    pub fn set_input(&mut self, input: Vec<i32>) {
+        self.input = input;
+        self.reload();
+    }
+
+    fn reload(&mut self) {
+        self.steps = sort_steps(self.algorithm, &self.input);
+        self.values = self.input.clone();
+        self.player.load(self.steps.len());
+    }
+
+    /// Brings `values` in line with the player after it moved.
+    ///
+    /// Moving forward applies the new steps; moving back replays from the input, which is
+    /// cheap next to drawing a frame.
+    pub fn sync(&mut self, previous: usize) {
+        let position = self.player.position();
+        if position < previous {
+            self.values = self.input.clone();
+            self.steps[..position].iter().for_each(|step| step.apply(&mut self.values));
+        } else {
+            self.steps[previous..position].iter().for_each(|step| step.apply(&mut self.values));
+        }
+    }
+
+    /// The step that produced the current state, if any.
+    fn current_step(&self) -> Option<SortStep> {
+        self.player.position().checked_sub(1).map(|i| self.steps[i])
+    }
+
+    fn bar_color(&self, index: usize) -> Color {
+        if self.player.is_finished() && !self.steps.is_empty() {
+            return SET;
+        }
+        match self.current_step() {
+            Some(SortStep::Compare(i, j)) if index == i || index == j => COMPARE,
+            Some(SortStep::Swap(i, j)) if index == i || index == j => SWAP,
+            Some(SortStep::Set(i, _)) if index == i => SET,
+            _ => IDLE,
+        }
+    }
+
+    fn status(&self) -> Vec<Line<'static>> {
+        let applied = &self.steps[..self.player.position()];
+        let count = |f: fn(&SortStep) -> bool| applied.iter().filter(|s| f(s)).count();
+        let current = match self.current_step() {
+            None => "input".to_string(),
+            Some(SortStep::Compare(i, j)) => format!("compare [{}] and [{}]", i, j),
+            Some(SortStep::Swap(i, j)) => format!("swap [{}] and [{}]", i, j),
+            Some(SortStep::Set(i, value)) => format!("write {} to [{}]", value, i),
+        };
+
+        vec![
+            Line::from(format!(
+                "{} sort  step {}/{}  compares {}  swaps {}  writes {}",
+                self.algorithm.name(),
+                self.player.position(),
+                self.steps.len(),
+                count(|s| matches!(s, SortStep::Compare(..))),
+                count(|s| matches!(s, SortStep::Swap(..))),
+                count(|s| matches!(s, SortStep::Set(..))),
+            )),
+            Line::from(current),
+        ]
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let [chart_area, status_area] = Layout::vertical([Constraint::Min(3), Constraint::Length(2)]).areas(area);
+
+        let n = self.values.len().max(1) as u16;
+        let inner_width = chart_area.width.saturating_sub(2);
+        let gap = u16::from(inner_width >= n * 3);
+        let bar_width = (inner_width.saturating_sub(gap * n) / n).max(1);
+
+        let bars: Vec<Bar> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                Bar::default()
+                    .value(value.max(0) as u64)
+                    .text_value(String::new())
+                    .style(Style::default().fg(self.bar_color(i)))
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .block(Block::bordered().title(format!(" {} sort ", self.algorithm.name())))
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(gap);
+        frame.render_widget(chart, chart_area);
+        frame.render_widget(Paragraph::new(self.status()), status_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_matches_a_fresh_replay_in_both_directions() {
+        let input = vec![5, 1, 4, 2, 8, 0, 3];
+        let mut view = SortView::new(SortAlgorithm::Heap, input.clone());
+        let steps = sort_steps(SortAlgorithm::Heap, &input);
+
+        let replay = |n: usize| {
+            let mut values = input.clone();
+            steps[..n].iter().for_each(|s| s.apply(&mut values));
+            values
+        };
+
+        for _ in 0..steps.len() {
+            let before = view.player.position();
+            view.player.step_forward();
+            view.sync(before);
+            assert_eq!(view.values, replay(view.player.position()));
+        }
+        let mut sorted = input.clone();
+        sorted.sort();
+        assert_eq!(view.values, sorted);
+
+        for _ in 0..3 {
+            let before = view.player.position();
+            view.player.step_back();
+            view.sync(before);
+        }
+        assert_eq!(view.values, replay(steps.len() - 3));
+    }
+
+    #[test]
+    fn the_current_step_is_highlighted() {
+        let mut view = SortView::new(SortAlgorithm::Bubble, vec![2, 1, 3]);
+        assert_eq!(view.bar_color(0), IDLE);
+
+        view.player.step_forward();
+        view.sync(0);
+        assert_eq!((view.bar_color(0), view.bar_color(1), view.bar_color(2)), (COMPARE, COMPARE, IDLE));
+
+        view.player.step_forward();
+        view.sync(1);
+        assert_eq!((view.bar_color(0), view.bar_color(1)), (SWAP, SWAP));
+    }
+
+    #[test]
+    fn switching_algorithms_rewinds() {
+        let mut view = SortView::new(SortAlgorithm::Bubble, vec![3, 2, 1]);
+        view.player.step_forward();
+        view.sync(0);
+        view.next_algorithm();
+        assert_eq!(view.algorithm, SortAlgorithm::Selection);
+        assert_eq!(view.player.position(), 0);
+        assert_eq!(view.values, [3, 2, 1]);
+
+        let last = *SortAlgorithm::ALL.last().unwrap();
+        while view.algorithm != last {
+            view.next_algorithm();
+        }
+        view.next_algorithm();
+        assert_eq!(view.algorithm, SortAlgorithm::ALL[0]);
+    }
+}